@@ -0,0 +1,217 @@
+//! Public API comparison between two snapshots of a codebase.
+//!
+//! Entities are matched across snapshots by file, entity type and name
+//! rather than ISGL1 key, since keys embed line ranges that shift with any
+//! edit above the entity. Each difference in the public surface is rated
+//! with the semver bump it calls for:
+//!
+//! - a public entity removed, or no longer public: major
+//! - a public signature changed in a way `signature_diff` rates breaking: major
+//! - a public entity added, or its signature changed compatibly: minor
+//!
+//! Documentation and location changes are not API changes and are ignored.
+
+use std::collections::HashMap;
+
+use crate::entities::{CodeEntity, Visibility};
+use crate::signature_diff::{diff_entities, FieldComparison};
+
+/// Semver component a change requires bumping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SemverImpact {
+    Minor,
+    Major,
+}
+
+/// What happened to a public entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiChangeKind {
+    Added,
+    /// Gone from the public API; `now` is its visibility if it still exists
+    Removed { now: Option<String> },
+    /// Fields that differ (documentation and location excluded)
+    SignatureChanged { fields: Vec<FieldComparison> },
+}
+
+/// One difference in the public API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub name: String,
+    /// Key in the old snapshot, or in the new one for additions
+    pub isgl1_key: String,
+    pub kind: ApiChangeKind,
+    pub impact: SemverImpact,
+}
+
+/// Public API differences between an old and a new snapshot
+#[derive(Debug, Clone, Default)]
+pub struct ApiReport {
+    /// Major changes first, then by key
+    pub changes: Vec<ApiChange>,
+}
+
+impl ApiReport {
+    /// Compare the public entities of `old` against `new`
+    ///
+    /// Non-public entities of `old` are ignored, so the result of
+    /// `CozoDbStorage::get_public_entities` can be passed directly; `new`
+    /// should hold every entity so that items made private are told apart
+    /// from deleted ones.
+    pub fn compare(old: &[CodeEntity], new: &[CodeEntity]) -> Self {
+        let new_by_identity: HashMap<_, &CodeEntity> = new.iter().map(|e| (identity(e), e)).collect();
+        let old_public: HashMap<_, &CodeEntity> = old
+            .iter()
+            .filter(|e| is_public(e))
+            .map(|e| (identity(e), e))
+            .collect();
+
+        let mut changes = Vec::new();
+        for (id, old_entity) in &old_public {
+            let change = |kind, impact| ApiChange {
+                name: old_entity.interface_signature.name.clone(),
+                isgl1_key: old_entity.isgl1_key.clone(),
+                kind,
+                impact,
+            };
+            match new_by_identity.get(id) {
+                None => changes.push(change(ApiChangeKind::Removed { now: None }, SemverImpact::Major)),
+                Some(new_entity) if !is_public(new_entity) => {
+                    let now = format!("{:?}", new_entity.interface_signature.effective_visibility());
+                    changes.push(change(ApiChangeKind::Removed { now: Some(now) }, SemverImpact::Major));
+                }
+                Some(new_entity) => {
+                    let diff = diff_entities(old_entity, new_entity);
+                    let fields: Vec<FieldComparison> = diff
+                        .changes()
+                        .filter(|f| f.field != "documentation" && f.field != "location")
+                        .cloned()
+                        .collect();
+                    if fields.is_empty() {
+                        continue;
+                    }
+                    let impact = if diff.is_breaking() { SemverImpact::Major } else { SemverImpact::Minor };
+                    changes.push(change(ApiChangeKind::SignatureChanged { fields }, impact));
+                }
+            }
+        }
+
+        for new_entity in new.iter().filter(|e| is_public(e)) {
+            if !old_public.contains_key(&identity(new_entity)) {
+                changes.push(ApiChange {
+                    name: new_entity.interface_signature.name.clone(),
+                    isgl1_key: new_entity.isgl1_key.clone(),
+                    kind: ApiChangeKind::Added,
+                    impact: SemverImpact::Minor,
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| b.impact.cmp(&a.impact).then_with(|| a.isgl1_key.cmp(&b.isgl1_key)));
+        Self { changes }
+    }
+
+    /// Largest bump required, `None` when the public API is unchanged
+    pub fn required_bump(&self) -> Option<SemverImpact> {
+        self.changes.iter().map(|c| c.impact).max()
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|c| c.impact == SemverImpact::Major)
+    }
+}
+
+fn is_public(entity: &CodeEntity) -> bool {
+    entity.interface_signature.effective_visibility() == Visibility::Public
+}
+
+/// File, entity type and name: stable across line shifts
+fn identity(entity: &CodeEntity) -> (String, String, String) {
+    let sig = &entity.interface_signature;
+    (
+        sig.file_path.to_string_lossy().into_owned(),
+        format!("{:?}", sig.entity_type),
+        sig.name.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{
+        EntityClass, EntityType, InterfaceSignature, LanguageSpecificSignature, LineRange, RustSignature,
+    };
+    use std::path::PathBuf;
+
+    fn rust_fn(name: &str, code: &str, visibility: Visibility) -> CodeEntity {
+        let signature = InterfaceSignature {
+            entity_type: EntityType::Function,
+            name: name.to_string(),
+            visibility,
+            file_path: PathBuf::from("src/lib.rs"),
+            line_range: LineRange::new(1, 1).unwrap(),
+            module_path: vec![],
+            documentation: None,
+            language_specific: LanguageSpecificSignature::Rust(RustSignature {
+                generics: vec![],
+                lifetimes: vec![],
+                where_clauses: vec![],
+                attributes: vec![],
+                trait_impl: None,
+            }),
+        };
+        let mut entity = CodeEntity::new(
+            format!("rust:fn:{}:src_lib_rs:1-1", name),
+            signature,
+            EntityClass::CodeImplementation,
+        )
+        .unwrap();
+        entity.current_code = Some(code.to_string());
+        entity
+    }
+
+    #[test]
+    fn test_added_public_function_is_minor() {
+        let old = vec![rust_fn("a", "pub fn a() {}", Visibility::Public)];
+        let new = vec![
+            rust_fn("a", "pub fn a() {}", Visibility::Public),
+            rust_fn("b", "pub fn b() {}", Visibility::Public),
+        ];
+
+        let report = ApiReport::compare(&old, &new);
+
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].kind, ApiChangeKind::Added);
+        assert_eq!(report.required_bump(), Some(SemverImpact::Minor));
+    }
+
+    #[test]
+    fn test_public_function_made_private_is_major() {
+        let old = vec![rust_fn("a", "pub fn a() {}", Visibility::Public)];
+        let new = vec![rust_fn("a", "fn a() {}", Visibility::Private)];
+
+        let report = ApiReport::compare(&old, &new);
+
+        assert_eq!(
+            report.changes[0].kind,
+            ApiChangeKind::Removed { now: Some("Private".to_string()) }
+        );
+        assert_eq!(report.required_bump(), Some(SemverImpact::Major));
+    }
+
+    #[test]
+    fn test_changed_return_type_is_major_and_moved_function_is_ignored() {
+        let old = vec![
+            rust_fn("a", "pub fn a() -> u32 { 1 }", Visibility::Public),
+            rust_fn("b", "pub fn b() {}", Visibility::Public),
+        ];
+        let mut moved = rust_fn("b", "pub fn b() {}", Visibility::Public);
+        moved.interface_signature.line_range = LineRange::new(40, 40).unwrap();
+        let new = vec![rust_fn("a", "pub fn a() -> u64 { 1 }", Visibility::Public), moved];
+
+        let report = ApiReport::compare(&old, &new);
+
+        assert_eq!(report.changes.len(), 1, "{:?}", report.changes);
+        assert_eq!(report.changes[0].name, "a");
+        assert_eq!(report.changes[0].impact, SemverImpact::Major);
+    }
+}
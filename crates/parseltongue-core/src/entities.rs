@@ -53,6 +53,12 @@ impl Language {
     pub fn from_file_path(path: &PathBuf) -> Option<Self> {
         let extension = path.extension()?.to_str()?;
 
+        Self::all().into_iter().find(|&language| language.file_extensions().contains(&extension))
+    }
+
+    /// Every language parseltongue knows about, whether or not a grammar
+    /// for it is compiled in
+    pub fn all() -> [Language; 14] {
         [
             Language::Rust,
             Language::JavaScript,
@@ -68,7 +74,82 @@ impl Language {
             Language::Swift,
             Language::Kotlin,
             Language::Scala,
-        ].into_iter().find(|&language| language.file_extensions().contains(&extension))
+        ]
+    }
+
+    /// Detect language from file content (fallback for extensionless files)
+    ///
+    /// Looks at the shebang interpreter first (`#!/usr/bin/env python3`),
+    /// then at a few unambiguous opening lines (`<?php`, `package main`).
+    /// A UTF-8 BOM is ignored; NUL bytes or invalid UTF-8 mean binary data.
+    /// Returns `None` when still ambiguous — callers should skip the file
+    /// rather than guess.
+    pub fn from_content(bytes: &[u8]) -> Option<Self> {
+        const SNIFF_LIMIT: usize = 8 * 1024;
+
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        let prefix = &bytes[..bytes.len().min(SNIFF_LIMIT)];
+        if prefix.contains(&0) {
+            return None;
+        }
+        let text = match std::str::from_utf8(prefix) {
+            Ok(text) => text,
+            // Cut inside a multi-byte character at the sniff limit
+            Err(e) if e.error_len().is_none() => std::str::from_utf8(&prefix[..e.valid_up_to()]).ok()?,
+            Err(_) => return None,
+        };
+
+        // `#![...]` opens a Rust inner attribute, not a shebang
+        if let Some(shebang) = text.strip_prefix("#!").filter(|rest| !rest.starts_with('[')) {
+            let first_line = shebang.lines().next().unwrap_or_default();
+            return Self::from_interpreter(first_line);
+        }
+
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let first = lines.next()?;
+        if first.starts_with("<?php") {
+            return Some(Language::Php);
+        }
+        if let Some(name) = first.strip_prefix("package ") {
+            // Go: `package main`; Java/Kotlin/Scala use dotted names or `;`
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Some(Language::Go);
+            }
+        }
+        if first.starts_with("#![") || first.starts_with("use std::") {
+            return Some(Language::Rust);
+        }
+        if first.starts_with("from ") && first.contains(" import ") {
+            return Some(Language::Python);
+        }
+
+        None
+    }
+
+    /// Map a shebang line (without `#!`) to a language
+    ///
+    /// Handles `/usr/bin/env [-S] interpreter` and versioned interpreter
+    /// names (`python3.11`). Shells and unknown interpreters yield `None`.
+    fn from_interpreter(shebang: &str) -> Option<Self> {
+        let mut words = shebang.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+        }
+        let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+        match name {
+            "python" | "pypy" => Some(Language::Python),
+            "node" | "nodejs" => Some(Language::JavaScript),
+            "ts-node" | "deno" => Some(Language::TypeScript),
+            "ruby" => Some(Language::Ruby),
+            "php" => Some(Language::Php),
+            "rust-script" => Some(Language::Rust),
+            "kotlin" | "kscript" => Some(Language::Kotlin),
+            "scala" => Some(Language::Scala),
+            "swift" => Some(Language::Swift),
+            _ => None,
+        }
     }
 }
 
@@ -115,6 +196,73 @@ pub enum EntityType {
     Constant,
 }
 
+impl EntityType {
+    /// Value stored in the `entity_type` column of `CodeGraph`
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            EntityType::Function => "function",
+            EntityType::Method => "method",
+            EntityType::Struct => "struct",
+            EntityType::Enum => "enum",
+            EntityType::Trait => "trait",
+            EntityType::Interface => "interface",
+            EntityType::Module => "module",
+            EntityType::ImplBlock { .. } => "impl",
+            EntityType::Macro => "macro",
+            EntityType::ProcMacro => "proc_macro",
+            EntityType::TestFunction => "test",
+            EntityType::Class => "class",
+            EntityType::Variable => "variable",
+            EntityType::Constant => "constant",
+        }
+    }
+
+    /// Inverse of [`column_name`](Self::column_name)
+    ///
+    /// `"impl"` yields an `ImplBlock` with empty names, which is enough to
+    /// filter on the column.
+    pub fn from_column_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "function" => EntityType::Function,
+            "method" => EntityType::Method,
+            "struct" => EntityType::Struct,
+            "enum" => EntityType::Enum,
+            "trait" => EntityType::Trait,
+            "interface" => EntityType::Interface,
+            "module" => EntityType::Module,
+            "impl" => EntityType::ImplBlock { trait_name: None, struct_name: String::new() },
+            "macro" => EntityType::Macro,
+            "proc_macro" => EntityType::ProcMacro,
+            "test" => EntityType::TestFunction,
+            "class" => EntityType::Class,
+            "variable" => EntityType::Variable,
+            "constant" => EntityType::Constant,
+            _ => return None,
+        })
+    }
+
+    /// Every [`column_name`](Self::column_name), in declaration order
+    pub fn column_names() -> [&'static str; 14] {
+        [
+            EntityType::Function,
+            EntityType::Method,
+            EntityType::Struct,
+            EntityType::Enum,
+            EntityType::Trait,
+            EntityType::Interface,
+            EntityType::Module,
+            EntityType::ImplBlock { trait_name: None, struct_name: String::new() },
+            EntityType::Macro,
+            EntityType::ProcMacro,
+            EntityType::TestFunction,
+            EntityType::Class,
+            EntityType::Variable,
+            EntityType::Constant,
+        ]
+        .map(|entity_type| entity_type.column_name())
+    }
+}
+
 /// Temporal action for state transitions
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TemporalAction {
@@ -145,6 +293,19 @@ impl TemporalAction {
             }),
         }
     }
+
+    /// Check that the action carries the code it needs
+    ///
+    /// Create and Edit write `future_code`, so it must be present; Delete may
+    /// leave it `None`.
+    pub fn check_future_code(&self, future_code: Option<&str>) -> std::result::Result<(), String> {
+        match self {
+            TemporalAction::Create | TemporalAction::Edit if future_code.is_none() => {
+                Err(format!("{:?} action requires future_code", self))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Temporal state tracking for entities
@@ -262,6 +423,24 @@ pub struct InterfaceSignature {
     pub language_specific: LanguageSpecificSignature,
 }
 
+impl InterfaceSignature {
+    /// Visibility as seen by callers
+    ///
+    /// Java records it as an access modifier; every other language uses
+    /// `visibility`.
+    pub fn effective_visibility(&self) -> Visibility {
+        match &self.language_specific {
+            LanguageSpecificSignature::Java(java) => match java.access_modifier {
+                AccessModifier::Public => Visibility::Public,
+                AccessModifier::Protected => Visibility::Protected,
+                AccessModifier::Package => Visibility::Module,
+                AccessModifier::Private => Visibility::Private,
+            },
+            _ => self.visibility.clone(),
+        }
+    }
+}
+
 /// Visibility levels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Visibility {
@@ -480,6 +659,141 @@ pub struct CodeEntity {
     pub entity_class: EntityClass,
 }
 
+/// Location of an entity's code in its source file
+///
+/// Stored instead of the code text in spans-only ingestion; the code is read
+/// back from `file_path` on demand, so the file must still exist unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CodeSpan {
+    pub file_path: String,
+    /// Byte offset of the first byte of the code
+    pub byte_start: usize,
+    /// Byte offset one past the last byte of the code
+    pub byte_end: usize,
+}
+
+/// Which tool last wrote an entity, and when
+///
+/// Recorded by writing tools (pt03, the unified binary's edit path) so an
+/// automated change can be told apart from a manual one when auditing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Provenance {
+    /// Tool name, e.g. `pt03`
+    pub tool: String,
+    /// RFC 3339 timestamp of the write
+    pub modified_at: String,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.tool, self.modified_at)
+    }
+}
+
+/// How often an entity has been read, recorded when access logging is on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccessStats {
+    /// RFC 3339 timestamp of the most recent read
+    pub last_queried: String,
+    pub query_count: u64,
+}
+
+/// What `CozoDbStorage::upsert_entity` keeps of an entity already stored
+///
+/// The default keeps everything; `MergePolicy::OVERWRITE` starts the
+/// entity over, as if it were new.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergePolicy {
+    /// Keep the stored `created_at` instead of the new entity's
+    pub preserve_created_at: bool,
+    /// Keep counting reads on top of the stored `query_count`; otherwise
+    /// the access counters restart
+    pub accumulate_query_count: bool,
+    /// Keep stored `metadata.additional` entries the new entity does not set
+    pub keep_additional: bool,
+}
+
+impl MergePolicy {
+    /// Replace everything, like a first insert
+    pub const OVERWRITE: Self = Self {
+        preserve_created_at: false,
+        accumulate_query_count: false,
+        keep_additional: false,
+    };
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        Self {
+            preserve_created_at: true,
+            accumulate_query_count: true,
+            keep_additional: true,
+        }
+    }
+}
+
+/// A write applied under an idempotency key (pt03 `--idempotency-key`)
+///
+/// A retry carrying the same key gets this back instead of writing again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppliedWrite {
+    /// Entity the write targeted
+    pub isgl1_key: String,
+    /// `create`, `edit` or `delete`
+    pub action: String,
+    /// What the write reported, e.g. the entity's new version
+    pub result: String,
+    /// RFC 3339 timestamp of the write
+    pub applied_at: String,
+}
+
+/// Outcome of `CozoDbStorage::compact`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Entities removed because neither their current nor future version exists
+    pub tombstones_removed: usize,
+    /// On-disk size before compacting; `None` for the in-memory engine
+    pub bytes_before: Option<u64>,
+    pub bytes_after: Option<u64>,
+}
+
+impl CompactionReport {
+    /// Bytes freed on disk (0 if the database grew), `None` without a disk size
+    pub fn bytes_reclaimed(&self) -> Option<u64> {
+        Some(self.bytes_before?.saturating_sub(self.bytes_after?))
+    }
+}
+
+/// Outcome of `CozoDbStorage::copy_entities_to`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CopyReport {
+    pub entities_copied: usize,
+    pub edges_copied: usize,
+    /// Edges with one endpoint outside the copied set that were left behind
+    pub dangling_edges_dropped: usize,
+}
+
+/// Syntax problem found while ingesting a file
+///
+/// tree-sitter recovers from errors and pt01 still extracts entities from
+/// the rest of the file, so these are kept alongside the graph rather than
+/// failing ingestion.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ParseErrorRecord {
+    pub file_path: String,
+    /// 1-based
+    pub line: usize,
+    /// 1-based
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseErrorRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file_path, self.line, self.column, self.message)
+    }
+}
+
 /// Entity classification for TDD workflow
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum EntityClass {
@@ -542,18 +856,86 @@ pub enum RiskLevel {
 }
 
 /// LSP metadata from rust-analyzer
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// Stored as JSON in `lsp_meta_data`. Every field defaults, so metadata
+/// written by older versions (or holding only some fields) still parses;
+/// see [`LspMetadata::from_stored`] for pre-structured values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct LspMetadata {
+    /// Hover text (markdown) at the entity's definition
+    #[serde(default)]
+    pub hover: Option<String>,
     /// Type information
+    #[serde(default)]
     pub type_information: TypeInformation,
     /// Usage analysis
+    #[serde(default)]
     pub usage_analysis: UsageAnalysis,
+    /// Diagnostics reported inside the entity
+    #[serde(default)]
+    pub diagnostics: Vec<LspDiagnostic>,
     /// Semantic tokens
+    #[serde(default)]
     pub semantic_tokens: Vec<SemanticToken>,
 }
 
-/// Type information from LSP
+impl LspMetadata {
+    /// Parse a stored `lsp_meta_data` value
+    ///
+    /// Early versions stored the raw hover response (`{"contents": ...}`)
+    /// or plain hover text instead of this struct; those become metadata
+    /// carrying just the hover text rather than failing the read.
+    pub fn from_stored(stored: &str) -> Self {
+        const FIELDS: [&str; 5] = ["hover", "type_information", "usage_analysis", "diagnostics", "semantic_tokens"];
+
+        let hover = match serde_json::from_str::<serde_json::Value>(stored) {
+            Ok(serde_json::Value::Object(raw)) if FIELDS.iter().any(|field| raw.contains_key(*field)) => {
+                if let Ok(metadata) = serde_json::from_value(serde_json::Value::Object(raw)) {
+                    return metadata;
+                }
+                stored.to_string()
+            }
+            Ok(serde_json::Value::Object(raw)) => match raw.get("contents") {
+                Some(serde_json::Value::String(text)) => text.clone(),
+                Some(serde_json::Value::Object(markup)) => markup
+                    .get("value")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => stored.to_string(),
+            },
+            Ok(serde_json::Value::String(text)) => text,
+            _ => stored.to_string(),
+        };
+        Self {
+            hover: Some(hover),
+            ..Self::default()
+        }
+    }
+
+    /// Where the entity is defined, if the server reported it
+    pub fn definition_location(&self) -> Option<&Location> {
+        self.type_information.definition_location.as_ref()
+    }
+
+    /// Number of references to the entity
+    pub fn references_count(&self) -> usize {
+        self.usage_analysis.total_references
+    }
+}
+
+/// A diagnostic the language server reported for an entity
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LspDiagnostic {
+    /// LSP severity name: "error", "warning", "information" or "hint"
+    pub severity: String,
+    pub message: String,
+    /// 1-based line in the entity's file
+    pub line: u32,
+}
+
+/// Type information from LSP
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct TypeInformation {
     /// Resolved type
     pub resolved_type: String,
@@ -566,7 +948,7 @@ pub struct TypeInformation {
 }
 
 /// Usage analysis from LSP
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct UsageAnalysis {
     /// Total references
     pub total_references: usize,
@@ -602,6 +984,18 @@ pub struct EntityMetadata {
     pub modified_at: chrono::DateTime<chrono::Utc>,
     /// Hash of entity content
     pub content_hash: String,
+    /// Formatting-insensitive hash of the code's syntax tree (empty if unknown)
+    ///
+    /// See [`crate::semantic_hash`]; unlike `content_hash` it survives
+    /// reformatting, so it identifies "same code" across `cargo fmt` runs.
+    #[serde(default)]
+    pub semantic_hash: String,
+    /// Cyclomatic complexity of the code's syntax tree (`None` if not measured)
+    ///
+    /// See [`crate::metrics`]; `tdd_classification.complexity` is derived
+    /// from it at ingestion.
+    #[serde(default)]
+    pub complexity_score: Option<u32>,
     /// Additional key-value metadata
     pub additional: HashMap<String, String>,
 }
@@ -659,6 +1053,57 @@ impl CodeEntity {
         Ok(())
     }
 
+    /// Version token over the mutable, persisted state (pure function)
+    ///
+    /// Covers code and temporal indicators, so any write through Tool 2/3
+    /// changes it. Storage fills `metadata.content_hash` with this on read
+    /// and compares it on update to detect concurrent edits.
+    pub fn version_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for code in [&self.current_code, &self.future_code] {
+            match code {
+                Some(code) => {
+                    hasher.update([1u8]);
+                    hasher.update((code.len() as u64).to_le_bytes());
+                    hasher.update(code.as_bytes());
+                }
+                None => hasher.update([0u8]),
+            }
+        }
+        let action = match self.temporal_state.future_action {
+            Some(TemporalAction::Create) => 1u8,
+            Some(TemporalAction::Edit) => 2,
+            Some(TemporalAction::Delete) => 3,
+            None => 0,
+        };
+        hasher.update([
+            self.temporal_state.current_ind as u8,
+            self.temporal_state.future_ind as u8,
+            action,
+        ]);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Formatting-insensitive hash of `current_code` (pure function)
+    ///
+    /// `None` without current code or a grammar for the file's language.
+    pub fn semantic_hash(&self) -> Option<String> {
+        let language = Language::from_file_path(&self.interface_signature.file_path)?;
+        crate::semantic_hash::semantic_hash(self.current_code.as_deref()?, language)
+    }
+
+    /// Complexity metrics of `current_code` (pure function)
+    ///
+    /// `None` without current code or a grammar for the file's language.
+    /// Builds a parser per call; to measure many entities, use a
+    /// [`crate::metrics::ComplexityMeter`].
+    pub fn complexity_metrics(&self) -> Option<crate::metrics::ComplexityMetrics> {
+        let language = Language::from_file_path(&self.interface_signature.file_path)?;
+        crate::metrics::measure(self.current_code.as_deref()?, language)
+    }
+
     /// Check if entity is modified
     pub fn is_modified(&self) -> bool {
         self.temporal_state.is_changed()
@@ -695,6 +1140,17 @@ impl CodeEntity {
         Ok(())
     }
 
+    /// Check that `future_action` agrees with `future_code` presence
+    ///
+    /// Non-panicking; used by `db-check` to report entities left inconsistent
+    /// by a buggy writer.
+    pub fn validate_temporal_consistency(&self) -> std::result::Result<(), String> {
+        match &self.temporal_state.future_action {
+            Some(action) => action.check_future_code(self.future_code.as_deref()),
+            None => Ok(()),
+        }
+    }
+
     fn validate_isgl1_key(&self) -> Result<()> {
         if self.isgl1_key.is_empty() {
             return Err(ParseltongError::InvalidIsgl1Key {
@@ -828,12 +1284,19 @@ impl Default for TddClassification {
 
 impl EntityMetadata {
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            created_at: chrono::Utc::now(),
-            modified_at: chrono::Utc::now(),
+        Ok(Self::at(chrono::Utc::now()))
+    }
+
+    /// Metadata created and last modified at `now`
+    pub fn at(now: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            created_at: now,
+            modified_at: now,
             content_hash: String::new(), // Will be set when content is available
+            semantic_hash: String::new(),
+            complexity_score: None,
             additional: HashMap::new(),
-        })
+        }
     }
 }
 
@@ -930,19 +1393,41 @@ pub enum EdgeType {
     /// Function call relationship (A calls B)
     Calls,
     /// Usage relationship (A uses B's type/interface)
+    ///
+    /// Written by v0.9.6 and earlier for imports and type references;
+    /// new ingestion emits `Imports` / `References` instead.
     Uses,
     /// Trait implementation (A implements trait B)
     Implements,
+    /// Import relationship (file A imports module/item B)
+    Imports,
+    /// Structural nesting (A's body contains definition B)
+    Contains,
+    /// Type reference (A mentions type B in a signature or field)
+    References,
 }
 
 // S77 Pattern A.1: Expression-oriented code
 impl EdgeType {
+    /// Every edge type, in declaration order
+    pub const ALL: [EdgeType; 6] = [
+        Self::Calls,
+        Self::Uses,
+        Self::Implements,
+        Self::Imports,
+        Self::Contains,
+        Self::References,
+    ];
+
     /// Returns string representation of edge type
     pub fn as_str(self) -> &'static str {
         match self {
             Self::Calls => "Calls",
             Self::Uses => "Uses",
             Self::Implements => "Implements",
+            Self::Imports => "Imports",
+            Self::Contains => "Contains",
+            Self::References => "References",
         }
     }
 }
@@ -958,16 +1443,14 @@ impl std::str::FromStr for EdgeType {
     type Err = ParseltongError;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "Calls" => Ok(Self::Calls),
-            "Uses" => Ok(Self::Uses),
-            "Implements" => Ok(Self::Implements),
-            _ => Err(ParseltongError::ValidationError {
+        Self::ALL
+            .into_iter()
+            .find(|edge_type| edge_type.as_str() == s)
+            .ok_or_else(|| ParseltongError::ValidationError {
                 field: "edge_type".to_string(),
-                expected: "Calls, Uses, or Implements".to_string(),
+                expected: "Calls, Uses, Implements, Imports, Contains, or References".to_string(),
                 actual: s.to_owned(),
-            }),
-        }
+            })
     }
 }
 
@@ -1144,6 +1627,35 @@ mod tests {
         assert_eq!(Language::from_file_path(&unknown_path), None);
     }
 
+    #[test]
+    fn language_detection_from_content() {
+        assert_eq!(
+            Language::from_content(b"#!/usr/bin/env python3\nimport sys\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_content(b"\xEF\xBB\xBF#!/usr/bin/python3.11 -u\nprint(1)\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_content(b"#!/usr/bin/env -S node --harmony\n"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(Language::from_content(b"<?php\necho 'hi';\n"), Some(Language::Php));
+        assert_eq!(Language::from_content(b"package main\n\nfunc main() {}\n"), Some(Language::Go));
+        assert_eq!(Language::from_content(b"#![allow(dead_code)]\nfn main() {}\n"), Some(Language::Rust));
+
+        // Shell scripts are recognized as unsupported, not guessed as Rust
+        assert_eq!(Language::from_content(b"#!/bin/bash\necho hello\n"), None);
+        assert_eq!(Language::from_content(b"#!/usr/bin/env sh\nset -e\n"), None);
+
+        // Ambiguous text and binary blobs
+        assert_eq!(Language::from_content(b"hello world\n"), None);
+        assert_eq!(Language::from_content(b"package com.example;\n"), None);
+        assert_eq!(Language::from_content(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0]), None);
+        assert_eq!(Language::from_content(&[0xff, 0xfe, 0xfd]), None);
+    }
+
     #[test]
     fn code_entity_validation() {
         let mut entity = CodeEntity::new(
@@ -1191,6 +1703,55 @@ mod tests {
 
         assert!(entity.is_modified());
         assert!(entity.effective_code().is_some());
+        assert_eq!(entity.validate_temporal_consistency(), Ok(()));
+
+        // Edit without future_code (e.g. from a buggy writer)
+        entity.future_code = None;
+        assert_eq!(
+            entity.validate_temporal_consistency(),
+            Err("Edit action requires future_code".to_string())
+        );
+
+        // Delete may drop future_code
+        entity.temporal_state = TemporalState::delete();
+        assert_eq!(entity.validate_temporal_consistency(), Ok(()));
+    }
+
+    #[test]
+    fn semantic_hash_ignores_indentation_but_content_hash_does_not() {
+        let with_code = |code: &str| {
+            let mut entity = CodeEntity::new(
+                "rust:fn:add:src_lib_rs:1-3".to_string(),
+                InterfaceSignature {
+                    entity_type: EntityType::Function,
+                    name: "add".to_string(),
+                    visibility: Visibility::Public,
+                    file_path: PathBuf::from("src/lib.rs"),
+                    line_range: LineRange::new(1, 3).unwrap(),
+                    module_path: vec![],
+                    documentation: None,
+                    language_specific: LanguageSpecificSignature::Rust(RustSignature {
+                        generics: vec![],
+                        lifetimes: vec![],
+                        where_clauses: vec![],
+                        attributes: vec![],
+                        trait_impl: None,
+                    }),
+                },
+                EntityClass::CodeImplementation,
+            )
+            .unwrap();
+            entity.current_code = Some(code.to_string());
+            entity.future_code = Some(code.to_string());
+            entity
+        };
+
+        let two_spaces = with_code("pub fn add(a: i32, b: i32) -> i32 {\n  a + b\n}");
+        let four_spaces = with_code("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}");
+
+        assert!(two_spaces.semantic_hash().is_some());
+        assert_eq!(two_spaces.semantic_hash(), four_spaces.semantic_hash());
+        assert_ne!(two_spaces.version_hash(), four_spaces.version_hash());
     }
 
     #[test]
@@ -1377,6 +1938,14 @@ mod tests {
         assert!(impl_key.contains("-impl-"));
     }
 
+    #[test]
+    fn test_column_names_round_trip() {
+        for name in EntityType::column_names() {
+            let entity_type = EntityType::from_column_name(name).unwrap();
+            assert_eq!(entity_type.column_name(), name);
+        }
+    }
+
     #[test]
     fn test_entity_class_enum() {
         // Test that EntityClass enum exists with correct variants
@@ -1440,7 +2009,7 @@ mod tests {
         use std::str::FromStr;
 
         // Test all variants
-        for edge_type in [EdgeType::Calls, EdgeType::Uses, EdgeType::Implements] {
+        for edge_type in EdgeType::ALL {
             let s = edge_type.as_str();
             let parsed = EdgeType::from_str(s).unwrap();
             assert_eq!(parsed, edge_type);
@@ -0,0 +1,307 @@
+//! Typed entity filters compiled to Datalog
+//!
+//! [`QueryBuilder`] is the validated alternative to a hand-written WHERE
+//! clause. It lives in core so every tool can offer it: pt02 compiles
+//! `--entity-type`/`--visibility` through it, and pt03 re-exports it for
+//! callers composing `--query` scripts. Its output is a plain condition
+//! list, so raw Datalog stays available as the escape hatch.
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::entities::{EntityType, Visibility};
+use crate::storage::DEFAULT_RELATION_NAME;
+
+/// A `QueryBuilder` chain that does not compile to a meaningful filter
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QueryBuildError {
+    /// `.and()` / `.or()` without a condition on both sides
+    #[error("`.{connector}()` needs a condition on both sides")]
+    MissingOperand { connector: &'static str },
+
+    /// Two different values for one field ANDed together (matches nothing)
+    #[error("{field} cannot be both '{first}' and '{second}'")]
+    Contradiction {
+        field: &'static str,
+        first: String,
+        second: String,
+    },
+}
+
+/// Datalog condition keeping entities of `relation` with `last_modified`
+/// after `since`
+///
+/// `last_modified` is stored as RFC 3339 in UTC, which orders correctly as
+/// a string. `relation` is the entity relation the database was opened
+/// with (`DEFAULT_RELATION_NAME` unless pt01 chose another).
+pub fn since_condition(relation: &str, since: DateTime<Utc>) -> String {
+    format!(
+        "*{}{{ISGL1_key, last_modified}}, last_modified > '{}'",
+        relation,
+        since.to_rfc3339()
+    )
+}
+
+/// Condition keeping only entities whose stored signature has `visibility`
+///
+/// Matches the `"visibility"` member exactly as serde writes it into the
+/// stored `interface_signature` JSON.
+pub fn visibility_condition(visibility: &Visibility) -> String {
+    let member = serde_json::json!({ "visibility": visibility }).to_string();
+    format!(
+        "str_includes(interface_signature, '{}')",
+        member.trim_start_matches('{').trim_end_matches('}')
+    )
+}
+
+
+/// Typed alternative to a hand-written `--where-clause`
+///
+/// Conditions next to each other are ANDed and `.or()` starts a new
+/// alternative; AND binds tighter than OR, so `a.and().b.or().c` means
+/// `(a AND b) OR c`. Chains that cannot select anything sensible (a
+/// connector missing an operand, two entity types ANDed together) fail in
+/// [`build`](Self::build). Raw Datalog via `--where-clause` stays
+/// available for everything this does not cover.
+///
+/// ```
+/// use parseltongue_core::entities::{EntityType, Visibility};
+/// use parseltongue_core::entity_filter::QueryBuilder;
+///
+/// let filter = QueryBuilder::new()
+///     .entity_type(EntityType::Function)
+///     .and()
+///     .visibility(Visibility::Public)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     filter,
+///     r#"entity_type = 'function', str_includes(interface_signature, '"visibility":"Public"')"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    alternatives: Vec<Vec<FilterTerm>>,
+    pending: Option<&'static str>,
+    error: Option<QueryBuildError>,
+    /// Entity relation `modified_after` reads (`DEFAULT_RELATION_NAME` if unset)
+    relation: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum FilterTerm {
+    EntityType(EntityType),
+    Visibility(Visibility),
+    ModifiedAfter(DateTime<Utc>),
+}
+
+impl FilterTerm {
+    fn condition(&self, relation: &str) -> String {
+        match self {
+            FilterTerm::EntityType(entity_type) => format!("entity_type = '{}'", entity_type.column_name()),
+            FilterTerm::Visibility(visibility) => visibility_condition(visibility),
+            FilterTerm::ModifiedAfter(time) => since_condition(relation, *time),
+        }
+    }
+}
+
+impl QueryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep entities of this type
+    pub fn entity_type(self, entity_type: EntityType) -> Self {
+        self.term(FilterTerm::EntityType(entity_type))
+    }
+
+    /// Keep entities whose stored signature has this visibility
+    pub fn visibility(self, visibility: Visibility) -> Self {
+        self.term(FilterTerm::Visibility(visibility))
+    }
+
+    /// Keep entities whose `last_modified` is later than `time`
+    pub fn modified_after(self, time: DateTime<Utc>) -> Self {
+        self.term(FilterTerm::ModifiedAfter(time))
+    }
+
+    /// Entity relation of the database the filter runs against
+    pub fn relation(mut self, relation: impl Into<String>) -> Self {
+        self.relation = Some(relation.into());
+        self
+    }
+
+    /// AND the conditions on either side (the default between conditions)
+    pub fn and(self) -> Self {
+        self.connector("and")
+    }
+
+    /// OR the AND-groups on either side
+    pub fn or(self) -> Self {
+        self.connector("or")
+    }
+
+    /// Datalog condition for `query_entities`, or `"ALL"` for an empty chain
+    pub fn build(self) -> Result<String, QueryBuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if let Some(connector) = self.pending {
+            return Err(QueryBuildError::MissingOperand { connector });
+        }
+        for group in &self.alternatives {
+            check_consistent(group)?;
+        }
+
+        let relation = self.relation.as_deref().unwrap_or(DEFAULT_RELATION_NAME);
+        let groups: Vec<String> = self
+            .alternatives
+            .iter()
+            .map(|group| group.iter().map(|term| term.condition(relation)).collect::<Vec<_>>().join(", "))
+            .collect();
+        Ok(match groups.len() {
+            0 => "ALL".to_string(),
+            1 => groups.into_iter().next().unwrap(),
+            // Cozo binds `or` tighter than `,`, so each group needs its own parens
+            _ => format!("(({}))", groups.join(") or (")),
+        })
+    }
+
+    fn term(mut self, term: FilterTerm) -> Self {
+        match (self.pending.take(), self.alternatives.last_mut()) {
+            (Some("or"), _) | (_, None) => self.alternatives.push(vec![term]),
+            (_, Some(group)) => group.push(term),
+        }
+        self
+    }
+
+    fn connector(mut self, connector: &'static str) -> Self {
+        if self.error.is_none() && (self.alternatives.is_empty() || self.pending.is_some()) {
+            self.error = Some(QueryBuildError::MissingOperand { connector });
+        }
+        self.pending = Some(connector);
+        self
+    }
+}
+
+/// Reject an AND-group that asks for two different entity types or
+/// visibilities, which no entity can satisfy
+fn check_consistent(group: &[FilterTerm]) -> Result<(), QueryBuildError> {
+    let mut entity_type: Option<&'static str> = None;
+    let mut visibility: Option<&Visibility> = None;
+    for term in group {
+        match term {
+            FilterTerm::EntityType(next) => {
+                let next = next.column_name();
+                match entity_type {
+                    Some(first) if first != next => {
+                        return Err(QueryBuildError::Contradiction {
+                            field: "entity_type",
+                            first: first.to_string(),
+                            second: next.to_string(),
+                        })
+                    }
+                    _ => entity_type = Some(next),
+                }
+            }
+            FilterTerm::Visibility(next) => match visibility {
+                Some(first) if first != next => {
+                    return Err(QueryBuildError::Contradiction {
+                        field: "visibility",
+                        first: format!("{:?}", first),
+                        second: format!("{:?}", next),
+                    })
+                }
+                _ => visibility = Some(next),
+            },
+            FilterTerm::ModifiedAfter(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{InterfaceSignature, LanguageSpecificSignature, LineRange, RustSignature};
+
+    #[test]
+    fn test_query_builder_compiles_and_groups_before_or() {
+        let since = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(QueryBuilder::new().build().unwrap(), "ALL");
+        assert_eq!(
+            QueryBuilder::new().visibility(Visibility::Public).build().unwrap(),
+            r#"str_includes(interface_signature, '"visibility":"Public"')"#
+        );
+        assert_eq!(
+            QueryBuilder::new()
+                .entity_type(EntityType::Function)
+                .and()
+                .modified_after(since)
+                .or()
+                .entity_type(EntityType::Struct)
+                .build()
+                .unwrap(),
+            "((entity_type = 'function', *CodeGraph{ISGL1_key, last_modified}, \
+             last_modified > '2024-01-01T00:00:00+00:00') or (entity_type = 'struct'))"
+        );
+    }
+
+    #[test]
+    fn test_query_builder_rejects_invalid_chains() {
+        let missing = |connector| Err(QueryBuildError::MissingOperand { connector });
+
+        assert_eq!(QueryBuilder::new().and().entity_type(EntityType::Enum).build(), missing("and"));
+        assert_eq!(QueryBuilder::new().entity_type(EntityType::Enum).or().build(), missing("or"));
+        assert_eq!(
+            QueryBuilder::new().entity_type(EntityType::Enum).and().or().entity_type(EntityType::Trait).build(),
+            missing("or")
+        );
+        assert_eq!(
+            QueryBuilder::new()
+                .entity_type(EntityType::Function)
+                .and()
+                .entity_type(EntityType::Struct)
+                .build(),
+            Err(QueryBuildError::Contradiction {
+                field: "entity_type",
+                first: "function".to_string(),
+                second: "struct".to_string(),
+            })
+        );
+        assert!(QueryBuilder::new()
+            .visibility(Visibility::Public)
+            .or()
+            .visibility(Visibility::Private)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_visibility_condition_matches_stored_signature() {
+        let signature = InterfaceSignature {
+            entity_type: EntityType::Function,
+            name: "api".to_string(),
+            visibility: Visibility::Crate,
+            file_path: "src/lib.rs".into(),
+            line_range: LineRange::new(1, 3).unwrap(),
+            module_path: vec![],
+            documentation: None,
+            language_specific: LanguageSpecificSignature::Rust(RustSignature {
+                generics: vec![],
+                lifetimes: vec![],
+                where_clauses: vec![],
+                attributes: vec![],
+                trait_impl: None,
+            }),
+        };
+        let stored = serde_json::to_string(&signature).unwrap();
+        let condition = visibility_condition(&Visibility::Crate);
+        let needle = condition
+            .strip_prefix("str_includes(interface_signature, '")
+            .and_then(|rest| rest.strip_suffix("')"))
+            .unwrap();
+        assert!(stored.contains(needle), "{} not in {}", needle, stored);
+    }
+}
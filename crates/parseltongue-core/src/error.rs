@@ -24,6 +24,16 @@ pub enum ParseltongError {
         isgl1_key: String,
     },
 
+    /// Concurrent edit conflict: the stored entity changed since it was read
+    ///
+    /// Re-read the entity (new `content_hash`) and retry the write.
+    #[error("Conflicting write to {isgl1_key}: expected version {expected}, found {actual}")]
+    ConflictError {
+        isgl1_key: String,
+        expected: String,
+        actual: String,
+    },
+
     /// File system operation errors
     #[error("File system error: {path} - {source}")]
     FileSystemError {
@@ -129,6 +139,13 @@ pub enum ParseltongError {
         from_key: String,
         to_key: String,
     },
+
+    /// Output sink write or dispatch errors
+    #[error("Output to {destination} failed: {reason}")]
+    OutputError {
+        destination: String,
+        reason: String,
+    },
 }
 
 /// Result type alias for convenience
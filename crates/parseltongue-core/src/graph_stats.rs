@@ -0,0 +1,181 @@
+//! Per-entity centrality over the dependency graph.
+//!
+//! Computed from the `DependencyEdges` table alone, so every key that
+//! appears on an edge gets metrics, whether or not it has an entity row.
+//!
+//! - **In-degree / out-degree**: distinct callers and callees (fan-in and
+//!   fan-out); parallel edges of different types count once.
+//! - **Betweenness** (Brandes): how many shortest paths between other
+//!   entities pass through this one. Exact up to
+//!   `EXACT_BETWEENNESS_LIMIT` nodes; above that it is estimated from
+//!   `BETWEENNESS_SAMPLES` evenly spaced source nodes and scaled up, which
+//!   keeps the ranking while bounding the cost on large graphs.
+//! - **God object**: fan-in and fan-out both at least `GOD_OBJECT_DEGREE`,
+//!   an entity many things depend on that itself depends on many things.
+
+use std::collections::{HashMap, VecDeque};
+
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::DependencyEdge;
+use crate::error::Result;
+use crate::storage::CozoDbStorage;
+
+/// Graphs up to this many nodes get exact betweenness
+pub const EXACT_BETWEENNESS_LIMIT: usize = 2_000;
+
+/// Source nodes sampled for approximate betweenness
+pub const BETWEENNESS_SAMPLES: usize = 256;
+
+/// Fan-in and fan-out an entity needs to be flagged as a god object
+pub const GOD_OBJECT_DEGREE: usize = 10;
+
+/// Centrality of one entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CentralityMetrics {
+    /// Distinct entities depending on this one (fan-in)
+    pub in_degree: usize,
+    /// Distinct entities this one depends on (fan-out)
+    pub out_degree: usize,
+    /// Shortest paths between other entities passing through this one
+    /// (approximate above `EXACT_BETWEENNESS_LIMIT` nodes)
+    pub betweenness: f64,
+    /// High fan-in and high fan-out (see `GOD_OBJECT_DEGREE`)
+    pub god_object: bool,
+}
+
+/// Centrality of every entity on an edge in `storage`, keyed by ISGL1 key
+pub async fn compute_centrality(storage: &CozoDbStorage) -> Result<HashMap<String, CentralityMetrics>> {
+    let edges = storage.get_all_dependencies().await?;
+    Ok(centrality_from_edges(&edges))
+}
+
+/// Centrality of every entity on `edges`
+pub fn centrality_from_edges(edges: &[DependencyEdge]) -> HashMap<String, CentralityMetrics> {
+    let mut graph: DiGraph<&str, ()> = DiGraph::new();
+    let mut nodes: HashMap<&str, NodeIndex> = HashMap::new();
+    for edge in edges {
+        let from = *nodes.entry(edge.from_key.as_str()).or_insert_with(|| graph.add_node(edge.from_key.as_str()));
+        let to = *nodes.entry(edge.to_key.as_str()).or_insert_with(|| graph.add_node(edge.to_key.as_str()));
+        if from != to && graph.find_edge(from, to).is_none() {
+            graph.add_edge(from, to, ());
+        }
+    }
+
+    let betweenness = betweenness(&graph);
+    graph
+        .node_indices()
+        .map(|node| {
+            let in_degree = graph.neighbors_directed(node, petgraph::Incoming).count();
+            let out_degree = graph.neighbors_directed(node, petgraph::Outgoing).count();
+            let metrics = CentralityMetrics {
+                in_degree,
+                out_degree,
+                betweenness: betweenness[node.index()],
+                god_object: in_degree >= GOD_OBJECT_DEGREE && out_degree >= GOD_OBJECT_DEGREE,
+            };
+            (graph[node].to_string(), metrics)
+        })
+        .collect()
+}
+
+/// Brandes betweenness, from every node or from evenly spaced samples
+fn betweenness(graph: &DiGraph<&str, ()>) -> Vec<f64> {
+    let count = graph.node_count();
+    let mut centrality = vec![0.0; count];
+    if count == 0 {
+        return centrality;
+    }
+    let (step, scale) = if count <= EXACT_BETWEENNESS_LIMIT {
+        (1, 1.0)
+    } else {
+        let step = count.div_ceil(BETWEENNESS_SAMPLES);
+        (step, step as f64)
+    };
+
+    let mut sigma = vec![0.0_f64; count];
+    let mut distance = vec![usize::MAX; count];
+    let mut delta = vec![0.0_f64; count];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for source in (0..count).step_by(step) {
+        sigma.iter_mut().for_each(|s| *s = 0.0);
+        distance.iter_mut().for_each(|d| *d = usize::MAX);
+        delta.iter_mut().for_each(|d| *d = 0.0);
+        predecessors.iter_mut().for_each(Vec::clear);
+
+        // Breadth-first search counting shortest paths from `source`
+        let mut order = Vec::with_capacity(count);
+        let mut queue = VecDeque::from([source]);
+        sigma[source] = 1.0;
+        distance[source] = 0;
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for next in graph.neighbors_directed(NodeIndex::new(node), petgraph::Outgoing) {
+                let next = next.index();
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[node] + 1;
+                    queue.push_back(next);
+                }
+                if distance[next] == distance[node] + 1 {
+                    sigma[next] += sigma[node];
+                    predecessors[next].push(node);
+                }
+            }
+        }
+
+        // Accumulate dependencies back from the farthest nodes
+        for &node in order.iter().rev() {
+            for &previous in &predecessors[node] {
+                delta[previous] += sigma[previous] / sigma[node] * (1.0 + delta[node]);
+            }
+            if node != source {
+                centrality[node] += delta[node] * scale;
+            }
+        }
+    }
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::EdgeType;
+
+    fn edge(from: &str, to: &str) -> DependencyEdge {
+        DependencyEdge::new(from, to, EdgeType::Calls, None).unwrap()
+    }
+
+    #[test]
+    fn test_star_hub_has_highest_in_degree() {
+        // Twelve leaves call the hub; the hub calls twelve helpers
+        let mut edges = Vec::new();
+        for i in 0..12 {
+            edges.push(edge(&format!("leaf{}", i), "hub"));
+            edges.push(edge("hub", &format!("helper{}", i)));
+        }
+        edges.push(edge("leaf0", "hub")); // duplicate edge counts once
+
+        let metrics = centrality_from_edges(&edges);
+        let hub = &metrics["hub"];
+        assert_eq!((hub.in_degree, hub.out_degree), (12, 12));
+        assert!(hub.god_object);
+        let (top, _) = metrics.iter().max_by_key(|(_, m)| m.in_degree).unwrap();
+        assert_eq!(top, "hub");
+
+        // Every leaf-to-helper path runs through the hub
+        assert_eq!(hub.betweenness, 144.0);
+        assert_eq!(metrics["leaf3"].betweenness, 0.0);
+        assert!(!metrics["leaf3"].god_object);
+    }
+
+    #[test]
+    fn test_chain_betweenness_counts_paths_through_each_node() {
+        // a -> b -> c -> d
+        let metrics = centrality_from_edges(&[edge("a", "b"), edge("b", "c"), edge("c", "d")]);
+        assert_eq!(metrics["b"].betweenness, 2.0); // a->c, a->d
+        assert_eq!(metrics["c"].betweenness, 2.0); // a->d, b->d
+        assert_eq!(metrics["a"].betweenness, 0.0);
+        assert!(centrality_from_edges(&[]).is_empty());
+    }
+}
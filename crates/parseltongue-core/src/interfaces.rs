@@ -6,6 +6,7 @@
 use crate::entities::*;
 use crate::error::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -412,7 +413,7 @@ pub enum OptimizationStrategy {
 }
 
 /// CodeGraph context for LLM
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeGraphContext {
     pub version: String,
     pub generated_at: chrono::DateTime<chrono::Utc>,
@@ -423,7 +424,7 @@ pub struct CodeGraphContext {
 }
 
 /// Context entity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextEntity {
     pub isgl1_key: String,
     pub interface_signature: InterfaceSignature,
@@ -434,7 +435,7 @@ pub struct ContextEntity {
 }
 
 /// Context relationship
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextRelationship {
     pub dependent: String,
     pub dependency: String,
@@ -443,7 +444,7 @@ pub struct ContextRelationship {
 }
 
 /// Optimization information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizationInfo {
     pub excluded_entities: Vec<String>,
     pub truncation_applied: bool,
@@ -0,0 +1,191 @@
+//! ISGL1 key formats.
+//!
+//! Two layouts of the same five components exist:
+//!
+//! - **Colon-qualified** (default): `rust:fn:add:src_lib_rs:10-20`
+//! - **Dash-simple**: `src_lib_rs-add-fn-rust-10-20`, path first, for
+//!   consumers that treat `:` as a separator of their own
+//!
+//! pt01 emits whichever `KeyFormat` it is configured with. Every tool that
+//! reads components out of a key goes through [`parse_key`], which tells
+//! the two apart by the presence of `:` (the dash form never contains one),
+//! so either layout works end to end. The dash form flattens separators
+//! inside its path and name segments, since they would be ambiguous.
+
+use crate::error::{ParseltongError, Result};
+
+/// Layout of generated ISGL1 keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyFormat {
+    /// `{language}:{type}:{name}:{path}:{start}-{end}`
+    #[default]
+    ColonQualified,
+    /// `{path}-{name}-{type}-{language}-{start}-{end}`
+    ///
+    /// `-` and `:` inside the path and name segments become `_`.
+    DashSimple,
+}
+
+/// The parts an ISGL1 key encodes
+///
+/// `file_path` is the sanitized path (`src_lib_rs`) and `name` the
+/// normalized name segment, exactly as they appear in the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyComponents {
+    pub language: String,
+    pub entity_type: String,
+    pub name: String,
+    pub file_path: String,
+    pub line_range: (usize, usize),
+}
+
+impl KeyFormat {
+    /// Build the key for `components` in this format
+    pub fn format(self, components: &KeyComponents) -> String {
+        let (start, end) = components.line_range;
+        match self {
+            KeyFormat::ColonQualified => format!(
+                "{}:{}:{}:{}:{}-{}",
+                components.language, components.entity_type, components.name, components.file_path, start, end
+            ),
+            KeyFormat::DashSimple => format!(
+                "{}-{}-{}-{}-{}-{}",
+                dash_segment(&components.file_path),
+                dash_segment(&components.name),
+                components.entity_type,
+                components.language,
+                start,
+                end
+            ),
+        }
+    }
+
+    /// Split a key written in this format back into its components
+    ///
+    /// A colon-qualified key may end in a single line (`...:42`), read as
+    /// the one-line range `42-42`.
+    pub fn parse(self, key: &str) -> Result<KeyComponents> {
+        let invalid = |reason: &str| ParseltongError::InvalidIsgl1Key {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        };
+        match self {
+            KeyFormat::ColonQualified => {
+                // Preserved names may contain `::`, so the name is whatever
+                // lies between the two leading and two trailing segments
+                let parts: Vec<&str> = key.split(':').collect();
+                if parts.len() < 5 {
+                    return Err(invalid("expected language:type:name:path:start-end"));
+                }
+                let range = parts[parts.len() - 1];
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let start = line(start).ok_or_else(|| invalid("bad start line"))?;
+                let end = line(end).ok_or_else(|| invalid("bad end line"))?;
+                Ok(KeyComponents {
+                    language: parts[0].to_string(),
+                    entity_type: parts[1].to_string(),
+                    name: parts[2..parts.len() - 2].join(":"),
+                    file_path: parts[parts.len() - 2].to_string(),
+                    line_range: (start, end),
+                })
+            }
+            KeyFormat::DashSimple => {
+                let mut parts = key.rsplitn(5, '-');
+                let end = parts.next().and_then(line).ok_or_else(|| invalid("bad end line"))?;
+                let start = parts.next().and_then(line).ok_or_else(|| invalid("bad start line"))?;
+                let language = parts.next().ok_or_else(|| invalid("missing language"))?;
+                let entity_type = parts.next().ok_or_else(|| invalid("missing entity type"))?;
+                let (file_path, name) = parts
+                    .next()
+                    .and_then(|rest| rest.split_once('-'))
+                    .ok_or_else(|| invalid("expected path-name-type-language-start-end"))?;
+                Ok(KeyComponents {
+                    language: language.to_string(),
+                    entity_type: entity_type.to_string(),
+                    name: name.to_string(),
+                    file_path: file_path.to_string(),
+                    line_range: (start, end),
+                })
+            }
+        }
+    }
+
+    /// Format a key is written in: dash-simple keys never contain `:`
+    pub fn of(key: &str) -> Self {
+        if key.contains(':') {
+            KeyFormat::ColonQualified
+        } else {
+            KeyFormat::DashSimple
+        }
+    }
+}
+
+/// Parse a key in either format
+pub fn parse_key(key: &str) -> Result<KeyComponents> {
+    KeyFormat::of(key).parse(key)
+}
+
+fn dash_segment(segment: &str) -> String {
+    segment.replace(['-', ':'], "_")
+}
+
+fn line(text: &str) -> Option<usize> {
+    text.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(name: &str) -> KeyComponents {
+        KeyComponents {
+            language: "rust".to_string(),
+            entity_type: "fn".to_string(),
+            name: name.to_string(),
+            file_path: "src_lib_rs".to_string(),
+            line_range: (10, 20),
+        }
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(KeyFormat::ColonQualified.format(&components("add")), "rust:fn:add:src_lib_rs:10-20");
+        assert_eq!(KeyFormat::DashSimple.format(&components("add")), "src_lib_rs-add-fn-rust-10-20");
+    }
+
+    #[test]
+    fn test_each_format_round_trips() {
+        for format in [KeyFormat::ColonQualified, KeyFormat::DashSimple] {
+            let key = format.format(&components("add"));
+            assert_eq!(KeyFormat::of(&key), format);
+            assert_eq!(parse_key(&key).unwrap(), components("add"), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_colon_key_keeps_qualified_names() {
+        let key = KeyFormat::ColonQualified.format(&components("Foo::bar"));
+        assert_eq!(parse_key(&key).unwrap(), components("Foo::bar"));
+    }
+
+    #[test]
+    fn test_dash_key_flattens_separators_in_segments() {
+        let key = KeyFormat::DashSimple.format(&components("Foo::bar-baz"));
+        assert_eq!(key, "src_lib_rs-Foo__bar_baz-fn-rust-10-20");
+        assert_eq!(parse_key(&key).unwrap(), components("Foo__bar_baz"));
+    }
+
+    #[test]
+    fn test_single_line_colon_key() {
+        assert_eq!(parse_key("rust:fn:main:src_main_rs:42").unwrap().line_range, (42, 42));
+    }
+
+    #[test]
+    fn test_malformed_keys_are_rejected() {
+        assert!(parse_key("rust:fn:add").is_err());
+        assert!(parse_key("rust:fn:add:src_lib_rs:ten-20").is_err());
+        assert!(parse_key("src_lib_rs-add-fn-rust").is_err());
+        // Hash-based keys of new entities carry no line range
+        assert!(parse_key("src_lib_rs-new_feature-fn-abc12345").is_err());
+    }
+}
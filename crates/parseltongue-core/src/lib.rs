@@ -8,19 +8,35 @@
 #![warn(rust_2018_idioms)]
 #![allow(missing_docs)]
 
+pub mod api_report;
+pub mod clock;
 pub mod entities;
 pub mod entity_class_specifications;
+pub mod entity_filter;
 pub mod error;
+pub mod graph_stats;
+pub mod isgl1_key;
 pub mod interfaces;
+pub mod llm_backend;
+pub mod metrics;
+pub mod output_sink;
+#[cfg(feature = "parquet")]
+pub mod parquet_export;
 pub mod query_extractor;
+pub mod semantic_hash;
 pub mod serializers; // v0.10.0: Core serialization (JSON, TOON)
+pub mod signature_diff;
 pub mod storage;
 pub mod temporal;
+pub mod text;
 
 // Re-export commonly used types
+pub use clock::{Clock, FixedClock, SystemClock};
 pub use entities::*;
 pub use error::*;
 pub use interfaces::*;
+pub use llm_backend::*;
+pub use output_sink::{sink_for_output, FileSink, OutputSink};
 pub use serializers::*; // Export Serializer trait + implementations
 pub use storage::*;
 pub use temporal::*;
\ No newline at end of file
@@ -0,0 +1,316 @@
+//! LLM backend selection shared by every LLM-facing tool.
+//!
+//! Tools pick a backend from `--llm-backend` or the `PARSELTONGUE_LLM_BACKEND`
+//! environment variable. The `Mock` backend never touches the network and
+//! returns deterministic responses derived from the request, so the full
+//! pt02 → pt03 pipeline can run hermetically in CI.
+
+use crate::entities::{CodeEntity, TemporalAction};
+use crate::error::{ParseltongError, Result};
+use crate::interfaces::*;
+use async_trait::async_trait;
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable consulted when no `--llm-backend` flag is given
+pub const LLM_BACKEND_ENV_VAR: &str = "PARSELTONGUE_LLM_BACKEND";
+
+/// Endpoint used by `local` when no explicit endpoint is supplied
+pub const DEFAULT_LOCAL_ENDPOINT: &str = "http://localhost:11434/v1/chat/completions";
+
+/// Which LLM implementation a tool should talk to
+///
+/// Accepted spellings: `openai`, `mock`, `local`, `local:<endpoint>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LlmBackend {
+    /// Hosted OpenAI-compatible API (requires credentials)
+    #[default]
+    OpenAi,
+    /// Deterministic in-process backend, no network access
+    Mock,
+    /// Self-hosted OpenAI-compatible endpoint
+    Local(String),
+}
+
+impl LlmBackend {
+    /// Resolve the backend: CLI value first, then environment, then default
+    ///
+    /// Empty values are treated as absent so `PARSELTONGUE_LLM_BACKEND=` does
+    /// not shadow the default.
+    pub fn resolve(cli_value: Option<&str>) -> Result<Self> {
+        let env_value = std::env::var(LLM_BACKEND_ENV_VAR).ok();
+        Self::resolve_from(cli_value, env_value.as_deref())
+    }
+
+    /// Pure form of [`LlmBackend::resolve`] with the environment passed in
+    pub fn resolve_from(cli_value: Option<&str>, env_value: Option<&str>) -> Result<Self> {
+        match cli_value
+            .or(env_value)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            Some(value) => value.parse(),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Whether this backend performs network calls
+    pub fn requires_network(&self) -> bool {
+        !matches!(self, LlmBackend::Mock)
+    }
+}
+
+impl FromStr for LlmBackend {
+    type Err = ParseltongError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lowered = s.to_ascii_lowercase();
+        match lowered.as_str() {
+            "openai" => Ok(LlmBackend::OpenAi),
+            "mock" => Ok(LlmBackend::Mock),
+            "local" => Ok(LlmBackend::Local(DEFAULT_LOCAL_ENDPOINT.to_string())),
+            _ if lowered.starts_with("local:") => {
+                let endpoint = s["local:".len()..].trim();
+                if endpoint.is_empty() {
+                    return Err(ParseltongError::ConfigurationError {
+                        details: "local LLM backend requires an endpoint after 'local:'".to_string(),
+                    });
+                }
+                Ok(LlmBackend::Local(endpoint.to_string()))
+            }
+            _ => Err(ParseltongError::ConfigurationError {
+                details: format!(
+                    "unknown LLM backend '{}' (expected openai, mock, local or local:<endpoint>)",
+                    s
+                ),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for LlmBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LlmBackend::OpenAi => write!(f, "openai"),
+            LlmBackend::Mock => write!(f, "mock"),
+            LlmBackend::Local(endpoint) => write!(f, "local:{}", endpoint),
+        }
+    }
+}
+
+/// Deterministic offline LLM client
+///
+/// Responses echo the request: every context entity becomes an `Edit`
+/// proposal whose content is the instruction applied to the entity name.
+/// Identical requests always yield identical responses.
+#[derive(Debug, Clone, Default)]
+pub struct MockLlmClient {
+    _private: (),
+}
+
+impl MockLlmClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn propose_change(entity: &ContextEntity, request: &LlmRequest) -> ProposedChange {
+        let affected_dependencies = request
+            .context
+            .relationships
+            .iter()
+            .filter(|rel| rel.dependent == entity.isgl1_key)
+            .map(|rel| rel.dependency.clone())
+            .collect();
+
+        ProposedChange {
+            target_entity: entity.isgl1_key.clone(),
+            change_type: TemporalAction::Edit,
+            new_content: format!(
+                "// mock: {}\n{}",
+                request.task.instruction, entity.interface_signature.name
+            ),
+            justification: format!("mock backend echo for {}", entity.isgl1_key),
+            affected_dependencies,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for MockLlmClient {
+    async fn send_request(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let proposed_changes = request
+            .context
+            .entities
+            .iter()
+            .map(|entity| Self::propose_change(entity, &request))
+            .collect::<Vec<_>>();
+
+        Ok(LlmResponse {
+            request_id: request.request_id,
+            reasoning: format!(
+                "mock backend: {:?} over {} entities",
+                request.task.task_type,
+                proposed_changes.len()
+            ),
+            proposed_changes,
+            confidence_score: 1.0,
+            validation_status: ValidationStatus::Valid,
+        })
+    }
+
+    fn validate_response(&self, response: &LlmResponse, request: &LlmRequest) -> Result<()> {
+        if response.request_id != request.request_id {
+            return Err(ParseltongError::LlmError {
+                reason: format!(
+                    "response id {} does not match request id {}",
+                    response.request_id, request.request_id
+                ),
+            });
+        }
+        if response.confidence_score < request.constraints.min_confidence {
+            return Err(ParseltongError::LlmError {
+                reason: format!(
+                    "confidence {} below required {}",
+                    response.confidence_score, request.constraints.min_confidence
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    async fn get_rate_limit_status(&self) -> Result<RateLimitStatus> {
+        Ok(RateLimitStatus {
+            requests_remaining: u32::MAX,
+            reset_time: std::time::UNIX_EPOCH,
+            limit: u32::MAX,
+        })
+    }
+
+    fn estimate_tokens(&self, content: &str) -> usize {
+        content.len() / 4
+    }
+}
+
+#[async_trait]
+impl ContextGenerator for MockLlmClient {
+    async fn generate_context(
+        &self,
+        entities: Vec<CodeEntity>,
+        query: &ContextQuery,
+    ) -> Result<CodeGraphContext> {
+        let mut context_entities: Vec<ContextEntity> = entities
+            .into_iter()
+            .map(|entity| {
+                let is_base = query.base_entities.contains(&entity.isgl1_key);
+                ContextEntity {
+                    isgl1_key: entity.isgl1_key,
+                    interface_signature: entity.interface_signature,
+                    tdd_classification: entity.tdd_classification,
+                    lsp_metadata: entity.lsp_metadata,
+                    relevance_score: if is_base { 1.0 } else { 0.5 },
+                    dependency_level: if is_base { 0 } else { 1 },
+                }
+            })
+            .collect();
+        context_entities.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+
+        let mut context = CodeGraphContext {
+            version: "mock-1".to_string(),
+            generated_at: chrono::DateTime::<chrono::Utc>::default(),
+            token_count: 0,
+            entities: context_entities,
+            relationships: vec![],
+            optimization_info: OptimizationInfo {
+                excluded_entities: vec![],
+                truncation_applied: false,
+                prioritization_strategy: format!("{:?}", query.optimization_strategy),
+            },
+        };
+        self.optimize_context(&mut context, query.size_limit)?;
+        Ok(context)
+    }
+
+    fn optimize_context(&self, context: &mut CodeGraphContext, token_limit: usize) -> Result<()> {
+        // Drop least relevant entities first; ties broken by key for determinism
+        context.entities.sort_by(|a, b| {
+            b.relevance_score
+                .total_cmp(&a.relevance_score)
+                .then_with(|| a.isgl1_key.cmp(&b.isgl1_key))
+        });
+        while ContextGenerator::estimate_tokens(self, context) > token_limit {
+            match context.entities.pop() {
+                Some(dropped) => {
+                    context.optimization_info.excluded_entities.push(dropped.isgl1_key);
+                    context.optimization_info.truncation_applied = true;
+                }
+                None => break,
+            }
+        }
+        context.token_count = ContextGenerator::estimate_tokens(self, context);
+        Ok(())
+    }
+
+    fn estimate_tokens(&self, context: &CodeGraphContext) -> usize {
+        context
+            .entities
+            .iter()
+            .map(|entity| (entity.isgl1_key.len() + entity.interface_signature.name.len()) / 4 + 1)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_backend_spellings() {
+        assert_eq!("openai".parse::<LlmBackend>().unwrap(), LlmBackend::OpenAi);
+        assert_eq!("MOCK".parse::<LlmBackend>().unwrap(), LlmBackend::Mock);
+        assert_eq!(
+            "local".parse::<LlmBackend>().unwrap(),
+            LlmBackend::Local(DEFAULT_LOCAL_ENDPOINT.to_string())
+        );
+        assert_eq!(
+            "local:http://10.0.0.2:8080/v1/chat/completions".parse::<LlmBackend>().unwrap(),
+            LlmBackend::Local("http://10.0.0.2:8080/v1/chat/completions".to_string())
+        );
+        assert!("local:".parse::<LlmBackend>().is_err());
+        assert!("anthropomorphic".parse::<LlmBackend>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for backend in [
+            LlmBackend::OpenAi,
+            LlmBackend::Mock,
+            LlmBackend::Local("http://host:1234/v1".to_string()),
+        ] {
+            assert_eq!(backend.to_string().parse::<LlmBackend>().unwrap(), backend);
+        }
+    }
+
+    #[test]
+    fn cli_value_takes_precedence_over_env() {
+        assert_eq!(
+            LlmBackend::resolve_from(Some("mock"), Some("openai")).unwrap(),
+            LlmBackend::Mock
+        );
+        assert_eq!(
+            LlmBackend::resolve_from(None, Some("mock")).unwrap(),
+            LlmBackend::Mock
+        );
+        assert_eq!(
+            LlmBackend::resolve_from(None, Some("  ")).unwrap(),
+            LlmBackend::OpenAi
+        );
+        assert_eq!(LlmBackend::resolve_from(None, None).unwrap(), LlmBackend::OpenAi);
+    }
+
+    #[test]
+    fn only_mock_is_offline() {
+        assert!(!LlmBackend::Mock.requires_network());
+        assert!(LlmBackend::OpenAi.requires_network());
+        assert!(LlmBackend::Local(DEFAULT_LOCAL_ENDPOINT.to_string()).requires_network());
+    }
+}
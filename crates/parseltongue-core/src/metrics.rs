@@ -0,0 +1,316 @@
+//! Complexity metrics computed from tree-sitter syntax trees.
+//!
+//! Both metrics count syntax nodes, never text, so keywords inside strings,
+//! comments or identifiers do not contribute. Node kinds are matched across
+//! the bundled grammars (`if_expression` in Rust, `if_statement` in most
+//! others, `elif_clause` in Python, ...), so one tree of any supported
+//! language can be measured without knowing which grammar produced it.
+//!
+//! - **Cyclomatic complexity** (McCabe): one plus the number of decision
+//!   points - conditionals, loops, `case`/match arms, catch clauses,
+//!   ternaries, `&&`/`||` operators and Rust's `?`.
+//! - **Cognitive complexity** (SonarSource): each break in linear flow
+//!   (`if`, loop, switch/match, catch, ternary) costs one plus its nesting
+//!   depth; `else`/`elif` branches and each run of identical boolean
+//!   operators cost one. Closures and lambdas deepen nesting for free.
+
+use std::collections::HashMap;
+
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::entities::{ComplexityLevel, Language};
+use crate::query_extractor::tree_sitter_language;
+
+const IF_KINDS: &[&str] = &["if_expression", "if_statement", "if", "unless", "if_modifier", "unless_modifier"];
+const ELIF_KINDS: &[&str] = &["elif_clause", "elsif"];
+const ELSE_KINDS: &[&str] = &["else_clause", "else"];
+const LOOP_KINDS: &[&str] = &[
+    "for_expression",
+    "while_expression",
+    "loop_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "while_statement",
+    "do_statement",
+    "repeat_while_statement",
+    "for",
+    "while",
+    "until",
+    "while_modifier",
+    "until_modifier",
+];
+const SWITCH_KINDS: &[&str] = &[
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+    "case",
+];
+const CASE_KINDS: &[&str] = &[
+    "match_arm",
+    "switch_case",
+    "switch_label",
+    "switch_section",
+    "case_statement",
+    "case_clause",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "when",
+];
+const CATCH_KINDS: &[&str] = &["catch_clause", "except_clause", "rescue"];
+const TERNARY_KINDS: &[&str] = &["ternary_expression", "conditional_expression"];
+const LAMBDA_KINDS: &[&str] = &[
+    "closure_expression",
+    "lambda",
+    "lambda_expression",
+    "arrow_function",
+    "function_expression",
+    "anonymous_function",
+    "func_literal",
+];
+const LOGICAL_OPERATORS: &[&str] = &["&&", "||", "and", "or"];
+
+/// Scores from which a function rates `Moderate` and `Complex`
+const MODERATE_FROM: u32 = 5;
+const COMPLEX_FROM: u32 = 11;
+
+/// Both metrics for one piece of code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityMetrics {
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+}
+
+/// Parse `source` and measure it
+///
+/// `None` when no grammar is bundled for `language`. Builds a parser per
+/// call; use a [`ComplexityMeter`] to measure many entities.
+pub fn measure(source: &str, language: Language) -> Option<ComplexityMetrics> {
+    ComplexityMeter::new().measure(source, language)
+}
+
+/// Measures code with one reusable parser per language
+#[derive(Default)]
+pub struct ComplexityMeter {
+    parsers: HashMap<Language, Parser>,
+}
+
+impl ComplexityMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `source` and measure it; `None` when no grammar is bundled
+    /// for `language`
+    pub fn measure(&mut self, source: &str, language: Language) -> Option<ComplexityMetrics> {
+        let parser = match self.parsers.entry(language) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut parser = Parser::new();
+                parser.set_language(&tree_sitter_language(language)?).ok()?;
+                entry.insert(parser)
+            }
+        };
+        let tree = parser.parse(source, None)?;
+        Some(ComplexityMetrics {
+            cyclomatic: cyclomatic_complexity(&tree),
+            cognitive: cognitive_complexity(&tree),
+        })
+    }
+}
+
+/// McCabe cyclomatic complexity: one plus the decision points in `tree`
+pub fn cyclomatic_complexity(tree: &Tree) -> u32 {
+    let mut decisions = 0;
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if is_decision(node) {
+            decisions += 1;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    1 + decisions
+}
+
+/// SonarSource cognitive complexity of `tree`
+pub fn cognitive_complexity(tree: &Tree) -> u32 {
+    let mut score = 0;
+    // (node, nesting depth, reached as the `else if` of an enclosing if)
+    let mut stack = vec![(tree.root_node(), 0u32, false)];
+    while let Some((node, nesting, else_if)) = stack.pop() {
+        let kind = node.kind();
+        let mut cursor = node.walk();
+
+        if is_kind(node, IF_KINDS) {
+            score += if else_if { 1 } else { 1 + nesting };
+            for (index, child) in node.named_children(&mut cursor).enumerate() {
+                match node.field_name_for_named_child(index as u32) {
+                    // `else`/`elif` branches sit at the if's own level
+                    Some("alternative") if is_kind(child, IF_KINDS) => stack.push((child, nesting, true)),
+                    Some("alternative") if !is_kind(child, ELSE_KINDS) && !is_kind(child, ELIF_KINDS) => {
+                        score += 1;
+                        stack.push((child, nesting + 1, false));
+                    }
+                    _ if is_kind(child, ELSE_KINDS) || is_kind(child, ELIF_KINDS) => {
+                        stack.push((child, nesting, false))
+                    }
+                    _ => stack.push((child, nesting + 1, false)),
+                }
+            }
+            continue;
+        }
+
+        if is_kind(node, ELSE_KINDS) {
+            let mut children = node.named_children(&mut cursor);
+            match (children.next(), children.next()) {
+                // `else if`: the inner if scores itself
+                (Some(child), None) if is_kind(child, IF_KINDS) => stack.push((child, nesting, true)),
+                _ => {
+                    score += 1;
+                    let mut cursor = node.walk();
+                    stack.extend(node.named_children(&mut cursor).map(|child| (child, nesting + 1, false)));
+                }
+            }
+            continue;
+        }
+
+        let nests = if is_kind(node, ELIF_KINDS) {
+            score += 1;
+            true
+        } else if is_kind(node, LOOP_KINDS)
+            || is_kind(node, SWITCH_KINDS)
+            || is_kind(node, CATCH_KINDS)
+            || is_kind(node, TERNARY_KINDS)
+        {
+            score += 1 + nesting;
+            true
+        } else if let Some(operator) = logical_operator(node) {
+            // A run of the same operator (`a && b && c`) counts once
+            let continues_run = node.parent().and_then(logical_operator) == Some(operator);
+            if !continues_run {
+                score += 1;
+            }
+            false
+        } else {
+            LAMBDA_KINDS.contains(&kind) && node.is_named()
+        };
+
+        let depth = if nests { nesting + 1 } else { nesting };
+        stack.extend(node.named_children(&mut cursor).map(|child| (child, depth, false)));
+    }
+    score
+}
+
+/// `ComplexityLevel` for a cyclomatic score
+pub fn complexity_level(cyclomatic: u32) -> ComplexityLevel {
+    if cyclomatic >= COMPLEX_FROM {
+        ComplexityLevel::Complex
+    } else if cyclomatic >= MODERATE_FROM {
+        ComplexityLevel::Moderate
+    } else {
+        ComplexityLevel::Simple
+    }
+}
+
+fn is_kind(node: Node<'_>, kinds: &[&str]) -> bool {
+    node.is_named() && kinds.contains(&node.kind())
+}
+
+fn is_decision(node: Node<'_>) -> bool {
+    [IF_KINDS, ELIF_KINDS, LOOP_KINDS, CASE_KINDS, CATCH_KINDS, TERNARY_KINDS]
+        .iter()
+        .any(|kinds| is_kind(node, kinds))
+        || is_kind(node, &["try_expression"])
+        || logical_operator(node).is_some()
+}
+
+/// The short-circuit operator of a boolean binary node
+fn logical_operator(node: Node<'_>) -> Option<&'static str> {
+    if !is_kind(node, &["binary_expression", "boolean_operator", "binary"]) {
+        return None;
+    }
+    let operator = node.child_by_field_name("operator")?;
+    LOGICAL_OPERATORS.iter().copied().find(|op| *op == operator.kind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust(source: &str) -> ComplexityMetrics {
+        measure(source, Language::Rust).unwrap()
+    }
+
+    #[test]
+    fn test_straight_line_code_scores_one() {
+        let metrics = rust("fn id(x: u32) -> u32 { let iffy = x; iffy }");
+        assert_eq!(metrics, ComplexityMetrics { cyclomatic: 1, cognitive: 0 });
+    }
+
+    #[test]
+    fn test_three_branch_function() {
+        let source = r#"
+fn sign(x: i32) -> &'static str {
+    if x > 0 {
+        "positive"
+    } else if x < 0 {
+        "negative"
+    } else if x == i32::MIN {
+        "min"
+    } else {
+        "zero"
+    }
+}"#;
+        let metrics = rust(source);
+        assert_eq!(metrics.cyclomatic, 4);
+        // if, two else-ifs and the final else, all at nesting level 0
+        assert_eq!(metrics.cognitive, 4);
+    }
+
+    #[test]
+    fn test_nesting_raises_cognitive_but_not_cyclomatic() {
+        let flat = rust("fn f(a: bool, b: bool) { if a {} if b {} }");
+        let nested = rust("fn f(a: bool, b: bool) { for _ in 0..3 { if a { if b {} } } }");
+
+        assert_eq!(flat.cyclomatic, 3);
+        assert_eq!(flat.cognitive, 2);
+        assert_eq!(nested.cyclomatic, 4);
+        // loop +1, if +2 (nesting 1), inner if +3 (nesting 2)
+        assert_eq!(nested.cognitive, 6);
+    }
+
+    #[test]
+    fn test_boolean_operator_runs_count_once_for_cognitive() {
+        let metrics = rust("fn f(a: bool, b: bool, c: bool) -> bool { a && b && c || a }");
+        assert_eq!(metrics.cyclomatic, 4);
+        assert_eq!(metrics.cognitive, 2);
+    }
+
+    #[test]
+    fn test_keywords_in_strings_and_comments_do_not_count() {
+        let metrics = rust("fn f() -> &'static str { // if while for\n \"if x && y { loop }\" }");
+        assert_eq!(metrics.cyclomatic, 1);
+    }
+
+    #[test]
+    fn test_python_elif_chain() {
+        let source = "def grade(n):\n    if n > 90:\n        return 'A'\n    elif n > 80:\n        return 'B'\n    elif n > 70:\n        return 'C'\n    else:\n        return 'F'\n";
+        let metrics = measure(source, Language::Python).unwrap();
+        assert_eq!(metrics.cyclomatic, 4);
+        assert_eq!(metrics.cognitive, 4);
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(complexity_level(1), ComplexityLevel::Simple);
+        assert_eq!(complexity_level(5), ComplexityLevel::Moderate);
+        assert_eq!(complexity_level(11), ComplexityLevel::Complex);
+    }
+}
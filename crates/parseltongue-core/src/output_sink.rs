@@ -0,0 +1,244 @@
+//! Destinations for exported artifacts.
+//!
+//! Exporters hand finished bytes to an `OutputSink` instead of calling
+//! `std::fs::write`, so the same export can land on local disk, an HTTP
+//! endpoint, or anything else that implements the trait.
+//!
+//! `sink_for_output` maps an `--output` value to a sink plus the name to
+//! write under:
+//! - plain paths and `file://` URIs → `FileSink`
+//! - `http://` / `https://` → `HttpSink` (PUT; requires the `http-sink` feature)
+//! - any other scheme (`s3://`, ...) → `OutputError`; implement
+//!   `OutputSink` for the backing store and pass it to the exporter directly
+
+use async_trait::async_trait;
+
+use crate::error::{ParseltongError, Result};
+use crate::serializers::JsonCase;
+
+/// Destination that exported artifacts are written to
+#[async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Write `bytes` as the artifact `name`, replacing any previous content
+    ///
+    /// `name` is sink-relative: a path for `FileSink`, a URL path segment
+    /// for `HttpSink`.
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> Result<()>;
+}
+
+/// Writes artifacts to the local filesystem; `name` is the file path
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSink;
+
+#[async_trait]
+impl OutputSink for FileSink {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        tokio::fs::write(name, bytes)
+            .await
+            .map_err(|source| ParseltongError::FileSystemError {
+                path: name.to_string(),
+                source,
+            })
+    }
+}
+
+/// Uploads artifacts with HTTP `PUT {base_url}{name}`
+#[cfg(feature = "http-sink")]
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    base_url: url::Url,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "http-sink")]
+impl HttpSink {
+    /// Sink rooted at `base_url`; names are resolved relative to it, so a
+    /// base ending in `/` keeps its last path segment
+    pub fn new(base_url: url::Url) -> Self {
+        Self {
+            base_url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "http-sink")]
+#[async_trait]
+impl OutputSink for HttpSink {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let url = self.base_url.join(name).map_err(|e| ParseltongError::OutputError {
+            destination: format!("{}{}", self.base_url, name),
+            reason: e.to_string(),
+        })?;
+        let output_error = |reason: String| ParseltongError::OutputError {
+            destination: url.to_string(),
+            reason,
+        };
+
+        let response = self
+            .client
+            .put(url.clone())
+            .body(bytes.to_vec())
+            .send()
+            .await
+            .map_err(|e| output_error(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(output_error(format!("server responded {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Rewrites the field names of `.json` artifacts to a `JsonCase`
+///
+/// Wrapping the sink applies `--json-case` to every exporter without
+/// threading the policy through their signatures. Pretty output stays
+/// pretty (detected by a newline); bytes that do not parse as JSON pass
+/// through unchanged.
+pub struct JsonCaseSink {
+    inner: Box<dyn OutputSink>,
+    case: JsonCase,
+}
+
+impl JsonCaseSink {
+    pub fn new(inner: Box<dyn OutputSink>, case: JsonCase) -> Self {
+        Self { inner, case }
+    }
+}
+
+#[async_trait]
+impl OutputSink for JsonCaseSink {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        if self.case == JsonCase::Snake || !name.ends_with(".json") {
+            return self.inner.write_all(name, bytes).await;
+        }
+        let Ok(value) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+            return self.inner.write_all(name, bytes).await;
+        };
+        let value = self.case.rename_keys(value);
+        let renamed = if bytes.contains(&b'\n') {
+            serde_json::to_vec_pretty(&value)
+        } else {
+            serde_json::to_vec(&value)
+        }
+        .map_err(|e| ParseltongError::SerializationError {
+            details: format!("Renaming fields of {} failed: {}", name, e),
+        })?;
+        self.inner.write_all(name, &renamed).await
+    }
+}
+
+/// Resolve an `--output` value to its sink and the name to write under
+///
+/// For URLs the last path segment becomes the name, so exporters can derive
+/// sibling artifacts (`x.toon`, `x_test.json`) next to it.
+///
+/// # Example
+/// ```
+/// use parseltongue_core::output_sink::sink_for_output;
+///
+/// let (_sink, name) = sink_for_output("out/context.json").unwrap();
+/// assert_eq!(name, "out/context.json");
+/// assert!(sink_for_output("s3://bucket/context.json").is_err());
+/// ```
+pub fn sink_for_output(output: &str) -> Result<(Box<dyn OutputSink>, String)> {
+    if !output.contains("://") {
+        return Ok((Box::new(FileSink), output.to_string()));
+    }
+
+    let output_error = |reason: String| ParseltongError::OutputError {
+        destination: output.to_string(),
+        reason,
+    };
+    let url = url::Url::parse(output).map_err(|e| output_error(e.to_string()))?;
+
+    match url.scheme() {
+        "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| output_error("not a local file path".to_string()))?;
+            Ok((Box::new(FileSink), path.to_string_lossy().into_owned()))
+        }
+        "http" | "https" => http_sink_for(&url).map_err(output_error),
+        scheme => Err(output_error(format!("no output sink for scheme '{}'", scheme))),
+    }
+}
+
+#[cfg(feature = "http-sink")]
+fn http_sink_for(url: &url::Url) -> std::result::Result<(Box<dyn OutputSink>, String), String> {
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| "URL must end with a file name".to_string())?
+        .to_string();
+    let base_url = url.join("./").map_err(|e| e.to_string())?;
+    Ok((Box::new(HttpSink::new(base_url)), name))
+}
+
+#[cfg(not(feature = "http-sink"))]
+fn http_sink_for(_url: &url::Url) -> std::result::Result<(Box<dyn OutputSink>, String), String> {
+    Err("HTTP output requires building with the `http-sink` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_file_sink_writes_and_replaces() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.json");
+        let name = path.to_str().unwrap();
+
+        FileSink.write_all(name, b"first").await.unwrap();
+        FileSink.write_all(name, b"second").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        let missing_dir = dir.path().join("missing").join("out.json");
+        let err = FileSink.write_all(missing_dir.to_str().unwrap(), b"x").await.unwrap_err();
+        assert!(matches!(err, ParseltongError::FileSystemError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_json_case_sink_renames_json_artifacts_only() {
+        let dir = TempDir::new().unwrap();
+        let json_path = dir.path().join("out.json");
+        let toon_path = dir.path().join("out.toon");
+        let sink = JsonCaseSink::new(Box::new(FileSink), JsonCase::Camel);
+
+        sink.write_all(json_path.to_str().unwrap(), br#"{"isgl1_key":"a"}"#).await.unwrap();
+        sink.write_all(toon_path.to_str().unwrap(), b"isgl1_key\ta").await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&json_path).unwrap(), r#"{"isgl1Key":"a"}"#);
+        assert_eq!(std::fs::read_to_string(&toon_path).unwrap(), "isgl1_key\ta");
+    }
+
+    #[test]
+    fn test_sink_for_output_dispatches_by_scheme() {
+        let (_, name) = sink_for_output("exports/edges.json").unwrap();
+        assert_eq!(name, "exports/edges.json");
+
+        let (_, name) = sink_for_output("file:///tmp/exports/edges.json").unwrap();
+        assert_eq!(name, "/tmp/exports/edges.json");
+
+        let err = sink_for_output("s3://bucket/edges.json").err().unwrap();
+        assert!(err.to_string().contains("no output sink for scheme 's3'"), "{}", err);
+    }
+
+    #[cfg(feature = "http-sink")]
+    #[test]
+    fn test_http_output_splits_base_and_name() {
+        let (_, name) = sink_for_output("https://example.com/exports/edges.json").unwrap();
+        assert_eq!(name, "edges.json");
+        assert!(sink_for_output("https://example.com/exports/").is_err());
+    }
+
+    #[cfg(not(feature = "http-sink"))]
+    #[test]
+    fn test_http_output_needs_feature() {
+        let err = sink_for_output("https://example.com/edges.json").err().unwrap();
+        assert!(err.to_string().contains("http-sink"), "{}", err);
+    }
+}
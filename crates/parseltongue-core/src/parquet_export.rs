@@ -0,0 +1,124 @@
+//! Parquet export of entity inventories (requires the `parquet` feature)
+//!
+//! Writes one row per entity with typed columns for the flat fields, so the
+//! graph can be loaded straight into pandas, polars, DuckDB or Spark.
+//! Nested data (LSP metadata, TDD classification, language-specific
+//! signatures) is left out; use the JSON export for that.
+//!
+//! # Columns
+//!
+//! | column | type |
+//! |--------|------|
+//! | `isgl1_key`, `name`, `entity_type`, `entity_class`, `language`, `visibility`, `file_path` | utf8 |
+//! | `line_start`, `line_end` | uint32 |
+//! | `current_ind`, `future_ind` | bool |
+//! | `future_action`, `documentation` | utf8, nullable |
+//! | `content_hash` | utf8 |
+//! | `current_code`, `future_code` | utf8, nullable (only with `include_code`) |
+
+use std::io::Write;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow::array::{ArrayRef, BooleanArray, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::entities::{CodeEntity, EntityClass, LanguageSpecificSignature};
+
+/// Column layout of the export
+pub fn entity_schema(include_code: bool) -> Schema {
+    let mut fields = vec![
+        Field::new("isgl1_key", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("entity_type", DataType::Utf8, false),
+        Field::new("entity_class", DataType::Utf8, false),
+        Field::new("language", DataType::Utf8, false),
+        Field::new("visibility", DataType::Utf8, false),
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("line_start", DataType::UInt32, false),
+        Field::new("line_end", DataType::UInt32, false),
+        Field::new("current_ind", DataType::Boolean, false),
+        Field::new("future_ind", DataType::Boolean, false),
+        Field::new("future_action", DataType::Utf8, true),
+        Field::new("documentation", DataType::Utf8, true),
+        Field::new("content_hash", DataType::Utf8, false),
+    ];
+    if include_code {
+        fields.push(Field::new("current_code", DataType::Utf8, true));
+        fields.push(Field::new("future_code", DataType::Utf8, true));
+    }
+    Schema::new(fields)
+}
+
+/// Write `entities` as a Snappy-compressed Parquet file to `writer`
+///
+/// Returns the number of rows written.
+pub fn write_entities_parquet<W: Write + Send>(
+    entities: &[CodeEntity],
+    writer: W,
+    include_code: bool,
+) -> Result<usize> {
+    let schema = Arc::new(entity_schema(include_code));
+    let strings = |f: &dyn Fn(&CodeEntity) -> String| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(entities.iter().map(f)))
+    };
+    let optional = |f: &dyn Fn(&CodeEntity) -> Option<String>| -> ArrayRef {
+        Arc::new(entities.iter().map(f).collect::<StringArray>())
+    };
+
+    let mut columns: Vec<ArrayRef> = vec![
+        strings(&|e| e.isgl1_key.clone()),
+        strings(&|e| e.interface_signature.name.clone()),
+        strings(&|e| e.interface_signature.entity_type.column_name().to_string()),
+        strings(&|e| {
+            match e.entity_class {
+                EntityClass::TestImplementation => "TEST",
+                EntityClass::CodeImplementation => "CODE",
+            }
+            .to_string()
+        }),
+        strings(&|e| language_name(&e.interface_signature.language_specific).to_string()),
+        strings(&|e| format!("{:?}", e.interface_signature.effective_visibility())),
+        strings(&|e| e.interface_signature.file_path.to_string_lossy().into_owned()),
+        Arc::new(UInt32Array::from_iter_values(
+            entities.iter().map(|e| e.interface_signature.line_range.start),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            entities.iter().map(|e| e.interface_signature.line_range.end),
+        )),
+        Arc::new(BooleanArray::from(
+            entities.iter().map(|e| e.temporal_state.current_ind).collect::<Vec<_>>(),
+        )),
+        Arc::new(BooleanArray::from(
+            entities.iter().map(|e| e.temporal_state.future_ind).collect::<Vec<_>>(),
+        )),
+        optional(&|e| e.temporal_state.future_action.as_ref().map(|a| format!("{:?}", a))),
+        optional(&|e| e.interface_signature.documentation.clone()),
+        strings(&|e| e.metadata.content_hash.clone()),
+    ];
+    if include_code {
+        columns.push(optional(&|e| e.current_code.clone()));
+        columns.push(optional(&|e| e.future_code.clone()));
+    }
+
+    let batch = RecordBatch::try_new(Arc::clone(&schema), columns).context("Failed to build record batch")?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut parquet = ArrowWriter::try_new(writer, schema, Some(properties)).context("Failed to start Parquet file")?;
+    parquet.write(&batch).context("Failed to write Parquet rows")?;
+    parquet.close().context("Failed to finish Parquet file")?;
+    Ok(entities.len())
+}
+
+fn language_name(signature: &LanguageSpecificSignature) -> &'static str {
+    match signature {
+        LanguageSpecificSignature::Rust(_) => "rust",
+        LanguageSpecificSignature::JavaScript(_) => "javascript",
+        LanguageSpecificSignature::TypeScript(_) => "typescript",
+        LanguageSpecificSignature::Python(_) => "python",
+        LanguageSpecificSignature::Java(_) => "java",
+    }
+}
@@ -279,13 +279,16 @@ impl QueryBasedExtractor {
         let entities = self.execute_query(&tree, source, file_path, language, query_source)?;
 
         // v0.9.0: Execute dependency query if available
-        let dependencies = if let Some(dep_query_source) = self.dependency_queries.get(&language) {
+        let mut dependencies = if let Some(dep_query_source) = self.dependency_queries.get(&language) {
             self.execute_dependency_query(&tree, source, file_path, language, dep_query_source, &entities)?
         } else {
             // Graceful degradation: if no dependency query, return empty vec
             vec![]
         };
 
+        // Structural edges need no query: they follow from entity line ranges
+        dependencies.extend(self.containment_edges(language, &entities));
+
         Ok((entities, dependencies))
     }
 
@@ -337,7 +340,7 @@ impl QueryBasedExtractor {
             let capture_name = &query.capture_names()[capture.index as usize];
 
             if *capture_name == "name" {
-                entity_name = Some(source[capture.node.byte_range()].to_string());
+                entity_name = source.get(capture.node.byte_range()).map(str::to_string);
             } else if capture_name.starts_with("definition.") {
                 entity_type = self.parse_entity_type(capture_name);
                 node = Some(capture.node);
@@ -379,29 +382,14 @@ impl QueryBasedExtractor {
     }
 
     fn get_ts_language(&self, language: Language) -> Result<tree_sitter::Language> {
-        Ok(match language {
-            Language::Rust => tree_sitter_rust::LANGUAGE.into(),
-            Language::Python => tree_sitter_python::LANGUAGE.into(),
-            Language::C => tree_sitter_c::LANGUAGE.into(),
-            Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
-            Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
-            Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
-            Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-            Language::Go => tree_sitter_go::LANGUAGE.into(),
-            Language::Java => tree_sitter_java::LANGUAGE.into(),
-            Language::Php => tree_sitter_php::LANGUAGE_PHP.into(),
-            Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
-            Language::Swift => tree_sitter_swift::LANGUAGE.into(),
-            // NOTE: Kotlin temporarily disabled due to tree-sitter version incompatibility
-            // Language::Kotlin => tree_sitter_kotlin::language(),
-            _ => anyhow::bail!("Unsupported language: {:?}", language),
-        })
+        tree_sitter_language(language)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported language: {:?}", language))
     }
 
     /// Execute dependency query and extract relationships (v0.9.0)
     ///
     /// Processes tree-sitter query matches to build DependencyEdge objects.
-    /// Handles three edge types: Calls, Uses, Implements.
+    /// Handles four edge types: Calls, Imports, Implements, References.
     fn execute_dependency_query(
         &self,
         tree: &Tree,
@@ -450,7 +438,7 @@ impl QueryBasedExtractor {
         for capture in m.captures {
             let capture_name = &query.capture_names()[capture.index as usize];
             let node = capture.node;
-            let node_text = &source[node.byte_range()];
+            let node_text = source.get(node.byte_range()).unwrap_or_default();
 
             // Identify dependency type
             if capture_name.starts_with("dependency.") {
@@ -460,8 +448,11 @@ impl QueryBasedExtractor {
                     dependency_type = Some(EdgeType::Calls);
                     // For calls, find containing function
                     from_entity = self.find_containing_entity(node, entities);
-                } else if capture_name.contains("use") || capture_name.contains("import") || capture_name.contains("type_ref") {
-                    dependency_type = Some(EdgeType::Uses);
+                } else if capture_name.contains("use") || capture_name.contains("import") {
+                    dependency_type = Some(EdgeType::Imports);
+                } else if capture_name.contains("type_ref") {
+                    dependency_type = Some(EdgeType::References);
+                    from_entity = self.find_containing_entity(node, entities);
                 } else if capture_name.contains("implement") || capture_name.contains("inherits") {
                     dependency_type = Some(EdgeType::Implements);
                 }
@@ -483,9 +474,10 @@ impl QueryBasedExtractor {
 
         // Build DependencyEdge if we have enough information
         if let (Some(edge_type), Some(to)) = (dependency_type, to_name) {
-            // For Uses edges (imports, use declarations), create simplified keys
-            if edge_type == EdgeType::Uses {
-                let from_key = format!("{}:file:{}:1-1", language, file_path.display());
+            // Imports (and type references outside any entity) are file-level:
+            // create simplified keys
+            if edge_type == EdgeType::Imports || (edge_type == EdgeType::References && from_entity.is_none()) {
+                let from_key = file_key(language, file_path);
                 let to_key = format!("{}:module:{}:0-0", language, to);
 
                 return DependencyEdge::builder()
@@ -497,21 +489,14 @@ impl QueryBasedExtractor {
                     .ok();
             }
 
-            // For Calls and Implements, we need a from_entity
+            // For Calls, Implements and References, we need a from_entity
             if let Some(from) = from_entity {
-                let from_key = format!(
-                    "{}:{}:{}:{}:{}-{}",
-                    language,
-                    self.entity_type_to_key_component(&from.entity_type),
-                    from.name,
-                    from.file_path,
-                    from.line_range.0,
-                    from.line_range.1
-                );
+                let from_key = self.entity_key(language, from);
 
                 let to_key = format!(
-                    "{}:fn:{}:unknown:0-0",
+                    "{}:{}:{}:unknown:0-0",
                     language,
+                    if edge_type == EdgeType::References { "type" } else { "fn" },
                     to
                 );
 
@@ -546,10 +531,6 @@ impl QueryBasedExtractor {
             .filter(|e| e.line_range.0 <= node_line && node_line <= e.line_range.1)
             .collect();
 
-        if candidates.is_empty() {
-            return None;
-        }
-
         // Sort by specificity
         candidates.sort_by(|a, b| {
             // Primary: Prefer smaller line ranges (more specific)
@@ -572,7 +553,49 @@ impl QueryBasedExtractor {
         });
 
         // Return most specific entity
-        Some(candidates[0])
+        candidates.first().copied()
+    }
+
+    /// Emit `Contains` edges from each entity to the entities nested in it
+    ///
+    /// Only the innermost container is linked (impl → method, not
+    /// module → impl → method twice), keeping the tree shape.
+    fn containment_edges(&self, language: Language, entities: &[ParsedEntity]) -> Vec<DependencyEdge> {
+        entities
+            .iter()
+            .filter_map(|child| {
+                let parent = entities
+                    .iter()
+                    .filter(|candidate| {
+                        !std::ptr::eq(*candidate, child)
+                            && candidate.line_range.0 <= child.line_range.0
+                            && child.line_range.1 <= candidate.line_range.1
+                            && candidate.line_range != child.line_range
+                    })
+                    .min_by_key(|candidate| candidate.line_range.1 - candidate.line_range.0)?;
+
+                DependencyEdge::builder()
+                    .from_key(self.entity_key(language, parent))
+                    .to_key(self.entity_key(language, child))
+                    .edge_type(EdgeType::Contains)
+                    .source_location(format!("{}:{}", child.file_path, child.line_range.0))
+                    .build()
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Key used for an extracted entity on either end of an edge
+    fn entity_key(&self, language: Language, entity: &ParsedEntity) -> String {
+        format!(
+            "{}:{}:{}:{}:{}-{}",
+            language,
+            self.entity_type_to_key_component(&entity.entity_type),
+            entity.name,
+            entity.file_path,
+            entity.line_range.0,
+            entity.line_range.1
+        )
     }
 
     /// Convert EntityType to ISGL1 key component
@@ -592,3 +615,41 @@ impl QueryBasedExtractor {
         }
     }
 }
+
+/// Key standing for a whole file as the source of file-level edges
+///
+/// Always colon-qualified, whatever `KeyFormat` entity keys use; the path
+/// is kept as given rather than sanitized.
+pub fn file_key(language: Language, file_path: &Path) -> String {
+    format!("{}:file:{}:1-1", language, file_path.display())
+}
+
+/// Whether `key` was built by [`file_key`]
+pub fn is_file_key(key: &str) -> bool {
+    key.split(':').nth(1) == Some("file")
+}
+
+/// tree-sitter grammar for a language, if one is bundled
+///
+/// The one grammar registry: pt01's parsers and chunkers load theirs
+/// from here too.
+pub fn tree_sitter_language(language: Language) -> Option<tree_sitter::Language> {
+    Some(match language {
+        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+        Language::Python => tree_sitter_python::LANGUAGE.into(),
+        Language::C => tree_sitter_c::LANGUAGE.into(),
+        Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+        Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+        Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Go => tree_sitter_go::LANGUAGE.into(),
+        Language::Java => tree_sitter_java::LANGUAGE.into(),
+        Language::Php => tree_sitter_php::LANGUAGE_PHP.into(),
+        Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+        Language::Swift => tree_sitter_swift::LANGUAGE.into(),
+        Language::Scala => tree_sitter_scala::LANGUAGE.into(),
+        // NOTE: Kotlin temporarily disabled due to tree-sitter version incompatibility
+        // Language::Kotlin => tree_sitter_kotlin::language(),
+        _ => return None,
+    })
+}
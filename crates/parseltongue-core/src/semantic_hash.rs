@@ -0,0 +1,102 @@
+//! Formatting-insensitive hashing of source code.
+//!
+//! `content_hash` covers raw bytes, so a `cargo fmt` run changes it for every
+//! reformatted entity. The semantic hash is taken over the tree-sitter syntax
+//! tree instead: node kinds, tree shape and the text of leaf tokens, with
+//! comment subtrees skipped. Re-indenting or re-wrapping code keeps the hash;
+//! changing any token (an identifier, a literal, an operator) does not.
+//!
+//! Comments, including doc comments, do not contribute, so a comment-only
+//! edit is also treated as unchanged.
+
+use sha2::{Digest, Sha256};
+use tree_sitter::{Node, Parser};
+
+use crate::entities::Language;
+use crate::query_extractor::tree_sitter_language;
+
+/// Marks the end of a node's children, so `a(b, c)` and `a(b(c))` differ
+const END_OF_CHILDREN: [u8; 2] = [0xFF, 0xFF];
+
+/// Hex-encoded SHA-256 over the syntax tree of `source`
+///
+/// `None` when no grammar is bundled for `language`. Source with syntax
+/// errors still hashes, over the error-recovered tree.
+pub fn semantic_hash(source: &str, language: Language) -> Option<String> {
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_language(language)?).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut hasher = Sha256::new();
+    hash_node(tree.root_node(), source.as_bytes(), &mut hasher);
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Pre-order walk with an explicit stack (deep trees must not overflow)
+fn hash_node(root: Node<'_>, source: &[u8], hasher: &mut Sha256) {
+    // `None` entries close the node opened before its children were pushed
+    let mut stack = vec![Some(root)];
+    while let Some(entry) = stack.pop() {
+        let Some(node) = entry else {
+            hasher.update(END_OF_CHILDREN);
+            continue;
+        };
+        if node.kind().contains("comment") {
+            continue;
+        }
+
+        hasher.update(node.kind_id().to_le_bytes());
+        if node.child_count() == 0 {
+            let text = &source[node.byte_range()];
+            hasher.update((text.len() as u64).to_le_bytes());
+            hasher.update(text);
+            continue;
+        }
+
+        stack.push(None);
+        let mut cursor = node.walk();
+        let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev().map(Some));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whitespace_and_comments_do_not_change_hash() {
+        let compact = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let formatted = "fn add(a: i32, b: i32) -> i32 {\n    // sum\n    a + b\n}\n";
+
+        assert_eq!(
+            semantic_hash(compact, Language::Rust),
+            semantic_hash(formatted, Language::Rust)
+        );
+    }
+
+    #[test]
+    fn test_token_and_structure_changes_do_change_hash() {
+        let original = semantic_hash("fn add(a: i32, b: i32) -> i32 { a + b }", Language::Rust);
+
+        for edited in [
+            "fn add(a: i32, b: i32) -> i32 { a - b }",
+            "fn sum(a: i32, b: i32) -> i32 { a + b }",
+            "fn add(a: i64, b: i32) -> i32 { a + b }",
+        ] {
+            assert_ne!(original, semantic_hash(edited, Language::Rust), "{}", edited);
+        }
+        assert!(original.is_some());
+    }
+
+    #[test]
+    fn test_python_reindented_block_keeps_hash() {
+        let two_spaces = "def f(x):\n  if x:\n    return 1\n  return 0\n";
+        let four_spaces = "def f(x):\n    if x:\n        return 1\n    return 0\n";
+        let dedented_return = "def f(x):\n    if x:\n        return 1\n        return 0\n";
+
+        let hash = |source| semantic_hash(source, Language::Python);
+        assert_eq!(hash(two_spaces), hash(four_spaces));
+        assert_ne!(hash(four_spaces), hash(dedented_return), "indentation that moves code is structural");
+    }
+}
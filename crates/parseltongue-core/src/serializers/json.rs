@@ -5,6 +5,89 @@
 use super::Serializer;
 use anyhow::Result;
 use serde::Serialize;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// Field-naming policy for JSON output (`--json-case`)
+///
+/// Structs serialize with their snake_case field names; `Camel` rewrites
+/// object keys after serialization so JS consumers need no translation
+/// layer. Only identifier-like keys are renamed: map keys such as file
+/// paths and ISGL1 keys (which contain `/`, `.` or `:`) pass through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonCase {
+    /// serde field names as declared (`isgl1_key`)
+    #[default]
+    Snake,
+    /// camelCase field names (`isgl1Key`)
+    Camel,
+}
+
+impl FromStr for JsonCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "snake" => Ok(Self::Snake),
+            "camel" => Ok(Self::Camel),
+            other => Err(format!("unknown JSON case '{}' (expected snake or camel)", other)),
+        }
+    }
+}
+
+impl JsonCase {
+    /// Rename every identifier-like object key in `value` to this case
+    ///
+    /// `Snake` maps camelCase keys back, so camel output round-trips.
+    pub fn rename_keys(self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (self.rename_key(&key), self.rename_keys(value)))
+                    .collect::<Map<String, Value>>(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.rename_keys(v)).collect()),
+            other => other,
+        }
+    }
+
+    fn rename_key(self, key: &str) -> String {
+        let identifier = key.starts_with(|c: char| c.is_ascii_lowercase())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !identifier {
+            return key.to_string();
+        }
+        match self {
+            Self::Camel => {
+                let mut renamed = String::with_capacity(key.len());
+                let mut upper_next = false;
+                for c in key.chars() {
+                    if c == '_' {
+                        upper_next = true;
+                    } else if upper_next {
+                        renamed.push(c.to_ascii_uppercase());
+                        upper_next = false;
+                    } else {
+                        renamed.push(c);
+                    }
+                }
+                renamed
+            }
+            Self::Snake => {
+                let mut renamed = String::with_capacity(key.len() + 4);
+                for c in key.chars() {
+                    if c.is_ascii_uppercase() {
+                        renamed.push('_');
+                        renamed.push(c.to_ascii_lowercase());
+                    } else {
+                        renamed.push(c);
+                    }
+                }
+                renamed
+            }
+        }
+    }
+}
 
 /// JSON serializer using serde_json
 ///
@@ -12,25 +95,62 @@ use serde::Serialize;
 /// - Human-readable format
 /// - Universal tool compatibility
 /// - ~30 tokens per entity (baseline)
-/// - Pretty-printed for readability
-pub struct JsonSerializer;
+/// - Pretty-printed for readability by default; `compact()` emits a single
+///   line (roughly half the bytes) for machine consumers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer {
+    compact: bool,
+    case: JsonCase,
+}
 
 impl JsonSerializer {
+    /// Pretty-printing serializer (default)
     pub fn new() -> Self {
-        Self
+        Self { compact: false, case: JsonCase::Snake }
+    }
+
+    /// Single-line serializer (`--compact`)
+    pub fn compact() -> Self {
+        Self { compact: true, case: JsonCase::Snake }
     }
-}
 
-impl Default for JsonSerializer {
-    fn default() -> Self {
-        Self::new()
+    /// Pick the style from a `--compact` flag
+    pub fn with_compact(compact: bool) -> Self {
+        Self { compact, case: JsonCase::Snake }
+    }
+
+    /// Emit field names in `case` (`--json-case`)
+    pub fn with_case(mut self, case: JsonCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    pub fn is_compact(&self) -> bool {
+        self.compact
+    }
+
+    /// Serialize any single value in this serializer's style
+    pub fn to_json_string<T: Serialize + ?Sized>(&self, value: &T) -> Result<String> {
+        if self.case != JsonCase::Snake {
+            let value = self.case.rename_keys(serde_json::to_value(value)?);
+            return Ok(if self.compact {
+                serde_json::to_string(&value)?
+            } else {
+                serde_json::to_string_pretty(&value)?
+            });
+        }
+        Ok(if self.compact {
+            serde_json::to_string(value)?
+        } else {
+            serde_json::to_string_pretty(value)?
+        })
     }
 }
 
 impl Serializer for JsonSerializer {
     fn serialize<T: Serialize>(&self, data: &[T]) -> Result<String> {
         // serde_json handles empty arrays gracefully: "[]"
-        Ok(serde_json::to_string_pretty(data)?)
+        self.to_json_string(data)
     }
 
     fn extension(&self) -> &'static str {
@@ -81,6 +201,57 @@ mod tests {
         assert!(result.contains("42"));
     }
 
+    #[test]
+    fn test_json_compact_round_trips() {
+        #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Row {
+            name: String,
+            value: i32,
+        }
+        let data = vec![
+            Row { name: "a".to_string(), value: 1 },
+            Row { name: "b".to_string(), value: 2 },
+        ];
+
+        let compact = JsonSerializer::compact().serialize(&data).unwrap();
+        let pretty = JsonSerializer::new().serialize(&data).unwrap();
+
+        assert!(!compact.contains('\n'));
+        assert!(compact.len() < pretty.len());
+        assert_eq!(serde_json::from_str::<Vec<Row>>(&compact).unwrap(), data);
+        assert_eq!(serde_json::from_str::<Vec<Row>>(&pretty).unwrap(), data);
+    }
+
+    #[test]
+    fn test_json_camel_case_round_trips() {
+        #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Row {
+            isgl1_key: String,
+            current_ind: bool,
+            tdd_classification: std::collections::HashMap<String, i32>,
+        }
+        let row = Row {
+            isgl1_key: "rust:fn:main:src_main_rs:1-5".to_string(),
+            current_ind: true,
+            tdd_classification: [("src/main_file.rs".to_string(), 1)].into_iter().collect(),
+        };
+
+        let camel = JsonSerializer::new()
+            .with_case(JsonCase::Camel)
+            .to_json_string(&row)
+            .unwrap();
+        assert!(camel.contains("\"isgl1Key\""), "{}", camel);
+        assert!(camel.contains("\"currentInd\""), "{}", camel);
+        assert!(!camel.contains("isgl1_key"), "{}", camel);
+        // Map keys that are not identifiers keep their spelling
+        assert!(camel.contains("\"src/main_file.rs\""), "{}", camel);
+
+        let snake = JsonCase::Snake.rename_keys(serde_json::from_str(&camel).unwrap());
+        assert_eq!(serde_json::from_value::<Row>(snake).unwrap(), row);
+        assert_eq!("camel".parse::<JsonCase>().unwrap(), JsonCase::Camel);
+        assert!("kebab".parse::<JsonCase>().is_err());
+    }
+
     #[test]
     fn test_json_extension() {
         let serializer = JsonSerializer::new();
@@ -0,0 +1,197 @@
+//! Markdown serializer for human-readable exports
+//!
+//! Renders export records for pasting into review documents and PR
+//! descriptions rather than for tools:
+//!
+//! - entities are grouped under a `## <file_path>` header per file
+//! - each entity is a `### <entity_name>` section with its signature in a
+//!   fenced block (language taken from the ISGL1 key), its doc comment as
+//!   prose, its code when exported, and a list of its forward dependencies
+//! - edge records (Level 0) become a bullet list under `## Dependency edges`
+//!
+//! Records are read through `serde_json::Value`, so any export type with the
+//! usual field names (`entity_name`, `file_path`, `interface_signature`, ...)
+//! renders without a dedicated impl.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::Serializer;
+use crate::isgl1_key::parse_key;
+
+/// Markdown serializer (`--format markdown`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownSerializer;
+
+impl MarkdownSerializer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Serializer for MarkdownSerializer {
+    fn serialize<T: Serialize>(&self, data: &[T]) -> Result<String> {
+        let mut by_file: BTreeMap<String, Vec<Map<String, Value>>> = BTreeMap::new();
+        let mut edges = Vec::new();
+        for item in data {
+            let Value::Object(record) = serde_json::to_value(item)? else {
+                continue;
+            };
+            if record.contains_key("entity_name") {
+                let file = text(&record, "file_path").unwrap_or("(unknown file)").to_string();
+                by_file.entry(file).or_default().push(record);
+            } else if record.contains_key("from_key") {
+                edges.push(record);
+            }
+        }
+
+        if by_file.is_empty() && edges.is_empty() {
+            return Ok("_No entities._\n".to_string());
+        }
+
+        let mut out = String::new();
+        for (file, entities) in &by_file {
+            out.push_str(&format!("## {}\n\n", file));
+            for entity in entities {
+                render_entity(&mut out, entity);
+            }
+        }
+        if !edges.is_empty() {
+            out.push_str("## Dependency edges\n\n");
+            for edge in &edges {
+                out.push_str(&format!(
+                    "- `{}` → `{}` ({})\n",
+                    text(edge, "from_key").unwrap_or_default(),
+                    text(edge, "to_key").unwrap_or_default(),
+                    text(edge, "edge_type").unwrap_or("unknown"),
+                ));
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn estimate_tokens(&self, entity_count: usize) -> usize {
+        // Headers and fences cost more than JSON punctuation saves: ~40 per entity
+        if entity_count == 0 {
+            4
+        } else {
+            entity_count * 40
+        }
+    }
+}
+
+fn render_entity(out: &mut String, entity: &Map<String, Value>) {
+    out.push_str(&format!("### {}\n\n", text(entity, "entity_name").unwrap_or_default()));
+
+    let language = text(entity, "isgl1_key")
+        .and_then(|key| parse_key(key).ok())
+        .map(|components| components.language)
+        .unwrap_or_default();
+    if let Some(signature) = text(entity, "interface_signature") {
+        push_fenced(out, &language, signature);
+    }
+    if let Some(doc) = text(entity, "doc_comment") {
+        out.push_str(doc.trim());
+        out.push_str("\n\n");
+    }
+    if let Some(code) = text(entity, "current_code") {
+        push_fenced(out, &language, code);
+    }
+
+    let deps: Vec<&str> = entity
+        .get("forward_deps")
+        .and_then(Value::as_array)
+        .map(|deps| deps.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if !deps.is_empty() {
+        out.push_str("Depends on:\n\n");
+        for dep in deps {
+            out.push_str(&format!("- `{}`\n", dep));
+        }
+        out.push('\n');
+    }
+}
+
+/// Fence longer than any backtick run inside `body`, so code containing
+/// ``` cannot close the block early
+fn push_fenced(out: &mut String, language: &str, body: &str) {
+    let longest_run = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    out.push_str(&format!("{}{}\n{}\n{}\n\n", fence, language, body.trim_end(), fence));
+}
+
+fn text<'a>(record: &'a Map<String, Value>, field: &str) -> Option<&'a str> {
+    record.get(field).and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_markdown_groups_by_file_with_fenced_signatures() {
+        let data = vec![
+            json!({
+                "isgl1_key": "rust:fn:load:src_config_rs:3-9",
+                "entity_name": "load",
+                "file_path": "src/config.rs",
+                "interface_signature": "pub fn load(path: &Path) -> Config",
+                "doc_comment": "Read the config file.",
+                "forward_deps": ["rust:fn:parse:src_config_rs:11-20"],
+            }),
+            json!({
+                "isgl1_key": "rust:struct:App:src_app_rs:1-4",
+                "entity_name": "App",
+                "file_path": "src/app.rs",
+                "interface_signature": "pub struct App",
+                "forward_deps": [],
+            }),
+        ];
+
+        let markdown = MarkdownSerializer::new().serialize(&data).unwrap();
+
+        assert!(markdown.find("## src/app.rs").unwrap() < markdown.find("## src/config.rs").unwrap());
+        assert!(markdown.contains("### load\n\n```rust\npub fn load(path: &Path) -> Config\n```\n"));
+        assert!(markdown.contains("Read the config file.\n"));
+        assert!(markdown.contains("Depends on:\n\n- `rust:fn:parse:src_config_rs:11-20`\n"));
+        let app_section = &markdown[markdown.find("### App").unwrap()..markdown.find("## src/config.rs").unwrap()];
+        assert!(!app_section.contains("Depends on"), "{}", app_section);
+    }
+
+    #[test]
+    fn test_code_with_backticks_gets_longer_fence() {
+        let data = vec![json!({
+            "isgl1_key": "rust:fn:doc:src_lib_rs:1-3",
+            "entity_name": "doc",
+            "file_path": "src/lib.rs",
+            "interface_signature": "fn doc()",
+            "current_code": "/// ```\n/// doc();\n/// ```\nfn doc() {}",
+        })];
+
+        let markdown = MarkdownSerializer::new().serialize(&data).unwrap();
+        assert!(markdown.contains("````rust\n/// ```"), "{}", markdown);
+    }
+
+    #[test]
+    fn test_edges_and_empty_input() {
+        let edges = vec![json!({ "from_key": "a", "to_key": "b", "edge_type": "Calls" })];
+        let markdown = MarkdownSerializer::new().serialize(&edges).unwrap();
+        assert!(markdown.contains("## Dependency edges\n\n- `a` → `b` (Calls)\n"));
+
+        let empty: Vec<Value> = vec![];
+        assert_eq!(MarkdownSerializer::new().serialize(&empty).unwrap(), "_No entities._\n");
+    }
+}
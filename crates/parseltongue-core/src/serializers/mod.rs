@@ -16,15 +16,18 @@
 //!
 //! - **JSON**: Standard format for tool compatibility
 //! - **TOON**: Tab-Oriented Object Notation for 30-40% token reduction
+//! - **Markdown**: Per-file sections for human review (PR descriptions, docs)
 
 use anyhow::Result;
 use serde::Serialize;
 use std::path::{Path, PathBuf};
 
 pub mod json;
+pub mod markdown;
 pub mod toon;
 
-pub use json::JsonSerializer;
+pub use json::{JsonCase, JsonSerializer};
+pub use markdown::MarkdownSerializer;
 pub use toon::{ToonDelimiter, ToonSerializer};
 
 /// Core serialization trait for data export formats
@@ -46,7 +49,7 @@ pub use toon::{ToonDelimiter, ToonSerializer};
 /// # Example
 ///
 /// ```rust,ignore
-/// let serializer = JsonSerializer;
+/// let serializer = JsonSerializer::new();
 /// let data = vec![Entity { name: "foo".into() }];
 /// let output = serializer.serialize(&data)?;
 /// assert!(output.contains("foo"));
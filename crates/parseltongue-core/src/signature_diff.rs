@@ -0,0 +1,477 @@
+//! Field-by-field comparison of two entities' interface signatures.
+//!
+//! Used for API-compatibility review of renames and refactors: entity `a` is
+//! the old API, entity `b` the new one. Each compared field is classified as
+//! unchanged, a compatible change, or a breaking change for callers of `a`.
+//!
+//! Parameters and return types come from the language-specific signature;
+//! Rust signatures do not store them, so they are read from the function
+//! header in the entity's code.
+
+use crate::entities::{CodeEntity, InterfaceSignature, LanguageSpecificSignature, Visibility};
+
+/// How a field differs between the two signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldChange {
+    Unchanged,
+    /// Differs, but existing callers keep working
+    Compatible,
+    /// Existing callers may fail to compile or link
+    Breaking,
+}
+
+/// One compared field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldComparison {
+    pub field: String,
+    pub a: String,
+    pub b: String,
+    pub change: FieldChange,
+}
+
+/// Result of comparing two signatures
+#[derive(Debug, Clone, Default)]
+pub struct SignatureDiff {
+    pub fields: Vec<FieldComparison>,
+}
+
+impl SignatureDiff {
+    pub fn is_breaking(&self) -> bool {
+        self.fields.iter().any(|f| f.change == FieldChange::Breaking)
+    }
+
+    /// Fields that differ
+    pub fn changes(&self) -> impl Iterator<Item = &FieldComparison> {
+        self.fields.iter().filter(|f| f.change != FieldChange::Unchanged)
+    }
+
+    fn push(&mut self, field: impl Into<String>, a: String, b: String, change_if_different: FieldChange) {
+        let change = if a == b { FieldChange::Unchanged } else { change_if_different };
+        self.fields.push(FieldComparison { field: field.into(), a, b, change });
+    }
+}
+
+/// Parameter shape shared by all languages
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParamShape {
+    name: String,
+    type_annotation: Option<String>,
+    /// Callers may omit it (default value, optional, varargs)
+    optional: bool,
+}
+
+impl ParamShape {
+    fn render(&self) -> String {
+        match &self.type_annotation {
+            Some(ty) => format!("{}: {}", self.name, ty),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Compare two entities, `a` being the old API and `b` the new one
+pub fn diff_entities(a: &CodeEntity, b: &CodeEntity) -> SignatureDiff {
+    let sig_a = &a.interface_signature;
+    let sig_b = &b.interface_signature;
+    let mut diff = SignatureDiff::default();
+
+    diff.push("name", sig_a.name.clone(), sig_b.name.clone(), FieldChange::Breaking);
+    diff.push(
+        "entity_type",
+        format!("{:?}", sig_a.entity_type),
+        format!("{:?}", sig_b.entity_type),
+        FieldChange::Breaking,
+    );
+
+    let (vis_a, vis_b) = (sig_a.effective_visibility(), sig_b.effective_visibility());
+    let visibility_change = if visibility_rank(&vis_b) < visibility_rank(&vis_a) {
+        FieldChange::Breaking
+    } else {
+        FieldChange::Compatible
+    };
+    diff.push("visibility", format!("{:?}", vis_a), format!("{:?}", vis_b), visibility_change);
+
+    diff.push(
+        "module_path",
+        sig_a.module_path.join("::"),
+        sig_b.module_path.join("::"),
+        FieldChange::Breaking,
+    );
+
+    let (params_a, return_a) = callable_shape(a);
+    let (params_b, return_b) = callable_shape(b);
+    diff.push(
+        "params",
+        render_params(&params_a),
+        render_params(&params_b),
+        classify_params(&params_a, &params_b),
+    );
+    diff.push(
+        "return_type",
+        return_a.unwrap_or_default(),
+        return_b.unwrap_or_default(),
+        FieldChange::Breaking,
+    );
+
+    diff.push("generics", generics(sig_a).join(", "), generics(sig_b).join(", "), FieldChange::Breaking);
+    if let (LanguageSpecificSignature::Rust(rust_a), LanguageSpecificSignature::Rust(rust_b)) =
+        (&sig_a.language_specific, &sig_b.language_specific)
+    {
+        diff.push("lifetimes", rust_a.lifetimes.join(", "), rust_b.lifetimes.join(", "), FieldChange::Breaking);
+        // New bounds can reject existing callers; dropped bounds cannot
+        let added_bound = rust_b.where_clauses.iter().any(|w| !rust_a.where_clauses.contains(w));
+        diff.push(
+            "where_clauses",
+            rust_a.where_clauses.join(", "),
+            rust_b.where_clauses.join(", "),
+            if added_bound { FieldChange::Breaking } else { FieldChange::Compatible },
+        );
+    }
+
+    diff.push(
+        "documentation",
+        sig_a.documentation.clone().unwrap_or_default(),
+        sig_b.documentation.clone().unwrap_or_default(),
+        FieldChange::Compatible,
+    );
+    diff.push(
+        "location",
+        location(sig_a),
+        location(sig_b),
+        FieldChange::Compatible,
+    );
+
+    diff
+}
+
+/// Wider visibility ranks higher
+fn visibility_rank(visibility: &Visibility) -> u8 {
+    match visibility {
+        Visibility::Public => 3,
+        Visibility::Crate => 2,
+        Visibility::Protected | Visibility::Module => 1,
+        Visibility::Private => 0,
+    }
+}
+
+fn generics(sig: &InterfaceSignature) -> Vec<String> {
+    match &sig.language_specific {
+        LanguageSpecificSignature::Rust(rust) => rust.generics.clone(),
+        LanguageSpecificSignature::TypeScript(ts) => ts.generics.clone(),
+        LanguageSpecificSignature::Java(java) => java.generics.clone(),
+        LanguageSpecificSignature::JavaScript(_) | LanguageSpecificSignature::Python(_) => vec![],
+    }
+}
+
+fn location(sig: &InterfaceSignature) -> String {
+    format!(
+        "{}:{}-{}",
+        sig.file_path.display(),
+        sig.line_range.start,
+        sig.line_range.end
+    )
+}
+
+/// Parameters and return type of a callable entity
+fn callable_shape(entity: &CodeEntity) -> (Vec<ParamShape>, Option<String>) {
+    match &entity.interface_signature.language_specific {
+        LanguageSpecificSignature::Rust(_) => entity
+            .current_code
+            .as_deref()
+            .or(entity.future_code.as_deref())
+            .and_then(rust_fn_shape)
+            .unwrap_or_default(),
+        LanguageSpecificSignature::JavaScript(js) => (
+            js.parameters
+                .iter()
+                .map(|p| ParamShape {
+                    name: p.name.clone(),
+                    type_annotation: p.type_annotation.clone(),
+                    optional: false,
+                })
+                .collect(),
+            js.return_type.clone(),
+        ),
+        LanguageSpecificSignature::TypeScript(ts) => (
+            ts.parameters
+                .iter()
+                .map(|p| ParamShape {
+                    name: p.name.clone(),
+                    type_annotation: Some(p.type_annotation.clone()),
+                    optional: p.optional,
+                })
+                .collect(),
+            ts.return_type.clone(),
+        ),
+        LanguageSpecificSignature::Python(py) => (
+            py.parameters
+                .iter()
+                .map(|p| ParamShape {
+                    name: p.name.clone(),
+                    type_annotation: p.type_annotation.clone(),
+                    optional: p.default_value.is_some() || p.is_varargs || p.is_kwargs,
+                })
+                .collect(),
+            py.return_type.clone(),
+        ),
+        LanguageSpecificSignature::Java(java) => (
+            java.parameters
+                .iter()
+                .map(|p| ParamShape {
+                    name: p.name.clone(),
+                    type_annotation: Some(p.type_annotation.clone()),
+                    optional: p.is_varargs,
+                })
+                .collect(),
+            Some(java.return_type.clone()),
+        ),
+    }
+}
+
+fn render_params(params: &[ParamShape]) -> String {
+    params.iter().map(ParamShape::render).collect::<Vec<_>>().join(", ")
+}
+
+/// Removed, retyped, reordered or new required parameters break callers
+fn classify_params(a: &[ParamShape], b: &[ParamShape]) -> FieldChange {
+    if a == b {
+        return FieldChange::Unchanged;
+    }
+    for (idx, old) in a.iter().enumerate() {
+        match b.get(idx) {
+            Some(new) if new.name == old.name && new.type_annotation == old.type_annotation => {}
+            _ => return FieldChange::Breaking,
+        }
+    }
+    if b[a.len()..].iter().any(|added| !added.optional) {
+        FieldChange::Breaking
+    } else {
+        FieldChange::Compatible
+    }
+}
+
+/// Parameters and return type from a Rust `fn` header
+///
+/// `None` when the code has no `fn` header (structs, impls, ...).
+fn rust_fn_shape(code: &str) -> Option<(Vec<ParamShape>, Option<String>)> {
+    let fn_pos = find_fn_keyword(code)?;
+    // First `(` outside the generic parameter list
+    let mut angle_depth = 0i32;
+    let mut prev = ' ';
+    let open = fn_pos
+        + code[fn_pos..].char_indices().find_map(|(offset, ch)| {
+            match ch {
+                '<' => angle_depth += 1,
+                '>' if prev != '-' => angle_depth -= 1,
+                '(' if angle_depth == 0 => return Some(offset),
+                _ => {}
+            }
+            prev = ch;
+            None
+        })?;
+
+    let mut depth = 0usize;
+    let mut close = None;
+    for (offset, ch) in code[open..].char_indices() {
+        match ch {
+            '(' | '[' | '<' | '{' => depth += 1,
+            ')' | ']' | '>' | '}' if code[open..][..offset].ends_with('-') => {}
+            ')' | ']' | '>' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + offset);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+
+    let params = split_top_level(&code[open + 1..close])
+        .into_iter()
+        .map(|param| match param.split_once(':') {
+            Some((name, ty)) => ParamShape {
+                name: name.trim().trim_start_matches("mut ").to_string(),
+                type_annotation: Some(collapse_whitespace(ty)),
+                optional: false,
+            },
+            // Receivers: self, &self, &mut self
+            None => ParamShape {
+                name: collapse_whitespace(&param),
+                type_annotation: None,
+                optional: false,
+            },
+        })
+        .collect();
+
+    let rest = &code[close + 1..];
+    let where_clause = rest.match_indices("where").map(|(idx, _)| idx).find(|&idx| {
+        rest[..idx].ends_with(char::is_whitespace)
+            && rest[idx + 5..].chars().next().map_or(true, char::is_whitespace)
+    });
+    let header_end = [rest.find('{'), rest.find(';'), where_clause]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(rest.len());
+    let return_type = rest[..header_end]
+        .trim()
+        .strip_prefix("->")
+        .map(collapse_whitespace);
+
+    Some((params, return_type))
+}
+
+/// Byte offset of the `fn` keyword, skipping attributes and doc comments
+fn find_fn_keyword(code: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in code.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if !trimmed.starts_with("//") && !trimmed.starts_with("#[") {
+            let mut search = 0;
+            while let Some(pos) = line[search..].find("fn") {
+                let at = search + pos;
+                let before_ok = at == 0 || !is_ident_char(line[..at].chars().last().unwrap());
+                let after_ok = line[at + 2..].chars().next().is_some_and(char::is_whitespace);
+                if before_ok && after_ok {
+                    return Some(offset + at);
+                }
+                search = at + 2;
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Split on commas outside brackets, dropping empty pieces
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for ch in list.chars() {
+        match ch {
+            '(' | '[' | '<' | '{' => depth += 1,
+            ')' | ']' | '>' | '}' if !current.ends_with('-') => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityClass, EntityType, LineRange, RustSignature};
+    use std::path::PathBuf;
+
+    fn rust_fn(name: &str, visibility: Visibility, code: &str) -> CodeEntity {
+        let signature = InterfaceSignature {
+            entity_type: EntityType::Function,
+            name: name.to_string(),
+            visibility,
+            file_path: PathBuf::from("src/lib.rs"),
+            line_range: LineRange::new(1, 3).unwrap(),
+            module_path: vec![],
+            documentation: None,
+            language_specific: LanguageSpecificSignature::Rust(RustSignature {
+                generics: vec![],
+                lifetimes: vec![],
+                where_clauses: vec![],
+                attributes: vec![],
+                trait_impl: None,
+            }),
+        };
+        let mut entity = CodeEntity::new(
+            format!("rust:fn:{}:src_lib_rs:1-3", name),
+            signature,
+            EntityClass::CodeImplementation,
+        )
+        .unwrap();
+        entity.current_code = Some(code.to_string());
+        entity
+    }
+
+    fn change_of<'a>(diff: &'a SignatureDiff, field: &str) -> &'a FieldComparison {
+        diff.fields.iter().find(|f| f.field == field).unwrap()
+    }
+
+    #[test]
+    fn test_narrowed_visibility_is_breaking() {
+        let a = rust_fn("total", Visibility::Public, "pub fn total(items: &[u32]) -> u32 { 0 }");
+        let b = rust_fn("total", Visibility::Private, "fn total(items: &[u32]) -> u32 { 0 }");
+
+        let diff = diff_entities(&a, &b);
+        let visibility = change_of(&diff, "visibility");
+        assert_eq!((visibility.a.as_str(), visibility.b.as_str()), ("Public", "Private"));
+        assert_eq!(visibility.change, FieldChange::Breaking);
+        assert!(diff.is_breaking());
+        assert_eq!(diff.changes().count(), 1, "{:?}", diff);
+
+        // Widening is compatible
+        let widened = diff_entities(&b, &a);
+        assert_eq!(change_of(&widened, "visibility").change, FieldChange::Compatible);
+        assert!(!widened.is_breaking());
+    }
+
+    #[test]
+    fn test_rust_params_and_return_type_from_header() {
+        let a = rust_fn(
+            "fetch",
+            Visibility::Public,
+            "/// Docs\n#[inline]\npub async fn fetch<'a>(&self, key: &'a str, opts: HashMap<String, Vec<u8>>) -> Result<(), Error>\nwhere\n    Self: Sized,\n{\n}",
+        );
+        let (params, ret) = callable_shape(&a);
+        assert_eq!(
+            rust_fn_shape("fn apply<F: Fn(u8) -> u8>(f: F) -> u8 { f(1) }").map(|(p, r)| (render_params(&p), r)),
+            Some(("f: F".to_string(), Some("u8".to_string())))
+        );
+        assert_eq!(render_params(&params), "&self, key: &'a str, opts: HashMap<String, Vec<u8>>");
+        assert_eq!(ret.as_deref(), Some("Result<(), Error>"));
+
+        let b = rust_fn("fetch", Visibility::Public, "pub fn fetch(&self, key: &str) -> bool {}");
+        let diff = diff_entities(&a, &b);
+        assert_eq!(change_of(&diff, "params").change, FieldChange::Breaking, "param removed");
+        assert_eq!(change_of(&diff, "return_type").change, FieldChange::Breaking);
+    }
+
+    #[test]
+    fn test_added_optional_param_is_compatible() {
+        let optional = |name: &str| ParamShape {
+            name: name.to_string(),
+            type_annotation: None,
+            optional: true,
+        };
+        let required = ParamShape { optional: false, ..optional("x") };
+
+        assert_eq!(classify_params(&[required.clone()], &[required.clone()]), FieldChange::Unchanged);
+        assert_eq!(
+            classify_params(&[required.clone()], &[required.clone(), optional("verbose")]),
+            FieldChange::Compatible
+        );
+        assert_eq!(
+            classify_params(&[required.clone()], &[required.clone(), ParamShape { optional: false, ..optional("y") }]),
+            FieldChange::Breaking
+        );
+        assert_eq!(classify_params(&[required.clone()], &[]), FieldChange::Breaking);
+    }
+}
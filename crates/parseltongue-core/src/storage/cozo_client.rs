@@ -3,12 +3,56 @@
 //! Real database implementation following the ultra-minimalist architecture
 //! and TDD-first principles. No mocks, no placeholders - this is the real deal.
 
+use crate::clock::{Clock, SystemClock};
 use crate::entities::*;
 use crate::error::{ParseltongError, Result};
 use crate::interfaces::*;
 use async_trait::async_trait;
-use cozo::{DataValue, DbInstance, ScriptMutability};
-use std::collections::BTreeMap;
+use super::migrations::{
+    pending_migrations, side_relation_steps, MigrationStep, CURRENT_SCHEMA_VERSION, RELATION_PLACEHOLDER,
+    SCHEMA_VERSION_RELATION,
+};
+use super::options::{is_relation_name, StorageOptions, DEFAULT_RELATION_NAME, ROCKSDB_OPTIONS_FILE};
+use super::query_cache::{CacheStats, QueryCache};
+use cozo::{DataValue, DbInstance, NamedRows, ScriptMutability};
+use futures::stream::{self, Stream, TryStreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Relation mapping spans-only entities to their code location
+pub const CODE_SPANS_RELATION: &str = "CodeSpans";
+
+/// Relation recording which tool last wrote each entity
+pub const PROVENANCE_RELATION: &str = "Provenance";
+
+/// Relation holding syntax errors found during ingestion, per file
+pub const PARSE_ERRORS_RELATION: &str = "ParseErrors";
+
+/// Relation flagging entities ingested from generated or vendored files
+pub const GENERATED_RELATION: &str = "GeneratedEntities";
+
+/// Relation counting entity reads (see `with_access_logging`)
+pub const ACCESS_LOG_RELATION: &str = "AccessLog";
+
+/// Relation holding writes applied under an idempotency key
+pub const APPLIED_WRITES_RELATION: &str = "AppliedWrites";
+
+/// Entity relation columns every write stores and every read returns, in
+/// `row_to_entity` order (`deleted_at` is only set by deletes)
+const ENTITY_COLUMNS: [&str; 18] = [
+    "ISGL1_key", "Current_Code", "Future_Code", "interface_signature", "TDD_Classification",
+    "lsp_meta_data", "current_ind", "future_ind", "Future_Action", "file_path", "language",
+    "last_modified", "entity_type", "entity_class", "additional_metadata", "created_at",
+    "complexity_score", "semantic_hash",
+];
+
+/// Hops `shortest_path` explores before giving up
+pub const DEFAULT_MAX_PATH_HOPS: usize = 32;
+
+/// Entities per query in `entities_stream`
+pub const DEFAULT_STREAM_PAGE_SIZE: usize = 1_000;
 
 /// CozoDB storage client
 ///
@@ -18,6 +62,24 @@ use std::collections::BTreeMap;
 /// - Full CodeGraph schema from technical specifications
 pub struct CozoDbStorage {
     db: DbInstance,
+    /// Serializes version-checked updates so check-then-put is atomic
+    update_lock: tokio::sync::Mutex<()>,
+    /// Bumped by every mutating script; part of the query cache key
+    revision: AtomicU64,
+    /// Optional `raw_query` result cache (see `with_cache`)
+    query_cache: Option<Mutex<QueryCache>>,
+    /// Record reads in `AccessLog` (see `with_access_logging`)
+    access_logging: bool,
+    /// Database file or directory of a persistent engine, for `compact`
+    disk_path: Option<PathBuf>,
+    /// Rows per transaction in the batched inserts (see `open_with_options`)
+    commit_batch: usize,
+    /// Name of the entity relation (`CodeGraph` unless set by `open_with_options`)
+    relation: String,
+    /// Stamps `deleted_at` and decides expiry in `purge_expired`
+    clock: Arc<dyn Clock>,
+    /// Set once `CodeSpans` was seen, so reads stop listing relations
+    code_spans_exist: AtomicBool,
 }
 
 impl CozoDbStorage {
@@ -50,13 +112,150 @@ impl CozoDbStorage {
                 details: format!("Failed to create CozoDB instance with engine '{}' and path '{}': {}", engine, path, e),
             })?;
 
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            update_lock: tokio::sync::Mutex::new(()),
+            revision: AtomicU64::new(0),
+            query_cache: None,
+            access_logging: false,
+            disk_path: (engine != "mem" && !path.is_empty()).then(|| PathBuf::from(path)),
+            commit_batch: StorageOptions::default().commit_batch,
+            relation: DEFAULT_RELATION_NAME.to_string(),
+            clock: Arc::new(SystemClock),
+            code_spans_exist: AtomicBool::new(false),
+        })
+    }
+
+    /// Open storage like `new`, with tuned commit batching and RocksDB writes
+    ///
+    /// For RocksDB the options are written to the database directory before
+    /// opening (see `storage::options`), so they persist for later opens.
+    /// `sync_writes: false` trades durability for speed: a crash of the
+    /// machine can lose recent writes. `relation_name` selects which entity
+    /// relation every method reads and writes, so several graphs can share
+    /// one database; the other relations (edges, spans, ...) are shared.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use parseltongue_core::storage::{CozoDbStorage, StorageOptions};
+    ///
+    /// let options = StorageOptions { commit_batch: 5000, sync_writes: false, ..Default::default() };
+    /// let db = CozoDbStorage::open_with_options("mem", options).await.unwrap();
+    /// assert!(db.is_connected().await);
+    /// # });
+    /// ```
+    pub async fn open_with_options(engine_spec: &str, options: StorageOptions) -> Result<Self> {
+        if !is_relation_name(&options.relation_name) {
+            return Err(ParseltongError::DatabaseError {
+                operation: "connection".to_string(),
+                details: format!(
+                    "Invalid relation name '{}': use letters, digits and '_', starting with a letter",
+                    options.relation_name
+                ),
+            });
+        }
+
+        if let Some(path) = engine_spec.strip_prefix("rocksdb:") {
+            let write = std::fs::create_dir_all(path).and_then(|()| {
+                std::fs::write(Path::new(path).join(ROCKSDB_OPTIONS_FILE), options.rocksdb_options_file())
+            });
+            write.map_err(|e| ParseltongError::DatabaseError {
+                operation: "connection".to_string(),
+                details: format!("Failed to write RocksDB options to '{}': {}", path, e),
+            })?;
+        }
+
+        let mut storage = Self::new(engine_spec).await?;
+        storage.commit_batch = options.commit_batch.max(1);
+        storage.relation = options.relation_name;
+        Ok(storage)
+    }
+
+    /// Cache up to `capacity` `raw_query` results in process
+    ///
+    /// Repeated identical reads are served from memory until the next write
+    /// through this storage bumps the revision. Writes made by other processes
+    /// to the same database are not observed, so keep caching to short-lived
+    /// interactive sessions.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use parseltongue_core::storage::CozoDbStorage;
+    ///
+    /// let db = CozoDbStorage::new("mem").await.unwrap().with_cache(64);
+    /// db.raw_query("?[x] := x = 1").await.unwrap();
+    /// db.raw_query("?[x] := x = 1").await.unwrap();
+    /// assert_eq!(db.cache_stats().unwrap().hits, 1);
+    /// # });
+    /// ```
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.query_cache = Some(Mutex::new(QueryCache::new(capacity)));
+        self
+    }
+
+    /// Count reads made through `get_entity` and `get_entities_by_file`
+    ///
+    /// Every entity returned gets its `query_count` bumped and `last_queried`
+    /// set to now, which turns each read into a write; off by default. Stats
+    /// are read back with `get_access_stats`.
+    pub fn with_access_logging(mut self, enabled: bool) -> Self {
+        self.access_logging = enabled;
+        self
+    }
+
+    /// Stamp soft deletes, provenance and reads with `clock` instead of the
+    /// system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Time source of this storage; tools writing through it stamp their
+    /// own timestamps with it too
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Name of the entity relation, for callers composing their own queries
+    pub fn relation(&self) -> &str {
+        &self.relation
+    }
+
+    /// Query cache counters, or `None` when caching is disabled
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.query_cache
+            .as_ref()
+            .map(|cache| cache.lock().unwrap_or_else(|e| e.into_inner()).stats())
+    }
+
+    /// Write revision, bumped by every mutating script run through this storage
+    ///
+    /// Cheap to poll; an unchanged revision means nothing was written in this
+    /// process since the last read.
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
+    /// Run a script, bumping the write revision for mutable ones
+    fn run_script(
+        &self,
+        script: &str,
+        params: BTreeMap<String, DataValue>,
+        mutability: ScriptMutability,
+    ) -> std::result::Result<NamedRows, cozo::Error> {
+        let result = self.db.run_script(script, params, mutability);
+        if matches!(mutability, ScriptMutability::Mutable) {
+            self.revision.fetch_add(1, Ordering::SeqCst);
+        }
+        result
     }
 
     /// Check if database connection is alive
     pub async fn is_connected(&self) -> bool {
         // Test query to verify connection - use ::relations which always works
-        self.db
+        self
             .run_script("::relations", Default::default(), ScriptMutability::Immutable)
             .is_ok()
     }
@@ -65,9 +264,17 @@ impl CozoDbStorage {
     ///
     /// Implements schema from 01-cozodb-schema.md specification
     /// v0.9.0 Enhancement: Added entity_class column for test/code separation
+    /// `deleted_at` (RFC 3339, null while live) marks soft-deleted rows
+    /// `additional_metadata` holds `metadata.additional` as a JSON object
+    /// (null when empty); `created_at` is RFC 3339, null only for rows
+    /// written without it (they read as created when last modified);
+    /// `complexity_score` is the cyclomatic complexity, null when unmeasured;
+    /// `semantic_hash` is the formatting-insensitive hash of the current
+    /// code, null without a grammar
     pub async fn create_schema(&self) -> Result<()> {
-        let schema = r#"
-            :create CodeGraph {
+        let schema = format!(
+            r#"
+            :create {relation} {{
                 ISGL1_key: String =>
                 Current_Code: String?,
                 Future_Code: String?,
@@ -81,12 +288,19 @@ impl CozoDbStorage {
                 language: String,
                 last_modified: String,
                 entity_type: String,
-                entity_class: String
-            }
-        "#;
+                entity_class: String,
+                deleted_at: String? default null,
+                additional_metadata: String? default null,
+                created_at: String? default null,
+                complexity_score: Int? default null,
+                semantic_hash: String? default null
+            }}
+        "#,
+            relation = self.relation
+        );
 
-        self.db
-            .run_script(schema, Default::default(), ScriptMutability::Mutable)
+        self
+            .run_script(&schema, Default::default(), ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "schema_creation".to_string(),
                 details: format!("Failed to create schema: {}", e),
@@ -95,6 +309,258 @@ impl CozoDbStorage {
         Ok(())
     }
 
+    /// Create missing relations and migrate an older schema to the current one
+    ///
+    /// Idempotent: safe to call every time a tool opens the database, and
+    /// tolerant of another process creating the same relations concurrently.
+    /// Fresh entity relations start at `CURRENT_SCHEMA_VERSION`; existing ones,
+    /// named or not, are brought forward by `migrate`.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        let relations = self.list_relations().await?;
+        let exists = |name: &str| relations.iter().any(|r| r == name);
+        let relation_existed = exists(&self.relation);
+
+        if !relation_existed {
+            ignore_already_exists(self.create_schema().await)?;
+        }
+        if !exists("DependencyEdges") {
+            ignore_already_exists(self.create_dependency_edges_schema().await)?;
+        }
+        if !exists(CODE_SPANS_RELATION) {
+            ignore_already_exists(self.create_code_spans_schema().await)?;
+        }
+        if !exists(PROVENANCE_RELATION) {
+            ignore_already_exists(self.create_provenance_schema().await)?;
+        }
+        if !exists(PARSE_ERRORS_RELATION) {
+            ignore_already_exists(self.create_parse_errors_schema().await)?;
+        }
+        self.ensure_schema_version_relation(exists(SCHEMA_VERSION_RELATION)).await?;
+        if self.schema_version().await?.is_none() {
+            // Side relations are created regardless of the inferred version:
+            // an untracked relation may already have later columns
+            for step in side_relation_steps() {
+                self.apply_migration_step(step).await?;
+            }
+            let version = if relation_existed {
+                self.infer_untracked_schema_version().await?
+            } else {
+                CURRENT_SCHEMA_VERSION
+            };
+            self.set_schema_version(version).await?;
+        }
+
+        self.migrate().await?;
+        Ok(())
+    }
+
+    /// Rewrite `lsp_meta_data` values stored before it was structured
+    ///
+    /// Raw hover responses and plain hover text become [`LspMetadata`] JSON
+    /// (see [`LspMetadata::from_stored`]); values already in the current
+    /// form are left alone. Returns the number of entities rewritten.
+    ///
+    /// Runs once per relation as schema migration v3; rows written since
+    /// are already structured.
+    pub async fn upgrade_legacy_lsp_metadata(&self) -> Result<usize> {
+        let map_err = |e: cozo::Error| ParseltongError::DatabaseError {
+            operation: "upgrade_legacy_lsp_metadata".to_string(),
+            details: e.to_string(),
+        };
+        let query = format!(
+            "?[ISGL1_key, lsp_meta_data] := *{}{{ ISGL1_key, lsp_meta_data }}, !is_null(lsp_meta_data)",
+            self.relation
+        );
+        let result = self
+            .run_script(&query, Default::default(), ScriptMutability::Immutable)
+            .map_err(map_err)?;
+
+        let mut rows = Vec::new();
+        for row in &result.rows {
+            let (DataValue::Str(key), DataValue::Str(stored)) = (&row[0], &row[1]) else {
+                continue;
+            };
+            let upgraded = serde_json::to_string(&LspMetadata::from_stored(stored)).map_err(|e| {
+                ParseltongError::SerializationError {
+                    details: format!("Failed to serialize lsp_meta_data of {}: {}", key, e),
+                }
+            })?;
+            if upgraded != stored.as_str() {
+                rows.push(DataValue::List(vec![
+                    DataValue::Str(key.clone()),
+                    DataValue::Str(upgraded.into()),
+                ]));
+            }
+        }
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let upgraded = rows.len();
+        let update = format!(
+            "?[ISGL1_key, lsp_meta_data] <- $rows
+             :update {} {{ ISGL1_key => lsp_meta_data }}",
+            self.relation
+        );
+        let mut params = BTreeMap::new();
+        params.insert("rows".to_string(), DataValue::List(rows));
+        self.run_script(&update, params, ScriptMutability::Mutable).map_err(map_err)?;
+        Ok(upgraded)
+    }
+
+    /// Apply pending migrations in order, returning the versions applied
+    ///
+    /// Each applied step is recorded immediately, so a failure leaves the
+    /// database at the last successful version.
+    pub async fn migrate(&self) -> Result<Vec<i64>> {
+        let current = self.schema_version().await?.unwrap_or(0);
+        let mut applied = Vec::new();
+
+        for migration in pending_migrations(current) {
+            self.apply_migration_step(migration.step)
+                .await
+                .map_err(|e| ParseltongError::DatabaseError {
+                    operation: "migrate".to_string(),
+                    details: format!(
+                        "{} v{} ({}) failed: {}",
+                        self.relation, migration.version, migration.description, e
+                    ),
+                })?;
+            self.set_schema_version(migration.version).await?;
+            applied.push(migration.version);
+        }
+
+        Ok(applied)
+    }
+
+    async fn apply_migration_step(&self, step: MigrationStep) -> Result<()> {
+        match step {
+            MigrationStep::Script(script) => {
+                let script = script.replace(RELATION_PLACEHOLDER, &self.relation);
+                self.run_script(&script, Default::default(), ScriptMutability::Mutable)
+                    .map_err(|e| ParseltongError::DatabaseError {
+                        operation: "migrate".to_string(),
+                        details: e.to_string(),
+                    })?;
+            }
+            MigrationStep::UpgradeLegacyLspMetadata => {
+                self.upgrade_legacy_lsp_metadata().await?;
+            }
+            MigrationStep::CreateGeneratedEntities => {
+                ignore_already_exists(self.create_generated_schema().await)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Recorded schema version of the entity relation, or `None` if untracked
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        if !self.list_relations().await?.iter().any(|r| r == SCHEMA_VERSION_RELATION) {
+            return Ok(None);
+        }
+        let query = format!("?[version] := *{}{{relation: $relation, version}}", SCHEMA_VERSION_RELATION);
+        let mut params = BTreeMap::new();
+        params.insert("relation".to_string(), DataValue::Str(self.relation.as_str().into()));
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "schema_version".to_string(),
+                details: format!("Failed to read schema version of {}: {}", self.relation, e),
+            })?;
+
+        Ok(result.rows.first().and_then(|row| row.first()).and_then(|v| v.get_int()))
+    }
+
+    async fn set_schema_version(&self, version: i64) -> Result<()> {
+        let script = format!(
+            "?[relation, version] <- [[$relation, $version]] :put {} {{relation => version}}",
+            SCHEMA_VERSION_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("relation".to_string(), DataValue::Str(self.relation.as_str().into()));
+        params.insert("version".to_string(), DataValue::from(version));
+        self.run_script(&script, params, ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "set_schema_version".to_string(),
+                details: format!("Failed to record schema version {} of {}: {}", version, self.relation, e),
+            })?;
+        Ok(())
+    }
+
+    /// Create `SchemaVersion`, or re-key a single-row one by relation name
+    ///
+    /// Before versions were tracked per relation, `SchemaVersion` held one
+    /// `id = 0` row for `CodeGraph`.
+    async fn ensure_schema_version_relation(&self, exists: bool) -> Result<()> {
+        let map_err = |e: cozo::Error| ParseltongError::DatabaseError {
+            operation: "ensure_schema".to_string(),
+            details: format!("Failed to set up {}: {}", SCHEMA_VERSION_RELATION, e),
+        };
+        if !exists {
+            let create = format!(":create {} {{ relation: String => version: Int }}", SCHEMA_VERSION_RELATION);
+            return ignore_already_exists(
+                self.run_script(&create, Default::default(), ScriptMutability::Mutable)
+                    .map(|_| ())
+                    .map_err(map_err),
+            );
+        }
+        if self.relation_columns(SCHEMA_VERSION_RELATION).await?.iter().any(|c| c == "relation") {
+            return Ok(());
+        }
+        let rekey = format!(
+            r#"
+            ?[relation, version] := *{versions}{{id: 0, version}}, relation = "{code_graph}"
+            :replace {versions} {{ relation: String => version: Int }}
+            "#,
+            versions = SCHEMA_VERSION_RELATION,
+            code_graph = DEFAULT_RELATION_NAME
+        );
+        self.run_script(&rekey, Default::default(), ScriptMutability::Mutable)
+            .map_err(map_err)?;
+        Ok(())
+    }
+
+    /// Column names of `relation`, keys first
+    async fn relation_columns(&self, relation: &str) -> Result<Vec<String>> {
+        let result = self
+            .run_script(&format!("::columns {}", relation), Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "ensure_schema".to_string(),
+                details: format!("Failed to inspect {} columns: {}", relation, e),
+            })?;
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| match row.first() {
+                Some(DataValue::Str(name)) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Version of an entity relation created before it was tracked
+    async fn infer_untracked_schema_version(&self) -> Result<i64> {
+        let columns = self.relation_columns(&self.relation).await?;
+        let has_column = |column: &str| columns.iter().any(|c| c == column);
+
+        // Column inference cannot tell whether lsp_meta_data was upgraded
+        Ok(if has_column("semantic_hash") {
+            8
+        } else if has_column("complexity_score") {
+            7
+        } else if has_column("created_at") {
+            6
+        } else if has_column("additional_metadata") {
+            5
+        } else if has_column("deleted_at") {
+            2
+        } else if has_column("entity_class") {
+            1
+        } else {
+            0
+        })
+    }
+
     /// Create DependencyEdges schema for code dependency graph
     ///
     /// Implements dependency tracking with composite key (from_key, to_key, edge_type).
@@ -125,7 +591,7 @@ impl CozoDbStorage {
             }
         "#;
 
-        self.db
+        self
             .run_script(schema, Default::default(), ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "create_dependency_edges_schema".to_string(),
@@ -135,6 +601,603 @@ impl CozoDbStorage {
         Ok(())
     }
 
+    /// Create the CodeSpans relation used by spans-only ingestion
+    ///
+    /// `ensure_schema` creates it; call directly only for databases set up
+    /// with `create_schema`.
+    pub async fn create_code_spans_schema(&self) -> Result<()> {
+        let schema = format!(
+            ":create {} {{ ISGL1_key: String => file_path: String, byte_start: Int, byte_end: Int }}",
+            CODE_SPANS_RELATION
+        );
+
+        self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "create_code_spans_schema".to_string(),
+                details: format!("Failed to create {} schema: {}", CODE_SPANS_RELATION, e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Record where an entity's code lives instead of storing the text
+    ///
+    /// Pair with an entity inserted with `current_code: None`; every read
+    /// then returns the code read from disk.
+    pub async fn insert_code_span(&self, isgl1_key: &str, span: &CodeSpan) -> Result<()> {
+        let script = format!(
+            "?[ISGL1_key, file_path, byte_start, byte_end] <- [[$key, $file_path, $byte_start, $byte_end]]
+             :put {} {{ ISGL1_key => file_path, byte_start, byte_end }}",
+            CODE_SPANS_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+        params.insert("file_path".to_string(), DataValue::Str(span.file_path.as_str().into()));
+        params.insert("byte_start".to_string(), DataValue::from(span.byte_start as i64));
+        params.insert("byte_end".to_string(), DataValue::from(span.byte_end as i64));
+
+        self.run_script(&script, params, ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "insert_code_span".to_string(),
+                details: format!("Failed to store code span for {}: {}", isgl1_key, e),
+            })?;
+        Ok(())
+    }
+
+    /// Stored code span of an entity, if it was ingested spans-only
+    pub async fn get_code_span(&self, isgl1_key: &str) -> Result<Option<CodeSpan>> {
+        Ok(self.get_code_spans(&[isgl1_key]).await?.remove(isgl1_key))
+    }
+
+    /// Stored code spans of the spans-only entities among `keys`, in one query
+    pub async fn get_code_spans(&self, keys: &[&str]) -> Result<HashMap<String, CodeSpan>> {
+        if keys.is_empty() || !self.code_spans_exist().await? {
+            return Ok(HashMap::new());
+        }
+        let query = format!(
+            "?[ISGL1_key, file_path, byte_start, byte_end] :=
+                ISGL1_key in $keys,
+                *{}{{ ISGL1_key, file_path, byte_start, byte_end }}",
+            CODE_SPANS_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert(
+            "keys".to_string(),
+            DataValue::List(keys.iter().map(|key| DataValue::Str((*key).into())).collect()),
+        );
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_code_span".to_string(),
+                details: format!("Failed to read code spans of {} entities: {}", keys.len(), e),
+            })?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [DataValue::Str(key), DataValue::Str(file_path), start, end] => Some((
+                    key.to_string(),
+                    CodeSpan {
+                        file_path: file_path.to_string(),
+                        byte_start: start.get_int()? as usize,
+                        byte_end: end.get_int()? as usize,
+                    },
+                )),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Code of the spans-only entities among `keys`, read from disk
+    ///
+    /// Fails if a file changed so that its span no longer holds UTF-8 text.
+    pub async fn code_for_spans(&self, keys: &[&str]) -> Result<HashMap<String, String>> {
+        let mut code = HashMap::new();
+        for (key, span) in self.get_code_spans(keys).await? {
+            let bytes = std::fs::read(&span.file_path).map_err(|source| ParseltongError::FileSystemError {
+                path: span.file_path.clone(),
+                source,
+            })?;
+            let text = bytes
+                .get(span.byte_start..span.byte_end)
+                .and_then(|slice| std::str::from_utf8(slice).ok())
+                .ok_or_else(|| ParseltongError::DatabaseError {
+                    operation: "code_for_spans".to_string(),
+                    details: format!(
+                        "Span {}..{} of {} no longer matches the file (was it modified since ingestion?)",
+                        span.byte_start, span.byte_end, span.file_path
+                    ),
+                })?;
+            code.insert(key, text.to_string());
+        }
+        Ok(code)
+    }
+
+    /// Whether `CodeSpans` exists; only a positive answer is cached, since
+    /// the relation may be created after this client opened the database
+    async fn code_spans_exist(&self) -> Result<bool> {
+        if self.code_spans_exist.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        let exists = self.list_relations().await?.iter().any(|r| r == CODE_SPANS_RELATION);
+        self.code_spans_exist.store(exists, Ordering::Relaxed);
+        Ok(exists)
+    }
+
+    /// Create the Provenance relation written by `record_provenance`
+    ///
+    /// `ensure_schema` creates it; call directly only for databases set up
+    /// with `create_schema`.
+    pub async fn create_provenance_schema(&self) -> Result<()> {
+        let schema = format!(
+            ":create {} {{ ISGL1_key: String => tool: String, modified_at: String }}",
+            PROVENANCE_RELATION
+        );
+
+        self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "create_provenance_schema".to_string(),
+                details: format!("Failed to create {} schema: {}", PROVENANCE_RELATION, e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Record `tool` as the last writer of an entity, timestamped now
+    ///
+    /// Replaces any earlier record; only the most recent writer is kept.
+    pub async fn record_provenance(&self, isgl1_key: &str, tool: &str) -> Result<Provenance> {
+        let provenance = Provenance {
+            tool: tool.to_string(),
+            modified_at: self.clock.now().to_rfc3339(),
+        };
+        let script = format!(
+            "?[ISGL1_key, tool, modified_at] <- [[$key, $tool, $modified_at]]
+             :put {} {{ ISGL1_key => tool, modified_at }}",
+            PROVENANCE_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+        params.insert("tool".to_string(), DataValue::Str(provenance.tool.as_str().into()));
+        params.insert("modified_at".to_string(), DataValue::Str(provenance.modified_at.as_str().into()));
+
+        self.run_script(&script, params, ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "record_provenance".to_string(),
+                details: format!("Failed to record provenance for {}: {}", isgl1_key, e),
+            })?;
+        Ok(provenance)
+    }
+
+    /// Last writer of an entity, if any tool recorded one
+    pub async fn get_provenance(&self, isgl1_key: &str) -> Result<Option<Provenance>> {
+        Ok(self
+            .query_provenance(Some(isgl1_key))
+            .await?
+            .into_iter()
+            .next()
+            .map(|(_, provenance)| provenance))
+    }
+
+    /// Every recorded provenance, keyed by ISGL1 key
+    pub async fn get_all_provenance(&self) -> Result<HashMap<String, Provenance>> {
+        Ok(self.query_provenance(None).await?.into_iter().collect())
+    }
+
+    /// Create the ParseErrors relation written by `replace_parse_errors`
+    ///
+    /// `ensure_schema` creates it; call directly only for databases set up
+    /// with `create_schema`.
+    pub async fn create_parse_errors_schema(&self) -> Result<()> {
+        let schema = format!(
+            ":create {} {{ file_path: String, line: Int, column: Int => message: String }}",
+            PARSE_ERRORS_RELATION
+        );
+
+        self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "create_parse_errors_schema".to_string(),
+                details: format!("Failed to create {} schema: {}", PARSE_ERRORS_RELATION, e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Replace the recorded parse errors of one file
+    ///
+    /// An empty `errors` clears the file, so re-ingesting a fixed file
+    /// removes its stale rows.
+    pub async fn replace_parse_errors(&self, file_path: &str, errors: &[ParseErrorRecord]) -> Result<()> {
+        let map_err = |e: cozo::Error| ParseltongError::DatabaseError {
+            operation: "replace_parse_errors".to_string(),
+            details: format!("Failed to record parse errors for {}: {}", file_path, e),
+        };
+
+        let clear = format!(
+            "?[file_path, line, column] := *{rel}{{ file_path, line, column }}, file_path = $file
+             :rm {rel} {{ file_path, line, column }}",
+            rel = PARSE_ERRORS_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("file".to_string(), DataValue::Str(file_path.into()));
+        self.run_script(&clear, params, ScriptMutability::Mutable).map_err(map_err)?;
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let rows = errors
+            .iter()
+            .map(|error| {
+                DataValue::List(vec![
+                    DataValue::Str(file_path.into()),
+                    DataValue::from(error.line as i64),
+                    DataValue::from(error.column as i64),
+                    DataValue::Str(error.message.as_str().into()),
+                ])
+            })
+            .collect();
+        let put = format!(
+            "?[file_path, line, column, message] <- $rows
+             :put {} {{ file_path, line, column => message }}",
+            PARSE_ERRORS_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("rows".to_string(), DataValue::List(rows));
+        self.run_script(&put, params, ScriptMutability::Mutable).map_err(map_err)?;
+        Ok(())
+    }
+
+    /// Every recorded parse error, ordered by file, line and column
+    ///
+    /// Empty if the relation is missing (a database never ingested by pt01).
+    pub async fn get_parse_errors(&self) -> Result<Vec<ParseErrorRecord>> {
+        if !self.list_relations().await?.iter().any(|r| r == PARSE_ERRORS_RELATION) {
+            return Ok(Vec::new());
+        }
+        let query = format!(
+            "?[file_path, line, column, message] := *{}{{ file_path, line, column, message }}
+             :order file_path, line, column",
+            PARSE_ERRORS_RELATION
+        );
+
+        let result = self
+            .run_script(&query, Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_parse_errors".to_string(),
+                details: format!("Failed to read {}: {}", PARSE_ERRORS_RELATION, e),
+            })?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [DataValue::Str(file_path), line, column, DataValue::Str(message)] => Some(ParseErrorRecord {
+                    file_path: file_path.to_string(),
+                    line: line.get_int()? as usize,
+                    column: column.get_int()? as usize,
+                    message: message.to_string(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Create the GeneratedEntities relation written by `replace_generated_entities`
+    ///
+    /// Schema migration v4 creates it; call directly only for databases set
+    /// up with `create_schema`.
+    pub async fn create_generated_schema(&self) -> Result<()> {
+        let schema = format!(
+            ":create {} {{ ISGL1_key: String => file_path: String }}",
+            GENERATED_RELATION
+        );
+
+        self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "create_generated_schema".to_string(),
+                details: format!("Failed to create {} schema: {}", GENERATED_RELATION, e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Replace the entities of one file flagged as generated
+    ///
+    /// An empty `keys` clears the file, so a file that is no longer
+    /// generated loses its flags on re-ingestion.
+    pub async fn replace_generated_entities(&self, file_path: &str, keys: &[String]) -> Result<()> {
+        let map_err = |e: cozo::Error| ParseltongError::DatabaseError {
+            operation: "replace_generated_entities".to_string(),
+            details: format!("Failed to flag generated entities of {}: {}", file_path, e),
+        };
+
+        let clear = format!(
+            "?[ISGL1_key] := *{rel}{{ ISGL1_key, file_path }}, file_path = $file
+             :rm {rel} {{ ISGL1_key }}",
+            rel = GENERATED_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("file".to_string(), DataValue::Str(file_path.into()));
+        self.run_script(&clear, params, ScriptMutability::Mutable).map_err(map_err)?;
+
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let rows = keys
+            .iter()
+            .map(|key| DataValue::List(vec![DataValue::Str(key.as_str().into()), DataValue::Str(file_path.into())]))
+            .collect();
+        let put = format!(
+            "?[ISGL1_key, file_path] <- $rows
+             :put {} {{ ISGL1_key => file_path }}",
+            GENERATED_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("rows".to_string(), DataValue::List(rows));
+        self.run_script(&put, params, ScriptMutability::Mutable).map_err(map_err)?;
+        Ok(())
+    }
+
+    /// Keys of every entity flagged as generated
+    ///
+    /// Empty if the relation is missing (a database never ingested by pt01).
+    pub async fn get_generated_keys(&self) -> Result<HashSet<String>> {
+        if !self.list_relations().await?.iter().any(|r| r == GENERATED_RELATION) {
+            return Ok(HashSet::new());
+        }
+        let query = format!("?[ISGL1_key] := *{}{{ ISGL1_key }}", GENERATED_RELATION);
+
+        let result = self
+            .run_script(&query, Default::default(), ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_generated_keys".to_string(),
+                details: format!("Failed to read {}: {}", GENERATED_RELATION, e),
+            })?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| match row.first() {
+                Some(DataValue::Str(key)) => Some(key.to_string()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Bump the access counters of `keys`, creating the relation on first use
+    async fn record_access(&self, keys: &[&str]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        if !self.list_relations().await?.iter().any(|r| r == ACCESS_LOG_RELATION) {
+            let schema = format!(
+                ":create {} {{ ISGL1_key: String => last_queried: String, query_count: Int }}",
+                ACCESS_LOG_RELATION
+            );
+            ignore_already_exists(
+                self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+                    .map(|_| ())
+                    .map_err(|e| ParseltongError::DatabaseError {
+                        operation: "record_access".to_string(),
+                        details: format!("Failed to create {} schema: {}", ACCESS_LOG_RELATION, e),
+                    }),
+            )?;
+        }
+
+        // Read-increment-write in one script, so concurrent reads never lose a count
+        let script = format!(
+            "seen[k, c] := k in $keys, *{rel}{{ ISGL1_key: k, query_count: c }}
+             seen[k, c] := k in $keys, not *{rel}{{ ISGL1_key: k }}, c = 0
+             ?[ISGL1_key, last_queried, query_count] := seen[ISGL1_key, c], last_queried = $now, query_count = c + 1
+             :put {rel} {{ ISGL1_key => last_queried, query_count }}",
+            rel = ACCESS_LOG_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert(
+            "keys".to_string(),
+            DataValue::List(keys.iter().map(|key| DataValue::Str((*key).into())).collect()),
+        );
+        params.insert("now".to_string(), DataValue::Str(self.clock.now().to_rfc3339().into()));
+
+        self.run_script(&script, params, ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "record_access".to_string(),
+                details: format!("Failed to record access: {}", e),
+            })?;
+        Ok(())
+    }
+
+    /// Read counters of an entity; `None` if it was never read with access
+    /// logging on
+    pub async fn get_access_stats(&self, isgl1_key: &str) -> Result<Option<AccessStats>> {
+        if !self.list_relations().await?.iter().any(|r| r == ACCESS_LOG_RELATION) {
+            return Ok(None);
+        }
+        let query = format!(
+            "?[last_queried, query_count] := *{}{{ ISGL1_key: $key, last_queried, query_count }}",
+            ACCESS_LOG_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_access_stats".to_string(),
+                details: format!("Failed to read {}: {}", ACCESS_LOG_RELATION, e),
+            })?;
+
+        Ok(result.rows.first().and_then(|row| match row.as_slice() {
+            [DataValue::Str(last_queried), count] => Some(AccessStats {
+                last_queried: last_queried.to_string(),
+                query_count: count.get_int()? as u64,
+            }),
+            _ => None,
+        }))
+    }
+
+    /// Record a write applied under `idempotency_key`, creating the
+    /// relation on first use
+    ///
+    /// Only the first record for a key is kept, so a racing retry can't
+    /// overwrite the original result. Returns the record now stored.
+    pub async fn record_applied_write(&self, idempotency_key: &str, write: &AppliedWrite) -> Result<AppliedWrite> {
+        if !self.list_relations().await?.iter().any(|r| r == APPLIED_WRITES_RELATION) {
+            let schema = format!(
+                ":create {} {{ idempotency_key: String => ISGL1_key: String, action: String, result: String, applied_at: String }}",
+                APPLIED_WRITES_RELATION
+            );
+            ignore_already_exists(
+                self.run_script(&schema, Default::default(), ScriptMutability::Mutable)
+                    .map(|_| ())
+                    .map_err(|e| ParseltongError::DatabaseError {
+                        operation: "record_applied_write".to_string(),
+                        details: format!("Failed to create {} schema: {}", APPLIED_WRITES_RELATION, e),
+                    }),
+            )?;
+        }
+
+        let script = format!(
+            "?[idempotency_key, ISGL1_key, action, result, applied_at] <- [[$id, $key, $action, $result, $applied_at]]
+             :insert {} {{ idempotency_key => ISGL1_key, action, result, applied_at }}",
+            APPLIED_WRITES_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("id".to_string(), DataValue::Str(idempotency_key.into()));
+        params.insert("key".to_string(), DataValue::Str(write.isgl1_key.as_str().into()));
+        params.insert("action".to_string(), DataValue::Str(write.action.as_str().into()));
+        params.insert("result".to_string(), DataValue::Str(write.result.as_str().into()));
+        params.insert("applied_at".to_string(), DataValue::Str(write.applied_at.as_str().into()));
+
+        match self.run_script(&script, params, ScriptMutability::Mutable) {
+            Ok(_) => Ok(write.clone()),
+            // `:insert` refuses an existing key: another run got there first
+            Err(e) => match self.get_applied_write(idempotency_key).await? {
+                Some(existing) => Ok(existing),
+                None => Err(ParseltongError::DatabaseError {
+                    operation: "record_applied_write".to_string(),
+                    details: format!("Failed to record idempotency key {}: {}", idempotency_key, e),
+                }),
+            },
+        }
+    }
+
+    /// Write previously applied under `idempotency_key`, if any
+    pub async fn get_applied_write(&self, idempotency_key: &str) -> Result<Option<AppliedWrite>> {
+        if !self.list_relations().await?.iter().any(|r| r == APPLIED_WRITES_RELATION) {
+            return Ok(None);
+        }
+        let query = format!(
+            "?[ISGL1_key, action, result, applied_at] := *{}{{ idempotency_key: $id, ISGL1_key, action, result, applied_at }}",
+            APPLIED_WRITES_RELATION
+        );
+        let mut params = BTreeMap::new();
+        params.insert("id".to_string(), DataValue::Str(idempotency_key.into()));
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_applied_write".to_string(),
+                details: format!("Failed to read {}: {}", APPLIED_WRITES_RELATION, e),
+            })?;
+
+        Ok(result.rows.first().and_then(|row| match row.as_slice() {
+            [DataValue::Str(key), DataValue::Str(action), DataValue::Str(result), DataValue::Str(applied_at)] => {
+                Some(AppliedWrite {
+                    isgl1_key: key.to_string(),
+                    action: action.to_string(),
+                    result: result.to_string(),
+                    applied_at: applied_at.to_string(),
+                })
+            }
+            _ => None,
+        }))
+    }
+
+    /// Provenance rows for one key or all keys; empty if the relation is missing
+    async fn query_provenance(&self, isgl1_key: Option<&str>) -> Result<Vec<(String, Provenance)>> {
+        if !self.list_relations().await?.iter().any(|r| r == PROVENANCE_RELATION) {
+            return Ok(Vec::new());
+        }
+        let mut params = BTreeMap::new();
+        let key_filter = match isgl1_key {
+            Some(key) => {
+                params.insert("key".to_string(), DataValue::Str(key.into()));
+                ", ISGL1_key = $key"
+            }
+            None => "",
+        };
+        let query = format!(
+            "?[ISGL1_key, tool, modified_at] := *{}{{ ISGL1_key, tool, modified_at }}{}",
+            PROVENANCE_RELATION, key_filter
+        );
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_provenance".to_string(),
+                details: format!("Failed to read {}: {}", PROVENANCE_RELATION, e),
+            })?;
+
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [DataValue::Str(key), DataValue::Str(tool), DataValue::Str(modified_at)] => Some((
+                    key.to_string(),
+                    Provenance {
+                        tool: tool.to_string(),
+                        modified_at: modified_at.to_string(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Convert rows to entities, reading the code of spans-only ones from disk
+    ///
+    /// Every entity read goes through here, so spans-only entities come
+    /// back with code whichever read returned them. Unchanged entities (no
+    /// `future_action`) get the same text as `future_code`, matching what
+    /// full-text ingestion stores.
+    async fn rows_to_entities(&self, rows: &[Vec<DataValue>]) -> Result<Vec<CodeEntity>> {
+        let mut entities = rows.iter().map(|row| self.row_to_entity(row)).collect::<Result<Vec<_>>>()?;
+        let without_code: Vec<&str> = entities
+            .iter()
+            .filter(|e| e.current_code.is_none())
+            .map(|e| e.isgl1_key.as_str())
+            .collect();
+        let mut code = self.code_for_spans(&without_code).await?;
+        if code.is_empty() {
+            return Ok(entities);
+        }
+        for entity in &mut entities {
+            let Some(text) = code.remove(&entity.isgl1_key) else {
+                continue;
+            };
+            if entity.temporal_state.future_action.is_none() && entity.future_code.is_none() {
+                entity.future_code = Some(text.clone());
+            }
+            entity.current_code = Some(text);
+        }
+        Ok(entities)
+    }
+
+    /// Read query over the entity rows whose `deleted_at` matches `deleted`
+    ///
+    /// `rest` follows the relation atom: further conditions (each starting
+    /// with a comma) and query options.
+    fn entity_query(&self, deleted: &str, rest: &str) -> String {
+        let columns = ENTITY_COLUMNS.join(", ");
+        format!(
+            "?[{columns}] := *{relation}{{{columns}, {deleted}}}{rest}",
+            relation = self.relation
+        )
+    }
+
     /// Insert a single dependency edge
     ///
     /// # Performance Contract
@@ -175,7 +1238,7 @@ impl CozoDbStorage {
                 .unwrap_or(DataValue::Null),
         );
 
-        self.db
+        self
             .run_script(query, params, ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "insert_edge".to_string(),
@@ -201,10 +1264,14 @@ impl CozoDbStorage {
     /// storage.insert_edges_batch(&edges).await?;
     /// ```
     pub async fn insert_edges_batch(&self, edges: &[DependencyEdge]) -> Result<()> {
-        if edges.is_empty() {
-            return Ok(());
+        for chunk in edges.chunks(self.commit_batch) {
+            self.insert_edges_chunk(chunk)?;
         }
+        Ok(())
+    }
 
+    /// Insert one transaction's worth of edges
+    fn insert_edges_chunk(&self, edges: &[DependencyEdge]) -> Result<()> {
         // Build query with inline data for batch insert
         let query = format!(
             r#"
@@ -236,7 +1303,7 @@ impl CozoDbStorage {
                 .join(", ")
         );
 
-        self.db
+        self
             .run_script(&query, Default::default(), ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "insert_edges_batch".to_string(),
@@ -246,6 +1313,39 @@ impl CozoDbStorage {
         Ok(())
     }
 
+    /// Replace the outgoing edges of one entity's code
+    ///
+    /// Removes the edges from `from_key`, then inserts `edges` (which
+    /// should all start at `from_key`). `Contains` edges record where
+    /// entities sit in the file rather than what the code references, so
+    /// they are kept, as are incoming edges. Returns how many edges were
+    /// removed.
+    pub async fn replace_outgoing_edges(&self, from_key: &str, edges: &[DependencyEdge]) -> Result<usize> {
+        let map_err = |e: cozo::Error| ParseltongError::DependencyError {
+            operation: "replace_outgoing_edges".to_string(),
+            reason: format!("Failed to remove edges from {}: {}", from_key, e),
+        };
+        let select = "?[from_key, to_key, edge_type] := *DependencyEdges{from_key, to_key, edge_type},
+                from_key = $key, edge_type != 'Contains'";
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(from_key.into()));
+
+        let removed = self
+            .run_script(select, params.clone(), ScriptMutability::Immutable)
+            .map_err(map_err)?
+            .rows
+            .len();
+        if removed > 0 {
+            let clear = format!("{}\n:rm DependencyEdges {{ from_key, to_key, edge_type }}", select);
+            self.run_script(&clear, params, ScriptMutability::Mutable).map_err(map_err)?;
+        }
+
+        if !edges.is_empty() {
+            self.insert_edges_batch(edges).await?;
+        }
+        Ok(removed)
+    }
+
     /// Calculate blast radius: Find all entities within N hops of a changed entity.
     ///
     /// Uses CozoDB recursive Datalog queries to perform bounded BFS graph traversal,
@@ -343,7 +1443,6 @@ impl CozoDbStorage {
         params.insert("max_hops".to_string(), DataValue::from(max_hops as i64));
 
         let result = self
-            .db
             .run_script(&query, params, ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "calculate_blast_radius".to_string(),
@@ -426,7 +1525,6 @@ impl CozoDbStorage {
         params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
 
         let result = self
-            .db
             .run_script(query, params, ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "get_forward_dependencies".to_string(),
@@ -497,7 +1595,6 @@ impl CozoDbStorage {
         params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
 
         let result = self
-            .db
             .run_script(query, params, ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "get_reverse_dependencies".to_string(),
@@ -551,52 +1648,65 @@ impl CozoDbStorage {
         let query = "?[from_key, to_key, edge_type, source_location] := *DependencyEdges{from_key, to_key, edge_type, source_location}";
 
         let result = self
-            .db
             .run_script(query, BTreeMap::new(), ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "get_all_dependencies".to_string(),
                 reason: format!("Failed to query all dependencies: {}", e),
             })?;
 
-        // Parse results into DependencyEdge structs
-        let mut dependencies = Vec::new();
-        for row in result.rows {
-            if row.len() >= 3 {
-                if let (Some(DataValue::Str(from_key)), Some(DataValue::Str(to_key)), Some(DataValue::Str(edge_type_str))) =
-                    (row.get(0), row.get(1), row.get(2))
-                {
-                    let edge_type = match edge_type_str.as_str() {
-                        "Calls" => EdgeType::Calls,
-                        "Uses" => EdgeType::Uses,
-                        "Implements" => EdgeType::Implements,
-                        _ => continue, // Skip unknown edge types
-                    };
+        rows_to_dependency_edges(result.rows, "get_all_dependencies")
+    }
 
-                    let source_location = row.get(3).and_then(|v| {
-                        if let DataValue::Str(loc) = v {
-                            Some(loc.to_string())
-                        } else {
-                            None
-                        }
-                    });
-
-                    let edge = DependencyEdge::builder()
-                        .from_key(from_key.to_string())
-                        .to_key(to_key.to_string())
-                        .edge_type(edge_type)
-                        .source_location(source_location.unwrap_or_default())
-                        .build()
-                        .map_err(|e| ParseltongError::DependencyError {
-                            operation: "get_all_dependencies".to_string(),
-                            reason: format!("Failed to build DependencyEdge: {}", e),
-                        })?;
-
-                    dependencies.push(edge);
-                }
-            }
-        }
+    /// Find edges whose `from_key` or `to_key` has no entity in CodeGraph
+    ///
+    /// Orphans appear after an entity is deleted while edges still point at
+    /// it (or at unresolved external symbols), and would surface as dangling
+    /// references in exports.
+    ///
+    /// # Example
+    /// ```
+    /// use parseltongue_core::storage::CozoDbStorage;
+    /// use parseltongue_core::entities::{DependencyEdge, EdgeType};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let storage = CozoDbStorage::new("mem").await.unwrap();
+    /// storage.create_schema().await.unwrap();
+    /// storage.create_dependency_edges_schema().await.unwrap();
+    ///
+    /// let edge = DependencyEdge::builder()
+    ///     .from_key("rust:fn:main:src_main_rs:1-10")
+    ///     .to_key("rust:fn:gone:src_lib_rs:5-20")
+    ///     .edge_type(EdgeType::Calls)
+    ///     .build()
+    ///     .unwrap();
+    /// storage.insert_edge(&edge).await.unwrap();
+    ///
+    /// let orphans = storage.find_orphan_edges().await.unwrap();
+    /// assert_eq!(orphans.len(), 1);
+    /// assert_eq!(orphans[0].to_key, edge.to_key);
+    /// # });
+    /// ```
+    pub async fn find_orphan_edges(&self) -> Result<Vec<DependencyEdge>> {
+        let query = format!(
+            r#"
+            ?[from_key, to_key, edge_type, source_location] :=
+                *DependencyEdges{{from_key, to_key, edge_type, source_location}},
+                not *{relation}{{ISGL1_key: from_key, deleted_at: null}}
+            ?[from_key, to_key, edge_type, source_location] :=
+                *DependencyEdges{{from_key, to_key, edge_type, source_location}},
+                not *{relation}{{ISGL1_key: to_key, deleted_at: null}}
+        "#,
+            relation = self.relation
+        );
 
-        Ok(dependencies)
+        let result = self
+            .run_script(&query, BTreeMap::new(), ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DependencyError {
+                operation: "find_orphan_edges".to_string(),
+                reason: format!("Failed to query orphan edges: {}", e),
+            })?;
+
+        rows_to_dependency_edges(result.rows, "find_orphan_edges")
     }
 
     /// Get transitive closure: all entities reachable from this entity (unbounded).
@@ -692,7 +1802,6 @@ impl CozoDbStorage {
         params.insert("start_key".to_string(), DataValue::Str(isgl1_key.into()));
 
         let result = self
-            .db
             .run_script(query, params, ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DependencyError {
                 operation: "get_transitive_closure".to_string(),
@@ -710,12 +1819,107 @@ impl CozoDbStorage {
         Ok(reachable)
     }
 
+    /// Shortest dependency path from `from` to `to`, following edge direction.
+    ///
+    /// Returns the keys along the path, both ends included, or `None` when
+    /// `to` is unreachable within [`DEFAULT_MAX_PATH_HOPS`]. See
+    /// [`shortest_path_within`] for a different cap.
+    ///
+    /// # Example
+    /// ```
+    /// use parseltongue_core::storage::CozoDbStorage;
+    /// use parseltongue_core::entities::{DependencyEdge, EdgeType};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let storage = CozoDbStorage::new("mem").await.unwrap();
+    /// storage.create_dependency_edges_schema().await.unwrap();
+    ///
+    /// // A -> B
+    /// let edge = DependencyEdge::builder()
+    ///     .from_key("rust:fn:A:test_rs:1-5")
+    ///     .to_key("rust:fn:B:test_rs:10-15")
+    ///     .edge_type(EdgeType::Calls)
+    ///     .build().unwrap();
+    /// storage.insert_edge(&edge).await.unwrap();
+    ///
+    /// let path = storage.shortest_path("rust:fn:A:test_rs:1-5", "rust:fn:B:test_rs:10-15").await.unwrap();
+    /// assert_eq!(path.unwrap().len(), 2);
+    /// assert!(storage.shortest_path("rust:fn:B:test_rs:10-15", "rust:fn:A:test_rs:1-5").await.unwrap().is_none());
+    /// # });
+    /// ```
+    pub async fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Vec<String>>> {
+        self.shortest_path_within(from, to, DEFAULT_MAX_PATH_HOPS).await
+    }
+
+    /// [`shortest_path`] exploring at most `max_hops` edges from `from`
+    ///
+    /// # Algorithm
+    /// Breadth-first search, one query per hop for the edges leaving the
+    /// current frontier. Each node keeps the first predecessor that reached
+    /// it, so the path is rebuilt by walking predecessors back from `to`.
+    /// Cost is bounded by `max_hops` queries however dense the graph.
+    pub async fn shortest_path_within(
+        &self,
+        from: &str,
+        to: &str,
+        max_hops: usize,
+    ) -> Result<Option<Vec<String>>> {
+        if from == to {
+            return Ok(Some(vec![from.to_string()]));
+        }
+
+        let query = "?[from_key, to_key] := *DependencyEdges{from_key, to_key}, is_in(from_key, $frontier)";
+        let mut predecessor: HashMap<String, String> = HashMap::new();
+        let mut frontier = vec![from.to_string()];
+
+        for _ in 0..max_hops {
+            if frontier.is_empty() {
+                break;
+            }
+            let mut params = BTreeMap::new();
+            params.insert(
+                "frontier".to_string(),
+                DataValue::List(frontier.iter().map(|key| DataValue::Str(key.as_str().into())).collect()),
+            );
+            let result = self
+                .run_script(query, params, ScriptMutability::Immutable)
+                .map_err(|e| ParseltongError::DependencyError {
+                    operation: "shortest_path".to_string(),
+                    reason: format!("Failed to expand path frontier: {}", e),
+                })?;
+
+            let mut next = Vec::new();
+            for row in result.rows {
+                if let (Some(DataValue::Str(source)), Some(DataValue::Str(target))) = (row.first(), row.get(1)) {
+                    let target = target.to_string();
+                    if target == from || predecessor.contains_key(&target) {
+                        continue;
+                    }
+                    predecessor.insert(target.clone(), source.to_string());
+                    next.push(target);
+                }
+            }
+
+            if predecessor.contains_key(to) {
+                let mut path = vec![to.to_string()];
+                while let Some(previous) = predecessor.get(path.last().unwrap()) {
+                    path.push(previous.clone());
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            frontier = next;
+        }
+
+        Ok(None)
+    }
+
     /// Execute raw Datalog query (S01 ultra-minimalist - direct CozoDB access)
     ///
     /// For Tool 2 --query interface. Executes user-provided Datalog directly.
     /// NO query validation, NO safety checks - trust the user (S01 principle).
     pub async fn execute_query(&self, query: &str) -> Result<()> {
-        self.db
+        self
             .run_script(query, Default::default(), ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "execute_query".to_string(),
@@ -740,20 +1944,32 @@ impl CozoDbStorage {
     /// }
     /// ```
     pub async fn raw_query(&self, query: &str) -> Result<cozo::NamedRows> {
+        let revision = self.revision.load(Ordering::SeqCst);
+        if let Some(cache) = &self.query_cache {
+            if let Some(rows) = cache.lock().unwrap_or_else(|e| e.into_inner()).get(query, revision) {
+                return Ok(rows);
+            }
+        }
+
         let result = self
-            .db
             .run_script(query, Default::default(), ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "raw_query".to_string(),
                 details: format!("Datalog query failed: {}", e),
             })?;
+
+        if let Some(cache) = &self.query_cache {
+            cache
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(query, revision, result.clone());
+        }
         Ok(result)
     }
 
     /// List all relations in the database
     pub async fn list_relations(&self) -> Result<Vec<String>> {
         let result = self
-            .db
             .run_script("::relations", Default::default(), ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "list_relations".to_string(),
@@ -771,53 +1987,130 @@ impl CozoDbStorage {
     }
 
     /// Insert entity into database
+    ///
+    /// Writing over a soft-deleted entity restores it (`deleted_at` resets).
     pub async fn insert_entity(&self, entity: &CodeEntity) -> Result<()> {
-        let query = r#"
-            ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-              lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-              last_modified, entity_type, entity_class] <-
-            [[$ISGL1_key, $Current_Code, $Future_Code, $interface_signature, $TDD_Classification,
-              $lsp_meta_data, $current_ind, $future_ind, $Future_Action, $file_path, $language,
-              $last_modified, $entity_type, $entity_class]]
-
-            :put CodeGraph {
-                ISGL1_key =>
-                Current_Code, Future_Code, interface_signature, TDD_Classification,
-                lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-                last_modified, entity_type, entity_class
-            }
-        "#;
+        let query = format!(
+            "?[{cols}] <- [[{params}]]
+             :put {relation} {{ ISGL1_key => {values} }}",
+            cols = ENTITY_COLUMNS.join(", "),
+            params = ENTITY_COLUMNS.map(|column| format!("${}", column)).join(", "),
+            relation = self.relation,
+            values = ENTITY_COLUMNS[1..].join(", ")
+        );
+
+        let params = self.entity_to_params(entity)?;
+
+        self
+            .run_script(&query, params, ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "insert_entity".to_string(),
+                details: format!("Failed to insert entity: {}", e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Insert many entities, committing `commit_batch` rows per transaction
+    ///
+    /// Much faster than `insert_entity` in a loop for bulk ingestion. A
+    /// failed batch leaves earlier batches committed.
+    pub async fn insert_entities_batch(&self, entities: &[CodeEntity]) -> Result<()> {
+        let query = format!(
+            "?[{cols}] <- $rows
+             :put {relation} {{ ISGL1_key => {values} }}",
+            cols = ENTITY_COLUMNS.join(", "),
+            relation = self.relation,
+            values = ENTITY_COLUMNS[1..].join(", ")
+        );
+
+        for chunk in entities.chunks(self.commit_batch) {
+            let rows = chunk
+                .iter()
+                .map(|entity| {
+                    let mut params = self.entity_to_params(entity)?;
+                    Ok(DataValue::List(
+                        ENTITY_COLUMNS
+                            .iter()
+                            .map(|column| params.remove(*column).unwrap_or(DataValue::Null))
+                            .collect(),
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut params = BTreeMap::new();
+            params.insert("rows".to_string(), DataValue::List(rows));
+
+            self.run_script(&query, params, ScriptMutability::Mutable)
+                .map_err(|e| ParseltongError::DatabaseError {
+                    operation: "insert_entities_batch".to_string(),
+                    details: format!("Failed to insert batch of {} entities: {}", chunk.len(), e),
+                })?;
+        }
+        Ok(())
+    }
 
-        let params = self.entity_to_params(entity)?;
+    /// Insert an entity, merging with the stored one as `merge` says
+    ///
+    /// The stored `created_at` and `metadata.additional` are read from the
+    /// entity's row, so an upsert is one read and one write (plus clearing
+    /// the access counters when they restart).
+    pub async fn upsert_entity(&self, entity: &CodeEntity, merge: MergePolicy) -> Result<()> {
+        let map_err = |e: cozo::Error| ParseltongError::DatabaseError {
+            operation: "upsert_entity".to_string(),
+            details: format!("Failed to upsert {}: {}", entity.isgl1_key, e),
+        };
 
-        self.db
-            .run_script(query, params, ScriptMutability::Mutable)
-            .map_err(|e| ParseltongError::DatabaseError {
-                operation: "insert_entity".to_string(),
-                details: format!("Failed to insert entity: {}", e),
-            })?;
+        let mut merged = entity.clone();
+        let query = format!(
+            "?[created_at, additional_metadata] := *{}{{ ISGL1_key: $key, created_at, additional_metadata }}",
+            self.relation
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(entity.isgl1_key.as_str().into()));
+        let stored = self.run_script(&query, params, ScriptMutability::Immutable).map_err(map_err)?;
+        if let Some(row) = stored.rows.first() {
+            if let (true, DataValue::Str(created_at)) = (merge.preserve_created_at, &row[0]) {
+                merged.metadata.created_at = chrono::DateTime::parse_from_rfc3339(created_at)
+                    .map_err(|e| ParseltongError::DatabaseError {
+                        operation: "upsert_entity".to_string(),
+                        details: format!("Invalid created_at '{}' for {}: {}", created_at, entity.isgl1_key, e),
+                    })?
+                    .with_timezone(&chrono::Utc);
+            }
+            if let (true, DataValue::Str(additional)) = (merge.keep_additional, &row[1]) {
+                let additional: HashMap<String, String> =
+                    serde_json::from_str(additional).map_err(|e| ParseltongError::SerializationError {
+                        details: format!("Failed to deserialize additional_metadata: {}", e),
+                    })?;
+                for (key, value) in additional {
+                    merged.metadata.additional.entry(key).or_insert(value);
+                }
+            }
+        }
 
+        self.insert_entity(&merged).await?;
+
+        if !merge.accumulate_query_count && self.list_relations().await?.iter().any(|r| r == ACCESS_LOG_RELATION) {
+            let reset = format!(
+                "?[ISGL1_key] <- [[$key]]
+                 :rm {} {{ ISGL1_key }}",
+                ACCESS_LOG_RELATION
+            );
+            let mut params = BTreeMap::new();
+            params.insert("key".to_string(), DataValue::Str(entity.isgl1_key.as_str().into()));
+            self.run_script(&reset, params, ScriptMutability::Mutable).map_err(map_err)?;
+        }
         Ok(())
     }
 
     /// Get entity by ISGL1 key
     pub async fn get_entity(&self, isgl1_key: &str) -> Result<CodeEntity> {
-        let query = r#"
-            ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-              lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-              last_modified, entity_type, entity_class] :=
-            *CodeGraph{
-                ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-                lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-                last_modified, entity_type, entity_class
-            },
-            ISGL1_key == $key
-        "#;
+        let query = self.entity_query("deleted_at: null", ", ISGL1_key == $key");
 
         let mut params = BTreeMap::new();
         params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
 
-        let result = self.db.run_script(query, params, ScriptMutability::Immutable).map_err(|e| {
+        let result = self.run_script(&query, params, ScriptMutability::Immutable).map_err(|e| {
             ParseltongError::DatabaseError {
                 operation: "get_entity".to_string(),
                 details: format!("Failed to get entity: {}", e),
@@ -830,27 +2123,132 @@ impl CozoDbStorage {
             });
         }
 
-        self.row_to_entity(&result.rows[0])
+        let entity = self.rows_to_entities(&result.rows[..1]).await?.remove(0);
+        if self.access_logging {
+            self.record_access(&[isgl1_key]).await?;
+        }
+        Ok(entity)
     }
 
     /// Update entity in database (internal method)
+    ///
+    /// `entity.metadata.content_hash` is the expected-version token: entities
+    /// read through this client carry the stored version, and the write fails
+    /// with `ConflictError` if someone else updated the row in between. An
+    /// empty hash (entity built by hand) skips the check.
     pub async fn update_entity_internal(&self, entity: &CodeEntity) -> Result<()> {
-        // Update is same as insert with :put which replaces existing
+        let expected = entity.metadata.content_hash.as_str();
+        if expected.is_empty() {
+            // Update is same as insert with :put which replaces existing
+            return self.insert_entity(entity).await;
+        }
+        self.update_entity_if_version(entity, expected).await
+    }
+
+    /// Update entity only if its stored version still equals `expected_hash`
+    ///
+    /// Compares against `CodeEntity::version_hash` of the stored row; a
+    /// missing row yields `EntityNotFound`.
+    pub async fn update_entity_if_version(
+        &self,
+        entity: &CodeEntity,
+        expected_hash: &str,
+    ) -> Result<()> {
+        let _guard = self.update_lock.lock().await;
+
+        let stored = self.get_entity(&entity.isgl1_key).await?;
+        let actual = stored.version_hash();
+        if actual != expected_hash {
+            return Err(ParseltongError::ConflictError {
+                isgl1_key: entity.isgl1_key.clone(),
+                expected: expected_hash.to_string(),
+                actual,
+            });
+        }
+
         self.insert_entity(entity).await
     }
 
-    /// Delete entity from database
+    /// Apply `action` with `future_code` by updating only the temporal columns
+    ///
+    /// Unlike `update_entity_internal` this does not read the entity first,
+    /// so there is no version check; use it when the caller holds no expected
+    /// version. Delete stores no future code, as in
+    /// `CodeEntity::apply_temporal_change`. A missing row falls back to the
+    /// full read-modify-write path, which reports `EntityNotFound`.
+    pub async fn set_future_code(
+        &self,
+        isgl1_key: &str,
+        future_code: &str,
+        action: TemporalAction,
+    ) -> Result<()> {
+        let state = match action {
+            TemporalAction::Create => TemporalState::create(),
+            TemporalAction::Edit => TemporalState::edit(),
+            TemporalAction::Delete => TemporalState::delete(),
+        };
+        let stored_code = match action {
+            TemporalAction::Delete => DataValue::Null,
+            _ => DataValue::Str(future_code.into()),
+        };
+
+        let query = format!(
+            r#"
+            ?[ISGL1_key, Future_Code, current_ind, future_ind, Future_Action] <-
+            [[$key, $Future_Code, $current_ind, $future_ind, $Future_Action]]
+
+            :update {relation} {{ ISGL1_key => Future_Code, current_ind, future_ind, Future_Action }}
+        "#,
+            relation = self.relation
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+        params.insert("Future_Code".to_string(), stored_code);
+        params.insert("current_ind".to_string(), DataValue::Bool(state.current_ind));
+        params.insert("future_ind".to_string(), DataValue::Bool(state.future_ind));
+        params.insert("Future_Action".to_string(), DataValue::Str(action_label(&action).into()));
+
+        let _guard = self.update_lock.lock().await;
+        match self.run_script(&query, params, ScriptMutability::Mutable) {
+            Ok(_) => Ok(()),
+            // cozo rejects `:update` of an absent key with this notice
+            Err(e) if e.chain().any(|cause| cause.to_string().contains("key to update does not exist")) => {
+                let mut entity = self.get_entity(isgl1_key).await?;
+                entity.apply_temporal_change(action, Some(future_code.to_string()))?;
+                self.insert_entity(&entity).await
+            }
+            Err(e) => Err(ParseltongError::DatabaseError {
+                operation: "set_future_code".to_string(),
+                details: format!("Failed to update {}: {}", isgl1_key, e),
+            }),
+        }
+    }
+
+    /// Soft-delete an entity: stamp `deleted_at` instead of removing the row
+    ///
+    /// The entity disappears from every read except
+    /// `get_all_entities_including_deleted`, and stays recoverable (by
+    /// inserting it again) until `purge_expired` reclaims it. Deleting a
+    /// missing or already deleted entity is a no-op, so the original
+    /// deletion time is kept.
     pub async fn delete_entity(&self, isgl1_key: &str) -> Result<()> {
-        let query = r#"
-            ?[ISGL1_key] <- [[$key]]
-            :rm CodeGraph { ISGL1_key }
-        "#;
+        let query = format!(
+            r#"
+            ?[ISGL1_key, deleted_at] :=
+                *{relation}{{ ISGL1_key, deleted_at: null }},
+                ISGL1_key == $key,
+                deleted_at = $now
+            :update {relation} {{ ISGL1_key => deleted_at }}
+        "#,
+            relation = self.relation
+        );
 
         let mut params = BTreeMap::new();
         params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+        params.insert("now".to_string(), DataValue::Str(deletion_stamp(self.clock.now()).into()));
 
-        self.db
-            .run_script(query, params, ScriptMutability::Mutable)
+        self
+            .run_script(&query, params, ScriptMutability::Mutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "delete_entity".to_string(),
                 details: format!("Failed to delete entity: {}", e),
@@ -859,6 +2257,156 @@ impl CozoDbStorage {
         Ok(())
     }
 
+    /// Remove tombstoned entities and compact the storage backend
+    ///
+    /// A tombstone is a row with both `current_ind` and `future_ind` false:
+    /// a delete that has been applied, leaving nothing to diff or export.
+    /// Those rows go, along with their code spans, provenance and access
+    /// counts; then the backend compacts (RocksDB compacts its key range,
+    /// SQLite releases its connections). Pending deletes
+    /// (`current_ind` still true) are kept.
+    ///
+    /// The report gives on-disk sizes for persistent engines only.
+    pub async fn compact(&self) -> Result<CompactionReport> {
+        let bytes_before = self.disk_path.as_deref().map(disk_usage);
+
+        let _guard = self.update_lock.lock().await;
+        let tombstones = self
+            .run_script(
+                &format!(
+                    "?[ISGL1_key] := *{}{{ ISGL1_key, current_ind: false, future_ind: false }}",
+                    self.relation
+                ),
+                Default::default(),
+                ScriptMutability::Immutable,
+            )
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "compact".to_string(),
+                details: format!("Failed to find tombstoned entities: {}", e),
+            })?;
+
+        self.remove_entity_rows(&tombstones.rows, "compact").await?;
+
+        self.run_script("::compact", Default::default(), ScriptMutability::Mutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "compact".to_string(),
+                details: format!("Backend compaction failed: {}", e),
+            })?;
+
+        Ok(CompactionReport {
+            tombstones_removed: tombstones.rows.len(),
+            bytes_before,
+            bytes_after: self.disk_path.as_deref().map(disk_usage),
+        })
+    }
+
+    /// Permanently remove entities soft-deleted at least `retention` ago
+    ///
+    /// Their code spans, provenance and access counts go too, as in
+    /// `compact`. Returns the number of entities purged; entities deleted
+    /// more recently stay recoverable.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use parseltongue_core::storage::CozoDbStorage;
+    /// use std::time::Duration;
+    ///
+    /// let db = CozoDbStorage::new("mem").await.unwrap();
+    /// db.ensure_schema().await.unwrap();
+    /// assert_eq!(db.purge_expired(Duration::from_secs(7 * 24 * 3600)).await.unwrap(), 0);
+    /// # });
+    /// ```
+    pub async fn purge_expired(&self, retention: std::time::Duration) -> Result<usize> {
+        let cutoff = chrono::Duration::from_std(retention)
+            .ok()
+            .and_then(|retention| self.clock.now().checked_sub_signed(retention));
+        let Some(cutoff) = cutoff else {
+            // Retention reaches before any representable deletion time
+            return Ok(0);
+        };
+
+        let _guard = self.update_lock.lock().await;
+        let query = format!(
+            "?[ISGL1_key] := *{}{{ ISGL1_key, deleted_at }}, !is_null(deleted_at), deleted_at <= $cutoff",
+            self.relation
+        );
+        let mut params = BTreeMap::new();
+        params.insert("cutoff".to_string(), DataValue::Str(deletion_stamp(cutoff).into()));
+        let expired = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "purge_expired".to_string(),
+                details: format!("Failed to find expired entities: {}", e),
+            })?;
+
+        self.remove_entity_rows(&expired.rows, "purge_expired").await?;
+        Ok(expired.rows.len())
+    }
+
+    /// When `isgl1_key` was soft-deleted, `None` if it is live
+    ///
+    /// A key that was never stored (or has been purged) is `EntityNotFound`.
+    pub async fn deleted_at(&self, isgl1_key: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let query = format!(
+            "?[deleted_at] := *{}{{ ISGL1_key, deleted_at }}, ISGL1_key == $key",
+            self.relation
+        );
+        let mut params = BTreeMap::new();
+        params.insert("key".to_string(), DataValue::Str(isgl1_key.into()));
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "deleted_at".to_string(),
+                details: format!("Failed to read deletion time of {}: {}", isgl1_key, e),
+            })?;
+
+        match result.rows.first().and_then(|row| row.first()) {
+            None => Err(ParseltongError::EntityNotFound {
+                isgl1_key: isgl1_key.to_string(),
+            }),
+            Some(DataValue::Str(stamp)) => chrono::DateTime::parse_from_rfc3339(stamp)
+                .map(|t| Some(t.with_timezone(&chrono::Utc)))
+                .map_err(|e| ParseltongError::DatabaseError {
+                    operation: "deleted_at".to_string(),
+                    details: format!("Invalid deleted_at '{}' for {}: {}", stamp, isgl1_key, e),
+                }),
+            Some(_) => Ok(None),
+        }
+    }
+
+    /// Remove the entity rows keyed by the first column of `rows`
+    ///
+    /// Clears the keys from the entity relation and from the per-entity
+    /// side relations that exist. The caller holds `update_lock`.
+    async fn remove_entity_rows(&self, rows: &[Vec<DataValue>], operation: &str) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let relations = self.list_relations().await?;
+        let mut params = BTreeMap::new();
+        params.insert(
+            "keys".to_string(),
+            DataValue::List(rows.iter().map(|row| row[0].clone()).collect()),
+        );
+        for relation in [self.relation.as_str(), CODE_SPANS_RELATION, PROVENANCE_RELATION, ACCESS_LOG_RELATION, GENERATED_RELATION] {
+            if !relations.iter().any(|r| r == relation) {
+                continue;
+            }
+            let script = format!(
+                "?[ISGL1_key] := ISGL1_key in $keys, *{rel}{{ ISGL1_key }}
+                 :rm {rel} {{ ISGL1_key }}",
+                rel = relation
+            );
+            self.run_script(&script, params.clone(), ScriptMutability::Mutable)
+                .map_err(|e| ParseltongError::DatabaseError {
+                    operation: operation.to_string(),
+                    details: format!("Failed to remove entities from {}: {}", relation, e),
+                })?;
+        }
+        Ok(())
+    }
+
     /// Update temporal state of entity
     pub async fn update_temporal_state(
         &self,
@@ -882,66 +2430,252 @@ impl CozoDbStorage {
 
     /// Get entities with pending changes
     pub async fn get_changed_entities(&self) -> Result<Vec<CodeEntity>> {
-        let query = r#"
-            ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-              lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-              last_modified, entity_type, entity_class] :=
-            *CodeGraph{
-                ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-                lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-                last_modified, entity_type, entity_class
-            },
-            Future_Action != null
-        "#;
+        let query = self.entity_query("deleted_at: null", ", Future_Action != null");
 
         let result = self
-            .db
-            .run_script(query, Default::default(), ScriptMutability::Immutable)
+            .run_script(&query, Default::default(), ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "get_changed_entities".to_string(),
                 details: format!("Failed to query changed entities: {}", e),
             })?;
 
-        let mut entities = Vec::new();
-        for row in result.rows {
-            entities.push(self.row_to_entity(&row)?);
-        }
-
-        Ok(entities)
+        self.rows_to_entities(&result.rows).await
     }
 
     /// Get all entities from database
     ///
     /// Returns all entities in the CodeGraph table, regardless of temporal state.
+    /// Soft-deleted entities are left out (see `get_all_entities_including_deleted`).
     /// Useful for testing and diagnostic purposes.
     pub async fn get_all_entities(&self) -> Result<Vec<CodeEntity>> {
-        let query = r#"
-            ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-              lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-              last_modified, entity_type, entity_class] :=
-            *CodeGraph{
-                ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
-                lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
-                last_modified, entity_type, entity_class
-            }
-        "#;
+        self.all_entities("deleted_at: null").await
+    }
+
+    /// `get_all_entities` plus the soft-deleted entities not yet purged
+    pub async fn get_all_entities_including_deleted(&self) -> Result<Vec<CodeEntity>> {
+        self.all_entities("deleted_at").await
+    }
+
+    /// Every entity whose row matches the `deleted_at` binding in `deleted`
+    async fn all_entities(&self, deleted: &str) -> Result<Vec<CodeEntity>> {
+        let query = self.entity_query(deleted, "");
 
         let result = self
-            .db
-            .run_script(query, Default::default(), ScriptMutability::Immutable)
+            .run_script(&query, Default::default(), ScriptMutability::Immutable)
             .map_err(|e| ParseltongError::DatabaseError {
                 operation: "get_all_entities".to_string(),
                 details: format!("Failed to query all entities: {}", e),
             })?;
 
-        let mut entities = Vec::new();
-        for row in result.rows {
-            entities.push(self.row_to_entity(&row)?);
+        self.rows_to_entities(&result.rows).await
+    }
+
+    /// Stream every entity in ISGL1 key order without loading them all
+    ///
+    /// Yields the same entities in the same order as `get_all_entities`,
+    /// reading `DEFAULT_STREAM_PAGE_SIZE` at a time.
+    pub fn entities_stream(&self) -> impl Stream<Item = Result<CodeEntity>> + '_ {
+        self.entities_stream_paged(DEFAULT_STREAM_PAGE_SIZE)
+    }
+
+    /// `entities_stream` reading `page_size` entities per query
+    pub fn entities_stream_paged(&self, page_size: usize) -> impl Stream<Item = Result<CodeEntity>> + '_ {
+        self.paged_entities(None, page_size)
+    }
+
+    /// One page of entities in ISGL1 key order, for cursor pagination
+    ///
+    /// Returns up to `limit` entities with keys after `after` (from the
+    /// start when `None`) and the cursor for the next page: the last key
+    /// returned, or `None` once a short page shows nothing is left. The
+    /// cursor is a key bound, not an offset, so entities inserted or
+    /// deleted between requests neither shift nor repeat later pages.
+    pub async fn get_entities_page(&self, after: Option<&str>, limit: usize) -> Result<(Vec<CodeEntity>, Option<String>)> {
+        let limit = limit.max(1);
+        let page = self.entity_page(None, after, limit).await?;
+        let next = match page.last() {
+            Some(last) if page.len() == limit => Some(last.isgl1_key.clone()),
+            _ => None,
+        };
+        Ok((page, next))
+    }
+
+    /// Stream the entities with a `Future_Action`, as `get_changed_entities` returns them
+    pub fn changed_entities_stream(&self) -> impl Stream<Item = Result<CodeEntity>> + '_ {
+        self.paged_entities(Some("Future_Action != null"), DEFAULT_STREAM_PAGE_SIZE)
+    }
+
+    /// Page through the entities matching `condition` by key range
+    ///
+    /// Each page starts after the last key of the one before, so pages
+    /// stay consistent even if entities are written between queries.
+    fn paged_entities(
+        &self,
+        condition: Option<&'static str>,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<CodeEntity>> + '_ {
+        let page_size = page_size.max(1);
+        stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| async move {
+            let Some(after) = cursor else {
+                return Ok(None);
+            };
+            let page = self.entity_page(condition, after.as_deref(), page_size).await?;
+            let next = match page.last() {
+                Some(last) if page.len() == page_size => Some(Some(last.isgl1_key.clone())),
+                _ => None,
+            };
+            if page.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+
+    /// Up to `limit` entities matching `condition` with keys after `after`
+    async fn entity_page(&self, condition: Option<&str>, after: Option<&str>, limit: usize) -> Result<Vec<CodeEntity>> {
+        let rows = self.entity_page_rows(condition, after, limit)?;
+        self.rows_to_entities(&rows).await
+    }
+
+    /// Rows of `entity_page`, before conversion
+    fn entity_page_rows(&self, condition: Option<&str>, after: Option<&str>, limit: usize) -> Result<Vec<Vec<DataValue>>> {
+        let mut conditions = String::new();
+        if let Some(condition) = condition {
+            conditions.push_str(&format!(", {}", condition));
+        }
+        let mut params = BTreeMap::new();
+        if let Some(after) = after {
+            conditions.push_str(", ISGL1_key > $after");
+            params.insert("after".to_string(), DataValue::Str(after.into()));
         }
+        let query = self.entity_query(
+            "deleted_at: null",
+            &format!("{}\n:order ISGL1_key\n:limit {}", conditions, limit),
+        );
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "entities_stream".to_string(),
+                details: format!("Failed to read entity page: {}", e),
+            })?;
+        Ok(result.rows)
+    }
 
+    /// Get all entities visible outside their crate, package or module
+    ///
+    /// Filters on `InterfaceSignature::effective_visibility() == Public`;
+    /// visibility lives inside the stored signature, so the filter runs
+    /// after loading rather than in Datalog.
+    pub async fn get_public_entities(&self) -> Result<Vec<CodeEntity>> {
+        let mut entities = self.get_all_entities().await?;
+        entities.retain(|e| e.interface_signature.effective_visibility() == Visibility::Public);
+        Ok(entities)
+    }
+
+    /// Get all entities whose `file_path` column equals `file_path`
+    ///
+    /// Ordered by start line; an unknown file yields an empty vec.
+    pub async fn get_entities_by_file(&self, file_path: &str) -> Result<Vec<CodeEntity>> {
+        let query = self.entity_query("deleted_at: null", ", file_path == $file_path");
+
+        let mut params = BTreeMap::new();
+        params.insert("file_path".to_string(), DataValue::Str(file_path.into()));
+
+        let result = self
+            .run_script(&query, params, ScriptMutability::Immutable)
+            .map_err(|e| ParseltongError::DatabaseError {
+                operation: "get_entities_by_file".to_string(),
+                details: format!("Failed to query entities for {}: {}", file_path, e),
+            })?;
+
+        let mut entities = self.rows_to_entities(&result.rows).await?;
+        entities.sort_by_key(|e| (e.interface_signature.line_range.start, e.interface_signature.line_range.end));
+
+        if self.access_logging {
+            let keys: Vec<&str> = entities.iter().map(|e| e.isgl1_key.as_str()).collect();
+            self.record_access(&keys).await?;
+        }
         Ok(entities)
     }
 
+    /// Copy the entities matching `condition` and their edges into `target`
+    ///
+    /// `condition` is Datalog over the entity columns (as in pt02's
+    /// `--where-clause`); `None` copies everything. Soft-deleted entities
+    /// are not copied. Entities go in with `insert_entities_batch`, code
+    /// spans along with them. Edges between copied entities are always
+    /// copied; an edge with only one copied endpoint is dropped unless
+    /// `keep_dangling` is set. `target` needs its schema (`ensure_schema`).
+    ///
+    /// # Example
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use parseltongue_core::storage::CozoDbStorage;
+    ///
+    /// let source = CozoDbStorage::new("mem").await.unwrap();
+    /// source.ensure_schema().await.unwrap();
+    /// let target = CozoDbStorage::new("mem").await.unwrap();
+    /// target.ensure_schema().await.unwrap();
+    ///
+    /// let report = source
+    ///     .copy_entities_to(&target, Some("file_path == 'src/lib.rs'"), false)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(report.entities_copied, 0);
+    /// # });
+    /// ```
+    pub async fn copy_entities_to(
+        &self,
+        target: &CozoDbStorage,
+        condition: Option<&str>,
+        keep_dangling: bool,
+    ) -> Result<CopyReport> {
+        let mut report = CopyReport::default();
+        let mut copied: HashSet<String> = HashSet::new();
+        let mut after: Option<String> = None;
+        loop {
+            // Copied as stored: spans-only entities stay spans-only
+            let page = self
+                .entity_page_rows(condition, after.as_deref(), DEFAULT_STREAM_PAGE_SIZE)?
+                .iter()
+                .map(|row| self.row_to_entity(row))
+                .collect::<Result<Vec<_>>>()?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some(last.isgl1_key.clone());
+            target.insert_entities_batch(&page).await?;
+            let keys: Vec<&str> = page.iter().map(|e| e.isgl1_key.as_str()).collect();
+            for (key, span) in self.get_code_spans(&keys).await? {
+                target.insert_code_span(&key, &span).await?;
+            }
+            copied.extend(keys.into_iter().map(str::to_string));
+            report.entities_copied += page.len();
+            if page.len() < DEFAULT_STREAM_PAGE_SIZE {
+                break;
+            }
+        }
+
+        let mut edges = Vec::new();
+        for edge in self.get_all_dependencies().await? {
+            let from = copied.contains(edge.from_key.as_str());
+            let to = copied.contains(edge.to_key.as_str());
+            if (from && to) || (keep_dangling && (from || to)) {
+                edges.push(edge);
+            } else if from || to {
+                report.dangling_edges_dropped += 1;
+            }
+        }
+        if !edges.is_empty() {
+            target.insert_edges_batch(&edges).await?;
+        }
+        report.edges_copied = edges.len();
+
+        Ok(report)
+    }
+
     // Helper methods for data conversion
 
     /// Convert CodeEntity to CozoDB parameters
@@ -1019,16 +2753,7 @@ impl CozoDbStorage {
                 .temporal_state
                 .future_action
                 .as_ref()
-                .map(|action| {
-                    DataValue::Str(
-                        match action {
-                            TemporalAction::Create => "Create",
-                            TemporalAction::Edit => "Edit",
-                            TemporalAction::Delete => "Delete",
-                        }
-                        .into(),
-                    )
-                })
+                .map(|action| DataValue::Str(action_label(action).into()))
                 .unwrap_or(DataValue::Null),
         );
 
@@ -1063,27 +2788,31 @@ impl CozoDbStorage {
             DataValue::Str(entity.metadata.modified_at.to_rfc3339().into()),
         );
 
+        params.insert(
+            "created_at".to_string(),
+            DataValue::Str(entity.metadata.created_at.to_rfc3339().into()),
+        );
+
+        params.insert(
+            "complexity_score".to_string(),
+            match entity.metadata.complexity_score {
+                Some(score) => DataValue::from(score as i64),
+                None => DataValue::Null,
+            },
+        );
+
+        params.insert(
+            "semantic_hash".to_string(),
+            if entity.metadata.semantic_hash.is_empty() {
+                DataValue::Null
+            } else {
+                DataValue::Str(entity.metadata.semantic_hash.as_str().into())
+            },
+        );
+
         params.insert(
             "entity_type".to_string(),
-            DataValue::Str(
-                match &entity.interface_signature.entity_type {
-                    EntityType::Function => "function",
-                    EntityType::Method => "method",
-                    EntityType::Struct => "struct",
-                    EntityType::Enum => "enum",
-                    EntityType::Trait => "trait",
-                    EntityType::Interface => "interface",
-                    EntityType::Module => "module",
-                    EntityType::ImplBlock { .. } => "impl",
-                    EntityType::Macro => "macro",
-                    EntityType::ProcMacro => "proc_macro",
-                    EntityType::TestFunction => "test",
-                    EntityType::Class => "class",
-                    EntityType::Variable => "variable",
-                    EntityType::Constant => "constant",
-                }
-                .into(),
-            ),
+            DataValue::Str(entity.interface_signature.entity_type.column_name().into()),
         );
 
         // v0.9.3 FIX: Use actual entity_class from entity (was hardcoded to "CODE")
@@ -1098,15 +2827,28 @@ impl CozoDbStorage {
             ),
         );
 
+        params.insert(
+            "additional_metadata".to_string(),
+            if entity.metadata.additional.is_empty() {
+                DataValue::Null
+            } else {
+                let additional_json = serde_json::to_string(&entity.metadata.additional)
+                    .map_err(|e| ParseltongError::SerializationError {
+                        details: format!("Failed to serialize additional_metadata: {}", e),
+                    })?;
+                DataValue::Str(additional_json.into())
+            },
+        );
+
         Ok(params)
     }
 
     /// Convert CozoDB row to CodeEntity
     fn row_to_entity(&self, row: &[DataValue]) -> Result<CodeEntity> {
-        if row.len() < 14 {
+        if row.len() < ENTITY_COLUMNS.len() {
             return Err(ParseltongError::DatabaseError {
                 operation: "row_to_entity".to_string(),
-                details: format!("Invalid row length: expected 14, got {}", row.len()),
+                details: format!("Invalid row length: expected {}, got {}", ENTITY_COLUMNS.len(), row.len()),
             });
         }
 
@@ -1176,12 +2918,9 @@ impl CozoDbStorage {
         };
 
         // Deserialize lsp_meta_data
+        // (legacy opaque strings are read as hover text)
         let lsp_metadata: Option<LspMetadata> = match &row[5] {
-            DataValue::Str(s) => Some(serde_json::from_str(s).map_err(|e| {
-                ParseltongError::SerializationError {
-                    details: format!("Failed to deserialize lsp_meta_data: {}", e),
-                }
-            })?),
+            DataValue::Str(s) => Some(LspMetadata::from_stored(s)),
             DataValue::Null => None,
             _ => {
                 return Err(ParseltongError::DatabaseError {
@@ -1239,6 +2978,15 @@ impl CozoDbStorage {
             future_action,
         };
 
+        let additional: HashMap<String, String> = match &row[14] {
+            DataValue::Str(s) => serde_json::from_str(s).map_err(|e| {
+                ParseltongError::SerializationError {
+                    details: format!("Failed to deserialize additional_metadata: {}", e),
+                }
+            })?,
+            _ => HashMap::new(),
+        };
+
         // Extract entity_class (v0.9.0) - currently ignored in CodeEntity
         let _entity_class = match &row[13] {
             DataValue::Str(s) => s.to_string(),
@@ -1266,11 +3014,109 @@ impl CozoDbStorage {
         entity.temporal_state = temporal_state;
         entity.tdd_classification = tdd_classification;
         entity.lsp_metadata = lsp_metadata;
+        entity.metadata.additional = additional;
+        entity.metadata.complexity_score = row[16].get_int().and_then(|score| u32::try_from(score).ok());
+        entity.metadata.semantic_hash = row[17].get_str().unwrap_or_default().to_string();
+        entity.metadata.content_hash = entity.version_hash();
+
+        let timestamp = |value: &DataValue| match value {
+            DataValue::Str(s) => chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|t| t.with_timezone(&chrono::Utc)),
+            _ => None,
+        };
+        // Timestamps come from the row only, never from the clock at read
+        // time; an unreadable one is the epoch
+        let modified_at = timestamp(&row[11]).unwrap_or(chrono::DateTime::UNIX_EPOCH);
+        entity.metadata.modified_at = modified_at;
+        // Rows written without created_at were created no later than this
+        entity.metadata.created_at = timestamp(&row[15]).unwrap_or(modified_at);
 
         Ok(entity)
     }
 }
 
+/// Treat "relation already exists" from a `:create` as success
+///
+/// Another process may create the relation between our existence check and
+/// our `:create`; either way the relation is there afterwards.
+fn ignore_already_exists(result: Result<()>) -> Result<()> {
+    match result {
+        Err(e) if e.to_string().contains("already exists") || e.to_string().contains("conflicts with an existing") => Ok(()),
+        other => other,
+    }
+}
+
+/// Total size of the files under `path` (or of `path` itself), 0 if missing
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Stored `deleted_at` value: fixed-width UTC, so string order is time order
+fn deletion_stamp(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+/// Stored `Future_Action` value of an action
+fn action_label(action: &TemporalAction) -> &'static str {
+    match action {
+        TemporalAction::Create => "Create",
+        TemporalAction::Edit => "Edit",
+        TemporalAction::Delete => "Delete",
+    }
+}
+
+/// Convert `[from_key, to_key, edge_type, source_location]` rows to edges
+///
+/// Rows with an unknown edge type are skipped.
+fn rows_to_dependency_edges(rows: Vec<Vec<DataValue>>, operation: &str) -> Result<Vec<DependencyEdge>> {
+    // Parse results into DependencyEdge structs
+    let mut dependencies = Vec::new();
+    for row in rows {
+        if row.len() >= 3 {
+            if let (Some(DataValue::Str(from_key)), Some(DataValue::Str(to_key)), Some(DataValue::Str(edge_type_str))) =
+                (row.get(0), row.get(1), row.get(2))
+            {
+                let edge_type = match edge_type_str.parse::<EdgeType>() {
+                    Ok(edge_type) => edge_type,
+                    Err(_) => continue, // Skip unknown edge types
+                };
+
+                let source_location = row.get(3).and_then(|v| {
+                    if let DataValue::Str(loc) = v {
+                        Some(loc.to_string())
+                    } else {
+                        None
+                    }
+                });
+
+                let edge = DependencyEdge::builder()
+                    .from_key(from_key.to_string())
+                    .to_key(to_key.to_string())
+                    .edge_type(edge_type)
+                    .source_location(source_location.unwrap_or_default())
+                    .build()
+                    .map_err(|e| ParseltongError::DependencyError {
+                        operation: operation.to_string(),
+                        reason: format!("Failed to build DependencyEdge: {}", e),
+                    })?;
+
+                dependencies.push(edge);
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
+
 // Implement CodeGraphRepository trait
 #[async_trait]
 impl CodeGraphRepository for CozoDbStorage {
@@ -0,0 +1,320 @@
+//! Ordered entity relation schema migrations.
+//!
+//! Every entity relation (`CodeGraph`, or the one named in
+//! `StorageOptions::relation_name`) has its own applied version, one row per
+//! relation in `SchemaVersion`. `CozoDbStorage::ensure_schema` creates fresh
+//! relations at `CURRENT_SCHEMA_VERSION`; older ones are upgraded by running
+//! every migration above their recorded version, in order.
+//!
+//! Relations created before version tracking have no row; their version is
+//! inferred from their columns. Databases from before per-relation tracking
+//! keep a single `id = 0` row, which belongs to `CodeGraph`.
+
+/// Relation recording the applied schema version of each entity relation
+pub const SCHEMA_VERSION_RELATION: &str = "SchemaVersion";
+
+/// Stands for the entity relation name in migration scripts
+pub const RELATION_PLACEHOLDER: &str = "{relation}";
+
+/// Version a freshly created database starts at
+pub const CURRENT_SCHEMA_VERSION: i64 = 8;
+
+/// One schema change, upgrading `version - 1` to `version`
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub step: MigrationStep,
+}
+
+/// How a migration changes the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStep {
+    /// Mutable Datalog script, with [`RELATION_PLACEHOLDER`] in place of the
+    /// relation name
+    Script(&'static str),
+    /// Rewrite `lsp_meta_data` stored before it was structured
+    /// (`CozoDbStorage::upgrade_legacy_lsp_metadata`)
+    UpgradeLegacyLspMetadata,
+    /// Create the `GeneratedEntities` side relation
+    /// (`CozoDbStorage::create_generated_schema`)
+    ///
+    /// Side relations are shared by every entity relation, so a relation
+    /// migrated after another finds it already there. Fresh entity
+    /// relations, which skip migrations, get it from `ensure_schema`.
+    CreateGeneratedEntities,
+}
+
+impl Migration {
+    /// The script of a `Script` step, applied to `relation`
+    pub fn script_for(&self, relation: &str) -> Option<String> {
+        match self.step {
+            MigrationStep::Script(script) => Some(script.replace(RELATION_PLACEHOLDER, relation)),
+            MigrationStep::UpgradeLegacyLspMetadata | MigrationStep::CreateGeneratedEntities => None,
+        }
+    }
+}
+
+/// All migrations, ascending by version
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "add entity_class column (v0.9.0)",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type
+        },
+        entity_class = "CODE"
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String
+        }
+    "#),
+    },
+    Migration {
+        version: 2,
+        description: "add deleted_at column for soft deletes",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class, deleted_at] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type, entity_class
+        },
+        deleted_at = null
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String,
+            deleted_at: String? default null
+        }
+    "#),
+    },
+    Migration {
+        version: 3,
+        description: "structure lsp_meta_data stored as raw hover responses or text",
+        step: MigrationStep::UpgradeLegacyLspMetadata,
+    },
+    Migration {
+        version: 4,
+        description: "create GeneratedEntities for generated and vendored files",
+        step: MigrationStep::CreateGeneratedEntities,
+    },
+    Migration {
+        version: 5,
+        description: "add additional_metadata column keeping metadata.additional",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class, deleted_at, additional_metadata] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type, entity_class, deleted_at
+        },
+        additional_metadata = null
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String,
+            deleted_at: String? default null,
+            additional_metadata: String? default null
+        }
+    "#),
+    },
+    Migration {
+        version: 6,
+        description: "add created_at column, backfilled from last_modified",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class, deleted_at, additional_metadata, created_at] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type, entity_class, deleted_at, additional_metadata
+        },
+        created_at = last_modified
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String,
+            deleted_at: String? default null,
+            additional_metadata: String? default null,
+            created_at: String? default null
+        }
+    "#),
+    },
+    Migration {
+        version: 7,
+        description: "add complexity_score column (cyclomatic complexity)",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class, deleted_at, additional_metadata, created_at,
+          complexity_score] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type, entity_class, deleted_at, additional_metadata, created_at
+        },
+        complexity_score = null
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String,
+            deleted_at: String? default null,
+            additional_metadata: String? default null,
+            created_at: String? default null,
+            complexity_score: Int? default null
+        }
+    "#),
+    },
+    Migration {
+        version: 8,
+        description: "add semantic_hash column stored alongside the content",
+        step: MigrationStep::Script(r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type, entity_class, deleted_at, additional_metadata, created_at,
+          complexity_score, semantic_hash] :=
+        *{relation}{
+            ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type, entity_class, deleted_at, additional_metadata, created_at,
+            complexity_score
+        },
+        semantic_hash = null
+
+        :replace {relation} {
+            ISGL1_key: String =>
+            Current_Code: String?,
+            Future_Code: String?,
+            interface_signature: String,
+            TDD_Classification: String,
+            lsp_meta_data: String?,
+            current_ind: Bool,
+            future_ind: Bool,
+            Future_Action: String?,
+            file_path: String,
+            language: String,
+            last_modified: String,
+            entity_type: String,
+            entity_class: String,
+            deleted_at: String? default null,
+            additional_metadata: String? default null,
+            created_at: String? default null,
+            complexity_score: Int? default null,
+            semantic_hash: String? default null
+        }
+    "#),
+    },
+];
+
+/// Steps creating side relations, which fresh databases need as well
+pub fn side_relation_steps() -> impl Iterator<Item = MigrationStep> {
+    MIGRATIONS
+        .iter()
+        .map(|m| m.step)
+        .filter(|step| matches!(step, MigrationStep::CreateGeneratedEntities))
+}
+
+/// Migrations still to apply on a database at `version`
+pub fn pending_migrations(version: i64) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_sequential_and_end_at_current() {
+        for (idx, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, idx as i64 + 1, "{}", migration.description);
+        }
+        assert_eq!(MIGRATIONS.last().map(|m| m.version), Some(CURRENT_SCHEMA_VERSION));
+        assert_eq!(pending_migrations(CURRENT_SCHEMA_VERSION).count(), 0);
+        assert_eq!(pending_migrations(0).count(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_scripts_target_the_given_relation() {
+        for migration in MIGRATIONS {
+            let Some(script) = migration.script_for("Snapshot") else {
+                continue;
+            };
+            assert!(!script.contains("CodeGraph"), "{}", migration.description);
+            assert!(!script.contains(RELATION_PLACEHOLDER), "{}", migration.description);
+            assert!(script.contains(":replace Snapshot {"), "{}", migration.description);
+        }
+    }
+}
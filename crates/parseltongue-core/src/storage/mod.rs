@@ -4,5 +4,10 @@
 //! implementing the CodeGraphRepository trait for dependency injection.
 
 pub mod cozo_client;
+pub mod migrations;
+pub mod options;
+pub mod query_cache;
 
 pub use cozo_client::CozoDbStorage;
+pub use options::{StorageOptions, DEFAULT_RELATION_NAME};
+pub use query_cache::CacheStats;
@@ -0,0 +1,111 @@
+//! Write-path tuning for `CozoDbStorage::open_with_options`.
+//!
+//! cozo opens RocksDB with the settings in an `options` file inside the
+//! database directory when one exists, so RocksDB tuning is applied by
+//! writing that file before opening. The file stays behind and applies to
+//! every later open of the same directory, including `CozoDbStorage::new`.
+//! The in-memory engine ignores the RocksDB settings; `commit_batch` and
+//! `relation_name` apply to every engine.
+
+/// File in a RocksDB database directory that cozo loads options from
+pub const ROCKSDB_OPTIONS_FILE: &str = "options";
+
+/// Entity relation used unless `StorageOptions.relation_name` says otherwise
+pub const DEFAULT_RELATION_NAME: &str = "CodeGraph";
+
+/// Commit batching, RocksDB write settings and the entity relation name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageOptions {
+    /// Rows written per transaction by the batched inserts
+    pub commit_batch: usize,
+    /// Sync the WAL and table files to disk in the background while writing
+    ///
+    /// With `false` nothing is synced until RocksDB closes the files, so an
+    /// OS crash or power loss can lose or corrupt recent writes. Only use it
+    /// for throwaway analysis databases that can be re-ingested.
+    pub sync_writes: bool,
+    /// Memtable size in MiB: how much is buffered before flushing to disk
+    pub cache_mb: usize,
+    /// Relation holding the entities, e.g. `before` and `after` for two
+    /// graphs in one database
+    pub relation_name: String,
+}
+
+impl Default for StorageOptions {
+    fn default() -> Self {
+        Self {
+            commit_batch: 1000,
+            sync_writes: true,
+            cache_mb: 64,
+            relation_name: DEFAULT_RELATION_NAME.to_string(),
+        }
+    }
+}
+
+impl StorageOptions {
+    /// Contents of the RocksDB `options` file
+    ///
+    /// Besides the tuned settings, repeats the defaults cozo applies when
+    /// no file is present, since a loaded file replaces them.
+    pub fn rocksdb_options_file(&self) -> String {
+        let bytes_per_sync = if self.sync_writes { 1 << 20 } else { 0 };
+        format!(
+            "[Version]\n  \
+             rocksdb_version=8.1.1\n  \
+             options_file_version=1.1\n\
+             \n\
+             [DBOptions]\n  \
+             max_background_jobs=6\n  \
+             bytes_per_sync={bytes_per_sync}\n  \
+             wal_bytes_per_sync={bytes_per_sync}\n  \
+             strict_bytes_per_sync={strict}\n\
+             \n\
+             [CFOptions \"default\"]\n  \
+             compression=kLZ4Compression\n  \
+             bottommost_compression=kZSTD\n  \
+             level_compaction_dynamic_level_bytes=true\n  \
+             compaction_pri=kMinOverlappingRatio\n  \
+             write_buffer_size={write_buffer_size}\n",
+            bytes_per_sync = bytes_per_sync,
+            strict = self.sync_writes,
+            write_buffer_size = self.cache_mb.max(1) << 20,
+        )
+    }
+}
+
+/// Whether `name` can be used as a relation name in a script
+pub fn is_relation_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_file_reflects_sync_and_buffer_size() {
+        let fast = StorageOptions { commit_batch: 5000, sync_writes: false, cache_mb: 256, ..Default::default() };
+        let file = fast.rocksdb_options_file();
+
+        assert!(file.contains("[DBOptions]\n  max_background_jobs=6\n  bytes_per_sync=0\n  wal_bytes_per_sync=0\n"));
+        assert!(file.contains("strict_bytes_per_sync=false\n"));
+        assert!(file.contains("[CFOptions \"default\"]\n"));
+        assert!(file.contains("write_buffer_size=268435456\n"));
+
+        let safe = StorageOptions::default().rocksdb_options_file();
+        assert!(safe.contains("wal_bytes_per_sync=1048576\n"));
+        assert!(safe.contains("strict_bytes_per_sync=true\n"));
+    }
+
+    #[test]
+    fn test_relation_names() {
+        assert!(is_relation_name(DEFAULT_RELATION_NAME));
+        assert!(is_relation_name("graph_v2"));
+        assert!(!is_relation_name(""));
+        assert!(!is_relation_name("2graph"));
+        assert!(!is_relation_name("Code Graph"));
+        assert!(!is_relation_name("x{}"));
+    }
+}
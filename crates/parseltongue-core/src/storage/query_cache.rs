@@ -0,0 +1,138 @@
+//! In-process LRU cache for read-only Datalog results.
+//!
+//! Entries are keyed by (whitespace-normalized query, write revision). Every
+//! mutating script bumps the storage revision, so stale results are never
+//! served: they simply stop matching and age out of the LRU.
+
+use std::collections::{HashMap, VecDeque};
+
+use cozo::NamedRows;
+
+/// Hit/miss counters and occupancy of a [`QueryCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+type CacheKey = (String, u64);
+
+/// Least-recently-used map from (query, revision) to result rows
+#[derive(Debug)]
+pub struct QueryCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, NamedRows>,
+    /// Recency order, least recently used at the front
+    order: VecDeque<CacheKey>,
+    hits: u64,
+    misses: u64,
+}
+
+impl QueryCache {
+    /// Create a cache holding at most `capacity` results (0 caches nothing)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Cached rows for `query` at `revision`, counting a hit or miss
+    pub fn get(&mut self, query: &str, revision: u64) -> Option<NamedRows> {
+        let key = (normalize_query(query), revision);
+        match self.entries.get(&key) {
+            Some(rows) => {
+                let rows = rows.clone();
+                self.touch(&key);
+                self.hits += 1;
+                Some(rows)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store `rows` for `query` at `revision`, evicting the LRU entry if full
+    pub fn insert(&mut self, query: &str, revision: u64, rows: NamedRows) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = (normalize_query(query), revision);
+        if self.entries.insert(key.clone(), rows).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.entries.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(evicted) => {
+                    self.entries.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+}
+
+/// Collapse runs of whitespace so reformatted queries share an entry
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(n: i64) -> NamedRows {
+        NamedRows::new(vec!["n".to_string()], vec![vec![cozo::DataValue::from(n)]])
+    }
+
+    #[test]
+    fn test_whitespace_variants_share_an_entry() {
+        let mut cache = QueryCache::new(4);
+        cache.insert("?[a] := a = 1", 0, rows(1));
+
+        assert!(cache.get("?[a]  :=\n  a = 1", 0).is_some());
+        assert!(cache.get("?[a] := a = 1", 1).is_none(), "new revision misses");
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let mut cache = QueryCache::new(2);
+        cache.insert("q1", 0, rows(1));
+        cache.insert("q2", 0, rows(2));
+        cache.get("q1", 0);
+        cache.insert("q3", 0, rows(3));
+
+        assert!(cache.get("q1", 0).is_some());
+        assert!(cache.get("q2", 0).is_none());
+        assert!(cache.get("q3", 0).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
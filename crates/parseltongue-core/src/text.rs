@@ -0,0 +1,262 @@
+//! Byte offset ↔ line/column mapping for source text.
+//!
+//! Tree-sitter reports byte offsets and byte columns; people and editors
+//! count lines from 1 and columns in characters. `LineIndex` records where
+//! each line starts once, so every conversion is a binary search plus a scan
+//! of a single line.
+//!
+//! Lines are split on `\n` only: a `\r` before it is part of the line, and a
+//! trailing newline starts an empty last line (as in an editor).
+//!
+//! `strip_comments` removes comments found by tree-sitter, so comment
+//! markers inside string literals are left alone.
+
+use tree_sitter::{Node, Parser};
+
+use crate::entities::Language;
+use crate::query_extractor::tree_sitter_language;
+
+/// 1-based line and 1-based column counted in `char`s
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Line-start offsets of one source string
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    /// Byte offset of the first byte of every line; `line_starts[0] == 0`
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    /// Number of lines; empty text has one empty line
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Text of 1-based `line` without its `\n`
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.text.len(), |next| next - 1);
+        Some(&self.text[start..end])
+    }
+
+    /// Line and column of the character starting at byte `offset`
+    ///
+    /// `offset == text.len()` maps to just past the last character. `None`
+    /// when `offset` is beyond the text or inside a multi-byte character.
+    pub fn offset_to_line_col(&self, offset: usize) -> Option<LineCol> {
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+        let line_idx = match self.line_starts.binary_search(&offset) {
+            Ok(idx) => idx,
+            Err(next) => next - 1,
+        };
+        let start = self.line_starts[line_idx];
+        Some(LineCol {
+            line: line_idx + 1,
+            col: self.text[start..offset].chars().count() + 1,
+        })
+    }
+
+    /// Byte offset of 1-based `line` and character `col`
+    ///
+    /// `col` may be one past the last character (the end of the line).
+    /// `None` for a line or column outside the text.
+    pub fn line_col_to_offset(&self, line: usize, col: usize) -> Option<usize> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let line_text = self.line_text(line)?;
+        let chars_before = col.checked_sub(1)?;
+        line_text
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(line_text.len()))
+            .nth(chars_before)
+            .map(|idx| start + idx)
+    }
+}
+
+/// `code` with its comments removed
+///
+/// Comments are the tree-sitter nodes whose kind contains `comment`. A
+/// comment alone on its lines is removed with those lines; a trailing
+/// comment takes the whitespace before it along. Code in a language
+/// without a bundled grammar is returned unchanged.
+pub fn strip_comments(code: &str, language: Language) -> String {
+    strip(code, language, false)
+}
+
+/// Same as `strip_comments`, keeping doc comments (`///`, `//!`, `/** */`,
+/// `/*! */`)
+pub fn strip_non_doc_comments(code: &str, language: Language) -> String {
+    strip(code, language, true)
+}
+
+fn strip(code: &str, language: Language, keep_doc_comments: bool) -> String {
+    let Some(grammar) = tree_sitter_language(language) else {
+        return code.to_string();
+    };
+    let mut parser = Parser::new();
+    let Some(tree) = parser.set_language(&grammar).ok().and_then(|_| parser.parse(code, None)) else {
+        return code.to_string();
+    };
+
+    let mut ranges: Vec<(usize, usize)> = comment_nodes(tree.root_node())
+        .into_iter()
+        .filter(|node| !(keep_doc_comments && is_doc_comment(&code[node.byte_range()])))
+        .map(|node| removal_range(code, node.start_byte(), node.end_byte()))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut stripped = String::with_capacity(code.len());
+    let mut copied_to = 0;
+    for (start, end) in ranges {
+        if start > copied_to {
+            stripped.push_str(&code[copied_to..start]);
+        }
+        copied_to = copied_to.max(end);
+    }
+    stripped.push_str(&code[copied_to..]);
+    stripped
+}
+
+/// Outermost comment nodes under `root`, walked with an explicit stack
+fn comment_nodes(root: Node<'_>) -> Vec<Node<'_>> {
+    let mut comments = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind().contains("comment") {
+            comments.push(node);
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    comments
+}
+
+fn is_doc_comment(comment: &str) -> bool {
+    (comment.starts_with("///") && !comment.starts_with("////"))
+        || comment.starts_with("//!")
+        || (comment.starts_with("/**") && !comment.starts_with("/**/"))
+        || comment.starts_with("/*!")
+}
+
+/// Bytes to drop for the comment at `start..end`: its whole line when
+/// nothing else is on it, else the comment plus whitespace before a
+/// trailing one
+fn removal_range(code: &str, start: usize, end: usize) -> (usize, usize) {
+    // Some grammars end line comments after their newline
+    let end = if code[start..end].ends_with('\n') { end - 1 } else { end };
+    let line_start = code[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = code[end..].find('\n').map_or(code.len(), |idx| end + idx);
+    let before = &code[line_start..start];
+    let after = &code[end..line_end];
+
+    match (before.trim().is_empty(), after.trim().is_empty()) {
+        (true, true) => (line_start, (line_end + 1).min(code.len())),
+        (false, true) => (start - (before.len() - before.trim_end().len()), line_end),
+        _ => (start, end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lc(line: usize, col: usize) -> Option<LineCol> {
+        Some(LineCol { line, col })
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let index = LineIndex::new("fn a() {}\nfn b() {}");
+
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.offset_to_line_col(0), lc(1, 1));
+        assert_eq!(index.offset_to_line_col(9), lc(1, 10)); // the '\n'
+        assert_eq!(index.offset_to_line_col(10), lc(2, 1));
+        assert_eq!(index.offset_to_line_col(19), lc(2, 10)); // end of text
+        assert_eq!(index.offset_to_line_col(20), None);
+
+        for offset in 0..=19 {
+            let LineCol { line, col } = index.offset_to_line_col(offset).unwrap();
+            assert_eq!(index.line_col_to_offset(line, col), Some(offset));
+        }
+    }
+
+    #[test]
+    fn test_multibyte_characters_count_as_one_column() {
+        // 'é' is 2 bytes, '𝔘' is 4
+        let text = "let é = 1;\n\"𝔘\" + x";
+        let index = LineIndex::new(text);
+
+        let x = text.find('x').unwrap();
+        assert_eq!(index.offset_to_line_col(x), lc(2, 7));
+        assert_eq!(index.line_col_to_offset(2, 7), Some(x));
+
+        let after_e = text.find(" =").unwrap();
+        assert_eq!(index.offset_to_line_col(after_e), lc(1, 6));
+        assert_eq!(index.offset_to_line_col(after_e - 1), None, "inside 'é'");
+        assert_eq!(index.line_col_to_offset(1, 5), Some(text.find('é').unwrap()));
+    }
+
+    #[test]
+    fn test_trailing_newline_starts_an_empty_line() {
+        let index = LineIndex::new("a\r\nb\n");
+
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_text(1), Some("a\r"));
+        assert_eq!(index.line_text(3), Some(""));
+        assert_eq!(index.offset_to_line_col(5), lc(3, 1));
+        assert_eq!(index.line_col_to_offset(3, 1), Some(5));
+        assert_eq!(index.line_col_to_offset(3, 2), None);
+        assert_eq!(index.line_col_to_offset(4, 1), None);
+        assert_eq!(index.line_col_to_offset(1, 0), None);
+    }
+
+    #[test]
+    fn test_strip_comments_leaves_string_literals_alone() {
+        let code = "/// Adds one\nfn add_one(x: i32) -> i32 {\n    // bump it\n    let url = \"http://example.com\"; // trailing\n    /* block\n       comment */\n    x + /* inline */ 1\n}\n";
+
+        assert_eq!(
+            strip_comments(code, Language::Rust),
+            "fn add_one(x: i32) -> i32 {\n    let url = \"http://example.com\";\n    x +  1\n}\n"
+        );
+        assert_eq!(
+            strip_non_doc_comments(code, Language::Rust),
+            "/// Adds one\nfn add_one(x: i32) -> i32 {\n    let url = \"http://example.com\";\n    x +  1\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_comments_python_hash_comments() {
+        let code = "def f():\n    # note\n    return \"# not a comment\"  # trailing\n";
+        assert_eq!(
+            strip_comments(code, Language::Python),
+            "def f():\n    return \"# not a comment\"\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let index = LineIndex::new("");
+        assert_eq!(index.line_count(), 1);
+        assert_eq!(index.offset_to_line_col(0), lc(1, 1));
+        assert_eq!(index.line_col_to_offset(1, 1), Some(0));
+    }
+}
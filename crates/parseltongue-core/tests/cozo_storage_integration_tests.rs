@@ -168,6 +168,68 @@ async fn test_delete_entity() {
     assert!(result.is_err() || result.unwrap().isgl1_key.is_empty());
 }
 
+/// Clock the test moves forward by hand
+struct ManualClock(std::sync::Mutex<chrono::DateTime<chrono::Utc>>);
+
+impl ManualClock {
+    fn advance(&self, by: chrono::Duration) {
+        let mut now = self.0.lock().unwrap();
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_soft_deleted_entity_hidden_until_purged_after_retention() {
+    let start = chrono::DateTime::parse_from_rfc3339("2026-01-10T09:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    let clock = std::sync::Arc::new(ManualClock(std::sync::Mutex::new(start)));
+    let db = CozoDbStorage::new("mem").await.unwrap().with_clock(clock.clone());
+    db.ensure_schema().await.unwrap();
+    let key = "test-file-rs-TestStruct";
+    db.insert_entity(&create_test_entity_with_key(key)).await.unwrap();
+    db.insert_entity(&create_test_entity_with_key("test-file-rs-Kept")).await.unwrap();
+
+    db.delete_entity(key).await.unwrap();
+
+    // Hidden from normal reads
+    assert!(matches!(db.get_entity(key).await, Err(ParseltongError::EntityNotFound { .. })));
+    let live: Vec<_> = db.get_all_entities().await.unwrap().into_iter().map(|e| e.isgl1_key).collect();
+    assert_eq!(live, vec!["test-file-rs-Kept".to_string()]);
+    // Still there for recovery
+    let all = db.get_all_entities_including_deleted().await.unwrap();
+    assert!(all.iter().any(|e| e.isgl1_key == key));
+    assert_eq!(db.deleted_at(key).await.unwrap(), Some(start));
+
+    let retention = Duration::from_secs(7 * 24 * 3600);
+    clock.advance(chrono::Duration::days(6));
+    assert_eq!(db.purge_expired(retention).await.unwrap(), 0, "still within the retention window");
+    assert_eq!(db.get_all_entities_including_deleted().await.unwrap().len(), 2);
+
+    clock.advance(chrono::Duration::days(1));
+    assert_eq!(db.purge_expired(retention).await.unwrap(), 1);
+    assert_eq!(db.get_all_entities_including_deleted().await.unwrap().len(), 1);
+    assert!(matches!(db.deleted_at(key).await, Err(ParseltongError::EntityNotFound { .. })));
+}
+
+#[tokio::test]
+async fn test_reinserting_soft_deleted_entity_restores_it() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let entity = create_test_entity();
+    db.insert_entity(&entity).await.unwrap();
+    db.delete_entity(&entity.isgl1_key).await.unwrap();
+
+    db.insert_entity(&entity).await.unwrap();
+
+    assert_eq!(db.deleted_at(&entity.isgl1_key).await.unwrap(), None);
+    assert_eq!(db.get_entity(&entity.isgl1_key).await.unwrap().isgl1_key, entity.isgl1_key);
+}
+
 #[tokio::test]
 async fn test_codegraph_repository_trait() {
     // Test: CodeGraphRepository trait implementation
@@ -347,6 +409,34 @@ async fn test_batch_insert_edges() {
     db.insert_edges_batch(&edges).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_find_orphan_edges_after_target_deleted() {
+    // Test: Deleting an edge's target entity leaves an orphan edge behind
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_schema().await.unwrap();
+    db.create_dependency_edges_schema().await.unwrap();
+
+    db.insert_entity(&create_test_entity_with_key("caller-rs-Caller")).await.unwrap();
+    db.insert_entity(&create_test_entity_with_key("callee-rs-Callee")).await.unwrap();
+    let edge = DependencyEdge::builder()
+        .from_key("caller-rs-Caller")
+        .to_key("callee-rs-Callee")
+        .edge_type(EdgeType::Calls)
+        .build()
+        .unwrap();
+    db.insert_edge(&edge).await.unwrap();
+
+    assert!(db.find_orphan_edges().await.unwrap().is_empty());
+
+    db.delete_entity("callee-rs-Callee").await.unwrap();
+
+    let orphans = db.find_orphan_edges().await.unwrap();
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].from_key.as_str(), "caller-rs-Caller");
+    assert_eq!(orphans[0].to_key.as_str(), "callee-rs-Callee");
+    assert_eq!(orphans[0].edge_type, EdgeType::Calls);
+}
+
 #[tokio::test]
 async fn test_batch_insert_empty_slice() {
     // Test: Batch insert with empty slice should succeed (no-op)
@@ -825,6 +915,45 @@ async fn test_transitive_closure_cycle() {
     assert!(reachable.contains(&"rust:fn:C:test_rs:20-25".to_string()));
 }
 
+#[tokio::test]
+async fn test_shortest_path_follows_edge_direction() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_dependency_edges_schema().await.unwrap();
+
+    // A -> B -> C
+    let edges = vec![
+        DependencyEdge::builder()
+            .from_key("rust:fn:A:test_rs:1-5")
+            .to_key("rust:fn:B:test_rs:10-15")
+            .edge_type(EdgeType::Calls)
+            .build()
+            .unwrap(),
+        DependencyEdge::builder()
+            .from_key("rust:fn:B:test_rs:10-15")
+            .to_key("rust:fn:C:test_rs:20-25")
+            .edge_type(EdgeType::Calls)
+            .build()
+            .unwrap(),
+    ];
+    db.insert_edges_batch(&edges).await.unwrap();
+
+    let path = db.shortest_path("rust:fn:A:test_rs:1-5", "rust:fn:C:test_rs:20-25").await.unwrap();
+    assert_eq!(
+        path,
+        Some(vec![
+            "rust:fn:A:test_rs:1-5".to_string(),
+            "rust:fn:B:test_rs:10-15".to_string(),
+            "rust:fn:C:test_rs:20-25".to_string(),
+        ])
+    );
+    assert_eq!(db.shortest_path("rust:fn:C:test_rs:20-25", "rust:fn:A:test_rs:1-5").await.unwrap(), None);
+    assert_eq!(
+        db.shortest_path_within("rust:fn:A:test_rs:1-5", "rust:fn:C:test_rs:20-25", 1).await.unwrap(),
+        None,
+        "C is two hops away"
+    );
+}
+
 #[tokio::test]
 async fn test_transitive_closure_empty() {
     // RED: Entity with no outgoing edges
@@ -1017,3 +1146,708 @@ async fn test_forward_dependencies_performance_10k_nodes() {
         "Should find forward dependencies for first node"
     );
 }
+
+#[tokio::test]
+async fn test_query_cache_serves_repeats_and_invalidates_on_write() {
+    let db = CozoDbStorage::new("mem").await.unwrap().with_cache(16);
+    db.create_schema().await.unwrap();
+    db.insert_entity(&create_test_entity_with_key("entity1")).await.unwrap();
+
+    let query = "?[key] := *CodeGraph{ISGL1_key: key}";
+    let first = db.raw_query(query).await.unwrap();
+    let second = db.raw_query(query).await.unwrap();
+    assert_eq!(first.rows, second.rows);
+
+    let stats = db.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (1, 1), "repeat is served from cache");
+
+    // A write bumps the revision, so the next read sees the new entity
+    db.insert_entity(&create_test_entity_with_key("entity2")).await.unwrap();
+    let after_write = db.raw_query(query).await.unwrap();
+    assert_eq!(after_write.rows.len(), 2);
+    assert_eq!(db.cache_stats().unwrap().misses, 2);
+
+    // Caching is opt-in
+    let uncached = CozoDbStorage::new("mem").await.unwrap();
+    assert!(uncached.cache_stats().is_none());
+}
+
+// ================== Schema versioning and migrations ==================
+
+#[tokio::test]
+async fn test_ensure_schema_is_idempotent() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+
+    // Fresh database: every relation created at the current version
+    db.ensure_schema().await.unwrap();
+    let relations = db.list_relations().await.unwrap();
+    assert!(relations.contains(&"CodeGraph".to_string()));
+    assert!(relations.contains(&"DependencyEdges".to_string()));
+    assert!(relations.contains(&"GeneratedEntities".to_string()), "side relations of migrations too");
+    assert_eq!(db.schema_version().await.unwrap(), Some(storage::migrations::CURRENT_SCHEMA_VERSION));
+
+    // Reopen: no "already exists" errors, data untouched
+    db.insert_entity(&create_test_entity()).await.unwrap();
+    db.ensure_schema().await.unwrap();
+    assert!(db.migrate().await.unwrap().is_empty());
+    assert_eq!(db.get_all_entities().await.unwrap().len(), 1);
+
+    // Databases made by create_schema() before version tracking are adopted
+    let legacy = CozoDbStorage::new("mem").await.unwrap();
+    legacy.create_schema().await.unwrap();
+    assert_eq!(legacy.schema_version().await.unwrap(), None);
+    legacy.ensure_schema().await.unwrap();
+    assert_eq!(legacy.schema_version().await.unwrap(), Some(storage::migrations::CURRENT_SCHEMA_VERSION));
+}
+
+#[tokio::test]
+async fn test_ensure_schema_migrates_v0_database() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+
+    // v0 CodeGraph: no entity_class column
+    db.execute_query(
+        r#"
+        :create CodeGraph {
+            ISGL1_key: String =>
+            Current_Code: String?, Future_Code: String?, interface_signature: String,
+            TDD_Classification: String, lsp_meta_data: String?, current_ind: Bool,
+            future_ind: Bool, Future_Action: String?, file_path: String, language: String,
+            last_modified: String, entity_type: String
+        }
+        "#,
+    )
+    .await
+    .unwrap();
+    db.execute_query(
+        r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type] <-
+        [["rust:fn:old:src_old_rs:1-3", "fn old() {}", "fn old() {}", "{}", "{}",
+          null, true, true, null, "src/old.rs", "rust", "2024-01-01", "function"]]
+        :put CodeGraph {
+            ISGL1_key => Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type
+        }
+        "#,
+    )
+    .await
+    .unwrap();
+
+    db.ensure_schema().await.unwrap();
+
+    assert_eq!(db.schema_version().await.unwrap(), Some(storage::migrations::CURRENT_SCHEMA_VERSION));
+    let rows = db
+        .raw_query("?[key, class] := *CodeGraph{ISGL1_key: key, entity_class: class}")
+        .await
+        .unwrap()
+        .rows;
+    assert_eq!(rows.len(), 1, "existing rows survive the migration");
+    assert_eq!(rows[0][1], cozo::DataValue::from("CODE"));
+    assert!(db.list_relations().await.unwrap().contains(&"GeneratedEntities".to_string()));
+}
+
+#[tokio::test]
+async fn test_set_future_code_touches_only_temporal_columns() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_schema().await.unwrap();
+    let key = "test-file-rs-TestStruct";
+    db.insert_entity(&create_test_entity_with_key(key)).await.unwrap();
+    let before = db.get_entity(key).await.unwrap();
+
+    db.set_future_code(key, "struct TestStruct { x: u8 }", TemporalAction::Edit)
+        .await
+        .unwrap();
+    let after = db.get_entity(key).await.unwrap();
+
+    assert_eq!(after.future_code.as_deref(), Some("struct TestStruct { x: u8 }"));
+    assert_eq!(after.temporal_state, TemporalState::edit());
+    assert_ne!(after.version_hash(), before.version_hash());
+
+    assert_eq!(after.current_code, before.current_code);
+    assert_eq!(after.interface_signature, before.interface_signature);
+    assert_eq!(after.tdd_classification, before.tdd_classification);
+    assert_eq!(after.lsp_metadata, before.lsp_metadata);
+    assert_eq!(after.entity_class, before.entity_class);
+}
+
+#[tokio::test]
+async fn test_set_future_code_on_missing_entity_is_not_found() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_schema().await.unwrap();
+
+    let err = db
+        .set_future_code("rust:fn:ghost:src_lib_rs:1-1", "fn ghost() {}", TemporalAction::Edit)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, ParseltongError::EntityNotFound { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_access_logging_counts_reads_and_updates_timestamp() {
+    let db = CozoDbStorage::new("mem").await.unwrap().with_access_logging(true);
+    db.ensure_schema().await.unwrap();
+    let key = "test-file-rs-TestStruct";
+    db.insert_entity(&create_test_entity_with_key(key)).await.unwrap();
+    assert_eq!(db.get_access_stats(key).await.unwrap(), None);
+
+    db.get_entity(key).await.unwrap();
+    let first = db.get_access_stats(key).await.unwrap().unwrap();
+    assert_eq!(first.query_count, 1);
+
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    db.get_entities_by_file("test/file.rs").await.unwrap();
+    let second = db.get_access_stats(key).await.unwrap().unwrap();
+    assert_eq!(second.query_count, 2);
+    let parse = |stamp: &str| chrono::DateTime::parse_from_rfc3339(stamp).unwrap();
+    assert!(parse(&second.last_queried) > parse(&first.last_queried), "{:?} then {:?}", first, second);
+}
+
+#[tokio::test]
+async fn test_reads_are_not_logged_by_default() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let key = "test-file-rs-TestStruct";
+    db.insert_entity(&create_test_entity_with_key(key)).await.unwrap();
+
+    db.get_entity(key).await.unwrap();
+    assert_eq!(db.get_access_stats(key).await.unwrap(), None);
+}
+
+/// Store `count` entities, then apply deletes to all but the first 50
+///
+/// Returns the live and tombstoned keys. Applied deletes leave both
+/// indicators false; the pending delete on the first live key does not.
+async fn tombstone_all_but_50(db: &CozoDbStorage, count: usize, code_len: usize) -> (Vec<String>, Vec<String>) {
+    let keys: Vec<String> = (0..count).map(|i| format!("test-file-rs-Struct{}", i)).collect();
+    for (i, key) in keys.iter().enumerate() {
+        let mut entity = create_test_entity_with_key(key);
+        // Varied filler so the backend cannot compress it away
+        let filler: String = (0..code_len).map(|j| char::from(b'a' + ((i * 7919 + j * 104_729) % 26) as u8)).collect();
+        entity.current_code = Some(format!("struct TestStruct {{}} // {}", filler));
+        entity.future_code = entity.current_code.clone();
+        db.insert_entity(&entity).await.unwrap();
+        db.record_provenance(key, "pt01").await.unwrap();
+    }
+
+    let (live, deleted) = keys.split_at(50);
+    db.set_future_code(&live[0], "", TemporalAction::Delete).await.unwrap();
+    let deleted_list = deleted.iter().map(|k| format!("{:?}", k)).collect::<Vec<_>>().join(", ");
+    db.execute_query(&format!(
+        "?[ISGL1_key, current_ind, future_ind] := ISGL1_key in [{}], current_ind = false, future_ind = false
+         :update CodeGraph {{ ISGL1_key => current_ind, future_ind }}",
+        deleted_list
+    ))
+    .await
+    .unwrap();
+    (live.to_vec(), deleted.to_vec())
+}
+
+#[tokio::test]
+async fn test_compact_removes_tombstones_and_keeps_live_entities() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let (live, deleted) = tombstone_all_but_50(&db, 200, 0).await;
+
+    let report = db.compact().await.unwrap();
+
+    assert_eq!(report.tombstones_removed, 150);
+    assert_eq!(report.bytes_reclaimed(), None, "in-memory engine has no disk size");
+    let mut surviving: Vec<String> = db.get_all_entities().await.unwrap().into_iter().map(|e| e.isgl1_key).collect();
+    surviving.sort();
+    let mut expected = live.clone();
+    expected.sort();
+    assert_eq!(surviving, expected);
+    assert!(db.get_provenance(&deleted[0]).await.unwrap().is_none());
+    assert!(db.get_provenance(&live[0]).await.unwrap().is_some());
+
+    assert_eq!(db.compact().await.unwrap().tombstones_removed, 0);
+}
+
+#[tokio::test]
+async fn test_compact_shrinks_rocksdb_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = CozoDbStorage::new(&format!("rocksdb:{}", dir.path().join("compact.db").display()))
+        .await
+        .unwrap();
+    db.ensure_schema().await.unwrap();
+    tombstone_all_but_50(&db, 200, 8 * 1024).await;
+
+    let report = db.compact().await.unwrap();
+
+    assert_eq!(report.tombstones_removed, 150);
+    let (before, after) = (report.bytes_before.unwrap(), report.bytes_after.unwrap());
+    assert!(after < before, "compaction should shrink the database: {} -> {} bytes", before, after);
+    assert_eq!(report.bytes_reclaimed(), Some(before - after));
+    assert_eq!(db.get_all_entities().await.unwrap().len(), 50);
+}
+
+#[tokio::test]
+async fn test_copy_entities_to_copies_filtered_subgraph() {
+    let source = CozoDbStorage::new("mem").await.unwrap();
+    source.ensure_schema().await.unwrap();
+    let entity = |name: &str, file: &str| {
+        let key = format!("rust:struct:{}:{}:1-10", name, file.replace(['/', '.'], "_"));
+        let mut entity = create_test_entity_with_key(&key);
+        entity.interface_signature.file_path = PathBuf::from(file);
+        entity
+    };
+    let core_a = entity("CoreA", "core/a.rs");
+    let core_b = entity("CoreB", "core/b.rs");
+    let cli = entity("Cli", "cli/main.rs");
+    for e in [&core_a, &core_b, &cli] {
+        source.insert_entity(e).await.unwrap();
+    }
+    let edge = |from: &CodeEntity, to: &CodeEntity| {
+        DependencyEdge::builder()
+            .from_key(from.isgl1_key.clone())
+            .to_key(to.isgl1_key.clone())
+            .edge_type(EdgeType::Uses)
+            .build()
+            .unwrap()
+    };
+    // Inside the subgraph, into it, and out of it
+    source
+        .insert_edges_batch(&[edge(&core_a, &core_b), edge(&cli, &core_a), edge(&core_b, &cli)])
+        .await
+        .unwrap();
+
+    let target = CozoDbStorage::new("mem").await.unwrap();
+    target.ensure_schema().await.unwrap();
+    let report = source
+        .copy_entities_to(&target, Some("starts_with(file_path, 'core/')"), false)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report,
+        CopyReport { entities_copied: 2, edges_copied: 1, dangling_edges_dropped: 2 }
+    );
+    let mut copied: Vec<_> = target
+        .get_all_entities()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.isgl1_key)
+        .collect();
+    copied.sort();
+    assert_eq!(copied, vec![core_a.isgl1_key.clone(), core_b.isgl1_key.clone()]);
+    let edges: Vec<_> = target
+        .get_all_dependencies()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.from_key.as_str().to_string(), e.to_key.as_str().to_string()))
+        .collect();
+    assert_eq!(edges, vec![(core_a.isgl1_key.clone(), core_b.isgl1_key.clone())]);
+    assert_eq!(target.get_entity(&core_a.isgl1_key).await.unwrap().current_code, core_a.current_code);
+
+    let with_dangling = CozoDbStorage::new("mem").await.unwrap();
+    with_dangling.ensure_schema().await.unwrap();
+    let report = source
+        .copy_entities_to(&with_dangling, Some("starts_with(file_path, 'core/')"), true)
+        .await
+        .unwrap();
+    assert_eq!((report.entities_copied, report.edges_copied, report.dangling_edges_dropped), (2, 3, 0));
+    assert_eq!(with_dangling.get_all_dependencies().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_api_report_flags_removed_public_entity_as_breaking() {
+    use parseltongue_core::api_report::{ApiChangeKind, ApiReport, SemverImpact};
+
+    let entity = |name: &str, visibility: Visibility| {
+        let mut entity = create_test_entity_with_key(&format!("rust:struct:{}:test_file_rs:1-10", name));
+        entity.interface_signature.name = name.to_string();
+        entity.interface_signature.visibility = visibility;
+        entity
+    };
+    let old = CozoDbStorage::new("mem").await.unwrap();
+    let new = CozoDbStorage::new("mem").await.unwrap();
+    old.ensure_schema().await.unwrap();
+    new.ensure_schema().await.unwrap();
+    for e in [entity("Alpha", Visibility::Public), entity("Beta", Visibility::Public), entity("Gamma", Visibility::Private)] {
+        old.insert_entity(&e).await.unwrap();
+    }
+    new.insert_entity(&entity("Alpha", Visibility::Public)).await.unwrap();
+
+    let old_public = old.get_public_entities().await.unwrap();
+    assert_eq!(old_public.len(), 2);
+
+    let report = ApiReport::compare(&old_public, &new.get_all_entities().await.unwrap());
+
+    assert_eq!(report.changes.len(), 1, "{:?}", report.changes);
+    assert_eq!(report.changes[0].name, "Beta");
+    assert_eq!(report.changes[0].kind, ApiChangeKind::Removed { now: None });
+    assert_eq!(report.required_bump(), Some(SemverImpact::Major));
+}
+
+fn bulk_entities(count: usize) -> Vec<CodeEntity> {
+    (0..count)
+        .map(|i| create_test_entity_with_key(&format!("rust:struct:S{}:test_file_rs:{}-{}", i, i, i + 1)))
+        .collect()
+}
+
+async fn sorted_keys(db: &CozoDbStorage) -> Vec<String> {
+    let mut keys: Vec<String> = db.get_all_entities().await.unwrap().into_iter().map(|e| e.isgl1_key).collect();
+    keys.sort();
+    keys
+}
+
+#[tokio::test]
+async fn test_insert_entities_batch_commits_in_chunks() {
+    let options = StorageOptions { commit_batch: 7, ..Default::default() };
+    let db = CozoDbStorage::open_with_options("mem", options).await.unwrap();
+    db.create_schema().await.unwrap();
+    let entities = bulk_entities(50);
+
+    db.insert_entities_batch(&entities).await.unwrap();
+
+    let mut expected: Vec<String> = entities.iter().map(|e| e.isgl1_key.clone()).collect();
+    expected.sort();
+    assert_eq!(sorted_keys(&db).await, expected);
+    let stored = db.get_entity(&entities[49].isgl1_key).await.unwrap();
+    assert_eq!(stored.current_code, entities[49].current_code);
+    assert_eq!(stored.interface_signature, entities[49].interface_signature);
+}
+
+#[tokio::test]
+async fn test_sync_and_no_sync_rocksdb_store_the_same_entities() {
+    let dir = tempfile::tempdir().unwrap();
+    let entities = bulk_entities(300);
+
+    let mut results = Vec::new();
+    for sync_writes in [true, false] {
+        let path = dir.path().join(format!("sync-{}.db", sync_writes));
+        let options = StorageOptions { commit_batch: 64, sync_writes, cache_mb: 8, ..Default::default() };
+        {
+            let db = CozoDbStorage::open_with_options(&format!("rocksdb:{}", path.display()), options)
+                .await
+                .unwrap();
+            db.create_schema().await.unwrap();
+            db.insert_entities_batch(&entities).await.unwrap();
+        }
+        // Reopen to read what actually reached the database
+        let db = CozoDbStorage::new(&format!("rocksdb:{}", path.display())).await.unwrap();
+        results.push(sorted_keys(&db).await);
+    }
+
+    assert_eq!(results[0].len(), 300);
+    assert_eq!(results[0], results[1]);
+}
+
+#[tokio::test]
+#[ignore] // Benchmark - run with: cargo test --release -- --ignored --nocapture
+async fn test_bulk_ingestion_sync_vs_no_sync_benchmark() {
+    let dir = tempfile::tempdir().unwrap();
+    let entities = bulk_entities(20_000);
+
+    for sync_writes in [true, false] {
+        let path = dir.path().join(format!("bench-{}.db", sync_writes));
+        let options = StorageOptions { sync_writes, ..Default::default() };
+        let db = CozoDbStorage::open_with_options(&format!("rocksdb:{}", path.display()), options)
+            .await
+            .unwrap();
+        db.create_schema().await.unwrap();
+
+        let start = Instant::now();
+        db.insert_entities_batch(&entities).await.unwrap();
+        println!("sync_writes={}: {} entities in {:?}", sync_writes, entities.len(), start.elapsed());
+    }
+}
+
+fn graph_options(relation_name: &str) -> StorageOptions {
+    StorageOptions { relation_name: relation_name.to_string(), ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_named_relation_is_used_by_every_helper() {
+    let db = CozoDbStorage::open_with_options("mem", graph_options("after")).await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let relations = db.list_relations().await.unwrap();
+    assert!(relations.contains(&"after".to_string()), "{:?}", relations);
+    assert!(!relations.contains(&"CodeGraph".to_string()), "{:?}", relations);
+
+    let entity = create_test_entity_with_key("rust:struct:TestStruct:test_file_rs:1-10");
+    db.insert_entity(&entity).await.unwrap();
+    db.insert_entities_batch(&bulk_entities(3)).await.unwrap();
+    db.update_temporal_state(&entity.isgl1_key, false, Some(TemporalAction::Delete)).await.unwrap();
+
+    assert_eq!(db.get_all_entities().await.unwrap().len(), 4);
+    assert_eq!(db.get_changed_entities().await.unwrap().len(), 1);
+    let stored = db.raw_query("?[k] := *after{ISGL1_key: k}").await.unwrap();
+    assert_eq!(stored.rows.len(), 4);
+
+    db.delete_entity(&entity.isgl1_key).await.unwrap();
+    assert_eq!(sorted_keys(&db).await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_named_v0_relation_is_migrated_and_versioned_on_its_own() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = format!("rocksdb:{}", dir.path().join("graphs.db").display());
+
+    // A current CodeGraph tracked by the single-row SchemaVersion, next to
+    // a named relation still at v0
+    let db = CozoDbStorage::new(&spec).await.unwrap();
+    db.create_schema().await.unwrap();
+    db.execute_query(
+        r#"
+        :create SchemaVersion { id: Int => version: Int }
+        "#,
+    )
+    .await
+    .unwrap();
+    db.execute_query("?[id, version] <- [[0, 2]] :put SchemaVersion { id => version }").await.unwrap();
+    db.execute_query(
+        r#"
+        :create before {
+            ISGL1_key: String =>
+            Current_Code: String?, Future_Code: String?, interface_signature: String,
+            TDD_Classification: String, lsp_meta_data: String?, current_ind: Bool,
+            future_ind: Bool, Future_Action: String?, file_path: String, language: String,
+            last_modified: String, entity_type: String
+        }
+        "#,
+    )
+    .await
+    .unwrap();
+    db.execute_query(
+        r#"
+        ?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
+          lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+          last_modified, entity_type] <-
+        [["rust:fn:old:src_old_rs:1-3", "fn old() {}", "fn old() {}", "{}", "{}",
+          null, true, true, null, "src/old.rs", "rust", "2024-01-01", "function"]]
+        :put before {
+            ISGL1_key => Current_Code, Future_Code, interface_signature, TDD_Classification,
+            lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
+            last_modified, entity_type
+        }
+        "#,
+    )
+    .await
+    .unwrap();
+    drop(db);
+
+    let before = CozoDbStorage::open_with_options(&spec, graph_options("before")).await.unwrap();
+    before.ensure_schema().await.unwrap();
+    assert_eq!(before.schema_version().await.unwrap(), Some(storage::migrations::CURRENT_SCHEMA_VERSION));
+    let rows = before
+        .raw_query("?[key, class, deleted_at] := *before{ISGL1_key: key, entity_class: class, deleted_at}")
+        .await
+        .unwrap()
+        .rows;
+    assert_eq!(rows.len(), 1, "existing rows survive the migration");
+    assert_eq!(rows[0][1], cozo::DataValue::from("CODE"));
+    assert_eq!(rows[0][2], cozo::DataValue::Null);
+    drop(before);
+
+    // CodeGraph kept the version recorded for it before the re-keying
+    let code_graph = CozoDbStorage::new(&spec).await.unwrap();
+    assert_eq!(code_graph.schema_version().await.unwrap(), Some(2));
+}
+
+#[tokio::test]
+async fn test_additional_metadata_round_trips_through_the_database() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+
+    let mut entity = create_test_entity();
+    entity.metadata.additional.insert("source_encoding".to_string(), "windows-1252".to_string());
+    db.insert_entity(&entity).await.unwrap();
+    db.insert_entity(&create_test_entity_with_key("plain-utf8")).await.unwrap();
+
+    let stored = db.get_entity(&entity.isgl1_key).await.unwrap();
+    assert_eq!(stored.metadata.additional.get("source_encoding").map(String::as_str), Some("windows-1252"));
+    let all = db.get_all_entities().await.unwrap();
+    let listed = all.iter().find(|e| e.isgl1_key == entity.isgl1_key).unwrap();
+    assert_eq!(listed.metadata.additional, entity.metadata.additional);
+    let plain = all.iter().find(|e| e.isgl1_key == "plain-utf8").unwrap();
+    assert!(plain.metadata.additional.is_empty(), "empty map stored as null");
+}
+
+#[tokio::test]
+async fn test_invalid_relation_name_is_rejected() {
+    let err = CozoDbStorage::open_with_options("mem", graph_options("x}, :rm")).await.err().unwrap();
+    assert!(err.to_string().contains("Invalid relation name"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_two_named_graphs_coexist_in_one_rocksdb() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = format!("rocksdb:{}", dir.path().join("graphs.db").display());
+    let before = create_test_entity_with_key("rust:fn:total:src_lib_rs:1-5");
+    let after = create_test_entity_with_key("rust:fn:sum_total:src_lib_rs:1-6");
+
+    // One handle at a time: RocksDB locks its directory
+    for (relation, entity) in [("before", &before), ("after", &after)] {
+        let db = CozoDbStorage::open_with_options(&spec, graph_options(relation)).await.unwrap();
+        db.ensure_schema().await.unwrap();
+        db.insert_entity(entity).await.unwrap();
+    }
+
+    for (relation, entity) in [("before", &before), ("after", &after)] {
+        let db = CozoDbStorage::open_with_options(&spec, graph_options(relation)).await.unwrap();
+        assert_eq!(sorted_keys(&db).await, vec![entity.isgl1_key.clone()], "{}", relation);
+    }
+}
+
+#[tokio::test]
+async fn test_entities_stream_matches_get_all_entities() {
+    use futures::TryStreamExt;
+
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_schema().await.unwrap();
+    for i in 0..23 {
+        let mut entity = create_test_entity_with_key(&format!("rust:struct:S{}:src_lib_rs:{}-{}", (i * 7) % 23, i, i));
+        if i % 5 == 0 {
+            entity.apply_temporal_change(TemporalAction::Edit, Some("struct Changed {}".to_string())).unwrap();
+        }
+        db.insert_entity(&entity).await.unwrap();
+    }
+
+    let keys = |entities: Vec<CodeEntity>| entities.into_iter().map(|e| e.isgl1_key).collect::<Vec<_>>();
+    let all = keys(db.get_all_entities().await.unwrap());
+    assert_eq!(all.len(), 23);
+
+    // Pages of 4 leave a partial last page; 23 makes the last page full
+    for page_size in [4, 23, 1000] {
+        let streamed: Vec<CodeEntity> = db.entities_stream_paged(page_size).try_collect().await.unwrap();
+        assert_eq!(keys(streamed), all, "page size {}", page_size);
+    }
+
+    let changed: Vec<CodeEntity> = db.changed_entities_stream().try_collect().await.unwrap();
+    assert_eq!(keys(changed), keys(db.get_changed_entities().await.unwrap()));
+}
+
+#[tokio::test]
+async fn test_entities_pages_cover_get_all_entities() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.create_schema().await.unwrap();
+    for i in 0..17 {
+        let key = format!("rust:fn:f{:02}:src_lib_rs:{}-{}", (i * 5) % 17, i, i);
+        db.insert_entity(&create_test_entity_with_key(&key)).await.unwrap();
+    }
+
+    let mut all: Vec<String> = db.get_all_entities().await.unwrap().into_iter().map(|e| e.isgl1_key).collect();
+    all.sort();
+
+    for limit in [1, 4, 17, 100] {
+        let mut walked = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = db.get_entities_page(cursor.as_deref(), limit).await.unwrap();
+            assert!(page.len() <= limit);
+            walked.extend(page.into_iter().map(|e| e.isgl1_key));
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(walked, all, "limit {}", limit);
+    }
+
+    // Writes between pages don't break the cursor
+    let (first, cursor) = db.get_entities_page(None, 5).await.unwrap();
+    db.delete_entity(&first[0].isgl1_key).await.unwrap();
+    db.delete_entity(&all[7]).await.unwrap();
+    db.insert_entity(&create_test_entity_with_key("rust:fn:a_new:src_lib_rs:1-1")).await.unwrap();
+    let (second, _) = db.get_entities_page(cursor.as_deref(), 5).await.unwrap();
+    let second: Vec<String> = second.into_iter().map(|e| e.isgl1_key).collect();
+    let expected: Vec<String> = all[5..].iter().filter(|key| *key != &all[7]).take(5).cloned().collect();
+    assert_eq!(second, expected);
+}
+
+#[tokio::test]
+async fn test_structured_lsp_metadata_round_trips_and_legacy_strings_upgrade() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+
+    let mut entity = create_test_entity_with_key("rust:struct:TestStruct:test_file_rs:1-10");
+    entity.lsp_metadata = Some(LspMetadata {
+        hover: Some("struct TestStruct".to_string()),
+        type_information: TypeInformation {
+            resolved_type: "TestStruct".to_string(),
+            definition_location: Some(Location {
+                file_path: PathBuf::from("test/file.rs"),
+                line: 1,
+                character: 7,
+            }),
+            ..TypeInformation::default()
+        },
+        usage_analysis: UsageAnalysis {
+            total_references: 3,
+            ..UsageAnalysis::default()
+        },
+        diagnostics: vec![LspDiagnostic {
+            severity: "warning".to_string(),
+            message: "struct is never constructed".to_string(),
+            line: 1,
+        }],
+        semantic_tokens: vec![],
+    });
+    db.insert_entity(&entity).await.unwrap();
+
+    let stored = db.get_entity(&entity.isgl1_key).await.unwrap().lsp_metadata.unwrap();
+    assert_eq!(Some(&stored), entity.lsp_metadata.as_ref());
+    assert_eq!(stored.references_count(), 3);
+    assert_eq!(stored.definition_location().map(|l| l.character), Some(7));
+
+    // A raw hover response stored by an early version
+    let legacy = serde_json::to_string(r#"{"contents":"struct Legacy"}"#).unwrap();
+    let key = serde_json::to_string(&entity.isgl1_key).unwrap();
+    db.execute_query(&format!(
+        "?[ISGL1_key, lsp_meta_data] <- [[{}, {}]]\n:update CodeGraph {{ ISGL1_key => lsp_meta_data }}",
+        key, legacy
+    ))
+    .await
+    .unwrap();
+
+    let read = db.get_entity(&entity.isgl1_key).await.unwrap().lsp_metadata.unwrap();
+    assert_eq!(read.hover.as_deref(), Some("struct Legacy"));
+    assert_eq!(read.references_count(), 0);
+
+    assert_eq!(db.upgrade_legacy_lsp_metadata().await.unwrap(), 1);
+    assert_eq!(db.upgrade_legacy_lsp_metadata().await.unwrap(), 0, "already structured");
+    let raw = db.raw_query("?[lsp_meta_data] := *CodeGraph{ lsp_meta_data }").await.unwrap();
+    let upgraded: LspMetadata = serde_json::from_str(raw.rows[0][0].get_str().unwrap()).unwrap();
+    assert_eq!(upgraded, read);
+}
+
+#[tokio::test]
+async fn test_upsert_keeps_created_at_and_updates_modified_at() {
+    let db = CozoDbStorage::new("mem").await.unwrap().with_access_logging(true);
+    db.ensure_schema().await.unwrap();
+    let key = "test-file-rs-TestStruct";
+    let day = |d: u32| chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 1, d, 0, 0, 0).unwrap();
+
+    let mut first = create_test_entity_with_key(key);
+    first.metadata = EntityMetadata::at(day(1));
+    first.metadata.additional.insert("owner".to_string(), "core".to_string());
+    db.upsert_entity(&first, MergePolicy::default()).await.unwrap();
+    db.get_entity(key).await.unwrap();
+
+    let mut second = create_test_entity_with_key(key);
+    second.metadata = EntityMetadata::at(day(2));
+    second.current_code = Some("struct TestStruct { field: u8 }".to_string());
+    db.upsert_entity(&second, MergePolicy::default()).await.unwrap();
+
+    let stored = db.get_entity(key).await.unwrap();
+    assert_eq!(stored.metadata.created_at, day(1));
+    assert_eq!(stored.metadata.modified_at, day(2));
+    assert_eq!(stored.metadata.additional.get("owner").map(String::as_str), Some("core"));
+    assert_eq!(stored.current_code, second.current_code);
+    assert_eq!(db.get_access_stats(key).await.unwrap().unwrap().query_count, 2);
+    let listed = db.get_all_entities().await.unwrap();
+    assert_eq!(listed[0].metadata.created_at, day(1), "every read path sees the created_at column");
+    assert_eq!(listed[0].metadata.additional, stored.metadata.additional);
+
+    db.upsert_entity(&second, MergePolicy::OVERWRITE).await.unwrap();
+    assert_eq!(db.get_access_stats(key).await.unwrap(), None, "counts restart");
+    let stored = db.get_entity(key).await.unwrap();
+    assert_eq!(stored.metadata.created_at, day(2));
+    assert!(stored.metadata.additional.is_empty());
+}
@@ -0,0 +1,80 @@
+//! Parquet export round trip (run with `--features parquet`)
+
+#![cfg(feature = "parquet")]
+
+use arrow::array::{Array, StringArray, UInt32Array};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parseltongue_core::parquet_export::write_entities_parquet;
+use parseltongue_core::*;
+use std::path::PathBuf;
+
+fn entity(key: &str, name: &str, start: u32, end: u32) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(start, end).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity = CodeEntity::new(key.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(format!("fn {}() {{}}", name));
+    entity.future_code = entity.current_code.clone();
+    entity
+}
+
+#[tokio::test]
+async fn test_written_parquet_reads_back_with_typed_columns() {
+    let storage = CozoDbStorage::new("mem").await.unwrap();
+    storage.create_schema().await.unwrap();
+    storage.insert_entity(&entity("rust:fn:alpha:src_lib_rs:1-3", "alpha", 1, 3)).await.unwrap();
+    storage.insert_entity(&entity("rust:fn:beta:src_lib_rs:5-9", "beta", 5, 9)).await.unwrap();
+    let mut entities = storage.get_all_entities().await.unwrap();
+    entities.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("entities.parquet");
+    let written = write_entities_parquet(&entities, std::fs::File::create(&path).unwrap(), true).unwrap();
+    assert_eq!(written, 2);
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches: Vec<_> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+    let batch = &batches[0];
+    let column = |name: &str| batch.column(batch.schema().index_of(name).unwrap()).clone();
+    let names = column("name");
+    let names = names.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((names.value(0), names.value(1)), ("alpha", "beta"));
+    let ends = column("line_end");
+    let ends = ends.as_any().downcast_ref::<UInt32Array>().unwrap();
+    assert_eq!((ends.value(0), ends.value(1)), (3, 9));
+    let code = column("current_code");
+    assert_eq!(code.as_any().downcast_ref::<StringArray>().unwrap().value(1), "fn beta() {}");
+    assert!(column("documentation").is_null(0));
+}
+
+#[test]
+fn test_code_columns_are_opt_in() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("entities.parquet");
+    let entities = [entity("rust:fn:alpha:src_lib_rs:1-3", "alpha", 1, 3)];
+    write_entities_parquet(&entities, std::fs::File::create(&path).unwrap(), false).unwrap();
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+    let schema = builder.schema();
+    assert!(schema.index_of("isgl1_key").is_ok());
+    assert!(schema.index_of("current_code").is_err());
+    assert!(schema.index_of("future_code").is_err());
+}
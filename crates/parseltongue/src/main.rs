@@ -24,6 +24,12 @@ use parseltongue_core::entities::{
     TddClassification, EntityClass, TestabilityLevel, ComplexityLevel, RiskLevel,
     EntityMetadata,
 };
+use parseltongue_core::clock::Clock;
+use parseltongue_core::output_sink::{sink_for_output, JsonCaseSink, OutputSink};
+use parseltongue_core::serializers::JsonCase;
+
+mod pipeline;
+mod repl;
 
 /// Build a new CodeEntity for CREATE action
 ///
@@ -36,16 +42,19 @@ use parseltongue_core::entities::{
 /// # Arguments
 /// * `isgl1_key` - Entity key in format: "filepath-filename-interface" or full ISGL1 format
 /// * `future_code` - Code content for the new entity
+/// * `clock` - Source of the `created_at`/`modified_at` timestamps
 ///
 /// # Returns
 /// * `Result<CodeEntity>` - Constructed entity ready for insertion
-fn build_create_entity(isgl1_key: &str, future_code: String) -> Result<CodeEntity> {
+fn build_create_entity(isgl1_key: &str, future_code: String, clock: &dyn Clock) -> Result<CodeEntity> {
     // Parse file path and entity name from ISGL1 key
     let (file_path, entity_name, language) = parse_isgl1_key_components(isgl1_key)?;
 
-    // Calculate hash before consuming future_code
+    // Calculate hashes before consuming future_code
     let content_hash = calculate_hash(&future_code);
-    let now = chrono::Utc::now();
+    let semantic_hash = parseltongue_core::semantic_hash::semantic_hash(&future_code, language)
+        .unwrap_or_default();
+    let now = clock.now();
 
     // Construct entity using functional composition
     let entity = CodeEntity {
@@ -77,6 +86,8 @@ fn build_create_entity(isgl1_key: &str, future_code: String) -> Result<CodeEntit
             created_at: now,
             modified_at: now,
             content_hash,
+            semantic_hash,
+            complexity_score: None,
             additional: HashMap::new(),
         },
         // v0.9.0: Add mandatory entity_class field
@@ -88,30 +99,41 @@ fn build_create_entity(isgl1_key: &str, future_code: String) -> Result<CodeEntit
 
 /// Parse ISGL1 key into components (pure function)
 fn parse_isgl1_key_components(key: &str) -> Result<(PathBuf, String, Language)> {
-    // Support both simple "filepath-filename-interface" and full ISGL1 formats
-    let parts: Vec<&str> = key.split(':').collect();
-
-    let (file_str, name) = if parts.len() >= 4 {
-        // Full format: "rust:fn:name:file_path:start-end"
-        (parts[3], parts[2].to_string())
-    } else {
-        // Simple format: "filepath-filename-interface" - parse backwards
-        let segments: Vec<&str> = key.rsplitn(3, '-').collect();
-        if segments.len() < 2 {
-            anyhow::bail!("Invalid ISGL1 key format. Expected 'filepath-filename-interface' or 'lang:type:name:path:range'");
-        }
-        let interface = segments[0];
-        let filepath = segments[2];
-        (filepath, interface.to_string())
+    let language_of = |name: &str, file_path: &PathBuf| {
+        Language::all()
+            .into_iter()
+            .find(|language| language.to_string() == name)
+            .or_else(|| Language::from_file_path(file_path))
+            .unwrap_or(Language::Rust)
     };
 
-    let file_path = PathBuf::from(file_str);
+    // Full ISGL1 keys, in either `KeyFormat`, carry their language
+    if let Ok(components) = parseltongue_core::isgl1_key::parse_key(key) {
+        let file_path = PathBuf::from(&components.file_path);
+        let language = language_of(&components.language, &file_path);
+        return Ok((file_path, components.name, language));
+    }
+
+    // Without a line range: "rust:fn:name:file_path"
+    let parts: Vec<&str> = key.split(':').collect();
+    if parts.len() == 4 {
+        let file_path = PathBuf::from(parts[3]);
+        let language = language_of(parts[0], &file_path);
+        return Ok((file_path, parts[2].to_string(), language));
+    }
+
+    // Simple format: "filepath-filename-interface" - parse backwards
+    let segments: Vec<&str> = key.rsplitn(3, '-').collect();
+    if segments.len() < 3 {
+        anyhow::bail!("Invalid ISGL1 key format. Expected 'filepath-filename-interface' or 'lang:type:name:path:range'");
+    }
+    let file_path = PathBuf::from(segments[2]);
 
     // Infer language from file extension
     let language = Language::from_file_path(&file_path)
         .unwrap_or(Language::Rust); // Default to Rust if cannot infer
 
-    Ok((file_path, name, language))
+    Ok((file_path, segments[0].to_string(), language))
 }
 
 /// Build default language-specific signature (pure function)
@@ -161,6 +183,9 @@ async fn main() -> Result<()> {
         Some(("pt02-level02", sub_matches)) => {
             run_pt02_level02(sub_matches).await
         }
+        Some(("pt02-compare-levels", sub_matches)) => {
+            run_pt02_compare_levels(sub_matches).await
+        }
         Some(("pt03-llm-to-cozodb-writer", sub_matches)) => {
             run_llm_to_cozodb_writer(sub_matches).await
         }
@@ -176,6 +201,43 @@ async fn main() -> Result<()> {
         Some(("pt07", sub_matches)) => {
             run_pt07(sub_matches).await
         }
+        Some(("skeleton", sub_matches)) => {
+            run_skeleton(sub_matches).await
+        }
+        Some(("db-check", sub_matches)) => {
+            run_db_check(sub_matches).await
+        }
+        Some(("db-compact", sub_matches)) => {
+            run_db_compact(sub_matches).await
+        }
+        Some(("db-copy", sub_matches)) => {
+            run_db_copy(sub_matches).await
+        }
+        Some(("diff-entities", sub_matches)) => {
+            run_diff_entities(sub_matches).await
+        }
+        Some(("api-report", sub_matches)) => {
+            run_api_report(sub_matches).await
+        }
+        Some(("export-parquet", sub_matches)) => {
+            run_export_parquet(sub_matches).await
+        }
+        Some(("repl", sub_matches)) => {
+            run_repl(sub_matches).await
+        }
+        Some(("pipeline", sub_matches)) => {
+            run_pipeline(sub_matches).await
+        }
+        Some(("path", sub_matches)) => {
+            run_path(sub_matches).await
+        }
+        Some(("graph-stats", sub_matches)) => {
+            run_graph_stats(sub_matches).await
+        }
+        Some(("languages", _)) => {
+            run_languages();
+            Ok(())
+        }
         _ => {
             println!("{}", style("Parseltongue CLI Toolkit").blue().bold());
             println!("{}", style("Ultra-minimalist code analysis and modification toolkit").blue());
@@ -189,17 +251,241 @@ async fn main() -> Result<()> {
             println!("    pt02-level00                       - Pure edge list (~2-5K tokens) [RECOMMENDED]");
             println!("    pt02-level01                       - Entity + ISG + Temporal (~30K tokens)");
             println!("    pt02-level02                       - + Type system (~60K tokens)");
+            println!("    pt02-compare-levels                - What each level adds, without writing files");
             println!("");
             println!("  pt03-llm-to-cozodb-writer            - Write LLM changes to temporal state (Tool 3: Edit)");
             println!("  pt04-syntax-preflight-validator      - Validate syntax of proposed changes (Tool 4: Validate)");
             println!("  pt05-llm-cozodb-to-diff-writer       - Generate CodeDiff.json (Tool 5: Diff)");
             println!("  pt06-cozodb-make-future-code-current - Reset database state (Tool 6: Reset)");
             println!("  pt07                                 - Visual analytics (Tool 7: Visualize)");
+            println!("  skeleton                             - Interface-only view of one file");
+            println!("  db-check                             - Report entities violating temporal invariants");
+            println!("  db-compact                           - Drop applied deletes and compact storage");
+            println!("  db-copy                              - Copy a filtered subgraph into another database");
+            println!("  diff-entities                        - Compare two entities' signatures for API breaks");
+            println!("  api-report                           - Public API changes between two databases, rated by semver");
+            println!("  export-parquet                       - Entity inventory as a Parquet file (parquet feature)");
+            println!("  repl                                 - Interactive shell for exploring the graph");
+            println!("  pipeline                             - Run several tools in order with one summary");
+            println!("  path                                 - Shortest dependency path between two entities");
+            println!("  graph-stats                          - Fan-in, fan-out and betweenness per entity");
+            println!("  languages                            - List languages with a grammar in this build");
             Ok(())
         }
     }
 }
 
+/// `--fail-on-warnings`, shared by pt01, pt02 and pt04 so strict CI has one knob
+fn fail_on_warnings_arg() -> Arg {
+    Arg::new("fail-on-warnings")
+        .long("fail-on-warnings")
+        .help("Exit non-zero on warnings too (skipped files, orphan edges, unknown keys, name mismatches)")
+        .action(clap::ArgAction::SetTrue)
+}
+
+/// `--json-case`, shared by the pt02 levels and pt05 so JS consumers get
+/// camelCase without a translation layer
+fn json_case_arg() -> Arg {
+    Arg::new("json-case")
+        .long("json-case")
+        .value_name("CASE")
+        .help("Field naming for JSON output")
+        .value_parser(["snake", "camel"])
+        .default_value("snake")
+}
+
+/// Wrap `sink` so JSON artifacts use the `--json-case` field naming
+fn with_json_case(matches: &ArgMatches, sink: Box<dyn OutputSink>) -> Box<dyn OutputSink> {
+    let case = matches
+        .get_one::<String>("json-case")
+        .and_then(|case| case.parse::<JsonCase>().ok())
+        .unwrap_or_default();
+    match case {
+        JsonCase::Snake => sink,
+        case => Box::new(JsonCaseSink::new(sink, case)),
+    }
+}
+
+/// `--where-clause` of a pt02 level, with its own `examples`
+fn pt02_where_clause_arg(examples: &str) -> Arg {
+    Arg::new("where-clause")
+        .long("where-clause")
+        .help("Datalog WHERE clause (use 'ALL' for everything)")
+        .long_help(format!(
+            "Datalog WHERE clause or 'ALL' (MANDATORY)\n\nExamples:\n  --where-clause \"ALL\"\n  {}\n\nDatalog syntax:\n  - AND: Use comma (,)     NOT &&\n  - OR: Use semicolon (;)  NOT ||\n  - Equality: Use =        NOT ==",
+            examples
+        ))
+        .required(true)
+}
+
+/// `--output` of a pt02 level, defaulting to `default`
+fn pt02_output_arg(default: &'static str) -> Arg {
+    Arg::new("output")
+        .long("output")
+        .short('o')
+        .help("Output JSON file path or http(s):// URL (requires the http-sink feature)")
+        .default_value(default)
+}
+
+/// `--include-code` of the entity levels (1 and 2)
+fn pt02_include_code_arg() -> Arg {
+    Arg::new("include-code")
+        .long("include-code")
+        .help("Include current_code field: 0=signatures only (cheap), 1=with code (expensive)")
+        .value_parser(["0", "1"])
+        .required(true)
+}
+
+/// Flags every pt02 level takes: database, key selection, sampling, output
+/// shape, key validation and the explain / count-only fast paths
+fn pt02_common_args() -> Vec<Arg> {
+    vec![
+        Arg::new("db")
+            .long("db")
+            .help("Database file path")
+            .default_value("parseltongue.db"),
+        Arg::new("verbose")
+            .long("verbose")
+            .short('v')
+            .help("Show progress and token estimates")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("format")
+            .long("format")
+            .help("json (JSON + TOON) or markdown (also writes a .md per JSON file)")
+            .value_parser(["json", "markdown"])
+            .default_value("json"),
+        Arg::new("keys")
+            .long("keys")
+            .help("Export only these ISGL1 keys (comma-separated); missing keys are reported")
+            .value_delimiter(',')
+            .action(clap::ArgAction::Append),
+        Arg::new("keys-file")
+            .long("keys-file")
+            .help("Export only the ISGL1 keys listed in this file (one per line)"),
+        Arg::new("with-deps")
+            .long("with-deps")
+            .help("With --keys/--keys-file: also export each selected entity's direct dependencies")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("sample")
+            .long("sample")
+            .help("Export a deterministic sample of N entities (hash of isgl1_key, stable across runs)")
+            .value_parser(clap::value_parser!(usize))
+            .conflicts_with("sample-pct"),
+        Arg::new("sample-pct")
+            .long("sample-pct")
+            .help("Export a deterministic sample of this percentage of entities (0-100)")
+            .value_parser(clap::value_parser!(f64)),
+        Arg::new("sample-by")
+            .long("sample-by")
+            .help("With --sample/--sample-pct: keep the proportions of this field's values")
+            .value_parser(["entity_type", "entity_class", "file_path"]),
+        Arg::new("compact")
+            .long("compact")
+            .help("Write single-line JSON instead of pretty-printed")
+            .action(clap::ArgAction::SetTrue),
+        json_case_arg(),
+        Arg::new("no-timestamp")
+            .long("no-timestamp")
+            .help("Leave export_metadata.timestamp out so identical data exports byte-identically")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("validate-keys")
+            .long("validate-keys")
+            .help("Report edges whose endpoints have no entity before exporting")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("strict")
+            .long("strict")
+            .help("With --validate-keys: fail when orphan edges exist")
+            .requires("validate-keys")
+            .action(clap::ArgAction::SetTrue),
+        fail_on_warnings_arg(),
+        Arg::new("explain")
+            .long("explain")
+            .help("Print the generated Datalog queries to stderr before running them")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("dry-run")
+            .long("dry-run")
+            .help("With --explain: print the queries without running the export")
+            .requires("explain")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("count-only")
+            .long("count-only")
+            .help("Print how many rows each export file would hold, without fetching them or writing files")
+            .action(clap::ArgAction::SetTrue),
+    ]
+}
+
+/// Flags of the entity levels (1 and 2): time window, redaction, entity
+/// filters, provenance, doc dedup and ordering
+fn pt02_entity_args() -> Vec<Arg> {
+    vec![
+        Arg::new("since")
+            .long("since")
+            .help("Only entities modified after this time (RFC 3339 or YYYY-MM-DD)")
+            .conflicts_with("since-commit"),
+        Arg::new("since-commit")
+            .long("since-commit")
+            .value_name("REV")
+            .help("Only entities modified after this git revision was committed (e.g. v1.2)"),
+        Arg::new("repo")
+            .long("repo")
+            .help("Git repository --since-commit is resolved in")
+            .default_value("."),
+        Arg::new("redact")
+            .long("redact")
+            .help("Replace code with <redacted: N bytes, sha256=...> (signatures, edges and metadata kept)")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("redact-docs")
+            .long("redact-docs")
+            .help("With --redact: redact doc comments too")
+            .requires("redact")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("strip-comments")
+            .long("strip-comments")
+            .help("Remove comments from exported code (tree-sitter, string literals untouched); doc comments too unless --keep-doc-comments")
+            .conflicts_with("redact")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("keep-doc-comments")
+            .long("keep-doc-comments")
+            .help("With --strip-comments: keep doc comments")
+            .requires("strip-comments")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("exclude-tests")
+            .long("exclude-tests")
+            .help("Leave out entities classified as tests at ingestion")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("exclude-generated")
+            .long("exclude-generated")
+            .help("Leave out entities from files flagged as generated or vendored at ingestion")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("exclude-private")
+            .long("exclude-private")
+            .help("Leave out entities whose visibility is not public")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("entity-type")
+            .long("entity-type")
+            .help("Only entities of this type (repeat to allow several); ANDed with --where-clause")
+            .value_parser(EntityType::column_names())
+            .action(clap::ArgAction::Append),
+        Arg::new("visibility")
+            .long("visibility")
+            .help("Only entities with this visibility; ANDed with --where-clause")
+            .value_parser(["public", "private", "protected", "crate", "module"]),
+        Arg::new("include-provenance")
+            .long("include-provenance")
+            .help("Add each entity's last writing tool and timestamp (provenance field)")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("dedup-docs")
+            .long("dedup-docs")
+            .help("Write doc comments shared by several entities once in shared_docs; entities reference them by doc_ref")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("order")
+            .long("order")
+            .help("Entity order: key (file, line, key) or topological (dependencies first; cycles listed in the output)")
+            .value_parser(["key", "topological"])
+            .default_value("key"),
+    ]
+}
+
 fn build_cli() -> Command {
     Command::new("parseltongue")
         .version(env!("CARGO_PKG_VERSION"))
@@ -240,76 +526,167 @@ fn build_cli() -> Command {
                         .short('q')
                         .help("Suppress output")
                         .action(clap::ArgAction::SetTrue),
-                ),
-        )
-        .subcommand(
-            Command::new("pt02-level00")
-                .about("Tool 2a: Export pure edge list (Level 0 - ~2-5K tokens) [RECOMMENDED]")
-                .long_about("Export dependency edges only for graph visualization and dependency analysis.\n\nExample:\n  parseltongue pt02-level00 --where-clause \"ALL\" --output edges.json")
+                )
                 .arg(
-                    Arg::new("where-clause")
-                        .long("where-clause")
-                        .help("Datalog WHERE clause (use 'ALL' for everything)")
-                        .long_help("Datalog WHERE clause or 'ALL' (MANDATORY)\n\nExamples:\n  --where-clause \"ALL\"\n  --where-clause \"edge_type = 'depends_on'\"\n  --where-clause \"from_key ~ 'rust:fn'\"\n\nDatalog syntax:\n  - AND: Use comma (,)     NOT &&\n  - OR: Use semicolon (;)  NOT ||\n  - Equality: Use =        NOT ==")
-                        .required(true),
+                    Arg::new("no-resume")
+                        .long("no-resume")
+                        .help("Ignore the checkpoint of an interrupted run and re-ingest every file")
+                        .action(clap::ArgAction::SetTrue),
                 )
                 .arg(
-                    Arg::new("output")
-                        .long("output")
-                        .short('o')
-                        .help("Output JSON file path")
-                        .default_value("ISGLevel00.json"),
+                    Arg::new("max-file-size")
+                        .long("max-file-size")
+                        .value_name("SIZE")
+                        .help("Skip (and report) files larger than SIZE, e.g. 512KB, 10MB, 1.5GB")
+                        .value_parser(pt01_folder_to_cozodb_streamer::cli::parse_human_size)
+                        .default_value("100MB"),
                 )
                 .arg(
-                    Arg::new("db")
-                        .long("db")
-                        .help("Database file path")
-                        .default_value("parseltongue.db"),
+                    Arg::new("file-read-timeout")
+                        .long("file-read-timeout")
+                        .value_name("SECS")
+                        .help("Skip (and report) files not read within SECS seconds, e.g. on a stalled network mount")
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("30"),
                 )
                 .arg(
-                    Arg::new("verbose")
-                        .long("verbose")
-                        .short('v')
-                        .help("Show progress and token estimates")
+                    Arg::new("dialect")
+                        .long("dialect")
+                        .value_name("LANG=DIALECT")
+                        .help("Pin a grammar dialect, e.g. python=py3 or rust=2015 (repeatable)")
+                        .value_parser(pt01_folder_to_cozodb_streamer::dialect::parse_dialect_arg)
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("extra-query")
+                        .long("extra-query")
+                        .value_name("LANG=FILE")
+                        .help("Extract more entities with a tree-sitter query file, e.g. rust=macros.scm (repeatable)")
+                        .value_parser(pt01_folder_to_cozodb_streamer::extra_queries::parse_extra_query_arg)
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("store-spans-only")
+                        .long("store-spans-only")
+                        .help("Store byte spans instead of code text (source files must stay in place)")
                         .action(clap::ArgAction::SetTrue),
-                ),
+                )
+                .arg(
+                    Arg::new("git-diff-base")
+                        .long("git-diff-base")
+                        .value_name("REF")
+                        .help("Only ingest files changed in <REF>...HEAD (e.g. origin/main)"),
+                )
+                .arg(
+                    Arg::new("absolute-paths")
+                        .long("absolute-paths")
+                        .help("Store absolute file paths instead of paths relative to the directory")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("follow-symlinks")
+                        .long("follow-symlinks")
+                        .help("Descend into symlinked directories (each directory is visited once)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("min-entity-lines")
+                        .long("min-entity-lines")
+                        .value_name("N")
+                        .help("Skip entities spanning fewer than N lines (e.g. one-line getters)")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::new("keep-filtered-signatures")
+                        .long("keep-filtered-signatures")
+                        .help("With --min-entity-lines: store small entities without their code instead of skipping them")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("profile")
+                        .long("profile")
+                        .help("Print time spent per phase (walk, read, parse, key generation, database writes)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("memory-budget")
+                        .long("memory-budget")
+                        .value_name("SIZE")
+                        .help("Skip files whose estimated parse memory exceeds this, e.g. 512MB")
+                        .value_parser(pt01_folder_to_cozodb_streamer::cli::parse_human_size),
+                )
+                .arg(
+                    Arg::new("generated-marker")
+                        .long("generated-marker")
+                        .value_name("MARKER")
+                        .help("Also flag files as generated by this path pattern (dir/, *.ext) or header text (repeatable; defaults: @generated, // GENERATED, vendor/, *.pb.rs)")
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("keep-tests")
+                        .long("keep-tests")
+                        .help("Store test entities too (skipped by default), e.g. for pt02 --pair-tests")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("lsp")
+                        .long("lsp")
+                        .help("Add rust-analyzer hover, definition, references and diagnostics to Rust entities (needs rust-analyzer on PATH)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("key-format")
+                        .long("key-format")
+                        .help("ISGL1 key layout: colon (rust:fn:name:path:1-5) or dash (path-name-fn-rust-1-5)")
+                        .value_parser(["colon", "dash"])
+                        .default_value("colon"),
+                )
+                .arg(
+                    Arg::new("report-errors")
+                        .long("report-errors")
+                        .help("List recorded syntax errors after ingesting; exit non-zero if any")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(fail_on_warnings_arg()),
+        )
+        .subcommand(
+            Command::new("pt02-level00")
+                .about("Tool 2a: Export pure edge list (Level 0 - ~2-5K tokens) [RECOMMENDED]")
+                .long_about("Export dependency edges only for graph visualization and dependency analysis.\n\nExample:\n  parseltongue pt02-level00 --where-clause \"ALL\" --output edges.json")
+                .arg(pt02_where_clause_arg("--where-clause \"edge_type = 'depends_on'\"\n  --where-clause \"from_key ~ 'rust:fn'\""))
+                .arg(pt02_output_arg("ISGLevel00.json"))
+                .args(pt02_common_args()),
         )
         .subcommand(
             Command::new("pt02-level01")
                 .about("Tool 2b: Export entities with ISG + temporal (Level 1 - ~30K tokens)")
                 .long_about("Export entities with Interface Signature Graph and temporal state.\n\nExamples:\n  # Signatures only (CHEAP - ~30K tokens)\n  parseltongue pt02-level01 --include-code 0 --where-clause \"ALL\" --output entities.json\n\n  # With code (EXPENSIVE - 100× more tokens)\n  parseltongue pt02-level01 --include-code 1 --where-clause \"future_action != null\" --output changes.json")
+                .arg(pt02_include_code_arg())
+                .arg(pt02_where_clause_arg("--where-clause \"is_public = true, entity_type = 'fn'\"\n  --where-clause \"future_action != null\""))
+                .arg(pt02_output_arg("ISGLevel01.json"))
+                .args(pt02_common_args())
+                .args(pt02_entity_args())
                 .arg(
-                    Arg::new("include-code")
-                        .long("include-code")
-                        .help("Include current_code field: 0=signatures only (cheap), 1=with code (expensive)")
-                        .value_parser(["0", "1"])
-                        .required(true),
-                )
-                .arg(
-                    Arg::new("where-clause")
-                        .long("where-clause")
-                        .help("Datalog WHERE clause (use 'ALL' for everything)")
-                        .long_help("Datalog WHERE clause or 'ALL' (MANDATORY)\n\nExamples:\n  --where-clause \"ALL\"\n  --where-clause \"is_public = true, entity_type = 'fn'\"\n  --where-clause \"future_action != null\"\n\nDatalog syntax:\n  - AND: Use comma (,)\n  - OR: Use semicolon (;)\n  - Equality: Use =")
-                        .required(true),
-                )
-                .arg(
-                    Arg::new("output")
-                        .long("output")
-                        .short('o')
-                        .help("Output JSON file path")
-                        .default_value("ISGLevel01.json"),
+                    Arg::new("external-sort")
+                        .long("external-sort")
+                        .help("Sort through temp-file runs for graphs too large for memory (JSON only; automatic above 500k entities)")
+                        .action(clap::ArgAction::SetTrue),
                 )
+                .mut_arg("dedup-docs", |arg| arg.conflicts_with("external-sort"))
+                .mut_arg("order", |arg| arg.conflicts_with("external-sort"))
                 .arg(
-                    Arg::new("db")
-                        .long("db")
-                        .help("Database file path")
-                        .default_value("parseltongue.db"),
+                    Arg::new("pair-tests")
+                        .long("pair-tests")
+                        .help("List each entity's tests (test_<name>, <name>_test) in its tests field; needs a database ingested with --keep-tests")
+                        .conflicts_with_all(["external-sort", "as-context"])
+                        .action(clap::ArgAction::SetTrue),
                 )
                 .arg(
-                    Arg::new("verbose")
-                        .long("verbose")
-                        .short('v')
-                        .help("Show progress and token estimates")
+                    Arg::new("as-context")
+                        .long("as-context")
+                        .help("Write one CodeGraphContext JSON (pt03's shape: signatures, TDD, LSP metadata; no code) instead of the Level 1 files")
+                        .conflicts_with_all(["format", "external-sort", "redact", "dedup-docs"])
                         .action(clap::ArgAction::SetTrue),
                 ),
         )
@@ -317,39 +694,34 @@ fn build_cli() -> Command {
             Command::new("pt02-level02")
                 .about("Tool 2c: Export entities with type system (Level 2 - ~60K tokens)")
                 .long_about("Export entities with full type system information for type-safe refactoring.\n\nExamples:\n  # Find async functions\n  parseltongue pt02-level02 --include-code 0 --where-clause \"is_async = true\" --output async.json\n\n  # Find unsafe code\n  parseltongue pt02-level02 --include-code 0 --where-clause \"is_unsafe = true\" --output unsafe.json")
-                .arg(
-                    Arg::new("include-code")
-                        .long("include-code")
-                        .help("Include current_code field: 0=signatures only (cheap), 1=with code (expensive)")
-                        .value_parser(["0", "1"])
-                        .required(true),
-                )
+                .arg(pt02_include_code_arg())
+                .arg(pt02_where_clause_arg("--where-clause \"is_async = true\"\n  --where-clause \"is_unsafe = true\"\n  --where-clause \"is_public = true\""))
+                .arg(pt02_output_arg("ISGLevel02.json"))
+                .args(pt02_common_args())
+                .args(pt02_entity_args()),
+        )
+        .subcommand(
+            Command::new("pt02-compare-levels")
+                .about("Compare what Levels 0, 1 and 2 export for one filter, without writing files")
+                .long_about("Run all three PT02 exporters against the same filter and print entity count,\nedge count, estimated tokens and the fields each level adds.\n\nExample:\n  parseltongue pt02-compare-levels --where-clause \"ALL\"")
                 .arg(
                     Arg::new("where-clause")
                         .long("where-clause")
                         .help("Datalog WHERE clause (use 'ALL' for everything)")
-                        .long_help("Datalog WHERE clause or 'ALL' (MANDATORY)\n\nExamples:\n  --where-clause \"ALL\"\n  --where-clause \"is_async = true\"\n  --where-clause \"is_unsafe = true\"\n  --where-clause \"is_public = true\"\n\nDatalog syntax:\n  - AND: Use comma (,)\n  - OR: Use semicolon (;)\n  - Equality: Use =")
-                        .required(true),
+                        .default_value("ALL"),
                 )
                 .arg(
-                    Arg::new("output")
-                        .long("output")
-                        .short('o')
-                        .help("Output JSON file path")
-                        .default_value("ISGLevel02.json"),
+                    Arg::new("include-code")
+                        .long("include-code")
+                        .help("Estimate Levels 1-2 with code: 0=signatures only, 1=with code")
+                        .value_parser(["0", "1"])
+                        .default_value("0"),
                 )
                 .arg(
                     Arg::new("db")
                         .long("db")
                         .help("Database file path")
                         .default_value("parseltongue.db"),
-                )
-                .arg(
-                    Arg::new("verbose")
-                        .long("verbose")
-                        .short('v')
-                        .help("Show progress and token estimates")
-                        .action(clap::ArgAction::SetTrue),
                 ),
         )
         .subcommand(
@@ -373,6 +745,24 @@ fn build_cli() -> Command {
                         .long("future-code")
                         .help("Future code content (required for create/edit)"),
                 )
+                .arg(
+                    Arg::new("expect-hash")
+                        .long("expect-hash")
+                        .value_name("VERSION")
+                        .help("Fail with a conflict if the entity version differs (edit/delete)"),
+                )
+                .arg(
+                    Arg::new("update-edges")
+                        .long("update-edges")
+                        .help("After an edit, re-parse the future code and replace the entity's outgoing edges")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("idempotency-key")
+                        .long("idempotency-key")
+                        .value_name("KEY")
+                        .help("Record this write under KEY; a retry with the same KEY returns the earlier result without writing again"),
+                )
                 .arg(
                     Arg::new("db")
                         .long("db")
@@ -395,7 +785,33 @@ fn build_cli() -> Command {
                         .short('v')
                         .help("Show detailed errors")
                         .action(clap::ArgAction::SetTrue),
-                ),
+                )
+                .arg(
+                    Arg::new("normalize-whitespace")
+                        .long("normalize-whitespace")
+                        .alias("normalize")
+                        .help("Write valid future_code back with tabs expanded, trailing whitespace stripped and a final newline")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("check-name-match")
+                        .long("check-name-match")
+                        .help("Warn about entities whose future_code defines an item other than the one their key names (a warning: add --fail-on-warnings to fail the run on it)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print a JSON report (per-entity validity, positioned errors) instead of the text summary")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("only-new")
+                        .long("only-new")
+                        .help("Skip entities whose future_code passed cleanly in an earlier run (recorded next to the database)")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(fail_on_warnings_arg()),
         )
         .subcommand(
             Command::new("pt05-llm-cozodb-to-diff-writer")
@@ -404,14 +820,89 @@ fn build_cli() -> Command {
                     Arg::new("output")
                         .long("output")
                         .short('o')
-                        .help("Output JSON file")
+                        .help("Output JSON file or http(s):// URL (requires the http-sink feature)")
                         .required(true),
                 )
+                .arg(
+                    Arg::new("patch")
+                        .long("patch")
+                        .help("Also write a git-apply-compatible unified diff to this file"),
+                )
+                .arg(
+                    Arg::new("root")
+                        .long("root")
+                        .help("Project root containing the original sources (used by --patch)")
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("group-by-file")
+                        .long("group-by-file")
+                        .help("Write { \"files\": { path: [changes by line] } } instead of a flat changes array")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("detect-moves")
+                        .long("detect-moves")
+                        .help("Report a delete and a create with the same body as one MOVE change")
+                        .action(clap::ArgAction::SetTrue),
+                )
                 .arg(
                     Arg::new("db")
                         .long("db")
                         .help("Database file path")
                         .default_value("parseltongue.db"),
+                )
+                .arg(
+                    Arg::new("compact")
+                        .long("compact")
+                        .help("Write single-line JSON instead of pretty-printed")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(json_case_arg())
+                .arg(
+                    Arg::new("validate-keys")
+                        .long("validate-keys")
+                        .help("Report edges whose endpoints have no entity before exporting")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .help("With --validate-keys: fail when orphan edges exist")
+                        .requires("validate-keys")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .help("Before diffing, verify each edited or deleted entity's file under --root still holds its current_code")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("With --check: report drifted files but continue")
+                        .requires("check")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("apply")
+                        .long("apply")
+                        .help("Also splice the changes into the files under --root, through a crash-safe write journal")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("recover")
+                        .long("recover")
+                        .help("First finish the file writes of an interrupted --apply")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("line-ending")
+                        .long("line-ending")
+                        .help("Line ending --apply writes: match each file's existing one, or force LF / CRLF")
+                        .value_parser(["preserve", "lf", "crlf"])
+                        .default_value("preserve"),
                 ),
         )
         .subcommand(
@@ -465,25 +956,441 @@ fn build_cli() -> Command {
                                 .help("Include test entities (default: implementation-only)")
                                 .action(clap::ArgAction::SetTrue),
                         ),
+                )
+                .subcommand(
+                    Command::new("heatmap")
+                        .about("Most-depended-upon entities (in-degree heatmap)")
+                        .arg(
+                            Arg::new("db")
+                                .long("db")
+                                .help("Database file path")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("top")
+                                .long("top")
+                                .help("Number of entities to show")
+                                .value_parser(clap::value_parser!(usize))
+                                .default_value("10"),
+                        )
+                        .arg(
+                            Arg::new("include-tests")
+                                .long("include-tests")
+                                .help("Include test entities (default: implementation-only)")
+                                .action(clap::ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("output-dir")
+                                .long("output-dir")
+                                .help("Directory for the saved copy (created if missing; default: current directory)"),
+                        )
+                        .arg(
+                            Arg::new("filename-template")
+                                .long("filename-template")
+                                .help("Saved file name; placeholders {command}, {timestamp}, {db}")
+                                .default_value(pt07_visual_analytics_terminal::DEFAULT_FILENAME_TEMPLATE),
+                        ),
+                )
+                .subcommand(
+                    Command::new("pending")
+                        .about("Pending temporal changes (future_action set) before applying them")
+                        .arg(
+                            Arg::new("db")
+                                .long("db")
+                                .help("Database file path")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("top")
+                                .long("top")
+                                .help("Number of files to show")
+                                .value_parser(clap::value_parser!(usize))
+                                .default_value("5"),
+                        )
+                        .arg(
+                            Arg::new("sample")
+                                .long("sample")
+                                .help("Number of affected entities to list")
+                                .value_parser(clap::value_parser!(usize))
+                                .default_value("5"),
+                        )
+                        .arg(
+                            Arg::new("output-dir")
+                                .long("output-dir")
+                                .help("Directory for the saved copy (created if missing; default: current directory)"),
+                        )
+                        .arg(
+                            Arg::new("filename-template")
+                                .long("filename-template")
+                                .help("Saved file name; placeholders {command}, {timestamp}, {db}")
+                                .default_value(pt07_visual_analytics_terminal::DEFAULT_FILENAME_TEMPLATE),
+                        ),
                 ),
         )
-}
-
-async fn run_folder_to_cozodb_streamer(matches: &ArgMatches) -> Result<()> {
-    let directory = matches.get_one::<String>("directory").unwrap();
-    let db = matches.get_one::<String>("db").unwrap();
-    let verbose = matches.get_flag("verbose");
-    let quiet = matches.get_flag("quiet");
-
-    println!("{}", style("Running Tool 1: folder-to-cozodb-streamer").cyan());
-
-    // Create config (S01 ultra-minimalist: let tree-sitter decide what to parse)
-    let config = pt01_folder_to_cozodb_streamer::StreamerConfig {
-        root_dir: std::path::PathBuf::from(directory),
-        db_path: db.clone(),
-        max_file_size: 100 * 1024 * 1024,  // 100MB - no artificial limits
-        include_patterns: vec!["*".to_string()],  // ALL files - tree-sitter handles it
-        exclude_patterns: vec![
+        .subcommand(
+            Command::new("skeleton")
+                .about("Print a file's API surface (signatures + docs, bodies elided)")
+                .long_about(
+                    "Examples:\n  \
+                    parseltongue skeleton --file src/lib.rs --db rocksdb:parseltongue.db"
+                )
+                .arg(
+                    Arg::new("file")
+                        .long("file")
+                        .help("Source file path as indexed by pt01")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("db-check")
+                .about("Scan the database for entities violating temporal invariants")
+                .long_about(
+                    "Reports entities whose Future_Action disagrees with Future_Code \
+                    (e.g. an Edit with no future code). Exits non-zero if any are found.\n\n\
+                    Examples:\n  \
+                    parseltongue db-check --db rocksdb:parseltongue.db"
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("db-compact")
+                .about("Remove tombstoned entities and compact the database")
+                .long_about(
+                    "Deletes entities whose current and future versions are both gone \
+                    (applied deletes), then compacts the storage backend and reports \
+                    the disk space reclaimed.\n\n\
+                    Examples:\n  \
+                    parseltongue db-compact --db rocksdb:parseltongue.db"
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("db-copy")
+                .about("Copy matching entities and their edges into another database")
+                .long_about(
+                    "Copies the entities selected by --where (Datalog over CodeGraph columns, \
+                    as in pt02's --where-clause) into --to, creating its schema. Edges between \
+                    copied entities come along; edges to entities left behind are dropped \
+                    unless --keep-dangling is given.\n\n\
+                    Examples:\n  \
+                    parseltongue db-copy --from rocksdb:big.db --to rocksdb:sub.db \
+                    --where \"starts_with(file_path, 'crates/core/')\""
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .help("Source database")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .help("Target database (created if missing)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("where")
+                        .long("where")
+                        .help("Datalog conditions selecting the entities to copy (use 'ALL' for everything)")
+                        .default_value("ALL"),
+                )
+                .arg(
+                    Arg::new("keep-dangling")
+                        .long("keep-dangling")
+                        .help("Also copy edges whose other endpoint is not copied")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("diff-entities")
+                .about("Compare two entities' interface signatures, flagging breaking changes")
+                .long_about(
+                    "Field-by-field comparison of entity A (old API) against entity B (new API). \
+                    Exits non-zero when any change is breaking.\n\n\
+                    Examples:\n  \
+                    parseltongue diff-entities --a rust:fn:total:src_lib_rs:10-20 --b rust:fn:sum_total:src_lib_rs:10-22"
+                )
+                .arg(
+                    Arg::new("a")
+                        .long("a")
+                        .help("ISGL1 key of the old entity")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("b")
+                        .long("b")
+                        .help("ISGL1 key of the new entity")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("api-report")
+                .about("Compare the public API of two databases and rate the changes by semver")
+                .long_about(
+                    "Matches public entities by file, type and name. Removed or narrowed public \
+                    items and breaking signature changes need a major bump; additions and \
+                    compatible signature changes a minor one. Exits non-zero when a major \
+                    bump is required.\n\n\
+                    Examples:\n  \
+                    parseltongue api-report --db rocksdb:new.db --against rocksdb:old.db"
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database with the new version")
+                        .default_value("parseltongue.db"),
+                )
+                .arg(
+                    Arg::new("against")
+                        .long("against")
+                        .help("Database with the old version to compare against")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("export-parquet")
+                .about("Export entities to a Parquet file for data tooling")
+                .long_about(
+                    "Writes one row per entity with typed columns (key, name, type, class, \
+                    language, visibility, file, line range, temporal state, documentation), \
+                    ready for pandas, polars or DuckDB. Code text is left out unless \
+                    --include-code is given. Requires building with the `parquet` feature.\n\n\
+                    Examples:\n  \
+                    parseltongue export-parquet --db rocksdb:parseltongue.db --output entities.parquet\n  \
+                    parseltongue export-parquet --db rocksdb:parseltongue.db --output entities.parquet --include-code"
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Parquet file to write")
+                        .default_value("entities.parquet"),
+                )
+                .arg(
+                    Arg::new("include-code")
+                        .long("include-code")
+                        .help("Add current_code and future_code columns")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("repl")
+                .about("Explore the graph interactively")
+                .long_about(
+                    "Opens the database once and reads commands until quit or Ctrl-D: \
+                    get, deps, rdeps, find and query. History is kept in \
+                    ~/.parseltongue_history.\n\n\
+                    Examples:\n  \
+                    parseltongue repl --db rocksdb:parseltongue.db"
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("pipeline")
+                .about("Run several tools in order and summarize the outcome")
+                .long_about(
+                    "Runs each step against the same database, stopping at the first \
+                    failure unless --keep-going is set, then prints which steps \
+                    succeeded, failed or were skipped and how long each took.\n\n\
+                    Steps: pt01 (ingest --directory), pt03 (write --entity/--action/--future-code), \
+                    pt04 (validate), pt05 (diff to --output), pt06 (reset --directory).\n\n\
+                    Examples:\n  \
+                    parseltongue pipeline --steps pt01,pt04 --db rocksdb:parseltongue.db\n  \
+                    parseltongue pipeline --steps pt03,pt04,pt05 --db rocksdb:parseltongue.db \\\n    \
+                    --entity \"rust:fn:hello:src_lib_rs:4-6\" --action edit --future-code \"fn hello() {}\""
+                )
+                .arg(
+                    Arg::new("steps")
+                        .long("steps")
+                        .help("Comma-separated steps: pt01, pt03, pt04, pt05, pt06")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                )
+                .arg(
+                    Arg::new("keep-going")
+                        .long("keep-going")
+                        .help("Run the remaining steps after a failure")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("directory")
+                        .long("directory")
+                        .help("Project directory for pt01 and pt06")
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("entity")
+                        .long("entity")
+                        .help("pt03: ISGL1 key of the entity to change"),
+                )
+                .arg(
+                    Arg::new("action")
+                        .long("action")
+                        .help("pt03: create, edit or delete")
+                        .value_parser(["create", "edit", "delete"]),
+                )
+                .arg(
+                    Arg::new("future-code")
+                        .long("future-code")
+                        .help("pt03: future code (create/edit)"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("pt05: CodeDiff.json output path")
+                        .default_value("CodeDiff.json"),
+                ),
+        )
+        .subcommand(
+            Command::new("path")
+                .about("Shortest dependency path between two entities")
+                .long_about(
+                    "Breadth-first search along dependency edges (caller to callee), \
+                    printing each entity on the path from --from to --to. Search stops \
+                    after --max-hops edges to bound the cost on dense graphs.\n\n\
+                    Examples:\n  \
+                    parseltongue path --from rust:fn:main:src_main_rs:1-10 --to rust:fn:save:src_db_rs:40-52"
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .help("ISGL1 key the path starts at")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .help("ISGL1 key the path ends at")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("max-hops")
+                        .long("max-hops")
+                        .help("Longest path searched, in edges")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("32"),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("graph-stats")
+                .about("Fan-in, fan-out and betweenness of the most central entities")
+                .long_about(
+                    "Centrality over the dependency edges: in-degree (fan-in), out-degree \
+                    (fan-out) and betweenness, approximated from sampled sources on graphs \
+                    over 2000 entities. Entities with both fan-in and fan-out of 10 or more \
+                    are flagged as god objects.\n\n\
+                    Examples:\n  \
+                    parseltongue graph-stats --db rocksdb:parseltongue.db --top 20\n  \
+                    parseltongue graph-stats --sort-by in-degree"
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("N")
+                        .help("Entities to list")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("20"),
+                )
+                .arg(
+                    Arg::new("sort-by")
+                        .long("sort-by")
+                        .help("Metric the list is ranked by")
+                        .value_parser(["betweenness", "in-degree", "out-degree"])
+                        .default_value("betweenness"),
+                )
+                .arg(
+                    Arg::new("db")
+                        .long("db")
+                        .help("Database file path")
+                        .default_value("parseltongue.db"),
+                ),
+        )
+        .subcommand(
+            Command::new("languages")
+                .about("List the languages pt01 can parse in this build")
+                .long_about(
+                    "Languages without a compiled-in grammar are listed as unavailable; \
+                    pt01 reports their files as \"language not supported in this build\".\n\n\
+                    Examples:\n  \
+                    parseltongue languages"
+                ),
+        )
+}
+
+/// Print each known language and whether its grammar is compiled in
+fn run_languages() {
+    let available = pt01_folder_to_cozodb_streamer::available_languages();
+    for language in parseltongue_core::entities::Language::all() {
+        let extensions = language.file_extensions().join(", ");
+        if available.contains(&language) {
+            println!("{} {:<12} {}", style("✓").green(), language, extensions);
+        } else {
+            println!("{} {:<12} {} {}", style("✗").red(), language, extensions, style("(not in this build)").dim());
+        }
+    }
+}
+
+async fn run_folder_to_cozodb_streamer(matches: &ArgMatches) -> Result<()> {
+    let directory = matches.get_one::<String>("directory").unwrap();
+    let db = matches.get_one::<String>("db").unwrap();
+    let verbose = matches.get_flag("verbose");
+    let quiet = matches.get_flag("quiet");
+
+    println!("{}", style("Running Tool 1: folder-to-cozodb-streamer").cyan());
+
+    // Create config (S01 ultra-minimalist: let tree-sitter decide what to parse)
+    let config = pt01_folder_to_cozodb_streamer::StreamerConfig {
+        root_dir: std::path::PathBuf::from(directory),
+        db_path: db.clone(),
+        max_file_size: *matches.get_one::<usize>("max-file-size").unwrap(),
+        file_read_timeout: std::time::Duration::from_secs(*matches.get_one::<u64>("file-read-timeout").unwrap()),
+        include_patterns: vec!["*".to_string()],  // ALL files - tree-sitter handles it
+        exclude_patterns: vec![
             "target".to_string(),
             "node_modules".to_string(),
             ".git".to_string(),
@@ -495,6 +1402,46 @@ async fn run_folder_to_cozodb_streamer(matches: &ArgMatches) -> Result<()> {
         ],
         parsing_library: "tree-sitter".to_string(),
         chunking: "ISGL1".to_string(),
+        name_normalization: pt01_folder_to_cozodb_streamer::NameNormalizationPolicy::default(),
+        key_format: match matches.get_one::<String>("key-format").map(String::as_str) {
+            Some("dash") => pt01_folder_to_cozodb_streamer::KeyFormat::DashSimple,
+            _ => pt01_folder_to_cozodb_streamer::KeyFormat::ColonQualified,
+        },
+        checkpoint_path: pt01_folder_to_cozodb_streamer::IngestionCheckpoint::sidecar_path_for_db(db),
+        resume: !matches.get_flag("no-resume"),
+        checkpoint_interval: pt01_folder_to_cozodb_streamer::checkpoint::DEFAULT_CHECKPOINT_INTERVAL,
+        max_doc_len: pt01_folder_to_cozodb_streamer::doc_comments::DEFAULT_MAX_DOC_LEN,
+        language_dialects: matches
+            .get_many::<(parseltongue_core::entities::Language, String)>("dialect")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        store_spans_only: matches.get_flag("store-spans-only"),
+        git_diff_base: matches.get_one::<String>("git-diff-base").cloned(),
+        path_style: if matches.get_flag("absolute-paths") {
+            pt01_folder_to_cozodb_streamer::PathStyle::Absolute
+        } else {
+            pt01_folder_to_cozodb_streamer::PathStyle::Relative
+        },
+        follow_symlinks: matches.get_flag("follow-symlinks"),
+        min_entity_lines: *matches.get_one::<usize>("min-entity-lines").unwrap(),
+        keep_signatures_of_filtered: matches.get_flag("keep-filtered-signatures"),
+        profile: matches.get_flag("profile"),
+        memory_budget_bytes: matches.get_one::<usize>("memory-budget").copied(),
+        generated_markers: pt01_folder_to_cozodb_streamer::generated::default_generated_markers()
+            .into_iter()
+            .chain(matches.get_many::<String>("generated-marker").into_iter().flatten().cloned())
+            .collect(),
+        generated_header_lines: pt01_folder_to_cozodb_streamer::generated::DEFAULT_GENERATED_HEADER_LINES,
+        keep_test_entities: matches.get_flag("keep-tests"),
+        lsp: matches.get_flag("lsp"),
+        extra_queries: matches
+            .get_many::<(parseltongue_core::entities::Language, String)>("extra-query")
+            .into_iter()
+            .flatten()
+            .fold(std::collections::HashMap::new(), |mut queries, (language, query)| {
+                queries.entry(*language).or_insert_with(Vec::new).push(query.clone());
+                queries
+            }),
     };
 
     // Create and run streamer
@@ -509,13 +1456,30 @@ async fn run_folder_to_cozodb_streamer(matches: &ArgMatches) -> Result<()> {
             println!("  Duration: {:?}", result.duration);
         }
     }
+    if matches.get_flag("fail-on-warnings") {
+        result.fail_on_warnings()?;
+    }
+
+    if matches.get_flag("report-errors") {
+        let parse_errors = streamer.storage().get_parse_errors().await?;
+        if parse_errors.is_empty() {
+            println!("{} No syntax errors recorded", style("✓").green());
+            return Ok(());
+        }
+        for error in &parse_errors {
+            println!("    {}", error);
+        }
+        let files: std::collections::HashSet<&str> =
+            parse_errors.iter().map(|e| e.file_path.as_str()).collect();
+        anyhow::bail!("{} syntax error(s) in {} file(s)", parse_errors.len(), files.len())
+    }
 
     Ok(())
 }
 
 async fn run_llm_to_cozodb_writer(matches: &ArgMatches) -> Result<()> {
     use parseltongue_core::storage::CozoDbStorage;
-    use parseltongue_core::entities::TemporalAction;
+    use pt03_llm_to_cozodb_writer::{write_once, WriteOnce};
 
     let entity_key = matches.get_one::<String>("entity").unwrap();
     let action = matches.get_one::<String>("action").unwrap();
@@ -534,8 +1498,49 @@ async fn run_llm_to_cozodb_writer(matches: &ArgMatches) -> Result<()> {
     let storage = CozoDbStorage::new(db)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    // Process action; with an idempotency key a retried write is not re-run
+    match matches.get_one::<String>("idempotency-key") {
+        Some(idempotency_key) => {
+            let write = || apply_llm_write(&storage, matches);
+            match write_once(&storage, idempotency_key, entity_key, action, write).await? {
+                WriteOnce::Applied(_) => {}
+                WriteOnce::Replayed(prior) => {
+                    println!(
+                        "{}",
+                        style(format!(
+                            "✓ Already applied under idempotency key {} at {}; not re-executed",
+                            idempotency_key, prior.applied_at
+                        ))
+                        .green()
+                    );
+                    println!("  Result: {}", prior.result);
+                }
+            }
+        }
+        None => {
+            apply_llm_write(&storage, matches).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply one pt03 write, returning what it did for the idempotency record
+async fn apply_llm_write(
+    storage: &parseltongue_core::storage::CozoDbStorage,
+    matches: &ArgMatches,
+) -> Result<String> {
+    use parseltongue_core::entities::TemporalAction;
+    use pt03_llm_to_cozodb_writer::{write_entity_change, write_future_code, PROVENANCE_TOOL};
+
+    let entity_key = matches.get_one::<String>("entity").unwrap();
+    let action = matches.get_one::<String>("action").unwrap();
+    let future_code = matches.get_one::<String>("future-code");
+    let expect_hash = matches.get_one::<String>("expect-hash");
+    let update_edges = matches.get_flag("update-edges");
 
-    // Process action
     match action.as_str() {
         "create" => {
             println!("  Creating entity: {}", entity_key);
@@ -543,281 +1548,1216 @@ async fn run_llm_to_cozodb_writer(matches: &ArgMatches) -> Result<()> {
             println!("  Future code: {} bytes", future_code_content.len());
 
             // Build new entity using functional composition
-            let entity = build_create_entity(&entity_key, future_code_content)
+            let entity = build_create_entity(&entity_key, future_code_content, storage.clock().as_ref())
                 .with_context(|| format!("Failed to construct entity from key: {}", entity_key))?;
 
-            // Persist to database
-            storage.insert_entity(&entity)
-                .await
-                .with_context(|| "Failed to insert new entity into database")?;
+            // Persist to database, recording pt03 as the last modifier
+            storage.insert_entity(&entity)
+                .await
+                .with_context(|| "Failed to insert new entity into database")?;
+            storage.record_provenance(&entity.isgl1_key, PROVENANCE_TOOL)
+                .await
+                .with_context(|| "Failed to record provenance")?;
+
+            println!("{}", style("✓ Entity created successfully").green());
+            println!("  Temporal state: Create pending (current_ind=false, future_ind=true)");
+            println!("  Entity type: {:?}", entity.interface_signature.entity_type);
+            println!("  File path: {}", entity.interface_signature.file_path.display());
+            Ok("Create pending".to_string())
+        }
+        "edit" if expect_hash.is_none() => {
+            println!("  Editing entity: {}", entity_key);
+
+            // No version to check, so update the temporal columns in place
+            write_future_code(storage, entity_key, future_code.unwrap())
+                .await
+                .map_err(|e| write_error(e, "Failed to persist entity changes"))?;
+
+            println!("{}", style("✓ Entity updated with future code").green());
+            println!("  Temporal state: Edit pending (future_ind=true)");
+            if update_edges {
+                let entity = storage.get_entity(entity_key)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch entity: {}", e))?;
+                refresh_edges_after_edit(storage, &entity).await?;
+            }
+            Ok("Edit pending".to_string())
+        }
+        "edit" => {
+            println!("  Editing entity: {}", entity_key);
+
+            // Fetch existing entity
+            let mut entity = storage.get_entity(entity_key)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch entity: {}", e))?;
+
+            // The caller's expected version overrides the one just read
+            if let Some(expected) = expect_hash {
+                entity.metadata.content_hash = expected.clone();
+            }
+
+            // Update future_code
+            entity.future_code = Some(future_code.unwrap().clone());
+
+            // Set temporal action
+            entity.temporal_state.future_action = Some(TemporalAction::Edit);
+            entity.temporal_state.future_ind = true;
+
+            // Persist updated entity back to database (records provenance)
+            write_entity_change(storage, &entity)
+                .await
+                .map_err(|e| write_error(e, "Failed to persist entity changes"))?;
+
+            println!("{}", style("✓ Entity updated with future code").green());
+            println!("  Temporal state: Edit pending (future_ind=true)");
+            println!("  Version: {}", entity.version_hash());
+            if update_edges {
+                refresh_edges_after_edit(storage, &entity).await?;
+            }
+            Ok(format!("Edit pending, version {}", entity.version_hash()))
+        }
+        "delete" => {
+            println!("  Deleting entity: {}", entity_key);
+
+            // Fetch existing entity
+            let mut entity = storage.get_entity(entity_key)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch entity: {}", e))?;
+
+            if let Some(expected) = expect_hash {
+                entity.metadata.content_hash = expected.clone();
+            }
+
+            // Mark for deletion via temporal state
+            entity.temporal_state.future_ind = false;
+            entity.temporal_state.future_action = Some(TemporalAction::Delete);
+
+            // Persist updated entity
+            write_entity_change(storage, &entity)
+                .await
+                .map_err(|e| write_error(e, "Failed to mark for deletion"))?;
+
+            println!("{}", style("✓ Entity marked for deletion").green());
+            println!("  Temporal state: Delete pending (future_ind=false)");
+            println!("  Version: {}", entity.version_hash());
+            Ok(format!("Delete pending, version {}", entity.version_hash()))
+        }
+        _ => unreachable!("clap validation should prevent this"),
+    }
+}
+
+/// --update-edges: swap the entity's outgoing edges for those of its future code
+async fn refresh_edges_after_edit(
+    storage: &parseltongue_core::storage::CozoDbStorage,
+    entity: &parseltongue_core::entities::CodeEntity,
+) -> Result<()> {
+    let generator = pt01_folder_to_cozodb_streamer::Isgl1KeyGeneratorImpl::new();
+    let edges = pt01_folder_to_cozodb_streamer::refresh_entity_edges(storage, &generator, entity)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update edges: {}", e))?;
+    println!("  Outgoing edges: {} (re-extracted from future code)", edges.len());
+    Ok(())
+}
+
+/// Turn a storage write failure into an actionable message
+///
+/// Conflicts tell the agent to re-read the entity rather than blindly retry.
+fn write_error(error: parseltongue_core::ParseltongError, context: &str) -> anyhow::Error {
+    match error {
+        parseltongue_core::ParseltongError::ConflictError { isgl1_key, expected, actual } => {
+            anyhow::anyhow!(
+                "Conflict: {} was modified since it was read (expected version {}, stored version {}). \
+                 Re-read the entity, then retry with --expect-hash {}",
+                isgl1_key, expected, actual, actual
+            )
+        }
+        other => anyhow::anyhow!("{}: {}", context, other),
+    }
+}
+
+/// --validate-keys preflight: list orphan edges, failing under --strict
+fn report_orphan_edges(orphans: &[parseltongue_core::entities::DependencyEdge], strict: bool) -> Result<()> {
+    const MAX_LISTED: usize = 10;
+
+    if orphans.is_empty() {
+        println!("{}", style("✓ Key validation: no orphan edges").green());
+        return Ok(());
+    }
+
+    let color = if strict { style("✗").red() } else { style("⚠").yellow() };
+    println!("{} Key validation: {} edge(s) reference missing entities", color, orphans.len());
+    for edge in orphans.iter().take(MAX_LISTED) {
+        println!("    {} -> {} ({})", edge.from_key, edge.to_key, edge.edge_type.as_str());
+    }
+    if orphans.len() > MAX_LISTED {
+        println!("    ... and {} more", orphans.len() - MAX_LISTED);
+    }
+
+    if strict {
+        anyhow::bail!("{} orphan edge(s) found (--strict / --fail-on-warnings)", orphans.len());
+    }
+    Ok(())
+}
+
+/// pt05 `--check`: compare each Edit/Delete target on disk with the
+/// current_code the graph holds, failing on drift unless `force`
+fn report_source_drift(root: &str, changed: &[parseltongue_core::entities::CodeEntity], force: bool) -> Result<()> {
+    use pt05_llm_cozodb_to_diff_writer::{CheckResult, FileWriter};
+
+    let mismatched = FileWriter::new(PathBuf::from(root)).check_entities(changed);
+    if mismatched.is_empty() {
+        println!("{}", style("✓ Source check: files match the graph").green());
+        return Ok(());
+    }
+
+    let color = if force { style("⚠").yellow() } else { style("✗").red() };
+    println!("{} Source check: {} entity(ies) changed outside the graph", color, mismatched.len());
+    for (key, result) in &mismatched {
+        match result {
+            CheckResult::Drift { path, .. } => println!("    {} drifted in {}", key, path.display()),
+            CheckResult::Missing { path } => println!("    {} not found in {}", key, path.display()),
+            CheckResult::Match => {}
+        }
+    }
+
+    if !force {
+        anyhow::bail!("{} entity(ies) drifted; re-ingest or rerun with --force", mismatched.len());
+    }
+    Ok(())
+}
+
+/// Restrict a pt02 export to `--keys` / `--keys-file`, reporting keys that
+/// match no entity; `None` when no selection was given
+async fn select_keys<'a>(
+    matches: &ArgMatches,
+    repository: &'a dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+) -> Result<Option<pt02_llm_cozodb_to_context_writer::SelectedRepository<'a>>> {
+    use pt02_llm_cozodb_to_context_writer::KeySelection;
+
+    let keys = matches.get_many::<String>("keys").into_iter().flatten().cloned();
+    let selection = match matches.get_one::<String>("keys-file") {
+        Some(path) => KeySelection::from_keys_file(std::path::Path::new(path))?.extend(keys),
+        None => KeySelection::new(keys),
+    };
+    if selection.is_empty() {
+        if matches.get_flag("with-deps") {
+            anyhow::bail!("--with-deps requires --keys or --keys-file");
+        }
+        return Ok(None);
+    }
+
+    let selected = selection.with_deps(matches.get_flag("with-deps")).resolve(repository).await?;
+    if !selected.missing().is_empty() {
+        println!("{} {} selected key(s) match no entity:", style("⚠").yellow(), selected.missing().len());
+        for key in selected.missing() {
+            println!("    {}", key);
+        }
+        if matches.get_flag("fail-on-warnings") {
+            anyhow::bail!("{} selected key(s) match no entity (--fail-on-warnings)", selected.missing().len());
+        }
+    }
+    println!("  Selected entities: {}", selected.len());
+    Ok(Some(selected))
+}
+
+/// Restrict a pt02 export to a `--sample` / `--sample-pct` of the entities
+/// matching `where_clause`; `None` when no sample was requested
+async fn sample_entities<'a>(
+    matches: &ArgMatches,
+    repository: &'a dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+    where_clause: &str,
+) -> Result<Option<(pt02_llm_cozodb_to_context_writer::SelectedRepository<'a>, pt02_llm_cozodb_to_context_writer::SamplingInfo)>> {
+    use pt02_llm_cozodb_to_context_writer::{EntitySampler, SampleSize, SampleStratum};
+
+    let size = match (matches.get_one::<usize>("sample"), matches.get_one::<f64>("sample-pct")) {
+        (Some(&count), _) => SampleSize::Count(count),
+        (None, Some(&pct)) => SampleSize::Percent(pct),
+        (None, None) if matches.get_one::<String>("sample-by").is_some() => {
+            anyhow::bail!("--sample-by requires --sample or --sample-pct");
+        }
+        (None, None) => return Ok(None),
+    };
+    let stratum = matches
+        .get_one::<String>("sample-by")
+        .map(|field| field.parse::<SampleStratum>().map_err(anyhow::Error::msg))
+        .transpose()?;
+
+    let (sampled, info) = EntitySampler::new(size)?
+        .with_stratum(stratum)
+        .resolve(repository, where_clause)
+        .await?;
+    println!(
+        "  Sampled {} of {} entities ({:.1}%)",
+        info.sampled,
+        info.population,
+        info.ratio() * 100.0
+    );
+    Ok(Some((sampled, info)))
+}
+
+async fn run_skeleton(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+    use pt02_llm_cozodb_to_context_writer::render_file_skeleton;
+
+    let file = matches.get_one::<String>("file").unwrap();
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    // Indexed paths keep whatever prefix pt01 saw (./src/..., absolute),
+    // so fall back to a path-suffix match when the exact path is unknown
+    let mut entities = storage.get_entities_by_file(file).await?;
+    if entities.is_empty() {
+        let wanted = std::path::Path::new(file);
+        entities = storage
+            .get_all_entities()
+            .await?
+            .into_iter()
+            .filter(|e| e.interface_signature.file_path.ends_with(wanted))
+            .collect();
+    }
+
+    if entities.is_empty() {
+        anyhow::bail!("No entities indexed for file: {}", file);
+    }
+
+    print!("{}", render_file_skeleton(&entities));
+    Ok(())
+}
+
+async fn run_db_check(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+    const MAX_LISTED: usize = 20;
+
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    use futures::TryStreamExt;
+
+    let mut checked = 0;
+    let mut violations: Vec<(String, String)> = Vec::new();
+    let mut entities = std::pin::pin!(storage.entities_stream());
+    while let Some(entity) = entities.try_next().await? {
+        checked += 1;
+        if let Err(reason) = entity.validate_temporal_consistency() {
+            violations.push((entity.isgl1_key, reason));
+        }
+    }
+
+    if violations.is_empty() {
+        println!(
+            "{} Temporal consistency: {} entities checked, no violations",
+            style("✓").green(),
+            checked
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} Temporal consistency: {} of {} entities violate future_action/future_code",
+        style("✗").red(),
+        violations.len(),
+        checked
+    );
+    for (key, reason) in violations.iter().take(MAX_LISTED) {
+        println!("    {}: {}", key, reason);
+    }
+    if violations.len() > MAX_LISTED {
+        println!("    ... and {} more", violations.len() - MAX_LISTED);
+    }
+    anyhow::bail!("{} temporal consistency violation(s) found", violations.len())
+}
+
+async fn run_db_compact(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    let report = storage.compact().await?;
+    println!(
+        "{} Compacted: {} tombstoned entities removed",
+        style("✓").green(),
+        report.tombstones_removed
+    );
+    if let (Some(before), Some(after)) = (report.bytes_before, report.bytes_after) {
+        println!(
+            "    {} -> {} bytes ({} reclaimed)",
+            before,
+            after,
+            report.bytes_reclaimed().unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
+async fn run_db_copy(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let from = matches.get_one::<String>("from").unwrap();
+    let to = matches.get_one::<String>("to").unwrap();
+    let where_clause = matches.get_one::<String>("where").unwrap();
+    if from == to {
+        anyhow::bail!("--from and --to must be different databases");
+    }
+
+    let source = CozoDbStorage::new(from).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", from, e))?;
+    source.ensure_schema().await?;
+    let target = CozoDbStorage::new(to).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", to, e))?;
+    target.ensure_schema().await?;
+
+    let condition = (where_clause != "ALL").then_some(where_clause.as_str());
+    let report = source
+        .copy_entities_to(&target, condition, matches.get_flag("keep-dangling"))
+        .await
+        .map_err(|e| anyhow::anyhow!("Copy failed: {}", e))?;
+
+    println!(
+        "{} Copied {} entities and {} edges from {} to {}",
+        style("✓").green(),
+        report.entities_copied,
+        report.edges_copied,
+        from,
+        to
+    );
+    if report.dangling_edges_dropped > 0 {
+        println!(
+            "    {} dangling edges dropped (pass --keep-dangling to keep them)",
+            report.dangling_edges_dropped
+        );
+    }
+    Ok(())
+}
+
+async fn run_diff_entities(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::signature_diff::{diff_entities, FieldChange};
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let key_a = matches.get_one::<String>("a").unwrap();
+    let key_b = matches.get_one::<String>("b").unwrap();
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    let entity_a = storage.get_entity(key_a).await
+        .map_err(|e| anyhow::anyhow!("Entity A ({}): {}", key_a, e))?;
+    let entity_b = storage.get_entity(key_b).await
+        .map_err(|e| anyhow::anyhow!("Entity B ({}): {}", key_b, e))?;
+
+    let diff = diff_entities(&entity_a, &entity_b);
+
+    // Multi-line values (docs) show their first line only
+    let one_line = |value: &str| -> String {
+        let mut lines = value.lines();
+        let first = lines.next().unwrap_or("");
+        if lines.next().is_some() { format!("{} …", first) } else { first.to_string() }
+    };
+
+    println!("{}", style("Signature comparison").cyan().bold());
+    println!("  A: {}", key_a);
+    println!("  B: {}", key_b);
+    println!();
+    for field in &diff.fields {
+        match field.change {
+            FieldChange::Unchanged => {
+                println!("  {} {:<14} {}", style("=").dim(), field.field, style(one_line(&field.a)).dim());
+            }
+            FieldChange::Compatible => {
+                println!("  {} {:<14} {} → {}", style("~").yellow(), field.field, one_line(&field.a), one_line(&field.b));
+            }
+            FieldChange::Breaking => {
+                println!(
+                    "  {} {:<14} {} → {}  {}",
+                    style("✗").red(),
+                    field.field,
+                    one_line(&field.a),
+                    one_line(&field.b),
+                    style("BREAKING").red().bold()
+                );
+            }
+        }
+    }
+    println!();
+
+    let breaking = diff.changes().filter(|f| f.change == FieldChange::Breaking).count();
+    let changed = diff.changes().count();
+    if breaking > 0 {
+        println!("{} {} breaking change(s), {} change(s) total", style("✗").red(), breaking, changed);
+        anyhow::bail!("{} breaking change(s) between {} and {}", breaking, key_a, key_b);
+    } else if changed > 0 {
+        println!("{} {} compatible change(s)", style("✓").green(), changed);
+    } else {
+        println!("{} Signatures are identical", style("✓").green());
+    }
+    Ok(())
+}
+
+async fn run_api_report(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::api_report::{ApiChangeKind, ApiReport, SemverImpact};
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let db = matches.get_one::<String>("db").unwrap();
+    let against = matches.get_one::<String>("against").unwrap();
+
+    async fn connect(spec: &str) -> Result<CozoDbStorage> {
+        let storage = CozoDbStorage::new(spec).await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to database {}: {}", spec, e))?;
+        storage.ensure_schema().await?;
+        Ok(storage)
+    }
+    let old = connect(against).await?;
+    let new = connect(db).await?;
+
+    let report = ApiReport::compare(&old.get_public_entities().await?, &new.get_all_entities().await?);
+
+    println!("{}", style("Public API report").cyan().bold());
+    println!("  old: {}", against);
+    println!("  new: {}", db);
+    println!();
+    for change in &report.changes {
+        let marker = match change.impact {
+            SemverImpact::Major => style("MAJOR").red().bold(),
+            SemverImpact::Minor => style("minor").yellow(),
+        };
+        match &change.kind {
+            ApiChangeKind::Added => println!("  {} + {} ({})", marker, change.name, change.isgl1_key),
+            ApiChangeKind::Removed { now: None } => {
+                println!("  {} - {} ({}) removed", marker, change.name, change.isgl1_key)
+            }
+            ApiChangeKind::Removed { now: Some(visibility) } => {
+                println!("  {} - {} ({}) is now {}", marker, change.name, change.isgl1_key, visibility)
+            }
+            ApiChangeKind::SignatureChanged { fields } => {
+                println!("  {} ~ {} ({})", marker, change.name, change.isgl1_key);
+                for field in fields {
+                    println!("      {}: {} → {}", field.field, field.a, field.b);
+                }
+            }
+        }
+    }
+    if !report.changes.is_empty() {
+        println!();
+    }
+
+    match report.required_bump() {
+        Some(SemverImpact::Major) => {
+            let breaking = report.breaking_changes().count();
+            println!(
+                "{} {} breaking change(s): major version bump required",
+                style("✗").red(),
+                breaking
+            );
+            anyhow::bail!("{} breaking public API change(s)", breaking);
+        }
+        Some(SemverImpact::Minor) => println!(
+            "{} {} compatible change(s): minor version bump",
+            style("✓").green(),
+            report.changes.len()
+        ),
+        None => println!("{} Public API unchanged", style("✓").green()),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+async fn run_export_parquet(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::parquet_export::write_entities_parquet;
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let db = matches.get_one::<String>("db").unwrap();
+    let output = matches.get_one::<String>("output").unwrap();
+    let include_code = matches.get_flag("include-code");
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    let entities = storage.get_all_entities().await?;
+    let file = std::fs::File::create(output)
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", output, e))?;
+    let rows = write_entities_parquet(&entities, std::io::BufWriter::new(file), include_code)?;
+    println!("{} Wrote {} entities to {}", style("✓").green(), rows, output);
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+async fn run_export_parquet(_matches: &ArgMatches) -> Result<()> {
+    anyhow::bail!("Parquet export requires building with the `parquet` feature")
+}
+
+async fn run_repl(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+    use repl::{Repl, ReplCommand};
+    use rustyline::error::ReadlineError;
+
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+    let repl = Repl::new(storage);
+
+    let history = std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".parseltongue_history"));
+    let mut editor = rustyline::DefaultEditor::new()?;
+    if let Some(history) = &history {
+        // Missing on first use
+        let _ = editor.load_history(history);
+    }
+
+    println!("Connected to {}. Type 'help' for commands.", db);
+    loop {
+        let line = match editor.readline("pt> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let command = match ReplCommand::parse(&line) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("{} {}", style("✗").red(), e);
+                continue;
+            }
+        };
+        let _ = editor.add_history_entry(line.trim());
+        if command == ReplCommand::Quit {
+            break;
+        }
+        match repl.execute(&command).await {
+            Ok(output) => println!("{}", output),
+            Err(e) => eprintln!("{} {}", style("✗").red(), e),
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+    Ok(())
+}
+
+async fn run_pipeline(matches: &ArgMatches) -> Result<()> {
+    let summary = execute_pipeline(matches).await?;
+    println!();
+    if summary.failed() == 0 {
+        println!("{}", style(&summary).green());
+        return Ok(());
+    }
+    eprintln!("{}", style(&summary).red());
+    anyhow::bail!("Pipeline failed: {} of {} step(s) failed", summary.failed(), summary.outcomes.len())
+}
+
+/// Check the step arguments, then run every step
+async fn execute_pipeline(matches: &ArgMatches) -> Result<pipeline::PipelineSummary> {
+    use pipeline::{parse_steps, run_steps, PipelineStep};
+
+    let steps = parse_steps(matches.get_one::<String>("steps").unwrap())?;
+    if steps.contains(&PipelineStep::Write) {
+        let action = match (matches.get_one::<String>("entity"), matches.get_one::<String>("action")) {
+            (Some(_), Some(action)) => action,
+            _ => anyhow::bail!("The pt03 step needs --entity and --action"),
+        };
+        if action != "delete" && !matches.contains_id("future-code") {
+            anyhow::bail!("The pt03 step needs --future-code for {}", action);
+        }
+    }
+
+    let names: Vec<_> = steps.iter().map(|step| step.short_name()).collect();
+    println!("{}", style(format!("Running pipeline: {}", names.join(" → "))).cyan());
+
+    let keep_going = matches.get_flag("keep-going");
+    Ok(run_steps(&steps, keep_going, |step| run_pipeline_step(step, matches)).await)
+}
+
+/// Run one step through its subcommand, as if invoked on its own
+async fn run_pipeline_step(step: pipeline::PipelineStep, matches: &ArgMatches) -> Result<()> {
+    use pipeline::PipelineStep;
+
+    let arg = |id: &str| matches.get_one::<String>(id).cloned();
+    let mut argv = vec!["parseltongue".to_string(), step.subcommand().to_string()];
+    match step {
+        PipelineStep::Ingest => argv.extend(arg("directory")),
+        PipelineStep::Write => {
+            argv.extend(["--entity".to_string()].into_iter().chain(arg("entity")));
+            argv.extend(["--action".to_string()].into_iter().chain(arg("action")));
+            if let Some(code) = arg("future-code") {
+                argv.extend(["--future-code".to_string(), code]);
+            }
+        }
+        PipelineStep::Validate => {}
+        PipelineStep::Diff => argv.extend(["--output".to_string()].into_iter().chain(arg("output"))),
+        PipelineStep::Reset => argv.extend(["--project".to_string()].into_iter().chain(arg("directory"))),
+    }
+    argv.extend(["--db".to_string()].into_iter().chain(arg("db")));
+
+    let cli = build_cli().try_get_matches_from(argv)?;
+    let (_, sub_matches) = cli.subcommand().expect("pipeline argv names a subcommand");
+    match step {
+        PipelineStep::Ingest => run_folder_to_cozodb_streamer(sub_matches).await,
+        PipelineStep::Write => run_llm_to_cozodb_writer(sub_matches).await,
+        PipelineStep::Validate => run_rust_preflight_code_simulator(sub_matches).await,
+        PipelineStep::Diff => run_llm_cozodb_to_diff_writer(sub_matches).await,
+        PipelineStep::Reset => run_cozodb_make_future_code_current(sub_matches).await,
+    }
+}
+
+async fn run_path(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::storage::CozoDbStorage;
+
+    let from = matches.get_one::<String>("from").unwrap();
+    let to = matches.get_one::<String>("to").unwrap();
+    let max_hops = *matches.get_one::<usize>("max-hops").unwrap();
+    let db = matches.get_one::<String>("db").unwrap();
 
-            println!("{}", style("✓ Entity created successfully").green());
-            println!("  Temporal state: Create pending (current_ind=false, future_ind=true)");
-            println!("  Entity type: {:?}", entity.interface_signature.entity_type);
-            println!("  File path: {}", entity.interface_signature.file_path.display());
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    match storage.shortest_path_within(from, to, max_hops).await? {
+        Some(path) => {
+            println!("{}", style(format!("Path ({} hop(s))", path.len() - 1)).cyan().bold());
+            for (hop, key) in path.iter().enumerate() {
+                println!("  {:>3}  {}", hop, key);
+            }
         }
-        "edit" => {
-            println!("  Editing entity: {}", entity_key);
+        None => {
+            println!("{} No path from {} to {} within {} hop(s)", style("✗").yellow(), from, to, max_hops);
+        }
+    }
+    Ok(())
+}
 
-            // Fetch existing entity
-            let mut entity = storage.get_entity(entity_key)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to fetch entity: {}", e))?;
+async fn run_graph_stats(matches: &ArgMatches) -> Result<()> {
+    use parseltongue_core::graph_stats::compute_centrality;
+    use parseltongue_core::storage::CozoDbStorage;
 
-            // Update future_code
-            entity.future_code = Some(future_code.unwrap().clone());
+    let top = *matches.get_one::<usize>("top").unwrap();
+    let sort_by = matches.get_one::<String>("sort-by").unwrap();
+    let db = matches.get_one::<String>("db").unwrap();
 
-            // Set temporal action
-            entity.temporal_state.future_action = Some(TemporalAction::Edit);
-            entity.temporal_state.future_ind = true;
+    let storage = CozoDbStorage::new(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
+
+    let metrics = compute_centrality(&storage).await?;
+    let mut ranked: Vec<_> = metrics.iter().collect();
+    ranked.sort_by(|(a_key, a), (b_key, b)| {
+        let order = match sort_by.as_str() {
+            "in-degree" => b.in_degree.cmp(&a.in_degree),
+            "out-degree" => b.out_degree.cmp(&a.out_degree),
+            _ => b.betweenness.total_cmp(&a.betweenness),
+        };
+        order.then_with(|| a_key.cmp(b_key))
+    });
+
+    let god_objects = metrics.values().filter(|m| m.god_object).count();
+    println!(
+        "{}",
+        style(format!("Graph statistics: {} entities on edges, {} god object(s)", metrics.len(), god_objects)).cyan().bold()
+    );
+    println!("  {:>7} {:>7} {:>12}  Entity", "Fan-in", "Fan-out", "Betweenness");
+    for (key, m) in ranked.into_iter().take(top) {
+        let flag = if m.god_object { style(" [god object]").red().to_string() } else { String::new() };
+        println!("  {:>7} {:>7} {:>12.1}  {}{}", m.in_degree, m.out_degree, m.betweenness, key, flag);
+    }
+    Ok(())
+}
 
-            // Persist updated entity back to database
-            storage.update_entity_internal(&entity)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to persist entity changes: {}", e))?;
+/// `--where-clause` ANDed with the `--since` / `--since-commit` cut-off
+fn pt02_where_clause(matches: &ArgMatches) -> Result<String> {
+    use parseltongue_core::storage::DEFAULT_RELATION_NAME;
+    use pt02_llm_cozodb_to_context_writer::{commit_time, parse_since, with_api_scope, with_since, without_generated};
 
-            println!("{}", style("✓ Entity updated with future code").green());
-            println!("  Temporal state: Edit pending (future_ind=true)");
+    let raw = matches.get_one::<String>("where-clause").unwrap();
+    let where_clause = match (raw.as_str(), typed_entity_filter(matches)?) {
+        (_, typed) if typed == "ALL" => raw.clone(),
+        ("ALL", typed) => typed,
+        (raw, typed) => format!("{}, {}", raw, typed),
+    };
+    let where_clause = &with_api_scope(
+        &where_clause,
+        matches.get_flag("exclude-tests"),
+        matches.get_flag("exclude-private"),
+    );
+    let where_clause = &without_generated(where_clause, matches.get_flag("exclude-generated"));
+    let since = match (matches.get_one::<String>("since"), matches.get_one::<String>("since-commit")) {
+        (Some(since), _) => Some(parse_since(since)?),
+        (None, Some(rev)) => {
+            let repo = matches.get_one::<String>("repo").unwrap();
+            let since = commit_time(std::path::Path::new(repo), rev)?;
+            println!("  Since commit {} ({})", rev, since.to_rfc3339());
+            Some(since)
         }
-        "delete" => {
-            println!("  Deleting entity: {}", entity_key);
-
-            // Fetch existing entity
-            let mut entity = storage.get_entity(entity_key)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to fetch entity: {}", e))?;
-
-            // Mark for deletion via temporal state
-            entity.temporal_state.future_ind = false;
-            entity.temporal_state.future_action = Some(TemporalAction::Delete);
+        (None, None) => None,
+    };
+    Ok(with_since(where_clause, DEFAULT_RELATION_NAME, since))
+}
 
-            // Persist updated entity
-            storage.update_entity_internal(&entity)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to mark for deletion: {}", e))?;
+/// `--entity-type` (any of) and `--visibility`, compiled through `QueryBuilder`
+fn typed_entity_filter(matches: &ArgMatches) -> Result<String> {
+    use parseltongue_core::entities::{EntityType, Visibility};
+    use pt02_llm_cozodb_to_context_writer::QueryBuilder;
+
+    let visibility = matches.get_one::<String>("visibility").map(|name| match name.as_str() {
+        "public" => Visibility::Public,
+        "private" => Visibility::Private,
+        "protected" => Visibility::Protected,
+        "crate" => Visibility::Crate,
+        _ => Visibility::Module,
+    });
+    let entity_types: Vec<EntityType> = matches
+        .get_many::<String>("entity-type")
+        .into_iter()
+        .flatten()
+        .filter_map(|name| EntityType::from_column_name(name))
+        .collect();
+
+    let mut builder = QueryBuilder::new();
+    if entity_types.is_empty() {
+        if let Some(visibility) = &visibility {
+            builder = builder.visibility(visibility.clone());
+        }
+    }
+    for (i, entity_type) in entity_types.into_iter().enumerate() {
+        if i > 0 {
+            builder = builder.or();
+        }
+        builder = builder.entity_type(entity_type);
+        if let Some(visibility) = &visibility {
+            builder = builder.and().visibility(visibility.clone());
+        }
+    }
+    Ok(builder.build()?)
+}
 
-            println!("{}", style("✓ Entity marked for deletion").green());
-            println!("  Temporal state: Delete pending (future_ind=false)");
+/// Print the Datalog a pt02 export will run when `--explain` is set
+///
+/// Returns true when `--dry-run` asks to stop before executing anything.
+fn explain_pt02_queries(matches: &ArgMatches, level: u8, where_clause: &str) -> bool {
+    if matches.get_flag("explain") {
+        for query in pt02_llm_cozodb_to_context_writer::explain_export(level, where_clause) {
+            eprintln!("{}", query);
         }
-        _ => unreachable!("clap validation should prevent this"),
     }
+    matches.get_flag("dry-run")
+}
 
+/// `--count-only`: rows each file of a dual-file export at `level` would hold
+async fn print_pt02_counts(
+    repository: &dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+    level: u8,
+    where_clause: &str,
+) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::scope_to_entity_class;
+
+    let rows = if level == 0 { "edges" } else { "entities" };
+    let mut total = 0;
+    for (class, file) in [("CODE", "main"), ("TEST", "test")] {
+        let filter = scope_to_entity_class(class, where_clause);
+        let count = if level == 0 {
+            repository.count_edges(&filter).await?
+        } else {
+            repository.count_entities(&filter).await?
+        };
+        println!("  {} {} ({} file): {}", class, rows, file, count);
+        total += count;
+    }
+    println!("  Total {}: {}", rows, total);
     Ok(())
 }
 
 async fn run_pt02_level00(matches: &ArgMatches) -> Result<()> {
-    use pt02_llm_cozodb_to_context_writer::{CozoDbAdapter, Level0Exporter, LevelExporter};
+    use pt02_llm_cozodb_to_context_writer::Level0Exporter;
 
     let where_clause = matches.get_one::<String>("where-clause").unwrap();
-    let output = matches.get_one::<String>("output").unwrap();
-    let db = matches.get_one::<String>("db").unwrap();
-    let verbose = matches.get_flag("verbose");
-
-    println!("{}", style("Running PT02 Level 0: Pure Edge List Export").cyan());
-    if verbose {
-        println!("  Database: {}", db);
-        println!("  WHERE clause: {}", where_clause);
-        println!("  Output: {}", output);
-    }
+    let exporter = Level0Exporter::new().with_timestamp(!matches.get_flag("no-timestamp"));
+    run_pt02_export(matches, Pt02Exporter::Level0(exporter), where_clause, false).await
+}
 
-    // Connect to CozoDB
-    let db_adapter = CozoDbAdapter::connect(db).await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+/// The exporter behind a pt02 level
+enum Pt02Exporter {
+    Level0(pt02_llm_cozodb_to_context_writer::Level0Exporter),
+    Level1(pt02_llm_cozodb_to_context_writer::Level1Exporter),
+    Level2(pt02_llm_cozodb_to_context_writer::Level2Exporter),
+}
 
-    // Create exporter
-    let exporter = Level0Exporter::new();
-    
-    // Extract base output name (remove .json extension if present)
-    let base_output = if output.ends_with(".json") {
-        &output[..output.len() - 5]
-    } else {
-        output
-    };
+impl Pt02Exporter {
+    fn level(&self) -> u8 {
+        match self {
+            Pt02Exporter::Level0(_) => 0,
+            Pt02Exporter::Level1(_) => 1,
+            Pt02Exporter::Level2(_) => 2,
+        }
+    }
 
-    if verbose {
-        println!("  Estimated tokens: ~{}", exporter.estimated_tokens());
+    fn title(&self) -> &'static str {
+        match self {
+            Pt02Exporter::Level0(_) => "Pure Edge List Export",
+            Pt02Exporter::Level1(_) => "Entity + ISG + Temporal Export",
+            Pt02Exporter::Level2(_) => "Entity + ISG + Temporal + Type System Export",
+        }
     }
 
-    // Execute dual file export (REQ-V090-004.0: Automatic dual-file export)
-    exporter.export_dual_files(
-        &db_adapter,
-        base_output,
-        where_clause
-    ).await
-        .map_err(|e| anyhow::anyhow!("Export failed: {}", e))?;
+    /// Token estimate; entity levels cost ~20× more with code
+    fn estimated_tokens(&self, include_code: bool) -> usize {
+        use pt02_llm_cozodb_to_context_writer::LevelExporter;
+
+        let (base, entities) = match self {
+            Pt02Exporter::Level0(exporter) => (exporter.estimated_tokens(), false),
+            Pt02Exporter::Level1(exporter) => (exporter.estimated_tokens(), true),
+            Pt02Exporter::Level2(exporter) => (exporter.estimated_tokens(), true),
+        };
+        if entities && include_code {
+            base * 20
+        } else {
+            base
+        }
+    }
 
-    println!("{}", style("✓ PT02 Level 0 export completed").green().bold());
-    println!("  Output files: {}.json, {}_test.json", base_output, base_output);
-    
-    // Load and display edge counts from the main export file
-    let main_output_file = format!("{}.json", base_output);
-    if let Ok(content) = std::fs::read_to_string(&main_output_file) {
-        if let Ok(export_data) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(edges) = export_data["edges"].as_array() {
-                println!("  Edges exported: {}", edges.len());
+    async fn export_dual_files_to(
+        &self,
+        repository: &dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+        sink: &dyn OutputSink,
+        base_output: &str,
+        include_code: bool,
+        where_clause: &str,
+        compact: bool,
+    ) -> Result<()> {
+        let exported = match self {
+            Pt02Exporter::Level0(exporter) => {
+                exporter.export_dual_files_to(repository, sink, base_output, where_clause, compact).await
+            }
+            Pt02Exporter::Level1(exporter) => {
+                exporter.export_dual_files_to(repository, sink, base_output, include_code, where_clause, compact).await
+            }
+            Pt02Exporter::Level2(exporter) => {
+                exporter.export_dual_files_to(repository, sink, base_output, include_code, where_clause, compact).await
             }
+        };
+        exported.map_err(|e| anyhow::anyhow!("Export failed: {}", e))
+    }
+
+    /// Row count line and field summary printed after the export
+    fn summary(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Pt02Exporter::Level0(_) => ("edges", "Edges exported", "Fields per edge: 3 (from_key, to_key, edge_type)"),
+            Pt02Exporter::Level1(_) => (
+                "entities",
+                "Entities exported",
+                "Fields per entity: 14 (isgl1_key, forward_deps, reverse_deps, temporal state, etc.)",
+            ),
+            Pt02Exporter::Level2(_) => ("entities", "Entities exported", "Fields per entity: 16 (includes type system information)"),
         }
     }
-    println!("  Token estimate: ~{}", exporter.estimated_tokens());
-    println!("  Fields per edge: 3 (from_key, to_key, edge_type)");
+}
 
-    Ok(())
+/// Whether `id` is set, false for flags the level does not define
+fn pt02_flag(matches: &ArgMatches, id: &str) -> bool {
+    matches.try_get_one::<bool>(id).ok().flatten().copied().unwrap_or(false)
 }
 
-async fn run_pt02_level01(matches: &ArgMatches) -> Result<()> {
-    use pt02_llm_cozodb_to_context_writer::{CozoDbAdapter, Level1Exporter, LevelExporter};
+/// Run one pt02 level: connect, validate keys, select and sample, then
+/// write the dual files (rendered as Markdown with `--format markdown`)
+/// and their manifest
+///
+/// Flags only some levels define (`--redact`, `--as-context`,
+/// `--external-sort`, ...) apply where they exist.
+async fn run_pt02_export(matches: &ArgMatches, exporter: Pt02Exporter, where_clause: &str, include_code: bool) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::{manifest_name, CodeGraphRepository, CommentStrippedRepository, CozoDbAdapter, ExportManifest, ExternalSortExporter, ManifestRecorder, MarkdownSink, RedactedRepository, AUTO_EXTERNAL_SORT_ROWS};
 
-    let include_code = matches.get_one::<String>("include-code").unwrap();
-    let where_clause = matches.get_one::<String>("where-clause").unwrap();
+    let level = exporter.level();
     let output = matches.get_one::<String>("output").unwrap();
     let db = matches.get_one::<String>("db").unwrap();
     let verbose = matches.get_flag("verbose");
+    let compact = matches.get_flag("compact");
 
-    println!("{}", style("Running PT02 Level 1: Entity + ISG + Temporal Export").cyan());
+    println!("{}", style(format!("Running PT02 Level {}: {}", level, exporter.title())).cyan());
     if verbose {
         println!("  Database: {}", db);
-        println!("  Include code: {}", if include_code == "1" { "YES (expensive)" } else { "NO (cheap)" });
+        if level > 0 {
+            println!("  Include code: {}", if include_code { "YES (expensive)" } else { "NO (cheap)" });
+        }
         println!("  WHERE clause: {}", where_clause);
         println!("  Output: {}", output);
     }
 
+    if explain_pt02_queries(matches, level, where_clause) {
+        return Ok(());
+    }
+
     // Connect to CozoDB
     let db_adapter = CozoDbAdapter::connect(db).await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?
+        .with_provenance(pt02_flag(matches, "include-provenance"));
 
-    // Create exporter
-    let exporter = Level1Exporter::new();
-    
-    // Extract base output name (remove .json extension if present)
-    let base_output = if output.ends_with(".json") {
-        &output[..output.len() - 5]
-    } else {
-        output
+    let fail_on_warnings = matches.get_flag("fail-on-warnings");
+    if matches.get_flag("validate-keys") || fail_on_warnings {
+        report_orphan_edges(&db_adapter.find_orphan_edges().await?, matches.get_flag("strict") || fail_on_warnings)?;
+    }
+
+    let selected = select_keys(matches, &db_adapter).await?;
+    let repository: &dyn CodeGraphRepository = match &selected {
+        Some(selected) => selected,
+        None => &db_adapter,
+    };
+    let sampled = sample_entities(matches, repository, where_clause).await?;
+    let sampling = sampled.as_ref().map(|(_, info)| info.clone());
+    let repository: &dyn CodeGraphRepository = match &sampled {
+        Some((sampled, _)) => sampled,
+        None => repository,
+    };
+    if matches.get_flag("count-only") {
+        return print_pt02_counts(repository, level, where_clause).await;
+    }
+    if pt02_flag(matches, "as-context") {
+        return export_level01_as_context(matches, &db_adapter, repository, where_clause).await;
+    }
+    let redacted = pt02_flag(matches, "redact").then(|| {
+        RedactedRepository::new(repository).with_docs(pt02_flag(matches, "redact-docs"))
+    });
+    let repository: &dyn CodeGraphRepository = match &redacted {
+        Some(redacted) => redacted,
+        None => repository,
+    };
+    let stripped = pt02_flag(matches, "strip-comments").then(|| {
+        CommentStrippedRepository::new(repository).with_doc_comments(pt02_flag(matches, "keep-doc-comments"))
+    });
+    let repository: &dyn CodeGraphRepository = match &stripped {
+        Some(stripped) => stripped,
+        None => repository,
     };
 
-    let base_tokens = exporter.estimated_tokens();
-    let estimated = if include_code == "1" { base_tokens * 20 } else { base_tokens };
+    // Extract base output name (remove .json extension if present)
+    // Plain paths write locally; URLs (http://...) dispatch to their sink
+    let local_output = !output.contains("://") || output.starts_with("file://");
+    let (sink, output) = sink_for_output(output)
+        .map_err(|e| anyhow::anyhow!("Invalid output destination: {}", e))?;
+    let sink = with_json_case(matches, sink);
+    let base_output = output.strip_suffix(".json").unwrap_or(&output);
+
+    let markdown_format = matches.get_one::<String>("format").map(String::as_str) == Some("markdown");
+    if matches!(exporter, Pt02Exporter::Level1(_)) {
+        let default_order = matches.get_one::<String>("order").map(String::as_str) == Some("key");
+        let external_sort = matches.get_flag("external-sort")
+            || (local_output && !markdown_format && !matches.get_flag("dedup-docs") && !matches.get_flag("pair-tests") && default_order && db_adapter.entity_count().await? > AUTO_EXTERNAL_SORT_ROWS);
+        if external_sort {
+            if !local_output || markdown_format {
+                anyhow::bail!("--external-sort writes JSON to local files; pass a path as --output and drop --format markdown");
+            }
+            let exporter = ExternalSortExporter::new().with_timestamp(!matches.get_flag("no-timestamp"));
+            return export_level01_externally(&exporter, repository, base_output, include_code, where_clause).await;
+        }
+    }
 
+    let estimated = exporter.estimated_tokens(include_code);
     if verbose {
         println!("  Estimated tokens: ~{}", estimated);
     }
 
+    // Optionally render each JSON file as Markdown too; the manifest records
+    // whatever ends up written
+    let markdown = MarkdownSink::new(sink.as_ref());
+    let recorder = ManifestRecorder::new(if markdown_format { &markdown } else { sink.as_ref() });
+
     // Execute dual file export (REQ-V090-004.0: Automatic dual-file export)
-    exporter.export_dual_files(
-        &db_adapter,
-        base_output,
-        include_code == "1",
-        where_clause
-    ).await
-        .map_err(|e| anyhow::anyhow!("Export failed: {}", e))?;
+    exporter
+        .export_dual_files_to(repository, &recorder, base_output, include_code, where_clause, compact)
+        .await?;
+    recorder
+        .write_manifest(base_output, ExportManifest::new(level, where_clause, include_code, db).with_sampling(sampling), compact)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write export manifest: {}", e))?;
 
-    println!("{}", style("✓ PT02 Level 1 export completed").green().bold());
+    println!("{}", style(format!("✓ PT02 Level {} export completed", level)).green().bold());
     println!("  Output files: {}.json, {}_test.json", base_output, base_output);
-    
-    // Load and display entity counts from the main export file
+    if markdown_format {
+        println!("  Markdown: {}.md, {}_test.md", base_output, base_output);
+    }
+    println!("  Manifest: {}", manifest_name(base_output));
+
+    // Load and display row counts from the main export file
+    let (rows_field, rows_label, fields) = exporter.summary();
     let main_output_file = format!("{}.json", base_output);
     if let Ok(content) = std::fs::read_to_string(&main_output_file) {
         if let Ok(export_data) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(entities) = export_data["entities"].as_array() {
-                println!("  Entities exported: {}", entities.len());
+            if let Some(rows) = export_data[rows_field].as_array() {
+                println!("  {}: {}", rows_label, rows.len());
             }
         }
     }
     println!("  Token estimate: ~{} tokens", estimated);
-    println!("  Fields per entity: 14 (isgl1_key, forward_deps, reverse_deps, temporal state, etc.)");
+    println!("  {}", fields);
 
     Ok(())
 }
 
-async fn run_pt02_level02(matches: &ArgMatches) -> Result<()> {
-    use pt02_llm_cozodb_to_context_writer::{CozoDbAdapter, Level2Exporter, LevelExporter};
+async fn run_pt02_level01(matches: &ArgMatches) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::{ExportOrder, Level1Exporter};
+
+    let include_code = matches.get_one::<String>("include-code").unwrap() == "1";
+    let where_clause = &pt02_where_clause(matches)?;
+    let order = matches.get_one::<String>("order").unwrap().parse::<ExportOrder>().map_err(anyhow::Error::msg)?;
+    let exporter = Level1Exporter::new()
+        .with_timestamp(!matches.get_flag("no-timestamp"))
+        .with_dedup_docs(matches.get_flag("dedup-docs"))
+        .with_order(order)
+        .with_pair_tests(matches.get_flag("pair-tests"));
+    run_pt02_export(matches, Pt02Exporter::Level1(exporter), where_clause, include_code).await
+}
+
+/// Level 1 export as a single pt03 `CodeGraphContext` file (`--as-context`)
+async fn export_level01_as_context(
+    matches: &ArgMatches,
+    db_adapter: &pt02_llm_cozodb_to_context_writer::CozoDbAdapter,
+    repository: &dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+    where_clause: &str,
+) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::{export_code_graph_context, manifest_name, ExportManifest, ManifestRecorder};
 
-    let include_code = matches.get_one::<String>("include-code").unwrap();
-    let where_clause = matches.get_one::<String>("where-clause").unwrap();
     let output = matches.get_one::<String>("output").unwrap();
     let db = matches.get_one::<String>("db").unwrap();
-    let verbose = matches.get_flag("verbose");
-
-    println!("{}", style("Running PT02 Level 2: Entity + ISG + Temporal + Type System Export").cyan());
-    if verbose {
-        println!("  Database: {}", db);
-        println!("  Include code: {}", if include_code == "1" { "YES (expensive)" } else { "NO (cheap)" });
-        println!("  WHERE clause: {}", where_clause);
-        println!("  Output: {}", output);
-    }
-
-    // Connect to CozoDB
-    let db_adapter = CozoDbAdapter::connect(db).await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
-
-    // Create exporter
-    let exporter = Level2Exporter::new();
-    
-    // Extract base output name (remove .json extension if present)
-    let base_output = if output.ends_with(".json") {
-        &output[..output.len() - 5]
+    let compact = matches.get_flag("compact");
+    let (sink, output) = sink_for_output(output)
+        .map_err(|e| anyhow::anyhow!("Invalid output destination: {}", e))?;
+    let sink = with_json_case(matches, sink);
+    let base_output = output.strip_suffix(".json").unwrap_or(&output);
+
+    // --no-timestamp pins generated_at so unchanged data exports identically
+    let generated_at = if matches.get_flag("no-timestamp") {
+        chrono::DateTime::<chrono::Utc>::UNIX_EPOCH
     } else {
-        output
+        chrono::Utc::now()
+    };
+    let context = export_code_graph_context(repository, db_adapter.get_code_entities().await?, where_clause, generated_at)
+        .await
+        .map_err(|e| anyhow::anyhow!("Export failed: {}", e))?;
+    let json = if compact {
+        serde_json::to_vec(&context)?
+    } else {
+        serde_json::to_vec_pretty(&context)?
     };
 
-    let base_tokens = exporter.estimated_tokens();
-    let estimated = if include_code == "1" { base_tokens * 20 } else { base_tokens };
+    let file_name = format!("{}.json", base_output);
+    let recorder = ManifestRecorder::new(sink.as_ref());
+    recorder
+        .write_all(&file_name, &json)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", file_name, e))?;
+    recorder
+        .write_manifest(base_output, ExportManifest::new(1, where_clause, false, db), compact)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write export manifest: {}", e))?;
+
+    println!("{}", style("✓ PT02 Level 1 export completed (CodeGraphContext)").green().bold());
+    println!("  Output file: {}", file_name);
+    println!("  Manifest: {}", manifest_name(base_output));
+    println!("  Entities exported: {}", context.entities.len());
+    println!("  Relationships: {}", context.relationships.len());
+    println!("  Token estimate: ~{} tokens", context.token_count);
+    Ok(())
+}
 
-    if verbose {
-        println!("  Estimated tokens: ~{}", estimated);
+/// Level 1 dual-file export through `ExternalSortExporter`
+async fn export_level01_externally(
+    exporter: &pt02_llm_cozodb_to_context_writer::ExternalSortExporter,
+    repository: &dyn pt02_llm_cozodb_to_context_writer::CodeGraphRepository,
+    base_output: &str,
+    include_code: bool,
+    where_clause: &str,
+) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::scope_to_entity_class;
+
+    for (class, path) in [("CODE", format!("{}.json", base_output)), ("TEST", format!("{}_test.json", base_output))] {
+        let file = std::fs::File::create(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", path, e))?;
+        let mut out = std::io::BufWriter::new(file);
+        let written = exporter
+            .export_level1(repository, &scope_to_entity_class(class, where_clause), include_code, &mut out)
+            .await
+            .map_err(|e| anyhow::anyhow!("Export failed: {}", e))?;
+        println!("  {}: {} entities", path, written);
     }
+    println!("{}", style("✓ PT02 Level 1 export completed (external sort)").green().bold());
+    Ok(())
+}
 
-    // Execute dual file export (REQ-V090-004.0: Automatic dual-file export)
-    exporter.export_dual_files(
-        &db_adapter,
-        base_output,
-        include_code == "1",
-        where_clause
-    ).await
-        .map_err(|e| anyhow::anyhow!("Export failed: {}", e))?;
+async fn run_pt02_compare_levels(matches: &ArgMatches) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::{compare_levels, format_comparison, CozoDbAdapter};
 
-    println!("{}", style("✓ PT02 Level 2 export completed").green().bold());
-    println!("  Output files: {}.json, {}_test.json", base_output, base_output);
-    
-    // Load and display entity counts from the main export file
-    let main_output_file = format!("{}.json", base_output);
-    if let Ok(content) = std::fs::read_to_string(&main_output_file) {
-        if let Ok(export_data) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(entities) = export_data["entities"].as_array() {
-                println!("  Entities exported: {}", entities.len());
-            }
-        }
-    }
-    println!("  Token estimate: ~{} tokens", estimated);
-    println!("  Fields per entity: 16 (includes type system information)");
+    let where_clause = matches.get_one::<String>("where-clause").unwrap();
+    let include_code = matches.get_one::<String>("include-code").unwrap() == "1";
+    let db = matches.get_one::<String>("db").unwrap();
+
+    let db_adapter = CozoDbAdapter::connect(db).await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    let summaries = compare_levels(&db_adapter, where_clause, include_code).await
+        .map_err(|e| anyhow::anyhow!("Comparison failed: {}", e))?;
 
+    println!("{}", style(format!("PT02 level comparison (WHERE {})", where_clause)).cyan());
+    print!("{}", format_comparison(&summaries));
     Ok(())
 }
 
+async fn run_pt02_level02(matches: &ArgMatches) -> Result<()> {
+    use pt02_llm_cozodb_to_context_writer::{ExportOrder, Level2Exporter};
+
+    let include_code = matches.get_one::<String>("include-code").unwrap() == "1";
+    let where_clause = &pt02_where_clause(matches)?;
+    let order = matches.get_one::<String>("order").unwrap().parse::<ExportOrder>().map_err(anyhow::Error::msg)?;
+    let exporter = Level2Exporter::new()
+        .with_timestamp(!matches.get_flag("no-timestamp"))
+        .with_dedup_docs(matches.get_flag("dedup-docs"))
+        .with_order(order);
+    run_pt02_export(matches, Pt02Exporter::Level2(exporter), where_clause, include_code).await
+}
+
 async fn run_rust_preflight_code_simulator(matches: &ArgMatches) -> Result<()> {
     use parseltongue_core::storage::CozoDbStorage;
-    use pt04_syntax_preflight_validator::SimpleSyntaxValidator;
+    use pt04_syntax_preflight_validator::{SimpleSyntaxValidator, SyntaxValidationReport, ValidationCache, ValidationResult};
 
     let db = matches.get_one::<String>("db").unwrap();
     let verbose = matches.get_flag("verbose");
-
-    println!("{}", style("Running Tool 4: pt04-syntax-preflight-validator").cyan());
-    println!("  Database: {}", db);
+    let normalize = matches.get_flag("normalize-whitespace");
+    let check_name_match = matches.get_flag("check-name-match");
+    let fail_on_warnings = matches.get_flag("fail-on-warnings");
+    let only_new = matches.get_flag("only-new");
+    // The JSON report owns stdout, so progress and per-entity lines are skipped
+    let json = matches.get_flag("json");
+
+    if !json {
+        println!("{}", style("Running Tool 4: pt04-syntax-preflight-validator").cyan());
+        println!("  Database: {}", db);
+    }
 
     // Connect to database
     let storage = CozoDbStorage::new(db)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
 
     // Fetch changed entities (those with future_action set)
     let entities = storage.get_changed_entities().await?;
 
-    if entities.is_empty() {
+    if entities.is_empty() && !json {
         println!("{}", style("ℹ No entities with pending changes found").yellow());
         return Ok(());
     }
 
-    println!("  Validating {} changed entities...", entities.len());
+    if !json {
+        println!("  Validating {} changed entities...", entities.len());
+    }
 
     // Create syntax validator
     let mut validator = SimpleSyntaxValidator::new()
@@ -825,46 +2765,111 @@ async fn run_rust_preflight_code_simulator(matches: &ArgMatches) -> Result<()> {
 
     let mut total_validated = 0;
     let mut total_errors = 0;
+    let mut total_warnings = 0;
+    let mut total_normalized = 0;
+    let mut total_skipped = 0;
     let mut validation_details = Vec::new();
+    let mut report = SyntaxValidationReport::new();
+
+    // Clean passes are recorded every run; --only-new skips them
+    let cache_path = ValidationCache::sidecar_path_for_db(db);
+    let mut cache = cache_path
+        .as_deref()
+        .map(|path| ValidationCache::load(path, check_name_match))
+        .unwrap_or_else(|| ValidationCache::new(check_name_match));
 
     // Validate each entity's future_code
     for entity in &entities {
         if let Some(future_code) = &entity.future_code {
+            if only_new && cache.is_validated(&entity.isgl1_key, future_code) {
+                total_skipped += 1;
+                continue;
+            }
             total_validated += 1;
 
-            // Extract language from ISGL1 key (format: language:type:name:path:range)
-            let language = entity.isgl1_key.split(':').next()
-                .and_then(|lang_str| match lang_str {
-                    "rust" => Some(Language::Rust),
-                    _ => Some(Language::Rust), // Default to Rust for now
-                })
+            let language = parse_isgl1_key_components(&entity.isgl1_key)
+                .map(|(_, _, language)| language)
                 .unwrap_or(Language::Rust);
 
-            let result = validator.validate_syntax(future_code, language)
+            let (mut result, normalized) = validator.validate_and_normalize(future_code, language)
                 .map_err(|e| anyhow::anyhow!("Validation failed for {}: {}", entity.isgl1_key, e))?;
 
-            if !result.is_valid {
+            if check_name_match && result.is_valid {
+                match parse_isgl1_key_components(&entity.isgl1_key) {
+                    Ok((_, key_name, _)) => {
+                        let named = validator.check_name_match(future_code, language, &key_name)
+                            .map_err(|e| anyhow::anyhow!("Name check failed for {}: {}", entity.isgl1_key, e))?;
+                        result.warnings.extend(named.warnings);
+                    }
+                    // One unreadable key fails its entity, not the whole run
+                    Err(e) => result = ValidationResult::invalid(vec![format!("Cannot check name match: {}", e)]),
+                }
+            }
+            report.add_result(&entity.isgl1_key, &result, fail_on_warnings);
+            cache.record(&entity.isgl1_key, if normalize { &normalized } else { future_code }, &result);
+            for warning in &result.warnings {
+                total_warnings += 1;
+                if !json {
+                    eprintln!("{} {}: {}", style("⚠").yellow(), entity.isgl1_key, warning);
+                }
+            }
+
+            if normalize && normalized != *future_code {
+                let mut updated = entity.clone();
+                updated.future_code = Some(normalized);
+                storage.update_entity_internal(&updated).await?;
+                total_normalized += 1;
+            }
+
+            if !result.passes(fail_on_warnings) {
                 total_errors += 1;
 
-                if verbose {
+                if verbose && !json {
                     eprintln!("{} {}", style("✗").red(), entity.isgl1_key);
                     for error in &result.errors {
                         eprintln!("  {}", style(error).red());
                     }
                 }
 
-                validation_details.push((entity.isgl1_key.clone(), result.errors));
-            } else if verbose {
+                let mut failures = result.errors;
+                if fail_on_warnings {
+                    failures.extend(result.warnings);
+                }
+                validation_details.push((entity.isgl1_key.clone(), failures));
+            } else if verbose && !json {
                 println!("{} {}", style("✓").green(), entity.isgl1_key);
             }
         }
     }
 
+    if let Some(path) = &cache_path {
+        cache
+            .save(path)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.overall_valid {
+            return Err(anyhow::anyhow!("Syntax validation failed for {} entities", report.entities_failed));
+        }
+        return Ok(());
+    }
+
     // Print summary
     println!();
     if total_errors == 0 {
         println!("{}", style("✓ All syntax validations passed").green().bold());
         println!("  Entities validated: {}", total_validated);
+        if only_new {
+            println!("  Skipped (passed before): {}", total_skipped);
+        }
+        if total_warnings > 0 {
+            println!("  Warnings: {}", total_warnings);
+        }
+        if normalize {
+            println!("  Whitespace normalized: {}", total_normalized);
+        }
     } else {
         eprintln!("{}", style("✗ Syntax validation failed").red().bold());
         eprintln!("  Entities validated: {}", total_validated);
@@ -889,10 +2894,12 @@ async fn run_rust_preflight_code_simulator(matches: &ArgMatches) -> Result<()> {
 
 async fn run_llm_cozodb_to_diff_writer(matches: &ArgMatches) -> Result<()> {
     use parseltongue_core::storage::CozoDbStorage;
-    use pt05_llm_cozodb_to_diff_writer::DiffGenerator;
+    use pt05_llm_cozodb_to_diff_writer::{DiffGenerator, FileWriter, FileWriterConfig, LineEnding};
     use std::sync::Arc;
 
     let output = matches.get_one::<String>("output").unwrap();
+    let patch = matches.get_one::<String>("patch");
+    let root = matches.get_one::<String>("root").unwrap();
     let db = matches.get_one::<String>("db").unwrap();
 
     println!("{}", style("Running Tool 5: pt05-llm-cozodb-to-diff-writer").cyan());
@@ -905,9 +2912,50 @@ async fn run_llm_cozodb_to_diff_writer(matches: &ArgMatches) -> Result<()> {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?
     );
+    storage.ensure_schema().await?;
+
+    // --apply splices each change into its file rather than replacing files
+    let line_ending = match matches.get_one::<String>("line-ending").map(String::as_str) {
+        Some("lf") => LineEnding::Lf,
+        Some("crlf") => LineEnding::Crlf,
+        _ => LineEnding::Preserve,
+    };
+    let writer = FileWriter::new(PathBuf::from(root)).with_config(FileWriterConfig {
+        line_ending,
+        indent_context: true,
+        ..Default::default()
+    });
+    if matches.get_flag("recover") {
+        let summary = writer.recover()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to recover interrupted writes: {}", e))?;
+        println!("{}", style(format!("✓ Recovered interrupted writes: {} file(s)", summary.total)).green());
+    } else if writer.has_incomplete_journal() {
+        println!(
+            "{} An earlier --apply was interrupted ({}); rerun with --recover",
+            style("⚠").yellow(),
+            writer.journal_path().display()
+        );
+    }
+
+    if matches.get_flag("validate-keys") {
+        let orphans = storage.find_orphan_edges()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to validate keys: {}", e))?;
+        report_orphan_edges(&orphans, matches.get_flag("strict"))?;
+    }
+
+    if matches.get_flag("check") {
+        let changed = storage.get_changed_entities()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get changed entities: {}", e))?;
+        report_source_drift(root, &changed, matches.get_flag("force"))?;
+    }
 
     // Create diff generator with dependency injection
-    let generator = DiffGenerator::new(storage);
+    let generator = DiffGenerator::new(storage)
+        .with_source_root(root)
+        .with_move_detection(matches.get_flag("detect-moves"));
 
     // Generate CodeDiff from changed entities
     let diff = generator.generate_diff()
@@ -919,13 +2967,26 @@ async fn run_llm_cozodb_to_diff_writer(matches: &ArgMatches) -> Result<()> {
         return Ok(());
     }
 
-    // Serialize to JSON
-    let json = diff.to_json_pretty()
+    // Serialize to JSON, optionally grouped per file
+    let compact = matches.get_flag("compact");
+    let serialized = if matches.get_flag("group-by-file") {
+        let grouped = diff.group_by_file();
+        if compact { grouped.to_json_compact() } else { grouped.to_json_pretty() }
+    } else if compact {
+        diff.to_json_compact()
+    } else {
+        diff.to_json_pretty()
+    };
+    let json = serialized
         .map_err(|e| anyhow::anyhow!("Failed to serialize diff to JSON: {}", e))?;
 
-    // Write to file
-    std::fs::write(output, json)
-        .map_err(|e| anyhow::anyhow!("Failed to write to file: {}", e))?;
+    // Write to the output destination (local file or URL sink)
+    let (sink, output_name) = sink_for_output(output)
+        .map_err(|e| anyhow::anyhow!("Invalid output destination: {}", e))?;
+    let sink = with_json_case(matches, sink);
+    sink.write_all(&output_name, json.as_bytes())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write output: {}", e))?;
 
     println!("{}", style("✓ CodeDiff.json generated").green());
     println!("  Output file: {}", output);
@@ -935,16 +2996,43 @@ async fn run_llm_cozodb_to_diff_writer(matches: &ArgMatches) -> Result<()> {
     let mut creates = 0;
     let mut edits = 0;
     let mut deletes = 0;
+    let mut moves = 0;
     for change in &diff.changes {
         match change.operation {
             pt05_llm_cozodb_to_diff_writer::Operation::Create => creates += 1,
             pt05_llm_cozodb_to_diff_writer::Operation::Edit => edits += 1,
             pt05_llm_cozodb_to_diff_writer::Operation::Delete => deletes += 1,
+            pt05_llm_cozodb_to_diff_writer::Operation::Move { .. } => moves += 1,
         }
     }
     println!("    Creates: {}", creates);
     println!("    Edits: {}", edits);
     println!("    Deletes: {}", deletes);
+    if moves > 0 {
+        println!("    Moves: {}", moves);
+    }
+
+    if let Some(patch_path) = patch {
+        let patch_text = generator.generate_git_patch()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to generate git patch: {}", e))?;
+        std::fs::write(patch_path, patch_text)
+            .map_err(|e| anyhow::anyhow!("Failed to write patch file: {}", e))?;
+        println!("{}", style("✓ Patch generated (apply with `git apply`)").green());
+        println!("  Patch file: {}", patch_path);
+    }
+
+    if matches.get_flag("apply") {
+        let changed = storage.get_changed_entities()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get changed entities: {}", e))?;
+        let summary = writer.write_entities(&changed)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to apply changes: {}", e))?;
+        println!("{}", style(format!("✓ Changes applied under {}", root)).green());
+        println!("    Files created: {}", summary.created);
+        println!("    Files edited: {}", summary.edited);
+    }
 
     Ok(())
 }
@@ -965,6 +3053,7 @@ async fn run_cozodb_make_future_code_current(matches: &ArgMatches) -> Result<()>
     let storage = CozoDbStorage::new(db)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+    storage.ensure_schema().await?;
 
     // Create state reset manager
     let reset_manager = StateResetManager::new(storage);
@@ -985,9 +3074,12 @@ async fn run_cozodb_make_future_code_current(matches: &ArgMatches) -> Result<()>
 }
 
 async fn run_pt07(matches: &ArgMatches) -> Result<()> {
+    use pt07_visual_analytics_terminal::{save_visualization_output_to, OutputLocation};
     use pt07_visual_analytics_terminal::visualizations::{
         render_entity_count_bar_chart_visualization,
         render_dependency_cycle_warning_list_visualization,
+        render_dependency_heatmap_visualization,
+        render_pending_changes_visualization,
     };
 
     println!("{}", style("Running Tool 7: Visual Analytics").cyan());
@@ -1013,12 +3105,55 @@ async fn run_pt07(matches: &ArgMatches) -> Result<()> {
 
             Ok(())
         }
+        Some(("heatmap", sub_matches)) => {
+            let db = sub_matches.get_one::<String>("db").unwrap();
+            let top = *sub_matches.get_one::<usize>("top").unwrap();
+            let include_tests = sub_matches.get_flag("include-tests");
+
+            println!("🔥 Ranking most-depended-upon entities...");
+            let output = render_dependency_heatmap_visualization(db, include_tests, top).await?;
+
+            let mut command_args = format!("--db {} --top {}", db, top);
+            if include_tests {
+                command_args.push_str(" --include-tests");
+            }
+            let mut location = OutputLocation::default().with_filename_template(
+                sub_matches.get_one::<String>("filename-template").unwrap(),
+            );
+            if let Some(output_dir) = sub_matches.get_one::<String>("output-dir") {
+                location = location.with_output_dir(output_dir);
+            }
+            save_visualization_output_to(&location, "pt07-render-dependency-heatmap", &command_args, db, &output)?;
+
+            Ok(())
+        }
+        Some(("pending", sub_matches)) => {
+            let db = sub_matches.get_one::<String>("db").unwrap();
+            let top = *sub_matches.get_one::<usize>("top").unwrap();
+            let sample = *sub_matches.get_one::<usize>("sample").unwrap();
+
+            println!("⏳ Summarizing pending changes...");
+            let output = render_pending_changes_visualization(db, top, sample).await?;
+
+            let command_args = format!("--db {} --top {} --sample {}", db, top, sample);
+            let mut location = OutputLocation::default().with_filename_template(
+                sub_matches.get_one::<String>("filename-template").unwrap(),
+            );
+            if let Some(output_dir) = sub_matches.get_one::<String>("output-dir") {
+                location = location.with_output_dir(output_dir);
+            }
+            save_visualization_output_to(&location, "pt07-render-pending-changes", &command_args, db, &output)?;
+
+            Ok(())
+        }
         _ => {
             println!("Usage: parseltongue pt07 <SUBCOMMAND>");
             println!();
             println!("Subcommands:");
             println!("  entity-count  - Entity count bar chart");
             println!("  cycles        - Circular dependency detection");
+            println!("  heatmap       - Most-depended-upon entities");
+            println!("  pending       - Pending Create/Edit/Delete changes");
             Ok(())
         }
     }
@@ -1028,6 +3163,21 @@ async fn run_pt07(matches: &ArgMatches) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_create_key_components_in_either_format() {
+        let (path, name, language) = parse_isgl1_key_components("python:fn:save:app_repo_py:3-7").unwrap();
+        assert_eq!((path, name.as_str(), language), (PathBuf::from("app_repo_py"), "save", Language::Python));
+
+        let (path, name, language) = parse_isgl1_key_components("app_repo_py-save-fn-python-3-7").unwrap();
+        assert_eq!((path, name.as_str(), language), (PathBuf::from("app_repo_py"), "save", Language::Python));
+
+        let (path, name, language) = parse_isgl1_key_components("python:fn:save:app_repo_py").unwrap();
+        assert_eq!((path, name.as_str(), language), (PathBuf::from("app_repo_py"), "save", Language::Python));
+
+        let (path, name, _) = parse_isgl1_key_components("src/lib.rs-lib-add").unwrap();
+        assert_eq!((path, name.as_str()), (PathBuf::from("src/lib.rs"), "add"));
+    }
+
     #[test]
     fn test_cli_builds() {
         let cli = build_cli();
@@ -1037,10 +3187,120 @@ mod tests {
         assert!(subcommands.contains(&"pt02-level00")); // Progressive disclosure
         assert!(subcommands.contains(&"pt02-level01")); // Progressive disclosure
         assert!(subcommands.contains(&"pt02-level02")); // Progressive disclosure
+        assert!(subcommands.contains(&"pt02-compare-levels"));
         assert!(subcommands.contains(&"pt03-llm-to-cozodb-writer"));
         assert!(subcommands.contains(&"pt04-syntax-preflight-validator"));
         assert!(subcommands.contains(&"pt05-llm-cozodb-to-diff-writer"));
         assert!(subcommands.contains(&"pt06-cozodb-make-future-code-current"));
         assert!(subcommands.contains(&"pt07")); // NEW v0.9.2: Visual analytics
     }
+
+    #[test]
+    fn test_validation_tools_share_fail_on_warnings() {
+        for subcommand in [
+            "pt01-folder-to-cozodb-streamer",
+            "pt02-level00",
+            "pt02-level01",
+            "pt02-level02",
+            "pt04-syntax-preflight-validator",
+        ] {
+            let cli = build_cli().find_subcommand(subcommand).cloned().unwrap();
+            assert!(
+                cli.get_arguments().any(|arg| arg.get_id() == "fail-on-warnings"),
+                "{} lacks --fail-on-warnings",
+                subcommand
+            );
+        }
+    }
+
+    #[test]
+    fn test_pt02_levels_share_common_flags() {
+        build_cli().debug_assert();
+        for subcommand in ["pt02-level00", "pt02-level01", "pt02-level02"] {
+            let cli = build_cli().find_subcommand(subcommand).cloned().unwrap();
+            for arg in pt02_common_args() {
+                assert!(
+                    cli.get_arguments().any(|defined| defined.get_id() == arg.get_id()),
+                    "{} lacks --{}",
+                    subcommand,
+                    arg.get_id()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_typed_filters_are_anded_with_where_clause() {
+        let matches = build_cli()
+            .try_get_matches_from([
+                "parseltongue",
+                "pt02-level01",
+                "--include-code",
+                "0",
+                "--where-clause",
+                "future_action != null",
+                "--entity-type",
+                "function",
+                "--entity-type",
+                "struct",
+                "--visibility",
+                "public",
+            ])
+            .unwrap();
+        let (_, sub_matches) = matches.subcommand().unwrap();
+
+        let public = r#"str_includes(interface_signature, '"visibility":"Public"')"#;
+        assert_eq!(
+            pt02_where_clause(sub_matches).unwrap(),
+            format!(
+                "future_action != null, ((entity_type = 'function', {0}) or (entity_type = 'struct', {0}))",
+                public
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_stops_when_pt04_finds_a_syntax_error() {
+        use pipeline::{PipelineStep, StepStatus};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = format!("rocksdb:{}", dir.path().join("pipeline.db").display());
+        let output = dir.path().join("CodeDiff.json");
+        let cli = build_cli()
+            .try_get_matches_from([
+                "parseltongue",
+                "pipeline",
+                "--steps",
+                "pt03,pt04,pt05",
+                "--db",
+                &db,
+                "--entity",
+                "src_lib_rs-broken-fn",
+                "--action",
+                "create",
+                "--future-code",
+                "fn broken( {",
+                "--output",
+                output.to_str().unwrap(),
+            ])
+            .unwrap();
+        let (_, matches) = cli.subcommand().unwrap();
+
+        let summary = execute_pipeline(matches).await.unwrap();
+        let steps: Vec<_> = summary.outcomes.iter().map(|outcome| outcome.step).collect();
+        assert_eq!(steps, vec![PipelineStep::Write, PipelineStep::Validate, PipelineStep::Diff]);
+        assert_eq!(summary.outcomes[0].status, StepStatus::Succeeded);
+        assert!(
+            matches!(&summary.outcomes[1].status, StepStatus::Failed(e) if e.contains("Syntax validation failed")),
+            "{:?}",
+            summary.outcomes[1].status
+        );
+        assert_eq!(summary.outcomes[2].status, StepStatus::Skipped);
+        assert!(!output.exists());
+
+        let report = summary.to_string();
+        assert!(report.contains("✗ pt04  failed"), "{}", report);
+        assert!(report.contains("- pt05  skipped"), "{}", report);
+        assert!(report.contains("1 of 3 step(s) failed"), "{}", report);
+    }
 }
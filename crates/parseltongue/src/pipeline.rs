@@ -0,0 +1,185 @@
+//! Multi-tool pipeline (`parseltongue pipeline`).
+//!
+//! Runs tools in order against one database and collects each step's outcome
+//! and timing into a single summary. Steps after the first failure are
+//! skipped unless `--keep-going` is set.
+
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+/// A tool that can run as a pipeline step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PipelineStep {
+    Ingest,
+    Write,
+    Validate,
+    Diff,
+    Reset,
+}
+
+impl PipelineStep {
+    /// Parse a step from its short (`pt04`) or full subcommand name
+    pub(crate) fn parse(name: &str) -> Result<Self> {
+        let name = name.trim();
+        let step = [Self::Ingest, Self::Write, Self::Validate, Self::Diff, Self::Reset]
+            .into_iter()
+            .find(|step| name == step.short_name() || name == step.subcommand());
+        match step {
+            Some(step) => Ok(step),
+            None => bail!("Unknown pipeline step '{}' (expected pt01, pt03, pt04, pt05 or pt06)", name),
+        }
+    }
+
+    pub(crate) fn short_name(self) -> &'static str {
+        match self {
+            Self::Ingest => "pt01",
+            Self::Write => "pt03",
+            Self::Validate => "pt04",
+            Self::Diff => "pt05",
+            Self::Reset => "pt06",
+        }
+    }
+
+    /// Subcommand that implements the step
+    pub(crate) fn subcommand(self) -> &'static str {
+        match self {
+            Self::Ingest => "pt01-folder-to-cozodb-streamer",
+            Self::Write => "pt03-llm-to-cozodb-writer",
+            Self::Validate => "pt04-syntax-preflight-validator",
+            Self::Diff => "pt05-llm-cozodb-to-diff-writer",
+            Self::Reset => "pt06-cozodb-make-future-code-current",
+        }
+    }
+}
+
+/// Parse a comma-separated `--steps` list
+pub(crate) fn parse_steps(list: &str) -> Result<Vec<PipelineStep>> {
+    let steps = list
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(PipelineStep::parse)
+        .collect::<Result<Vec<_>>>()?;
+    if steps.is_empty() {
+        bail!("--steps needs at least one step");
+    }
+    Ok(steps)
+}
+
+/// How a step ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StepStatus {
+    Succeeded,
+    Failed(String),
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StepOutcome {
+    pub(crate) step: PipelineStep,
+    pub(crate) status: StepStatus,
+    pub(crate) elapsed: Duration,
+}
+
+/// Outcome of every requested step, in order
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PipelineSummary {
+    pub(crate) outcomes: Vec<StepOutcome>,
+}
+
+impl PipelineSummary {
+    pub(crate) fn failed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.status, StepStatus::Failed(_)))
+            .count()
+    }
+}
+
+impl fmt::Display for PipelineSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pipeline summary:")?;
+        for outcome in &self.outcomes {
+            let name = outcome.step.short_name();
+            match &outcome.status {
+                StepStatus::Succeeded => writeln!(f, "  ✓ {}  ok      {:?}", name, outcome.elapsed)?,
+                StepStatus::Failed(error) => {
+                    writeln!(f, "  ✗ {}  failed  {:?}  {}", name, outcome.elapsed, error)?
+                }
+                StepStatus::Skipped => writeln!(f, "  - {}  skipped", name)?,
+            }
+        }
+        let total: Duration = self.outcomes.iter().map(|outcome| outcome.elapsed).sum();
+        write!(
+            f,
+            "  {} of {} step(s) failed in {:?}",
+            self.failed(),
+            self.outcomes.len(),
+            total
+        )
+    }
+}
+
+/// Run `steps` in order with `run`, stopping at the first failure unless
+/// `keep_going`
+pub(crate) async fn run_steps<F, Fut>(steps: &[PipelineStep], keep_going: bool, mut run: F) -> PipelineSummary
+where
+    F: FnMut(PipelineStep) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut summary = PipelineSummary::default();
+    for &step in steps {
+        if summary.failed() > 0 && !keep_going {
+            summary.outcomes.push(StepOutcome {
+                step,
+                status: StepStatus::Skipped,
+                elapsed: Duration::ZERO,
+            });
+            continue;
+        }
+        let started = Instant::now();
+        let status = match run(step).await {
+            Ok(()) => StepStatus::Succeeded,
+            Err(e) => StepStatus::Failed(format!("{:#}", e)),
+        };
+        summary.outcomes.push(StepOutcome {
+            step,
+            status,
+            elapsed: started.elapsed(),
+        });
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_steps() {
+        assert_eq!(
+            parse_steps("pt01, pt03,pt04-syntax-preflight-validator").unwrap(),
+            vec![PipelineStep::Ingest, PipelineStep::Write, PipelineStep::Validate]
+        );
+        assert!(parse_steps("pt01,pt09").is_err());
+        assert!(parse_steps("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keep_going_runs_steps_after_a_failure() {
+        let steps = [PipelineStep::Validate, PipelineStep::Diff];
+        let summary = run_steps(&steps, true, |step| async move {
+            match step {
+                PipelineStep::Validate => bail!("invalid"),
+                _ => Ok(()),
+            }
+        })
+        .await;
+
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(summary.outcomes[1].status, StepStatus::Succeeded);
+    }
+}
@@ -0,0 +1,229 @@
+//! Interactive exploration shell (`parseltongue repl`).
+//!
+//! Keeps one database connection open and answers short commands against
+//! it. Commands are parsed and executed separately from the line editor, so
+//! they can be driven without a terminal.
+
+use anyhow::{bail, Result};
+use parseltongue_core::storage::CozoDbStorage;
+
+pub(crate) const HELP: &str = "\
+Commands:
+  get <key>          Show an entity
+  deps <key>         Entities <key> depends on
+  rdeps <key>        Entities depending on <key>
+  find <pattern>     Keys containing <pattern> (case-insensitive)
+  query <datalog>    Run a read-only CozoScript query
+  help               Show this help
+  quit               Leave (also Ctrl-D)";
+
+/// One parsed input line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReplCommand {
+    Get(String),
+    Deps(String),
+    Rdeps(String),
+    Find(String),
+    Query(String),
+    Help,
+    Quit,
+}
+
+impl ReplCommand {
+    /// Parse a line; `Ok(None)` for a blank one
+    pub(crate) fn parse(line: &str) -> Result<Option<Self>> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let argument = |name: &str| -> Result<String> {
+            if rest.is_empty() {
+                bail!("usage: {} <{}>", word, name);
+            }
+            Ok(rest.to_string())
+        };
+
+        Ok(Some(match word {
+            "get" => Self::Get(argument("key")?),
+            "deps" => Self::Deps(argument("key")?),
+            "rdeps" => Self::Rdeps(argument("key")?),
+            "find" => Self::Find(argument("pattern")?),
+            "query" => Self::Query(argument("datalog")?),
+            "help" | "?" => Self::Help,
+            "quit" | "exit" => Self::Quit,
+            other => bail!("unknown command '{}' (try 'help')", other),
+        }))
+    }
+}
+
+/// Command executor bound to an open database
+pub(crate) struct Repl {
+    storage: CozoDbStorage,
+}
+
+impl Repl {
+    pub(crate) fn new(storage: CozoDbStorage) -> Self {
+        Self { storage }
+    }
+
+    /// Text to print for `command` (`Quit` is the caller's to handle)
+    pub(crate) async fn execute(&self, command: &ReplCommand) -> Result<String> {
+        match command {
+            ReplCommand::Get(key) => {
+                let entity = self.storage.get_entity(key).await?;
+                let sig = &entity.interface_signature;
+                let mut out = format!(
+                    "{}\n  type:     {:?}\n  file:     {}:{}-{}\n  class:    {:?}\n  temporal: current={} future={} action={:?}",
+                    entity.isgl1_key,
+                    sig.entity_type,
+                    sig.file_path.display(),
+                    sig.line_range.start,
+                    sig.line_range.end,
+                    entity.entity_class,
+                    entity.temporal_state.current_ind,
+                    entity.temporal_state.future_ind,
+                    entity.temporal_state.future_action,
+                );
+                if let Some(code) = &entity.current_code {
+                    out.push_str("\n\n");
+                    out.push_str(code);
+                }
+                Ok(out)
+            }
+            ReplCommand::Deps(key) => Ok(list(self.storage.get_forward_dependencies(key).await?)),
+            ReplCommand::Rdeps(key) => Ok(list(self.storage.get_reverse_dependencies(key).await?)),
+            ReplCommand::Find(pattern) => {
+                let pattern = pattern.to_lowercase();
+                let keys = self
+                    .storage
+                    .get_all_entities()
+                    .await?
+                    .into_iter()
+                    .map(|entity| entity.isgl1_key)
+                    .filter(|key| key.to_lowercase().contains(&pattern))
+                    .collect();
+                Ok(list(keys))
+            }
+            ReplCommand::Query(script) => {
+                let rows = self.storage.raw_query(script).await?;
+                let mut lines = vec![rows.headers.join("\t")];
+                lines.extend(rows.rows.iter().map(|row| {
+                    row.iter().map(|value| value.to_string()).collect::<Vec<_>>().join("\t")
+                }));
+                lines.push(format!("({} rows)", rows.rows.len()));
+                Ok(lines.join("\n"))
+            }
+            ReplCommand::Help => Ok(HELP.to_string()),
+            ReplCommand::Quit => Ok(String::new()),
+        }
+    }
+}
+
+/// Sorted keys, one per line, with a count
+fn list(mut keys: Vec<String>) -> String {
+    keys.sort();
+    keys.dedup();
+    let count = format!("({} entities)", keys.len());
+    keys.push(count);
+    keys.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parseltongue_core::entities::*;
+    use std::path::PathBuf;
+
+    fn entity(key: &str, name: &str) -> CodeEntity {
+        let signature = InterfaceSignature {
+            entity_type: EntityType::Function,
+            name: name.to_string(),
+            visibility: Visibility::Public,
+            file_path: PathBuf::from("src/lib.rs"),
+            line_range: LineRange::new(1, 3).unwrap(),
+            module_path: vec![],
+            documentation: None,
+            language_specific: LanguageSpecificSignature::Rust(RustSignature {
+                generics: vec![],
+                lifetimes: vec![],
+                where_clauses: vec![],
+                attributes: vec![],
+                trait_impl: None,
+            }),
+        };
+        let mut entity = CodeEntity::new(key.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+        entity.current_code = Some(format!("fn {}() {{}}", name));
+        entity.future_code = entity.current_code.clone();
+        entity
+    }
+
+    async fn seeded_repl() -> Repl {
+        let storage = CozoDbStorage::new("mem").await.unwrap();
+        storage.ensure_schema().await.unwrap();
+        storage.insert_entity(&entity("rust:fn:main:src_lib_rs:1-3", "main")).await.unwrap();
+        storage.insert_entity(&entity("rust:fn:parse_args:src_lib_rs:5-7", "parse_args")).await.unwrap();
+        storage
+            .insert_edge(
+                &DependencyEdge::builder()
+                    .from_key("rust:fn:main:src_lib_rs:1-3")
+                    .to_key("rust:fn:parse_args:src_lib_rs:5-7")
+                    .edge_type(EdgeType::Calls)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        Repl::new(storage)
+    }
+
+    async fn run(repl: &Repl, line: &str) -> String {
+        let command = ReplCommand::parse(line).unwrap().unwrap();
+        repl.execute(&command).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_commands_against_in_memory_db() {
+        let repl = seeded_repl().await;
+
+        let got = run(&repl, "get rust:fn:main:src_lib_rs:1-3").await;
+        assert!(got.starts_with("rust:fn:main:src_lib_rs:1-3\n  type:     Function\n"), "{}", got);
+        assert!(got.ends_with("fn main() {}"), "{}", got);
+
+        assert_eq!(
+            run(&repl, "deps rust:fn:main:src_lib_rs:1-3").await,
+            "rust:fn:parse_args:src_lib_rs:5-7\n(1 entities)"
+        );
+        assert_eq!(
+            run(&repl, "rdeps rust:fn:parse_args:src_lib_rs:5-7").await,
+            "rust:fn:main:src_lib_rs:1-3\n(1 entities)"
+        );
+        assert_eq!(run(&repl, "find PARSE").await, "rust:fn:parse_args:src_lib_rs:5-7\n(1 entities)");
+        assert_eq!(
+            run(&repl, "query ?[n] := n = 1 + 1").await,
+            "n\n2\n(1 rows)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_do_not_end_the_session() {
+        let repl = seeded_repl().await;
+
+        let missing = ReplCommand::parse("get nope").unwrap().unwrap();
+        assert!(repl.execute(&missing).await.is_err());
+        assert_eq!(run(&repl, "find main").await, "rust:fn:main:src_lib_rs:1-3\n(1 entities)");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(ReplCommand::parse("   ").unwrap(), None);
+        assert_eq!(
+            ReplCommand::parse("query ?[x] := x = 1").unwrap(),
+            Some(ReplCommand::Query("?[x] := x = 1".to_string()))
+        );
+        assert_eq!(ReplCommand::parse("exit").unwrap(), Some(ReplCommand::Quit));
+        assert!(ReplCommand::parse("deps").unwrap_err().to_string().contains("usage: deps <key>"));
+        assert!(ReplCommand::parse("frobnicate x").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Guarded file reads (`StreamerConfig::max_file_size`, `file_read_timeout`)
+//!
+//! Only regular files are read: opening a FIFO blocks until a writer shows
+//! up and a device can stream forever, so both are skipped and reported.
+//! A regular file is read at most one byte past `max_file_size`, so a file
+//! that grew after its size was checked, or whose metadata understates it
+//! (e.g. under `/proc`), is rejected without being buffered whole.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Time allowed for opening and reading one file unless configured otherwise
+pub const DEFAULT_FILE_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a non-regular file is, for skip reports
+///
+/// Returns `None` for regular files, directories and symlinks.
+pub fn special_file_kind(file_type: &std::fs::FileType) -> Option<&'static str> {
+    if file_type.is_file() || file_type.is_dir() || file_type.is_symlink() {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_fifo() {
+            return Some("FIFO");
+        }
+        if file_type.is_char_device() {
+            return Some("character device");
+        }
+        if file_type.is_block_device() {
+            return Some("block device");
+        }
+        if file_type.is_socket() {
+            return Some("socket");
+        }
+    }
+    Some("special file")
+}
+
+/// Read all of `reader` if it holds at most `limit` bytes
+///
+/// Returns `None` as soon as byte `limit + 1` arrives; memory use stays
+/// bounded by `limit` however much the reader could still produce.
+pub async fn read_to_limit<R: AsyncRead + Unpin>(reader: R, limit: usize) -> std::io::Result<Option<Vec<u8>>> {
+    let mut bytes = Vec::new();
+    reader.take(limit as u64 + 1).read_to_end(&mut bytes).await?;
+    Ok((bytes.len() <= limit).then_some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_endless_reader_is_cut_off_past_the_limit() {
+        assert_eq!(read_to_limit(tokio::io::repeat(b'x'), 4096).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_reader_within_the_limit_is_read_whole() {
+        assert_eq!(read_to_limit(&b"fn a() {}"[..], 9).await.unwrap().as_deref(), Some(&b"fn a() {}"[..]));
+        assert_eq!(read_to_limit(&b"fn a() {}"[..], 8).await.unwrap(), None);
+    }
+}
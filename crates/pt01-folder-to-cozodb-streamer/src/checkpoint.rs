@@ -0,0 +1,249 @@
+//! Resume-from-checkpoint support for long ingestion runs.
+//!
+//! While `stream_directory` runs, the paths of fully-processed files are
+//! periodically written to a JSON sidecar next to the database. A restarted
+//! run skips every recorded file whose modification time is unchanged, or
+//! whose content still matches when only the mtime moved (checkout, `touch`).
+//! The sidecar is deleted once a run completes cleanly.
+//!
+//! A reformatting-only change (`cargo fmt`), recognised by an unchanged
+//! semantic hash of the file's syntax tree, is not skipped: keys and line
+//! numbers move with the layout. The streamer re-indexes such a file without
+//! counting its entities as modified.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parseltongue_core::entities::Language;
+use parseltongue_core::semantic_hash::semantic_hash;
+
+use crate::errors::*;
+
+/// Suffix appended to the database path to form the sidecar path
+pub const CHECKPOINT_FILE_SUFFIX: &str = ".pt01-checkpoint.json";
+
+/// Default number of processed files between checkpoint writes
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 50;
+
+/// What the checkpoint remembers about one processed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointEntry {
+    /// Modification time in nanoseconds since the Unix epoch
+    pub mtime_nanos: u128,
+    /// SHA-256 of the file content, hex encoded
+    pub content_hash: String,
+    /// Formatting-insensitive syntax tree hash; empty without a grammar
+    #[serde(default)]
+    pub semantic_hash: String,
+}
+
+/// Set of files already ingested by an interrupted run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestionCheckpoint {
+    /// Root directory the checkpoint was recorded for
+    pub root_dir: PathBuf,
+    /// Processed files keyed by path
+    pub files: BTreeMap<PathBuf, CheckpointEntry>,
+}
+
+impl IngestionCheckpoint {
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Sidecar location for a database connection string
+    ///
+    /// Returns `None` for in-memory databases: their contents do not survive
+    /// the interruption, so skipping files on restart would lose entities.
+    pub fn sidecar_path_for_db(db_path: &str) -> Option<PathBuf> {
+        let location = db_path.split_once(':').map_or(db_path, |(_, rest)| rest);
+        if db_path == "mem" || location.is_empty() {
+            return None;
+        }
+        Some(PathBuf::from(format!("{}{}", location, CHECKPOINT_FILE_SUFFIX)))
+    }
+
+    /// Load a checkpoint recorded for `root_dir`
+    ///
+    /// A missing, unreadable or foreign (different root) sidecar yields an
+    /// empty checkpoint so ingestion simply starts over.
+    pub fn load(path: &Path, root_dir: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(|checkpoint| checkpoint.root_dir == root_dir)
+            .unwrap_or_else(|| Self::new(root_dir))
+    }
+
+    /// Persist atomically (write to a temp file, then rename)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self).map_err(|e| StreamerError::ConfigurationError {
+            field: "checkpoint".to_string(),
+            reason: e.to_string(),
+        })?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, json)
+            .and_then(|_| std::fs::rename(&tmp_path, path))
+            .map_err(|e| StreamerError::FileSystemError {
+                path: path.to_string_lossy().to_string(),
+                source: e,
+            })
+    }
+
+    /// Delete the sidecar after a clean run (missing file is not an error)
+    pub fn remove(path: &Path) -> Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(StreamerError::FileSystemError {
+                    path: path.to_string_lossy().to_string(),
+                    source: e,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a fully processed file
+    pub fn record(&mut self, file_path: &Path, mtime_nanos: u128, content: &str) {
+        self.files.insert(
+            file_path.to_path_buf(),
+            CheckpointEntry {
+                mtime_nanos,
+                content_hash: content_hash(content),
+                semantic_hash: file_semantic_hash(file_path, content).unwrap_or_default(),
+            },
+        );
+    }
+
+    /// Whether `file_path` can be skipped based on its modification time alone
+    pub fn is_unchanged_by_mtime(&self, file_path: &Path, mtime_nanos: u128) -> bool {
+        self.files
+            .get(file_path)
+            .is_some_and(|entry| entry.mtime_nanos == mtime_nanos)
+    }
+
+    /// Whether `file_path` can be skipped because its content is unchanged
+    pub fn is_unchanged_by_content(&self, file_path: &Path, content: &str) -> bool {
+        self.files
+            .get(file_path)
+            .is_some_and(|entry| entry.content_hash == content_hash(content))
+    }
+
+    /// Whether `file_path` changed only in formatting since it was recorded
+    ///
+    /// Needs a grammar for the file: the content hash differs while the
+    /// semantic hash does not.
+    pub fn is_reformat_only(&self, file_path: &Path, content: &str) -> bool {
+        self.files.get(file_path).is_some_and(|entry| {
+            !entry.semantic_hash.is_empty()
+                && entry.content_hash != content_hash(content)
+                && file_semantic_hash(file_path, content).as_ref() == Some(&entry.semantic_hash)
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Modification time of `path` in nanoseconds since the Unix epoch
+pub fn file_mtime_nanos(path: &Path) -> Option<u128> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_nanos())
+}
+
+/// Hex-encoded SHA-256 of `content` (pure function)
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Semantic hash of a whole file, for languages with a bundled grammar
+fn file_semantic_hash(file_path: &Path, content: &str) -> Option<String> {
+    semantic_hash(content, Language::from_file_path(&file_path.to_path_buf())?)
+}
+
+/// Current time, used when a file reports no modification time
+pub(crate) fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_nanos())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn sidecar_path_follows_database() {
+        assert_eq!(IngestionCheckpoint::sidecar_path_for_db("mem"), None);
+        assert_eq!(
+            IngestionCheckpoint::sidecar_path_for_db("rocksdb:out/analysis.db"),
+            Some(PathBuf::from("out/analysis.db.pt01-checkpoint.json"))
+        );
+        assert_eq!(
+            IngestionCheckpoint::sidecar_path_for_db("analysis.db"),
+            Some(PathBuf::from("analysis.db.pt01-checkpoint.json"))
+        );
+    }
+
+    #[test]
+    fn round_trips_and_rejects_foreign_root() {
+        let dir = TempDir::new().unwrap();
+        let sidecar = dir.path().join("db.pt01-checkpoint.json");
+
+        let mut checkpoint = IngestionCheckpoint::new("/repo");
+        checkpoint.record(Path::new("/repo/src/lib.rs"), 42, "fn main() {}");
+        checkpoint.save(&sidecar).unwrap();
+
+        assert_eq!(IngestionCheckpoint::load(&sidecar, Path::new("/repo")), checkpoint);
+        assert!(IngestionCheckpoint::load(&sidecar, Path::new("/other")).is_empty());
+
+        IngestionCheckpoint::remove(&sidecar).unwrap();
+        assert!(!sidecar.exists());
+        IngestionCheckpoint::remove(&sidecar).unwrap();
+    }
+
+    #[test]
+    fn content_hash_survives_mtime_change() {
+        let mut checkpoint = IngestionCheckpoint::new("/repo");
+        let file = Path::new("/repo/src/lib.rs");
+        checkpoint.record(file, 42, "fn main() {}");
+
+        assert!(checkpoint.is_unchanged_by_mtime(file, 42));
+        assert!(!checkpoint.is_unchanged_by_mtime(file, 43));
+        assert!(checkpoint.is_unchanged_by_content(file, "fn main() {}"));
+        assert!(!checkpoint.is_unchanged_by_content(file, "fn main() { todo!() }"));
+    }
+
+    #[test]
+    fn reformatting_alone_is_reformat_only_not_unchanged() {
+        let mut checkpoint = IngestionCheckpoint::new("/repo");
+        let rust = Path::new("/repo/src/lib.rs");
+        checkpoint.record(rust, 42, "fn main() { run(); }");
+        assert!(!checkpoint.is_unchanged_by_content(rust, "fn main() {\n    run();\n}\n"));
+        assert!(checkpoint.is_reformat_only(rust, "fn main() {\n    run();\n}\n"));
+        assert!(!checkpoint.is_reformat_only(rust, "fn main() {\n    stop();\n}\n"));
+        assert!(!checkpoint.is_reformat_only(rust, "fn main() { run(); }"), "identical is unchanged");
+
+        // No grammar: exact content hash only
+        let text = Path::new("/repo/notes.txt");
+        checkpoint.record(text, 42, "a b");
+        assert!(!checkpoint.is_unchanged_by_content(text, "a  b"));
+        assert!(!checkpoint.is_reformat_only(text, "a  b"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Pluggable chunking: how a parsed file is cut into stored entities.
+//!
+//! ISGL1 (one entity per function, struct, class, ...) is the default. Every
+//! strategy, ISGL1 included, plugs into `Isgl1KeyGeneratorImpl::with_chunking`
+//! and receives the tree-sitter tree of every file; each `ChunkSpan` it
+//! returns becomes one entity keyed like any other. Only ISGL1 returns
+//! dependency edges, since they connect ISGL1 entities.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use parseltongue_core::entities::{DependencyEdge, Language};
+use parseltongue_core::query_extractor::{tree_sitter_language, QueryBasedExtractor};
+use tree_sitter::Tree;
+
+use crate::errors::{Result, StreamerError};
+use crate::isgl1_generator::{EntityType, Isgl1KeyGeneratorImpl};
+
+/// Name of the default strategy, as used in `StreamerConfig.chunking`
+pub const ISGL1_CHUNKING: &str = "ISGL1";
+
+/// One chunk of a file; becomes one entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkSpan {
+    /// Name segment of the entity's ISGL1 key
+    pub name: String,
+    pub entity_type: EntityType,
+    /// 1-based inclusive line range
+    pub line_range: (usize, usize),
+}
+
+/// Strategy for cutting a parsed file into chunks
+///
+/// A strategy that cannot cut a file returns an error rather than
+/// panicking or logging; the file is then reported as failed to parse.
+pub trait ChunkingStrategy: Send + Sync {
+    /// Name recorded in `StreamerConfig.chunking`
+    fn name(&self) -> &str;
+
+    /// Chunks of `source`, which `tree` was parsed from
+    fn chunk(&self, tree: &Tree, source: &str) -> Result<Vec<ChunkSpan>>;
+
+    /// Chunks of the file at `file_path` and the dependency edges between
+    /// them, in one pass
+    ///
+    /// The key generator calls this; the default has no edges.
+    fn chunk_with_dependencies(&self, tree: &Tree, source: &str, _file_path: &Path) -> Result<(Vec<ChunkSpan>, Vec<DependencyEdge>)> {
+        Ok((self.chunk(tree, source)?, Vec::new()))
+    }
+}
+
+/// One chunk per code entity, as found by the `.scm` entity queries (default)
+#[derive(Default)]
+pub struct Isgl1Chunking {
+    // Built on first use, so strategies that are never used cost nothing;
+    // a failure to build is kept and reported on every use
+    extractor: OnceLock<std::result::Result<Mutex<QueryBasedExtractor>, String>>,
+}
+
+impl Isgl1Chunking {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChunkingStrategy for Isgl1Chunking {
+    fn name(&self) -> &str {
+        ISGL1_CHUNKING
+    }
+
+    fn chunk(&self, tree: &Tree, source: &str) -> Result<Vec<ChunkSpan>> {
+        Ok(self.chunk_with_dependencies(tree, source, Path::new(""))?.0)
+    }
+
+    fn chunk_with_dependencies(&self, tree: &Tree, source: &str, file_path: &Path) -> Result<(Vec<ChunkSpan>, Vec<DependencyEdge>)> {
+        // The tree only knows its grammar; map it back to the language
+        let Some(language) = Language::all()
+            .into_iter()
+            .find(|&language| tree_sitter_language(language).is_some_and(|grammar| grammar == *tree.language()))
+        else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+
+        let failed = |reason: String| StreamerError::ParsingError {
+            file: file_path.to_string_lossy().to_string(),
+            reason,
+        };
+        let extractor = self
+            .extractor
+            .get_or_init(|| {
+                QueryBasedExtractor::new()
+                    .map(Mutex::new)
+                    .map_err(|e| format!("Failed to initialize QueryBasedExtractor: {}", e))
+            })
+            .as_ref()
+            .map_err(|reason| failed(reason.clone()))?;
+        let mut extractor = extractor
+            .lock()
+            .map_err(|e| failed(format!("QueryBasedExtractor lock poisoned: {}", e)))?;
+        let (entities, dependencies) = extractor
+            .parse_source(source, file_path, language)
+            .map_err(|e| failed(format!("QueryBasedExtractor failed for {:?}: {}", language, e)))?;
+        let chunks = entities
+            .into_iter()
+            .map(|entity| ChunkSpan {
+                name: entity.name,
+                entity_type: Isgl1KeyGeneratorImpl::map_query_entity_type(&entity.entity_type),
+                line_range: entity.line_range,
+            })
+            .collect();
+        Ok((chunks, dependencies))
+    }
+}
+
+/// The whole file as a single `Module` chunk named `file`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WholeFileChunking;
+
+impl ChunkingStrategy for WholeFileChunking {
+    fn name(&self) -> &str {
+        "whole-file"
+    }
+
+    fn chunk(&self, _tree: &Tree, source: &str) -> Result<Vec<ChunkSpan>> {
+        Ok(vec![ChunkSpan {
+            name: "file".to_string(),
+            entity_type: EntityType::Module,
+            line_range: (1, source.lines().count().max(1)),
+        }])
+    }
+}
+
+/// Built-in strategy for a `StreamerConfig.chunking` name (case-insensitive)
+///
+/// Errors with `ConfigurationError` on an unknown name.
+pub fn strategy_for_name(name: &str) -> Result<Arc<dyn ChunkingStrategy>> {
+    if name.eq_ignore_ascii_case(ISGL1_CHUNKING) {
+        Ok(Arc::new(Isgl1Chunking::new()))
+    } else if name.eq_ignore_ascii_case("whole-file") {
+        Ok(Arc::new(WholeFileChunking))
+    } else {
+        Err(StreamerError::ConfigurationError {
+            field: "chunking".to_string(),
+            reason: format!("unknown chunking strategy '{}' (expected ISGL1 or whole-file)", name),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_rust(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_language(Language::Rust).unwrap()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_isgl1_chunking_yields_one_chunk_per_entity() {
+        let source = "fn a() {}\n\nstruct B;\n";
+        let chunks = Isgl1Chunking::new().chunk(&parse_rust(source), source).unwrap();
+
+        let names: Vec<&str> = chunks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names.len(), 2, "{:?}", chunks);
+        assert!(names.contains(&"a") && names.contains(&"B"));
+    }
+
+    #[test]
+    fn test_strategy_for_name() {
+        assert_eq!(strategy_for_name("isgl1").unwrap().name(), ISGL1_CHUNKING);
+        assert_eq!(strategy_for_name("whole-file").unwrap().name(), "whole-file");
+        assert!(matches!(
+            strategy_for_name("semantic"),
+            Err(StreamerError::ConfigurationError { .. })
+        ));
+    }
+}
@@ -10,7 +10,7 @@
 //!
 //! 2. **Standalone Binary** (development): Defined in this file
 //!    - Same CLI as unified binary (for consistency)
-//!    - Internal fields (max_file_size, include_patterns, etc.) use hardcoded defaults
+//!    - Internal fields (include_patterns, etc.) use hardcoded defaults
 //!
 //! ## Philosophy (S01 Ultra-Minimalist)
 //!
@@ -20,9 +20,16 @@
 //! - Hardcoded sensible defaults matching unified binary
 
 use clap::{Arg, ArgAction, Command};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::StreamerConfig;
+use crate::checkpoint::{IngestionCheckpoint, DEFAULT_CHECKPOINT_INTERVAL};
+use crate::dialect::parse_dialect_arg;
+use crate::extra_queries::parse_extra_query_arg;
+use crate::doc_comments::DEFAULT_MAX_DOC_LEN;
+use crate::generated::{default_generated_markers, DEFAULT_GENERATED_HEADER_LINES};
+use crate::{KeyFormat, NameNormalizationPolicy, PathStyle, StreamerConfig};
+use parseltongue_core::entities::Language;
 
 /// CLI configuration builder
 pub struct CliConfig;
@@ -79,16 +86,133 @@ impl CliConfig {
 Examples: -e '.ref' -e 'archive' -e 'tmp/**'
 Patterns are simple substring matches (not regex)."),
             )
+            .arg(
+                Arg::new("no-resume")
+                    .long("no-resume")
+                    .help("Ignore the checkpoint of an interrupted run and re-ingest every file")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("max-file-size")
+                    .long("max-file-size")
+                    .value_name("SIZE")
+                    .help("Skip (and report) files larger than SIZE, e.g. 512KB, 10MB, 1.5GB")
+                    .value_parser(parse_human_size)
+                    .default_value("100MB"),
+            )
+            .arg(
+                Arg::new("file-read-timeout")
+                    .long("file-read-timeout")
+                    .value_name("SECS")
+                    .help("Skip (and report) files not read within SECS seconds, e.g. on a stalled network mount")
+                    .value_parser(clap::value_parser!(u64))
+                    .default_value("30"),
+            )
+            .arg(
+                Arg::new("dialect")
+                    .long("dialect")
+                    .value_name("LANG=DIALECT")
+                    .help("Pin a grammar dialect, e.g. python=py3 or rust=2015 (repeatable)")
+                    .value_parser(parse_dialect_arg)
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("extra-query")
+                    .long("extra-query")
+                    .value_name("LANG=FILE")
+                    .help("Extract more entities with a tree-sitter query file, e.g. rust=macros.scm (repeatable)")
+                    .value_parser(parse_extra_query_arg)
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("store-spans-only")
+                    .long("store-spans-only")
+                    .help("Store byte spans instead of code text (source files must stay in place)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("git-diff-base")
+                    .long("git-diff-base")
+                    .value_name("REF")
+                    .help("Only ingest files changed in <REF>...HEAD (e.g. origin/main)"),
+            )
+            .arg(
+                Arg::new("absolute-paths")
+                    .long("absolute-paths")
+                    .help("Store absolute file paths instead of paths relative to the directory")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("follow-symlinks")
+                    .long("follow-symlinks")
+                    .help("Descend into symlinked directories (each directory is visited once)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("min-entity-lines")
+                    .long("min-entity-lines")
+                    .value_name("N")
+                    .help("Skip entities spanning fewer than N lines (e.g. one-line getters)")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("0"),
+            )
+            .arg(
+                Arg::new("keep-filtered-signatures")
+                    .long("keep-filtered-signatures")
+                    .help("With --min-entity-lines: store small entities without their code instead of skipping them")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("profile")
+                    .long("profile")
+                    .help("Print time spent per phase (walk, read, parse, key generation, database writes)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("memory-budget")
+                    .long("memory-budget")
+                    .value_name("SIZE")
+                    .help("Skip files whose estimated parse memory exceeds this, e.g. 512MB")
+                    .value_parser(parse_human_size),
+            )
+            .arg(
+                Arg::new("generated-marker")
+                    .long("generated-marker")
+                    .value_name("MARKER")
+                    .help("Also flag files as generated by this path pattern (dir/, *.ext) or header text (repeatable; defaults: @generated, // GENERATED, vendor/, *.pb.rs)")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("keep-tests")
+                    .long("keep-tests")
+                    .help("Store test entities too (skipped by default), e.g. for pt02 --pair-tests")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("lsp")
+                    .long("lsp")
+                    .help("Add rust-analyzer hover, definition, references and diagnostics to Rust entities (needs rust-analyzer on PATH)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("key-format")
+                    .long("key-format")
+                    .help("ISGL1 key layout: colon (rust:fn:name:path:1-5) or dash (path-name-fn-rust-1-5)")
+                    .value_parser(["colon", "dash"])
+                    .default_value("colon"),
+            )
     }
 
     /// Parse CLI arguments into StreamerConfig
     ///
     /// Uses hardcoded defaults for internal fields (matching unified binary behavior):
-    /// - max_file_size: 100MB (ultra-minimalist: let tree-sitter decide what to parse)
+    /// - max_file_size: `--max-file-size` (default 100MB); larger files are reported as skipped
+    /// - file_read_timeout: `--file-read-timeout` (default 30s); slower files are reported as skipped
     /// - include_patterns: ALL files (tree-sitter handles unsupported files gracefully)
     /// - exclude_patterns: Common build/dependency dirs + user patterns
     /// - parsing_library: "tree-sitter"
     /// - chunking: "ISGL1"
+    /// - checkpoint_path: sidecar next to the database (none for `mem`)
     pub fn parse_config(matches: &clap::ArgMatches) -> StreamerConfig {
         // Start with default exclusion patterns
         let mut exclude_patterns = vec![
@@ -109,15 +233,58 @@ Patterns are simple substring matches (not regex)."),
             }
         }
         
+        let db_path = matches.get_one::<String>("database").unwrap().clone();
+
         StreamerConfig {
             root_dir: PathBuf::from(matches.get_one::<String>("directory").unwrap()),
-            db_path: matches.get_one::<String>("database").unwrap().clone(),
-            // Hardcoded defaults (S01 ultra-minimalist - NO artificial limits)
-            max_file_size: 100 * 1024 * 1024,  // 100MB - let tree-sitter decide
+            checkpoint_path: IngestionCheckpoint::sidecar_path_for_db(&db_path),
+            db_path,
+            // Oversized files are reported in StreamResult.errors, not dropped silently
+            max_file_size: *matches.get_one::<usize>("max-file-size").unwrap(),
+            file_read_timeout: std::time::Duration::from_secs(*matches.get_one::<u64>("file-read-timeout").unwrap()),
             include_patterns: vec!["*".to_string()],  // ALL files - tree-sitter handles it
             exclude_patterns,
             parsing_library: "tree-sitter".to_string(),
             chunking: "ISGL1".to_string(),
+            name_normalization: NameNormalizationPolicy::default(),
+            key_format: match matches.get_one::<String>("key-format").map(String::as_str) {
+                Some("dash") => KeyFormat::DashSimple,
+                _ => KeyFormat::ColonQualified,
+            },
+            resume: !matches.get_flag("no-resume"),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            max_doc_len: DEFAULT_MAX_DOC_LEN,
+            language_dialects: matches
+                .get_many::<(Language, String)>("dialect")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+            store_spans_only: matches.get_flag("store-spans-only"),
+            git_diff_base: matches.get_one::<String>("git-diff-base").cloned(),
+            path_style: if matches.get_flag("absolute-paths") {
+                PathStyle::Absolute
+            } else {
+                PathStyle::Relative
+            },
+            follow_symlinks: matches.get_flag("follow-symlinks"),
+            min_entity_lines: *matches.get_one::<usize>("min-entity-lines").unwrap(),
+            keep_signatures_of_filtered: matches.get_flag("keep-filtered-signatures"),
+            profile: matches.get_flag("profile"),
+            memory_budget_bytes: matches.get_one::<usize>("memory-budget").copied(),
+            generated_markers: default_generated_markers()
+                .into_iter()
+                .chain(matches.get_many::<String>("generated-marker").into_iter().flatten().cloned())
+                .collect(),
+            generated_header_lines: DEFAULT_GENERATED_HEADER_LINES,
+            keep_test_entities: matches.get_flag("keep-tests"),
+            lsp: matches.get_flag("lsp"),
+            extra_queries: matches
+                .get_many::<(Language, String)>("extra-query")
+                .into_iter()
+                .flatten()
+                .fold(HashMap::new(), |mut queries, (language, query)| {
+                    queries.entry(*language).or_insert_with(Vec::new).push(query.clone());
+                    queries
+                }),
         }
     }
 
@@ -134,6 +301,36 @@ Patterns are simple substring matches (not regex)."),
     }
 }
 
+/// Parse a human-readable size (`4096`, `512KB`, `10MB`, `1.5GB`) into bytes
+///
+/// Units are binary (1KB = 1024 bytes) and case-insensitive; `K`/`M`/`G`/`T`
+/// and `KiB`-style suffixes are accepted too. Usable as a clap `value_parser`.
+pub fn parse_human_size(value: &str) -> Result<usize, String> {
+    let trimmed = value.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{}': expected a number like 10MB", value))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        other => return Err(format!("invalid size unit '{}' in '{}' (use B, KB, MB, GB, TB)", other, value)),
+    };
+
+    let bytes = number * multiplier as f64;
+    if !bytes.is_finite() || bytes > usize::MAX as f64 {
+        return Err(format!("size '{}' is too large", value));
+    }
+    Ok(bytes.round() as usize)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,6 +443,54 @@ mod tests {
         assert_eq!(config.exclude_patterns.len(), 11); // 8 defaults + 3 user
     }
 
+    #[test]
+    fn test_parse_human_size() {
+        assert_eq!(parse_human_size("4096"), Ok(4096));
+        assert_eq!(parse_human_size("512KB"), Ok(512 * 1024));
+        assert_eq!(parse_human_size("10mb"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_human_size("1.5GB"), Ok(1536 * 1024 * 1024));
+        assert_eq!(parse_human_size("2 MiB"), Ok(2 * 1024 * 1024));
+        assert!(parse_human_size("MB").is_err());
+        assert!(parse_human_size("10XB").is_err());
+
+        let matches = CliConfig::build_cli()
+            .try_get_matches_from(["parseltongue-01", ".", "--max-file-size", "2MB"])
+            .unwrap();
+        assert_eq!(CliConfig::parse_config(&matches).max_file_size, 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_dialect_flags_parsed_and_validated() {
+        let matches = CliConfig::build_cli()
+            .try_get_matches_from(["parseltongue-01", ".", "--dialect", "python=py3", "--dialect", "rust=2015"])
+            .unwrap();
+        let config = CliConfig::parse_config(&matches);
+        assert_eq!(config.language_dialects.get(&Language::Python).map(String::as_str), Some("py3"));
+        assert_eq!(config.language_dialects.get(&Language::Rust).map(String::as_str), Some("2015"));
+
+        let unknown = CliConfig::build_cli().try_get_matches_from(["parseltongue-01", ".", "--dialect", "python=py4"]);
+        assert!(unknown.is_err(), "unknown dialects are rejected at argument parsing");
+    }
+
+    #[test]
+    fn test_extra_query_flags_grouped_by_language() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let macros = dir.path().join("macros.scm");
+        std::fs::write(&macros, "(macro_definition name: (identifier) @name) @definition.macro").unwrap();
+        let broken = dir.path().join("broken.scm");
+        std::fs::write(&broken, "(macro_definition").unwrap();
+        let arg = |lang: &str, file: &std::path::Path| format!("{}={}", lang, file.display());
+
+        let matches = CliConfig::build_cli()
+            .try_get_matches_from(["parseltongue-01", ".", "--extra-query", &arg("rust", &macros), "--extra-query", &arg("rust", &macros)])
+            .unwrap();
+        let config = CliConfig::parse_config(&matches);
+        assert_eq!(config.extra_queries.get(&Language::Rust).map(Vec::len), Some(2));
+
+        let invalid = CliConfig::build_cli().try_get_matches_from(["parseltongue-01", ".", "--extra-query", &arg("rust", &broken)]);
+        assert!(invalid.is_err(), "queries are compiled at argument parsing");
+    }
+
     #[test]
     fn test_no_exclusion_patterns_default() {
         // Test that defaults work when no -e flags specified
@@ -266,4 +511,23 @@ mod tests {
         assert!(config.exclude_patterns.contains(&"node_modules".to_string()));
         assert!(!config.exclude_patterns.contains(&".ref".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_checkpoint_follows_database_and_no_resume() {
+        let cli = CliConfig::build_cli();
+        let matches = cli
+            .try_get_matches_from(&["parseltongue-01", "./src", "--db", "rocksdb:out.db"])
+            .unwrap();
+        let config = CliConfig::parse_config(&matches);
+        assert_eq!(config.checkpoint_path, Some(PathBuf::from("out.db.pt01-checkpoint.json")));
+        assert!(config.resume);
+
+        let cli = CliConfig::build_cli();
+        let matches = cli
+            .try_get_matches_from(&["parseltongue-01", "./src", "--no-resume"])
+            .unwrap();
+        let config = CliConfig::parse_config(&matches);
+        assert_eq!(config.checkpoint_path, None, "in-memory databases cannot resume");
+        assert!(!config.resume);
+    }
+}
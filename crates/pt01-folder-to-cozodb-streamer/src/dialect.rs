@@ -0,0 +1,128 @@
+//! Per-language grammar dialects.
+//!
+//! Each language ships a single tree-sitter grammar that accepts the union of
+//! its historical syntaxes (tree-sitter-python still parses Python 2
+//! `print "x"`; tree-sitter-rust accepts `async` under any edition). Pinning a
+//! dialect rejects files that use syntax outside it, instead of silently
+//! indexing a misparse.
+//!
+//! Configured through `StreamerConfig.language_dialects` or
+//! `--dialect python=py3`.
+
+use std::collections::HashMap;
+
+use parseltongue_core::entities::Language;
+use tree_sitter::{Node, Tree};
+
+use crate::errors::{Result, StreamerError};
+
+/// Known dialects per language, each with the node kinds it rejects
+const DIALECTS: &[(Language, &str, &[&str])] = &[
+    (Language::Python, "py2", &[]),
+    (Language::Python, "py3", &["print_statement", "exec_statement"]),
+    (Language::Rust, "2015", &["async_block", "await_expression"]),
+    (Language::Rust, "2018", &[]),
+    (Language::Rust, "2021", &[]),
+    (Language::Rust, "2024", &[]),
+];
+
+/// Node kinds `dialect` rejects for `language`
+///
+/// Errors with `ConfigurationError` for a dialect this module doesn't know.
+pub fn rejected_node_kinds(language: Language, dialect: &str) -> Result<&'static [&'static str]> {
+    DIALECTS
+        .iter()
+        .find(|(lang, name, _)| *lang == language && *name == dialect)
+        .map(|(_, _, rejected)| *rejected)
+        .ok_or_else(|| StreamerError::ConfigurationError {
+            field: "language_dialects".to_string(),
+            reason: format!(
+                "unknown {} dialect '{}' (known: {})",
+                language,
+                dialect,
+                known_dialects(language).join(", ")
+            ),
+        })
+}
+
+/// Check every configured dialect is known
+pub fn validate_dialects(dialects: &HashMap<Language, String>) -> Result<()> {
+    for (language, dialect) in dialects {
+        rejected_node_kinds(*language, dialect)?;
+    }
+    Ok(())
+}
+
+/// Parse a `LANGUAGE=DIALECT` CLI value (e.g. `python=py3`, `rust=2015`)
+///
+/// Usable as a clap `value_parser` so bad values fail at argument parsing.
+pub fn parse_dialect_arg(value: &str) -> std::result::Result<(Language, String), String> {
+    let (lang_name, dialect) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected LANGUAGE=DIALECT, got '{}'", value))?;
+    let language = DIALECTS
+        .iter()
+        .map(|(lang, _, _)| *lang)
+        .find(|lang| lang.to_string() == lang_name.trim().to_lowercase())
+        .ok_or_else(|| format!("no dialects defined for language '{}'", lang_name))?;
+    let dialect = dialect.trim().to_string();
+    rejected_node_kinds(language, &dialect).map_err(|e| e.to_string())?;
+    Ok((language, dialect))
+}
+
+/// First node in `tree` the dialect rejects, as `(kind, 1-based line)`
+pub fn find_rejected_syntax(tree: &Tree, rejected: &[&str]) -> Option<(&'static str, usize)> {
+    if rejected.is_empty() {
+        return None;
+    }
+    find_in_node(tree.root_node(), rejected)
+}
+
+fn find_in_node(node: Node<'_>, rejected: &[&str]) -> Option<(&'static str, usize)> {
+    if rejected.contains(&node.kind()) {
+        return Some((node.kind(), node.start_position().row + 1));
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .find_map(|child| find_in_node(child, rejected));
+    found
+}
+
+fn known_dialects(language: Language) -> Vec<&'static str> {
+    DIALECTS
+        .iter()
+        .filter(|(lang, _, _)| *lang == language)
+        .map(|(_, name, _)| *name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dialect_arg() {
+        assert_eq!(
+            parse_dialect_arg("python=py3").unwrap(),
+            (Language::Python, "py3".to_string())
+        );
+        assert_eq!(parse_dialect_arg("Rust=2015").unwrap().0, Language::Rust);
+        assert!(parse_dialect_arg("python").is_err());
+        assert!(parse_dialect_arg("python=py4").unwrap_err().contains("py2, py3"));
+        assert!(parse_dialect_arg("cobol=85").is_err());
+    }
+
+    #[test]
+    fn test_unknown_dialect_fails_validation() {
+        let mut dialects = HashMap::new();
+        dialects.insert(Language::Python, "py3".to_string());
+        assert!(validate_dialects(&dialects).is_ok());
+
+        dialects.insert(Language::Rust, "2030".to_string());
+        assert!(matches!(
+            validate_dialects(&dialects),
+            Err(StreamerError::ConfigurationError { .. })
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Doc comment extraction for `InterfaceSignature.documentation`.
+//!
+//! Collects the `///` lines or `/** ... */` block directly above an item,
+//! skipping attributes (`#[derive(..)]`) and annotations (`@Override`) that
+//! sit between the comment and the item. Gives pt02 a one-paragraph summary
+//! of each entity without exporting its body.
+
+/// Default cap on stored doc text (characters)
+pub const DEFAULT_MAX_DOC_LEN: usize = 1024;
+
+/// Doc comment preceding the item that starts on `start_line` (1-based)
+///
+/// Returns the comment text with markers stripped and lines joined by `\n`,
+/// or `None` when the item is undocumented. A blank line between the
+/// comment and the item detaches it.
+pub fn extract_doc_comment(lines: &[&str], start_line: usize) -> Option<String> {
+    // Index of the line directly above the item
+    let mut idx = start_line.checked_sub(1)?;
+
+    // Skip attributes / annotations between the doc comment and the item
+    while idx > 0 && is_attribute_line(lines.get(idx - 1)?.trim()) {
+        idx -= 1;
+    }
+    if idx == 0 {
+        return None;
+    }
+
+    let above = lines.get(idx - 1)?.trim();
+    let doc_lines = if is_line_doc(above) {
+        let mut collected = Vec::new();
+        while idx > 0 && is_line_doc(lines[idx - 1].trim()) {
+            collected.push(strip_line_doc(lines[idx - 1].trim()));
+            idx -= 1;
+        }
+        collected.reverse();
+        collected
+    } else if above.ends_with("*/") {
+        block_doc_lines(lines, idx - 1)?
+    } else {
+        return None;
+    };
+
+    let doc = doc_lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Cut `doc` to at most `max_len` characters, marking the cut with `...`
+///
+/// A `max_len` of 0 disables truncation.
+pub fn truncate_doc(doc: &str, max_len: usize) -> String {
+    if max_len == 0 || doc.chars().count() <= max_len {
+        return doc.to_string();
+    }
+    let kept: String = doc.chars().take(max_len.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+fn is_attribute_line(line: &str) -> bool {
+    line.starts_with("#[") || line.starts_with('@')
+}
+
+/// `///` outer doc line (`////` is an ordinary comment in rustdoc)
+fn is_line_doc(line: &str) -> bool {
+    line.starts_with("///") && !line.starts_with("////")
+}
+
+fn strip_line_doc(line: &str) -> String {
+    let text = &line[3..];
+    text.strip_prefix(' ').unwrap_or(text).trim_end().to_string()
+}
+
+/// Lines of the `/** ... */` block ending on `end_idx` (0-based)
+///
+/// `None` when the block is a plain `/* */` comment.
+fn block_doc_lines(lines: &[&str], end_idx: usize) -> Option<Vec<String>> {
+    let mut start_idx = end_idx;
+    while !lines[start_idx].contains("/*") {
+        start_idx = start_idx.checked_sub(1)?;
+    }
+
+    let opening = lines[start_idx].trim();
+    if !opening.starts_with("/**") || opening.starts_with("/***") || opening == "/**/" {
+        return None;
+    }
+
+    let mut collected = Vec::new();
+    for line in &lines[start_idx..=end_idx] {
+        let mut text = line.trim();
+        text = text.strip_prefix("/**").unwrap_or(text);
+        text = text.strip_suffix("*/").unwrap_or(text);
+        text = text.trim();
+        text = text.strip_prefix('*').unwrap_or(text);
+        collected.push(text.trim().to_string());
+    }
+    Some(collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc_for(source: &str, start_line: usize) -> Option<String> {
+        let lines: Vec<&str> = source.lines().collect();
+        extract_doc_comment(&lines, start_line)
+    }
+
+    #[test]
+    fn test_line_docs_skip_attributes() {
+        let source = "\
+// not documentation
+/// Adds two numbers.
+///
+/// Never overflows.
+#[inline]
+#[must_use]
+pub fn add(a: u8, b: u8) -> u16 { 0 }
+";
+        assert_eq!(
+            doc_for(source, 7).as_deref(),
+            Some("Adds two numbers.\n\nNever overflows.")
+        );
+    }
+
+    #[test]
+    fn test_block_doc_comment() {
+        let source = "\
+/**
+ * A point in 2D space.
+ */
+struct Point;
+/* plain block comment */
+struct Plain;
+";
+        assert_eq!(doc_for(source, 4).as_deref(), Some("A point in 2D space."));
+        assert_eq!(doc_for(source, 6), None);
+    }
+
+    #[test]
+    fn test_undocumented_and_detached_items() {
+        let source = "\
+/// Detached by the blank line
+
+fn first() {}
+//// four slashes is a normal comment
+fn second() {}
+";
+        assert_eq!(doc_for(source, 1), None);
+        assert_eq!(doc_for(source, 3), None);
+        assert_eq!(doc_for(source, 5), None);
+    }
+
+    #[test]
+    fn test_truncate_doc_respects_limit() {
+        assert_eq!(truncate_doc("short", 10), "short");
+        assert_eq!(truncate_doc("exactly ten", 0), "exactly ten");
+        assert_eq!(truncate_doc("a long doc comment", 10), "a long...");
+        assert!(truncate_doc("ééééééééééé", 5).chars().count() <= 5);
+    }
+}
@@ -0,0 +1,75 @@
+//! Re-extract one entity's outgoing edges after its code changes.
+//!
+//! pt03 edits store new future_code but leave the edges ingestion derived
+//! from the old code. `refresh_entity_edges` parses the future_code alone
+//! (as if it were the whole file), keeps the calls, uses, references and
+//! implementations it makes, and swaps them in for the entity's stored
+//! outgoing edges (see `CozoDbStorage::replace_outgoing_edges`).
+
+use parseltongue_core::entities::{CodeEntity, DependencyEdge, EdgeType};
+use parseltongue_core::query_extractor::is_file_key;
+use parseltongue_core::storage::CozoDbStorage;
+
+use crate::errors::*;
+use crate::isgl1_generator::Isgl1KeyGenerator;
+
+/// Outgoing edges of `entity`'s future_code, starting at its key
+///
+/// Edges from the snippet's own entities (a method inside an edited impl,
+/// say) are attributed to `entity`. File-level imports and the snippet's
+/// `Contains` edges are dropped: they describe the file, not this entity.
+pub fn outgoing_edges_from_future_code(
+    generator: &dyn Isgl1KeyGenerator,
+    entity: &CodeEntity,
+) -> Result<Vec<DependencyEdge>> {
+    let Some(code) = entity.future_code.as_deref() else {
+        return Ok(Vec::new());
+    };
+    let (_, dependencies) = generator.parse_source(code, &entity.interface_signature.file_path)?;
+
+    let mut edges: Vec<DependencyEdge> = Vec::new();
+    for edge in dependencies {
+        if is_file_key(edge.from_key.as_ref()) || edge.edge_type == EdgeType::Contains {
+            continue;
+        }
+        let mut builder = DependencyEdge::builder()
+            .from_key(entity.isgl1_key.clone())
+            .to_key(edge.to_key.as_ref().to_string())
+            .edge_type(edge.edge_type);
+        if let Some(location) = edge.source_location {
+            builder = builder.source_location(location);
+        }
+        let retargeted = builder
+            .build()
+            .map_err(|e| StreamerError::ParsingError {
+                file: entity.interface_signature.file_path.display().to_string(),
+                reason: format!("Invalid edge from {}: {}", entity.isgl1_key, e),
+            })?;
+        if !edges
+            .iter()
+            .any(|e| e.to_key == retargeted.to_key && e.edge_type == retargeted.edge_type)
+        {
+            edges.push(retargeted);
+        }
+    }
+    Ok(edges)
+}
+
+/// Replace `entity`'s stored outgoing edges with those of its future_code
+///
+/// Returns the edges now stored. An entity without future_code (a pending
+/// delete) loses its outgoing edges.
+pub async fn refresh_entity_edges(
+    storage: &CozoDbStorage,
+    generator: &dyn Isgl1KeyGenerator,
+    entity: &CodeEntity,
+) -> Result<Vec<DependencyEdge>> {
+    let edges = outgoing_edges_from_future_code(generator, entity)?;
+    storage
+        .replace_outgoing_edges(&entity.isgl1_key, &edges)
+        .await
+        .map_err(|e| StreamerError::StorageError {
+            details: format!("Failed to update edges of {}: {}", entity.isgl1_key, e),
+        })?;
+    Ok(edges)
+}
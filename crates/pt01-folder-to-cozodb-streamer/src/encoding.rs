@@ -0,0 +1,127 @@
+//! Source text decoding for ingestion.
+//!
+//! tree-sitter needs UTF-8, but Windows-origin files are often UTF-16 or
+//! Windows-1252 (a superset of printable Latin-1). Detection order:
+//! 1. byte-order mark (UTF-8, UTF-16LE, UTF-16BE)
+//! 2. valid UTF-8
+//! 3. BOM-less UTF-16, recognised by NUL bytes in every other position
+//! 4. Windows-1252, unless the bytes contain NULs (binary data)
+//!
+//! Anything that fails is reported instead of being parsed as garbage.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// `EntityMetadata.additional` key holding the detected source encoding
+pub const SOURCE_ENCODING_KEY: &str = "source_encoding";
+
+/// File content decoded to UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedSource {
+    pub text: String,
+    /// WHATWG encoding name, e.g. `UTF-8`, `UTF-16LE`, `windows-1252`
+    pub encoding: &'static str,
+    /// `text` is byte-for-byte the file content (BOM-less UTF-8), so byte
+    /// offsets into it are valid file offsets
+    pub verbatim: bool,
+}
+
+/// Decode raw file bytes, or explain why they cannot be decoded
+pub fn decode_source(bytes: &[u8]) -> Result<DecodedSource, String> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return decode_strict(encoding, &bytes[bom_len..]);
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(DecodedSource {
+            text: text.to_string(),
+            encoding: UTF_8.name(),
+            verbatim: true,
+        });
+    }
+
+    if let Some(encoding) = sniff_utf16(bytes) {
+        return decode_strict(encoding, bytes);
+    }
+
+    if bytes.contains(&0) {
+        return Err("not valid UTF-8 and contains NUL bytes (binary or unknown encoding)".to_string());
+    }
+    decode_strict(WINDOWS_1252, bytes)
+}
+
+fn decode_strict(encoding: &'static Encoding, bytes: &[u8]) -> Result<DecodedSource, String> {
+    match encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+        Some(text) => Ok(DecodedSource {
+            text: text.into_owned(),
+            encoding: encoding.name(),
+            verbatim: false,
+        }),
+        None => Err(format!("invalid {} byte sequence", encoding.name())),
+    }
+}
+
+/// BOM-less UTF-16: ASCII-heavy source has a NUL in (nearly) every high byte
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let even_nuls = bytes.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_nuls = bytes.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+    // At least 90% of one side NUL and almost none on the other
+    let mostly = |count: usize| count * 10 >= pairs * 9;
+    let rarely = |count: usize| count * 10 <= pairs;
+    if mostly(odd_nuls) && rarely(even_nuls) {
+        Some(UTF_16LE)
+    } else if mostly(even_nuls) && rarely(odd_nuls) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { vec![] };
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_detects_each_supported_encoding() {
+        let source = "fn café() {}\n";
+
+        let utf8 = decode_source(source.as_bytes()).unwrap();
+        assert_eq!((utf8.text.as_str(), utf8.encoding), (source, "UTF-8"));
+        assert!(utf8.verbatim);
+
+        let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+        utf8_bom.extend_from_slice(source.as_bytes());
+        let utf8_bom = decode_source(&utf8_bom).unwrap();
+        assert_eq!((utf8_bom.text.as_str(), utf8_bom.verbatim), (source, false));
+
+        let with_bom = decode_source(&utf16le(source, true)).unwrap();
+        assert_eq!((with_bom.text.as_str(), with_bom.encoding), (source, "UTF-16LE"));
+
+        let without_bom = decode_source(&utf16le(source, false)).unwrap();
+        assert_eq!((without_bom.text.as_str(), without_bom.encoding), (source, "UTF-16LE"));
+
+        // 0xE9 is "é" in Latin-1 / Windows-1252 and invalid as UTF-8
+        let latin1 = decode_source(b"fn caf\xE9() {}\n").unwrap();
+        assert_eq!((latin1.text.as_str(), latin1.encoding), (source, "windows-1252"));
+    }
+
+    #[test]
+    fn test_undecodable_bytes_are_errors() {
+        // Binary blob: invalid UTF-8, NULs in no UTF-16 pattern
+        assert!(decode_source(&[0x7F, b'E', b'L', b'F', 0x02, 0x00, 0x00, 0xFF, 0x00]).is_err());
+
+        // BOM promises UTF-16LE but the payload ends in an unpaired surrogate
+        let err = decode_source(&[0xFF, 0xFE, b'a', 0x00, 0x00, 0xD8]).unwrap_err();
+        assert!(err.contains("UTF-16LE"), "{}", err);
+    }
+}
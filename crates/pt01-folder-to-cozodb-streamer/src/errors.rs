@@ -49,11 +49,60 @@ pub enum StreamerError {
         limit: usize,
     },
 
+    /// File grew past `max_file_size` while being read
+    #[error("File exceeded max_file_size while reading: {path} (> {limit} bytes)")]
+    ReadLimitExceeded {
+        path: String,
+        limit: usize,
+    },
+
+    /// Opening and reading a file took longer than `file_read_timeout`
+    #[error("Read timed out: {path} (after {timeout:?})")]
+    ReadTimeout {
+        path: String,
+        timeout: std::time::Duration,
+    },
+
+    /// FIFOs, devices and sockets are never read
+    #[error("Not a regular file: {path} ({kind})")]
+    NotRegularFile {
+        path: String,
+        kind: String,
+    },
+
+    /// A file's estimated ingestion memory exceeds `--memory-budget`
+    #[error("File exceeds memory budget: {path} (estimated {estimate} bytes > {budget} bytes)")]
+    ExceedsMemoryBudget {
+        path: String,
+        estimate: usize,
+        budget: usize,
+    },
+
+    /// File bytes could not be decoded to UTF-8 text
+    #[error("Cannot decode {path}: {reason}")]
+    UndecodableFile {
+        path: String,
+        reason: String,
+    },
+
+    /// File extension maps to a language whose grammar is not compiled in
+    #[error("language not supported in this build: {language}")]
+    LanguageNotSupported {
+        language: String,
+    },
+
     /// Unsupported file type
     #[error("Unsupported file type: {path}")]
     UnsupportedFileType {
         path: String,
     },
+
+    /// Ingestion finished with warnings under `--fail-on-warnings`
+    #[error("{count} warning(s) with --fail-on-warnings; first: {first}")]
+    WarningsPresent {
+        count: usize,
+        first: String,
+    },
 }
 
 impl From<StreamerError> for ParseltongError {
@@ -0,0 +1,171 @@
+//! User-supplied tree-sitter queries for entity kinds the built-in
+//! extraction misses (macro definitions, associated consts, ...).
+//!
+//! Queries follow the capture convention of the embedded `.scm` files:
+//! `@name` captures the entity name and `@definition.<type>` the entity
+//! node, where `<type>` picks the entity type it is stored as:
+//!
+//! ```text
+//! (macro_definition name: (identifier) @name) @definition.macro
+//! ```
+//!
+//! Configured through `StreamerConfig.extra_queries` or
+//! `--extra-query rust=macros.scm`. Every query is compiled against its
+//! grammar when the key generator is built, so a typo fails before any
+//! file is ingested.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use parseltongue_core::entities::Language;
+use parseltongue_core::query_extractor::tree_sitter_language;
+use tree_sitter::{Query, QueryCursor, StreamingIterator, Tree};
+
+use crate::errors::{Result, StreamerError};
+use crate::isgl1_generator::{EntityType, ParsedEntity};
+
+/// `@definition.<type>` suffixes and the entity type each produces
+const DEFINITION_TYPES: &[(&str, EntityType)] = &[
+    ("function", EntityType::Function),
+    ("class", EntityType::Class),
+    ("method", EntityType::Method),
+    ("struct", EntityType::Struct),
+    ("enum", EntityType::Enum),
+    ("trait", EntityType::Trait),
+    ("impl", EntityType::Impl),
+    ("module", EntityType::Module),
+    ("namespace", EntityType::Namespace),
+    ("typedef", EntityType::Typedef),
+    ("variable", EntityType::Variable),
+    ("macro", EntityType::Macro),
+];
+
+/// Extra queries per language, compiled and checked
+#[derive(Default)]
+pub struct ExtraQueries {
+    queries: HashMap<Language, Vec<Query>>,
+}
+
+impl ExtraQueries {
+    /// Compile every query against its language's grammar
+    ///
+    /// Errors with `ConfigurationError` on a syntax error, a node kind the
+    /// grammar lacks, or captures outside the `@name` /
+    /// `@definition.<type>` convention.
+    pub fn compile(sources: &HashMap<Language, Vec<String>>) -> Result<Self> {
+        let mut queries = HashMap::new();
+        for (language, sources) in sources {
+            let compiled = sources
+                .iter()
+                .map(|source| compile_query(*language, source))
+                .collect::<Result<Vec<_>>>()?;
+            queries.insert(*language, compiled);
+        }
+        Ok(Self { queries })
+    }
+
+    /// Entities captured by the extra queries for `language`
+    pub fn extract(&self, tree: &Tree, source: &str, file_path: &Path, language: Language) -> Vec<ParsedEntity> {
+        let mut entities = Vec::new();
+        for query in self.queries.get(&language).into_iter().flatten() {
+            let mut cursor = QueryCursor::new();
+            let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+            while let Some(m) = matches.next() {
+                let mut name = None;
+                let mut definition = None;
+                for capture in m.captures {
+                    let capture_name = query.capture_names()[capture.index as usize];
+                    if capture_name == "name" {
+                        name = source.get(capture.node.byte_range());
+                    } else if let Some(entity_type) = definition_type(capture_name) {
+                        definition = Some((entity_type, capture.node));
+                    }
+                }
+                if let (Some(name), Some((entity_type, node))) = (name, definition) {
+                    entities.push(ParsedEntity {
+                        entity_type,
+                        name: name.to_string(),
+                        language,
+                        line_range: (node.start_position().row + 1, node.end_position().row + 1),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        metadata: HashMap::new(),
+                    });
+                }
+            }
+        }
+        entities
+    }
+}
+
+/// Parse a `LANGUAGE=FILE` CLI value (e.g. `rust=macros.scm`), reading and
+/// compiling the query file
+///
+/// Usable as a clap `value_parser` so bad queries fail at argument parsing.
+pub fn parse_extra_query_arg(value: &str) -> std::result::Result<(Language, String), String> {
+    let (lang_name, file) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected LANGUAGE=FILE, got '{}'", value))?;
+    let language = Language::all()
+        .into_iter()
+        .find(|lang| lang.to_string() == lang_name.trim().to_lowercase())
+        .ok_or_else(|| format!("unknown language '{}'", lang_name))?;
+    let source = std::fs::read_to_string(file.trim())
+        .map_err(|e| format!("cannot read query file '{}': {}", file, e))?;
+    compile_query(language, &source).map_err(|e| e.to_string())?;
+    Ok((language, source))
+}
+
+fn compile_query(language: Language, source: &str) -> Result<Query> {
+    let invalid = |reason: String| StreamerError::ConfigurationError {
+        field: "extra_queries".to_string(),
+        reason: format!("{} query: {}", language, reason),
+    };
+    let grammar = tree_sitter_language(language).ok_or_else(|| invalid("grammar not compiled in".to_string()))?;
+    let query = Query::new(&grammar, source).map_err(|e| invalid(e.to_string()))?;
+
+    let captures = query.capture_names();
+    if !captures.contains(&"name") {
+        return Err(invalid("missing @name capture".to_string()));
+    }
+    let mut has_definition = false;
+    for capture in captures.iter().filter(|c| c.starts_with("definition.")) {
+        if definition_type(capture).is_none() {
+            let known: Vec<_> = DEFINITION_TYPES.iter().map(|(suffix, _)| *suffix).collect();
+            return Err(invalid(format!("unknown capture @{} (known types: {})", capture, known.join(", "))));
+        }
+        has_definition = true;
+    }
+    if !has_definition {
+        return Err(invalid("missing @definition.<type> capture".to_string()));
+    }
+    Ok(query)
+}
+
+fn definition_type(capture_name: &str) -> Option<EntityType> {
+    let suffix = capture_name.strip_prefix("definition.")?;
+    DEFINITION_TYPES
+        .iter()
+        .find(|(known, _)| *known == suffix)
+        .map(|(_, entity_type)| entity_type.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust(query: &str) -> HashMap<Language, Vec<String>> {
+        HashMap::from([(Language::Rust, vec![query.to_string()])])
+    }
+
+    #[test]
+    fn test_invalid_queries_are_rejected_at_compile_time() {
+        let error = |query| ExtraQueries::compile(&rust(query)).err().unwrap().to_string();
+
+        assert!(error("(macro_definition").contains("rust query"));
+        assert!(error("(no_such_node) @definition.macro").contains("rust query"));
+        assert!(error("(macro_definition) @definition.macro").contains("missing @name"));
+        assert!(error("(macro_definition name: (identifier) @name)").contains("missing @definition"));
+        assert!(error("(macro_definition name: (identifier) @name) @definition.gadget").contains("@definition.gadget"));
+        assert!(ExtraQueries::compile(&rust("(macro_definition name: (identifier) @name) @definition.macro")).is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+//! Generated and vendored code detection (`StreamerConfig::generated_markers`)
+//!
+//! Entities of matching files are still ingested, and flagged in the
+//! `GeneratedEntities` relation so exports can leave them out
+//! (`pt02 --exclude-generated`).
+//!
+//! A marker is either a path pattern or header text:
+//! - **Path patterns** end with `/` (a directory, e.g. `vendor/`) or contain
+//!   `*` (e.g. `*.pb.rs`). They match the stored path from its start or from
+//!   any directory boundary, so `vendor/` also matches `crates/x/vendor/y.rs`.
+//! - **Header text** is anything else, e.g. `@generated` or `// GENERATED`,
+//!   looked for in the first `StreamerConfig::generated_header_lines` lines.
+
+/// Lines at the top of a file searched for header markers
+pub const DEFAULT_GENERATED_HEADER_LINES: usize = 10;
+
+/// Markers recognized unless configured otherwise
+pub fn default_generated_markers() -> Vec<String> {
+    ["@generated", "// GENERATED", "vendor/", "*.pb.rs"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Decides whether a file is generated from its path and first lines
+#[derive(Debug, Clone)]
+pub struct GeneratedDetector {
+    path_patterns: Vec<String>,
+    header_markers: Vec<String>,
+    header_lines: usize,
+}
+
+impl GeneratedDetector {
+    pub fn new(markers: &[String], header_lines: usize) -> Self {
+        let (path_patterns, header_markers) = markers.iter().cloned().partition(|marker| is_path_pattern(marker));
+        Self { path_patterns, header_markers, header_lines }
+    }
+
+    /// Whether `path` matches a path pattern or `source` starts with a
+    /// header marker
+    pub fn is_generated(&self, path: &str, source: &str) -> bool {
+        let path = path.replace('\\', "/");
+        self.path_patterns.iter().any(|pattern| matches_path(&path, pattern))
+            || source
+                .lines()
+                .take(self.header_lines)
+                .any(|line| self.header_markers.iter().any(|marker| line.contains(marker.as_str())))
+    }
+}
+
+fn is_path_pattern(marker: &str) -> bool {
+    !marker.contains(char::is_whitespace) && (marker.ends_with('/') || marker.contains('*'))
+}
+
+/// `pattern` against `path` or any of its suffixes starting after a `/`
+fn matches_path(path: &str, pattern: &str) -> bool {
+    let pattern = match pattern.strip_suffix('/') {
+        Some(directory) => format!("{}/*", directory),
+        None => pattern.to_string(),
+    };
+    std::iter::once(path)
+        .chain(path.match_indices('/').map(|(i, _)| &path[i + 1..]))
+        .any(|suffix| wildcard_match(&pattern, suffix))
+}
+
+/// Whole-string match where `*` stands for any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> GeneratedDetector {
+        GeneratedDetector::new(&default_generated_markers(), DEFAULT_GENERATED_HEADER_LINES)
+    }
+
+    #[test]
+    fn test_path_patterns_match_at_directory_boundaries() {
+        let detector = detector();
+        assert!(detector.is_generated("src/proto/messages.pb.rs", ""));
+        assert!(detector.is_generated("vendor/serde/lib.rs", ""));
+        assert!(detector.is_generated("crates/app/vendor/lib.rs", ""));
+        assert!(!detector.is_generated("src/myvendor/lib.rs", ""));
+        assert!(!detector.is_generated("src/lib.rs", "fn main() {}"));
+    }
+
+    #[test]
+    fn test_header_markers_only_count_near_the_top() {
+        let detector = detector();
+        assert!(detector.is_generated("src/lib.rs", "// @generated by prost-build\nfn a() {}"));
+        assert!(detector.is_generated("src/lib.rs", "#![allow(clippy::all)] // GENERATED\n"));
+
+        let late = format!("{}// @generated\n", "fn a() {}\n".repeat(DEFAULT_GENERATED_HEADER_LINES));
+        assert!(!detector.is_generated("src/lib.rs", &late));
+    }
+}
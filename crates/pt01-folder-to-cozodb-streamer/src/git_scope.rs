@@ -0,0 +1,54 @@
+//! Restrict ingestion to the files a branch changed.
+//!
+//! With `StreamerConfig.git_diff_base` set, only paths listed by
+//! `git diff --name-only <base>...HEAD` are ingested (still subject to the
+//! include and exclude patterns). Shells out to `git`, so it must be on
+//! `PATH`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::errors::{Result, StreamerError};
+
+/// Files under `root` changed between the merge base of `base` and `HEAD`
+///
+/// Returned paths are `root` joined with the path git reports relative to
+/// it, matching what a directory walk from `root` yields. Deleted files are
+/// listed too; they are simply never encountered by the walk.
+pub fn changed_files(root: &Path, base: &str) -> Result<HashSet<PathBuf>> {
+    // `git diff` outside a work tree falls back to `--no-index` usage text
+    run_git(root, &["rev-parse", "--is-inside-work-tree"])
+        .map_err(|_| scope_error(root, "not a git repository".to_string()))?;
+
+    let range = format!("{}...HEAD", base);
+    let names = run_git(root, &["diff", "--name-only", "--relative", "-z", &range])?;
+    Ok(String::from_utf8_lossy(&names)
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(name))
+        .collect())
+}
+
+/// Stdout of `git -C root <args>`; a non-zero exit becomes its stderr
+fn run_git(root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|e| scope_error(root, format!("failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(scope_error(root, stderr.trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+fn scope_error(root: &Path, reason: String) -> StreamerError {
+    StreamerError::ConfigurationError {
+        field: "git_diff_base".to_string(),
+        reason: format!("cannot diff {}: {}", root.display(), reason),
+    }
+}
@@ -0,0 +1,42 @@
+//! Registry of tree-sitter grammars compiled into this build.
+//!
+//! `Language` covers every language parseltongue can name from a file
+//! extension; only some of them have a grammar linked in. Files in the
+//! others are reported as "language not supported in this build" instead of
+//! being lumped together with non-code files.
+//!
+//! The grammars themselves are parseltongue-core's
+//! (`query_extractor::tree_sitter_language`), so pt01 parses with exactly
+//! the grammars entity extraction uses. Kotlin is missing: tree-sitter-kotlin
+//! 0.3 targets tree-sitter 0.20 and cannot be loaded by the 0.24 runtime.
+
+use parseltongue_core::entities::Language;
+use parseltongue_core::query_extractor::tree_sitter_language;
+use tree_sitter::Parser;
+
+/// Languages whose grammar is compiled in and loads into a parser
+pub fn available_languages() -> Vec<Language> {
+    Language::all()
+        .into_iter()
+        .filter(|&language| {
+            tree_sitter_language(language).is_some_and(|grammar| Parser::new().set_language(&grammar).is_ok())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_available_languages_excludes_missing_grammars() {
+        let available = available_languages();
+
+        assert!(available.contains(&Language::Rust));
+        assert!(available.contains(&Language::Python));
+        assert!(available.contains(&Language::C));
+        assert!(available.contains(&Language::Scala));
+        assert!(!available.contains(&Language::Kotlin));
+        assert_eq!(available.len(), 13);
+    }
+}
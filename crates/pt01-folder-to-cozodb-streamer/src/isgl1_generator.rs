@@ -18,8 +18,15 @@ use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tree_sitter::{Parser, Tree};
 use parseltongue_core::entities::{Language, DependencyEdge};
-use parseltongue_core::query_extractor::QueryBasedExtractor;
+use parseltongue_core::isgl1_key::{KeyComponents, KeyFormat};
+use parseltongue_core::query_extractor::tree_sitter_language;
+use crate::chunking::{ChunkSpan, ChunkingStrategy, Isgl1Chunking};
+use crate::dialect::{find_rejected_syntax, rejected_node_kinds, validate_dialects};
+use crate::doc_comments::extract_doc_comment;
+use crate::extra_queries::ExtraQueries;
 use crate::errors::*;
+use crate::name_normalizer::{NameNormalizationPolicy, NameNormalizer};
+use crate::parse_diagnostics::{ParseDiagnostics, ParseIssue};
 
 /// ISGL1 key generator interface
 pub trait Isgl1KeyGenerator: Send + Sync {
@@ -35,10 +42,28 @@ pub trait Isgl1KeyGenerator: Send + Sync {
     /// Single-pass extraction: adds ~5-10% overhead vs entity-only extraction
     fn parse_source(&self, source: &str, file_path: &Path) -> Result<(Vec<ParsedEntity>, Vec<DependencyEdge>)>;
 
+    /// `parse_source`, plus the syntax errors tree-sitter recovered from
+    ///
+    /// The default reports none; implementations holding the tree override it.
+    fn parse_source_with_diagnostics(&self, source: &str, file_path: &Path) -> Result<ParsedSource> {
+        let (entities, dependencies) = self.parse_source(source, file_path)?;
+        Ok(ParsedSource { entities, dependencies, diagnostics: None })
+    }
+
     /// Get supported language for file extension
     fn get_language_type(&self, file_path: &Path) -> Result<Language>;
 }
 
+/// Everything one parse of a file produced
+#[derive(Debug, Clone)]
+pub struct ParsedSource {
+    pub entities: Vec<ParsedEntity>,
+    pub dependencies: Vec<DependencyEdge>,
+    /// Error and missing nodes in the tree; entities were still extracted
+    /// from the recovered parts
+    pub diagnostics: Option<ParseDiagnostics>,
+}
+
 /// Parsed code entity representation
 #[derive(Debug, Clone)]
 pub struct ParsedEntity {
@@ -81,20 +106,32 @@ pub enum EntityType {
 
     // Variables
     Variable,   // Module-level or global variables
+
+    // Only produced by `StreamerConfig.extra_queries`
+    Macro,      // Rust macro_rules!, C #define
 }
 
 /// ISGL1 key generator implementation using tree-sitter
 ///
 /// ## v0.8.9 Hybrid Architecture
 ///
-/// **Query-Based Extraction** (Primary): Uses QueryBasedExtractor for all 12 languages
+/// **Query-Based Extraction** (Primary): Uses QueryBasedExtractor for all 12 languages,
+/// through the default `Isgl1Chunking` strategy
 /// **Manual Extraction** (Legacy): Kept for Rust-specific dependency analysis (function calls)
 ///
 /// **Rationale**: QueryBasedExtractor handles entity extraction perfectly, but dependency
 /// extraction (function call graphs) requires custom traversal logic for Rust.
 pub struct Isgl1KeyGeneratorImpl {
     parsers: HashMap<Language, Arc<Mutex<Parser>>>,
-    query_extractor: Mutex<QueryBasedExtractor>,  // v0.8.9: Multi-language entity extraction
+    name_normalizer: NameNormalizer,
+    /// Pinned grammar dialect per language (see `crate::dialect`)
+    dialects: HashMap<Language, String>,
+    /// How parsed files are cut into entities (see `crate::chunking`)
+    chunking: Arc<dyn ChunkingStrategy>,
+    /// User-supplied queries run after the built-in ones (see `crate::extra_queries`)
+    extra_queries: ExtraQueries,
+    /// Layout of generated keys (see `parseltongue_core::isgl1_key`)
+    key_format: KeyFormat,
 }
 
 impl Default for Isgl1KeyGeneratorImpl {
@@ -104,48 +141,82 @@ impl Default for Isgl1KeyGeneratorImpl {
 }
 
 impl Isgl1KeyGeneratorImpl {
-    /// Create new ISGL1 key generator with support for 13 languages
+    /// Language from the extension, falling back to the content for
+    /// extensionless or unknown-extension files (shebang scripts)
+    ///
+    /// Still-ambiguous files stay `UnsupportedFileType` and are skipped.
+    fn detect_language(&self, source: &str, file_path: &Path) -> Result<Language> {
+        match self.get_language_type(file_path) {
+            Err(e) if Language::from_file_path(&file_path.to_path_buf()).is_none() => {
+                Language::from_content(source.as_bytes())
+                    .filter(|language| self.parsers.contains_key(language))
+                    .ok_or(e)
+            }
+            detected => detected,
+        }
+    }
+
+    /// Create new ISGL1 key generator with a parser per compiled-in grammar
     pub fn new() -> Self {
         let mut parsers = HashMap::new();
-
-        // Helper macro to initialize parser for a language
-        macro_rules! init_parser {
-            ($lang:expr, $grammar:expr) => {
-                let mut parser = Parser::new();
-                if parser.set_language($grammar).is_ok() {
-                    parsers.insert($lang, Arc::new(Mutex::new(parser)));
-                }
-            };
+        for language in Language::all() {
+            let Some(grammar) = tree_sitter_language(language) else { continue };
+            let mut parser = Parser::new();
+            if parser.set_language(&grammar).is_ok() {
+                parsers.insert(language, Arc::new(Mutex::new(parser)));
+            }
         }
 
-        // Initialize all language parsers
-        // LanguageFn must be converted to Language using .into() for tree-sitter 0.24+
-        init_parser!(Language::Rust, &tree_sitter_rust::LANGUAGE.into());
-        init_parser!(Language::Python, &tree_sitter_python::LANGUAGE.into());
-        init_parser!(Language::JavaScript, &tree_sitter_javascript::LANGUAGE.into());
-        init_parser!(Language::TypeScript, &tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into());
-        init_parser!(Language::Go, &tree_sitter_go::LANGUAGE.into());
-        init_parser!(Language::Java, &tree_sitter_java::LANGUAGE.into());
-        init_parser!(Language::Cpp, &tree_sitter_cpp::LANGUAGE.into());
-        init_parser!(Language::Ruby, &tree_sitter_ruby::LANGUAGE.into());
-        init_parser!(Language::Php, &tree_sitter_php::LANGUAGE_PHP.into());
-        init_parser!(Language::CSharp, &tree_sitter_c_sharp::LANGUAGE.into());
-        init_parser!(Language::Swift, &tree_sitter_swift::LANGUAGE.into());
-        // Note: Kotlin not supported in v0.8.7 - tree-sitter-kotlin v0.3 uses incompatible tree-sitter 0.20
-        // Will be added when tree-sitter-kotlin updates to 0.24+
-        init_parser!(Language::Scala, &tree_sitter_scala::LANGUAGE.into());
-
-        // v0.8.9: Initialize QueryBasedExtractor for multi-language entity extraction
-        let query_extractor = QueryBasedExtractor::new()
-            .expect("Failed to initialize QueryBasedExtractor - .scm query files missing");
-
         Self {
             parsers,
-            query_extractor: Mutex::new(query_extractor),
+            name_normalizer: NameNormalizer::default(),
+            dialects: HashMap::new(),
+            chunking: Arc::new(Isgl1Chunking::new()),
+            extra_queries: ExtraQueries::default(),
+            key_format: KeyFormat::default(),
         }
     }
 
-    /// Generate ISGL1 key format: {language}:{type}:{name}:{location}
+    /// Pin grammar dialects; files using syntax outside them fail to parse
+    ///
+    /// Errors with `ConfigurationError` on an unknown dialect.
+    pub fn with_dialects(mut self, dialects: HashMap<Language, String>) -> Result<Self> {
+        validate_dialects(&dialects)?;
+        self.dialects = dialects;
+        Ok(self)
+    }
+
+    /// Also extract the entities captured by `queries`, alongside the chunks
+    ///
+    /// Errors with `ConfigurationError` on a query that does not compile
+    /// against its grammar or lacks the `@name` / `@definition.<type>`
+    /// captures.
+    pub fn with_extra_queries(mut self, queries: &HashMap<Language, Vec<String>>) -> Result<Self> {
+        self.extra_queries = ExtraQueries::compile(queries)?;
+        Ok(self)
+    }
+
+    /// Cut files into entities with `strategy` instead of ISGL1
+    pub fn with_chunking(mut self, strategy: Arc<dyn ChunkingStrategy>) -> Self {
+        self.chunking = strategy;
+        self
+    }
+
+    /// Use `normalizer` for the name segment of generated keys
+    pub fn with_name_normalizer(mut self, normalizer: NameNormalizer) -> Self {
+        self.name_normalizer = normalizer;
+        self
+    }
+
+    /// Emit keys in `format` instead of the colon-qualified default
+    pub fn with_key_format(mut self, format: KeyFormat) -> Self {
+        self.key_format = format;
+        self
+    }
+
+    /// Generate the ISGL1 key in the configured `KeyFormat`
+    ///
+    /// The name segment passes through the configured `NameNormalizer`.
     fn format_key(&self, entity: &ParsedEntity) -> String {
         let type_str = match entity.entity_type {
             EntityType::Function => "fn",
@@ -159,17 +230,16 @@ impl Isgl1KeyGeneratorImpl {
             EntityType::Namespace => "namespace",
             EntityType::Typedef => "typedef",
             EntityType::Variable => "var",
+            EntityType::Macro => "macro",
         };
 
-        format!(
-            "{}:{}:{}:{}:{}-{}",
-            entity.language,
-            type_str,
-            entity.name,
-            self.sanitize_path(&entity.file_path),
-            entity.line_range.0,
-            entity.line_range.1
-        )
+        self.key_format.format(&KeyComponents {
+            language: entity.language.to_string(),
+            entity_type: type_str.to_string(),
+            name: self.name_normalizer.normalize(&entity.name, entity.language),
+            file_path: self.sanitize_path(&entity.file_path),
+            line_range: entity.line_range,
+        })
     }
 
     /// Sanitize file path for ISGL1 key
@@ -184,7 +254,12 @@ impl Isgl1KeyGenerator for Isgl1KeyGeneratorImpl {
     }
 
     fn parse_source(&self, source: &str, file_path: &Path) -> Result<(Vec<ParsedEntity>, Vec<DependencyEdge>)> {
-        let language_type = self.get_language_type(file_path)?;
+        self.parse_source_with_diagnostics(source, file_path)
+            .map(|parsed| (parsed.entities, parsed.dependencies))
+    }
+
+    fn parse_source_with_diagnostics(&self, source: &str, file_path: &Path) -> Result<ParsedSource> {
+        let language_type = self.detect_language(source, file_path)?;
 
         let parser_mutex = self.parsers.get(&language_type)
             .ok_or_else(|| StreamerError::ParsingError {
@@ -192,7 +267,10 @@ impl Isgl1KeyGenerator for Isgl1KeyGeneratorImpl {
                 reason: format!("No parser available for language: {:?}", language_type),
             })?;
 
-        let mut parser = parser_mutex.lock().unwrap();
+        let mut parser = parser_mutex.lock().map_err(|e| StreamerError::ParsingError {
+            file: file_path.to_string_lossy().to_string(),
+            reason: format!("Parser lock poisoned: {}", e),
+        })?;
         let tree = parser
             .parse(source, None)
             .ok_or_else(|| StreamerError::ParsingError {
@@ -200,11 +278,21 @@ impl Isgl1KeyGenerator for Isgl1KeyGeneratorImpl {
                 reason: "Failed to parse source code".to_string(),
             })?;
 
-        let mut entities = Vec::new();
-        let mut dependencies = Vec::new();
-        self.extract_entities(&tree, source, file_path, language_type, &mut entities, &mut dependencies);
+        if let Some(dialect) = self.dialects.get(&language_type) {
+            let rejected = rejected_node_kinds(language_type, dialect)?;
+            if let Some((kind, line)) = find_rejected_syntax(&tree, rejected) {
+                return Err(StreamerError::ParsingError {
+                    file: file_path.to_string_lossy().to_string(),
+                    reason: format!("{} at line {} is not valid in {} dialect '{}'", kind, line, language_type, dialect),
+                });
+            }
+        }
+
+        let diagnostics = ParseDiagnostics::from_tree(language_type, &tree);
+        let (chunks, dependencies) = self.chunking.chunk_with_dependencies(&tree, source, file_path)?;
+        let entities = self.chunk_entities(chunks, &tree, source, file_path, language_type);
 
-        Ok((entities, dependencies))
+        Ok(ParsedSource { entities, dependencies, diagnostics })
     }
 
     fn get_language_type(&self, file_path: &Path) -> Result<Language> {
@@ -215,12 +303,13 @@ impl Isgl1KeyGenerator for Isgl1KeyGeneratorImpl {
                 path: file_path.to_string_lossy().to_string(),
             })?;
 
-        // Verify we have a parser for this language
+        // A code file whose grammar is not compiled in is not "unsupported
+        // file type": say which language is missing
         if self.parsers.contains_key(&language) {
             Ok(language)
         } else {
-            Err(StreamerError::UnsupportedFileType {
-                path: file_path.to_string_lossy().to_string(),
+            Err(StreamerError::LanguageNotSupported {
+                language: language.to_string(),
             })
         }
     }
@@ -231,8 +320,7 @@ impl Isgl1KeyGeneratorImpl {
     ///
     /// **Design Pattern**: Pure function with exhaustive pattern matching
     /// **v0.8.9**: Bridges query-based extraction (parseltongue-core) to pt01's type system
-    fn map_query_entity_type(
-        &self,
+    pub(crate) fn map_query_entity_type(
         query_type: &parseltongue_core::query_extractor::EntityType
     ) -> EntityType {
         match query_type {
@@ -318,69 +406,90 @@ impl Isgl1KeyGeneratorImpl {
         }
     }
 
-    /// Extract entities AND dependencies from parse tree (two-pass for correctness)
+    /// Record each entity's preceding `///` / `/** */` doc comment
     ///
-    /// ## v0.8.9 Hybrid Approach
-    ///
-    /// **Pass 1** (All languages): Use QueryBasedExtractor for entity extraction
-    /// - Replaces manual walk_node() which only worked for Rust
-    /// - Fixes Ruby, Python, JS, TS, Go, Java, C, C++, PHP, C#, Swift extraction
+    /// Stored untruncated in metadata["documentation"]; the streamer applies
+    /// `StreamerConfig.max_doc_len` when building the signature.
+    fn enrich_entities_with_doc_comments(&self, entities: &mut [ParsedEntity], source: &str) {
+        let lines: Vec<&str> = source.lines().collect();
+        for entity in entities.iter_mut() {
+            if let Some(doc) = extract_doc_comment(&lines, entity.line_range.0) {
+                entity.metadata.insert("documentation".to_string(), doc);
+            }
+        }
+    }
+
+    /// Entities for the chunks the strategy cut, one per chunk
     ///
-    /// **Pass 2** (Rust only): Use manual traversal for dependency extraction
-    /// - Function call graphs require custom logic not yet in .scm queries
-    /// - Future: Move dependency extraction to queries as well
-    fn extract_entities(
+    /// User-supplied queries add their constructs (the chunks already found
+    /// win), then Rust test attributes and preceding doc comments are
+    /// recorded as for query-based extraction.
+    fn chunk_entities(
         &self,
-        _tree: &Tree,
+        chunks: Vec<ChunkSpan>,
+        tree: &Tree,
         source: &str,
         file_path: &Path,
         language: Language,
-        entities: &mut Vec<ParsedEntity>,
-        dependencies: &mut Vec<DependencyEdge>,
-    ) {
-        // v0.8.9 CRITICAL FIX: Use QueryBasedExtractor for entity extraction
-        //
-        // This replaces the broken walk_node() approach that only worked for Rust.
-        // Now ALL 12 languages extract entities correctly via .scm query files.
-        match self.query_extractor.lock() {
-            Ok(mut extractor) => {
-                match extractor.parse_source(source, file_path, language) {
-                    Ok((query_entities, query_deps)) => {
-                        // Convert QueryBasedExtractor entities to pt01 ParsedEntity format
-                        for query_entity in query_entities {
-                            entities.push(ParsedEntity {
-                                entity_type: self.map_query_entity_type(&query_entity.entity_type),
-                                name: query_entity.name,
-                                language: query_entity.language,
-                                line_range: query_entity.line_range,
-                                file_path: query_entity.file_path,
-                                metadata: query_entity.metadata,
-                            });
-                        }
+    ) -> Vec<ParsedEntity> {
+        let mut entities: Vec<ParsedEntity> = chunks
+            .into_iter()
+            .map(|chunk| ParsedEntity {
+                entity_type: chunk.entity_type,
+                name: chunk.name,
+                language,
+                line_range: chunk.line_range,
+                file_path: file_path.to_string_lossy().to_string(),
+                metadata: HashMap::from([("chunking".to_string(), self.chunking.name().to_string())]),
+            })
+            .collect();
+        for extra in self.extra_queries.extract(tree, source, file_path, language) {
+            if !entities.iter().any(|e| e.name == extra.name && e.line_range == extra.line_range) {
+                entities.push(extra);
+            }
+        }
+        if language == Language::Rust {
+            self.enrich_rust_entities_with_attributes(&mut entities, source);
+        }
+        self.enrich_entities_with_doc_comments(&mut entities, source);
+        entities
+    }
 
-                        // v0.9.0 FEATURE: Rust-specific attribute parsing
-                        // Enrich Rust entities with #[test] metadata after extraction
-                        if language == Language::Rust {
-                            self.enrich_rust_entities_with_attributes(entities, source);
-                        }
+    /// Parse arbitrary bytes without panicking
+    ///
+    /// Fuzzing and robustness entry point: invalid UTF-8, a missing grammar,
+    /// any syntax error (including truncated input) and extraction failures
+    /// all come back as `ParseDiagnostics` rather than a panic, a log line or
+    /// a silently partial result. Entity file paths are `<bytes>`.
+    pub fn try_parse_bytes(
+        &self,
+        bytes: &[u8],
+        language: Language,
+    ) -> std::result::Result<Vec<ParsedEntity>, ParseDiagnostics> {
+        let reject = |issue| ParseDiagnostics::single(language, issue);
+
+        let source = std::str::from_utf8(bytes)
+            .map_err(|e| reject(ParseIssue::InvalidUtf8 { valid_up_to: e.valid_up_to() }))?;
+        let parser = self
+            .parsers
+            .get(&language)
+            .ok_or_else(|| reject(ParseIssue::UnsupportedLanguage))?;
+        let tree = parser
+            .lock()
+            .map_err(|e| reject(ParseIssue::ExtractionFailed { reason: e.to_string() }))?
+            .parse(source, None)
+            .ok_or_else(|| reject(ParseIssue::NoTree))?;
 
-                        // v0.9.0 CRITICAL FIX: Use query-based dependency extraction
-                        // This replaces manual tree-walking for dependency extraction
-                        dependencies.extend(query_deps);
-                    }
-                    Err(e) => {
-                        // Graceful degradation: log error but continue
-                        eprintln!("QueryBasedExtractor failed for {:?}: {}", language, e);
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to lock query_extractor: {}", e);
-            }
+        if let Some(diagnostics) = ParseDiagnostics::from_tree(language, &tree) {
+            return Err(diagnostics);
         }
 
-        // v0.9.0: Manual dependency extraction replaced by query-based approach (REFACTORED)
-        // All entity and dependency extraction now handled by QueryBasedExtractor
+        let file_path = Path::new("<bytes>");
+        let (chunks, _) = self
+            .chunking
+            .chunk_with_dependencies(&tree, source, file_path)
+            .map_err(|e| reject(ParseIssue::ExtractionFailed { reason: e.to_string() }))?;
+        Ok(self.chunk_entities(chunks, &tree, source, file_path, language))
     }
 }
 
@@ -392,6 +501,11 @@ impl Isgl1KeyGeneratorFactory {
     pub fn new() -> Arc<dyn Isgl1KeyGenerator> {
         Arc::new(Isgl1KeyGeneratorImpl::new())
     }
+
+    /// Create a key generator using the given name normalization policy
+    pub fn with_name_normalization(policy: NameNormalizationPolicy) -> Arc<dyn Isgl1KeyGenerator> {
+        Arc::new(Isgl1KeyGeneratorImpl::new().with_name_normalizer(NameNormalizer::new(policy)))
+    }
 }
 
 #[cfg(test)]
@@ -416,6 +530,110 @@ mod tests {
         assert!(key.contains("10-15"));
     }
 
+    fn parsed(name: &str, entity_type: EntityType, language: Language, file_path: &str) -> ParsedEntity {
+        ParsedEntity {
+            entity_type,
+            name: name.to_string(),
+            language,
+            line_range: (3, 7),
+            file_path: file_path.to_string(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn canonical_generator() -> Isgl1KeyGeneratorImpl {
+        Isgl1KeyGeneratorImpl::new()
+            .with_name_normalizer(NameNormalizer::new(NameNormalizationPolicy::Canonical))
+    }
+
+    #[test]
+    fn test_rust_key_segment_is_normalized() {
+        let generator = canonical_generator();
+
+        let qualified = parsed("Foo::bar", EntityType::Method, Language::Rust, "src/lib.rs");
+        assert_eq!(generator.generate_key(&qualified).unwrap(), "rust:method:bar:src_lib_rs:3-7");
+
+        // Keys stay stable when only generic parameters change
+        let one_param = parsed("Cache<K>", EntityType::Struct, Language::Rust, "src/lib.rs");
+        let two_params = parsed("Cache<K, V>", EntityType::Struct, Language::Rust, "src/lib.rs");
+        assert_eq!(
+            generator.generate_key(&one_param).unwrap(),
+            generator.generate_key(&two_params).unwrap()
+        );
+        assert_eq!(generator.generate_key(&one_param).unwrap(), "rust:struct:Cache:src_lib_rs:3-7");
+    }
+
+    #[test]
+    fn test_python_key_segment_is_normalized() {
+        let generator = canonical_generator();
+
+        let method = parsed("Repository.save", EntityType::Method, Language::Python, "app/repo.py");
+        assert_eq!(generator.generate_key(&method).unwrap(), "python:method:save:app_repo_py:3-7");
+
+        let generic = parsed("Repository[User]", EntityType::Class, Language::Python, "app/repo.py");
+        assert_eq!(generator.generate_key(&generic).unwrap(), "python:class:Repository:app_repo_py:3-7");
+    }
+
+    #[test]
+    fn test_default_policy_keeps_raw_name() {
+        // Edges are keyed by raw names, so keys must be too unless asked otherwise
+        let generator = Isgl1KeyGeneratorImpl::new();
+
+        let qualified = parsed("Foo::bar", EntityType::Method, Language::Rust, "src/lib.rs");
+        assert_eq!(generator.generate_key(&qualified).unwrap(), "rust:method:Foo::bar:src_lib_rs:3-7");
+    }
+
+    #[test]
+    fn test_each_key_format_round_trips() {
+        let entity = parsed("Repository.save", EntityType::Method, Language::Python, "app/repo.py");
+        let expected = KeyComponents {
+            language: "python".to_string(),
+            entity_type: "method".to_string(),
+            name: "save".to_string(),
+            file_path: "app_repo_py".to_string(),
+            line_range: (3, 7),
+        };
+
+        let key = canonical_generator().generate_key(&entity).unwrap();
+        assert_eq!(key, "python:method:save:app_repo_py:3-7");
+
+        for format in [KeyFormat::ColonQualified, KeyFormat::DashSimple] {
+            let generator = canonical_generator().with_key_format(format);
+            let key = generator.generate_key(&entity).unwrap();
+            assert_eq!(parseltongue_core::isgl1_key::parse_key(&key).unwrap(), expected, "{}", key);
+            assert_eq!(format.parse(&key).unwrap(), expected, "{}", key);
+        }
+        assert_eq!(
+            canonical_generator().with_key_format(KeyFormat::DashSimple).generate_key(&entity).unwrap(),
+            "app_repo_py-save-method-python-3-7"
+        );
+    }
+
+    /// Named like the default, but cuts its own chunks
+    struct NamedIsgl1;
+
+    impl ChunkingStrategy for NamedIsgl1 {
+        fn name(&self) -> &str {
+            crate::chunking::ISGL1_CHUNKING
+        }
+
+        fn chunk(&self, _tree: &Tree, _source: &str) -> Result<Vec<ChunkSpan>> {
+            Ok(vec![ChunkSpan { name: "custom".to_string(), entity_type: EntityType::Module, line_range: (1, 1) }])
+        }
+    }
+
+    #[test]
+    fn test_entities_come_from_the_strategy_whatever_its_name() {
+        let generator = Isgl1KeyGeneratorImpl::new().with_chunking(Arc::new(NamedIsgl1));
+        let (entities, dependencies) = generator.parse_source("fn a() {}
+fn b() { a(); }
+", Path::new("lib.rs")).unwrap();
+
+        let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["custom"]);
+        assert!(dependencies.is_empty());
+    }
+
     #[test]
     fn test_rust_parsing() {
         let generator = Isgl1KeyGeneratorImpl::new();
@@ -443,6 +661,83 @@ struct TestStruct {
         assert_eq!(dependencies.len(), 0);
     }
 
+    #[test]
+    fn test_doc_comments_captured_in_metadata() {
+        let generator = Isgl1KeyGeneratorImpl::new();
+        let source = r#"
+/// Parses the config file.
+///
+/// Returns defaults when missing.
+#[inline]
+pub fn load_config() {}
+
+/** Connection pool. */
+#[derive(Debug)]
+struct Pool;
+
+fn undocumented() {}
+"#;
+
+        let (entities, _) = generator.parse_source(source, Path::new("config.rs")).unwrap();
+        let doc_of = |name: &str| {
+            entities
+                .iter()
+                .find(|e| e.name == name)
+                .and_then(|e| e.metadata.get("documentation").cloned())
+        };
+
+        assert_eq!(
+            doc_of("load_config").as_deref(),
+            Some("Parses the config file.\n\nReturns defaults when missing.")
+        );
+        assert_eq!(doc_of("Pool").as_deref(), Some("Connection pool."));
+        assert_eq!(doc_of("undocumented"), None);
+    }
+
+    #[test]
+    fn test_python2_print_statement_depends_on_dialect() {
+        let source = "def greet():\n    print \"hello\"\n";
+        let path = Path::new("legacy.py");
+        let with_dialect = |dialect: &str| {
+            Isgl1KeyGeneratorImpl::new()
+                .with_dialects(HashMap::from([(Language::Python, dialect.to_string())]))
+                .unwrap()
+        };
+
+        let (entities, _) = with_dialect("py2").parse_source(source, path).unwrap();
+        assert_eq!(entities[0].name, "greet");
+
+        let err = with_dialect("py3").parse_source(source, path).unwrap_err();
+        assert!(matches!(err, StreamerError::ParsingError { .. }));
+        assert!(err.to_string().contains("print_statement at line 2"), "{}", err);
+
+        assert!(Isgl1KeyGeneratorImpl::new()
+            .with_dialects(HashMap::from([(Language::Python, "py4".to_string())]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_extensionless_files_fall_back_to_content() {
+        let generator = Isgl1KeyGeneratorImpl::new();
+
+        let script = "#!/usr/bin/env python3\n\ndef deploy():\n    pass\n";
+        let (entities, _) = generator.parse_source(script, Path::new("bin/deploy")).unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].name, "deploy");
+        assert_eq!(entities[0].language, Language::Python);
+
+        // Shell scripts and unrecognizable files are skipped, not parsed as Rust
+        for (source, path) in [
+            ("#!/bin/sh\necho deploy\n", "bin/run"),
+            ("fn looks_like_rust() {}\n", "notes"),
+        ] {
+            assert!(matches!(
+                generator.parse_source(source, Path::new(path)),
+                Err(StreamerError::UnsupportedFileType { .. })
+            ));
+        }
+    }
+
     #[test]
     fn test_function_detection() {
         // v0.8.9: QueryBasedExtractor doesn't parse Rust attributes (#[test])
@@ -640,4 +935,34 @@ fn c() {}
         );
         assert!(b_to_c.is_some(), "Should have b -> c edge");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_emits_imports_implements_and_contains_edges() {
+        let generator = Isgl1KeyGeneratorImpl::new();
+        let source = r#"
+use std::fmt::Display;
+
+struct Point;
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "point")
+    }
+}
+"#;
+
+        let (_entities, dependencies) = generator.parse_source(source, Path::new("point.rs")).unwrap();
+
+        let has = |edge_type: EdgeType, from: &str, to: &str| {
+            dependencies.iter().any(|e| {
+                e.edge_type == edge_type
+                    && e.from_key.as_ref().contains(from)
+                    && e.to_key.as_ref().contains(to)
+            })
+        };
+        assert!(has(EdgeType::Imports, ":file:", "Display"), "use → Imports: {:?}", dependencies);
+        assert!(has(EdgeType::Implements, "Point", "Display"), "impl for → Implements: {:?}", dependencies);
+        assert!(has(EdgeType::Contains, ":impl:", ":method:fmt:"), "impl → method: {:?}", dependencies);
+        assert!(dependencies.iter().all(|e| e.edge_type != EdgeType::Uses), "Uses is legacy only");
+    }
+}
@@ -22,23 +22,59 @@
 #![warn(rust_2018_idioms)]
 #![allow(missing_docs)]
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
+use parseltongue_core::entities::Language;
+
+pub mod bounded_read;
+pub mod checkpoint;
+pub mod chunking;
 pub mod cli;
+pub mod dialect;
+pub mod doc_comments;
+pub mod edge_refresh;
+pub mod encoding;
 pub mod errors;
+pub mod extra_queries;
+pub mod generated;
+pub mod git_scope;
+pub mod grammars;
 pub mod isgl1_generator;
 pub mod lsp_client;
+pub mod memory_budget;
+pub mod name_normalizer;
+pub mod parse_diagnostics;
+pub mod paths;
+pub mod profile;
 pub mod streamer;
 pub mod test_detector;
+pub mod transform;
 pub mod v090_specifications;
+pub mod visibility;
 
 // Re-export commonly used types
+pub use checkpoint::IngestionCheckpoint;
+pub use chunking::{ChunkSpan, ChunkingStrategy, Isgl1Chunking, WholeFileChunking};
+pub use edge_refresh::refresh_entity_edges;
 pub use errors::*;
+pub use generated::GeneratedDetector;
+pub use grammars::available_languages;
 pub use isgl1_generator::*;
 pub use lsp_client::*;
+pub use memory_budget::{estimate_memory_usage, MemoryBudget};
+pub use name_normalizer::{NameNormalizationPolicy, NameNormalizer};
+pub use parse_diagnostics::{ParseDiagnostics, ParseIssue};
+pub use parseltongue_core::isgl1_key::{parse_key, KeyComponents, KeyFormat};
+pub use paths::PathStyle;
 pub use streamer::{FileStreamerImpl, *};
 pub use test_detector::*;
+pub use transform::{ComplexityTagger, EntityTransform};
+
+// Embedders cancel ingestion without depending on tokio-util themselves
+pub use tokio_util::sync::CancellationToken;
 
 /// Tool metadata and configuration
 #[derive(Debug, Clone)]
@@ -48,15 +84,90 @@ pub struct StreamerConfig {
     /// Database connection string
     pub db_path: String,
     /// Maximum file size to process (bytes)
+    ///
+    /// Checked against the file's metadata and again while reading, so a
+    /// file that turns out larger is abandoned mid-read (see `bounded_read`).
     pub max_file_size: usize,
+    /// Time allowed to open and read one file; slower files are skipped
+    /// and reported
+    pub file_read_timeout: Duration,
     /// File patterns to include
     pub include_patterns: Vec<String>,
     /// File patterns to exclude
     pub exclude_patterns: Vec<String>,
     /// Parsing library to use (default: "tree-sitter")
     pub parsing_library: String,
-    /// Chunking strategy to use (default: "ISGL1")
+    /// Chunking strategy to use (default: "ISGL1"; see `chunking` for the
+    /// built-in names)
     pub chunking: String,
+    /// How entity names become the ISGL1 key name segment (default: Preserve)
+    pub name_normalization: NameNormalizationPolicy,
+    /// Layout of generated ISGL1 keys (default: ColonQualified; see
+    /// `parseltongue_core::isgl1_key`)
+    pub key_format: KeyFormat,
+    /// Sidecar recording processed files so an interrupted run can resume
+    /// (`None` disables checkpointing)
+    pub checkpoint_path: Option<PathBuf>,
+    /// Skip files recorded in an existing checkpoint (`--no-resume` clears it)
+    pub resume: bool,
+    /// Processed files between checkpoint writes
+    pub checkpoint_interval: usize,
+    /// Maximum characters of doc comment kept per entity (0 = unlimited)
+    pub max_doc_len: usize,
+    /// Pinned grammar dialect per language, e.g. Python => "py3"
+    /// (see `dialect` for the known names)
+    pub language_dialects: HashMap<Language, String>,
+    /// Store each entity's byte span instead of its code text
+    ///
+    /// Roughly halves database size for read-mostly analysis, but code is
+    /// read back from the source files on `get_entity`, so they must remain
+    /// present and unmodified at the ingested paths. Files that are not
+    /// plain UTF-8 (or use CRLF line endings) still store full text.
+    pub store_spans_only: bool,
+    /// Only ingest files changed between the merge base of this ref and
+    /// `HEAD` (`git diff --name-only <base>...HEAD`); `root_dir` must be
+    /// inside a git work tree
+    pub git_diff_base: Option<String>,
+    /// Form of stored file paths; `Relative` (to `root_dir`) makes keys
+    /// independent of where and how the repository was passed in
+    pub path_style: PathStyle,
+    /// Descend into symlinked directories (default: false)
+    ///
+    /// Directories already visited through another path are skipped and
+    /// reported as "skipped symlink loop", so cycles terminate.
+    pub follow_symlinks: bool,
+    /// Additional tree-sitter queries per language whose `@name` /
+    /// `@definition.<type>` captures become entities (see `extra_queries`)
+    pub extra_queries: HashMap<Language, Vec<String>>,
+    /// Skip entities spanning fewer lines than this (default: 0, keep all)
+    ///
+    /// Filters one-line getters and other trivial entities out of the
+    /// graph; the count is reported in `StreamStats::small_entities_filtered`.
+    pub min_entity_lines: usize,
+    /// Store entities below `min_entity_lines` without their code instead
+    /// of dropping them, so their signatures (and edges) stay queryable
+    pub keep_signatures_of_filtered: bool,
+    /// Time each ingestion phase and print a summary table (see `profile`);
+    /// the totals are in `StreamResult::phase_timings`
+    pub profile: bool,
+    /// Cap on the estimated memory of ingesting one file (`None` =
+    /// unlimited; see `memory_budget`). Files whose estimate exceeds it are
+    /// skipped and reported.
+    pub memory_budget_bytes: Option<usize>,
+    /// Path patterns (`vendor/`, `*.pb.rs`) and header text (`@generated`)
+    /// marking generated or vendored files (see `generated`); their
+    /// entities are ingested and flagged, not skipped
+    pub generated_markers: Vec<String>,
+    /// Lines at the top of each file searched for header markers
+    pub generated_header_lines: usize,
+    /// Store entities classified as tests instead of skipping them
+    /// (default: false), so `pt02 --pair-tests` can attach them to the
+    /// code they test
+    pub keep_test_entities: bool,
+    /// Enrich Rust entities with hover, definition, references and
+    /// diagnostics from a `rust-analyzer` on `PATH` (default: false; see
+    /// `lsp_client`). Ingestion waits for it to load the workspace.
+    pub lsp: bool,
 }
 
 impl Default for StreamerConfig {
@@ -65,10 +176,31 @@ impl Default for StreamerConfig {
             root_dir: PathBuf::from("."),
             db_path: "mem".to_string(), // Use in-memory database by default
             max_file_size: 1024 * 1024, // 1MB
+            file_read_timeout: bounded_read::DEFAULT_FILE_READ_TIMEOUT,
             include_patterns: vec!["*.rs".to_string(), "*.py".to_string()], // Simplified patterns that work
             exclude_patterns: vec!["target/**".to_string(), "node_modules/**".to_string()],
             parsing_library: "tree-sitter".to_string(), // PRD default
             chunking: "ISGL1".to_string(), // PRD default
+            name_normalization: NameNormalizationPolicy::default(),
+            key_format: KeyFormat::default(),
+            checkpoint_path: None,
+            resume: true,
+            checkpoint_interval: checkpoint::DEFAULT_CHECKPOINT_INTERVAL,
+            max_doc_len: doc_comments::DEFAULT_MAX_DOC_LEN,
+            language_dialects: HashMap::new(),
+            store_spans_only: false,
+            git_diff_base: None,
+            path_style: PathStyle::Relative,
+            follow_symlinks: false,
+            extra_queries: HashMap::new(),
+            min_entity_lines: 0,
+            keep_signatures_of_filtered: false,
+            profile: false,
+            memory_budget_bytes: None,
+            generated_markers: generated::default_generated_markers(),
+            generated_header_lines: generated::DEFAULT_GENERATED_HEADER_LINES,
+            keep_test_entities: false,
+            lsp: false,
         }
     }
 }
@@ -79,7 +211,14 @@ pub struct ToolFactory;
 impl ToolFactory {
     /// Create a new file streamer instance with database connection
     pub async fn create_streamer(config: StreamerConfig) -> Result<Arc<FileStreamerImpl>> {
-        let generator = Isgl1KeyGeneratorFactory::new();
+        let generator: Arc<dyn Isgl1KeyGenerator> = Arc::new(
+            Isgl1KeyGeneratorImpl::new()
+                .with_name_normalizer(NameNormalizer::new(config.name_normalization))
+                .with_key_format(config.key_format)
+                .with_chunking(chunking::strategy_for_name(&config.chunking)?)
+                .with_dialects(config.language_dialects.clone())?
+                .with_extra_queries(&config.extra_queries)?,
+        );
         let test_detector = Arc::new(crate::test_detector::DefaultTestDetector::new());
         let streamer = FileStreamerImpl::new(config, generator, test_detector).await?;
         Ok(Arc::new(streamer))
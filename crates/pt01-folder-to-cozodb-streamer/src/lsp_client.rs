@@ -1,11 +1,20 @@
 //! LSP client for rust-analyzer integration.
 //!
-//! Provides hover metadata enrichment for Rust entities using rust-analyzer's LSP.
+//! Provides hover, definition, references and diagnostics enrichment for Rust
+//! entities using rust-analyzer's LSP (enabled by `StreamerConfig.lsp`).
 //! Follows graceful degradation: if rust-analyzer is unavailable, indexing continues without LSP metadata.
 
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use parseltongue_core::entities::{LspDiagnostic, Location};
 use crate::errors::*;
 
 /// Position in a text document (LSP protocol format)
@@ -38,10 +47,59 @@ pub struct HoverParams {
 pub struct HoverResponse {
     /// Markdown content from hover
     pub contents: String,
+    /// Signature or type of the hovered symbol, from the hover's code blocks
+    #[serde(default)]
+    pub resolved_type: Option<String>,
+    /// Module the hovered item lives in (`my_crate::module`), if shown
+    #[serde(default)]
+    pub module_path: Vec<String>,
     /// Raw JSON metadata for storage
     pub raw_metadata: serde_json::Value,
 }
 
+impl HoverResponse {
+    /// Hover laid out as rust-analyzer renders it: fenced code blocks, then
+    /// documentation after a `---` rule
+    ///
+    /// With two code blocks the first is the item's module path and the
+    /// last its signature; a lone block (a local, a field) is its type.
+    pub fn from_markdown(contents: impl Into<String>, raw_metadata: serde_json::Value) -> Self {
+        let contents = contents.into();
+        let mut blocks: Vec<Vec<&str>> = Vec::new();
+        let mut in_block = false;
+        for line in contents.lines() {
+            if line.starts_with("```") {
+                if !in_block {
+                    blocks.push(Vec::new());
+                }
+                in_block = !in_block;
+            } else if in_block {
+                if let Some(block) = blocks.last_mut() {
+                    block.push(line);
+                }
+            } else if line.trim() == "---" {
+                break;
+            }
+        }
+        let blocks: Vec<String> = blocks
+            .into_iter()
+            .map(|lines| lines.join("\n").trim().to_string())
+            .filter(|block| !block.is_empty())
+            .collect();
+
+        let module_path = match blocks.as_slice() {
+            [module, _, ..] => module.split("::").map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            resolved_type: blocks.last().cloned(),
+            module_path,
+            contents,
+            raw_metadata,
+        }
+    }
+}
+
 /// Trait for rust-analyzer LSP client (enables testability)
 #[async_trait]
 pub trait RustAnalyzerClient: Send + Sync {
@@ -54,23 +112,83 @@ pub trait RustAnalyzerClient: Send + Sync {
         character: u32,
     ) -> Result<Option<HoverResponse>>;
 
+    /// Where the symbol at a position is defined (`textDocument/definition`)
+    /// None if rust-analyzer is unavailable
+    async fn definition(&self, _file_path: &Path, _line: u32, _character: u32) -> Result<Option<Location>> {
+        Ok(None)
+    }
+
+    /// Locations referring to the symbol at a position (`textDocument/references`)
+    /// Empty if rust-analyzer is unavailable
+    async fn references(&self, _file_path: &Path, _line: u32, _character: u32) -> Result<Vec<Location>> {
+        Ok(Vec::new())
+    }
+
+    /// Diagnostics published for a file, lines 1-based
+    /// Empty if rust-analyzer is unavailable
+    async fn diagnostics(&self, _file_path: &Path) -> Result<Vec<LspDiagnostic>> {
+        Ok(Vec::new())
+    }
+
     /// Check if rust-analyzer is available
     async fn is_available(&self) -> bool;
 }
 
+/// How long rust-analyzer may take to load the workspace before requests
+const WORKSPACE_LOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long any one request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Real rust-analyzer LSP client implementation
+///
+/// Talks JSON-RPC to a `rust-analyzer` child process over stdio. Requests
+/// are sequential; a request that fails or times out drops the connection
+/// (and the process), after which every request degrades to empty.
 pub struct RustAnalyzerClientImpl {
-    // TODO: Add LSP process handle and communication channel
-    enabled: bool,
+    connection: tokio::sync::Mutex<Option<LspConnection>>,
 }
 
 impl RustAnalyzerClientImpl {
-    /// Create new rust-analyzer client
-    /// Attempts to spawn rust-analyzer process; if it fails, gracefully disables LSP
+    /// Create a client with no server behind it (LSP disabled)
     pub async fn new() -> Self {
-        // TODO: Implement actual LSP process spawning
-        // For now, stub implementation
-        Self { enabled: false }
+        Self { connection: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Spawn `rust-analyzer` for the workspace at `root_dir` and wait for it
+    /// to load; disabled if it cannot be started or never finishes loading
+    pub async fn start(root_dir: &Path) -> Self {
+        Self::start_program("rust-analyzer", root_dir).await
+    }
+
+    /// As `start`, running `program` as the server
+    pub async fn start_program(program: &str, root_dir: &Path) -> Self {
+        let connection = tokio::time::timeout(WORKSPACE_LOAD_TIMEOUT, LspConnection::start(program, root_dir))
+            .await
+            .ok()
+            .and_then(|started| started.ok());
+        Self { connection: tokio::sync::Mutex::new(connection) }
+    }
+
+    /// Send `method` with `params` about `file_path`, opening the file first
+    ///
+    /// None (and the connection dropped) on any failure.
+    async fn document_request(&self, method: &str, file_path: &Path, params: Value) -> Option<Value> {
+        let mut guard = self.connection.lock().await;
+        let connection = guard.as_mut()?;
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, async {
+            connection.open(file_path).await?;
+            connection.request(method, params).await
+        })
+        .await;
+        match response {
+            Ok(Ok(result)) => Some(result),
+            // A cancelled read leaves the stream mid-message: give up on it
+            _ => {
+                *guard = None;
+                None
+            }
+        }
     }
 }
 
@@ -78,28 +196,314 @@ impl RustAnalyzerClientImpl {
 impl RustAnalyzerClient for RustAnalyzerClientImpl {
     async fn hover(
         &self,
-        _file_path: &Path,
-        _line: u32,
-        _character: u32,
+        file_path: &Path,
+        line: u32,
+        character: u32,
     ) -> Result<Option<HoverResponse>> {
-        // Graceful degradation: return None if not enabled
-        if !self.enabled {
+        let params = position_params(file_path, line, character);
+        let Some(result) = self.document_request("textDocument/hover", file_path, params).await else {
+            return Ok(None);
+        };
+        let contents = hover_markdown(&result["contents"]);
+        if contents.is_empty() {
             return Ok(None);
         }
+        Ok(Some(HoverResponse::from_markdown(contents, result)))
+    }
 
-        // TODO: Implement actual LSP hover request
-        Ok(None)
+    async fn definition(&self, file_path: &Path, line: u32, character: u32) -> Result<Option<Location>> {
+        let params = position_params(file_path, line, character);
+        Ok(self
+            .document_request("textDocument/definition", file_path, params)
+            .await
+            .and_then(|result| parse_locations(&result).into_iter().next()))
+    }
+
+    async fn references(&self, file_path: &Path, line: u32, character: u32) -> Result<Vec<Location>> {
+        let mut params = position_params(file_path, line, character);
+        params["context"] = json!({ "includeDeclaration": false });
+        Ok(self
+            .document_request("textDocument/references", file_path, params)
+            .await
+            .map(|result| parse_locations(&result))
+            .unwrap_or_default())
+    }
+
+    async fn diagnostics(&self, file_path: &Path) -> Result<Vec<LspDiagnostic>> {
+        let guard = self.connection.lock().await;
+        Ok(guard
+            .as_ref()
+            .and_then(|connection| connection.diagnostics.get(&file_uri(file_path)).cloned())
+            .unwrap_or_default())
     }
 
     async fn is_available(&self) -> bool {
-        self.enabled
+        self.connection.lock().await.is_some()
     }
 }
 
+/// A running server and the state of the conversation with it
+struct LspConnection {
+    // Killed when the connection is dropped
+    _server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+    opened: HashSet<PathBuf>,
+    /// Latest `publishDiagnostics` per document URI
+    diagnostics: HashMap<String, Vec<LspDiagnostic>>,
+}
+
+impl LspConnection {
+    async fn start(program: &str, root_dir: &Path) -> io::Result<Self> {
+        let mut server = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (server.stdin.take(), server.stdout.take()) else {
+            return Err(io::Error::new(io::ErrorKind::Other, "server stdio not captured"));
+        };
+        let mut connection = Self {
+            _server: server,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 0,
+            opened: HashSet::new(),
+            diagnostics: HashMap::new(),
+        };
+
+        let root_uri = file_uri(root_dir);
+        connection
+            .request(
+                "initialize",
+                json!({
+                    "processId": std::process::id(),
+                    "rootUri": root_uri,
+                    "workspaceFolders": [{ "uri": root_uri, "name": "root" }],
+                    "capabilities": {
+                        "textDocument": {
+                            "hover": { "contentFormat": ["markdown", "plaintext"] },
+                            "definition": { "linkSupport": true },
+                            "publishDiagnostics": {},
+                        },
+                        "experimental": { "serverStatusNotification": true },
+                    },
+                }),
+            )
+            .await?;
+        connection.notify("initialized", json!({})).await?;
+        connection.wait_until_quiescent().await?;
+        Ok(connection)
+    }
+
+    async fn send(&mut self, message: Value) -> io::Result<()> {
+        let body = message.to_string();
+        self.stdin
+            .write_all(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+            .await?;
+        self.stdin.flush().await
+    }
+
+    async fn receive(&mut self) -> io::Result<Value> {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            if self.stdout.read_line(&mut header).await? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+        let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).await?;
+        serde_json::from_slice(&body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Deal with a message that is not the response being waited for
+    async fn handle(&mut self, message: &Value) -> io::Result<()> {
+        match (message.get("id"), message["method"].as_str()) {
+            // Server requests (progress tokens, registrations): acknowledge
+            (Some(id), Some(_)) => self.send(json!({ "jsonrpc": "2.0", "id": id, "result": null })).await,
+            (None, Some("textDocument/publishDiagnostics")) => {
+                let params = &message["params"];
+                if let Some(uri) = params["uri"].as_str() {
+                    let diagnostics = params["diagnostics"].as_array().map(Vec::as_slice).unwrap_or_default();
+                    self.diagnostics
+                        .insert(uri.to_string(), diagnostics.iter().filter_map(parse_diagnostic).collect());
+                }
+                Ok(())
+            }
+            // Stale responses and other notifications
+            _ => Ok(()),
+        }
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> io::Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        loop {
+            let message = self.receive().await?;
+            if message["id"].as_u64() == Some(id) && message.get("method").is_none() {
+                return match message.get("error") {
+                    Some(error) => Err(io::Error::new(io::ErrorKind::Other, error.to_string())),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+            }
+            self.handle(&message).await?;
+        }
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+    }
+
+    /// Send `didOpen` for `file_path` the first time it is queried
+    async fn open(&mut self, file_path: &Path) -> io::Result<()> {
+        if self.opened.contains(file_path) {
+            return Ok(());
+        }
+        let text = tokio::fs::read_to_string(file_path).await?;
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": file_uri(file_path), "languageId": "rust", "version": 1, "text": text },
+            }),
+        )
+        .await?;
+        self.opened.insert(file_path.to_path_buf());
+        Ok(())
+    }
+
+    /// Read until rust-analyzer reports the workspace loaded
+    /// (`experimental/serverStatus` with `quiescent: true`)
+    async fn wait_until_quiescent(&mut self) -> io::Result<()> {
+        loop {
+            let message = self.receive().await?;
+            if message["method"].as_str() == Some("experimental/serverStatus")
+                && message["params"]["quiescent"].as_bool() == Some(true)
+            {
+                return Ok(());
+            }
+            self.handle(&message).await?;
+        }
+    }
+}
+
+fn position_params(file_path: &Path, line: u32, character: u32) -> Value {
+    json!({
+        "textDocument": { "uri": file_uri(file_path) },
+        "position": { "line": line, "character": character },
+    })
+}
+
+/// Markdown text of a hover result's `contents`, in any of its LSP shapes
+/// (`MarkupContent`, a `MarkedString`, or a list of them)
+fn hover_markdown(contents: &Value) -> String {
+    match contents {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(hover_markdown).collect::<Vec<_>>().join("\n\n"),
+        Value::Object(object) => match (object.get("language").and_then(Value::as_str), object.get("value").and_then(Value::as_str)) {
+            (Some(language), Some(value)) => format!("```{}\n{}\n```", language, value),
+            (None, Some(value)) => value.to_string(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+/// Locations of a definition or references result: a `Location`, a list
+/// of them, or a list of `LocationLink`s
+fn parse_locations(result: &Value) -> Vec<Location> {
+    match result {
+        Value::Array(items) => items.iter().filter_map(parse_location).collect(),
+        Value::Null => Vec::new(),
+        single => parse_location(single).into_iter().collect(),
+    }
+}
+
+fn parse_location(value: &Value) -> Option<Location> {
+    let uri = value.get("uri").or_else(|| value.get("targetUri"))?.as_str()?;
+    let start = &value.get("range").or_else(|| value.get("targetSelectionRange"))?["start"];
+    Some(Location {
+        file_path: uri_path(uri)?,
+        line: u32::try_from(start["line"].as_u64()?).ok()?,
+        character: u32::try_from(start["character"].as_u64()?).ok()?,
+    })
+}
+
+fn parse_diagnostic(value: &Value) -> Option<LspDiagnostic> {
+    let severity = match value["severity"].as_u64() {
+        Some(1) => "error",
+        Some(2) | None => "warning",
+        Some(3) => "information",
+        _ => "hint",
+    };
+    Some(LspDiagnostic {
+        severity: severity.to_string(),
+        message: value["message"].as_str()?.to_string(),
+        // LSP lines are 0-based
+        line: u32::try_from(value["range"]["start"]["line"].as_u64()?).ok()? + 1,
+    })
+}
+
+/// `file://` URI of `path`, made absolute
+fn file_uri(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` URI
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = match encoded[i] {
+            b'%' => encoded
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&decoded).into_owned()))
+}
+
 /// Mock LSP client for testing
 #[cfg(test)]
 pub struct MockRustAnalyzerClient {
     responses: std::collections::HashMap<String, HoverResponse>,
+    definitions: std::collections::HashMap<String, Location>,
+    references: std::collections::HashMap<String, Vec<Location>>,
+    diagnostics: Vec<LspDiagnostic>,
 }
 
 #[cfg(test)]
@@ -107,12 +511,27 @@ impl MockRustAnalyzerClient {
     pub fn new() -> Self {
         Self {
             responses: std::collections::HashMap::new(),
+            definitions: std::collections::HashMap::new(),
+            references: std::collections::HashMap::new(),
+            diagnostics: Vec::new(),
         }
     }
 
     pub fn add_response(&mut self, key: String, response: HoverResponse) {
         self.responses.insert(key, response);
     }
+
+    pub fn add_definition(&mut self, key: String, location: Location) {
+        self.definitions.insert(key, location);
+    }
+
+    pub fn add_references(&mut self, key: String, locations: Vec<Location>) {
+        self.references.insert(key, locations);
+    }
+
+    pub fn add_diagnostic(&mut self, diagnostic: LspDiagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
 }
 
 #[cfg(test)]
@@ -128,6 +547,20 @@ impl RustAnalyzerClient for MockRustAnalyzerClient {
         Ok(self.responses.get(&key).cloned())
     }
 
+    async fn definition(&self, file_path: &Path, line: u32, character: u32) -> Result<Option<Location>> {
+        let key = format!("{}:{}:{}", file_path.display(), line, character);
+        Ok(self.definitions.get(&key).cloned())
+    }
+
+    async fn references(&self, file_path: &Path, line: u32, character: u32) -> Result<Vec<Location>> {
+        let key = format!("{}:{}:{}", file_path.display(), line, character);
+        Ok(self.references.get(&key).cloned().unwrap_or_default())
+    }
+
+    async fn diagnostics(&self, _file_path: &Path) -> Result<Vec<LspDiagnostic>> {
+        Ok(self.diagnostics.clone())
+    }
+
     async fn is_available(&self) -> bool {
         true
     }
@@ -141,14 +574,14 @@ mod tests {
     #[tokio::test]
     async fn test_mock_client_returns_configured_response() {
         let mut mock_client = MockRustAnalyzerClient::new();
-        let test_response = HoverResponse {
-            contents: "fn test() -> i32".to_string(),
-            raw_metadata: serde_json::json!({
+        let test_response = HoverResponse::from_markdown(
+            "fn test() -> i32",
+            serde_json::json!({
                 "type_info": {
                     "resolved_type": "i32"
                 }
             }),
-        };
+        );
 
         mock_client.add_response(
             "test.rs:10:5".to_string(),
@@ -195,4 +628,68 @@ mod tests {
         let client = RustAnalyzerClientImpl::new().await;
         assert!(!client.is_available().await);
     }
+
+    #[tokio::test]
+    async fn test_real_client_is_unavailable_when_server_cannot_start() {
+        let client = RustAnalyzerClientImpl::start_program("parseltongue-no-such-server", Path::new(".")).await;
+
+        assert!(!client.is_available().await);
+        assert!(client.definition(Path::new("test.rs"), 0, 3).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_hover_from_markdown_reads_module_path_and_signature() {
+        let markdown = "```rust\nmy_crate::math\n```\n\n```rust\npub fn add(a: i32, b: i32) -> i32\n```\n\n---\n\n```rust\nadd(1, 2)\n```";
+        let hover = HoverResponse::from_markdown(markdown, Value::Null);
+        assert_eq!(hover.resolved_type.as_deref(), Some("pub fn add(a: i32, b: i32) -> i32"));
+        assert_eq!(hover.module_path, ["my_crate", "math"]);
+
+        // A local: just its type
+        let hover = HoverResponse::from_markdown("```rust\nlet total: u64\n```", Value::Null);
+        assert_eq!(hover.resolved_type.as_deref(), Some("let total: u64"));
+        assert!(hover.module_path.is_empty());
+
+        assert!(HoverResponse::from_markdown("no code here", Value::Null).resolved_type.is_none());
+    }
+
+    #[test]
+    fn test_hover_markdown_accepts_each_contents_shape() {
+        assert_eq!(hover_markdown(&json!({ "kind": "markdown", "value": "text" })), "text");
+        assert_eq!(hover_markdown(&json!({ "language": "rust", "value": "i32" })), "```rust\ni32\n```");
+        assert_eq!(hover_markdown(&json!(["a", { "language": "rust", "value": "b" }])), "a\n\n```rust\nb\n```");
+    }
+
+    #[test]
+    fn test_parse_locations_accepts_locations_and_links() {
+        let range = json!({ "start": { "line": 4, "character": 7 }, "end": { "line": 4, "character": 10 } });
+        let expected = Location { file_path: PathBuf::from("/src/my lib.rs"), line: 4, character: 7 };
+
+        let location = json!({ "uri": "file:///src/my%20lib.rs", "range": range });
+        assert_eq!(parse_locations(&location), [expected.clone()]);
+        assert_eq!(parse_locations(&json!([location, location])).len(), 2);
+
+        let link = json!({ "targetUri": "file:///src/my%20lib.rs", "targetRange": range, "targetSelectionRange": range });
+        assert_eq!(parse_locations(&json!([link])), [expected]);
+        assert!(parse_locations(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_file_uri_round_trips() {
+        let path = Path::new("/no/such dir/lib%.rs");
+        assert_eq!(file_uri(path), "file:///no/such%20dir/lib%25.rs");
+        assert_eq!(uri_path(&file_uri(path)).unwrap(), path);
+    }
+
+    #[test]
+    fn test_parse_diagnostic_uses_one_based_lines() {
+        let diagnostic = json!({
+            "range": { "start": { "line": 2, "character": 0 }, "end": { "line": 2, "character": 5 } },
+            "severity": 1,
+            "message": "mismatched types",
+        });
+        assert_eq!(
+            parse_diagnostic(&diagnostic),
+            Some(LspDiagnostic { severity: "error".to_string(), message: "mismatched types".to_string(), line: 3 })
+        );
+    }
 }
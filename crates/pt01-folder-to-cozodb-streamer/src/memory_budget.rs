@@ -0,0 +1,49 @@
+//! Memory budget for ingestion (`--memory-budget`)
+//!
+//! Files are ingested one at a time, so peak memory is set by the largest
+//! file. Each file's peak is estimated from its size before it is read; a
+//! file whose estimate exceeds the budget is skipped with
+//! `ExceedsMemoryBudget` instead of risking an out-of-memory kill.
+
+use crate::errors::{Result, StreamerError};
+
+/// Peak bytes held per source byte while a file is ingested
+///
+/// Covers the raw bytes, the decoded text, the tree-sitter tree (several
+/// nodes per token) and the extracted entities with their code copies.
+pub const PARSE_MEMORY_FACTOR: usize = 12;
+
+/// Estimated peak memory for ingesting a file of `file_size` bytes
+pub fn estimate_memory_usage(file_size: usize) -> usize {
+    file_size.saturating_mul(PARSE_MEMORY_FACTOR)
+}
+
+/// Cap on the estimated memory of ingesting a single file
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryBudget {
+    limit_bytes: usize,
+}
+
+impl MemoryBudget {
+    /// Budget of `limit_bytes`
+    pub fn new(limit_bytes: usize) -> Self {
+        Self { limit_bytes }
+    }
+
+    pub fn limit_bytes(&self) -> usize {
+        self.limit_bytes
+    }
+
+    /// Fail with `ExceedsMemoryBudget` when `estimate` for `path` is larger
+    /// than the budget
+    pub fn check(&self, path: &str, estimate: usize) -> Result<()> {
+        if estimate > self.limit_bytes {
+            return Err(StreamerError::ExceedsMemoryBudget {
+                path: path.to_string(),
+                estimate,
+                budget: self.limit_bytes,
+            });
+        }
+        Ok(())
+    }
+}
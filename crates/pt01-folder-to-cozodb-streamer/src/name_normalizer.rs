@@ -0,0 +1,174 @@
+//! Entity name normalization for the ISGL1 key name segment.
+//!
+//! Grammars disagree on how they report names: a Rust method may surface as
+//! `Foo::bar` in one place and `bar` in another, generic types carry their
+//! parameters (`Cache<K, V>`), and PHP treats `Foo` and `foo` as the same
+//! symbol. Canonicalizing the segment keeps keys stable across those forms,
+//! so a change to generic parameters alone does not mint a new key.
+//!
+//! Only the key segment is normalized; `InterfaceSignature::name` keeps the
+//! name exactly as parsed. Dependency edges are keyed by the raw names the
+//! grammars report, so canonical keys leave those edges dangling; the
+//! default therefore preserves names, and `Canonical` is opt-in.
+
+use parseltongue_core::entities::Language;
+
+/// How entity names are turned into the ISGL1 key name segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameNormalizationPolicy {
+    /// Use the grammar-reported name verbatim, matching edge endpoints
+    #[default]
+    Preserve,
+    /// Strip qualifiers and generic parameters, fold case for
+    /// case-insensitive languages
+    Canonical,
+}
+
+/// Language-aware name canonicalizer used by the ISGL1 key generator
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NameNormalizer {
+    policy: NameNormalizationPolicy,
+}
+
+impl NameNormalizer {
+    pub fn new(policy: NameNormalizationPolicy) -> Self {
+        Self { policy }
+    }
+
+    pub fn policy(&self) -> NameNormalizationPolicy {
+        self.policy
+    }
+
+    /// Produce the key name segment for `name` (pure function)
+    ///
+    /// # Example
+    /// ```
+    /// use parseltongue_core::entities::Language;
+    /// use pt01_folder_to_cozodb_streamer::{NameNormalizationPolicy, NameNormalizer};
+    ///
+    /// let normalizer = NameNormalizer::new(NameNormalizationPolicy::Canonical);
+    /// assert_eq!(normalizer.normalize("Foo::bar", Language::Rust), "bar");
+    /// assert_eq!(normalizer.normalize("Cache<K, V>", Language::Rust), "Cache");
+    /// ```
+    pub fn normalize(&self, name: &str, language: Language) -> String {
+        match self.policy {
+            NameNormalizationPolicy::Preserve => name.to_string(),
+            NameNormalizationPolicy::Canonical => canonicalize(name, language),
+        }
+    }
+}
+
+fn canonicalize(name: &str, language: Language) -> String {
+    let without_generics = strip_bracketed(name.trim(), generic_brackets(language));
+    let unqualified = last_segment(&without_generics, qualifier_separators(language));
+    // ':' is the key delimiter and must never leak into a segment
+    let cleaned: String = unqualified
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+        .replace(':', "_");
+
+    if case_insensitive_names(language) {
+        cleaned.to_lowercase()
+    } else {
+        cleaned
+    }
+}
+
+/// Bracket pairs delimiting generic/type parameters in each language
+fn generic_brackets(language: Language) -> &'static [(char, char)] {
+    match language {
+        // Python subscripts (`List[int]`), Scala and Go type params use []
+        Language::Python | Language::Scala | Language::Go => &[('[', ']')],
+        _ => &[('<', '>')],
+    }
+}
+
+/// Separators between qualifier and member, longest first
+fn qualifier_separators(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust | Language::Cpp | Language::C => &["::"],
+        Language::Ruby => &["::", "#", "."],
+        Language::Php => &["::", "\\", "->"],
+        _ => &["::", "."],
+    }
+}
+
+fn case_insensitive_names(language: Language) -> bool {
+    matches!(language, Language::Php)
+}
+
+/// Remove every balanced bracketed span (nested spans included)
+fn strip_bracketed(name: &str, pairs: &[(char, char)]) -> String {
+    let mut depth = 0usize;
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        if pairs.iter().any(|(open, _)| *open == c) {
+            depth += 1;
+        } else if depth > 0 && pairs.iter().any(|(_, close)| *close == c) {
+            depth -= 1;
+        } else if depth == 0 {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Last non-empty segment after splitting on any of `separators`
+fn last_segment<'a>(name: &'a str, separators: &[&str]) -> &'a str {
+    let cut = separators
+        .iter()
+        .filter_map(|sep| name.rfind(sep).map(|idx| idx + sep.len()))
+        .filter(|&end| end < name.len())
+        .max();
+    match cut {
+        Some(end) => &name[end..],
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(name: &str, language: Language) -> String {
+        NameNormalizer::new(NameNormalizationPolicy::Canonical).normalize(name, language)
+    }
+
+    #[test]
+    fn rust_qualified_and_generic_forms_collapse() {
+        assert_eq!(canonical("Foo::bar", Language::Rust), "bar");
+        assert_eq!(canonical("bar", Language::Rust), "bar");
+        assert_eq!(canonical("crate::cache::Cache<K, V>", Language::Rust), "Cache");
+        assert_eq!(canonical("Cache<Vec<u8>>", Language::Rust), "Cache");
+        assert_eq!(canonical("<T as Display>::fmt", Language::Rust), "fmt");
+        assert_eq!(canonical("HashMap", Language::Rust), "HashMap");
+    }
+
+    #[test]
+    fn python_qualified_and_subscript_forms_collapse() {
+        assert_eq!(canonical("MyClass.method", Language::Python), "method");
+        assert_eq!(canonical("self.helper", Language::Python), "helper");
+        assert_eq!(canonical("Repository[User]", Language::Python), "Repository");
+        assert_eq!(canonical("__init__", Language::Python), "__init__");
+    }
+
+    #[test]
+    fn php_names_fold_case() {
+        assert_eq!(canonical("App\\Http\\UserController", Language::Php), "usercontroller");
+        assert_eq!(canonical("Foo::Bar", Language::Php), "bar");
+    }
+
+    #[test]
+    fn segment_never_contains_key_delimiters() {
+        let segment = canonical("operator ::", Language::Cpp);
+        assert!(!segment.contains(':'));
+        assert!(!canonical("weird name", Language::Java).contains(' '));
+    }
+
+    #[test]
+    fn preserve_policy_is_identity() {
+        let normalizer = NameNormalizer::new(NameNormalizationPolicy::Preserve);
+        assert_eq!(normalizer.normalize("Foo::bar<T>", Language::Rust), "Foo::bar<T>");
+    }
+}
@@ -0,0 +1,185 @@
+//! Structured diagnostics for malformed or partial source.
+//!
+//! `Isgl1KeyGeneratorImpl::try_parse_bytes` reports every problem as data
+//! instead of panicking or printing, which makes it usable as a fuzz target:
+//! any byte string yields either entities or a `ParseDiagnostics`.
+
+use std::fmt;
+
+use parseltongue_core::entities::{Language, ParseErrorRecord};
+use thiserror::Error;
+use tree_sitter::Tree;
+
+/// Cap on listed issues; a binary blob can contain thousands of error nodes
+pub const MAX_REPORTED_ISSUES: usize = 32;
+
+/// One reason the input could not be turned into entities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseIssue {
+    /// Input is not UTF-8; the first `valid_up_to` bytes are
+    InvalidUtf8 { valid_up_to: usize },
+    /// No grammar is loaded for the requested language
+    UnsupportedLanguage,
+    /// tree-sitter returned no tree at all
+    NoTree,
+    /// Region tree-sitter could not parse (1-based line and column)
+    SyntaxError { line: usize, column: usize, byte_len: usize },
+    /// Token tree-sitter had to invent to recover, e.g. a missing `}`
+    MissingToken { line: usize, column: usize, kind: String },
+    /// Parsing succeeded but entity or dependency extraction did not
+    ExtractionFailed { reason: String },
+}
+
+impl fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIssue::InvalidUtf8 { valid_up_to } => {
+                write!(f, "invalid UTF-8 after byte {}", valid_up_to)
+            }
+            ParseIssue::UnsupportedLanguage => write!(f, "no grammar for language"),
+            ParseIssue::NoTree => write!(f, "parser produced no tree"),
+            ParseIssue::SyntaxError { line, column, byte_len } => {
+                write!(f, "syntax error at {}:{} ({} bytes)", line, column, byte_len)
+            }
+            ParseIssue::MissingToken { line, column, kind } => {
+                write!(f, "missing {} at {}:{}", kind, line, column)
+            }
+            ParseIssue::ExtractionFailed { reason } => write!(f, "extraction failed: {}", reason),
+        }
+    }
+}
+
+/// Everything wrong with one parse attempt
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{language} input rejected: {}", .issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct ParseDiagnostics {
+    pub language: Language,
+    /// At most `MAX_REPORTED_ISSUES`, in source order; never empty
+    pub issues: Vec<ParseIssue>,
+    /// Issues found beyond the cap
+    pub omitted: usize,
+}
+
+impl ParseDiagnostics {
+    pub fn single(language: Language, issue: ParseIssue) -> Self {
+        Self {
+            language,
+            issues: vec![issue],
+            omitted: 0,
+        }
+    }
+
+    /// Issues with a source position, as rows for the `ParseErrors` relation
+    pub fn records(&self, file_path: &str) -> Vec<ParseErrorRecord> {
+        self.issues
+            .iter()
+            .filter_map(|issue| {
+                let (line, column, message) = match issue {
+                    ParseIssue::SyntaxError { line, column, byte_len } => {
+                        (*line, *column, format!("syntax error ({} bytes)", byte_len))
+                    }
+                    ParseIssue::MissingToken { line, column, kind } => {
+                        (*line, *column, format!("missing {}", kind))
+                    }
+                    _ => return None,
+                };
+                Some(ParseErrorRecord { file_path: file_path.to_string(), line, column, message })
+            })
+            .collect()
+    }
+
+    /// Diagnostics for every error and missing node in `tree`
+    ///
+    /// `None` when the tree is error-free.
+    pub fn from_tree(language: Language, tree: &Tree) -> Option<Self> {
+        let root = tree.root_node();
+        if !root.has_error() {
+            return None;
+        }
+
+        let mut issues = Vec::new();
+        let mut found = 0;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let position = node.start_position();
+            let issue = if node.is_error() {
+                ParseIssue::SyntaxError {
+                    line: position.row + 1,
+                    column: position.column + 1,
+                    byte_len: node.byte_range().len(),
+                }
+            } else if node.is_missing() {
+                ParseIssue::MissingToken {
+                    line: position.row + 1,
+                    column: position.column + 1,
+                    kind: node.kind().to_string(),
+                }
+            } else {
+                // Only subtrees containing an error are worth walking
+                let mut cursor = node.walk();
+                let children: Vec<_> = node.children(&mut cursor).filter(|c| c.has_error()).collect();
+                stack.extend(children.into_iter().rev());
+                continue;
+            };
+
+            found += 1;
+            if issues.len() < MAX_REPORTED_ISSUES {
+                issues.push(issue);
+            }
+        }
+
+        // `has_error` without a visible error node should not happen; still
+        // never return an empty diagnostic
+        if issues.is_empty() {
+            issues.push(ParseIssue::SyntaxError { line: 1, column: 1, byte_len: root.byte_range().len() });
+            found = 1;
+        }
+        Some(Self {
+            language,
+            omitted: found - issues.len(),
+            issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_rust(source: &str) -> Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&parseltongue_core::query_extractor::tree_sitter_language(Language::Rust).unwrap()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_clean_tree_has_no_diagnostics() {
+        assert_eq!(ParseDiagnostics::from_tree(Language::Rust, &parse_rust("fn main() {}\n")), None);
+    }
+
+    #[test]
+    fn test_truncated_source_reports_position() {
+        let diagnostics = ParseDiagnostics::from_tree(Language::Rust, &parse_rust("fn main() {\n    let x = 1;\n"))
+            .unwrap();
+
+        assert!(!diagnostics.issues.is_empty());
+        assert!(
+            diagnostics.issues.iter().any(|issue| matches!(
+                issue,
+                ParseIssue::MissingToken { .. } | ParseIssue::SyntaxError { .. }
+            )),
+            "{:?}",
+            diagnostics
+        );
+        assert!(diagnostics.to_string().starts_with("rust input rejected: "), "{}", diagnostics);
+    }
+
+    #[test]
+    fn test_issue_list_is_capped() {
+        let garbage = ") ".repeat(MAX_REPORTED_ISSUES * 4);
+        let diagnostics = ParseDiagnostics::from_tree(Language::Rust, &parse_rust(&garbage)).unwrap();
+
+        assert!(diagnostics.issues.len() <= MAX_REPORTED_ISSUES);
+        assert!(diagnostics.issues.len() + diagnostics.omitted >= 1);
+    }
+}
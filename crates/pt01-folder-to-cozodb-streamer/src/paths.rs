@@ -0,0 +1,111 @@
+//! How ingested file paths are recorded.
+//!
+//! Paths end up in every ISGL1 key, so the same file must always be stored
+//! under the same path: indexing `.` and `/abs/repo` have to agree. Paths
+//! are therefore resolved (`canonicalize`) against the resolved `root_dir`
+//! before any key is generated. The file is still read from the path the
+//! directory walk found.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Form of the file paths stored in entities and keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// Relative to `root_dir`, with `/` separators (`src/lib.rs`)
+    #[default]
+    Relative,
+    /// Resolved absolute path
+    Absolute,
+}
+
+/// `root_dir` resolved once per streamer
+#[derive(Debug, Clone)]
+pub struct PathCanonicalizer {
+    root: PathBuf,
+    style: PathStyle,
+}
+
+impl PathCanonicalizer {
+    pub fn new(root_dir: &Path, style: PathStyle) -> Self {
+        Self {
+            root: resolve(root_dir),
+            style,
+        }
+    }
+
+    /// Path to store for the file at `path`
+    ///
+    /// Files outside the root (a `stream_file` call on some other path) are
+    /// stored absolute whatever the style.
+    pub fn stored_path(&self, path: &Path) -> PathBuf {
+        let resolved = resolve(path);
+        match (self.style, resolved.strip_prefix(&self.root)) {
+            (PathStyle::Relative, Ok(relative)) => PathBuf::from(
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            ),
+            _ => resolved,
+        }
+    }
+}
+
+/// Canonical form of `path`, or an absolute, lexically cleaned one when
+/// it does not exist (yet)
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut cleaned = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                cleaned.pop();
+            }
+            other => cleaned.push(other),
+        }
+    }
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_paths_use_forward_slashes_from_root() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("src/nested")).unwrap();
+        let file = root.path().join("src/nested/lib.rs");
+        std::fs::write(&file, "").unwrap();
+
+        let paths = PathCanonicalizer::new(root.path(), PathStyle::Relative);
+        assert_eq!(paths.stored_path(&file), PathBuf::from("src/nested/lib.rs"));
+        assert_eq!(
+            paths.stored_path(&root.path().join("src/./nested/../nested/lib.rs")),
+            PathBuf::from("src/nested/lib.rs")
+        );
+
+        let absolute = PathCanonicalizer::new(root.path(), PathStyle::Absolute);
+        assert_eq!(absolute.stored_path(&file), std::fs::canonicalize(&file).unwrap());
+    }
+
+    #[test]
+    fn test_paths_outside_root_stay_absolute() {
+        let root = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let file = other.path().join("lib.rs");
+        std::fs::write(&file, "").unwrap();
+
+        let paths = PathCanonicalizer::new(root.path(), PathStyle::Relative);
+        assert_eq!(paths.stored_path(&file), std::fs::canonicalize(&file).unwrap());
+    }
+}
@@ -0,0 +1,75 @@
+//! Per-phase ingestion timing (`--profile`)
+//!
+//! Durations accumulate across all files so a run shows whether it is
+//! I/O-bound (walk, read, db_write) or parse-bound (parse, key_generation).
+//! With profiling off nothing is recorded and the map stays empty.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Directory traversal and file filtering
+pub const PHASE_WALK: &str = "walk";
+/// Reading and decoding file content
+pub const PHASE_READ: &str = "read";
+/// tree-sitter entity and dependency extraction
+pub const PHASE_PARSE: &str = "parse";
+/// ISGL1 key generation
+pub const PHASE_KEY_GENERATION: &str = "key_generation";
+/// Entity, span, edge and parse-error writes
+pub const PHASE_DB_WRITE: &str = "db_write";
+
+/// Phases in the order the summary table lists them
+pub const PHASES: [&str; 5] = [PHASE_WALK, PHASE_READ, PHASE_PARSE, PHASE_KEY_GENERATION, PHASE_DB_WRITE];
+
+/// Accumulated duration per phase
+#[derive(Debug, Default)]
+pub struct PhaseTimings {
+    enabled: bool,
+    totals: Mutex<HashMap<String, Duration>>,
+}
+
+impl PhaseTimings {
+    /// Timings that record only when `enabled`
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            totals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add `elapsed` to `phase`
+    pub fn record(&self, phase: &str, elapsed: Duration) {
+        if !self.enabled {
+            return;
+        }
+        let mut totals = self.totals.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *totals.entry(phase.to_string()).or_default() += elapsed;
+    }
+
+    /// Totals so far
+    pub fn snapshot(&self) -> HashMap<String, Duration> {
+        self.totals.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+/// Summary table of `timings`, with each phase's share of `total`
+pub fn format_table(timings: &HashMap<String, Duration>, total: Duration) -> String {
+    let mut table = format!("{:<16} {:>12} {:>7}\n", "Phase", "Time", "Share");
+    for phase in PHASES {
+        let elapsed = timings.get(phase).copied().unwrap_or_default();
+        let share = if total.is_zero() {
+            0.0
+        } else {
+            elapsed.as_secs_f64() / total.as_secs_f64() * 100.0
+        };
+        table.push_str(&format!(
+            "{:<16} {:>10.1}ms {:>6.1}%\n",
+            phase,
+            elapsed.as_secs_f64() * 1000.0,
+            share
+        ));
+    }
+    table.push_str(&format!("{:<16} {:>10.1}ms", "total", total.as_secs_f64() * 1000.0));
+    table
+}
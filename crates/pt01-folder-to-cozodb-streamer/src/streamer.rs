@@ -1,30 +1,53 @@
 //! File streaming implementation for folder-to-cozoDB processing.
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::fs;
-use tokio::io::AsyncReadExt;
+use tokio_util::sync::CancellationToken;
 use walkdir::WalkDir;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 
+use parseltongue_core::clock::{Clock, SystemClock};
 use parseltongue_core::entities::*;
+use parseltongue_core::metrics::{complexity_level, ComplexityMeter};
 use parseltongue_core::storage::CozoDbStorage;
+use crate::bounded_read::{read_to_limit, special_file_kind};
+use crate::checkpoint::{file_mtime_nanos, now_nanos, IngestionCheckpoint};
+use crate::doc_comments::truncate_doc;
+use crate::encoding::{decode_source, DecodedSource, SOURCE_ENCODING_KEY};
 use crate::errors::*;
+use crate::generated::GeneratedDetector;
+use crate::git_scope::changed_files;
 use crate::isgl1_generator::*;
 use crate::lsp_client::*;
+use crate::memory_budget::{estimate_memory_usage, MemoryBudget};
 use crate::test_detector::{TestDetector, EntityClass};
+use crate::paths::PathCanonicalizer;
+use crate::profile::{self, PhaseTimings, PHASE_DB_WRITE, PHASE_KEY_GENERATION, PHASE_PARSE, PHASE_READ, PHASE_WALK};
+use crate::transform::EntityTransform;
+use crate::visibility::{enclosing_item, infer_visibility};
 use crate::StreamerConfig;
 
 // Import LSP metadata types from parseltongue-core
-use parseltongue_core::entities::{LspMetadata, TypeInformation, UsageAnalysis};
+use parseltongue_core::entities::{LspMetadata, TypeInformation};
 
 /// File streamer interface
 #[async_trait::async_trait]
 pub trait FileStreamer: Send + Sync {
     /// Stream all files from the configured directory to database
-    async fn stream_directory(&self) -> Result<StreamResult>;
+    async fn stream_directory(&self) -> Result<StreamResult> {
+        self.stream_directory_cancellable(None).await
+    }
+
+    /// Stream all files, stopping early once `cancel` is triggered
+    ///
+    /// Cancellation is checked between files, so every file is either fully
+    /// stored or not touched. The partial result has `cancelled` set and, with
+    /// a checkpoint configured, a later `--resume` run picks up where it stopped.
+    async fn stream_directory_cancellable(&self, cancel: Option<&CancellationToken>) -> Result<StreamResult>;
 
     /// Stream a single file to database
     async fn stream_file(&self, file_path: &Path) -> Result<FileResult>;
@@ -38,9 +61,32 @@ pub trait FileStreamer: Send + Sync {
 pub struct StreamResult {
     pub total_files: usize,
     pub processed_files: usize,
+    /// Files skipped because a resumed checkpoint already covered them
+    pub skipped_files: usize,
     pub entities_created: usize,
     pub errors: Vec<String>,
     pub duration: std::time::Duration,
+    /// Stopped early by a cancellation token; counts cover committed files only
+    pub cancelled: bool,
+    /// Time per phase (`profile::PHASES`) with `StreamerConfig::profile`;
+    /// empty otherwise
+    pub phase_timings: HashMap<String, Duration>,
+}
+
+impl StreamResult {
+    /// Fail when the run recorded any warning in `errors`
+    ///
+    /// Skipped and unreadable files are reported without failing the run;
+    /// strict callers (`--fail-on-warnings`) turn them into an error here.
+    pub fn fail_on_warnings(&self) -> Result<()> {
+        match self.errors.first() {
+            Some(first) => Err(StreamerError::WarningsPresent {
+                count: self.errors.len(),
+                first: first.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
 }
 
 /// Single file processing result
@@ -59,6 +105,9 @@ pub struct StreamStats {
     pub entities_created: usize,
     pub code_entities_created: usize,  // v0.9.3: Track CODE entities separately
     pub test_entities_created: usize,  // v0.9.3: Track TEST entities separately
+    /// Entities below `StreamerConfig::min_entity_lines` (dropped, or stored
+    /// without code when `keep_signatures_of_filtered` is set)
+    pub small_entities_filtered: usize,
     pub errors_encountered: usize,
 }
 
@@ -70,6 +119,20 @@ pub struct FileStreamerImpl {
     test_detector: Arc<dyn TestDetector>,
     db: Arc<CozoDbStorage>,
     stats: std::sync::Mutex<StreamStats>,
+    /// Applied in order to each entity before it is stored
+    transforms: Vec<Box<dyn EntityTransform>>,
+    /// Maps walked paths to the paths stored in keys and signatures
+    paths: PathCanonicalizer,
+    /// Stamps `created_at`/`modified_at` of indexed entities
+    clock: Arc<dyn Clock>,
+    /// Per-phase durations, recorded when `config.profile` is set
+    timings: PhaseTimings,
+    /// Per-file size check, from `config.memory_budget_bytes`
+    memory_budget: Option<MemoryBudget>,
+    /// Flags files matching `config.generated_markers`
+    generated: GeneratedDetector,
+    /// Measures entity complexity, reusing one parser per language
+    complexity: std::sync::Mutex<ComplexityMeter>,
 }
 
 impl FileStreamerImpl {
@@ -86,23 +149,34 @@ impl FileStreamerImpl {
                 details: format!("Failed to create database: {}", e),
             })?;
 
-        // Create schema
-        db.create_schema()
+        // Create or migrate schema (idempotent, so re-indexing an existing db works)
+        db.ensure_schema()
             .await
             .map_err(|e| StreamerError::StorageError {
                 details: format!("Failed to create schema: {}", e),
             })?;
 
         // Initialize LSP client (graceful degradation if unavailable)
-        let lsp_client = RustAnalyzerClientImpl::new().await;
+        let lsp_client = if config.lsp {
+            RustAnalyzerClientImpl::start(&config.root_dir).await
+        } else {
+            RustAnalyzerClientImpl::new().await
+        };
 
         Ok(Self {
+            paths: PathCanonicalizer::new(&config.root_dir, config.path_style),
+            timings: PhaseTimings::new(config.profile),
+            memory_budget: config.memory_budget_bytes.map(MemoryBudget::new),
+            generated: GeneratedDetector::new(&config.generated_markers, config.generated_header_lines),
             config,
             key_generator,
             lsp_client: Arc::new(lsp_client),
             test_detector,
             db: Arc::new(db),
             stats: std::sync::Mutex::new(StreamStats::default()),
+            transforms: Vec::new(),
+            clock: Arc::new(SystemClock),
+            complexity: std::sync::Mutex::new(ComplexityMeter::new()),
         })
     }
 
@@ -121,23 +195,62 @@ impl FileStreamerImpl {
                 details: format!("Failed to create database: {}", e),
             })?;
 
-        // Create schema
-        db.create_schema()
+        // Create or migrate schema (idempotent, so re-indexing an existing db works)
+        db.ensure_schema()
             .await
             .map_err(|e| StreamerError::StorageError {
                 details: format!("Failed to create schema: {}", e),
             })?;
 
         Ok(Self {
+            paths: PathCanonicalizer::new(&config.root_dir, config.path_style),
+            timings: PhaseTimings::new(config.profile),
+            memory_budget: config.memory_budget_bytes.map(MemoryBudget::new),
+            generated: GeneratedDetector::new(&config.generated_markers, config.generated_header_lines),
             config,
             key_generator,
             lsp_client,
             test_detector,
             db: Arc::new(db),
             stats: std::sync::Mutex::new(StreamStats::default()),
+            transforms: Vec::new(),
+            clock: Arc::new(SystemClock),
+            complexity: std::sync::Mutex::new(ComplexityMeter::new()),
         })
     }
 
+    /// Post-process every stored entity with `transforms`, in order
+    ///
+    /// See `transform` for the hook and the bundled `ComplexityTagger`.
+    pub fn with_transforms(mut self, transforms: Vec<Box<dyn EntityTransform>>) -> Self {
+        self.transforms = transforms;
+        self
+    }
+
+    /// Timestamp entities with `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Database the streamer writes to
+    pub fn storage(&self) -> &Arc<CozoDbStorage> {
+        &self.db
+    }
+
+    /// Write one entity
+    ///
+    /// With a checkpoint (incremental re-indexing of an on-disk database)
+    /// the entity is upserted, so re-indexing keeps its `created_at` and
+    /// access counts; otherwise it is inserted outright.
+    async fn store_entity(&self, entity: &CodeEntity) -> parseltongue_core::error::Result<()> {
+        if self.config.checkpoint_path.is_some() {
+            self.db.upsert_entity(entity, MergePolicy::default()).await
+        } else {
+            self.db.insert_entity(entity).await
+        }
+    }
+
     /// Convert ParsedEntity to CodeEntity for database storage
     fn parsed_entity_to_code_entity(
         &self,
@@ -145,16 +258,31 @@ impl FileStreamerImpl {
         isgl1_key: &str,
         source_code: &str,
         file_path: &Path,
+        container: Option<&ParsedEntity>,
     ) -> std::result::Result<CodeEntity, parseltongue_core::error::ParseltongError> {
+        // Extract the code snippet from the source
+        let code_snippet = self.extract_code_snippet(source_code, parsed.line_range.0, parsed.line_range.1);
+        let container_code = container
+            .map(|item| self.extract_code_snippet(source_code, item.line_range.0, item.line_range.1));
+
         // Create InterfaceSignature
         let interface_signature = InterfaceSignature {
             entity_type: self.convert_entity_type(&parsed.entity_type),
             name: parsed.name.clone(),
-            visibility: Visibility::Public, // Default to public for now
+            visibility: infer_visibility(
+                parsed.language,
+                &parsed.entity_type,
+                &parsed.name,
+                &code_snippet,
+                container_code.as_deref(),
+            ),
             file_path: PathBuf::from(&parsed.file_path),
             line_range: LineRange::new(parsed.line_range.0 as u32, parsed.line_range.1 as u32)?,
             module_path: vec![], // TODO: Extract from file path
-            documentation: None,
+            documentation: parsed
+                .metadata
+                .get("documentation")
+                .map(|doc| truncate_doc(doc, self.config.max_doc_len)),
             language_specific: self.create_language_signature(&parsed.language),
         };
 
@@ -172,17 +300,27 @@ impl FileStreamerImpl {
         };
         
         let mut entity = CodeEntity::new(isgl1_key.to_string(), interface_signature, entity_class)?;
-
-        // Extract the code snippet from the source
-        let code_snippet = self.extract_code_snippet(source_code, parsed.line_range.0, parsed.line_range.1);
+        entity.metadata = EntityMetadata::at(self.clock.now());
 
         // Set current_code and future_code to the same value (unchanged state)
         entity.current_code = Some(code_snippet.clone());
         entity.future_code = Some(code_snippet);
+        entity.metadata.semantic_hash = entity.semantic_hash().unwrap_or_default();
 
         // GREEN Phase: Apply TDD classification based on parsed metadata
         entity.tdd_classification = self.classify_entity(parsed);
 
+        // Numeric complexity from the syntax tree, and the level derived from it
+        let language = Language::from_file_path(&entity.interface_signature.file_path);
+        let metrics = match (language, entity.current_code.as_deref()) {
+            (Some(language), Some(code)) => self.complexity.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).measure(code, language),
+            _ => None,
+        };
+        if let Some(metrics) = metrics {
+            entity.metadata.complexity_score = Some(metrics.cyclomatic);
+            entity.tdd_classification.complexity = complexity_level(metrics.cyclomatic);
+        }
+
         Ok(entity)
     }
 
@@ -233,6 +371,7 @@ impl FileStreamerImpl {
             crate::isgl1_generator::EntityType::Method => parseltongue_core::entities::EntityType::Method,
             crate::isgl1_generator::EntityType::Module => parseltongue_core::entities::EntityType::Module,
             crate::isgl1_generator::EntityType::Variable => parseltongue_core::entities::EntityType::Variable,
+            crate::isgl1_generator::EntityType::Macro => parseltongue_core::entities::EntityType::Macro,
 
             // Pragmatic mappings (v0.8.9 MVP)
             crate::isgl1_generator::EntityType::Namespace => parseltongue_core::entities::EntityType::Module,   // C++/C# namespace → Module
@@ -286,6 +425,25 @@ impl FileStreamerImpl {
             .join("\n")
     }
 
+    /// Byte span of the snippet `extract_code_snippet` returns for a line range
+    ///
+    /// `None` when the span would not reproduce the snippet exactly (CRLF line
+    /// endings), so the caller keeps the full text instead.
+    fn snippet_byte_span(&self, source: &str, start_line: usize, end_line: usize) -> Option<(usize, usize)> {
+        let mut offset = 0;
+        let mut start = None;
+        let mut end = None;
+        for (idx, line) in source.split_inclusive('\n').enumerate() {
+            if idx + 1 >= start_line && idx < end_line {
+                start.get_or_insert(offset);
+                end = Some(offset + line.trim_end_matches('\n').len());
+            }
+            offset += line.len();
+        }
+        let span = (start?, end?);
+        (source[span.0..span.1] == self.extract_code_snippet(source, start_line, end_line)).then_some(span)
+    }
+
     /// Check if file should be processed based on patterns
     fn should_process_file(&self, file_path: &Path) -> bool {
         let path_str = file_path.to_string_lossy();
@@ -356,48 +514,93 @@ impl FileStreamerImpl {
         false
     }
 
-    /// Read file content with size limit
-    async fn read_file_content(&self, file_path: &Path) -> Result<String> {
+    /// Check the file's estimated ingestion memory against the budget
+    ///
+    /// Fails with `ExceedsMemoryBudget` when the estimate is over it; always
+    /// passes without a budget.
+    async fn check_memory_budget(&self, file_path: &Path) -> Result<()> {
+        let Some(budget) = &self.memory_budget else {
+            return Ok(());
+        };
         let metadata = fs::metadata(file_path).await.map_err(|e| {
             StreamerError::FileSystemError {
                 path: file_path.to_string_lossy().to_string(),
                 source: e,
             }
         })?;
+        let estimate = estimate_memory_usage(metadata.len() as usize);
+        budget.check(&file_path.to_string_lossy(), estimate)
+    }
 
-        if metadata.len() as usize > self.config.max_file_size {
-            return Err(StreamerError::FileTooLarge {
-                path: file_path.to_string_lossy().to_string(),
-                size: metadata.len() as usize,
-                limit: self.config.max_file_size,
-            });
-        }
-
-        let mut content = String::new();
-        let mut file = fs::File::open(file_path).await.map_err(|e| {
+    /// Read file content with size limit, decoded to UTF-8
+    ///
+    /// Only regular files are read. The size limit is checked against the
+    /// metadata and enforced again while reading, and opening plus reading
+    /// must finish within `file_read_timeout` (see `bounded_read`).
+    /// UTF-16 and Windows-1252 files are transcoded (see `encoding`); bytes
+    /// that cannot be decoded fail with `UndecodableFile`.
+    async fn read_file_content(&self, file_path: &Path) -> Result<DecodedSource> {
+        let path_str = || file_path.to_string_lossy().to_string();
+        let metadata = fs::metadata(file_path).await.map_err(|e| {
             StreamerError::FileSystemError {
-                path: file_path.to_string_lossy().to_string(),
+                path: path_str(),
                 source: e,
             }
         })?;
 
-        file.read_to_string(&mut content).await.map_err(|e| {
-            StreamerError::FileSystemError {
-                path: file_path.to_string_lossy().to_string(),
-                source: e,
+        if let Some(kind) = special_file_kind(&metadata.file_type()) {
+            return Err(StreamerError::NotRegularFile {
+                path: path_str(),
+                kind: kind.to_string(),
+            });
+        }
+
+        let limit = self.config.max_file_size;
+        if metadata.len() as usize > limit {
+            return Err(StreamerError::FileTooLarge {
+                path: path_str(),
+                size: metadata.len() as usize,
+                limit,
+            });
+        }
+
+        let read = async {
+            let file = fs::File::open(file_path).await?;
+            read_to_limit(file, limit).await
+        };
+        let bytes = match tokio::time::timeout(self.config.file_read_timeout, read).await {
+            Ok(Ok(Some(bytes))) => bytes,
+            Ok(Ok(None)) => return Err(StreamerError::ReadLimitExceeded { path: path_str(), limit }),
+            Ok(Err(e)) => return Err(StreamerError::FileSystemError { path: path_str(), source: e }),
+            Err(_) => {
+                return Err(StreamerError::ReadTimeout {
+                    path: path_str(),
+                    timeout: self.config.file_read_timeout,
+                })
             }
-        })?;
+        };
 
-        Ok(content)
+        decode_source(&bytes).map_err(|reason| StreamerError::UndecodableFile {
+            path: path_str(),
+            reason,
+        })
     }
 
     /// Update streaming statistics (v0.9.3: track CODE/TEST separately)
-    fn update_stats(&self, entities_created: usize, code_count: usize, test_count: usize, had_error: bool) {
+    fn update_stats(
+        &self,
+        entities_created: usize,
+        code_count: usize,
+        test_count: usize,
+        small_count: usize,
+        had_error: bool,
+    ) {
         if let Ok(mut stats) = self.stats.lock() {
             stats.files_processed += 1;
             stats.entities_created += entities_created;
             stats.code_entities_created += code_count;
             stats.test_entities_created += test_count;
+            stats.small_entities_filtered += small_count;
             if had_error {
                 stats.errors_encountered += 1;
             }
@@ -407,13 +610,19 @@ impl FileStreamerImpl {
 
 #[async_trait::async_trait]
 impl FileStreamer for FileStreamerImpl {
-    async fn stream_directory(&self) -> Result<StreamResult> {
+    async fn stream_directory_cancellable(&self, cancel: Option<&CancellationToken>) -> Result<StreamResult> {
         let start_time = Instant::now();
         let mut total_files = 0;
         let mut processed_files = 0;
         let mut entities_created = 0;
         let mut errors = Vec::new();
 
+        // PR-scoped run: a failing diff aborts before anything is ingested
+        let git_scope = match &self.config.git_diff_base {
+            Some(base) => Some(changed_files(&self.config.root_dir, base)?),
+            None => None,
+        };
+
         println!(
             "{}",
             style("Starting directory streaming...").blue().bold()
@@ -428,34 +637,180 @@ impl FileStreamer for FileStreamerImpl {
         );
         pb.set_message("Scanning files...");
 
-        // Walk through directory
-        for entry in WalkDir::new(&self.config.root_dir)
-            .follow_links(false)
+        // Resume from the checkpoint of an interrupted run, if any
+        let mut checkpoint = self.config.checkpoint_path.as_ref().map(|path| {
+            if self.config.resume {
+                IngestionCheckpoint::load(path, &self.config.root_dir)
+            } else {
+                IngestionCheckpoint::new(&self.config.root_dir)
+            }
+        });
+        if let Some(resumed) = checkpoint.as_ref().filter(|c| !c.is_empty()) {
+            pb.println(format!(
+                "{} Resuming: {} files already ingested",
+                style("↻").cyan(),
+                resumed.len()
+            ));
+        }
+        let mut skipped_files = 0;
+        let mut since_checkpoint = 0;
+        let mut cancelled = false;
+
+        // Walk through directory; with follow_symlinks, each real directory
+        // is entered once so symlink cycles terminate
+        let follow_symlinks = self.config.follow_symlinks;
+        let mut visited_dirs = HashSet::new();
+        let mut symlink_loops = Vec::new();
+        let mut walker = WalkDir::new(&self.config.root_dir)
+            .follow_links(follow_symlinks)
             .into_iter()
-            .filter_map(|e| e.ok())
-        {
+            .filter_entry(|entry| {
+                !follow_symlinks || first_visit(entry, &mut visited_dirs, &mut symlink_loops)
+            });
+        loop {
+            let walk_started = Instant::now();
+            let Some(entry) = walker.next() else {
+                break;
+            };
+            self.timings.record(PHASE_WALK, walk_started.elapsed());
+            let entry = match entry {
+                Ok(entry) => entry,
+                // walkdir's own check for a link back to an ancestor
+                Err(e) if e.loop_ancestor().is_some() => {
+                    let skip_msg = format!("{}: skipped symlink loop", e.path().unwrap_or(Path::new("")).display());
+                    pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), skip_msg));
+                    errors.push(skip_msg);
+                    continue;
+                }
+                Err(_) => continue,
+            };
             let path = entry.path();
 
-            if path.is_file() && self.should_process_file(path) {
+            if cancel.is_some_and(|token| token.is_cancelled()) {
+                cancelled = true;
+                break;
+            }
+
+            let filter_started = Instant::now();
+            // FIFOs and devices pass so that reading reports them as skipped
+            let wanted = std::fs::metadata(path).is_ok_and(|metadata| !metadata.is_dir())
+                && self.should_process_file(path)
+                && git_scope.as_ref().map_or(true, |changed| changed.contains(path));
+            self.timings.record(PHASE_WALK, filter_started.elapsed());
+            if wanted {
                 total_files += 1;
+
+                let mtime = file_mtime_nanos(path).unwrap_or_else(now_nanos);
+                if checkpoint.as_ref().is_some_and(|c| c.is_unchanged_by_mtime(path, mtime)) {
+                    skipped_files += 1;
+                    continue;
+                }
+
                 pb.set_message(format!("Processing: {}", path.display()));
 
-                match self.stream_file(path).await {
-                    Ok(result) => {
-                        processed_files += 1;
-                        entities_created += result.entities_created;
+                match self.check_memory_budget(path).await {
+                    Ok(()) => {}
+                    Err(StreamerError::ExceedsMemoryBudget { estimate, budget, .. }) => {
+                        let skip_msg = format!(
+                            "{}: skipped: exceeds memory budget (estimated {} bytes > {} bytes)",
+                            path.display(),
+                            estimate,
+                            budget
+                        );
+                        errors.push(skip_msg.clone());
+                        pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), skip_msg));
+                        continue;
                     }
                     Err(e) => {
                         let error_msg = format!("{}: {}", path.display(), e);
                         errors.push(error_msg.clone());
                         pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), error_msg));
-                        self.update_stats(0, 0, 0, true);  // v0.9.3: No entities created on error
+                        self.update_stats(0, 0, 0, 0, true);
+                        continue;
+                    }
+                }
+
+                let read_started = Instant::now();
+                let read = self.read_file_content(path).await;
+                self.timings.record(PHASE_READ, read_started.elapsed());
+                let outcome = match read {
+                    Ok(source) => {
+                        // mtime moved but content did not (checkout, touch): still skip
+                        if let Some(c) = checkpoint.as_mut().filter(|c| c.is_unchanged_by_content(path, &source.text)) {
+                            c.record(path, mtime, &source.text);
+                            skipped_files += 1;
+                            continue;
+                        }
+                        if checkpoint.as_ref().is_some_and(|c| c.is_reformat_only(path, &source.text)) {
+                            self.stream_reformatted(path, &source).await.map(|result| (result, source.text))
+                        } else {
+                            self.stream_content(path, &source, &HashMap::new()).await.map(|result| (result, source.text))
+                        }
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match outcome {
+                    Ok((result, content)) => {
+                        processed_files += 1;
+                        entities_created += result.entities_created;
+
+                        if let (Some(c), Some(checkpoint_path)) =
+                            (checkpoint.as_mut(), self.config.checkpoint_path.as_ref())
+                        {
+                            c.record(path, mtime, &content);
+                            since_checkpoint += 1;
+                            if since_checkpoint >= self.config.checkpoint_interval.max(1) {
+                                since_checkpoint = 0;
+                                if let Err(e) = c.save(checkpoint_path) {
+                                    pb.println(format!("{} checkpoint not saved: {}", style("⚠").yellow().for_stderr(), e));
+                                }
+                            }
+                        }
                     }
+                    Err(e) => match skip_reason(&e) {
+                        // Not a failure, but never silent: say which file and why
+                        Some(reason) => {
+                            let skip_msg = format!("{}: skipped: {}", path.display(), reason);
+                            errors.push(skip_msg.clone());
+                            pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), skip_msg));
+                        }
+                        None => {
+                            let error_msg = format!("{}: {}", path.display(), e);
+                            errors.push(error_msg.clone());
+                            pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), error_msg));
+                            self.update_stats(0, 0, 0, 0, true);  // v0.9.3: No entities created on error
+                        }
+                    },
                 }
             }
         }
 
-        pb.finish_with_message("Directory streaming completed");
+        // Release the walker's borrow of `symlink_loops`
+        drop(walker);
+        for skipped in symlink_loops {
+            pb.println(format!("{} {}", style("⚠").yellow().for_stderr(), skipped));
+            errors.push(skipped);
+        }
+
+        if let Some(checkpoint_path) = &self.config.checkpoint_path {
+            let finished = if cancelled {
+                // Keep progress so a resumed run skips the committed files
+                checkpoint.as_ref().map_or(Ok(()), |c| c.save(checkpoint_path))
+            } else {
+                // Clean completion: the next run starts from scratch
+                IngestionCheckpoint::remove(checkpoint_path)
+            };
+            if let Err(e) = finished {
+                errors.push(e.to_string());
+            }
+        }
+
+        pb.finish_with_message(if cancelled {
+            "Directory streaming cancelled"
+        } else {
+            "Directory streaming completed"
+        });
 
         let duration = start_time.elapsed();
 
@@ -464,16 +819,35 @@ impl FileStreamer for FileStreamerImpl {
 
         // Print summary
         println!("\n{}", style("Streaming Summary:").green().bold());
+        if cancelled {
+            println!("{}", style("Cancelled: counts cover files committed before the stop").yellow());
+        }
         println!("Total files found: {}", total_files);
         println!("Files processed: {}", processed_files);
+        if skipped_files > 0 {
+            println!("Files skipped (resumed from checkpoint): {}", skipped_files);
+        }
         println!("Entities created: {} (CODE only)", style(entities_created).cyan().bold());
         println!("  └─ CODE entities: {}", style(final_stats.code_entities_created).cyan());
         println!("  └─ TEST entities: {} {}",
             style(final_stats.test_entities_created).yellow(),
             style("(excluded for optimal LLM context)").dim()
         );
+        if final_stats.small_entities_filtered > 0 {
+            println!(
+                "Entities below {} lines: {}{}",
+                self.config.min_entity_lines,
+                final_stats.small_entities_filtered,
+                if self.config.keep_signatures_of_filtered { " (signatures kept)" } else { " (skipped)" }
+            );
+        }
         println!("Errors encountered: {}", errors.len());
         println!("Duration: {:?}", duration);
+        let phase_timings = self.timings.snapshot();
+        if self.config.profile {
+            println!("\n{}", style("Phase Timings:").green().bold());
+            println!("{}", profile::format_table(&phase_timings, duration));
+        }
 
         // ✅ v0.9.6: Clear message about test exclusion
         if final_stats.test_entities_created > 0 {
@@ -485,56 +859,212 @@ impl FileStreamer for FileStreamerImpl {
         Ok(StreamResult {
             total_files,
             processed_files,
+            skipped_files,
             entities_created,
             errors,
             duration,
+            cancelled,
+            phase_timings,
         })
     }
 
     async fn stream_file(&self, file_path: &Path) -> Result<FileResult> {
+        let read_started = Instant::now();
+        let source = self.read_file_content(file_path).await;
+        self.timings.record(PHASE_READ, read_started.elapsed());
+        let source = source?;
+        self.stream_content(file_path, &source, &HashMap::new()).await
+    }
+
+    fn get_stats(&self) -> StreamStats {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+impl FileStreamerImpl {
+    /// Re-stream a file whose change since the checkpoint is formatting only
+    ///
+    /// Keys, line ranges and code follow the new layout, and keys the file
+    /// no longer produces are deleted. Entities whose semantic hash is
+    /// unchanged keep their stored `created_at` and `modified_at`.
+    async fn stream_reformatted(&self, file_path: &Path, source: &DecodedSource) -> Result<FileResult> {
+        let stored_path = self.paths.stored_path(file_path).to_string_lossy().to_string();
+        let storage_error = |e: parseltongue_core::error::ParseltongError| StreamerError::StorageError {
+            details: format!("Failed to re-index reformatted {}: {}", stored_path, e),
+        };
+
+        let previous = self.db.get_entities_by_file(&stored_path).await.map_err(storage_error)?;
+        let unchanged: HashMap<String, EntityMetadata> = previous
+            .iter()
+            .filter(|entity| !entity.metadata.semantic_hash.is_empty())
+            .map(|entity| (entity.metadata.semantic_hash.clone(), entity.metadata.clone()))
+            .collect();
+
+        let result = self.stream_content(file_path, source, &unchanged).await?;
+
+        let current: HashSet<String> = self
+            .db
+            .get_entities_by_file(&stored_path)
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .map(|entity| entity.isgl1_key)
+            .collect();
+        for stale in previous.iter().filter(|entity| !current.contains(&entity.isgl1_key)) {
+            self.db.delete_entity(&stale.isgl1_key).await.map_err(storage_error)?;
+        }
+        Ok(result)
+    }
+
+    /// Parse already-read file content and store its entities and edges
+    ///
+    /// Entities whose semantic hash is a key of `unchanged` take that
+    /// metadata's timestamps.
+    async fn stream_content(
+        &self,
+        file_path: &Path,
+        source: &DecodedSource,
+        unchanged: &HashMap<String, EntityMetadata>,
+    ) -> Result<FileResult> {
         let file_path_str = file_path.to_string_lossy().to_string();
+        let content = source.text.as_str();
 
-        // Read file content
-        let content = self.read_file_content(file_path).await?;
+        // Keys and signatures use the canonical path; spans, LSP and the
+        // checkpoint keep the one the file was read from
+        let stored_path = self.paths.stored_path(file_path);
+        let stored_path_str = stored_path.to_string_lossy().to_string();
 
         // Parse code entities AND dependencies (two-pass extraction)
-        let (parsed_entities, dependencies) = self.key_generator.parse_source(&content, file_path)?;
+        let parse_started = Instant::now();
+        let parsed = self.key_generator.parse_source_with_diagnostics(content, &stored_path);
+        self.timings.record(PHASE_PARSE, parse_started.elapsed());
+        let parsed = parsed?;
+        let (parsed_entities, dependencies) = (parsed.entities, parsed.dependencies);
 
         let mut entities_created = 0;
         let mut code_count = 0;  // v0.9.3: Track CODE entities
         let mut test_count = 0;  // v0.9.3: Track TEST entities
+        let mut small_count = 0;
         let mut errors: Vec<String> = Vec::new();
 
+        // Always replace, so a file fixed since the last run loses its rows
+        let parse_errors = parsed
+            .diagnostics
+            .map(|diagnostics| diagnostics.records(&stored_path_str))
+            .unwrap_or_default();
+        let write_started = Instant::now();
+        if let Err(e) = self.db.replace_parse_errors(&stored_path_str, &parse_errors).await {
+            errors.push(format!("Failed to record parse errors: {}", e));
+        }
+        self.timings.record(PHASE_DB_WRITE, write_started.elapsed());
+
+        // Generated files are ingested like any other, then flagged
+        let is_generated = self.generated.is_generated(&stored_path_str, content);
+        let mut generated_keys: Vec<String> = Vec::new();
+
+        // Trait and trait impl items take their visibility from the enclosing item
+        let containers: Vec<Option<ParsedEntity>> = parsed_entities
+            .iter()
+            .map(|entity| enclosing_item(entity, &parsed_entities).cloned())
+            .collect();
+
         // Process each parsed entity
-        for parsed_entity in parsed_entities {
+        for (parsed_entity, container) in parsed_entities.into_iter().zip(containers) {
+            let (start_line, end_line) = parsed_entity.line_range;
+            let below_min_size = end_line.saturating_sub(start_line) + 1 < self.config.min_entity_lines;
+            if below_min_size {
+                small_count += 1;
+                if !self.config.keep_signatures_of_filtered {
+                    continue;
+                }
+            }
+
             // Generate ISGL1 key
-            let isgl1_key = self.key_generator.generate_key(&parsed_entity)?;
+            let key_started = Instant::now();
+            let isgl1_key = self.key_generator.generate_key(&parsed_entity);
+            self.timings.record(PHASE_KEY_GENERATION, key_started.elapsed());
+            let isgl1_key = isgl1_key?;
 
             // Enrich with LSP metadata for Rust files (sequential hover requests)
-            let lsp_metadata = self.fetch_lsp_metadata_for_entity(&parsed_entity, file_path).await;
+            let lsp_metadata = self.fetch_lsp_metadata_for_entity(&parsed_entity, content, file_path).await;
 
             // Convert ParsedEntity to CodeEntity
-            match self.parsed_entity_to_code_entity(&parsed_entity, &isgl1_key, &content, file_path) {
+            match self.parsed_entity_to_code_entity(&parsed_entity, &isgl1_key, content, file_path, container.as_ref()) {
                 Ok(mut code_entity) => {
                     // Store LSP metadata as JSON string if available
                     if let Some(metadata) = lsp_metadata {
                         code_entity.lsp_metadata = Some(metadata);
                     }
+                    code_entity
+                        .metadata
+                        .additional
+                        .insert(SOURCE_ENCODING_KEY.to_string(), source.encoding.to_string());
+                    // Same code in a new layout: the content was not updated
+                    if let Some(stored) = unchanged.get(&code_entity.metadata.semantic_hash) {
+                        code_entity.metadata.created_at = stored.created_at;
+                        code_entity.metadata.modified_at = stored.modified_at;
+                    }
 
                     // v0.9.3: Track entity_class for stats
                     let entity_class = code_entity.entity_class;
 
                     // ✅ v0.9.6: Skip test entities - they pollute LLM context
-                    if matches!(entity_class, parseltongue_core::EntityClass::TestImplementation) {
+                    // (unless kept for `pt02 --pair-tests`)
+                    let is_test = matches!(entity_class, parseltongue_core::EntityClass::TestImplementation);
+                    if is_test {
                         test_count += 1;
-                        continue; // Don't insert tests into database
+                        if !self.config.keep_test_entities {
+                            continue; // Don't insert tests into database
+                        }
+                    }
+
+                    for transform in &self.transforms {
+                        transform.transform(&mut code_entity);
+                    }
+
+                    // Below min_entity_lines with keep_signatures_of_filtered:
+                    // the signature stays, the code goes
+                    if below_min_size {
+                        code_entity.current_code = None;
+                        code_entity.future_code = None;
+                    }
+
+                    // Spans-only mode: keep offsets, read the text back on demand
+                    let span = if self.config.store_spans_only && source.verbatim && !below_min_size {
+                        let (start_line, end_line) = parsed_entity.line_range;
+                        self.snippet_byte_span(content, start_line, end_line)
+                    } else {
+                        None
+                    }
+                    .map(|(byte_start, byte_end)| CodeSpan {
+                        file_path: file_path_str.clone(),
+                        byte_start,
+                        byte_end,
+                    });
+                    if span.is_some() {
+                        code_entity.current_code = None;
+                        code_entity.future_code = None;
                     }
 
-                    // Store in real database (CODE entities only)
-                    match self.db.insert_entity(&code_entity).await {
+                    // Store in real database (CODE entities, and TEST ones when kept)
+                    let write_started = Instant::now();
+                    let stored = match &span {
+                        Some(span) => match self.store_entity(&code_entity).await {
+                            Ok(_) => self.db.insert_code_span(&isgl1_key, span).await,
+                            Err(e) => Err(e),
+                        },
+                        None => self.store_entity(&code_entity).await,
+                    };
+                    self.timings.record(PHASE_DB_WRITE, write_started.elapsed());
+                    match stored {
                         Ok(_) => {
                             entities_created += 1;
-                            code_count += 1; // v0.9.6: Only CODE entities reach here
+                            if !is_test {
+                                code_count += 1;
+                            }
+                            if is_generated {
+                                generated_keys.push(isgl1_key.clone());
+                            }
                         }
                         Err(e) => {
                             let error_msg = format!("Failed to insert entity {}: {}", isgl1_key, e);
@@ -549,9 +1079,17 @@ impl FileStreamer for FileStreamerImpl {
             }
         }
 
+        // Always replace, so a file no longer generated loses its flags
+        let write_started = Instant::now();
+        if let Err(e) = self.db.replace_generated_entities(&stored_path_str, &generated_keys).await {
+            errors.push(format!("Failed to flag generated entities: {}", e));
+        }
+        self.timings.record(PHASE_DB_WRITE, write_started.elapsed());
+
         // ALWAYS create DependencyEdges schema, even if no dependencies
         // This ensures pt02-level00 can query the table (returns empty array if no edges)
         // Bug fix: Previously only created schema if dependencies.is_empty() == false
+        let write_started = Instant::now();
         if let Err(e) = self.db.create_dependency_edges_schema().await {
             // Schema might already exist - that's ok
             if !e.to_string().contains("already exists") && !e.to_string().contains("conflicts with an existing") {
@@ -571,8 +1109,9 @@ impl FileStreamer for FileStreamerImpl {
                 }
             }
         }
+        self.timings.record(PHASE_DB_WRITE, write_started.elapsed());
 
-        self.update_stats(entities_created, code_count, test_count, !errors.is_empty());
+        self.update_stats(entities_created, code_count, test_count, small_count, !errors.is_empty());
 
         Ok(FileResult {
             file_path: file_path_str,
@@ -586,17 +1125,12 @@ impl FileStreamer for FileStreamerImpl {
         })
     }
 
-    fn get_stats(&self) -> StreamStats {
-        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
-    }
-}
-
-impl FileStreamerImpl {
-    /// Fetch LSP metadata for an entity using rust-analyzer hover
+    /// Fetch LSP metadata for an entity using rust-analyzer hover, definition, references and diagnostics
     /// Returns LspMetadata if successful, None if unavailable or failed (graceful degradation)
     async fn fetch_lsp_metadata_for_entity(
         &self,
         entity: &ParsedEntity,
+        content: &str,
         file_path: &Path,
     ) -> Option<LspMetadata> {
         // Only fetch for Rust files
@@ -604,38 +1138,110 @@ impl FileStreamerImpl {
             return None;
         }
 
-        // Calculate hover position at the start of the entity (line is 0-indexed in LSP)
+        // Query at the entity's name on its first line (LSP positions are 0-indexed);
+        // the line start would hit `pub` or `fn` instead
         let line = entity.line_range.0.saturating_sub(1) as u32;
-        let character = 0u32; // Start of line (tree-sitter gives us the entity name position)
-
-        // Request hover metadata
-        match self.lsp_client.hover(file_path, line, character).await {
-            Ok(Some(hover_response)) => {
-                // Convert hover response to LspMetadata (stub/MVP implementation)
-                Self::hover_response_to_lsp_metadata(&hover_response)
-            }
-            Ok(None) => None, // Graceful degradation
-            Err(_) => None,   // Graceful degradation
-        }
+        let character = content
+            .lines()
+            .nth(line as usize)
+            .and_then(|text| name_column(text, &entity.name))
+            .unwrap_or(0);
+
+        // Request hover metadata; without it the entity gets none
+        let hover_response = match self.lsp_client.hover(file_path, line, character).await {
+            Ok(Some(hover_response)) => hover_response,
+            Ok(None) => return None, // Graceful degradation
+            Err(_) => return None,   // Graceful degradation
+        };
+        let definition = self
+            .lsp_client
+            .definition(file_path, line, character)
+            .await
+            .unwrap_or_default();
+        let references = self
+            .lsp_client
+            .references(file_path, line, character)
+            .await
+            .unwrap_or_default();
+        let (first_line, last_line) = (entity.line_range.0 as u32, entity.line_range.1 as u32);
+        let diagnostics = self
+            .lsp_client
+            .diagnostics(file_path)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|d| (first_line..=last_line).contains(&d.line))
+            .collect();
+
+        let mut metadata = Self::hover_response_to_lsp_metadata(&hover_response);
+        metadata.type_information.definition_location = definition;
+        metadata.usage_analysis.total_references = references.len();
+        metadata.usage_analysis.usage_locations = references;
+        metadata.diagnostics = diagnostics;
+        Some(metadata)
     }
 
-    /// Convert hover response to LspMetadata (stub implementation for MVP)
-    /// Future enhancement: parse rust-analyzer response for richer metadata
-    fn hover_response_to_lsp_metadata(hover: &HoverResponse) -> Option<LspMetadata> {
-        Some(LspMetadata {
+    /// Convert hover response to structured LspMetadata
+    ///
+    /// The hover text is kept whole; the resolved type and module path come
+    /// from its code blocks (see `HoverResponse::from_markdown`).
+    fn hover_response_to_lsp_metadata(hover: &HoverResponse) -> LspMetadata {
+        LspMetadata {
+            hover: Some(hover.contents.clone()),
             type_information: TypeInformation {
-                resolved_type: hover.contents.clone(),
-                module_path: vec![],
-                generic_parameters: vec![],
-                definition_location: None,
-            },
-            usage_analysis: UsageAnalysis {
-                total_references: 0,
-                usage_locations: vec![],
-                dependents: vec![],
+                resolved_type: hover.resolved_type.clone().unwrap_or_default(),
+                module_path: hover.module_path.clone(),
+                ..TypeInformation::default()
             },
-            semantic_tokens: vec![],
+            ..LspMetadata::default()
+        }
+    }
+}
+
+/// Column of `name`'s last path segment as a whole word in `line`
+fn name_column(line: &str, name: &str) -> Option<u32> {
+    let segment = name.rsplit("::").next().unwrap_or(name);
+    if segment.is_empty() {
+        return None;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(segment)
+        .find(|&(at, _)| {
+            !line[..at].ends_with(is_ident) && !line[at + segment.len()..].starts_with(is_ident)
         })
+        .map(|(at, _)| line[..at].chars().count() as u32)
+}
+
+/// Whether the walk should enter `entry`: false for a directory whose
+/// canonical path was already visited, recording symlinks that lead there
+fn first_visit(entry: &walkdir::DirEntry, visited: &mut HashSet<PathBuf>, symlink_loops: &mut Vec<String>) -> bool {
+    if !entry.file_type().is_dir() {
+        return true;
+    }
+    let Ok(canonical) = entry.path().canonicalize() else {
+        return true;
+    };
+    if visited.insert(canonical) {
+        return true;
+    }
+    if entry.path_is_symlink() {
+        symlink_loops.push(format!("{}: skipped symlink loop", entry.path().display()));
+    }
+    false
+}
+
+/// Why a file was skipped, for errors that skip a file without failing it
+fn skip_reason(err: &StreamerError) -> Option<String> {
+    match err {
+        StreamerError::FileTooLarge { size, limit, .. } => {
+            Some(format!("exceeds max_file_size ({} bytes > {} bytes)", size, limit))
+        }
+        StreamerError::ReadLimitExceeded { limit, .. } => {
+            Some(format!("exceeded max_file_size while reading (> {} bytes)", limit))
+        }
+        StreamerError::ReadTimeout { timeout, .. } => Some(format!("read timed out after {:?}", timeout)),
+        StreamerError::NotRegularFile { kind, .. } => Some(format!("not a regular file ({})", kind)),
+        _ => None,
     }
 }
 
@@ -29,28 +29,28 @@ struct Calculator {
         // Setup: Create mock LSP client with hover responses
         let mut mock_lsp = MockRustAnalyzerClient::new();
 
-        // Add hover response for function (line 1)
+        // Add hover response for function (line 1, at its name)
         mock_lsp.add_response(
-            format!("{}:0:0", test_file.display()),
-            HoverResponse {
-                contents: "fn calculate_sum(a: i32, b: i32) -> i32".to_string(),
-                raw_metadata: serde_json::json!({
+            format!("{}:0:3", test_file.display()),
+            HoverResponse::from_markdown(
+                "fn calculate_sum(a: i32, b: i32) -> i32",
+                serde_json::json!({
                     "type": "function",
                     "signature": "fn(i32, i32) -> i32"
                 }),
-            },
+            ),
         );
 
-        // Add hover response for struct (line 5)
+        // Add hover response for struct (line 5, at its name)
         mock_lsp.add_response(
-            format!("{}:4:0", test_file.display()),
-            HoverResponse {
-                contents: "struct Calculator".to_string(),
-                raw_metadata: serde_json::json!({
+            format!("{}:4:7", test_file.display()),
+            HoverResponse::from_markdown(
+                "struct Calculator",
+                serde_json::json!({
                     "type": "struct",
                     "fields": ["name: String"]
                 }),
-            },
+            ),
         );
 
         // Setup: Create streamer with mock LSP client
@@ -62,6 +62,7 @@ struct Calculator {
             exclude_patterns: vec![],
             parsing_library: "tree-sitter".to_string(),
             chunking: "ISGL1".to_string(),
+            ..Default::default()
         };
 
         let key_generator = Isgl1KeyGeneratorFactory::new();
@@ -86,6 +87,62 @@ struct Calculator {
         assert!(result.error.is_none(), "Should have no errors");
     }
 
+    #[tokio::test]
+    async fn test_stored_metadata_has_definition_references_and_diagnostics() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("test.rs");
+        std::fs::write(&test_file, "fn unused(a: i32) -> i32 {\n    a\n}\n\nfn other() {}\n").unwrap();
+
+        // Queried at the name: `fn unused` puts it in column 3
+        let key = format!("{}:0:3", test_file.display());
+        let mut mock_lsp = MockRustAnalyzerClient::new();
+        mock_lsp.add_response(
+            key.clone(),
+            HoverResponse::from_markdown(
+                "```rust\ntest\n```\n\n```rust\nfn unused(a: i32) -> i32\n```",
+                serde_json::json!({}),
+            ),
+        );
+        let reference = |line| Location { file_path: test_file.clone(), line, character: 4 };
+        mock_lsp.add_definition(key.clone(), reference(0));
+        mock_lsp.add_references(key, vec![reference(7), reference(9)]);
+        for (line, message) in [(1, "function `unused` is never used"), (5, "function `other` is never used")] {
+            mock_lsp.add_diagnostic(LspDiagnostic {
+                severity: "warning".to_string(),
+                message: message.to_string(),
+                line,
+            });
+        }
+
+        let config = StreamerConfig {
+            root_dir: temp_dir.path().to_path_buf(),
+            db_path: "mem".to_string(),
+            include_patterns: vec!["*.rs".to_string()],
+            exclude_patterns: vec![],
+            ..Default::default()
+        };
+        let streamer = FileStreamerImpl::new_with_lsp(
+            config,
+            Isgl1KeyGeneratorFactory::new(),
+            std::sync::Arc::new(mock_lsp),
+            std::sync::Arc::new(crate::test_detector::DefaultTestDetector::new()),
+        )
+        .await
+        .unwrap();
+        streamer.stream_file(&test_file).await.unwrap();
+
+        let entities = streamer.db.get_all_entities().await.unwrap();
+        let unused = entities.iter().find(|e| e.interface_signature.name == "unused").unwrap();
+        let metadata = unused.lsp_metadata.as_ref().unwrap();
+        let definition = metadata.definition_location().unwrap();
+        assert_eq!((definition.file_path.as_path(), definition.line, definition.character), (test_file.as_path(), 0, 4));
+        assert_eq!(metadata.type_information.resolved_type, "fn unused(a: i32) -> i32");
+        assert_eq!(metadata.type_information.module_path, ["test"]);
+        assert_eq!(metadata.references_count(), 2);
+        assert_eq!(metadata.diagnostics.len(), 1, "only diagnostics inside the entity");
+        assert_eq!(metadata.diagnostics[0].message, "function `unused` is never used");
+    }
+
     #[tokio::test]
     async fn test_streamer_gracefully_degrades_without_lsp() {
         // Setup: Create temp directory with Rust file
@@ -112,6 +169,7 @@ struct Calculator {
             exclude_patterns: vec![],
             parsing_library: "tree-sitter".to_string(),
             chunking: "ISGL1".to_string(),
+            ..Default::default()
         };
 
         let key_generator = Isgl1KeyGeneratorFactory::new();
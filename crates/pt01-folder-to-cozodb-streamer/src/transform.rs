@@ -0,0 +1,112 @@
+//! Entity post-processing hooks run during ingestion.
+//!
+//! An `EntityTransform` sees every entity after it is built from the parse
+//! (and enriched with LSP metadata) and right before it is stored, so it can
+//! fill in or adjust any field without changes to the streamer. Transforms
+//! registered with `FileStreamerImpl::with_transforms` run in order. Test
+//! entities reach them only when `StreamerConfig.keep_test_entities` keeps
+//! them; otherwise they are dropped before the transforms run.
+
+use parseltongue_core::entities::{CodeEntity, ComplexityLevel};
+
+/// Hook that edits an entity before it is stored
+pub trait EntityTransform: Send + Sync {
+    fn transform(&self, entity: &mut CodeEntity);
+}
+
+/// Sets `tdd_classification.complexity` from the entity's code, with
+/// custom thresholds
+///
+/// Uses `metadata.complexity_score` (cyclomatic complexity from the syntax
+/// tree, set by the streamer) when present. Otherwise approximates it as
+/// one plus the number of branch points: branch keywords (`if`, loops,
+/// `match`, `case`, `catch`, ...) and the `&&`, `||` and `?` operators,
+/// which works for languages without a bundled grammar. Entities without
+/// code keep their classification.
+#[derive(Debug, Clone, Copy)]
+pub struct ComplexityTagger {
+    /// Lowest score rated `Moderate`
+    moderate_from: usize,
+    /// Lowest score rated `Complex`
+    complex_from: usize,
+}
+
+impl Default for ComplexityTagger {
+    fn default() -> Self {
+        Self {
+            moderate_from: 5,
+            complex_from: 11,
+        }
+    }
+}
+
+impl ComplexityTagger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scores from which entities rate `Moderate` and `Complex`
+    pub fn with_thresholds(mut self, moderate_from: usize, complex_from: usize) -> Self {
+        self.moderate_from = moderate_from;
+        self.complex_from = complex_from.max(moderate_from);
+        self
+    }
+
+    /// Approximate cyclomatic complexity of `code`
+    pub fn score(code: &str) -> usize {
+        const BRANCH_WORDS: [&str; 10] =
+            ["if", "elif", "for", "while", "loop", "case", "catch", "except", "when", "match"];
+
+        let words = code
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|word| BRANCH_WORDS.contains(word))
+            .count();
+        let operators = code.matches("&&").count() + code.matches("||").count() + code.matches('?').count();
+        1 + words + operators
+    }
+
+    fn level(&self, score: usize) -> ComplexityLevel {
+        if score >= self.complex_from {
+            ComplexityLevel::Complex
+        } else if score >= self.moderate_from {
+            ComplexityLevel::Moderate
+        } else {
+            ComplexityLevel::Simple
+        }
+    }
+}
+
+impl EntityTransform for ComplexityTagger {
+    fn transform(&self, entity: &mut CodeEntity) {
+        let score = match (entity.metadata.complexity_score, entity.current_code.as_deref()) {
+            (Some(score), _) => score as usize,
+            (None, Some(code)) => Self::score(code),
+            (None, None) => return,
+        };
+        entity.tdd_classification.complexity = self.level(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_counts_branch_points() {
+        assert_eq!(ComplexityTagger::score("fn id(x: u32) -> u32 { x }"), 1);
+        assert_eq!(
+            ComplexityTagger::score("fn f(a: bool, b: bool) -> Result<()> { if a && b { g()?; } for _ in 0..3 {} Ok(()) }"),
+            5
+        );
+        // Identifiers containing a keyword are not branches
+        assert_eq!(ComplexityTagger::score("let iffy = format_for(x);"), 1);
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let tagger = ComplexityTagger::new().with_thresholds(2, 3);
+        assert_eq!(tagger.level(1), ComplexityLevel::Simple);
+        assert_eq!(tagger.level(2), ComplexityLevel::Moderate);
+        assert_eq!(tagger.level(3), ComplexityLevel::Complex);
+    }
+}
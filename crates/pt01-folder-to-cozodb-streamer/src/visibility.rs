@@ -0,0 +1,167 @@
+//! Visibility of parsed entities, read from their declaration.
+//!
+//! The grammars' entity queries do not capture modifiers, so visibility is
+//! recovered from the entity's code with each language's convention:
+//! Rust `pub`, Go capitalization, Python's leading underscore, and the
+//! `private`/`protected` keywords elsewhere. pt02's `--exclude-private`
+//! filters on the result.
+//!
+//! Rust trait items and trait impl items take no modifier: they are as
+//! visible as the trait or impl, so their enclosing item decides.
+
+use parseltongue_core::entities::{Language, Visibility};
+
+use crate::isgl1_generator::{EntityType, ParsedEntity};
+
+/// Visibility of the entity named `name` whose code is `code`
+///
+/// `container` is the code of the enclosing `impl` or `trait` item, if
+/// any (see [`enclosing_item`]).
+pub fn infer_visibility(
+    language: Language,
+    entity_type: &EntityType,
+    name: &str,
+    code: &str,
+    container: Option<&str>,
+) -> Visibility {
+    let declaration = declaration_line(code);
+    match language {
+        // impl blocks have no modifier of their own
+        Language::Rust if *entity_type == EntityType::Impl => Visibility::Public,
+        Language::Rust => match container.map(declaration_line) {
+            Some(item) if declares(item, "trait") => rust_visibility(item),
+            // Trait impl items are as visible as the impl itself
+            Some(item) if declares(item, "impl") && declares(item, "for") => Visibility::Public,
+            _ => rust_visibility(declaration),
+        },
+        Language::Go => match name.chars().next() {
+            Some(first) if first.is_lowercase() => Visibility::Module,
+            _ => Visibility::Public,
+        },
+        Language::Python => {
+            let unqualified = name.rsplit('.').next().unwrap_or(name);
+            if unqualified.starts_with('_') && !unqualified.ends_with("__") {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        Language::C | Language::Cpp if declaration.starts_with("static ") => Visibility::Module,
+        Language::JavaScript | Language::TypeScript if name.starts_with('#') => Visibility::Private,
+        _ => keyword_visibility(declaration),
+    }
+}
+
+/// The innermost `impl` or `trait` item among `entities` whose lines
+/// enclose `entity`
+pub fn enclosing_item<'a>(entity: &ParsedEntity, entities: &'a [ParsedEntity]) -> Option<&'a ParsedEntity> {
+    let (start, end) = entity.line_range;
+    entities
+        .iter()
+        .filter(|item| matches!(item.entity_type, EntityType::Impl | EntityType::Trait))
+        .filter(|item| item.line_range != entity.line_range && item.line_range.0 <= start && end <= item.line_range.1)
+        .min_by_key(|item| item.line_range.1 - item.line_range.0)
+}
+
+/// Whether `declaration` has `keyword` before its body
+fn declares(declaration: &str, keyword: &str) -> bool {
+    declaration
+        .split_whitespace()
+        .take_while(|word| !word.starts_with('{'))
+        .any(|word| word == keyword)
+}
+
+/// First line of `code` that is not an attribute, decorator or comment
+fn declaration_line(code: &str) -> &str {
+    code.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !["//", "/*", "*", "#", "@"].iter().any(|p| line.starts_with(p)))
+        .unwrap_or("")
+}
+
+fn rust_visibility(declaration: &str) -> Visibility {
+    if declaration.starts_with("pub(crate)") {
+        Visibility::Crate
+    } else if declaration.starts_with("pub(") {
+        Visibility::Module
+    } else if declaration.starts_with("pub ") {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
+fn keyword_visibility(declaration: &str) -> Visibility {
+    let words: Vec<&str> = declaration.split_whitespace().collect();
+    if words.contains(&"private") || words.contains(&"fileprivate") {
+        Visibility::Private
+    } else if words.contains(&"protected") {
+        Visibility::Protected
+    } else if words.contains(&"internal") {
+        Visibility::Crate
+    } else {
+        Visibility::Public
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_modifiers() {
+        let visibility = |code| infer_visibility(Language::Rust, &EntityType::Function, "f", code, None);
+        assert_eq!(visibility("/// Docs\n#[inline]\npub fn f() {}"), Visibility::Public);
+        assert_eq!(visibility("pub(crate) fn f() {}"), Visibility::Crate);
+        assert_eq!(visibility("pub(super) fn f() {}"), Visibility::Module);
+        assert_eq!(visibility("fn f() {}"), Visibility::Private);
+        assert_eq!(
+            infer_visibility(Language::Rust, &EntityType::Impl, "Foo", "impl Foo {}", None),
+            Visibility::Public
+        );
+    }
+
+    #[test]
+    fn test_rust_trait_items_inherit_visibility() {
+        let method = |container| infer_visibility(Language::Rust, &EntityType::Method, "run", "fn run(&self) {}", Some(container));
+        assert_eq!(method("pub trait Task {\n    fn run(&self);\n}"), Visibility::Public);
+        assert_eq!(method("pub(crate) trait Task {}"), Visibility::Crate);
+        assert_eq!(method("impl<T: Clone> Task for Vec<T> {}"), Visibility::Public);
+        assert_eq!(method("impl Runner {}"), Visibility::Private, "inherent methods keep their own modifier");
+    }
+
+    #[test]
+    fn test_enclosing_item_is_innermost_impl_or_trait() {
+        let parsed = |entity_type, line_range| ParsedEntity {
+            entity_type,
+            name: "x".to_string(),
+            language: Language::Rust,
+            line_range,
+            file_path: "src/lib.rs".to_string(),
+            metadata: Default::default(),
+        };
+        let entities = [
+            parsed(EntityType::Module, (1, 20)),
+            parsed(EntityType::Impl, (2, 10)),
+            parsed(EntityType::Method, (3, 5)),
+        ];
+        assert_eq!(enclosing_item(&entities[2], &entities).map(|e| e.line_range), Some((2, 10)));
+        assert!(enclosing_item(&entities[1], &entities).is_none());
+    }
+
+    #[test]
+    fn test_naming_conventions() {
+        assert_eq!(infer_visibility(Language::Python, &EntityType::Function, "_helper", "def _helper(): pass", None), Visibility::Private);
+        assert_eq!(infer_visibility(Language::Python, &EntityType::Method, "__init__", "def __init__(self): pass", None), Visibility::Public);
+        assert_eq!(infer_visibility(Language::Go, &EntityType::Function, "helper", "func helper() {}", None), Visibility::Module);
+        assert_eq!(infer_visibility(Language::Go, &EntityType::Function, "Serve", "func Serve() {}", None), Visibility::Public);
+    }
+
+    #[test]
+    fn test_keyword_modifiers() {
+        let java = |code| infer_visibility(Language::Java, &EntityType::Method, "m", code, None);
+        assert_eq!(java("@Override\nprivate void m() {}"), Visibility::Private);
+        assert_eq!(java("protected void m() {}"), Visibility::Protected);
+        assert_eq!(java("public void m() {}"), Visibility::Public);
+    }
+}
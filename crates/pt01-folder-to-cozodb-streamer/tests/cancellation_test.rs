@@ -0,0 +1,92 @@
+//! Cancelling `stream_directory_cancellable`
+//!
+//! Cancellation is checked between files: the file being parsed when the token
+//! fires is stored completely, and nothing after it is touched.
+
+mod common;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use common::{default_streamer, rust_config, streamer_with};
+use parseltongue_core::entities::{DependencyEdge, Language};
+use pt01_folder_to_cozodb_streamer::{
+    streamer::FileStreamer, CancellationToken, Isgl1KeyGenerator, Isgl1KeyGeneratorFactory,
+    ParsedEntity, Result,
+};
+use tempfile::TempDir;
+
+/// Delegating generator that cancels the token as soon as a file is parsed
+struct CancelOnFirstParse {
+    inner: Arc<dyn Isgl1KeyGenerator>,
+    token: CancellationToken,
+}
+
+impl Isgl1KeyGenerator for CancelOnFirstParse {
+    fn generate_key(&self, entity: &ParsedEntity) -> Result<String> {
+        self.inner.generate_key(entity)
+    }
+
+    fn parse_source(
+        &self,
+        source: &str,
+        file_path: &Path,
+    ) -> Result<(Vec<ParsedEntity>, Vec<DependencyEdge>)> {
+        self.token.cancel();
+        self.inner.parse_source(source, file_path)
+    }
+
+    fn get_language_type(&self, file_path: &Path) -> Result<Language> {
+        self.inner.get_language_type(file_path)
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_after_first_file_keeps_exactly_that_file() {
+    let root = TempDir::new().unwrap();
+    for name in ["a", "b", "c"] {
+        std::fs::write(
+            root.path().join(format!("{}.rs", name)),
+            format!("pub fn {0}_one() {{}}\n\npub fn {0}_two() {{}}\n", name),
+        )
+        .unwrap();
+    }
+
+    let config = rust_config(root.path());
+    let token = CancellationToken::new();
+    let generator = Arc::new(CancelOnFirstParse {
+        inner: Isgl1KeyGeneratorFactory::new(),
+        token: token.clone(),
+    });
+    let streamer = streamer_with(config, generator).await;
+
+    let result = streamer.stream_directory_cancellable(Some(&token)).await.unwrap();
+
+    assert!(result.cancelled);
+    assert_eq!(result.processed_files, 1);
+    assert_eq!(result.entities_created, 2);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    assert_eq!(entities.len(), 2, "only the first file's entities are stored");
+    let files: std::collections::HashSet<_> =
+        entities.iter().map(|e| e.interface_signature.file_path.clone()).collect();
+    assert_eq!(files.len(), 1, "{:?}", files);
+}
+
+#[tokio::test]
+async fn test_uncancelled_run_is_not_marked_cancelled() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn only() {}\n").unwrap();
+
+    let config = rust_config(root.path());
+    let streamer = default_streamer(config).await;
+
+    let result = streamer
+        .stream_directory_cancellable(Some(&CancellationToken::new()))
+        .await
+        .unwrap();
+
+    assert!(!result.cancelled);
+    assert_eq!(result.processed_files, 1);
+}
@@ -0,0 +1,203 @@
+//! Resume-from-checkpoint ingestion
+//!
+//! An interrupted `stream_directory` run must leave a checkpoint behind so
+//! that the next run skips every fully processed file, and a clean run must
+//! remove it.
+
+mod common;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use common::{default_streamer, rust_config};
+use parseltongue_core::entities::{DependencyEdge, Language};
+use pt01_folder_to_cozodb_streamer::{
+    streamer::FileStreamer, FileStreamerImpl, IngestionCheckpoint, Isgl1KeyGenerator,
+    Isgl1KeyGeneratorFactory, ParsedEntity, Result, StreamerConfig,
+};
+use tempfile::TempDir;
+
+const FILE_COUNT: usize = 6;
+
+/// Delegating generator that counts parses per file and can "crash"
+/// (panic) before parsing the file after the first `crash_after` ones
+struct CountingGenerator {
+    inner: Arc<dyn Isgl1KeyGenerator>,
+    parses: Arc<Mutex<HashMap<PathBuf, usize>>>,
+    crash_after: Option<usize>,
+}
+
+impl Isgl1KeyGenerator for CountingGenerator {
+    fn generate_key(&self, entity: &ParsedEntity) -> Result<String> {
+        self.inner.generate_key(entity)
+    }
+
+    fn parse_source(
+        &self,
+        source: &str,
+        file_path: &Path,
+    ) -> Result<(Vec<ParsedEntity>, Vec<DependencyEdge>)> {
+        let parsed_so_far: usize = self.parses.lock().unwrap().values().sum();
+        if self.crash_after == Some(parsed_so_far) {
+            panic!("simulated interruption");
+        }
+        *self.parses.lock().unwrap().entry(file_path.to_path_buf()).or_default() += 1;
+        self.inner.parse_source(source, file_path)
+    }
+
+    fn get_language_type(&self, file_path: &Path) -> Result<Language> {
+        self.inner.get_language_type(file_path)
+    }
+}
+
+fn config_for(root: &Path, checkpoint: &Path) -> StreamerConfig {
+    StreamerConfig {
+        checkpoint_path: Some(checkpoint.to_path_buf()),
+        checkpoint_interval: 1,
+        ..rust_config(root)
+    }
+}
+
+async fn streamer_with(
+    config: StreamerConfig,
+    parses: &Arc<Mutex<HashMap<PathBuf, usize>>>,
+    crash_after: Option<usize>,
+) -> Arc<FileStreamerImpl> {
+    let generator = Arc::new(CountingGenerator {
+        inner: Isgl1KeyGeneratorFactory::new(),
+        parses: Arc::clone(parses),
+        crash_after,
+    });
+    Arc::new(common::streamer_with(config, generator).await)
+}
+
+#[tokio::test]
+async fn test_interrupted_run_resumes_without_reparsing() {
+    let source = TempDir::new().unwrap();
+    for i in 0..FILE_COUNT {
+        std::fs::write(
+            source.path().join(format!("module_{}.rs", i)),
+            format!("pub fn function_{}() -> usize {{\n    {}\n}}\n", i, i),
+        )
+        .unwrap();
+    }
+    let state = TempDir::new().unwrap();
+    let checkpoint_path = state.path().join("db.pt01-checkpoint.json");
+    let parses = Arc::new(Mutex::new(HashMap::new()));
+
+    // Run 1: interrupted after half the files
+    let interrupted = streamer_with(
+        config_for(source.path(), &checkpoint_path),
+        &parses,
+        Some(FILE_COUNT / 2),
+    )
+    .await;
+    let crash = tokio::spawn(async move { interrupted.stream_directory().await }).await;
+    assert!(crash.is_err(), "first run must be interrupted");
+
+    let checkpoint = IngestionCheckpoint::load(&checkpoint_path, source.path());
+    assert_eq!(checkpoint.len(), FILE_COUNT / 2, "completed files are checkpointed");
+
+    // Run 2: resumes and finishes the rest
+    let resumed = streamer_with(config_for(source.path(), &checkpoint_path), &parses, None).await;
+    let result = resumed.stream_directory().await.unwrap();
+
+    assert_eq!(result.total_files, FILE_COUNT);
+    assert_eq!(result.skipped_files, FILE_COUNT / 2);
+    assert_eq!(result.processed_files, FILE_COUNT - FILE_COUNT / 2);
+
+    let parses = parses.lock().unwrap();
+    assert_eq!(parses.len(), FILE_COUNT, "every file parsed");
+    assert!(
+        parses.values().all(|&count| count == 1),
+        "no file parsed twice: {:?}",
+        parses
+    );
+    assert!(!checkpoint_path.exists(), "clean completion removes the checkpoint");
+}
+
+#[tokio::test]
+async fn test_modified_and_no_resume_files_are_reprocessed() {
+    let source = TempDir::new().unwrap();
+    let touched = source.path().join("touched.rs");
+    let edited = source.path().join("edited.rs");
+    std::fs::write(&touched, "pub fn touched() {}\n").unwrap();
+    std::fs::write(&edited, "pub fn edited() {}\n").unwrap();
+
+    let state = TempDir::new().unwrap();
+    let checkpoint_path = state.path().join("db.pt01-checkpoint.json");
+
+    // Pretend an earlier run finished both files, with stale mtimes
+    let mut checkpoint = IngestionCheckpoint::new(source.path());
+    checkpoint.record(&touched, 1, "pub fn touched() {}\n");
+    checkpoint.record(&edited, 1, "pub fn edited_before() {}\n");
+    checkpoint.save(&checkpoint_path).unwrap();
+
+    let parses = Arc::new(Mutex::new(HashMap::new()));
+    let streamer = streamer_with(config_for(source.path(), &checkpoint_path), &parses, None).await;
+    let result = streamer.stream_directory().await.unwrap();
+
+    // Same content, new mtime → skipped via content hash; edited → reparsed
+    // (the generator sees the root-relative path)
+    assert_eq!(result.skipped_files, 1);
+    assert_eq!(parses.lock().unwrap().keys().collect::<Vec<_>>(), vec![&PathBuf::from("edited.rs")]);
+
+    // --no-resume ignores the checkpoint entirely
+    checkpoint.save(&checkpoint_path).unwrap();
+    let parses = Arc::new(Mutex::new(HashMap::new()));
+    let config = StreamerConfig {
+        resume: false,
+        ..config_for(source.path(), &checkpoint_path)
+    };
+    let result = streamer_with(config, &parses, None).await.stream_directory().await.unwrap();
+    assert_eq!(result.skipped_files, 0);
+    assert_eq!(parses.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_reformatted_file_moves_keys_but_keeps_modified_at() {
+    use chrono::TimeZone;
+    use parseltongue_core::clock::FixedClock;
+
+    let source = TempDir::new().unwrap();
+    let file = source.path().join("lib.rs");
+    let original = "pub fn add(a: i32, b: i32) -> i32 { a + b }\n";
+    std::fs::write(&file, original).unwrap();
+    let state = TempDir::new().unwrap();
+    let checkpoint_path = state.path().join("db.pt01-checkpoint.json");
+    let config = StreamerConfig {
+        db_path: format!("rocksdb:{}", state.path().join("db").display()),
+        ..config_for(source.path(), &checkpoint_path)
+    };
+    let day = |d: u32| chrono::Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap();
+    let streamer_at = |config: StreamerConfig, d: u32| async move {
+        default_streamer(config).await.with_clock(Arc::new(FixedClock(day(d))))
+    };
+
+    let first = streamer_at(config.clone(), 1).await;
+    first.stream_directory().await.unwrap();
+    let before = first.storage().get_all_entities().await.unwrap();
+    drop(first);
+
+    // The clean run removed the checkpoint; an earlier run recorded the file
+    let mut checkpoint = IngestionCheckpoint::new(source.path());
+    checkpoint.record(&file, 1, original);
+    checkpoint.save(&checkpoint_path).unwrap();
+    let reformatted = "\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+    std::fs::write(&file, reformatted).unwrap();
+
+    let second = streamer_at(config, 2).await;
+    let result = second.stream_directory().await.unwrap();
+    assert_eq!(result.skipped_files, 0, "reformatting is re-indexed, not skipped");
+
+    let after = second.storage().get_all_entities().await.unwrap();
+    assert_eq!(before.len(), 1);
+    assert_eq!(after.len(), 1, "the old key is gone: {:?}", after.iter().map(|e| &e.isgl1_key).collect::<Vec<_>>());
+    assert_ne!(after[0].isgl1_key, before[0].isgl1_key, "line range moved");
+    assert_eq!(after[0].interface_signature.line_range.start, 2);
+    assert_eq!(after[0].current_code.as_deref(), Some(reformatted.trim()));
+    assert_eq!(after[0].metadata.semantic_hash, before[0].metadata.semantic_hash);
+    assert_eq!(after[0].metadata.modified_at, day(1), "content not updated");
+    assert_eq!(after[0].metadata.created_at, day(1));
+}
@@ -0,0 +1,42 @@
+//! Pluggable chunking strategies
+//!
+//! A non-ISGL1 strategy decides what becomes an entity; `WholeFileChunking`
+//! must store exactly one entity per file however many functions it holds.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{rust_config, streamer_with};
+use pt01_folder_to_cozodb_streamer::{
+    streamer::FileStreamer, Isgl1KeyGeneratorImpl, StreamerConfig, WholeFileChunking,
+};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_whole_file_chunking_stores_one_entity_per_file() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("a.rs"), "fn one() {}\n\nfn two() {}\n\nstruct Three;\n").unwrap();
+    std::fs::write(root.path().join("b.rs"), "//! Module docs\n\npub fn four() -> u8 {\n    4\n}\n").unwrap();
+
+    let config = StreamerConfig {
+        chunking: "whole-file".to_string(),
+        ..rust_config(root.path())
+    };
+    let generator = Isgl1KeyGeneratorImpl::new().with_chunking(Arc::new(WholeFileChunking));
+    let streamer = streamer_with(config, Arc::new(generator)).await;
+
+    let result = streamer.stream_directory().await.unwrap();
+    assert_eq!(result.entities_created, 2, "{:?}", result.errors);
+
+    let mut entities = streamer.storage().get_all_entities().await.unwrap();
+    entities.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+    assert_eq!(entities.len(), 2);
+    for (entity, lines) in entities.iter().zip([5, 5]) {
+        assert_eq!(entity.interface_signature.name, "file");
+        assert_eq!(entity.interface_signature.line_range.start, 1);
+        assert_eq!(entity.interface_signature.line_range.end, lines);
+    }
+    assert!(entities[0].isgl1_key.contains("a_rs"), "{}", entities[0].isgl1_key);
+    assert!(entities[0].current_code.as_deref().unwrap().contains("struct Three;"));
+}
@@ -0,0 +1,48 @@
+//! Fixtures shared by the pt01 integration tests
+//!
+//! Every test binary compiles its own copy of this module and uses only
+//! part of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::Arc;
+
+use pt01_folder_to_cozodb_streamer::{
+    streamer::FileStreamer, DefaultTestDetector, FileStreamerImpl, Isgl1KeyGenerator,
+    Isgl1KeyGeneratorFactory, StreamResult, StreamerConfig, ToolFactory,
+};
+
+/// In-memory database over the `*.rs` files under `root`; everything else
+/// is the default
+pub fn rust_config(root: &Path) -> StreamerConfig {
+    StreamerConfig {
+        root_dir: root.to_path_buf(),
+        db_path: "mem".to_string(),
+        include_patterns: vec!["*.rs".to_string()],
+        exclude_patterns: vec![],
+        ..Default::default()
+    }
+}
+
+/// Streamer parsing through `generator`, with the default test detector
+pub async fn streamer_with(config: StreamerConfig, generator: Arc<dyn Isgl1KeyGenerator>) -> FileStreamerImpl {
+    FileStreamerImpl::new(config, generator, Arc::new(DefaultTestDetector::new()))
+        .await
+        .unwrap()
+}
+
+/// Streamer with the default generator and test detector
+///
+/// Unlike [`ingest_with`], the generator ignores the config's key format,
+/// chunking and query options.
+pub async fn default_streamer(config: StreamerConfig) -> FileStreamerImpl {
+    streamer_with(config, Isgl1KeyGeneratorFactory::new()).await
+}
+
+/// Build the `ToolFactory` streamer for `config` and ingest its root
+pub async fn ingest_with(config: StreamerConfig) -> (Arc<FileStreamerImpl>, StreamResult) {
+    let streamer = ToolFactory::create_streamer(config).await.unwrap();
+    let result = streamer.stream_directory().await.unwrap();
+    (streamer, result)
+}
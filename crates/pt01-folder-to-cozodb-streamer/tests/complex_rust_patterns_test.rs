@@ -359,6 +359,7 @@ async fn extract_dependencies(source: &str) -> Vec<DependencyEdge> {
         exclude_patterns: vec![],
         parsing_library: "tree-sitter".to_string(),
         chunking: "ISGL1".to_string(),
+        ..Default::default()
     };
 
     {
@@ -383,11 +384,7 @@ fn find_call_source(deps: &[DependencyEdge], target: &str) -> Option<String> {
 fn print_dependencies(deps: &[DependencyEdge]) {
     println!("Dependencies ({})", deps.len());
     for (i, dep) in deps.iter().enumerate() {
-        let edge_type = match dep.edge_type {
-            parseltongue_core::entities::EdgeType::Calls => "Calls",
-            parseltongue_core::entities::EdgeType::Uses => "Uses",
-            parseltongue_core::entities::EdgeType::Implements => "Implements",
-        };
+        let edge_type = dep.edge_type.as_str();
         println!("  {}. {} -> {} ({})",
             i + 1,
             dep.from_key.as_str().split(':').nth(2).unwrap_or("?"),
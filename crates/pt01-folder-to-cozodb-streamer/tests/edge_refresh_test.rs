@@ -0,0 +1,58 @@
+//! Edge recomputation after an edit (`edge_refresh`, pt03 `--update-edges`)
+//!
+//! Re-extracting from the future_code replaces the entity's outgoing edges:
+//! a call the edit adds appears and one it removes disappears.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use parseltongue_core::entities::EdgeType;
+use pt01_folder_to_cozodb_streamer::{refresh_entity_edges, Isgl1KeyGeneratorImpl};
+use tempfile::TempDir;
+
+const SOURCE: &str = "pub fn entry() -> u32 {
+    old_target()
+}
+
+fn old_target() -> u32 {
+    1
+}
+
+fn new_target() -> u32 {
+    2
+}
+";
+
+#[tokio::test]
+async fn test_edit_adding_a_call_gets_a_new_calls_edge() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), SOURCE).unwrap();
+    let config = rust_config(root.path());
+    let (streamer, _) = ingest_with(config).await;
+    let storage = streamer.storage();
+
+    let mut entry = storage
+        .get_all_entities()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|e| e.interface_signature.name == "entry")
+        .unwrap();
+    let calls_from_entry = |edges: Vec<parseltongue_core::entities::DependencyEdge>, key: &str| {
+        edges
+            .into_iter()
+            .filter(|e| e.from_key.as_ref() == key && e.edge_type == EdgeType::Calls)
+            .map(|e| e.to_key.as_ref().to_string())
+            .collect::<Vec<_>>()
+    };
+    let before = calls_from_entry(storage.get_all_dependencies().await.unwrap(), &entry.isgl1_key);
+    assert!(before.iter().any(|to| to.contains("old_target")), "{:?}", before);
+
+    entry.future_code = Some("pub fn entry() -> u32 {\n    new_target() + 1\n}".to_string());
+    let edges = refresh_entity_edges(storage, &Isgl1KeyGeneratorImpl::new(), &entry).await.unwrap();
+    assert!(edges.iter().all(|e| e.from_key.as_ref() == entry.isgl1_key));
+
+    let after = calls_from_entry(storage.get_all_dependencies().await.unwrap(), &entry.isgl1_key);
+    assert!(after.iter().any(|to| to.contains("new_target")), "{:?}", after);
+    assert!(!after.iter().any(|to| to.contains("old_target")), "{:?}", after);
+}
@@ -0,0 +1,112 @@
+//! Entity transforms
+//!
+//! Transforms registered on the streamer edit each entity before it is
+//! stored; their changes must be what lands in the database.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{default_streamer, rust_config};
+use parseltongue_core::entities::{CodeEntity, ComplexityLevel, RiskLevel};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, ComplexityTagger, EntityTransform};
+use tempfile::TempDir;
+
+/// Flags functions that handle credentials
+struct SecurityTagger;
+
+impl EntityTransform for SecurityTagger {
+    fn transform(&self, entity: &mut CodeEntity) {
+        if entity.interface_signature.name.contains("password") {
+            entity.tdd_classification.critical_path = true;
+            entity.tdd_classification.change_risk = RiskLevel::High;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_transforms_run_before_entities_are_stored() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(
+        root.path().join("lib.rs"),
+        "pub fn check_password(input: &str, stored: &str) -> bool {\n    \
+             if input.is_empty() || stored.is_empty() {\n        return false;\n    }\n    \
+             for (a, b) in input.bytes().zip(stored.bytes()) {\n        if a != b && a != 0 {\n            return false;\n        }\n    }\n    \
+             input.len() == stored.len()\n}\n\n\
+         pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )
+    .unwrap();
+    let config = rust_config(root.path());
+    let streamer = default_streamer(config).await.with_transforms(vec![
+        Box::new(ComplexityTagger::new().with_thresholds(3, 10)),
+        Box::new(SecurityTagger),
+    ]);
+
+    streamer.stream_directory().await.unwrap();
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    let by_name = |name: &str| {
+        entities
+            .iter()
+            .find(|e| e.interface_signature.name == name)
+            .unwrap_or_else(|| panic!("{} not stored", name))
+    };
+    let check = &by_name("check_password").tdd_classification;
+    assert!(check.critical_path);
+    assert_eq!(check.change_risk, RiskLevel::High);
+    assert_eq!(check.complexity, ComplexityLevel::Moderate);
+
+    let add = &by_name("add").tdd_classification;
+    assert!(!add.critical_path);
+    assert_eq!(add.complexity, ComplexityLevel::Simple);
+}
+
+/// Records the complexity score each entity reaches the transforms with
+#[derive(Clone, Default)]
+struct ScoreRecorder(Arc<Mutex<Vec<(String, Option<u32>)>>>);
+
+impl EntityTransform for ScoreRecorder {
+    fn transform(&self, entity: &mut CodeEntity) {
+        let name = entity.interface_signature.name.clone();
+        self.0.lock().unwrap().push((name, entity.metadata.complexity_score));
+    }
+}
+
+#[tokio::test]
+async fn test_streamer_scores_complexity_from_the_syntax_tree() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(
+        root.path().join("lib.rs"),
+        "pub fn sign(x: i32) -> i32 {\n    \
+             if x > 0 {\n        1\n    } else if x < 0 {\n        -1\n    } else if x == 0 {\n        0\n    } else {\n        2\n    }\n}\n\n\
+         pub fn busy(a: bool, b: bool) -> u8 {\n    \
+             // if while for match: comments are not branches\n    \
+             let mut n = 0;\n    \
+             if a { n += 1; }\n    if b { n += 1; }\n    while n > 9 { n -= 1; }\n    \
+             for _ in 0..2 { if a && b { n += 1; } }\n    \
+             n\n}\n",
+    )
+    .unwrap();
+    let config = rust_config(root.path());
+    let recorder = ScoreRecorder::default();
+    let streamer = default_streamer(config).await.with_transforms(vec![Box::new(recorder.clone())]);
+
+    streamer.stream_directory().await.unwrap();
+
+    let mut scores = recorder.0.lock().unwrap().clone();
+    scores.sort();
+    assert_eq!(
+        scores,
+        vec![("busy".to_string(), Some(7)), ("sign".to_string(), Some(4))]
+    );
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    let level = |name: &str| {
+        entities
+            .iter()
+            .find(|e| e.interface_signature.name == name)
+            .map(|e| e.tdd_classification.complexity.clone())
+    };
+    assert_eq!(level("sign"), Some(ComplexityLevel::Simple));
+    assert_eq!(level("busy"), Some(ComplexityLevel::Moderate));
+}
@@ -0,0 +1,57 @@
+//! User-supplied tree-sitter queries
+//!
+//! `StreamerConfig.extra_queries` adds entity kinds the built-in queries do
+//! not extract; a query that does not compile fails streamer construction.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{ingest_with, rust_config};
+use parseltongue_core::entities::{EntityType, Language};
+use pt01_folder_to_cozodb_streamer::{StreamerConfig, ToolFactory};
+use tempfile::TempDir;
+
+const MACRO_QUERY: &str = "(macro_definition name: (identifier) @name) @definition.macro";
+
+fn config(root: &TempDir, query: &str) -> StreamerConfig {
+    StreamerConfig {
+        extra_queries: HashMap::from([(Language::Rust, vec![query.to_string()])]),
+        ..rust_config(root.path())
+    }
+}
+
+#[tokio::test]
+async fn test_macro_rules_query_produces_macro_entities() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(
+        root.path().join("lib.rs"),
+        "macro_rules! my_macro {\n    () => {};\n}\n\npub fn run() {}\n",
+    )
+    .unwrap();
+
+    let (streamer, _) = ingest_with(config(&root, MACRO_QUERY)).await;
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    let mac = entities
+        .iter()
+        .find(|e| e.interface_signature.name == "my_macro")
+        .expect("macro_rules! definition is extracted");
+    assert_eq!(mac.interface_signature.entity_type, EntityType::Macro);
+    assert!(mac.isgl1_key.contains(":macro:my_macro:"), "{}", mac.isgl1_key);
+    assert_eq!(mac.interface_signature.line_range.start, 1);
+    assert_eq!(mac.interface_signature.line_range.end, 3);
+    // Built-in extraction is unaffected
+    assert!(entities.iter().any(|e| e.interface_signature.name == "run"));
+}
+
+#[tokio::test]
+async fn test_invalid_query_fails_streamer_construction() {
+    let root = TempDir::new().unwrap();
+
+    let err = ToolFactory::create_streamer(config(&root, "(macro_definition name: (no_such_node) @name) @definition.macro"))
+        .await
+        .err()
+        .expect("query is validated against the grammar");
+    assert!(err.to_string().contains("extra_queries"), "{}", err);
+}
@@ -0,0 +1,62 @@
+//! Generated code flagging (`StreamerConfig.generated_markers`)
+//!
+//! Entities of files with a generated header or a generated path are
+//! ingested like any other and recorded in `GeneratedEntities`.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamerConfig, ToolFactory};
+use tempfile::TempDir;
+
+const GENERATED: &str = "// @generated by build.rs - do not edit
+
+pub fn generated_table() -> u8 {
+    42
+}
+";
+
+const HANDWRITTEN: &str = "pub fn handwritten() -> u8 {
+    7
+}
+";
+
+#[tokio::test]
+async fn test_generated_header_flags_entities() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("table.rs"), GENERATED).unwrap();
+    std::fs::write(root.path().join("lib.rs"), HANDWRITTEN).unwrap();
+    let config = rust_config(root.path());
+
+    let (streamer, _) = ingest_with(config).await;
+
+    let storage = streamer.storage();
+    let entities = storage.get_all_entities().await.unwrap();
+    let key_of = |name: &str| {
+        entities
+            .iter()
+            .find(|e| e.interface_signature.name == name)
+            .map(|e| e.isgl1_key.clone())
+            .unwrap_or_else(|| panic!("{} is ingested", name))
+    };
+
+    let generated = storage.get_generated_keys().await.unwrap();
+    assert!(generated.contains(&key_of("generated_table")), "{:?}", generated);
+    assert!(!generated.contains(&key_of("handwritten")), "{:?}", generated);
+}
+
+#[tokio::test]
+async fn test_generated_flags_cleared_when_header_removed() {
+    let root = TempDir::new().unwrap();
+    let file = root.path().join("table.rs");
+    std::fs::write(&file, GENERATED).unwrap();
+    let config = rust_config(root.path());
+
+    let streamer = ToolFactory::create_streamer(config).await.unwrap();
+    streamer.stream_file(&file).await.unwrap();
+    assert_eq!(streamer.storage().get_generated_keys().await.unwrap().len(), 1);
+
+    std::fs::write(&file, GENERATED.replacen("// @generated by build.rs - do not edit\n", "", 1)).unwrap();
+    streamer.stream_file(&file).await.unwrap();
+    assert!(streamer.storage().get_generated_keys().await.unwrap().is_empty());
+}
@@ -0,0 +1,70 @@
+//! `git_diff_base` limits ingestion to the files a branch changed
+//!
+//! Built on a throwaway two-commit repository; requires `git` on `PATH`.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamerConfig, StreamerError, ToolFactory};
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+fn scoped_config(root: &Path, base: &str) -> StreamerConfig {
+    StreamerConfig {
+        git_diff_base: Some(base.to_string()),
+        ..rust_config(root)
+    }
+}
+
+#[tokio::test]
+async fn test_only_files_changed_since_base_are_ingested() {
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/untouched.rs"), "pub fn untouched() {}\n").unwrap();
+    std::fs::write(root.path().join("src/changed.rs"), "pub fn before() {}\n").unwrap();
+    git(root.path(), &["init", "-q"]);
+    git(root.path(), &["add", "."]);
+    git(root.path(), &["commit", "-q", "-m", "base"]);
+    git(root.path(), &["tag", "base"]);
+
+    std::fs::write(root.path().join("src/changed.rs"), "pub fn before() {}\npub fn after() {}\n").unwrap();
+    std::fs::write(root.path().join("notes.md"), "not code\n").unwrap();
+    git(root.path(), &["add", "."]);
+    git(root.path(), &["commit", "-q", "-m", "change"]);
+
+    let (_, result) = ingest_with(scoped_config(root.path(), "base")).await;
+
+    // notes.md changed too but is outside the include patterns
+    assert_eq!((result.total_files, result.processed_files), (1, 1), "{:?}", result.errors);
+    assert_eq!(result.entities_created, 2);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+}
+
+#[tokio::test]
+async fn test_non_git_directory_is_a_configuration_error() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn f() {}\n").unwrap();
+
+    let streamer = ToolFactory::create_streamer(scoped_config(root.path(), "main")).await.unwrap();
+    let err = streamer.stream_directory().await.unwrap_err();
+
+    assert!(
+        matches!(&err, StreamerError::ConfigurationError { field, .. } if field == "git_diff_base"),
+        "{:?}",
+        err
+    );
+    assert!(err.to_string().contains("not a git repository"), "{}", err);
+}
@@ -0,0 +1,66 @@
+//! Guarded reads: size limit enforced mid-read, special files skipped
+//!
+//! A file whose metadata passes `max_file_size` but whose content does not
+//! is abandoned once the limit is passed, and FIFOs are reported without
+//! ever being opened (opening one would block until a writer appears).
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamerConfig};
+use tempfile::TempDir;
+
+const LIMIT: usize = 64;
+
+fn config_for(root: &TempDir) -> StreamerConfig {
+    StreamerConfig {
+        max_file_size: LIMIT,
+        ..rust_config(root.path())
+    }
+}
+
+/// `/proc/self/maps` reports a size of 0 but reads as several kilobytes
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_file_exceeding_limit_mid_read_is_aborted_and_reported() {
+    let root = TempDir::new().unwrap();
+    std::os::unix::fs::symlink("/proc/self/maps", root.path().join("maps.rs")).unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn small() {}\n").unwrap();
+    assert_eq!(std::fs::metadata(root.path().join("maps.rs")).unwrap().len(), 0);
+
+    let (_, result) = ingest_with(config_for(&root)).await;
+
+    assert_eq!(result.processed_files, 1, "{:?}", result.errors);
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    let report = &result.errors[0];
+    assert!(report.contains("maps.rs"), "{}", report);
+    assert!(
+        report.contains(&format!("skipped: exceeded max_file_size while reading (> {} bytes)", LIMIT)),
+        "{}",
+        report
+    );
+}
+
+#[tokio::test]
+async fn test_fifo_is_skipped_and_reported() {
+    let root = TempDir::new().unwrap();
+    let fifo = root.path().join("pipe.rs");
+    let made = std::process::Command::new("mkfifo").arg(&fifo).status();
+    if !made.is_ok_and(|status| status.success()) {
+        eprintln!("mkfifo unavailable; skipping");
+        return;
+    }
+    std::fs::write(root.path().join("lib.rs"), "pub fn small() {}\n").unwrap();
+
+    let (streamer, result) = ingest_with(config_for(&root)).await;
+
+    assert_eq!(result.processed_files, 1, "{:?}", result.errors);
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    let report = &result.errors[0];
+    assert!(report.contains("pipe.rs: skipped: not a regular file (FIFO)"), "{}", report);
+
+    let direct = streamer.stream_file(&fifo).await;
+    assert!(direct.is_err(), "stream_file refuses a FIFO too");
+}
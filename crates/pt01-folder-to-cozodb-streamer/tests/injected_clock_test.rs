@@ -0,0 +1,30 @@
+//! Injected clock
+//!
+//! Entities are timestamped by the streamer's `Clock`; with a `FixedClock`
+//! the stored timestamps are the injected instant, run after run.
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use common::{default_streamer, rust_config};
+use parseltongue_core::clock::FixedClock;
+use pt01_folder_to_cozodb_streamer::streamer::FileStreamer;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_entities_carry_the_injected_timestamp() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+    let config = rust_config(root.path());
+    let instant = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    let streamer = default_streamer(config).await.with_clock(Arc::new(FixedClock(instant)));
+
+    streamer.stream_directory().await.unwrap();
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].metadata.created_at, instant);
+    assert_eq!(entities[0].metadata.modified_at, instant);
+}
@@ -0,0 +1,69 @@
+//! Oversized files are reported, not silently dropped
+//!
+//! A file over `max_file_size` must show up in `StreamResult.errors` with its
+//! size; a file at or under the limit is processed normally. Under
+//! `--fail-on-warnings` the skip fails the run.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamerConfig, ToolFactory};
+use tempfile::TempDir;
+
+const LIMIT: usize = 256;
+
+/// Rust source padded with a trailing comment to exactly `len` bytes
+fn source_of_len(name: &str, len: usize) -> String {
+    let mut source = format!("pub fn {}() {{}}\n//", name);
+    source.push_str(&"x".repeat(len - source.len() - 1));
+    source.push('\n');
+    assert_eq!(source.len(), len);
+    source
+}
+
+#[tokio::test]
+async fn test_file_over_limit_is_reported_and_under_limit_processed() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("just_under.rs"), source_of_len("just_under", LIMIT)).unwrap();
+    std::fs::write(root.path().join("just_over.rs"), source_of_len("just_over", LIMIT + 1)).unwrap();
+
+    let config = StreamerConfig {
+        max_file_size: LIMIT,
+        ..rust_config(root.path())
+    };
+    let (_, result) = ingest_with(config).await;
+
+    assert_eq!(result.total_files, 2);
+    assert_eq!(result.processed_files, 1, "the file at the limit is processed");
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+
+    let report = &result.errors[0];
+    assert!(report.contains("just_over.rs"), "{}", report);
+    assert!(report.contains("skipped: exceeds max_file_size"), "{}", report);
+    assert!(report.contains(&format!("{} bytes > {} bytes", LIMIT + 1, LIMIT)), "{}", report);
+}
+
+#[tokio::test]
+async fn test_skipped_file_fails_under_fail_on_warnings() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("small.rs"), source_of_len("small", LIMIT)).unwrap();
+
+    let config = StreamerConfig {
+        max_file_size: LIMIT,
+        ..rust_config(root.path())
+    };
+    let streamer = ToolFactory::create_streamer(config.clone()).await.unwrap();
+    assert!(streamer.stream_directory().await.unwrap().fail_on_warnings().is_ok());
+
+    std::fs::write(root.path().join("big.rs"), source_of_len("big", LIMIT + 1)).unwrap();
+    let (streamer, result) = ingest_with(config).await;
+    assert_eq!(result.processed_files, 1, "the run itself still succeeds");
+
+    let strict = result.fail_on_warnings().unwrap_err();
+    assert!(
+        matches!(strict, pt01_folder_to_cozodb_streamer::StreamerError::WarningsPresent { count: 1, .. }),
+        "{}",
+        strict
+    );
+    assert!(strict.to_string().contains("big.rs"), "{}", strict);
+}
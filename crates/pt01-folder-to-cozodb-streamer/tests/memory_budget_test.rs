@@ -0,0 +1,45 @@
+//! Memory budget for ingestion
+//!
+//! A file whose estimated parse memory exceeds the budget is skipped and
+//! reported.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{estimate_memory_usage, MemoryBudget, StreamerConfig, StreamerError};
+use tempfile::TempDir;
+
+#[test]
+fn test_estimate_larger_than_budget_is_rejected() {
+    let budget = MemoryBudget::new(1024);
+    let err = budget.check("huge.rs", 1025).unwrap_err();
+    assert!(
+        matches!(err, StreamerError::ExceedsMemoryBudget { estimate: 1025, budget: 1024, .. }),
+        "{}",
+        err
+    );
+    assert!(budget.check("fits.rs", 1024).is_ok());
+}
+
+#[tokio::test]
+async fn test_streamer_skips_files_over_memory_budget() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("small.rs"), "pub fn small() {}\n").unwrap();
+    let big = format!("pub fn big() {{}}\n//{}\n", "x".repeat(2000));
+    std::fs::write(root.path().join("big.rs"), &big).unwrap();
+
+    let budget = 4096;
+    assert!(estimate_memory_usage(big.len()) > budget);
+    let config = StreamerConfig {
+        memory_budget_bytes: Some(budget),
+        ..rust_config(root.path())
+    };
+    let (_, result) = ingest_with(config).await;
+
+    assert_eq!(result.total_files, 2);
+    assert_eq!(result.processed_files, 1, "the file within budget is processed");
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    let report = &result.errors[0];
+    assert!(report.contains("big.rs"), "{}", report);
+    assert!(report.contains("skipped: exceeds memory budget"), "{}", report);
+}
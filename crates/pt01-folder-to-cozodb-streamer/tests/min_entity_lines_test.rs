@@ -0,0 +1,57 @@
+//! Minimum entity size (`StreamerConfig.min_entity_lines`)
+//!
+//! Entities spanning fewer lines than the threshold are skipped and counted,
+//! or stored without code when `keep_signatures_of_filtered` is set.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamerConfig};
+use tempfile::TempDir;
+
+const SOURCE: &str = "pub fn tiny() -> u8 { 1 }
+
+pub fn substantial(x: u8) -> u8 {
+    let doubled = x * 2;
+    let shifted = doubled + 1;
+    shifted
+}
+";
+
+async fn ingest(keep_signatures_of_filtered: bool) -> (Vec<parseltongue_core::entities::CodeEntity>, usize) {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), SOURCE).unwrap();
+    let config = StreamerConfig {
+        min_entity_lines: 3,
+        keep_signatures_of_filtered,
+        ..rust_config(root.path())
+    };
+
+    let (streamer, _) = ingest_with(config).await;
+
+    let entities = streamer.storage().get_all_entities().await.unwrap();
+    (entities, streamer.get_stats().small_entities_filtered)
+}
+
+#[tokio::test]
+async fn test_one_line_function_skipped_five_line_function_kept() {
+    let (entities, filtered) = ingest(false).await;
+
+    let names: Vec<_> = entities.iter().map(|e| e.interface_signature.name.as_str()).collect();
+    assert_eq!(names, vec!["substantial"]);
+    assert_eq!(filtered, 1);
+}
+
+#[tokio::test]
+async fn test_filtered_entity_keeps_signature_without_code() {
+    let (entities, filtered) = ingest(true).await;
+
+    let tiny = entities
+        .iter()
+        .find(|e| e.interface_signature.name == "tiny")
+        .expect("signature of the filtered entity is stored");
+    assert_eq!(tiny.current_code, None);
+    let substantial = entities.iter().find(|e| e.interface_signature.name == "substantial").unwrap();
+    assert!(substantial.current_code.as_deref().unwrap().contains("doubled"));
+    assert_eq!(filtered, 1);
+}
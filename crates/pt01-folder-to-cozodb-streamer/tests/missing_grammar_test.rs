@@ -0,0 +1,33 @@
+//! Code files in a language without a compiled-in grammar are named as such
+//!
+//! Kotlin has no grammar in this build, so a `.kt` file must be reported as
+//! "language not supported in this build: kotlin", not as an unknown file.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use parseltongue_core::entities::Language;
+use pt01_folder_to_cozodb_streamer::{available_languages, StreamerConfig};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_missing_grammar_is_reported_by_language() {
+    assert!(!available_languages().contains(&Language::Kotlin));
+
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn parsed() {}\n").unwrap();
+    std::fs::write(root.path().join("Main.kt"), "fun main() {}\n").unwrap();
+
+    let config = StreamerConfig {
+        include_patterns: vec!["*.rs".to_string(), "*.kt".to_string()],
+        ..rust_config(root.path())
+    };
+    let (_, result) = ingest_with(config).await;
+
+    assert_eq!((result.total_files, result.processed_files), (2, 1));
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+
+    let report = &result.errors[0];
+    assert!(report.contains("Main.kt"), "{}", report);
+    assert!(report.ends_with("language not supported in this build: kotlin"), "{}", report);
+}
@@ -0,0 +1,35 @@
+//! Syntax errors survive ingestion as `ParseErrors` rows
+//!
+//! tree-sitter recovers from the error and entities are still extracted;
+//! the problem itself must be queryable afterwards without re-parsing.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::streamer::FileStreamer;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_syntax_error_is_recorded_and_cleared_on_fix() {
+    let root = TempDir::new().unwrap();
+    let broken = root.path().join("broken.rs");
+    std::fs::write(root.path().join("clean.rs"), "pub fn clean() {}\n").unwrap();
+    std::fs::write(&broken, "pub fn fine() {}\n\npub fn broken(x: i32 {\n    x\n}\n").unwrap();
+
+    let config = rust_config(root.path());
+    let (streamer, result) = ingest_with(config).await;
+    assert_eq!(result.processed_files, 2, "{:?}", result.errors);
+
+    let parse_errors = streamer.storage().get_parse_errors().await.unwrap();
+    assert!(!parse_errors.is_empty());
+    assert!(
+        parse_errors.iter().all(|e| e.file_path.ends_with("broken.rs")),
+        "clean.rs has no syntax errors: {:?}",
+        parse_errors
+    );
+    assert!(parse_errors.iter().any(|e| e.line == 3), "{:?}", parse_errors);
+
+    std::fs::write(&broken, "pub fn fine() {}\n\npub fn broken(x: i32) -> i32 {\n    x\n}\n").unwrap();
+    streamer.stream_file(&broken).await.unwrap();
+    assert_eq!(streamer.storage().get_parse_errors().await.unwrap(), vec![]);
+}
@@ -0,0 +1,64 @@
+//! Path canonicalization
+//!
+//! Keys embed the file path, so ingesting one repository through different
+//! spellings of its root must produce the same keys.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use common::{default_streamer, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, PathStyle, StreamerConfig};
+use tempfile::TempDir;
+
+async fn ingest(root_dir: &Path, path_style: PathStyle) -> Vec<(String, PathBuf)> {
+    let config = StreamerConfig {
+        path_style,
+        ..rust_config(root_dir)
+    };
+    let streamer = default_streamer(config).await;
+    streamer.stream_directory().await.unwrap();
+
+    let mut stored: Vec<_> = streamer
+        .storage()
+        .get_all_entities()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.isgl1_key, e.interface_signature.file_path))
+        .collect();
+    stored.sort();
+    stored
+}
+
+fn repo() -> TempDir {
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir_all(root.path().join("src/util")).unwrap();
+    std::fs::write(root.path().join("src/lib.rs"), "pub fn run() {\n    helper();\n}\n").unwrap();
+    std::fs::write(root.path().join("src/util/mod.rs"), "pub fn helper() {}\n").unwrap();
+    root
+}
+
+#[tokio::test]
+async fn test_relative_and_absolute_roots_produce_identical_keys() {
+    let root = repo();
+    let absolute = ingest(root.path(), PathStyle::Relative).await;
+    let dotted = ingest(&root.path().join("src/../."), PathStyle::Relative).await;
+
+    assert_eq!(absolute, dotted);
+    let mut paths: Vec<_> = absolute.iter().map(|(_, path)| path.clone()).collect();
+    paths.sort();
+    paths.dedup();
+    assert_eq!(paths, vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/util/mod.rs")]);
+    assert!(absolute.iter().any(|(key, _)| key.contains("src_util_mod_rs")), "{:?}", absolute);
+}
+
+#[tokio::test]
+async fn test_absolute_style_stores_resolved_paths() {
+    let root = repo();
+    let stored = ingest(&root.path().join("."), PathStyle::Absolute).await;
+
+    let expected = std::fs::canonicalize(root.path().join("src/lib.rs")).unwrap();
+    assert!(stored.iter().any(|(_, path)| *path == expected), "{:?}", stored);
+    assert!(stored.iter().all(|(_, path)| path.is_absolute()));
+}
@@ -0,0 +1,45 @@
+//! Per-phase ingestion timing (`StreamerConfig.profile`)
+//!
+//! With profiling on, `StreamResult.phase_timings` holds a non-zero
+//! duration for every phase; with it off the map is empty.
+
+mod common;
+
+use common::{ingest_with, rust_config};
+use pt01_folder_to_cozodb_streamer::profile::PHASES;
+use pt01_folder_to_cozodb_streamer::StreamerConfig;
+use tempfile::TempDir;
+
+async fn ingest(profile: bool) -> pt01_folder_to_cozodb_streamer::StreamResult {
+    let root = TempDir::new().unwrap();
+    std::fs::write(
+        root.path().join("lib.rs"),
+        "pub fn add(a: i32, b: i32) -> i32 {\n    helper(a) + b\n}\n\nfn helper(x: i32) -> i32 {\n    x\n}\n",
+    )
+    .unwrap();
+    let config = StreamerConfig {
+        profile,
+        ..rust_config(root.path())
+    };
+
+    ingest_with(config).await.1
+}
+
+#[tokio::test]
+async fn test_profile_records_every_phase() {
+    let result = ingest(true).await;
+
+    assert_eq!(result.entities_created, 2);
+    for phase in PHASES {
+        let elapsed = result.phase_timings.get(phase).copied().unwrap_or_default();
+        assert!(!elapsed.is_zero(), "no time recorded for {}: {:?}", phase, result.phase_timings);
+    }
+    assert_eq!(result.phase_timings.len(), PHASES.len());
+}
+
+#[tokio::test]
+async fn test_phase_timings_empty_without_profile() {
+    let result = ingest(false).await;
+
+    assert!(result.phase_timings.is_empty());
+}
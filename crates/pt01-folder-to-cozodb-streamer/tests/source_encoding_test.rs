@@ -0,0 +1,96 @@
+//! Non-UTF-8 source files
+//!
+//! UTF-16 and Latin-1 files must reach the parser as correctly decoded UTF-8
+//! text; bytes that cannot be decoded are reported, never parsed.
+
+mod common;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use common::{rust_config, streamer_with};
+use parseltongue_core::entities::{DependencyEdge, Language};
+use pt01_folder_to_cozodb_streamer::{
+    encoding::SOURCE_ENCODING_KEY, streamer::FileStreamer, Isgl1KeyGenerator,
+    Isgl1KeyGeneratorFactory, ParsedEntity, Result,
+};
+use tempfile::TempDir;
+
+/// Delegating generator that records the text each file was parsed from
+struct RecordingGenerator {
+    inner: Arc<dyn Isgl1KeyGenerator>,
+    sources: Arc<Mutex<HashMap<PathBuf, String>>>,
+}
+
+impl Isgl1KeyGenerator for RecordingGenerator {
+    fn generate_key(&self, entity: &ParsedEntity) -> Result<String> {
+        self.inner.generate_key(entity)
+    }
+
+    fn parse_source(
+        &self,
+        source: &str,
+        file_path: &Path,
+    ) -> Result<(Vec<ParsedEntity>, Vec<DependencyEdge>)> {
+        self.sources.lock().unwrap().insert(file_path.to_path_buf(), source.to_string());
+        self.inner.parse_source(source, file_path)
+    }
+
+    fn get_language_type(&self, file_path: &Path) -> Result<Language> {
+        self.inner.get_language_type(file_path)
+    }
+}
+
+#[tokio::test]
+async fn test_utf16le_with_bom_rust_file_ingests() {
+    let root = TempDir::new().unwrap();
+    let source = "pub fn greet() -> &'static str {\n    \"héllo wörld\"\n}\n";
+
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend(source.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    let utf16_path = root.path().join("greet.rs");
+    std::fs::write(&utf16_path, utf16).unwrap();
+
+    // "café" in Latin-1 (0xE9), not valid UTF-8
+    let latin1_path = root.path().join("cafe.rs");
+    std::fs::write(&latin1_path, b"pub fn cafe() -> &'static str {\n    \"caf\xE9\"\n}\n").unwrap();
+
+    // Invalid UTF-8 with NULs in no UTF-16 pattern
+    std::fs::write(root.path().join("blob.rs"), [0x7F, b'E', b'L', b'F', 0x02, 0x00, 0x00, 0xFF, 0x00])
+        .unwrap();
+
+    let config = rust_config(root.path());
+    let sources = Arc::new(Mutex::new(HashMap::new()));
+    let generator = Arc::new(RecordingGenerator {
+        inner: Isgl1KeyGeneratorFactory::new(),
+        sources: Arc::clone(&sources),
+    });
+    let streamer = streamer_with(config, generator).await;
+
+    let result = streamer.stream_directory().await.unwrap();
+
+    assert_eq!(result.total_files, 3);
+    assert_eq!(result.processed_files, 2);
+    assert_eq!(result.entities_created, 2);
+
+    // Keyed by the root-relative path the generator is given
+    let sources = sources.lock().unwrap();
+    assert_eq!(sources[Path::new("greet.rs")], source, "UTF-16LE decoded without BOM or NULs");
+    let latin1 = &sources[Path::new("cafe.rs")];
+    assert!(latin1.contains("\"café\""), "{}", latin1);
+    assert_eq!(sources.len(), 2, "undecodable file never reaches the parser");
+
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert!(result.errors[0].contains("blob.rs"), "{}", result.errors[0]);
+    assert!(result.errors[0].contains("Cannot decode"), "{}", result.errors[0]);
+
+    // The detected encoding is stored with each entity
+    let stored = streamer.storage().get_all_entities().await.unwrap();
+    let encoding_of = |name: &str| {
+        let entity = stored.iter().find(|e| e.interface_signature.name == name).unwrap();
+        entity.metadata.additional.get(SOURCE_ENCODING_KEY).cloned()
+    };
+    assert_eq!(encoding_of("greet").as_deref(), Some("UTF-16LE"));
+    assert_eq!(encoding_of("cafe").as_deref(), Some("windows-1252"));
+}
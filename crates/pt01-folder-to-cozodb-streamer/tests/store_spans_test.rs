@@ -0,0 +1,84 @@
+//! Spans-only storage
+//!
+//! With `store_spans_only` the database keeps byte offsets instead of code
+//! text; every read must reconstruct exactly what full-store mode returns.
+
+mod common;
+
+use common::{default_streamer, rust_config};
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, FileStreamerImpl, StreamerConfig};
+use tempfile::TempDir;
+
+async fn ingest(root: &TempDir, store_spans_only: bool) -> FileStreamerImpl {
+    let config = StreamerConfig {
+        store_spans_only,
+        ..rust_config(root.path())
+    };
+    let streamer = default_streamer(config).await;
+    streamer.stream_directory().await.unwrap();
+    streamer
+}
+
+#[tokio::test]
+async fn test_lazy_span_reconstruction_matches_full_store() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(
+        root.path().join("lib.rs"),
+        "/// Adds\npub fn add(a: i32, b: i32) -> i32 {\n    a + b // ünïcode\n}\n\npub struct Point {\n    x: i32,\n}\n",
+    )
+    .unwrap();
+
+    let full = ingest(&root, false).await;
+    let spans = ingest(&root, true).await;
+
+    let entities = full.storage().get_all_entities().await.unwrap();
+    assert_eq!(entities.len(), 2);
+
+    for stored in entities {
+        let key = &stored.isgl1_key;
+        assert!(spans.storage().get_code_span(key).await.unwrap().is_some(), "{}", key);
+        assert_eq!(full.storage().get_code_span(key).await.unwrap(), None);
+
+        let expected = full.storage().get_entity(key).await.unwrap();
+        let lazy = spans.storage().get_entity(key).await.unwrap();
+        assert!(expected.current_code.is_some());
+        assert_eq!(lazy.current_code, expected.current_code, "{}", key);
+        assert_eq!(lazy.future_code, expected.future_code, "{}", key);
+    }
+}
+
+#[tokio::test]
+async fn test_every_read_path_reconstructs_code() {
+    let root = TempDir::new().unwrap();
+    std::fs::write(root.path().join("lib.rs"), "pub fn one() -> u32 {\n    1\n}\n\npub fn two() -> u32 {\n    2\n}\n").unwrap();
+
+    let full = ingest(&root, false).await;
+    let spans = ingest(&root, true).await;
+    let code = |entities: Vec<parseltongue_core::entities::CodeEntity>| {
+        let mut code: Vec<_> = entities.into_iter().map(|e| (e.isgl1_key, e.current_code, e.future_code)).collect();
+        code.sort();
+        code
+    };
+
+    let expected = code(full.storage().get_all_entities().await.unwrap());
+    assert!(expected.iter().all(|(_, current, _)| current.is_some()));
+    assert_eq!(code(spans.storage().get_all_entities().await.unwrap()), expected);
+    assert_eq!(code(spans.storage().get_entities_page(None, 10).await.unwrap().0), expected);
+    let file_path = &spans.storage().get_all_entities().await.unwrap()[0].interface_signature.file_path;
+    let by_file = spans.storage().get_entities_by_file(&file_path.to_string_lossy()).await.unwrap();
+    assert_eq!(code(by_file), expected);
+}
+
+#[tokio::test]
+async fn test_modified_source_is_reported_not_misread() {
+    let root = TempDir::new().unwrap();
+    let path = root.path().join("lib.rs");
+    std::fs::write(&path, "pub fn long_function_name() -> u32 {\n    42\n}\n").unwrap();
+
+    let spans = ingest(&root, true).await;
+    let key = spans.storage().get_all_entities().await.unwrap()[0].isgl1_key.clone();
+
+    std::fs::write(&path, "fn f() {}\n").unwrap();
+    let err = spans.storage().get_entity(&key).await.unwrap_err();
+    assert!(err.to_string().contains("no longer matches"), "{}", err);
+}
@@ -0,0 +1,64 @@
+//! Symlink cycles during directory traversal
+//!
+//! Symlinked directories are not entered by default. With
+//! `follow_symlinks`, every real directory is walked once: a link back to
+//! an ancestor or to an already-walked directory is reported as "skipped
+//! symlink loop" instead of recursing or ingesting the same files again.
+
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::symlink;
+use std::time::Duration;
+
+use common::rust_config;
+use pt01_folder_to_cozodb_streamer::{streamer::FileStreamer, StreamResult, StreamerConfig, ToolFactory};
+use tempfile::TempDir;
+
+/// `src/lib.rs`, `src/again -> ..` (cycle) and `alias -> src` (duplicate)
+fn looping_repo() -> TempDir {
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/lib.rs"), "pub fn run() {}\n").unwrap();
+    symlink("..", root.path().join("src/again")).unwrap();
+    symlink("src", root.path().join("alias")).unwrap();
+    root
+}
+
+async fn ingest(root: &TempDir, follow_symlinks: bool) -> StreamResult {
+    let config = StreamerConfig {
+        follow_symlinks,
+        ..rust_config(root.path())
+    };
+    let streamer = ToolFactory::create_streamer(config).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(30), streamer.stream_directory())
+        .await
+        .expect("ingestion must terminate")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_self_referential_symlink_terminates_when_following() {
+    let root = looping_repo();
+
+    let result = ingest(&root, true).await;
+
+    assert_eq!(result.processed_files, 1, "lib.rs is ingested once: {:?}", result.errors);
+    assert!(!result.errors.is_empty());
+    assert!(
+        result.errors.iter().all(|e| e.ends_with("skipped symlink loop")),
+        "{:?}",
+        result.errors
+    );
+}
+
+#[tokio::test]
+async fn test_symlinked_directories_are_not_entered_by_default() {
+    let root = looping_repo();
+
+    let result = ingest(&root, false).await;
+
+    assert_eq!(result.processed_files, 1);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+}
@@ -31,9 +31,15 @@
 
 use clap::Parser;
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use parseltongue_core::storage::DEFAULT_RELATION_NAME;
 use std::path::PathBuf;
 
+use crate::markdown_export::ExportFormat;
 use crate::models::ExportConfig;
+use crate::sampling::{EntitySampler, SampleSize, SampleStratum};
+use crate::selection::KeySelection;
+use crate::since::{commit_time, parse_since, with_since};
 
 /// PT02: Export entity graphs from CozoDB to JSON
 ///
@@ -93,6 +99,82 @@ pub struct Cli {
     /// Verbose output (show progress, token estimates)
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Write single-line JSON instead of pretty-printed (smaller, faster to parse)
+    #[arg(long)]
+    pub compact: bool,
+
+    /// Report edges that reference missing entities before exporting
+    #[arg(long)]
+    pub validate_keys: bool,
+
+    /// With --validate-keys: fail instead of warn when orphan edges exist
+    #[arg(long, requires = "validate_keys")]
+    pub strict: bool,
+
+    /// Print the generated Datalog queries to stderr before running them
+    #[arg(long)]
+    pub explain: bool,
+
+    /// With --explain: print the queries without running the export
+    #[arg(long, requires = "explain")]
+    pub dry_run: bool,
+
+    /// Output format: json (JSON + TOON) or markdown (also writes a .md per JSON file)
+    #[arg(long, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// Export only these ISGL1 keys (comma-separated); missing keys are reported
+    #[arg(long, value_delimiter = ',')]
+    pub keys: Vec<String>,
+
+    /// Export only the ISGL1 keys listed in this file (one per line)
+    #[arg(long)]
+    pub keys_file: Option<PathBuf>,
+
+    /// With --keys/--keys-file: also export each selected entity's direct dependencies
+    #[arg(long)]
+    pub with_deps: bool,
+
+    /// Export a deterministic sample of this many entities
+    #[arg(long, conflicts_with = "sample_pct")]
+    pub sample: Option<usize>,
+
+    /// Export a deterministic sample of this percentage of entities (0-100)
+    #[arg(long)]
+    pub sample_pct: Option<f64>,
+
+    /// With --sample/--sample-pct: keep the proportions of this field's values
+    #[arg(long)]
+    pub sample_by: Option<SampleStratum>,
+
+    /// Export only entities modified after this time (RFC 3339 or YYYY-MM-DD)
+    #[arg(long, value_parser = parse_since, conflicts_with = "since_commit")]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Export only entities modified after this git revision was committed
+    #[arg(long)]
+    pub since_commit: Option<String>,
+
+    /// Repository --since-commit is resolved in
+    #[arg(long, default_value = ".")]
+    pub repo: PathBuf,
+
+    /// Replace current_code/future_code with `<redacted: N bytes, sha256=...>`
+    #[arg(long)]
+    pub redact: bool,
+
+    /// With --redact: redact doc comments too
+    #[arg(long, requires = "redact")]
+    pub redact_docs: bool,
+
+    /// Leave out entities classified as tests at ingestion
+    #[arg(long)]
+    pub exclude_tests: bool,
+
+    /// Leave out entities whose visibility is not public
+    #[arg(long)]
+    pub exclude_private: bool,
 }
 
 impl Cli {
@@ -103,6 +185,7 @@ impl Cli {
     /// 1. Level 0: Must NOT have --include-code (edges only)
     /// 2. Level 1-2: Must HAVE --include-code (entities need code flag)
     /// 3. WHERE clause: Must be non-empty string
+    /// 4. --since/--since-commit: Level 1-2 only; ANDed onto the WHERE clause
     ///
     /// # Returns
     ///
@@ -141,22 +224,67 @@ impl Cli {
             ));
         }
 
+        // --since filters entities, which Level 0 does not export
+        let since = self.since_cutoff()?;
+        if self.level == 0 && since.is_some() {
+            return Err(anyhow!("--since/--since-commit apply to entity exports (Level 1-2) only"));
+        }
+
         // Build config (always JSON - TOON is auto-generated)
 
         Ok(ExportConfig {
             level: self.level,
             include_code: self.include_code.map(|v| v == 1).unwrap_or(false),
-            where_filter: self.where_clause.clone(),
+            where_filter: with_since(&self.where_clause, DEFAULT_RELATION_NAME, since),
             output_path: self.output.clone().unwrap_or_else(|| {
                 PathBuf::from(format!("ISGLevel{:02}.json", self.level))
             }),
             // v0.9.0: Dual outputs for code/test separation (None for general CLI)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: self.compact,
             db_path: self.db.clone(),
         })
     }
 
+    /// Cut-off from `--since`, or the commit time of `--since-commit`
+    pub fn since_cutoff(&self) -> Result<Option<DateTime<Utc>>> {
+        match &self.since_commit {
+            Some(rev) => commit_time(&self.repo, rev).map(Some),
+            None => Ok(self.since),
+        }
+    }
+
+    /// Selection from `--keys` and `--keys-file`; `None` exports everything
+    /// the WHERE clause matches
+    pub fn key_selection(&self) -> Result<Option<KeySelection>> {
+        if self.keys.is_empty() && self.keys_file.is_none() {
+            if self.with_deps {
+                return Err(anyhow!("--with-deps requires --keys or --keys-file"));
+            }
+            return Ok(None);
+        }
+        let from_file = match &self.keys_file {
+            Some(path) => KeySelection::from_keys_file(path)?,
+            None => KeySelection::default(),
+        };
+        Ok(Some(from_file.extend(self.keys.iter().cloned()).with_deps(self.with_deps)))
+    }
+
+    /// Sampler from `--sample` / `--sample-pct` / `--sample-by`; `None`
+    /// exports every matching entity
+    pub fn entity_sampler(&self) -> Result<Option<EntitySampler>> {
+        let size = match (self.sample, self.sample_pct) {
+            (Some(count), _) => SampleSize::Count(count),
+            (None, Some(pct)) => SampleSize::Percent(pct),
+            (None, None) if self.sample_by.is_some() => {
+                return Err(anyhow!("--sample-by requires --sample or --sample-pct"));
+            }
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(EntitySampler::new(size)?.with_stratum(self.sample_by)))
+    }
+
     /// Print verbose output if enabled
     pub fn verbose_print(&self, message: &str) {
         if self.verbose {
@@ -188,6 +316,23 @@ mod tests {
         assert_eq!(config.include_code, false);
     }
 
+    #[test]
+    fn test_strict_requires_validate_keys() {
+        let cli = Cli::try_parse_from(&["pt02", "--level", "0", "--where-clause", "ALL", "--validate-keys", "--strict"]).unwrap();
+        assert!(cli.validate_keys && cli.strict);
+
+        assert!(Cli::try_parse_from(&["pt02", "--level", "0", "--where-clause", "ALL", "--strict"]).is_err());
+    }
+
+    #[test]
+    fn test_compact_flag_reaches_config() {
+        let pretty = Cli::parse_from(&["pt02", "--level", "0", "--where-clause", "ALL"]);
+        assert!(!pretty.validate().unwrap().compact_json);
+
+        let compact = Cli::parse_from(&["pt02", "--level", "0", "--where-clause", "ALL", "--compact"]);
+        assert!(compact.validate().unwrap().compact_json);
+    }
+
     #[test]
     fn test_level0_with_include_code_fails() {
         let cli = Cli::parse_from(&[
@@ -297,6 +442,25 @@ mod tests {
             output: None,
             db: "test.db".to_string(),
             verbose: false,
+            compact: false,
+            validate_keys: false,
+            strict: false,
+            explain: false,
+            dry_run: false,
+            format: ExportFormat::Json,
+            keys: vec![],
+            keys_file: None,
+            with_deps: false,
+            sample: None,
+            sample_pct: None,
+            sample_by: None,
+            since: None,
+            since_commit: None,
+            repo: PathBuf::from("."),
+            redact: false,
+            redact_docs: false,
+            exclude_tests: false,
+            exclude_private: false,
         };
 
         let result = cli.validate();
@@ -304,4 +468,20 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.to_string().contains("WHERE clause cannot be empty"));
     }
+
+    #[test]
+    fn test_since_is_anded_onto_where_clause_for_entity_levels() {
+        let cli = Cli::parse_from(&[
+            "pt02", "--level", "1", "--include-code", "0",
+            "--where-clause", "entity_type = 'function'", "--since", "2024-05-01",
+        ]);
+        assert_eq!(
+            cli.validate().unwrap().where_filter,
+            "entity_type = 'function', *CodeGraph{ISGL1_key, last_modified}, \
+             last_modified > '2024-05-01T00:00:00+00:00'"
+        );
+
+        let edges = Cli::parse_from(&["pt02", "--level", "0", "--where-clause", "ALL", "--since", "2024-05-01"]);
+        assert!(edges.validate().unwrap_err().to_string().contains("Level 1-2"));
+    }
 }
@@ -0,0 +1,104 @@
+//! Comments left out of exported code (`--strip-comments`).
+//!
+//! Comments cost tokens the LLM rarely needs. `CommentStrippedRepository`
+//! wraps the repository like `RedactedRepository` does and runs
+//! current_code / future_code through `parseltongue_core::text::strip_comments`,
+//! which finds comments with tree-sitter so `//` inside a string literal
+//! survives. The language comes from each entity's file extension; code in
+//! a language without a grammar passes through unchanged.
+//!
+//! Doc comments go too, including the `doc_comment` field, unless
+//! `with_doc_comments` keeps them.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use parseltongue_core::entities::Language;
+use parseltongue_core::text::{strip_comments, strip_non_doc_comments};
+
+use crate::export_trait::{CodeGraphRepository, Edge, Entity};
+
+/// Repository view with comments removed from code
+pub struct CommentStrippedRepository<'a> {
+    inner: &'a dyn CodeGraphRepository,
+    keep_doc_comments: bool,
+}
+
+impl<'a> CommentStrippedRepository<'a> {
+    pub fn new(inner: &'a dyn CodeGraphRepository) -> Self {
+        Self { inner, keep_doc_comments: false }
+    }
+
+    /// Keep doc comments in code and the `doc_comment` field
+    pub fn with_doc_comments(mut self, keep_doc_comments: bool) -> Self {
+        self.keep_doc_comments = keep_doc_comments;
+        self
+    }
+
+    fn strip_entity(&self, mut entity: Entity) -> Entity {
+        let Some(language) = Language::from_file_path(&PathBuf::from(&entity.file_path)) else {
+            return entity;
+        };
+        let strip = |code: &str| {
+            if self.keep_doc_comments {
+                strip_non_doc_comments(code, language)
+            } else {
+                strip_comments(code, language)
+            }
+        };
+        entity.current_code = entity.current_code.as_deref().map(strip);
+        entity.future_code = entity.future_code.as_deref().map(strip);
+        if !self.keep_doc_comments {
+            entity.doc_comment = None;
+        }
+        entity
+    }
+}
+
+#[async_trait]
+impl CodeGraphRepository for CommentStrippedRepository<'_> {
+    async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+        let entities = self.inner.get_all_entities().await?;
+        Ok(entities.into_iter().map(|entity| self.strip_entity(entity)).collect())
+    }
+
+    async fn query_entities(&self, where_clause: &str) -> Result<Vec<Entity>> {
+        let entities = self.inner.query_entities(where_clause).await?;
+        Ok(entities.into_iter().map(|entity| self.strip_entity(entity)).collect())
+    }
+
+    async fn entities_after(&self, where_clause: &str, after: Option<&str>, limit: usize) -> Result<Vec<Entity>> {
+        let entities = self.inner.entities_after(where_clause, after, limit).await?;
+        Ok(entities.into_iter().map(|entity| self.strip_entity(entity)).collect())
+    }
+
+    fn entities_stream<'a>(&'a self, where_clause: &'a str) -> BoxStream<'a, Result<Entity>> {
+        self.inner
+            .entities_stream(where_clause)
+            .map_ok(move |entity| self.strip_entity(entity))
+            .boxed()
+    }
+
+    async fn count_entities(&self, where_clause: &str) -> Result<usize> {
+        self.inner.count_entities(where_clause).await
+    }
+
+    async fn get_all_edges(&self) -> Result<Vec<Edge>> {
+        self.inner.get_all_edges().await
+    }
+
+    async fn query_edges(&self, where_clause: &str) -> Result<Vec<Edge>> {
+        self.inner.query_edges(where_clause).await
+    }
+
+    async fn count_edges(&self, where_clause: &str) -> Result<usize> {
+        self.inner.count_edges(where_clause).await
+    }
+
+    async fn get_provenance(&self) -> Result<HashMap<String, String>> {
+        self.inner.get_provenance().await
+    }
+}
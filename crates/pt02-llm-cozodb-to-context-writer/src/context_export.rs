@@ -0,0 +1,118 @@
+//! Level 1 export in pt03's `CodeGraphContext` shape (`--as-context`).
+//!
+//! pt02's native Level 1 JSON and the `CodeGraphContext` that pt03 hands to
+//! the LLM describe the same entities differently. With `--as-context`,
+//! Level 1 writes a single `{output}.json` holding a serialized
+//! `parseltongue_core::interfaces::CodeGraphContext`:
+//!
+//! ```json
+//! {
+//!   "version": "0.9.6",
+//!   "generated_at": "2026-01-10T09:00:00Z",
+//!   "token_count": 1234,
+//!   "entities": [
+//!     { "isgl1_key": "rust:fn:main:src_main_rs:1-3", "interface_signature": { ... },
+//!       "tdd_classification": { ... }, "lsp_metadata": null,
+//!       "relevance_score": 1.0, "dependency_level": 0 }
+//!   ],
+//!   "relationships": [
+//!     { "dependent": "rust:fn:main:...", "dependency": "rust:fn:run:...",
+//!       "relationship_type": "Calls", "strength": 1.0 }
+//!   ],
+//!   "optimization_info": { "excluded_entities": [], "truncation_applied": false,
+//!                          "prioritization_strategy": "none" }
+//! }
+//! ```
+//!
+//! Test and code entities share the file; `tdd_classification` tells them apart.
+
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use parseltongue_core::entities::CodeEntity;
+use parseltongue_core::interfaces::{CodeGraphContext, ContextEntity, ContextRelationship, OptimizationInfo};
+
+use crate::export_trait::{CodeGraphRepository, Edge};
+use crate::level_comparison::CHARS_PER_TOKEN;
+
+/// Build a `CodeGraphContext` from `entities` and the edges leaving them
+///
+/// Every entity was selected directly, so each gets relevance 1.0 at
+/// dependency level 0. Only edges whose dependent is one of `entities` are
+/// kept. Entities and relationships are sorted by key, so the same graph
+/// always serializes the same way.
+pub fn build_code_graph_context(
+    entities: Vec<CodeEntity>,
+    edges: &[Edge],
+    generated_at: DateTime<Utc>,
+) -> CodeGraphContext {
+    let mut context_entities: Vec<ContextEntity> = entities
+        .into_iter()
+        .map(|entity| ContextEntity {
+            isgl1_key: entity.isgl1_key,
+            interface_signature: entity.interface_signature,
+            tdd_classification: entity.tdd_classification,
+            lsp_metadata: entity.lsp_metadata,
+            relevance_score: 1.0,
+            dependency_level: 0,
+        })
+        .collect();
+    context_entities.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+
+    let selected: HashSet<&str> = context_entities.iter().map(|e| e.isgl1_key.as_str()).collect();
+    let relationships: Vec<ContextRelationship> = edges
+        .iter()
+        .filter(|edge| selected.contains(edge.from_key.as_str()))
+        .map(|edge| (edge.from_key.clone(), edge.to_key.clone(), edge.edge_type.clone()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|(dependent, dependency, relationship_type)| ContextRelationship {
+            dependent,
+            dependency,
+            relationship_type,
+            strength: 1.0,
+        })
+        .collect();
+
+    let token_count = serde_json::to_string(&context_entities).map_or(0, |json| json.len() / CHARS_PER_TOKEN);
+
+    CodeGraphContext {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at,
+        token_count,
+        entities: context_entities,
+        relationships,
+        optimization_info: OptimizationInfo {
+            excluded_entities: vec![],
+            truncation_applied: false,
+            prioritization_strategy: "none".to_string(),
+        },
+    }
+}
+
+/// `CodeGraphContext` of the entities `where_clause` selects from `repository`
+///
+/// `repository` decides which keys are exported (so key selection and
+/// sampling apply); `full_entities` supplies the signature, TDD and LSP
+/// data that pt02's `Entity` does not carry.
+pub async fn export_code_graph_context(
+    repository: &dyn CodeGraphRepository,
+    full_entities: Vec<CodeEntity>,
+    where_clause: &str,
+    generated_at: DateTime<Utc>,
+) -> Result<CodeGraphContext> {
+    let selected = if where_clause == "ALL" {
+        repository.get_all_entities().await?
+    } else {
+        repository.query_entities(where_clause).await?
+    };
+    let keys: HashSet<String> = selected.into_iter().map(|e| e.isgl1_key).collect();
+    let entities = full_entities
+        .into_iter()
+        .filter(|entity| keys.contains(&entity.isgl1_key))
+        .collect();
+    let edges = repository.get_all_edges().await?;
+
+    Ok(build_code_graph_context(entities, &edges, generated_at))
+}
@@ -11,9 +11,20 @@
 //! ```
 
 use crate::export_trait::{CodeGraphRepository, Edge, Entity};
+use crate::query_builder::{build_count_query, build_edge_query, build_entity_query};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use parseltongue_core::isgl1_key::parse_key;
 use parseltongue_core::storage::CozoDbStorage;
+use std::collections::HashMap;
+
+/// Entities per `entities_after` query in `entities_stream`
+const STREAM_PAGE_SIZE: usize = 1_000;
+
+/// Query results kept by `connect`; one export repeats the same few
+/// entity and edge queries (key validation, counts, the export itself)
+const QUERY_CACHE_CAPACITY: usize = 64;
 
 /// CozoDB adapter for PT02 exports
 ///
@@ -21,50 +32,111 @@ use parseltongue_core::storage::CozoDbStorage;
 /// the `CodeGraphRepository` trait that PT02 exporters expect.
 pub struct CozoDbAdapter {
     storage: CozoDbStorage,
+    /// Export the Provenance relation alongside entities
+    include_provenance: bool,
 }
 
 impl CozoDbAdapter {
     /// Create new adapter from CozoDbStorage
     pub fn new(storage: CozoDbStorage) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            include_provenance: false,
+        }
+    }
+
+    /// Include each entity's last writing tool in Level 1-2 exports
+    pub fn with_provenance(mut self, include_provenance: bool) -> Self {
+        self.include_provenance = include_provenance;
+        self
     }
 
-    /// Create adapter by connecting to database
+    /// Create adapter by connecting to database, creating or migrating the schema
+    ///
+    /// Repeated identical queries are served from the storage's query cache.
     pub async fn connect(db_path: &str) -> Result<Self> {
         let storage = CozoDbStorage::new(db_path)
             .await
-            .map_err(|e| anyhow!("Failed to connect to CozoDB: {}", e))?;
+            .map_err(|e| anyhow!("Failed to connect to CozoDB: {}", e))?
+            .with_cache(QUERY_CACHE_CAPACITY);
+        storage
+            .ensure_schema()
+            .await
+            .map_err(|e| anyhow!("Failed to prepare CozoDB schema: {}", e))?;
         Ok(Self::new(storage))
     }
+
+    /// Number of entities in the storage's relation, not counting soft-deleted ones
+    pub async fn entity_count(&self) -> Result<usize> {
+        let query = format!(
+            "?[count(ISGL1_key)] := *{}{{ISGL1_key, deleted_at: null}}",
+            self.storage.relation()
+        );
+        self.count(&query).await
+    }
+
+    /// Fill in the code of entities pt01 stored spans-only (`--store-spans`)
+    ///
+    /// Unchanged entities get the same text as `future_code`, as
+    /// `CozoDbStorage` reads return them.
+    async fn with_span_code(&self, mut entities: Vec<Entity>) -> Result<Vec<Entity>> {
+        let without_code: Vec<&str> = entities
+            .iter()
+            .filter(|e| e.current_code.is_none())
+            .map(|e| e.isgl1_key.as_str())
+            .collect();
+        let mut code = self.storage.code_for_spans(&without_code).await
+            .map_err(|e| anyhow!("Failed to read code spans: {}", e))?;
+        for entity in &mut entities {
+            let Some(text) = code.remove(&entity.isgl1_key) else {
+                continue;
+            };
+            if entity.future_action.is_none() && entity.future_code.is_none() {
+                entity.future_code = Some(text.clone());
+            }
+            entity.current_code = Some(text);
+        }
+        Ok(entities)
+    }
+
+    /// Run a single-cell `count` aggregation
+    async fn count(&self, query: &str) -> Result<usize> {
+        let result = self.storage.raw_query(query).await
+            .map_err(|e| anyhow!("Failed to count rows: {}", e))?;
+        Ok(result
+            .rows
+            .first()
+            .and_then(|row| row.first())
+            .and_then(|count| count.get_int())
+            .unwrap_or(0) as usize)
+    }
+
+    /// Every entity as stored, with the signature, TDD and LSP data
+    /// pt02's `Entity` leaves out (see `context_export`)
+    pub async fn get_code_entities(&self) -> Result<Vec<parseltongue_core::entities::CodeEntity>> {
+        self.storage
+            .get_all_entities()
+            .await
+            .map_err(|e| anyhow!("Failed to load entities: {}", e))
+    }
+
+    /// Edges whose from/to key has no entity (see `CozoDbStorage::find_orphan_edges`)
+    pub async fn find_orphan_edges(&self) -> Result<Vec<parseltongue_core::entities::DependencyEdge>> {
+        self.storage
+            .find_orphan_edges()
+            .await
+            .map_err(|e| anyhow!("Failed to validate keys: {}", e))
+    }
 }
 
 #[async_trait]
 impl CodeGraphRepository for CozoDbAdapter {
     async fn get_all_entities(&self) -> Result<Vec<Entity>> {
-        // Query all entities from CodeGraph
-        let query = r#"
-            ?[ISGL1_key, interface_signature, entity_type, file_path,
-              Current_Code, Future_Code, current_ind, future_ind, Future_Action, entity_class] :=
-            *CodeGraph{
-                ISGL1_key,
-                interface_signature,
-                entity_type,
-                file_path,
-                Current_Code,
-                Future_Code,
-                current_ind,
-                future_ind,
-                Future_Action,
-                entity_class
-            }
-        "#;
-
-        let result = self.storage.raw_query(query).await
+        let result = self.storage.raw_query(&build_entity_query("ALL")).await
             .map_err(|e| anyhow!("Failed to query entities: {}", e))?;
 
         // Parse result into Entity structs
-        let entities = parse_entities_from_query_result(&result)?;
-        Ok(entities)
+        self.with_span_code(parse_entities_from_query_result(&result)?).await
     }
 
     async fn query_entities(&self, where_clause: &str) -> Result<Vec<Entity>> {
@@ -72,43 +144,46 @@ impl CodeGraphRepository for CozoDbAdapter {
             return self.get_all_entities().await;
         }
 
-        // Build Datalog query with WHERE clause
-        let query = format!(
-            r#"
-            ?[ISGL1_key, interface_signature, entity_type, file_path,
-              Current_Code, Future_Code, current_ind, future_ind, Future_Action, entity_class] :=
-            *CodeGraph{{
-                ISGL1_key,
-                interface_signature,
-                entity_type,
-                file_path,
-                Current_Code,
-                Future_Code,
-                current_ind,
-                future_ind,
-                Future_Action,
-                entity_class
-            }},
-            {}
-            "#,
-            where_clause
-        );
+        let result = self.storage.raw_query(&build_entity_query(where_clause)).await
+            .map_err(|e| anyhow!("Failed to query entities with WHERE clause: {}", e))?;
+
+        self.with_span_code(parse_entities_from_query_result(&result)?).await
+    }
+
+    async fn entities_after(&self, where_clause: &str, after: Option<&str>, limit: usize) -> Result<Vec<Entity>> {
+        let mut query = build_entity_query(where_clause);
+        if let Some(after) = after {
+            // A JSON string literal is a valid Datalog string literal
+            query.push_str(&format!(", ISGL1_key > {}", serde_json::to_string(after)?));
+        }
+        query.push_str(&format!("\n:order ISGL1_key\n:limit {}", limit));
 
         let result = self.storage.raw_query(&query).await
-            .map_err(|e| anyhow!("Failed to query entities with WHERE clause: {}", e))?;
+            .map_err(|e| anyhow!("Failed to page entities: {}", e))?;
+        self.with_span_code(parse_entities_from_query_result(&result)?).await
+    }
 
-        let entities = parse_entities_from_query_result(&result)?;
-        Ok(entities)
+    fn entities_stream<'a>(&'a self, where_clause: &'a str) -> BoxStream<'a, Result<Entity>> {
+        stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| async move {
+            let Some(after) = cursor else {
+                return Ok(None);
+            };
+            let page = self.entities_after(where_clause, after.as_deref(), STREAM_PAGE_SIZE).await?;
+            let next = match page.last() {
+                Some(last) if page.len() == STREAM_PAGE_SIZE => Some(Some(last.isgl1_key.clone())),
+                _ => None,
+            };
+            if page.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+        .boxed()
     }
 
     async fn get_all_edges(&self) -> Result<Vec<Edge>> {
-        // Query all dependency edges
-        let query = r#"
-            ?[from_key, to_key, edge_type] :=
-            *DependencyEdges{from_key, to_key, edge_type}
-        "#;
-
-        let result = self.storage.raw_query(query).await
+        let result = self.storage.raw_query(&build_edge_query("ALL")).await
             .map_err(|e| anyhow!("Failed to query edges: {}", e))?;
 
         let edges = parse_edges_from_query_result(&result)?;
@@ -120,22 +195,32 @@ impl CodeGraphRepository for CozoDbAdapter {
             return self.get_all_edges().await;
         }
 
-        // Build Datalog query with WHERE clause
-        let query = format!(
-            r#"
-            ?[from_key, to_key, edge_type] :=
-            *DependencyEdges{{from_key, to_key, edge_type}},
-            {}
-            "#,
-            where_clause
-        );
-
-        let result = self.storage.raw_query(&query).await
+        let result = self.storage.raw_query(&build_edge_query(where_clause)).await
             .map_err(|e| anyhow!("Failed to query edges with WHERE clause: {}", e))?;
 
         let edges = parse_edges_from_query_result(&result)?;
         Ok(edges)
     }
+
+    async fn count_entities(&self, where_clause: &str) -> Result<usize> {
+        self.count(&build_count_query(1, where_clause)).await
+    }
+
+    async fn count_edges(&self, where_clause: &str) -> Result<usize> {
+        self.count(&build_count_query(0, where_clause)).await
+    }
+
+    async fn get_provenance(&self) -> Result<HashMap<String, String>> {
+        if !self.include_provenance {
+            return Ok(HashMap::new());
+        }
+        let provenance = self.storage.get_all_provenance().await
+            .map_err(|e| anyhow!("Failed to read provenance: {}", e))?;
+        Ok(provenance
+            .into_iter()
+            .map(|(key, provenance)| (key, provenance.to_string()))
+            .collect())
+    }
 }
 
 /// Parse entities from CozoDB query result
@@ -187,6 +272,16 @@ fn parse_entities_from_query_result(result: &cozo::NamedRows) -> Result<Vec<Enti
             is_public: None,
             is_async: None,
             is_unsafe: None,
+
+            // Structured LSP data (column 10); legacy strings become hover text
+            lsp_metadata: extract_optional_string(row, 10)
+                .map(|stored| parseltongue_core::entities::LspMetadata::from_stored(&stored)),
+
+            // Measured by pt01 (column 11)
+            complexity_score: row.get(11).and_then(|v| v.get_int()).and_then(|score| u32::try_from(score).ok()),
+
+            // Joined from GeneratedEntities (column 12)
+            is_generated: matches!(row.get(12), Some(cozo::DataValue::Bool(true))),
         };
 
         entities.push(entity);
@@ -237,28 +332,14 @@ fn extract_u8(row: &[cozo::DataValue], index: usize) -> Result<u8> {
     }
 }
 
-/// Parse entity name from ISGL1 key
-///
-/// Format: `rust:fn:entity_name:file_path:line`
+/// Parse entity name from ISGL1 key (either `KeyFormat`)
 fn parse_entity_name_from_key(key: &str) -> String {
-    let parts: Vec<&str> = key.split(':').collect();
-    if parts.len() >= 3 {
-        parts[2].to_string()
-    } else {
-        "unknown".to_string()
-    }
+    parse_key(key).map_or_else(|_| "unknown".to_string(), |components| components.name)
 }
 
-/// Parse line number from ISGL1 key
-///
-/// Format: `rust:fn:entity_name:file_path:line`
+/// Parse the first line from ISGL1 key (either `KeyFormat`)
 fn parse_line_number_from_key(key: &str) -> u32 {
-    let parts: Vec<&str> = key.split(':').collect();
-    if parts.len() >= 5 {
-        parts[4].parse().unwrap_or(0)
-    } else {
-        0
-    }
+    parse_key(key).map_or(0, |components| components.line_range.0 as u32)
 }
 
 #[cfg(test)]
@@ -279,5 +360,39 @@ mod tests {
             parse_line_number_from_key("rust:fn:main:src_main_rs:42"),
             42
         );
+        assert_eq!(parse_line_number_from_key("rust:fn:main:src_main_rs:42-50"), 42);
+        assert_eq!(parse_line_number_from_key("src_main_rs-main-fn-rust-42-50"), 42);
+    }
+
+    #[tokio::test]
+    async fn test_explained_queries_run_against_schema() {
+        let adapter = CozoDbAdapter::connect("mem").await.unwrap();
+
+        assert!(adapter.query_entities("entity_class = 'CODE', entity_type = 'fn'").await.unwrap().is_empty());
+        assert!(adapter.get_all_entities().await.unwrap().is_empty());
+        assert!(adapter.query_edges("edge_type = 'Calls'").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_connect_caches_repeated_queries() {
+        let adapter = CozoDbAdapter::connect("mem").await.unwrap();
+
+        adapter.get_all_edges().await.unwrap();
+        adapter.get_all_edges().await.unwrap();
+        assert_eq!(adapter.storage.cache_stats().unwrap().hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_provenance_is_opt_in() {
+        let storage = CozoDbStorage::new("mem").await.unwrap();
+        storage.ensure_schema().await.unwrap();
+        storage.record_provenance("rust:fn:main:src_main_rs:1", "pt03").await.unwrap();
+
+        let adapter = CozoDbAdapter::new(storage);
+        assert!(adapter.get_provenance().await.unwrap().is_empty());
+
+        let adapter = adapter.with_provenance(true);
+        let provenance = adapter.get_provenance().await.unwrap();
+        assert!(provenance["rust:fn:main:src_main_rs:1"].starts_with("pt03@"));
     }
 }
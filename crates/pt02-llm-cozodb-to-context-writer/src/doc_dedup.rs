@@ -0,0 +1,62 @@
+//! Shared doc comments (`--dedup-docs`).
+//!
+//! License headers and other boilerplate captured as docs can repeat across
+//! hundreds of entities. With deduplication the JSON export becomes
+//! `{ "shared_docs": { id: text }, "entities": [...] }`: every doc string
+//! used by two or more entities is emitted once in `shared_docs`, and those
+//! entities carry `doc_ref: id` in place of `doc_comment`. Docs used once
+//! stay inline. TOON output is unchanged.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Entities with their repeated doc comments moved into `shared_docs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DedupedEntities {
+    /// Doc text by id (`doc1`, `doc2`, ... in order of first use)
+    pub shared_docs: BTreeMap<String, String>,
+    pub entities: Vec<Value>,
+}
+
+/// Move doc comments shared by several `entities` into a shared table
+pub fn dedup_docs<T: Serialize>(entities: &[T]) -> serde_json::Result<DedupedEntities> {
+    let mut entities = entities
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<serde_json::Result<Vec<Value>>>()?;
+
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    for doc in entities.iter().filter_map(doc_comment) {
+        *uses.entry(doc.to_string()).or_default() += 1;
+    }
+
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut shared_docs = BTreeMap::new();
+    for entity in &mut entities {
+        let Some(doc) = doc_comment(entity).map(str::to_string) else {
+            continue;
+        };
+        if uses[&doc] < 2 {
+            continue;
+        }
+        let id = ids
+            .entry(doc.clone())
+            .or_insert_with(|| {
+                let id = format!("doc{}", shared_docs.len() + 1);
+                shared_docs.insert(id.clone(), doc.clone());
+                id
+            })
+            .clone();
+        if let Some(fields) = entity.as_object_mut() {
+            fields.remove("doc_comment");
+            fields.insert("doc_ref".to_string(), Value::String(id));
+        }
+    }
+
+    Ok(DedupedEntities { shared_docs, entities })
+}
+
+fn doc_comment(entity: &Value) -> Option<&str> {
+    entity.get("doc_comment").and_then(Value::as_str)
+}
@@ -45,6 +45,9 @@ mod pt02_entity_class_tests {
             is_public: None,
             is_async: None,
             is_unsafe: None,
+            lsp_metadata: None,
+            is_generated: false,
+            complexity_score: None,
         };
         
         assert_eq!(entity.entity_class, "CODE");
@@ -155,6 +158,9 @@ fn create_test_entity(file_path: &str, entity_class: &str) -> Entity {
         is_public: Some(true),
         is_async: Some(false),
         is_unsafe: Some(false),
+        lsp_metadata: None,
+        is_generated: false,
+        complexity_score: None,
     }
 }
 
@@ -23,4 +23,5 @@ pub enum ContextWriterError {
 }
 
 /// Re-export parseltongue-core errors for convenience
+pub use parseltongue_core::entity_filter::QueryBuildError;
 pub use parseltongue_core::error::{ParseltongError, Result};
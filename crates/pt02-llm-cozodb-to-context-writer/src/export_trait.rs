@@ -10,7 +10,8 @@
 //! ## Contract
 //!
 //! Each level exporter implements `LevelExporter`:
-//! - `export()`: Execute export operation (async, fallible)
+//! - `export_to()`: Execute export operation, writing through an `OutputSink`
+//! - `export()`: `export_to()` with the local-file sink
 //! - `level()`: Get level number (0, 1, 2)
 //! - `estimated_tokens()`: Get token count estimate (without code)
 //!
@@ -30,6 +31,9 @@
 use crate::models::{ExportConfig, ExportOutput};
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use parseltongue_core::output_sink::{FileSink, OutputSink};
+use std::collections::HashMap;
 
 /// Contract for PT02 export operations
 ///
@@ -63,7 +67,20 @@ pub trait LevelExporter: Send + Sync {
     /// let config = ExportConfig { level: 0, where_filter: "ALL".to_string(), ... };
     /// let output = exporter.export(&db, &config).await?;
     /// ```
-    async fn export(&self, db: &dyn CodeGraphRepository, config: &ExportConfig) -> Result<ExportOutput>;
+    async fn export(&self, db: &dyn CodeGraphRepository, config: &ExportConfig) -> Result<ExportOutput> {
+        self.export_to(db, config, &FileSink).await
+    }
+
+    /// Export entities at this level, writing artifacts through `sink`
+    ///
+    /// `export` is this with a `FileSink`. Artifact names are
+    /// `config.output_path` and its `.toon` sibling.
+    async fn export_to(
+        &self,
+        db: &dyn CodeGraphRepository,
+        config: &ExportConfig,
+        sink: &dyn OutputSink,
+    ) -> Result<ExportOutput>;
 
     /// Get level number (0, 1, 2)
     ///
@@ -105,6 +122,63 @@ pub trait CodeGraphRepository: Send + Sync {
 
     /// Query edges with Datalog WHERE clause
     async fn query_edges(&self, where_clause: &str) -> Result<Vec<Edge>>;
+
+    /// Up to `limit` entities matching `where_clause` whose keys sort after
+    /// `after`, in key order
+    ///
+    /// Pages for `ExternalSortExporter`. The default filters
+    /// `query_entities`, so decorators page correctly without overriding it;
+    /// `CozoDbAdapter` overrides it with a key-range query so that only one
+    /// page is ever in memory.
+    async fn entities_after(&self, where_clause: &str, after: Option<&str>, limit: usize) -> Result<Vec<Entity>> {
+        let mut entities = self.query_entities(where_clause).await?;
+        if let Some(after) = after {
+            entities.retain(|entity| entity.isgl1_key.as_str() > after);
+        }
+        entities.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+        entities.truncate(limit);
+        Ok(entities)
+    }
+
+    /// Entities `query_entities(where_clause)` would return, as a stream
+    ///
+    /// Level 1 and 2 exporters convert entities as they arrive instead of
+    /// holding every row first. The default yields `query_entities` in one
+    /// go; `CozoDbAdapter` pages through `entities_after`, and decorators
+    /// wrap their inner stream. Order is unspecified, exporters sort.
+    fn entities_stream<'a>(&'a self, where_clause: &'a str) -> BoxStream<'a, Result<Entity>> {
+        stream::once(async move {
+            if where_clause == "ALL" {
+                self.get_all_entities().await
+            } else {
+                self.query_entities(where_clause).await
+            }
+        })
+        .map_ok(|entities| stream::iter(entities.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Number of entities `query_entities(where_clause)` would return
+    ///
+    /// For `--count-only`. The default runs the query; `CozoDbAdapter`
+    /// overrides it with a Datalog `count` so no rows are fetched.
+    async fn count_entities(&self, where_clause: &str) -> Result<usize> {
+        Ok(self.query_entities(where_clause).await?.len())
+    }
+
+    /// Number of edges `query_edges(where_clause)` would return
+    async fn count_edges(&self, where_clause: &str) -> Result<usize> {
+        Ok(self.query_edges(where_clause).await?.len())
+    }
+
+    /// Last writer of each entity as `tool@timestamp`, keyed by ISGL1 key
+    ///
+    /// Exporters attach whatever this returns; the default (empty) leaves
+    /// provenance out of the export.
+    async fn get_provenance(&self) -> Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
 }
 
 /// Entity representation from database
@@ -140,6 +214,16 @@ pub struct Entity {
     pub is_public: Option<bool>,
     pub is_async: Option<bool>,
     pub is_unsafe: Option<bool>,
+
+    /// rust-analyzer hover, definition, references and diagnostics
+    /// (Level 2 surfaces them; `None` when ingested without LSP)
+    pub lsp_metadata: Option<parseltongue_core::entities::LspMetadata>,
+
+    /// Ingested from a generated or vendored file (pt01's `GeneratedEntities`)
+    pub is_generated: bool,
+
+    /// Cyclomatic complexity measured at ingestion (`None` when unmeasured)
+    pub complexity_score: Option<u32>,
 }
 
 /// Edge representation from database
@@ -164,7 +248,12 @@ mod tests {
 
     #[async_trait]
     impl LevelExporter for MockExporter {
-        async fn export(&self, _db: &dyn CodeGraphRepository, _config: &ExportConfig) -> Result<ExportOutput> {
+        async fn export_to(
+            &self,
+            _db: &dyn CodeGraphRepository,
+            _config: &ExportConfig,
+            _sink: &dyn OutputSink,
+        ) -> Result<ExportOutput> {
             todo!("Mock implementation")
         }
 
@@ -210,6 +299,9 @@ mod tests {
             is_public: None,
             is_async: None,
             is_unsafe: None,
+            lsp_metadata: None,
+            is_generated: false,
+            complexity_score: None,
         };
 
         let debug_str = format!("{:?}", entity);
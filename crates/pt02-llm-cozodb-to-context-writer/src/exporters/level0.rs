@@ -18,17 +18,28 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
+use parseltongue_core::output_sink::{FileSink, OutputSink};
 
 use crate::export_trait::{CodeGraphRepository, LevelExporter};
-use crate::models::{DependencyEdge, ExportConfig, ExportMetadata, ExportOutput};
+use crate::models::{export_timestamp, DependencyEdge, ExportConfig, ExportMetadata, ExportOutput};
+use crate::query_builder::scope_to_entity_class;
 
 /// Level 0 Exporter: Pure edge list (minimal)
-pub struct Level0Exporter;
+pub struct Level0Exporter {
+    include_timestamp: bool,
+}
 
 impl Level0Exporter {
     pub fn new() -> Self {
-        Self
+        Self { include_timestamp: true }
+    }
+
+    /// Stamp `export_metadata.timestamp` (default: true)
+    ///
+    /// Without it, exporting the same data twice writes byte-identical files.
+    pub fn with_timestamp(mut self, include_timestamp: bool) -> Self {
+        self.include_timestamp = include_timestamp;
+        self
     }
 
     /// REQ-V090-004.0: Export dual files (CODE and TEST) from single output name
@@ -41,6 +52,7 @@ impl Level0Exporter {
     /// * `repository` - Database repository (dependency injection)
     /// * `output_name` - Base name for both files
     /// * `where_clause` - Datalog WHERE clause for filtering
+    /// * `compact_json` - Write single-line JSON instead of pretty-printed
     /// 
     /// # Returns
     /// `Result<()>` - Structured error handling with thiserror
@@ -49,13 +61,22 @@ impl Level0Exporter {
         repository: &dyn CodeGraphRepository,
         output_name: &str,
         where_clause: &str,
+        compact_json: bool,
+    ) -> anyhow::Result<()> {
+        self.export_dual_files_to(repository, &FileSink, output_name, where_clause, compact_json).await
+    }
+
+    /// Same as `export_dual_files`, writing every artifact through `sink`
+    pub async fn export_dual_files_to(
+        &self,
+        repository: &dyn CodeGraphRepository,
+        sink: &dyn OutputSink,
+        output_name: &str,
+        where_clause: &str,
+        compact_json: bool,
     ) -> anyhow::Result<()> {
         // Export CODE entity edges (production code)
-        let code_filter = if where_clause == "ALL" {
-            "entity_class = 'CODE'".to_string()
-        } else {
-            format!("entity_class = 'CODE', {}", where_clause)
-        };
+        let code_filter = scope_to_entity_class("CODE", where_clause);
         let code_output = format!("{}.json", output_name);
         
         let config = ExportConfig {
@@ -66,17 +87,14 @@ impl Level0Exporter {
             level: 0,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let code_result = self.export(repository, &config).await?;
-        code_result.write_to_file(&code_output)?;
+        let code_result = self.export_to(repository, &config, sink).await?;
+        code_result.write_to_sink(sink, &code_output, compact_json).await?;
         
         // Export TEST entity edges (test code)
-        let test_filter = if where_clause == "ALL" {
-            "entity_class = 'TEST'".to_string()
-        } else {
-            format!("entity_class = 'TEST', {}", where_clause)
-        };
+        let test_filter = scope_to_entity_class("TEST", where_clause);
         let test_output = format!("{}_test.json", output_name);
         
         let test_config = ExportConfig {
@@ -87,10 +105,11 @@ impl Level0Exporter {
             level: 0,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let test_result = self.export(repository, &test_config).await?;
-        test_result.write_to_file(&test_output)?;
+        let test_result = self.export_to(repository, &test_config, sink).await?;
+        test_result.write_to_sink(sink, &test_output, compact_json).await?;
         
         Ok(())
     }
@@ -104,10 +123,11 @@ impl Default for Level0Exporter {
 
 #[async_trait]
 impl LevelExporter for Level0Exporter {
-    async fn export(
+    async fn export_to(
         &self,
         db: &dyn CodeGraphRepository,
         config: &ExportConfig,
+        sink: &dyn OutputSink,
     ) -> Result<ExportOutput> {
         // Phase 3 (GREEN): Minimal implementation to make tests pass
 
@@ -119,7 +139,7 @@ impl LevelExporter for Level0Exporter {
         };
 
         // 2. Convert to DependencyEdge format
-        let dependency_edges: Vec<DependencyEdge> = edges
+        let mut dependency_edges: Vec<DependencyEdge> = edges
             .into_iter()
             .map(|edge| DependencyEdge {
                 from_key: edge.from_key,
@@ -127,6 +147,10 @@ impl LevelExporter for Level0Exporter {
                 edge_type: edge.edge_type,
             })
             .collect();
+        // Pinned order: identical graphs export identically
+        dependency_edges.sort_by(|a, b| {
+            (&a.from_key, &a.to_key, &a.edge_type).cmp(&(&b.from_key, &b.to_key, &b.edge_type))
+        });
 
         // 3. Count edges for metadata
         let total_edges = dependency_edges.len();
@@ -135,20 +159,20 @@ impl LevelExporter for Level0Exporter {
         use parseltongue_core::serializers::{Serializer, JsonSerializer, ToonSerializer};
 
         // JSON serializer
-        let json_serializer = JsonSerializer::new();
+        let json_serializer = JsonSerializer::with_compact(config.compact_json);
         let json_content = json_serializer.serialize(&dependency_edges)?;
-        std::fs::write(&config.output_path, &json_content)?;
+        sink.write_all(&config.output_path.to_string_lossy(), json_content.as_bytes()).await?;
 
         // TOON serializer (automatically handles empty arrays)
         let toon_serializer = ToonSerializer::new();
         let toon_path = config.output_path.with_extension(toon_serializer.extension());
         let toon_content = toon_serializer.serialize(&dependency_edges)?;
-        std::fs::write(&toon_path, &toon_content)?;
+        sink.write_all(&toon_path.to_string_lossy(), toon_content.as_bytes()).await?;
 
         // 5. Build metadata
         let metadata = ExportMetadata {
             level: 0,
-            timestamp: Utc::now().to_rfc3339(),
+            timestamp: export_timestamp(self.include_timestamp),
             total_entities: None,  // Level 0 has no entities
             total_edges: Some(total_edges),
             include_code: None,    // N/A for Level 0
@@ -160,6 +184,8 @@ impl LevelExporter for Level0Exporter {
             export_metadata: metadata,
             entities: None,                    // Level 0 has no entities
             edges: Some(dependency_edges),     // Only edges
+            shared_docs: None,
+            cycles: None,
         })
     }
 
@@ -232,6 +258,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
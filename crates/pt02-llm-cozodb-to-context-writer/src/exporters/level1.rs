@@ -31,6 +31,8 @@
 //!
 //! ### Optional (Expensive)
 //! - current_code: Full implementation (only if config.include_code = true)
+//! - provenance: Last writing tool (only if the repository opts in, e.g.
+//!   `CozoDbAdapter::with_provenance`)
 //!
 //! ## Token Estimates
 //! - Without code: ~30K tokens for 590 entities
@@ -40,17 +42,64 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
+use futures::TryStreamExt;
+use parseltongue_core::output_sink::{FileSink, OutputSink};
 
+use crate::doc_dedup::dedup_docs;
 use crate::export_trait::{CodeGraphRepository, LevelExporter};
-use crate::models::{EntityExportLevel1, ExportConfig, ExportMetadata, ExportOutput};
+use crate::external_sort::sort_for_export;
+use crate::models::{export_timestamp, EntityExportLevel1, ExportConfig, ExportMetadata, ExportOutput};
+use crate::ordering::{sort_topologically, ExportOrder};
+use crate::query_builder::scope_to_entity_class;
+use crate::test_pairing::{pair_tests, with_test_keys, NamingConventionMatcher, TestMatcher};
 
 /// Level 1 Exporter: Node-centric + ISG + Temporal state
-pub struct Level1Exporter;
+pub struct Level1Exporter {
+    include_timestamp: bool,
+    dedup_docs: bool,
+    order: ExportOrder,
+    test_matcher: Option<Box<dyn TestMatcher>>,
+}
 
 impl Level1Exporter {
     pub fn new() -> Self {
-        Self
+        Self { include_timestamp: true, dedup_docs: false, order: ExportOrder::Key, test_matcher: None }
+    }
+
+    /// Stamp `export_metadata.timestamp` (default: true)
+    ///
+    /// Without it, exporting the same data twice writes byte-identical files.
+    pub fn with_timestamp(mut self, include_timestamp: bool) -> Self {
+        self.include_timestamp = include_timestamp;
+        self
+    }
+
+    /// Emit repeated doc comments once in `shared_docs` (see `doc_dedup`)
+    pub fn with_dedup_docs(mut self, dedup_docs: bool) -> Self {
+        self.dedup_docs = dedup_docs;
+        self
+    }
+
+    /// Entity order of the export (default: key order, see `ordering`)
+    pub fn with_order(mut self, order: ExportOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Attach each entity's tests, paired by naming convention (see
+    /// `test_pairing`)
+    pub fn with_pair_tests(self, pair_tests: bool) -> Self {
+        if pair_tests {
+            self.with_test_matcher(NamingConventionMatcher)
+        } else {
+            Self { test_matcher: None, ..self }
+        }
+    }
+
+    /// Attach each entity's tests as paired by `matcher`
+    pub fn with_test_matcher(mut self, matcher: impl TestMatcher + 'static) -> Self {
+        self.test_matcher = Some(Box::new(matcher));
+        self
     }
 
     /// REQ-V090-004.0: Export dual files (CODE and TEST) from single output name
@@ -64,6 +113,7 @@ impl Level1Exporter {
     /// * `output_name` - Base name for both files
     /// * `include_code` - Whether to include full implementation code
     /// * `where_clause` - Datalog WHERE clause for filtering
+    /// * `compact_json` - Write single-line JSON instead of pretty-printed
     /// 
     /// # Returns
     /// `Result<()>` - Structured error handling with thiserror
@@ -73,13 +123,23 @@ impl Level1Exporter {
         output_name: &str,
         include_code: bool,
         where_clause: &str,
+        compact_json: bool,
+    ) -> anyhow::Result<()> {
+        self.export_dual_files_to(repository, &FileSink, output_name, include_code, where_clause, compact_json).await
+    }
+
+    /// Same as `export_dual_files`, writing every artifact through `sink`
+    pub async fn export_dual_files_to(
+        &self,
+        repository: &dyn CodeGraphRepository,
+        sink: &dyn OutputSink,
+        output_name: &str,
+        include_code: bool,
+        where_clause: &str,
+        compact_json: bool,
     ) -> anyhow::Result<()> {
         // Export CODE entities (production code)
-        let code_filter = if where_clause == "ALL" {
-            "entity_class = 'CODE'".to_string()
-        } else {
-            format!("entity_class = 'CODE', {}", where_clause)
-        };
+        let code_filter = scope_to_entity_class("CODE", where_clause);
         let code_output = format!("{}.json", output_name);
         
         let config = ExportConfig {
@@ -90,17 +150,14 @@ impl Level1Exporter {
             level: 1,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let code_result = self.export(repository, &config).await?;
-        code_result.write_to_file(&code_output)?;
+        let code_result = self.export_to(repository, &config, sink).await?;
+        code_result.write_to_sink(sink, &code_output, compact_json).await?;
         
         // Export TEST entities (test code)
-        let test_filter = if where_clause == "ALL" {
-            "entity_class = 'TEST'".to_string()
-        } else {
-            format!("entity_class = 'TEST', {}", where_clause)
-        };
+        let test_filter = scope_to_entity_class("TEST", where_clause);
         let test_output = format!("{}_test.json", output_name);
         
         let test_config = ExportConfig {
@@ -111,18 +168,20 @@ impl Level1Exporter {
             level: 1,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let test_result = self.export(repository, &test_config).await?;
-        test_result.write_to_file(&test_output)?;
+        let test_result = self.export_to(repository, &test_config, sink).await?;
+        test_result.write_to_sink(sink, &test_output, compact_json).await?;
         
         Ok(())
     }
 
     /// Convert Entity to EntityExportLevel1 with null-skipping
-    fn convert_entity(
+    pub(crate) fn convert_entity(
         entity: &crate::export_trait::Entity,
         include_code: bool,
+        provenance: Option<String>,
     ) -> EntityExportLevel1 {
         EntityExportLevel1 {
             isgl1_key: entity.isgl1_key.clone(),
@@ -151,6 +210,10 @@ impl Level1Exporter {
             // v0.9.0: Include entity_class for code/test separation
             entity_class: entity.entity_class.clone(),
             doc_comment: entity.doc_comment.clone(),
+            provenance,
+            is_generated: entity.is_generated,
+            complexity_score: entity.complexity_score,
+            tests: Vec::new(),
         }
     }
 }
@@ -163,38 +226,60 @@ impl Default for Level1Exporter {
 
 #[async_trait]
 impl LevelExporter for Level1Exporter {
-    async fn export(
+    async fn export_to(
         &self,
         db: &dyn CodeGraphRepository,
         config: &ExportConfig,
+        sink: &dyn OutputSink,
     ) -> Result<ExportOutput> {
         // Phase 3 (GREEN): Minimal implementation to make tests pass
 
-        // 1. Query entities from database
-        let entities = if config.where_filter == "ALL" {
-            db.get_all_entities().await?
+        // 1. Stream entities from database, converting each to Level1 format
+        let provenance = db.get_provenance().await?;
+        let mut code_level1_entities: Vec<EntityExportLevel1> = Vec::new();
+        let mut test_level1_entities: Vec<EntityExportLevel1> = Vec::new();
+        let mut total_entities = 0;
+        let mut entities = db.entities_stream(&config.where_filter);
+        while let Some(entity) = entities.try_next().await? {
+            total_entities += 1;
+            let exported = Self::convert_entity(&entity, config.include_code, provenance.get(&entity.isgl1_key).cloned());
+            // v0.9.0: Separate entities by EntityClass for dual output
+            if entity.entity_class == "CODE" {
+                code_level1_entities.push(exported);
+            } else {
+                test_level1_entities.push(exported);
+            }
+        }
+
+        // Same order as `--external-sort`, independent of database row order
+        sort_for_export(&mut code_level1_entities);
+        sort_for_export(&mut test_level1_entities);
+        let cycles = if self.order == ExportOrder::Topological {
+            let edges = db.get_all_edges().await?;
+            sort_topologically(&mut test_level1_entities, |e| e.isgl1_key.as_str(), &edges);
+            Some(sort_topologically(&mut code_level1_entities, |e| e.isgl1_key.as_str(), &edges))
+                .filter(|cycles| !cycles.is_empty())
         } else {
-            db.query_entities(&config.where_filter).await?
+            None
         };
 
-        // v0.9.0: Separate entities by EntityClass for dual output
-        let (code_entities, test_entities): (Vec<_>, Vec<_>) = entities
-            .iter()
-            .partition(|e| e.entity_class == "CODE");
-
-        // 2. Convert to Level1 format (separate for code and tests)
-        let code_level1_entities: Vec<EntityExportLevel1> = code_entities
-            .iter()
-            .map(|e| Self::convert_entity(e, config.include_code))
-            .collect();
-        
-        let test_level1_entities: Vec<EntityExportLevel1> = test_entities
-            .iter()
-            .map(|e| Self::convert_entity(e, config.include_code))
-            .collect();
+        // Tests may live outside the exported filter, so look them up in full;
+        // the `_test` export of `export_dual_files` has nothing to pair
+        let scope = scope_to_entity_class("TEST", "ALL");
+        let exporting_tests =
+            config.where_filter == scope || config.where_filter.starts_with(&format!("{}, ", scope));
+        if let Some(matcher) = self.test_matcher.as_ref().filter(|_| !exporting_tests) {
+            let mut tests: Vec<EntityExportLevel1> = db
+                .entities_stream(&scope)
+                .try_filter(|e| futures::future::ready(e.entity_class == "TEST"))
+                .map_ok(|e| Self::convert_entity(&e, config.include_code, None))
+                .try_collect()
+                .await?;
+            sort_for_export(&mut tests);
+            pair_tests(&mut code_level1_entities, &tests, matcher.as_ref());
+        }
 
         // 3. Count entities for metadata
-        let total_entities = entities.len();
         let _code_entities_count = code_level1_entities.len();
         let _test_entities_count = test_level1_entities.len();
 
@@ -228,20 +313,28 @@ impl LevelExporter for Level1Exporter {
         use parseltongue_core::serializers::{Serializer, JsonSerializer, ToonSerializer};
 
         // JSON serializer
-        let json_serializer = JsonSerializer::new();
-        let json_content = json_serializer.serialize(&code_level1_entities)?;
-        std::fs::write(&config.output_path, &json_content)?;
+        let json_serializer = JsonSerializer::with_compact(config.compact_json);
+        let deduped = if self.dedup_docs { Some(dedup_docs(&code_level1_entities)?) } else { None };
+        let json_content = match &deduped {
+            Some(deduped) => json_serializer.to_json_string(deduped)?,
+            None => json_serializer.serialize(&code_level1_entities)?,
+        };
+        sink.write_all(&config.output_path.to_string_lossy(), json_content.as_bytes()).await?;
 
         // TOON serializer (automatically handles empty arrays)
         let toon_serializer = ToonSerializer::new();
         let toon_path = config.output_path.with_extension(toon_serializer.extension());
-        let toon_content = toon_serializer.serialize(&code_level1_entities)?;
-        std::fs::write(&toon_path, &toon_content)?;
+        let toon_content = if self.test_matcher.is_some() {
+            toon_serializer.serialize(&with_test_keys(&code_level1_entities)?)?
+        } else {
+            toon_serializer.serialize(&code_level1_entities)?
+        };
+        sink.write_all(&toon_path.to_string_lossy(), toon_content.as_bytes()).await?;
 
         // 5. Build metadata with EntityClass information
         let metadata = ExportMetadata {
             level: 1,
-            timestamp: Utc::now().to_rfc3339(),
+            timestamp: export_timestamp(self.include_timestamp),
             total_entities: Some(total_entities),
             total_edges: None,  // Level 1 has no edges
             include_code: Some(config.include_code),
@@ -251,8 +344,13 @@ impl LevelExporter for Level1Exporter {
         // 6. Build output (v0.9.0: support dual outputs)
         Ok(ExportOutput {
             export_metadata: metadata,
-            entities: Some(serde_json::to_value(&code_level1_entities)?), // Primary output
+            entities: Some(match &deduped {
+                Some(deduped) => serde_json::to_value(&deduped.entities)?,
+                None => serde_json::to_value(&code_level1_entities)?,
+            }),
             edges: None,  // Level 1 has no edges
+            shared_docs: deduped.map(|deduped| deduped.shared_docs),
+            cycles,
         })
     }
 
@@ -342,6 +440,9 @@ mod tests {
             is_public: Some(true),
             is_async: None,
             is_unsafe: None,
+            lsp_metadata: None,
+            is_generated: false,
+            complexity_score: None,
         }
     }
 
@@ -359,6 +460,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
@@ -393,6 +495,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
@@ -416,6 +519,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
@@ -23,6 +23,9 @@
 //! - is_async: Async function flag
 //! - is_unsafe: Unsafe code flag
 //!
+//! ### LSP (when ingested with rust-analyzer)
+//! - hover, definition_location, references_count, diagnostics
+//!
 //! ## Token Estimates
 //! - Without code: ~60K tokens for 590 entities
 //! - With code: ~500-700K tokens (expensive)
@@ -37,17 +40,45 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
+use futures::TryStreamExt;
+use parseltongue_core::output_sink::{FileSink, OutputSink};
 
+use crate::doc_dedup::dedup_docs;
 use crate::export_trait::{CodeGraphRepository, LevelExporter};
-use crate::models::{EntityExportLevel2, ExportConfig, ExportMetadata, ExportOutput};
+use crate::models::{export_timestamp, EntityExportLevel2, ExportConfig, ExportMetadata, ExportOutput};
+use crate::ordering::{sort_topologically, ExportOrder};
+use crate::query_builder::scope_to_entity_class;
 
 /// Level 2 Exporter: Type system essentials
-pub struct Level2Exporter;
+pub struct Level2Exporter {
+    include_timestamp: bool,
+    dedup_docs: bool,
+    order: ExportOrder,
+}
 
 impl Level2Exporter {
     pub fn new() -> Self {
-        Self
+        Self { include_timestamp: true, dedup_docs: false, order: ExportOrder::Key }
+    }
+
+    /// Stamp `export_metadata.timestamp` (default: true)
+    ///
+    /// Without it, exporting the same data twice writes byte-identical files.
+    pub fn with_timestamp(mut self, include_timestamp: bool) -> Self {
+        self.include_timestamp = include_timestamp;
+        self
+    }
+
+    /// Emit repeated doc comments once in `shared_docs` (see `doc_dedup`)
+    pub fn with_dedup_docs(mut self, dedup_docs: bool) -> Self {
+        self.dedup_docs = dedup_docs;
+        self
+    }
+
+    /// Entity order of the export (default: key order, see `ordering`)
+    pub fn with_order(mut self, order: ExportOrder) -> Self {
+        self.order = order;
+        self
     }
 
     /// REQ-V090-004.0: Export dual files (CODE and TEST) from single output name
@@ -61,6 +92,7 @@ impl Level2Exporter {
     /// * `output_name` - Base name for both files
     /// * `include_code` - Whether to include full implementation code
     /// * `where_clause` - Datalog WHERE clause for filtering
+    /// * `compact_json` - Write single-line JSON instead of pretty-printed
     /// 
     /// # Returns
     /// `Result<()>` - Structured error handling with thiserror
@@ -70,13 +102,23 @@ impl Level2Exporter {
         output_name: &str,
         include_code: bool,
         where_clause: &str,
+        compact_json: bool,
+    ) -> anyhow::Result<()> {
+        self.export_dual_files_to(repository, &FileSink, output_name, include_code, where_clause, compact_json).await
+    }
+
+    /// Same as `export_dual_files`, writing every artifact through `sink`
+    pub async fn export_dual_files_to(
+        &self,
+        repository: &dyn CodeGraphRepository,
+        sink: &dyn OutputSink,
+        output_name: &str,
+        include_code: bool,
+        where_clause: &str,
+        compact_json: bool,
     ) -> anyhow::Result<()> {
         // Export CODE entities (production code)
-        let code_filter = if where_clause == "ALL" {
-            "entity_class = 'CODE'".to_string()
-        } else {
-            format!("entity_class = 'CODE', {}", where_clause)
-        };
+        let code_filter = scope_to_entity_class("CODE", where_clause);
         let code_output = format!("{}.json", output_name);
         
         let config = ExportConfig {
@@ -87,17 +129,14 @@ impl Level2Exporter {
             level: 2,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let code_result = self.export(repository, &config).await?;
-        code_result.write_to_file(&code_output)?;
+        let code_result = self.export_to(repository, &config, sink).await?;
+        code_result.write_to_sink(sink, &code_output, compact_json).await?;
         
         // Export TEST entities (test code)
-        let test_filter = if where_clause == "ALL" {
-            "entity_class = 'TEST'".to_string()
-        } else {
-            format!("entity_class = 'TEST', {}", where_clause)
-        };
+        let test_filter = scope_to_entity_class("TEST", where_clause);
         let test_output = format!("{}_test.json", output_name);
         
         let test_config = ExportConfig {
@@ -108,10 +147,11 @@ impl Level2Exporter {
             level: 2,
             code_output_path: None,
             tests_output_path: None,
+            compact_json,
         };
         
-        let test_result = self.export(repository, &test_config).await?;
-        test_result.write_to_file(&test_output)?;
+        let test_result = self.export_to(repository, &test_config, sink).await?;
+        test_result.write_to_sink(sink, &test_output, compact_json).await?;
         
         Ok(())
     }
@@ -120,7 +160,9 @@ impl Level2Exporter {
     fn convert_entity(
         entity: &crate::export_trait::Entity,
         include_code: bool,
+        provenance: Option<String>,
     ) -> EntityExportLevel2 {
+        let lsp = entity.lsp_metadata.as_ref();
         EntityExportLevel2 {
             // Level 1 fields (inherited)
             isgl1_key: entity.isgl1_key.clone(),
@@ -159,6 +201,15 @@ impl Level2Exporter {
             is_public: entity.is_public.unwrap_or(false),
             is_async: entity.is_async.unwrap_or(false),
             is_unsafe: entity.is_unsafe.unwrap_or(false),
+
+            // LSP fields (typed, from lsp_meta_data)
+            hover: lsp.and_then(|lsp| lsp.hover.clone()),
+            definition_location: lsp.and_then(|lsp| lsp.definition_location().cloned()),
+            references_count: lsp.map(|lsp| lsp.references_count()),
+            diagnostics: lsp.map(|lsp| lsp.diagnostics.clone()).unwrap_or_default(),
+            provenance,
+            is_generated: entity.is_generated,
+            complexity_score: entity.complexity_score,
         }
     }
 }
@@ -171,26 +222,33 @@ impl Default for Level2Exporter {
 
 #[async_trait]
 impl LevelExporter for Level2Exporter {
-    async fn export(
+    async fn export_to(
         &self,
         db: &dyn CodeGraphRepository,
         config: &ExportConfig,
+        sink: &dyn OutputSink,
     ) -> Result<ExportOutput> {
         // Phase 4 (GREEN): Minimal implementation to make tests pass
 
-        // 1. Query entities from database
-        let entities = if config.where_filter == "ALL" {
-            db.get_all_entities().await?
+        // 1. Stream entities from database, converting each to Level2 format
+        let provenance = db.get_provenance().await?;
+        let mut level2_entities: Vec<EntityExportLevel2> = db
+            .entities_stream(&config.where_filter)
+            .map_ok(|e| Self::convert_entity(&e, config.include_code, provenance.get(&e.isgl1_key).cloned()))
+            .try_collect()
+            .await?;
+        // Level 1 export order (file, line, key), independent of database row order
+        level2_entities.sort_by(|a, b| {
+            (&a.file_path, a.line_number, &a.isgl1_key).cmp(&(&b.file_path, b.line_number, &b.isgl1_key))
+        });
+        let cycles = if self.order == ExportOrder::Topological {
+            let edges = db.get_all_edges().await?;
+            Some(sort_topologically(&mut level2_entities, |e| e.isgl1_key.as_str(), &edges))
+                .filter(|cycles| !cycles.is_empty())
         } else {
-            db.query_entities(&config.where_filter).await?
+            None
         };
 
-        // 2. Convert to Level2 format
-        let level2_entities: Vec<EntityExportLevel2> = entities
-            .iter()
-            .map(|e| Self::convert_entity(e, config.include_code))
-            .collect();
-
         // 3. Count entities for metadata
         let total_entities = level2_entities.len();
 
@@ -198,20 +256,24 @@ impl LevelExporter for Level2Exporter {
         use parseltongue_core::serializers::{Serializer, JsonSerializer, ToonSerializer};
 
         // JSON serializer
-        let json_serializer = JsonSerializer::new();
-        let json_content = json_serializer.serialize(&level2_entities)?;
-        std::fs::write(&config.output_path, &json_content)?;
+        let json_serializer = JsonSerializer::with_compact(config.compact_json);
+        let deduped = if self.dedup_docs { Some(dedup_docs(&level2_entities)?) } else { None };
+        let json_content = match &deduped {
+            Some(deduped) => json_serializer.to_json_string(deduped)?,
+            None => json_serializer.serialize(&level2_entities)?,
+        };
+        sink.write_all(&config.output_path.to_string_lossy(), json_content.as_bytes()).await?;
 
         // TOON serializer (automatically handles empty arrays)
         let toon_serializer = ToonSerializer::new();
         let toon_path = config.output_path.with_extension(toon_serializer.extension());
         let toon_content = toon_serializer.serialize(&level2_entities)?;
-        std::fs::write(&toon_path, &toon_content)?;
+        sink.write_all(&toon_path.to_string_lossy(), toon_content.as_bytes()).await?;
 
         // 5. Build metadata
         let metadata = ExportMetadata {
             level: 2,
-            timestamp: Utc::now().to_rfc3339(),
+            timestamp: export_timestamp(self.include_timestamp),
             total_entities: Some(total_entities),
             total_edges: None,  // Level 2 has no edges
             include_code: Some(config.include_code),
@@ -221,8 +283,13 @@ impl LevelExporter for Level2Exporter {
         // 6. Build output (v0.10.0: dual format support)
         Ok(ExportOutput {
             export_metadata: metadata,
-            entities: Some(serde_json::to_value(&level2_entities)?),
+            entities: Some(match &deduped {
+                Some(deduped) => serde_json::to_value(&deduped.entities)?,
+                None => serde_json::to_value(&level2_entities)?,
+            }),
             edges: None,  // Level 2 has no edges
+            shared_docs: deduped.map(|deduped| deduped.shared_docs),
+            cycles,
         })
     }
 
@@ -312,6 +379,9 @@ mod tests {
             is_public: Some(true),
             is_async: Some(true),
             is_unsafe: Some(false),
+            lsp_metadata: None,
+            is_generated: false,
+            complexity_score: None,
         }
     }
 
@@ -329,6 +399,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
@@ -361,6 +432,7 @@ mod tests {
             // v0.9.0: Dual outputs for code/test separation (None for tests)
             code_output_path: None,
             tests_output_path: None,
+            compact_json: false,
             db_path: "mem".to_string(),
         };
 
@@ -0,0 +1,260 @@
+//! Deterministic Level 1 export for graphs too large to sort in memory
+//! (`--external-sort`).
+//!
+//! Entities are paged from the repository in ISGL1 key ranges
+//! (`CodeGraphRepository::entities_after`). Each page is sorted into export
+//! order and written to a temporary run file, and the runs are then k-way
+//! merged straight into the output. Memory holds one page, plus one entity
+//! per run during the merge. Run files are removed by `FileHandleGuard`,
+//! whether the export succeeds or not.
+//!
+//! Export order is `file_path`, then `line_number`, then `isgl1_key`;
+//! `sort_for_export` applies the same order in memory, so both paths write
+//! identical entity lists.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+use anyhow::{Context, Result};
+
+use crate::export_trait::CodeGraphRepository;
+use crate::exporters::Level1Exporter;
+use crate::models::{export_timestamp, EntityExportLevel1, ExportMetadata};
+
+/// Entities per sorted run
+pub const DEFAULT_RUN_SIZE: usize = 50_000;
+
+/// Entity count above which pt02-level01 sorts externally without
+/// `--external-sort`
+pub const AUTO_EXTERNAL_SORT_ROWS: usize = 500_000;
+
+/// Export order: file, then line, then key
+pub fn export_order(a: &EntityExportLevel1, b: &EntityExportLevel1) -> Ordering {
+    (&a.file_path, a.line_number, &a.isgl1_key).cmp(&(&b.file_path, b.line_number, &b.isgl1_key))
+}
+
+/// Sort `entities` into export order in memory
+pub fn sort_for_export(entities: &mut [EntityExportLevel1]) {
+    entities.sort_by(export_order);
+}
+
+/// Removes the file at its path when dropped
+pub struct FileHandleGuard {
+    path: PathBuf,
+}
+
+impl FileHandleGuard {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileHandleGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Level 1 exporter that sorts through temporary run files
+pub struct ExternalSortExporter {
+    run_size: usize,
+    temp_dir: PathBuf,
+    include_timestamp: bool,
+}
+
+impl Default for ExternalSortExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExternalSortExporter {
+    pub fn new() -> Self {
+        Self {
+            run_size: DEFAULT_RUN_SIZE,
+            temp_dir: std::env::temp_dir(),
+            include_timestamp: true,
+        }
+    }
+
+    /// Entities per run (and per page read from the repository)
+    pub fn with_run_size(mut self, run_size: usize) -> Self {
+        self.run_size = run_size.max(1);
+        self
+    }
+
+    /// Directory for run files (default: the system temp directory)
+    pub fn with_temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Stamp `export_metadata.timestamp` (default: true)
+    pub fn with_timestamp(mut self, include_timestamp: bool) -> Self {
+        self.include_timestamp = include_timestamp;
+        self
+    }
+
+    /// Export the entities matching `where_clause` to `out` in export order
+    ///
+    /// Writes the same document shape as `Level1Exporter` (`export_metadata`
+    /// plus an `entities` array) and returns the number of entities written.
+    pub async fn export_level1(
+        &self,
+        repository: &dyn CodeGraphRepository,
+        where_clause: &str,
+        include_code: bool,
+        out: &mut dyn Write,
+    ) -> Result<usize> {
+        let provenance = repository.get_provenance().await?;
+        let runs = self.write_runs(repository, where_clause, include_code, &provenance).await?;
+        let total = runs.iter().map(|(_, len)| len).sum();
+
+        let metadata = ExportMetadata {
+            level: 1,
+            timestamp: export_timestamp(self.include_timestamp),
+            total_edges: None,
+            total_entities: Some(total),
+            include_code: Some(include_code),
+            where_filter: where_clause.to_string(),
+        };
+        write!(out, "{{\"export_metadata\":{},\"entities\":[", serde_json::to_string(&metadata)?)?;
+        let guards: Vec<FileHandleGuard> = runs.into_iter().map(|(guard, _)| guard).collect();
+        merge_runs(&guards, out)?;
+        writeln!(out, "]}}")?;
+        out.flush()?;
+        Ok(total)
+    }
+
+    /// Page through the repository, writing one sorted run per page
+    async fn write_runs(
+        &self,
+        repository: &dyn CodeGraphRepository,
+        where_clause: &str,
+        include_code: bool,
+        provenance: &HashMap<String, String>,
+    ) -> Result<Vec<(FileHandleGuard, usize)>> {
+        let mut runs = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let page = repository.entities_after(where_clause, after.as_deref(), self.run_size).await?;
+            let Some(last) = page.last() else { break };
+            after = Some(last.isgl1_key.clone());
+            let full_page = page.len() == self.run_size;
+
+            let mut entities: Vec<EntityExportLevel1> = page
+                .iter()
+                .map(|e| Level1Exporter::convert_entity(e, include_code, provenance.get(&e.isgl1_key).cloned()))
+                .collect();
+            sort_for_export(&mut entities);
+
+            let guard = FileHandleGuard::new(self.run_path());
+            let file = File::create(guard.path())
+                .with_context(|| format!("Failed to create sort run {}", guard.path().display()))?;
+            let mut writer = BufWriter::new(file);
+            for entity in &entities {
+                serde_json::to_writer(&mut writer, entity)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+            runs.push((guard, entities.len()));
+
+            if !full_page {
+                break;
+            }
+        }
+        Ok(runs)
+    }
+
+    fn run_path(&self) -> PathBuf {
+        static NEXT_RUN: AtomicU64 = AtomicU64::new(0);
+        self.temp_dir.join(format!(
+            "pt02-sort-run-{}-{}.jsonl",
+            std::process::id(),
+            NEXT_RUN.fetch_add(1, AtomicOrdering::Relaxed)
+        ))
+    }
+}
+
+/// Smallest unread entity of one run
+struct RunHead {
+    entity: EntityExportLevel1,
+    run: usize,
+}
+
+impl PartialEq for RunHead {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RunHead {}
+
+impl PartialOrd for RunHead {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RunHead {
+    // Reversed: BinaryHeap is a max-heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        export_order(&other.entity, &self.entity).then(other.run.cmp(&self.run))
+    }
+}
+
+/// k-way merge of sorted runs into comma-separated JSON entities
+fn merge_runs(runs: &[FileHandleGuard], out: &mut dyn Write) -> Result<()> {
+    let mut readers: Vec<Lines<BufReader<File>>> = runs
+        .iter()
+        .map(|run| File::open(run.path()).map(|file| BufReader::new(file).lines()))
+        .collect::<std::io::Result<_>>()?;
+
+    let mut heap = BinaryHeap::new();
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(entity) = next_entity(reader)? {
+            heap.push(RunHead { entity, run });
+        }
+    }
+
+    let mut first = true;
+    while let Some(RunHead { entity, run }) = heap.pop() {
+        if !first {
+            out.write_all(b",")?;
+        }
+        first = false;
+        serde_json::to_writer(&mut *out, &entity)?;
+        if let Some(entity) = next_entity(&mut readers[run])? {
+            heap.push(RunHead { entity, run });
+        }
+    }
+    Ok(())
+}
+
+fn next_entity(reader: &mut Lines<BufReader<File>>) -> Result<Option<EntityExportLevel1>> {
+    match reader.next() {
+        Some(line) => Ok(Some(serde_json::from_str(&line?)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_removes_its_file() {
+        let path = std::env::temp_dir().join(format!("pt02-guard-test-{}", std::process::id()));
+        std::fs::write(&path, "run").unwrap();
+        drop(FileHandleGuard::new(path.clone()));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,144 @@
+//! Side-by-side comparison of the three export levels.
+//!
+//! Runs Level 0, 1 and 2 against the same filter and reports what each one
+//! would cost, without writing any files. Helps new users pick a level:
+//!
+//! ```text
+//! Level  Entities  Edges   ~Tokens   Adds
+//! 0      -         42      1260      from_key, to_key, edge_type
+//! 1      17        -       4980      isgl1_key, forward_deps, ...
+//! 2      17        -       6115      return_type, param_types, ...
+//! ```
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use parseltongue_core::output_sink::OutputSink;
+
+use crate::export_trait::{CodeGraphRepository, LevelExporter};
+use crate::exporters::{Level0Exporter, Level1Exporter, Level2Exporter};
+use crate::models::ExportConfig;
+
+/// Fields Level 0 exports per edge
+pub const LEVEL0_FIELDS: &[&str] = &["from_key", "to_key", "edge_type"];
+
+/// Fields Level 1 adds: one record per entity instead of per edge
+pub const LEVEL1_ADDED_FIELDS: &[&str] = &[
+    "isgl1_key",
+    "forward_deps",
+    "reverse_deps",
+    "current_ind",
+    "future_ind",
+    "future_action",
+    "current_code",
+    "future_code",
+    "entity_name",
+    "entity_type",
+    "file_path",
+    "line_number",
+    "interface_signature",
+    "entity_class",
+    "doc_comment",
+];
+
+/// Fields Level 2 adds on top of Level 1
+pub const LEVEL2_ADDED_FIELDS: &[&str] = &[
+    "return_type",
+    "param_types",
+    "param_names",
+    "generic_constraints",
+    "trait_impls",
+    "is_public",
+    "is_async",
+    "is_unsafe",
+];
+
+/// Approximate characters per LLM token
+pub(crate) const CHARS_PER_TOKEN: usize = 4;
+
+/// What one level would export for the compared filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelSummary {
+    pub level: u8,
+    /// `None` for Level 0, which exports edges only
+    pub entity_count: Option<usize>,
+    /// `None` for Levels 1-2, which fold edges into per-entity dep lists
+    pub edge_count: Option<usize>,
+    /// Measured from the JSON the level would write
+    pub estimated_tokens: usize,
+    /// Fields this level introduces over the previous one
+    pub added_fields: &'static [&'static str],
+}
+
+/// Sink that measures JSON artifacts and drops everything
+#[derive(Default)]
+struct MeasuringSink {
+    json_bytes: Mutex<usize>,
+}
+
+#[async_trait]
+impl OutputSink for MeasuringSink {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> parseltongue_core::Result<()> {
+        if name.ends_with(".json") {
+            *self.json_bytes.lock().unwrap() += bytes.len();
+        }
+        Ok(())
+    }
+}
+
+/// Run every level against `where_filter` and summarise the results
+pub async fn compare_levels(
+    db: &dyn CodeGraphRepository,
+    where_filter: &str,
+    include_code: bool,
+) -> Result<Vec<LevelSummary>> {
+    let levels: [(Box<dyn LevelExporter>, &'static [&'static str]); 3] = [
+        (Box::new(Level0Exporter::new()), LEVEL0_FIELDS),
+        (Box::new(Level1Exporter::new()), LEVEL1_ADDED_FIELDS),
+        (Box::new(Level2Exporter::new()), LEVEL2_ADDED_FIELDS),
+    ];
+
+    let mut summaries = Vec::with_capacity(levels.len());
+    for (exporter, added_fields) in levels {
+        let config = ExportConfig {
+            level: exporter.level(),
+            include_code,
+            where_filter: where_filter.to_string(),
+            output_path: "compare-levels.json".into(),
+            db_path: String::new(),
+            code_output_path: None,
+            tests_output_path: None,
+            compact_json: true,
+        };
+        let sink = MeasuringSink::default();
+        let output = exporter.export_to(db, &config, &sink).await?;
+        let json_bytes = *sink.json_bytes.lock().unwrap();
+
+        summaries.push(LevelSummary {
+            level: exporter.level(),
+            entity_count: output.export_metadata.total_entities,
+            edge_count: output.export_metadata.total_edges,
+            estimated_tokens: json_bytes / CHARS_PER_TOKEN,
+            added_fields,
+        });
+    }
+    Ok(summaries)
+}
+
+/// Render summaries as a plain-text table
+pub fn format_comparison(summaries: &[LevelSummary]) -> String {
+    let count = |value: Option<usize>| value.map_or_else(|| "-".to_string(), |n| n.to_string());
+    let mut table = format!("{:<7}{:<10}{:<8}{:<10}{}\n", "Level", "Entities", "Edges", "~Tokens", "Adds");
+    for summary in summaries {
+        table.push_str(&format!(
+            "{:<7}{:<10}{:<8}{:<10}{}\n",
+            summary.level,
+            count(summary.entity_count),
+            count(summary.edge_count),
+            summary.estimated_tokens,
+            summary.added_fields.join(", "),
+        ));
+    }
+    table
+}
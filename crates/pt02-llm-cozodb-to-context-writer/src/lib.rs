@@ -73,13 +73,37 @@
 //! pt02-level02 --include-code 1 --where "future_action != null"
 //! ```
 //!
+//! ## Deterministic Output
+//!
+//! Exports do not depend on database row order: Level 0 edges are sorted by
+//! `(from_key, to_key, edge_type)` and Level 1-2 entities by
+//! `(file_path, line_number, isgl1_key)`, the `--external-sort` order. With
+//! `--no-timestamp` the `export_metadata.timestamp` field is left out too,
+//! so exporting unchanged data twice writes byte-identical files.
+//! `--order topological` reorders Level 1-2 entities dependencies-first
+//! from that starting point, so it is just as stable.
+//!
 //! ## Module Structure
 //!
 //! - `models`: Data structures (DependencyEdge, EntityExportLevel1/2, ExportConfig)
 //! - `export_trait`: LevelExporter trait contract
 //! - `cli`: Command-line interface with validation
+//! - `comment_stripping`: Comments removed from exported code (`--strip-comments`)
+//! - `context_export`: Level 1 as pt03's `CodeGraphContext` (`--as-context`)
+//! - `doc_dedup`: Repeated doc comments emitted once in `shared_docs` (`--dedup-docs`)
 //! - `exporters`: Level-specific exporters (level0, level1, level2)
-//! - `query_builder`: Datalog query composition
+//! - `external_sort`: Level 1 export sorted through temp-file runs (`--external-sort`)
+//! - `level_comparison`: Side-by-side cost report across all three levels
+//! - `manifest`: Parameters, counts and hashes of a dual-file export
+//! - `markdown_export`: `--format markdown` rendering next to each JSON file
+//! - `ordering`: Dependencies-first entity order (`--order topological`)
+//! - `query_builder`: Datalog query composition, including the typed `QueryBuilder`
+//! - `redaction`: Replace code with size + hash placeholders for sharing (`--redact`)
+//! - `sampling`: Deterministic hash-based entity sample (`--sample`)
+//! - `selection`: Export an explicit key list (`--keys`), optionally with dependencies
+//! - `since`: Only entities modified after a time or git commit (`--since`, `--since-commit`)
+//! - `skeleton`: Interface-only file skeleton (signatures + docs, no bodies)
+//! - `test_pairing`: Each entity's tests attached to it (`--pair-tests`)
 //! - `errors`: Error types (thiserror for library errors)
 
 #![warn(clippy::all)]
@@ -87,12 +111,26 @@
 #![allow(missing_docs)]
 
 pub mod cli;
+pub mod comment_stripping;
+pub mod context_export;
 pub mod cozodb_adapter;
+pub mod doc_dedup;
 pub mod errors;
 pub mod export_trait;
 pub mod exporters;
+pub mod external_sort;
+pub mod level_comparison;
+pub mod manifest;
+pub mod markdown_export;
 pub mod models;
+pub mod ordering;
 pub mod query_builder;
+pub mod redaction;
+pub mod sampling;
+pub mod selection;
+pub mod since;
+pub mod skeleton;
+pub mod test_pairing;
 
 // v0.9.0: EntityClass integration tests (executable specifications)
 #[cfg(test)]
@@ -100,15 +138,29 @@ pub mod entity_class_integration_tests;
 
 // Re-export commonly used types
 pub use cli::Cli;
+pub use comment_stripping::CommentStrippedRepository;
+pub use context_export::{build_code_graph_context, export_code_graph_context};
 pub use cozodb_adapter::CozoDbAdapter;
+pub use doc_dedup::{dedup_docs, DedupedEntities};
 pub use errors::*;
 pub use export_trait::{CodeGraphRepository, Edge, Entity, LevelExporter};
 pub use exporters::{Level0Exporter, Level1Exporter, Level2Exporter};
+pub use external_sort::{sort_for_export, ExternalSortExporter, FileHandleGuard, AUTO_EXTERNAL_SORT_ROWS};
+pub use level_comparison::{compare_levels, format_comparison, LevelSummary};
+pub use manifest::{manifest_name, ExportManifest, ManifestFile, ManifestRecorder};
+pub use markdown_export::{markdown_name, ExportFormat, MarkdownSink};
 pub use models::{
     DependencyEdge, EntityExportLevel1, EntityExportLevel2, ExportConfig, ExportMetadata,
-    ExportOutput,
+    ExportOutput, PairedTest,
 };
+pub use ordering::{sort_topologically, ExportOrder};
 pub use query_builder::*;
+pub use redaction::{redact, RedactedRepository};
+pub use sampling::{EntitySampler, SampleSize, SampleStratum, SamplingInfo};
+pub use selection::{KeySelection, SelectedRepository};
+pub use since::{commit_time, parse_since, since_condition, with_since};
+pub use skeleton::render_file_skeleton;
+pub use test_pairing::{pair_tests, NamingConventionMatcher, TestMatcher};
 
 // v0.10.0: TOON serialization now in parseltongue-core
 // Use: parseltongue_core::serializers::{ToonSerializer, ToonDelimiter}
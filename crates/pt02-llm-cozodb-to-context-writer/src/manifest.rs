@@ -0,0 +1,175 @@
+//! Manifest tying together the files of one dual-file export.
+//!
+//! Every pt02 export writes `{output}.json`, `{output}_test.json` and their
+//! TOON twins. `{output}.manifest.json` records how they were produced and
+//! what they contain, so downstream tools can check integrity and re-run the
+//! export:
+//!
+//! ```json
+//! {
+//!   "tool_version": "0.9.6",
+//!   "level": 1,
+//!   "where_filter": "ALL",
+//!   "include_code": false,
+//!   "db_path": "rocksdb:parseltongue.db",
+//!   "total_entities": 42,
+//!   "files": [
+//!     { "name": "context.json", "sha256": "9f2c...", "bytes": 18211, "entities": 30 },
+//!     { "name": "context.toon", "sha256": "01ab...", "bytes": 9120 }
+//!   ]
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use parseltongue_core::output_sink::OutputSink;
+use parseltongue_core::serializers::JsonSerializer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::ExportMetadata;
+use crate::sampling::SamplingInfo;
+
+/// Name of the manifest for the export whose base output name is `output_name`
+pub fn manifest_name(output_name: &str) -> String {
+    format!("{}.manifest.json", output_name)
+}
+
+/// Parameters and produced files of one export run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportManifest {
+    /// Version of the pt02 crate that wrote the export
+    pub tool_version: String,
+    pub level: u8,
+    pub where_filter: String,
+    pub include_code: bool,
+    pub db_path: String,
+
+    /// Sum over `files`; `None` when no file carries the count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_entities: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_edges: Option<usize>,
+
+    /// Set for `--sample` exports: sample size, population and ratio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingInfo>,
+
+    /// Sorted by name; names are relative to the manifest's directory
+    pub files: Vec<ManifestFile>,
+}
+
+/// One produced file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestFile {
+    pub name: String,
+    /// Hex-encoded SHA-256 of the bytes written
+    pub sha256: String,
+    pub bytes: usize,
+
+    /// From `export_metadata`; only the JSON exports carry counts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entities: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edges: Option<usize>,
+}
+
+impl ExportManifest {
+    /// Manifest with the run parameters and no files yet
+    pub fn new(level: u8, where_filter: &str, include_code: bool, db_path: &str) -> Self {
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            level,
+            where_filter: where_filter.to_string(),
+            include_code,
+            db_path: db_path.to_string(),
+            total_entities: None,
+            total_edges: None,
+            sampling: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Record that the export holds a sample (see `sampling`)
+    pub fn with_sampling(mut self, sampling: Option<SamplingInfo>) -> Self {
+        self.sampling = sampling;
+        self
+    }
+}
+
+/// Only the part of an export envelope the manifest needs
+#[derive(Deserialize)]
+struct Envelope {
+    export_metadata: ExportMetadata,
+}
+
+/// Sink that forwards every write and remembers what was written
+///
+/// Wrap the real sink with this for the export, then call
+/// `write_manifest`. A name written twice keeps only its last content,
+/// matching what ends up at the destination.
+pub struct ManifestRecorder<'a> {
+    inner: &'a dyn OutputSink,
+    files: Mutex<BTreeMap<String, ManifestFile>>,
+}
+
+impl<'a> ManifestRecorder<'a> {
+    pub fn new(inner: &'a dyn OutputSink) -> Self {
+        Self {
+            inner,
+            files: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Complete `manifest` with the recorded files and write it next to them
+    pub async fn write_manifest(
+        &self,
+        output_name: &str,
+        mut manifest: ExportManifest,
+        compact: bool,
+    ) -> anyhow::Result<ExportManifest> {
+        manifest.files = self.files.lock().unwrap().values().cloned().collect();
+        manifest.total_entities = sum(manifest.files.iter().map(|f| f.entities));
+        manifest.total_edges = sum(manifest.files.iter().map(|f| f.edges));
+
+        let json = JsonSerializer::with_compact(compact).to_json_string(&manifest)?;
+        self.inner.write_all(&manifest_name(output_name), json.as_bytes()).await?;
+        Ok(manifest)
+    }
+}
+
+#[async_trait]
+impl OutputSink for ManifestRecorder<'_> {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> parseltongue_core::Result<()> {
+        self.inner.write_all(name, bytes).await?;
+
+        let envelope = if name.ends_with(".json") {
+            serde_json::from_slice::<Envelope>(bytes).ok()
+        } else {
+            None
+        };
+        let file = ManifestFile {
+            name: Path::new(name)
+                .file_name()
+                .map_or_else(|| name.to_string(), |n| n.to_string_lossy().into_owned()),
+            sha256: format!("{:x}", Sha256::digest(bytes)),
+            bytes: bytes.len(),
+            entities: envelope.as_ref().and_then(|e| e.export_metadata.total_entities),
+            edges: envelope.as_ref().and_then(|e| e.export_metadata.total_edges),
+        };
+        self.files.lock().unwrap().insert(name.to_string(), file);
+        Ok(())
+    }
+}
+
+fn sum(counts: impl Iterator<Item = Option<usize>>) -> Option<usize> {
+    counts.fold(None, |total, count| match (total, count) {
+        (None, None) => None,
+        (total, count) => Some(total.unwrap_or(0) + count.unwrap_or(0)),
+    })
+}
@@ -0,0 +1,86 @@
+//! Markdown rendering of exports (`--format markdown`).
+//!
+//! The exporters always write JSON and TOON. `MarkdownSink` wraps the real
+//! sink and, for every `.json` artifact, also writes a `.md` twin rendered by
+//! `MarkdownSerializer`, so `context.json` and `context_test.json` gain
+//! `context.md` and `context_test.md` without the exporters knowing.
+
+use async_trait::async_trait;
+use parseltongue_core::output_sink::OutputSink;
+use parseltongue_core::serializers::{MarkdownSerializer, Serializer};
+use serde_json::Value;
+
+/// Output format selected with `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// JSON and TOON only
+    #[default]
+    Json,
+    /// JSON and TOON plus a Markdown rendering of each JSON file
+    Markdown,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => Err(format!("unknown format '{}' (expected json or markdown)", other)),
+        }
+    }
+}
+
+/// Name of the Markdown twin of a JSON artifact
+pub fn markdown_name(json_name: &str) -> String {
+    format!("{}.md", json_name.strip_suffix(".json").unwrap_or(json_name))
+}
+
+/// Sink that adds a Markdown rendering next to each JSON artifact
+pub struct MarkdownSink<'a> {
+    inner: &'a dyn OutputSink,
+}
+
+impl<'a> MarkdownSink<'a> {
+    pub fn new(inner: &'a dyn OutputSink) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl OutputSink for MarkdownSink<'_> {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> parseltongue_core::Result<()> {
+        self.inner.write_all(name, bytes).await?;
+        if !name.ends_with(".json") {
+            return Ok(());
+        }
+
+        // Exporters write either a bare record array or an envelope with
+        // `entities` / `edges` (`--dedup-docs` writes `shared_docs` +
+        // `entities`); other JSON (the manifest) has no records
+        let Ok(json) = serde_json::from_slice::<Value>(bytes) else {
+            return Ok(());
+        };
+        let records: Vec<Value> = match json {
+            Value::Array(records) => records,
+            Value::Object(mut envelope)
+                if envelope.contains_key("export_metadata") || envelope.contains_key("shared_docs") => ["entities", "edges"]
+                .iter()
+                .filter_map(|field| match envelope.remove(*field) {
+                    Some(Value::Array(records)) => Some(records),
+                    _ => None,
+                })
+                .flatten()
+                .collect(),
+            _ => return Ok(()),
+        };
+
+        let markdown = MarkdownSerializer::new()
+            .serialize(&records)
+            .map_err(|e| parseltongue_core::error::ParseltongError::SerializationError {
+                details: format!("Markdown rendering of {} failed: {}", name, e),
+            })?;
+        self.inner.write_all(&markdown_name(name), markdown.as_bytes()).await
+    }
+}
@@ -13,6 +13,9 @@
 //! 3. **Semantic ISGL1 Keys**: NOT integer indices (6.7× better effective context)
 //! 4. **Flat Hierarchy**: Level2 flattens Level1 (no nesting for LLM readability)
 
+use parseltongue_core::entities::{Location, LspDiagnostic};
+use parseltongue_core::output_sink::OutputSink;
+use parseltongue_core::serializers::JsonSerializer;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -44,6 +47,9 @@ pub struct ExportConfig {
 
     /// Test entities output path (when entity_class filtering is enabled)
     pub tests_output_path: Option<PathBuf>,
+
+    /// Write single-line JSON instead of pretty-printed (`--compact`)
+    pub compact_json: bool,
 }
 
 // ============================================================================
@@ -60,21 +66,45 @@ pub struct ExportOutput {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entities: Option<serde_json::Value>,
+
+    /// Doc comments shared by several entities, by id (`--dedup-docs`);
+    /// those entities carry `doc_ref` instead of `doc_comment`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub shared_docs: Option<std::collections::BTreeMap<String, String>>,
+
+    /// Dependency cycles among the exported entities (`--order topological`),
+    /// each listed by key; omitted when there are none
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cycles: Option<Vec<Vec<String>>>,
 }
 
 impl ExportOutput {
     /// Write export output to JSON file with structured error handling
     pub fn write_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
-        let json_content = serde_json::to_string_pretty(self)?;
+        self.write_to_file_with(path, false)
+    }
+
+    /// Write export output as pretty (`compact = false`) or single-line JSON
+    pub fn write_to_file_with<P: AsRef<std::path::Path>>(&self, path: P, compact: bool) -> anyhow::Result<()> {
+        let json_content = JsonSerializer::with_compact(compact).to_json_string(self)?;
         std::fs::write(path, json_content)?;
         Ok(())
     }
+
+    /// Write export output as JSON to `name` on `sink`
+    pub async fn write_to_sink(&self, sink: &dyn OutputSink, name: &str, compact: bool) -> anyhow::Result<()> {
+        let json_content = JsonSerializer::with_compact(compact).to_json_string(self)?;
+        sink.write_all(name, json_content.as_bytes()).await?;
+        Ok(())
+    }
 }
 
 /// Export metadata (common across all levels)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportMetadata {
     pub level: u8,
+    /// RFC 3339 export time; empty and left out of the JSON with `--no-timestamp`
+    #[serde(skip_serializing_if = "String::is_empty", default)]
     pub timestamp: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,7 +141,7 @@ pub struct ExportMetadata {
 ///   "edge_type": "depends_on"
 /// }
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct DependencyEdge {
     pub from_key: String,
     pub to_key: String,
@@ -171,6 +201,35 @@ pub struct EntityExportLevel1 {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub doc_comment: Option<String>,
+
+    /// Last writing tool as `tool@timestamp`, when provenance export is enabled
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub provenance: Option<String>,
+
+    /// Ingested from a generated or vendored file; omitted when false
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub is_generated: bool,
+
+    /// Cyclomatic complexity measured at ingestion
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub complexity_score: Option<u32>,
+
+    /// Test entities exercising this entity, with `--pair-tests`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tests: Vec<PairedTest>,
+}
+
+/// A test entity attached to the production entity it exercises
+/// (see `test_pairing`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PairedTest {
+    pub isgl1_key: String,
+    pub entity_name: String,
+    pub file_path: String,
+    pub line_number: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_code: Option<String>,
 }
 
 // ============================================================================
@@ -239,12 +298,49 @@ pub struct EntityExportLevel2 {
     pub is_public: bool,
     pub is_async: bool,
     pub is_unsafe: bool,
+
+    // LSP enrichment (rust-analyzer), omitted when ingested without it
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hover: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub definition_location: Option<Location>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub references_count: Option<usize>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub diagnostics: Vec<LspDiagnostic>,
+
+    /// Last writing tool as `tool@timestamp`, when provenance export is enabled
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub provenance: Option<String>,
+
+    /// Ingested from a generated or vendored file; omitted when false
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub is_generated: bool,
+
+    /// Cyclomatic complexity measured at ingestion
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub complexity_score: Option<u32>,
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// `export_metadata.timestamp` for an export run now
+///
+/// Empty when `include_timestamp` is false, so exports of identical data
+/// are byte-identical (cacheable, diffable in CI).
+pub fn export_timestamp(include_timestamp: bool) -> String {
+    if include_timestamp {
+        chrono::Utc::now().to_rfc3339()
+    } else {
+        String::new()
+    }
+}
+
 impl ExportMetadata {
     /// Create metadata for Level 0 (edges)
     pub fn for_level0(total_edges: usize, where_filter: String) -> Self {
@@ -279,6 +375,8 @@ impl ExportOutput {
             export_metadata: metadata,
             edges: Some(edges),
             entities: None,
+            shared_docs: None,
+            cycles: None,
         }
     }
 
@@ -295,6 +393,8 @@ impl ExportOutput {
             export_metadata: metadata,
             edges: None,
             entities: Some(entities),
+            shared_docs: None,
+            cycles: None,
         }
     }
 }
@@ -335,6 +435,10 @@ mod tests {
             // v0.9.0: EntityClass for code/test separation
             entity_class: "CODE".to_string(),
             doc_comment: None,  // Should be skipped
+            provenance: None,
+            is_generated: false,  // Should be skipped
+            complexity_score: None,
+            tests: vec![],
         };
 
         let json = serde_json::to_string(&entity).unwrap();
@@ -343,6 +447,7 @@ mod tests {
         assert!(!json.contains("future_action"));
         assert!(!json.contains("future_code"));
         assert!(!json.contains("doc_comment"));
+        assert!(!json.contains("is_generated"));
     }
 
     #[test]
@@ -0,0 +1,112 @@
+//! Entity order of Level 1/2 exports (`--order`).
+//!
+//! `key` (the default) is file, then line, then ISGL1 key. `topological`
+//! puts dependencies before their dependents, so an LLM reading top to
+//! bottom meets a callee before its callers. The edges between exported
+//! entities are condensed into petgraph's strongly connected components
+//! and those are sorted with Kahn's algorithm, always emitting the ready
+//! component that comes first in key order, so entities the edges do not
+//! order keep key order. Entities on a cycle have no dependency order, so
+//! each cycle's members stay together in key order and the cycle is
+//! reported.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::str::FromStr;
+
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::export_trait::Edge;
+
+/// Entity order of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportOrder {
+    /// File, then line, then ISGL1 key
+    #[default]
+    Key,
+    /// Dependencies before dependents
+    Topological,
+}
+
+impl FromStr for ExportOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "key" => Ok(ExportOrder::Key),
+            "topological" => Ok(ExportOrder::Topological),
+            other => Err(format!("Unknown export order '{}' (expected key or topological)", other)),
+        }
+    }
+}
+
+/// Reorder `entities` so dependencies come before dependents
+///
+/// `entities` should already be in key order; the result depends only on
+/// that order and the edges, so it is as deterministic as key order. Only
+/// edges between two of `entities` count. Returns the dependency cycles
+/// found, each as its members' keys in key order.
+pub fn sort_topologically<T>(entities: &mut Vec<T>, key_of: impl Fn(&T) -> &str, edges: &[Edge]) -> Vec<Vec<String>> {
+    let mut graph: DiGraph<usize, ()> = DiGraph::new();
+    let nodes: HashMap<&str, NodeIndex> = entities
+        .iter()
+        .enumerate()
+        .map(|(position, entity)| (key_of(entity), graph.add_node(position)))
+        .collect();
+    for edge in edges {
+        if let (Some(&from), Some(&to)) = (nodes.get(edge.from_key.as_str()), nodes.get(edge.to_key.as_str())) {
+            graph.update_edge(from, to, ());
+        }
+    }
+
+    // Each component's members in key order; a component of several nodes,
+    // or one calling itself, is a cycle
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut component_of = vec![0; graph.node_count()];
+    let mut cycles: Vec<(usize, Vec<String>)> = Vec::new();
+    for component in tarjan_scc(&graph) {
+        for node in &component {
+            component_of[node.index()] = components.len();
+        }
+        let mut positions: Vec<usize> = component.iter().map(|node| graph[*node]).collect();
+        positions.sort_unstable();
+        if component.len() > 1 || graph.contains_edge(component[0], component[0]) {
+            let keys = positions.iter().map(|&position| key_of(&entities[position]).to_string()).collect();
+            cycles.push((positions[0], keys));
+        }
+        components.push(positions);
+    }
+    cycles.sort_unstable_by_key(|(first, _)| *first);
+
+    // Kahn's algorithm: a component is ready once all of its callees are out
+    let mut pending_callees = vec![0usize; components.len()];
+    let mut callers: Vec<Vec<usize>> = vec![Vec::new(); components.len()];
+    for edge in graph.edge_indices() {
+        let (from, to) = graph.edge_endpoints(edge).expect("edge of this graph");
+        let (from, to) = (component_of[from.index()], component_of[to.index()]);
+        if from != to {
+            pending_callees[from] += 1;
+            callers[to].push(from);
+        }
+    }
+    let mut ready: BinaryHeap<Reverse<(usize, usize)>> = (0..components.len())
+        .filter(|&component| pending_callees[component] == 0)
+        .map(|component| Reverse((components[component][0], component)))
+        .collect();
+
+    let mut order: Vec<usize> = Vec::with_capacity(entities.len());
+    while let Some(Reverse((_, component))) = ready.pop() {
+        order.extend(&components[component]);
+        for &caller in &callers[component] {
+            pending_callees[caller] -= 1;
+            if pending_callees[caller] == 0 {
+                ready.push(Reverse((components[caller][0], caller)));
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<T>> = entities.drain(..).map(Some).collect();
+    entities.extend(order.into_iter().filter_map(|position| slots[position].take()));
+    cycles.into_iter().map(|(_, keys)| keys).collect()
+}
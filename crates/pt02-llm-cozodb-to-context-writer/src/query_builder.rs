@@ -7,6 +7,11 @@
 //!
 //! All functions take inputs, return outputs, no mutation, no I/O.
 
+use parseltongue_core::entities::Visibility;
+
+/// Typed filters live in core so every tool can offer them
+pub use parseltongue_core::entity_filter::{visibility_condition, QueryBuilder};
+
 /// L1 Pure Function: Build export query with optional WHERE filter
 ///
 /// # Arguments
@@ -81,6 +86,123 @@ pub fn compose_where_clause(conditions: Vec<&str>) -> String {
     }
 }
 
+/// Columns every Level 1-2 entity query reads from CodeGraph
+const ENTITY_COLUMNS: &str = "ISGL1_key, interface_signature, entity_type, file_path, \
+    Current_Code, Future_Code, current_ind, future_ind, Future_Action, entity_class, lsp_meta_data, \
+    complexity_score";
+
+/// Binds `is_generated` from pt01's `GeneratedEntities` flags
+const IS_GENERATED_BINDING: &str = "(*GeneratedEntities{ISGL1_key}, is_generated = true) \
+    or (not *GeneratedEntities{ISGL1_key}, is_generated = false)";
+
+/// Body shared by the Level 1-2 entity and count queries
+fn entity_query_body() -> String {
+    format!("*CodeGraph{{{}, deleted_at: null}}, {}", ENTITY_COLUMNS, IS_GENERATED_BINDING)
+}
+
+/// L1 Pure Function: Entity query run by `CozoDbAdapter::query_entities`
+///
+/// `"ALL"` selects every entity; anything else is appended as Datalog
+/// conditions. Soft-deleted entities are never exported. The last column,
+/// `is_generated`, is true for entities pt01 flagged as generated.
+pub fn build_entity_query(where_clause: &str) -> String {
+    let query = format!("?[{}, is_generated] := {}", ENTITY_COLUMNS, entity_query_body());
+    with_filter(query, where_clause)
+}
+
+/// L1 Pure Function: Edge query run by `CozoDbAdapter::query_edges`
+pub fn build_edge_query(where_clause: &str) -> String {
+    let query = "?[from_key, to_key, edge_type] := *DependencyEdges{from_key, to_key, edge_type}".to_string();
+    with_filter(query, where_clause)
+}
+
+/// L1 Pure Function: Count query run by `CozoDbAdapter::count_entities`
+/// (level 1-2) or `count_edges` (level 0)
+///
+/// Same body and filter as the export query, so it counts exactly the rows
+/// the export would fetch.
+pub fn build_count_query(level: u8, where_clause: &str) -> String {
+    let query = if level == 0 {
+        "?[count(edge)] := *DependencyEdges{from_key, to_key, edge_type}, edge = [from_key, to_key, edge_type]"
+            .to_string()
+    } else {
+        format!("?[count(ISGL1_key)] := {}", entity_query_body())
+    };
+    with_filter(query, where_clause)
+}
+
+/// L2 Pure Function: Restrict a WHERE clause to one entity class
+///
+/// Used by the dual-file exports, which run once for `CODE` and once for
+/// `TEST`.
+pub fn scope_to_entity_class(entity_class: &str, where_clause: &str) -> String {
+    if where_clause == "ALL" {
+        format!("entity_class = '{}'", entity_class)
+    } else {
+        format!("entity_class = '{}', {}", entity_class, where_clause)
+    }
+}
+
+/// Condition dropping entities ingestion classified as tests (`--exclude-tests`)
+pub const NON_TEST_CONDITION: &str = "entity_class != 'TEST'";
+
+/// L2 Pure Function: Narrow a WHERE clause to production API context
+///
+/// Both conditions read columns every entity query already binds, so they
+/// cost a comparison per row. `--exclude-private` keeps entities whose
+/// stored signature is `Public` (see [`visibility_condition`]).
+pub fn with_api_scope(where_clause: &str, exclude_tests: bool, exclude_private: bool) -> String {
+    let mut conditions: Vec<String> = Vec::new();
+    if where_clause != "ALL" {
+        conditions.push(where_clause.to_string());
+    }
+    if exclude_tests {
+        conditions.push(NON_TEST_CONDITION.to_string());
+    }
+    if exclude_private {
+        conditions.push(visibility_condition(&Visibility::Public));
+    }
+    if conditions.is_empty() {
+        "ALL".to_string()
+    } else {
+        conditions.join(", ")
+    }
+}
+
+/// Condition dropping entities pt01 flagged as generated or vendored
+/// (`--exclude-generated`)
+pub const NON_GENERATED_CONDITION: &str = "not *GeneratedEntities{ISGL1_key}";
+
+/// L2 Pure Function: Drop generated entities from a WHERE clause's matches
+///
+/// The flags live in pt01's `GeneratedEntities` relation, which schema
+/// migration v4 creates.
+pub fn without_generated(where_clause: &str, exclude_generated: bool) -> String {
+    match (where_clause, exclude_generated) {
+        (_, false) => where_clause.to_string(),
+        ("ALL", true) => NON_GENERATED_CONDITION.to_string(),
+        (filter, true) => format!("{}, {}", filter, NON_GENERATED_CONDITION),
+    }
+}
+
+/// L3 Pure Function: Every query a dual-file export at `level` runs, in order
+///
+/// This is what `--explain` prints: the CODE query, then the TEST query.
+pub fn explain_export(level: u8, where_clause: &str) -> Vec<String> {
+    let build = if level == 0 { build_edge_query } else { build_entity_query };
+    ["CODE", "TEST"]
+        .iter()
+        .map(|class| build(&scope_to_entity_class(class, where_clause)))
+        .collect()
+}
+
+fn with_filter(query: String, where_clause: &str) -> String {
+    match where_clause {
+        "ALL" => query,
+        filter => format!("{}, {}", query, filter),
+    }
+}
+
 /// L1 Pure Function: Extract column list from include_current_code flag
 ///
 /// Returns tuple of (query_columns, storage_fields)
@@ -127,6 +249,74 @@ mod tests {
         assert!(query.contains("future_code"));
     }
 
+    #[test]
+    fn test_without_generated() {
+        assert_eq!(without_generated("ALL", false), "ALL");
+        assert_eq!(without_generated("ALL", true), NON_GENERATED_CONDITION);
+        assert_eq!(
+            without_generated("entity_type = 'fn'", true),
+            "entity_type = 'fn', not *GeneratedEntities{ISGL1_key}"
+        );
+    }
+
+    #[test]
+    fn test_with_api_scope() {
+        assert_eq!(with_api_scope("ALL", false, false), "ALL");
+        assert_eq!(with_api_scope("ALL", true, false), "entity_class != 'TEST'");
+        assert_eq!(
+            with_api_scope("entity_type = 'fn'", true, true),
+            format!("entity_type = 'fn', entity_class != 'TEST', {}", visibility_condition(&Visibility::Public))
+        );
+    }
+
+    #[test]
+    fn test_explain_level1_with_filter() {
+        let queries = explain_export(1, "entity_type = 'fn'");
+
+        assert_eq!(
+            queries,
+            vec![
+                "?[ISGL1_key, interface_signature, entity_type, file_path, Current_Code, Future_Code, \
+                 current_ind, future_ind, Future_Action, entity_class, lsp_meta_data, complexity_score, is_generated] := \
+                 *CodeGraph{ISGL1_key, interface_signature, entity_type, file_path, Current_Code, \
+                 Future_Code, current_ind, future_ind, Future_Action, entity_class, lsp_meta_data, complexity_score, \
+                 deleted_at: null}, \
+                 (*GeneratedEntities{ISGL1_key}, is_generated = true) or \
+                 (not *GeneratedEntities{ISGL1_key}, is_generated = false), \
+                 entity_class = 'CODE', entity_type = 'fn'"
+                    .to_string(),
+                "?[ISGL1_key, interface_signature, entity_type, file_path, Current_Code, Future_Code, \
+                 current_ind, future_ind, Future_Action, entity_class, lsp_meta_data, complexity_score, is_generated] := \
+                 *CodeGraph{ISGL1_key, interface_signature, entity_type, file_path, Current_Code, \
+                 Future_Code, current_ind, future_ind, Future_Action, entity_class, lsp_meta_data, complexity_score, \
+                 deleted_at: null}, \
+                 (*GeneratedEntities{ISGL1_key}, is_generated = true) or \
+                 (not *GeneratedEntities{ISGL1_key}, is_generated = false), \
+                 entity_class = 'TEST', entity_type = 'fn'"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_level0_all() {
+        assert_eq!(
+            explain_export(0, "ALL")[0],
+            "?[from_key, to_key, edge_type] := *DependencyEdges{from_key, to_key, edge_type}, entity_class = 'CODE'"
+        );
+        assert_eq!(build_edge_query("ALL"), "?[from_key, to_key, edge_type] := *DependencyEdges{from_key, to_key, edge_type}");
+    }
+
+    #[test]
+    fn test_count_query_shares_the_export_body() {
+        let count = build_count_query(1, "entity_type = 'function'");
+        let export = build_entity_query("entity_type = 'function'");
+
+        assert!(count.starts_with("?[count(ISGL1_key)] := "), "{}", count);
+        assert_eq!(count.split_once(" := ").unwrap().1, export.split_once(" := ").unwrap().1);
+        assert!(build_count_query(0, "ALL").contains("*DependencyEdges{from_key, to_key, edge_type}"));
+    }
+
     #[test]
     fn test_compose_where_clause_empty() {
         let clause = compose_where_clause(vec![]);
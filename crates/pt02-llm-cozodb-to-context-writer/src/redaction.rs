@@ -0,0 +1,108 @@
+//! Strip code from exports for external sharing (`--redact`, `--redact-docs`).
+//!
+//! `RedactedRepository` wraps the repository like `SelectedRepository` does,
+//! so every level is covered without the exporters knowing. Code fields are
+//! replaced by a placeholder carrying the original size and SHA-256, which
+//! lets the recipient check that two exports describe the same code without
+//! seeing it. Keys, signatures, edges and metadata pass through unchanged.
+//!
+//! Doc comments often quote code, so `with_docs` redacts them as well.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use sha2::{Digest, Sha256};
+
+use crate::export_trait::{CodeGraphRepository, Edge, Entity};
+
+/// Placeholder standing in for `text`: `<redacted: N bytes, sha256=...>`
+pub fn redact(text: &str) -> String {
+    format!("<redacted: {} bytes, sha256={:x}>", text.len(), Sha256::digest(text.as_bytes()))
+}
+
+/// Repository view with code (and optionally doc comments) redacted
+pub struct RedactedRepository<'a> {
+    inner: &'a dyn CodeGraphRepository,
+    docs: bool,
+}
+
+impl<'a> RedactedRepository<'a> {
+    pub fn new(inner: &'a dyn CodeGraphRepository) -> Self {
+        Self { inner, docs: false }
+    }
+
+    /// Also redact doc comments
+    pub fn with_docs(mut self, docs: bool) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    fn redact_entity(&self, mut entity: Entity) -> Entity {
+        entity.current_code = entity.current_code.as_deref().map(redact);
+        entity.future_code = entity.future_code.as_deref().map(redact);
+        if self.docs {
+            entity.doc_comment = entity.doc_comment.as_deref().map(redact);
+        }
+        entity
+    }
+}
+
+#[async_trait]
+impl CodeGraphRepository for RedactedRepository<'_> {
+    async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+        let entities = self.inner.get_all_entities().await?;
+        Ok(entities.into_iter().map(|entity| self.redact_entity(entity)).collect())
+    }
+
+    async fn query_entities(&self, where_clause: &str) -> Result<Vec<Entity>> {
+        let entities = self.inner.query_entities(where_clause).await?;
+        Ok(entities.into_iter().map(|entity| self.redact_entity(entity)).collect())
+    }
+
+    async fn entities_after(&self, where_clause: &str, after: Option<&str>, limit: usize) -> Result<Vec<Entity>> {
+        let entities = self.inner.entities_after(where_clause, after, limit).await?;
+        Ok(entities.into_iter().map(|entity| self.redact_entity(entity)).collect())
+    }
+
+    fn entities_stream<'a>(&'a self, where_clause: &'a str) -> BoxStream<'a, Result<Entity>> {
+        self.inner
+            .entities_stream(where_clause)
+            .map_ok(move |entity| self.redact_entity(entity))
+            .boxed()
+    }
+
+    async fn count_entities(&self, where_clause: &str) -> Result<usize> {
+        self.inner.count_entities(where_clause).await
+    }
+
+    async fn get_all_edges(&self) -> Result<Vec<Edge>> {
+        self.inner.get_all_edges().await
+    }
+
+    async fn query_edges(&self, where_clause: &str) -> Result<Vec<Edge>> {
+        self.inner.query_edges(where_clause).await
+    }
+
+    async fn count_edges(&self, where_clause: &str) -> Result<usize> {
+        self.inner.count_edges(where_clause).await
+    }
+
+    async fn get_provenance(&self) -> Result<HashMap<String, String>> {
+        self.inner.get_provenance().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_records_size_and_hash() {
+        assert_eq!(
+            redact("abc"),
+            "<redacted: 3 bytes, sha256=ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad>"
+        );
+    }
+}
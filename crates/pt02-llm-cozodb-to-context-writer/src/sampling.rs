@@ -0,0 +1,312 @@
+//! Deterministic entity sampling for very large exports (`--sample`).
+//!
+//! Every entity is ranked by a SHA-256 hash of its ISGL1 key and the lowest
+//! ranks are kept (a bottom-k sketch). No randomness is involved, so reruns
+//! over the same graph export the same sample, and a larger sample always
+//! contains a smaller one.
+//!
+//! With a stratum (`--sample-by entity_type`), the sample size is split
+//! across the groups in proportion to their sizes (largest remainder) and
+//! each group is sampled on its own, keeping the mix of the full graph.
+//!
+//! Sampling runs over the entities matching the `--where-clause`; the result
+//! restricts the repository like a `--keys` selection.
+
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::export_trait::{CodeGraphRepository, Entity};
+use crate::selection::SelectedRepository;
+
+/// How many entities to keep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSize {
+    /// Exactly this many (or all, if there are fewer)
+    Count(usize),
+    /// This percentage of the matching entities, rounded
+    Percent(f64),
+}
+
+impl SampleSize {
+    fn of(self, population: usize) -> usize {
+        match self {
+            SampleSize::Count(count) => count.min(population),
+            SampleSize::Percent(pct) => ((population as f64 * pct / 100.0).round() as usize).min(population),
+        }
+    }
+}
+
+/// Field whose values define the strata of a stratified sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleStratum {
+    EntityType,
+    EntityClass,
+    FilePath,
+}
+
+impl SampleStratum {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SampleStratum::EntityType => "entity_type",
+            SampleStratum::EntityClass => "entity_class",
+            SampleStratum::FilePath => "file_path",
+        }
+    }
+
+    fn value(self, entity: &Entity) -> &str {
+        match self {
+            SampleStratum::EntityType => &entity.entity_type,
+            SampleStratum::EntityClass => &entity.entity_class,
+            SampleStratum::FilePath => &entity.file_path,
+        }
+    }
+}
+
+impl FromStr for SampleStratum {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "entity_type" => Ok(SampleStratum::EntityType),
+            "entity_class" => Ok(SampleStratum::EntityClass),
+            "file_path" => Ok(SampleStratum::FilePath),
+            other => Err(format!(
+                "cannot sample by '{}' (expected entity_type, entity_class or file_path)",
+                other
+            )),
+        }
+    }
+}
+
+/// What a sampled export contains relative to the full match (manifest entry)
+///
+/// The manifest also records [`ratio`](Self::ratio); it is derived from the
+/// counts rather than stored, so the type stays `Eq`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(into = "SamplingRecord", from = "SamplingRecord")]
+pub struct SamplingInfo {
+    pub sampled: usize,
+    /// Entities matching the WHERE clause before sampling
+    pub population: usize,
+    pub stratified_by: Option<String>,
+}
+
+impl SamplingInfo {
+    /// `sampled / population` (1.0 for an empty population)
+    pub fn ratio(&self) -> f64 {
+        if self.population == 0 {
+            1.0
+        } else {
+            self.sampled as f64 / self.population as f64
+        }
+    }
+}
+
+/// Manifest form of [`SamplingInfo`]
+#[derive(Serialize, Deserialize)]
+struct SamplingRecord {
+    sampled: usize,
+    population: usize,
+    ratio: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stratified_by: Option<String>,
+}
+
+impl From<SamplingInfo> for SamplingRecord {
+    fn from(info: SamplingInfo) -> Self {
+        Self {
+            ratio: info.ratio(),
+            sampled: info.sampled,
+            population: info.population,
+            stratified_by: info.stratified_by,
+        }
+    }
+}
+
+impl From<SamplingRecord> for SamplingInfo {
+    fn from(record: SamplingRecord) -> Self {
+        Self {
+            sampled: record.sampled,
+            population: record.population,
+            stratified_by: record.stratified_by,
+        }
+    }
+}
+
+/// Sampling parameters from `--sample` / `--sample-pct` / `--sample-by`
+#[derive(Debug, Clone, Copy)]
+pub struct EntitySampler {
+    size: SampleSize,
+    stratum: Option<SampleStratum>,
+}
+
+impl EntitySampler {
+    /// Errors on a percentage outside 0-100
+    pub fn new(size: SampleSize) -> Result<Self> {
+        if let SampleSize::Percent(pct) = size {
+            if !(0.0..=100.0).contains(&pct) {
+                return Err(anyhow!("--sample-pct must be between 0 and 100, got {}", pct));
+            }
+        }
+        Ok(Self { size, stratum: None })
+    }
+
+    /// Keep the proportions of `stratum`'s values
+    pub fn with_stratum(mut self, stratum: Option<SampleStratum>) -> Self {
+        self.stratum = stratum;
+        self
+    }
+
+    /// ISGL1 keys of the sample drawn from `entities`
+    pub fn sample_keys(&self, entities: &[Entity]) -> Vec<String> {
+        let target = self.size.of(entities.len());
+        let Some(stratum) = self.stratum else {
+            return bottom_k(entities.iter(), target);
+        };
+
+        let mut strata: BTreeMap<&str, Vec<&Entity>> = BTreeMap::new();
+        for entity in entities {
+            strata.entry(stratum.value(entity)).or_default().push(entity);
+        }
+        let sizes: Vec<usize> = strata.values().map(Vec::len).collect();
+        strata
+            .into_values()
+            .zip(allocate(target, &sizes))
+            .flat_map(|(members, quota)| bottom_k(members.into_iter(), quota))
+            .collect()
+    }
+
+    /// Sample the entities matching `where_clause` and restrict `repository`
+    /// to them
+    pub async fn resolve<'a>(
+        &self,
+        repository: &'a dyn CodeGraphRepository,
+        where_clause: &str,
+    ) -> Result<(SelectedRepository<'a>, SamplingInfo)> {
+        let entities = if where_clause == "ALL" {
+            repository.get_all_entities().await?
+        } else {
+            repository.query_entities(where_clause).await?
+        };
+        let keys = self.sample_keys(&entities);
+        let info = SamplingInfo {
+            sampled: keys.len(),
+            population: entities.len(),
+            stratified_by: self.stratum.map(|s| s.as_str().to_string()),
+        };
+        let keys: HashSet<String> = keys.into_iter().collect();
+        Ok((SelectedRepository::from_keys(repository, keys), info))
+    }
+}
+
+/// Keys of the `k` entities with the lowest key hashes
+fn bottom_k<'e>(entities: impl Iterator<Item = &'e Entity>, k: usize) -> Vec<String> {
+    let mut ranked: Vec<(u64, &str)> = entities
+        .map(|entity| (key_rank(&entity.isgl1_key), entity.isgl1_key.as_str()))
+        .collect();
+    ranked.sort_unstable();
+    ranked.into_iter().take(k).map(|(_, key)| key.to_string()).collect()
+}
+
+fn key_rank(key: &str) -> u64 {
+    let digest = Sha256::digest(key.as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 is 32 bytes"))
+}
+
+/// Split `target` across groups in proportion to `sizes` (largest remainder;
+/// ties go to the earlier group)
+fn allocate(target: usize, sizes: &[usize]) -> Vec<usize> {
+    let total: usize = sizes.iter().sum();
+    if total == 0 {
+        return vec![0; sizes.len()];
+    }
+    let mut quotas: Vec<usize> = sizes.iter().map(|&size| target * size / total).collect();
+    let mut by_remainder: Vec<usize> = (0..sizes.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(target * sizes[i] % total));
+    let assigned: usize = quotas.iter().sum();
+    for &i in by_remainder.iter().take(target - assigned) {
+        quotas[i] += 1;
+    }
+    quotas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(key: String, entity_type: &str) -> Entity {
+        Entity {
+            isgl1_key: key,
+            forward_deps: vec![],
+            reverse_deps: vec![],
+            current_ind: 1,
+            future_ind: 0,
+            future_action: None,
+            future_code: None,
+            current_code: None,
+            entity_name: String::new(),
+            entity_type: entity_type.to_string(),
+            file_path: "src/lib.rs".to_string(),
+            line_number: 1,
+            interface_signature: String::new(),
+            doc_comment: None,
+            entity_class: "CODE".to_string(),
+            return_type: None,
+            param_types: None,
+            param_names: None,
+            generic_constraints: None,
+            trait_impls: None,
+            is_public: None,
+            is_async: None,
+            is_unsafe: None,
+            lsp_metadata: None,
+            is_generated: false,
+            complexity_score: None,
+        }
+    }
+
+    #[test]
+    fn test_stratified_sample_keeps_proportions() {
+        let entities: Vec<Entity> = (0..80)
+            .map(|i| entity(format!("rust:fn:f{}:src_lib_rs:{}", i, i), "fn"))
+            .chain((0..20).map(|i| entity(format!("rust:struct:S{}:src_lib_rs:{}", i, i), "struct")))
+            .collect();
+
+        let sampler = EntitySampler::new(SampleSize::Count(10))
+            .unwrap()
+            .with_stratum(Some(SampleStratum::EntityType));
+        let keys = sampler.sample_keys(&entities);
+
+        assert_eq!(keys.len(), 10);
+        assert_eq!(keys.iter().filter(|k| k.starts_with("rust:fn:")).count(), 8);
+        assert_eq!(keys.iter().filter(|k| k.starts_with("rust:struct:")).count(), 2);
+    }
+
+    #[test]
+    fn test_larger_sample_contains_smaller_one() {
+        let entities: Vec<Entity> = (0..50).map(|i| entity(format!("k{}", i), "fn")).collect();
+
+        let small = EntitySampler::new(SampleSize::Count(5)).unwrap().sample_keys(&entities);
+        let large = EntitySampler::new(SampleSize::Percent(40.0)).unwrap().sample_keys(&entities);
+
+        assert_eq!(large.len(), 20);
+        assert!(small.iter().all(|key| large.contains(key)));
+    }
+
+    #[test]
+    fn test_allocate_uses_largest_remainder() {
+        assert_eq!(allocate(10, &[80, 20]), [8, 2]);
+        assert_eq!(allocate(3, &[1, 1, 1, 1]), [1, 1, 1, 0]);
+        assert_eq!(allocate(2, &[5, 1]), [2, 0]);
+        assert_eq!(allocate(5, &[0, 0]), [0, 0]);
+    }
+
+    #[test]
+    fn test_sample_pct_out_of_range_is_rejected() {
+        assert!(EntitySampler::new(SampleSize::Percent(120.0)).is_err());
+    }
+}
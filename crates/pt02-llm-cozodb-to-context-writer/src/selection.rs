@@ -0,0 +1,220 @@
+//! Export an explicit set of entities (`--keys` / `--keys-file`).
+//!
+//! A `KeySelection` is resolved against the repository once, then wraps it:
+//! every exporter reads through `SelectedRepository`, which only returns the
+//! selected entities (and the edges leaving them), so all levels support
+//! selections without knowing about them. The `--where-clause` still
+//! applies on top.
+//!
+//! With `with_deps` the one-hop dependencies of each selected entity are
+//! pulled in too. Keys that match no entity are kept in `missing` so the
+//! caller can report them.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::future;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+
+use crate::export_trait::{CodeGraphRepository, Edge, Entity};
+
+/// ISGL1 keys to export, before checking them against the database
+#[derive(Debug, Clone, Default)]
+pub struct KeySelection {
+    keys: Vec<String>,
+    with_deps: bool,
+}
+
+impl KeySelection {
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            keys: keys
+                .into_iter()
+                .map(Into::into)
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect(),
+            with_deps: false,
+        }
+    }
+
+    /// Keys from a file, one per line; blank lines and `#` comments are skipped
+    pub fn from_keys_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read keys file {}", path.display()))?;
+        Ok(Self::new(content.lines().filter(|line| !line.trim_start().starts_with('#'))))
+    }
+
+    /// Also export the entities each selected entity depends on (one hop)
+    pub fn with_deps(mut self, with_deps: bool) -> Self {
+        self.with_deps = with_deps;
+        self
+    }
+
+    /// Add keys (e.g. `--keys` alongside `--keys-file`)
+    pub fn extend<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys.extend(Self::new(keys).keys);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check the keys against `repository` and restrict it to them
+    ///
+    /// Dependencies come from both `forward_deps` and the dependency edges;
+    /// ones that are not entities themselves (external calls) are ignored.
+    pub async fn resolve<'a>(&self, repository: &'a dyn CodeGraphRepository) -> Result<SelectedRepository<'a>> {
+        let known: HashMap<String, Entity> = repository
+            .get_all_entities()
+            .await?
+            .into_iter()
+            .map(|entity| (entity.isgl1_key.clone(), entity))
+            .collect();
+
+        let mut keys: HashSet<String> = HashSet::new();
+        let mut missing = BTreeSet::new();
+        for key in &self.keys {
+            if known.contains_key(key) {
+                keys.insert(key.clone());
+            } else {
+                missing.insert(key.clone());
+            }
+        }
+
+        if self.with_deps {
+            let mut deps: Vec<String> = keys
+                .iter()
+                .flat_map(|key| known[key].forward_deps.iter().cloned())
+                .collect();
+            deps.extend(
+                repository
+                    .get_all_edges()
+                    .await?
+                    .into_iter()
+                    .filter(|edge| keys.contains(&edge.from_key))
+                    .map(|edge| edge.to_key),
+            );
+            keys.extend(deps.into_iter().filter(|dep| known.contains_key(dep)));
+        }
+
+        Ok(SelectedRepository {
+            inner: repository,
+            keys,
+            missing: missing.into_iter().collect(),
+        })
+    }
+}
+
+/// Repository view containing only a resolved selection
+pub struct SelectedRepository<'a> {
+    inner: &'a dyn CodeGraphRepository,
+    keys: HashSet<String>,
+    missing: Vec<String>,
+}
+
+impl<'a> SelectedRepository<'a> {
+    /// `inner` restricted to already-resolved `keys` (nothing missing)
+    pub(crate) fn from_keys(inner: &'a dyn CodeGraphRepository, keys: HashSet<String>) -> Self {
+        Self { inner, keys, missing: Vec::new() }
+    }
+
+    /// Requested keys with no matching entity, sorted
+    pub fn missing(&self) -> &[String] {
+        &self.missing
+    }
+
+    /// Number of entities in the selection, dependencies included
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn select_entities(&self, entities: Vec<Entity>) -> Vec<Entity> {
+        entities.into_iter().filter(|e| self.keys.contains(&e.isgl1_key)).collect()
+    }
+
+    fn select_edges(&self, edges: Vec<Edge>) -> Vec<Edge> {
+        edges.into_iter().filter(|e| self.keys.contains(&e.from_key)).collect()
+    }
+}
+
+#[async_trait]
+impl CodeGraphRepository for SelectedRepository<'_> {
+    async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+        Ok(self.select_entities(self.inner.get_all_entities().await?))
+    }
+
+    async fn query_entities(&self, where_clause: &str) -> Result<Vec<Entity>> {
+        Ok(self.select_entities(self.inner.query_entities(where_clause).await?))
+    }
+
+    async fn entities_after(&self, where_clause: &str, after: Option<&str>, limit: usize) -> Result<Vec<Entity>> {
+        // A short page reads as the last one, so keep paging the inner
+        // repository until `limit` selected entities are found
+        let mut selected = Vec::new();
+        let mut after = after.map(str::to_string);
+        loop {
+            let page = self.inner.entities_after(where_clause, after.as_deref(), limit).await?;
+            let exhausted = page.len() < limit;
+            after = page.last().map(|e| e.isgl1_key.clone());
+            selected.extend(self.select_entities(page));
+            if exhausted || selected.len() >= limit {
+                break;
+            }
+        }
+        selected.truncate(limit);
+        Ok(selected)
+    }
+
+    fn entities_stream<'a>(&'a self, where_clause: &'a str) -> BoxStream<'a, Result<Entity>> {
+        self.inner
+            .entities_stream(where_clause)
+            .try_filter(move |entity| future::ready(self.keys.contains(&entity.isgl1_key)))
+            .boxed()
+    }
+
+    async fn get_all_edges(&self) -> Result<Vec<Edge>> {
+        Ok(self.select_edges(self.inner.get_all_edges().await?))
+    }
+
+    async fn query_edges(&self, where_clause: &str) -> Result<Vec<Edge>> {
+        Ok(self.select_edges(self.inner.query_edges(where_clause).await?))
+    }
+
+    async fn get_provenance(&self) -> Result<HashMap<String, String>> {
+        self.inner.get_provenance().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_file_skips_blanks_and_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.txt");
+        std::fs::write(&path, "# from last week's review\nrust:fn:a:src_lib_rs:1-2\n\n  rust:fn:b:src_lib_rs:4-5  \n").unwrap();
+
+        let selection = KeySelection::from_keys_file(&path).unwrap().extend(["rust:fn:c:src_lib_rs:7-8"]);
+        assert_eq!(
+            selection.keys,
+            ["rust:fn:a:src_lib_rs:1-2", "rust:fn:b:src_lib_rs:4-5", "rust:fn:c:src_lib_rs:7-8"]
+        );
+    }
+}
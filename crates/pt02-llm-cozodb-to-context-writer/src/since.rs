@@ -0,0 +1,111 @@
+//! Export only entities modified after a point in time (`--since`,
+//! `--since-commit`).
+//!
+//! The cut-off becomes one more Datalog condition on the stored
+//! `last_modified` column, ANDed onto the `--where-clause`, so it composes
+//! with every other filter and selection. `--since-commit` resolves a git
+//! revision to its commit time first ("what changed since release v1.2").
+//! Shells out to `git`, so it must be on `PATH`.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+
+pub use parseltongue_core::entity_filter::since_condition;
+
+/// `where_clause` ANDed with the `since` condition on `relation`, if any
+pub fn with_since(where_clause: &str, relation: &str, since: Option<DateTime<Utc>>) -> String {
+    match (where_clause, since) {
+        (_, None) => where_clause.to_string(),
+        ("ALL", Some(since)) => since_condition(relation, since),
+        (filter, Some(since)) => format!("{}, {}", filter, since_condition(relation, since)),
+    }
+}
+
+/// Parse a `--since` value: RFC 3339 (`2024-05-01T12:00:00Z`) or a date
+/// (`2024-05-01`, midnight UTC)
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|midnight| Utc.from_utc_datetime(&midnight))
+        .ok_or_else(|| anyhow!("Invalid --since '{}': expected RFC 3339 or YYYY-MM-DD", value))
+}
+
+/// Commit time of `rev` in the repository containing `repo`
+///
+/// An unknown revision in a shallow clone usually means the history was
+/// cut off before it, so the error says how to fetch it.
+pub fn commit_time(repo: &Path, rev: &str) -> Result<DateTime<Utc>> {
+    let commit = format!("{}^{{commit}}", rev);
+    let sha = match run_git(repo, &["rev-parse", "--verify", "--quiet", &commit]) {
+        Ok(sha) => sha,
+        Err(_) => {
+            let shallow = run_git(repo, &["rev-parse", "--is-shallow-repository"])
+                .with_context(|| format!("{} is not inside a git repository", repo.display()))?;
+            if shallow == "true" {
+                return Err(anyhow!(
+                    "Unknown revision '{}' in shallow clone {}; fetch more history \
+                     (git fetch --unshallow, or git fetch --tags) and retry",
+                    rev,
+                    repo.display()
+                ));
+            }
+            return Err(anyhow!("Unknown revision '{}' in {}", rev, repo.display()));
+        }
+    };
+
+    let seconds = run_git(repo, &["show", "-s", "--format=%ct", &sha])?;
+    let seconds: i64 = seconds
+        .parse()
+        .with_context(|| format!("Unexpected commit time '{}' for {}", seconds, rev))?;
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .ok_or_else(|| anyhow!("Commit time {} of {} is out of range", seconds, rev))
+}
+
+/// Trimmed stdout of `git -C repo <args>`; a non-zero exit becomes its stderr
+fn run_git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!("git {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_since_ands_onto_the_where_clause() {
+        let since = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let condition = "*CodeGraph{ISGL1_key, last_modified}, last_modified > '2024-05-01T12:00:00+00:00'";
+
+        assert_eq!(with_since("ALL", "CodeGraph", None), "ALL");
+        assert_eq!(with_since("ALL", "CodeGraph", Some(since)), condition);
+        assert_eq!(
+            with_since("entity_type = 'fn'", "CodeGraph", Some(since)),
+            format!("entity_type = 'fn', {}", condition)
+        );
+        assert!(since_condition("Scratch", since).starts_with("*Scratch{"));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let midnight = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        assert_eq!(parse_since("2024-05-01").unwrap(), midnight);
+        assert_eq!(parse_since("2024-05-01T02:00:00+02:00").unwrap(), midnight);
+        assert!(parse_since("last tuesday").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Interface-only "skeleton" of a file, rebuilt from its entities.
+//!
+//! A rustdoc-lite view for code review: doc comments and declarations in
+//! source order, nested by line range, with every function body replaced by
+//! a `{ ... }` placeholder. Type declarations (structs, enums, constants) are
+//! kept whole since their fields are part of the API surface.
+
+use parseltongue_core::entities::{CodeEntity, EntityType, Language};
+
+const INDENT: &str = "    ";
+
+/// How an entity is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SkeletonShape {
+    /// Header plus body placeholder (functions, macros)
+    Body,
+    /// Header plus nested children (impls, traits, classes, modules)
+    Container,
+    /// Full declaration text (structs, enums, constants)
+    Declaration,
+}
+
+fn shape_of(entity_type: &EntityType) -> SkeletonShape {
+    match entity_type {
+        EntityType::Function
+        | EntityType::Method
+        | EntityType::TestFunction
+        | EntityType::Macro
+        | EntityType::ProcMacro => SkeletonShape::Body,
+        EntityType::Trait
+        | EntityType::Interface
+        | EntityType::Module
+        | EntityType::ImplBlock { .. }
+        | EntityType::Class => SkeletonShape::Container,
+        EntityType::Struct | EntityType::Enum | EntityType::Variable | EntityType::Constant => {
+            SkeletonShape::Declaration
+        }
+    }
+}
+
+/// Render the skeleton of one file's entities
+///
+/// Entities may arrive in any order; they are sorted by line range and an
+/// entity is nested under the closest container whose range encloses it.
+/// Entities without code render as a one-line `name` comment.
+pub fn render_file_skeleton(entities: &[CodeEntity]) -> String {
+    let mut sorted: Vec<&CodeEntity> = entities.iter().collect();
+    sorted.sort_by_key(|e| {
+        let range = &e.interface_signature.line_range;
+        (range.start, std::cmp::Reverse(range.end))
+    });
+
+    let mut out = String::new();
+    // Open containers: (end line, whether it closes with a brace)
+    let mut open: Vec<(u32, bool)> = Vec::new();
+
+    for (idx, entity) in sorted.iter().enumerate() {
+        let signature = &entity.interface_signature;
+        close_finished(&mut out, &mut open, signature.line_range.start);
+
+        let depth = open.len();
+        let python = is_python(entity);
+        let indent = INDENT.repeat(depth);
+
+        if let Some(doc) = &signature.documentation {
+            let marker = if python { "#" } else { "///" };
+            for line in doc.lines() {
+                let rendered = format!("{} {}", marker, line);
+                push_line(&mut out, &indent, rendered.trim_end());
+            }
+        }
+
+        let Some(code) = entity.current_code.as_deref().or(entity.future_code.as_deref()) else {
+            push_line(&mut out, &indent, &format!("// {} (no source)", signature.name));
+            continue;
+        };
+        let code = dedent(code);
+
+        let has_children = sorted.get(idx + 1).is_some_and(|next| {
+            next.interface_signature.line_range.end <= signature.line_range.end
+        });
+
+        match (shape_of(&signature.entity_type), split_header(&code, python)) {
+            (SkeletonShape::Declaration, _) | (_, None) => {
+                push_block(&mut out, &indent, &code);
+            }
+            (SkeletonShape::Container, Some(header)) if has_children => {
+                let opener = if python { "" } else { " {" };
+                push_block(&mut out, &indent, &format!("{}{}", header, opener));
+                open.push((signature.line_range.end, !python));
+            }
+            (_, Some(header)) => {
+                let placeholder = if python { " ..." } else { " { ... }" };
+                push_block(&mut out, &indent, &format!("{}{}", header, placeholder));
+            }
+        }
+    }
+    close_finished(&mut out, &mut open, u32::MAX);
+
+    out
+}
+
+/// Close every open container that ends before `line`
+fn close_finished(out: &mut String, open: &mut Vec<(u32, bool)>, line: u32) {
+    while let Some(&(end, braced)) = open.last() {
+        if end >= line {
+            break;
+        }
+        open.pop();
+        if braced {
+            push_line(out, &INDENT.repeat(open.len()), "}");
+        }
+    }
+}
+
+fn is_python(entity: &CodeEntity) -> bool {
+    Language::from_file_path(&entity.interface_signature.file_path) == Some(Language::Python)
+}
+
+/// Declaration text before the body: up to the first `{`, or for Python up
+/// to the line ending in `:`
+///
+/// `None` when there is no body to elide (`struct Unit;`, trait method
+/// declarations).
+fn split_header(code: &str, python: bool) -> Option<String> {
+    let end = if python {
+        let mut offset = 0;
+        let mut found = None;
+        for line in code.split_inclusive('\n') {
+            offset += line.len();
+            if line.trim_end().ends_with(':') {
+                found = Some(offset);
+                break;
+            }
+        }
+        found?
+    } else {
+        code.find('{')?
+    };
+    Some(code[..end].trim_end().to_string())
+}
+
+/// Strip the first line's indentation from every line
+fn dedent(code: &str) -> String {
+    let first = code.lines().next().unwrap_or("");
+    let prefix = &first[..first.len() - first.trim_start().len()];
+    code.lines()
+        .map(|line| line.strip_prefix(prefix).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn push_block(out: &mut String, indent: &str, block: &str) {
+    for line in block.lines() {
+        push_line(out, indent, line);
+    }
+}
+
+fn push_line(out: &mut String, indent: &str, line: &str) {
+    if line.is_empty() {
+        out.push('\n');
+    } else {
+        out.push_str(indent);
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_header_per_language() {
+        assert_eq!(
+            split_header("fn add(a: i32) -> i32 {\n    a\n}", false).as_deref(),
+            Some("fn add(a: i32) -> i32")
+        );
+        assert_eq!(
+            split_header("def add(a,\n        b):\n    return a + b", true).as_deref(),
+            Some("def add(a,\n        b):")
+        );
+        assert_eq!(split_header("struct Unit;", false), None);
+    }
+
+    #[test]
+    fn test_dedent_keeps_relative_indentation() {
+        assert_eq!(
+            dedent("    fn f()\n    where\n        T: Copy {}"),
+            "fn f()\nwhere\n    T: Copy {}"
+        );
+    }
+}
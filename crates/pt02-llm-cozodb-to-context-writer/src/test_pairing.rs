@@ -0,0 +1,100 @@
+//! Test pairing for Level 1 exports (`--pair-tests`).
+//!
+//! Each production entity lists the test entities that exercise it in its
+//! `tests` array, so an LLM reads the code and its tests together. Which
+//! test belongs to which entity is up to a [`TestMatcher`]; the default,
+//! [`NamingConventionMatcher`], pairs `add` with `test_add` and `add_test`.
+//!
+//! Tests are looked up across the whole database, not just the exported
+//! filter, since they usually live in another file. Ingestion stores test
+//! entities only when asked to (`pt01 --keep-tests`).
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::models::{EntityExportLevel1, PairedTest};
+
+/// Decides whether a test entity exercises a production entity
+pub trait TestMatcher: Send + Sync {
+    fn is_test_of(&self, test: &EntityExportLevel1, production: &EntityExportLevel1) -> bool;
+
+    /// Names of the only production entities `test` can be a test of
+    ///
+    /// Lets [`pair_tests`] index tests by name instead of comparing every
+    /// test with every entity. `None` (the default) means any entity.
+    fn tested_names<'t>(&self, _test: &'t EntityExportLevel1) -> Option<Vec<&'t str>> {
+        None
+    }
+}
+
+/// Pairs by name: `test_<name>` or `<name>_test`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NamingConventionMatcher;
+
+impl TestMatcher for NamingConventionMatcher {
+    fn is_test_of(&self, test: &EntityExportLevel1, production: &EntityExportLevel1) -> bool {
+        let name = production.entity_name.as_str();
+        test.entity_name.strip_prefix("test_") == Some(name) || test.entity_name.strip_suffix("_test") == Some(name)
+    }
+
+    fn tested_names<'t>(&self, test: &'t EntityExportLevel1) -> Option<Vec<&'t str>> {
+        Some(
+            [test.entity_name.strip_prefix("test_"), test.entity_name.strip_suffix("_test")]
+                .into_iter()
+                .flatten()
+                .collect(),
+        )
+    }
+}
+
+/// Fill each production entity's `tests` with the tests `matcher` pairs it with
+///
+/// Tests keep their order in `tests`; one test may be paired with several
+/// entities. Only the tests indexed under an entity's name (see
+/// [`TestMatcher::tested_names`]) and the unindexed ones are checked.
+pub fn pair_tests(production: &mut [EntityExportLevel1], tests: &[EntityExportLevel1], matcher: &dyn TestMatcher) {
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut unindexed: Vec<usize> = Vec::new();
+    for (index, test) in tests.iter().enumerate() {
+        match matcher.tested_names(test) {
+            Some(names) => names.into_iter().for_each(|name| by_name.entry(name).or_default().push(index)),
+            None => unindexed.push(index),
+        }
+    }
+
+    for entity in production.iter_mut() {
+        let mut candidates = by_name.get(entity.entity_name.as_str()).cloned().unwrap_or_default();
+        candidates.extend(&unindexed);
+        candidates.sort_unstable();
+        candidates.dedup();
+        entity.tests = candidates
+            .into_iter()
+            .map(|index| &tests[index])
+            .filter(|test| matcher.is_test_of(test, entity))
+            .map(|test| PairedTest {
+                isgl1_key: test.isgl1_key.clone(),
+                entity_name: test.entity_name.clone(),
+                file_path: test.file_path.clone(),
+                line_number: test.line_number,
+                current_code: test.current_code.clone(),
+            })
+            .collect();
+    }
+}
+
+/// TOON rows of paired entities, with each `tests` array reduced to keys
+///
+/// TOON tables cannot nest objects, so the full tests stay in the JSON.
+pub fn with_test_keys(entities: &[EntityExportLevel1]) -> serde_json::Result<Vec<Value>> {
+    entities
+        .iter()
+        .map(|entity| {
+            let mut row = serde_json::to_value(entity)?;
+            if let Some(tests) = row.get_mut("tests") {
+                *tests = entity.tests.iter().map(|test| Value::String(test.isgl1_key.clone())).collect();
+            }
+            Ok(row)
+        })
+        .collect()
+}
@@ -0,0 +1,104 @@
+//! `--exclude-tests` / `--exclude-private` / `--exclude-generated`
+//! production API context
+//!
+//! Tests are recognized by the `entity_class` ingestion stored, private
+//! helpers by the visibility in the stored signature, generated code by
+//! pt01's `GeneratedEntities` flags.
+
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{with_api_scope, without_generated, CodeGraphRepository, CozoDbAdapter};
+use std::path::PathBuf;
+
+fn entity(name: &str, visibility: Visibility, entity_class: EntityClass, line: u32) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(line, line + 2).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:fn:{}:src_lib_rs:{}-{}", name, line, line + 2);
+    let mut entity = CodeEntity::new(key, signature, entity_class).unwrap();
+    entity.current_code = Some(format!("fn {}() {{}}", name));
+    entity.future_code = entity.current_code.clone();
+    entity
+}
+
+async fn seeded() -> CozoDbAdapter {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    for entity in [
+        entity("api", Visibility::Public, EntityClass::CodeImplementation, 1),
+        entity("helper", Visibility::Private, EntityClass::CodeImplementation, 5),
+        entity("test_api", Visibility::Private, EntityClass::TestImplementation, 9),
+    ] {
+        db.insert_entity(&entity).await.unwrap();
+    }
+    CozoDbAdapter::new(db)
+}
+
+async fn names(adapter: &CozoDbAdapter, where_clause: &str) -> Vec<String> {
+    let mut names: Vec<_> = adapter
+        .query_entities(where_clause)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.isgl1_key.split(':').nth(2).unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_exclude_tests_removes_only_test_entities() {
+    let adapter = seeded().await;
+
+    assert_eq!(names(&adapter, &with_api_scope("ALL", false, false)).await, ["api", "helper", "test_api"]);
+    assert_eq!(names(&adapter, &with_api_scope("ALL", true, false)).await, ["api", "helper"]);
+}
+
+#[tokio::test]
+async fn test_exclude_private_removes_non_public_entities() {
+    let adapter = seeded().await;
+
+    assert_eq!(names(&adapter, &with_api_scope("ALL", false, true)).await, ["api"]);
+    assert_eq!(
+        names(&adapter, &with_api_scope("entity_type = 'function'", true, true)).await,
+        ["api"]
+    );
+}
+
+#[tokio::test]
+async fn test_generated_entities_are_flagged_and_excludable() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let api = entity("api", Visibility::Public, EntityClass::CodeImplementation, 1);
+    let bindings = entity("bindings", Visibility::Public, EntityClass::CodeImplementation, 5);
+    db.insert_entity(&api).await.unwrap();
+    db.insert_entity(&bindings).await.unwrap();
+    db.replace_generated_entities("src/lib.rs", &[bindings.isgl1_key.clone()]).await.unwrap();
+    let adapter = CozoDbAdapter::new(db);
+
+    let flagged: Vec<_> = adapter
+        .get_all_entities()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.isgl1_key.split(':').nth(2).unwrap().to_string(), e.is_generated))
+        .collect();
+    assert!(flagged.contains(&("api".to_string(), false)), "{:?}", flagged);
+    assert!(flagged.contains(&("bindings".to_string(), true)), "{:?}", flagged);
+
+    assert_eq!(names(&adapter, &without_generated("ALL", true)).await, ["api"]);
+    assert_eq!(adapter.count_entities(&without_generated("ALL", true)).await.unwrap(), 1);
+}
@@ -0,0 +1,146 @@
+//! Fixtures shared by the pt02 integration tests
+//!
+//! Every test binary compiles its own copy of this module and uses only
+//! part of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use parseltongue_core::output_sink::OutputSink;
+use pt02_llm_cozodb_to_context_writer::export_trait::{CodeGraphRepository, Edge, Entity};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Records every write instead of performing it
+#[derive(Default)]
+pub struct CapturingSink {
+    pub writes: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl CapturingSink {
+    pub fn text(&self, name: &str) -> String {
+        String::from_utf8(self.writes.lock().unwrap()[name].clone()).unwrap()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.writes.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Every artifact written, concatenated
+    pub fn everything(&self) -> String {
+        self.writes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .collect()
+    }
+
+    /// Number of rows in the `entities` array of a JSON export
+    pub fn rows(&self, name: &str) -> usize {
+        let json: serde_json::Value = serde_json::from_slice(&self.writes.lock().unwrap()[name]).unwrap();
+        json["entities"].as_array().unwrap().len()
+    }
+
+    pub fn into_writes(self) -> BTreeMap<String, Vec<u8>> {
+        self.writes.into_inner().unwrap()
+    }
+}
+
+#[async_trait]
+impl OutputSink for CapturingSink {
+    async fn write_all(&self, name: &str, bytes: &[u8]) -> parseltongue_core::Result<()> {
+        self.writes.lock().unwrap().insert(name.to_string(), bytes.to_vec());
+        Ok(())
+    }
+}
+
+/// Accepts and drops every write
+pub struct DiscardingSink;
+
+#[async_trait]
+impl OutputSink for DiscardingSink {
+    async fn write_all(&self, _name: &str, _bytes: &[u8]) -> parseltongue_core::Result<()> {
+        Ok(())
+    }
+}
+
+/// In-memory graph, returning rows in the order given like a database
+/// without ORDER BY
+///
+/// `query_entities` understands only the `entity_class = '...'` scope the
+/// exporters compose; any other clause matches every entity.
+#[derive(Default)]
+pub struct MemoryRepository {
+    pub entities: Vec<Entity>,
+    pub edges: Vec<Edge>,
+}
+
+#[async_trait]
+impl CodeGraphRepository for MemoryRepository {
+    async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+        Ok(self.entities.clone())
+    }
+
+    async fn query_entities(&self, where_clause: &str) -> Result<Vec<Entity>> {
+        if !where_clause.contains("entity_class = '") {
+            return Ok(self.entities.clone());
+        }
+        Ok(self
+            .entities
+            .iter()
+            .filter(|e| where_clause.contains(&format!("entity_class = '{}'", e.entity_class)))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_all_edges(&self) -> Result<Vec<Edge>> {
+        Ok(self.edges.clone())
+    }
+
+    async fn query_edges(&self, _where_clause: &str) -> Result<Vec<Edge>> {
+        Ok(self.edges.clone())
+    }
+}
+
+pub fn key(name: &str) -> String {
+    format!("rust:fn:{}:src_lib_rs:1-3", name)
+}
+
+/// Public `fn name() {}` at line 1 of `src/lib.rs`; tests override fields
+/// with struct update syntax
+pub fn entity(name: &str) -> Entity {
+    Entity {
+        isgl1_key: key(name),
+        forward_deps: vec![],
+        reverse_deps: vec![],
+        current_ind: 1,
+        future_ind: 0,
+        future_action: None,
+        future_code: None,
+        current_code: Some(format!("fn {}() {{}}", name)),
+        entity_name: name.to_string(),
+        entity_type: "fn".to_string(),
+        file_path: "src/lib.rs".to_string(),
+        line_number: 1,
+        interface_signature: format!("fn {}()", name),
+        doc_comment: None,
+        entity_class: "CODE".to_string(),
+        return_type: None,
+        param_types: None,
+        param_names: None,
+        generic_constraints: None,
+        trait_impls: None,
+        is_public: Some(true),
+        is_async: Some(false),
+        is_unsafe: Some(false),
+        lsp_metadata: None,
+        is_generated: false,
+        complexity_score: None,
+    }
+}
+
+pub fn calls(from: &str, to: &str) -> Edge {
+    Edge { from_key: key(from), to_key: key(to), edge_type: "Calls".to_string() }
+}
@@ -0,0 +1,75 @@
+//! Exported `complexity_score`
+//!
+//! pt01 stores each entity's cyclomatic complexity in the `complexity_score`
+//! column; Level 1 and 2 exports carry it, and omit it when unmeasured.
+
+mod common;
+
+use common::DiscardingSink;
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{
+    CodeGraphRepository, CozoDbAdapter, ExportConfig, Level1Exporter, LevelExporter,
+};
+use std::path::PathBuf;
+
+fn entity(name: &str, complexity_score: Option<u32>) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:fn:{}:src_lib_rs:1-3", name);
+    let mut entity = CodeEntity::new(key, signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(format!("fn {}() {{}}", name));
+    entity.future_code = entity.current_code.clone();
+    entity.metadata.complexity_score = complexity_score;
+    entity
+}
+
+#[tokio::test]
+async fn test_complexity_score_is_stored_and_exported() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    db.insert_entity(&entity("branchy", Some(7))).await.unwrap();
+    db.insert_entity(&entity("unmeasured", None)).await.unwrap();
+    assert_eq!(db.get_entity("rust:fn:branchy:src_lib_rs:1-3").await.unwrap().metadata.complexity_score, Some(7));
+    let adapter = CozoDbAdapter::new(db);
+
+    let config = ExportConfig {
+        level: 1,
+        include_code: false,
+        where_filter: "ALL".to_string(),
+        output_path: "export.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    };
+    let output = Level1Exporter::new().export_to(&adapter, &config, &DiscardingSink).await.unwrap();
+    let entities = output.entities.unwrap();
+    let exported = |name: &str| {
+        entities
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["entity_name"] == name)
+            .cloned()
+            .unwrap()
+    };
+
+    assert_eq!(exported("branchy")["complexity_score"], 7);
+    assert!(exported("unmeasured").get("complexity_score").is_none(), "omitted when unmeasured");
+    assert_eq!(adapter.count_entities("ALL").await.unwrap(), 2);
+}
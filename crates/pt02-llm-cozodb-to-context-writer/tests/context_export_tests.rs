@@ -0,0 +1,93 @@
+//! `--as-context`: Level 1 in pt03's `CodeGraphContext` shape
+//!
+//! The emitted JSON must deserialize into `CodeGraphContext`, carry the
+//! stored signature/TDD data, and respect the WHERE clause.
+
+use parseltongue_core::entities::*;
+use parseltongue_core::interfaces::CodeGraphContext;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{export_code_graph_context, CozoDbAdapter};
+use std::path::PathBuf;
+
+fn code_entity(name: &str, class: EntityClass) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: Some(format!("Runs {}", name)),
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity = CodeEntity::new(key(name), signature, class).unwrap();
+    entity.current_code = Some(format!("fn {}() {{}}", name));
+    entity
+}
+
+fn key(name: &str) -> String {
+    format!("rust:fn:{}:src_lib_rs:1-3", name)
+}
+
+async fn sample_database() -> CozoDbAdapter {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    db.insert_entity(&code_entity("run", EntityClass::CodeImplementation)).await.unwrap();
+    db.insert_entity(&code_entity("parse", EntityClass::CodeImplementation)).await.unwrap();
+    db.insert_entity(&code_entity("test_run", EntityClass::TestImplementation)).await.unwrap();
+    let edge = DependencyEdge::builder()
+        .from_key(key("run"))
+        .to_key(key("parse"))
+        .edge_type(EdgeType::Calls)
+        .build()
+        .unwrap();
+    db.insert_edge(&edge).await.unwrap();
+    CozoDbAdapter::new(db)
+}
+
+#[tokio::test]
+async fn test_as_context_json_deserializes_into_code_graph_context() {
+    let adapter = sample_database().await;
+    let generated_at = chrono::DateTime::<chrono::Utc>::UNIX_EPOCH;
+
+    let context = export_code_graph_context(&adapter, adapter.get_code_entities().await.unwrap(), "ALL", generated_at)
+        .await
+        .unwrap();
+    let json = serde_json::to_string_pretty(&context).unwrap();
+
+    let parsed: CodeGraphContext = serde_json::from_str(&json).unwrap();
+    let keys: Vec<_> = parsed.entities.iter().map(|e| e.isgl1_key.as_str()).collect();
+    assert_eq!(keys, vec![key("parse"), key("run"), key("test_run")]);
+    assert_eq!(parsed.entities[1].interface_signature.documentation.as_deref(), Some("Runs run"));
+    assert_eq!(parsed.relationships.len(), 1);
+    assert_eq!(parsed.relationships[0].dependent, key("run"));
+    assert_eq!(parsed.relationships[0].dependency, key("parse"));
+    assert_eq!(parsed.generated_at, generated_at);
+    assert!(parsed.token_count > 0);
+    // No code in the context, only the pt03 fields
+    assert!(!json.contains("Current_Code") && !json.contains("fn run() {}"), "{}", json);
+}
+
+#[tokio::test]
+async fn test_as_context_honours_where_clause() {
+    let adapter = sample_database().await;
+
+    let context = export_code_graph_context(
+        &adapter,
+        adapter.get_code_entities().await.unwrap(),
+        "entity_class = 'TEST'",
+        chrono::Utc::now(),
+    )
+    .await
+    .unwrap();
+
+    let keys: Vec<_> = context.entities.iter().map(|e| e.isgl1_key.as_str()).collect();
+    assert_eq!(keys, vec![key("test_run")]);
+    assert!(context.relationships.is_empty(), "edges leave unselected entities only");
+}
@@ -0,0 +1,101 @@
+//! `--count-only` fast path
+//!
+//! The Datalog `count` for a level and filter must equal the number of
+//! rows the full export writes for the same filter.
+
+mod common;
+
+use anyhow::Result;
+use common::CapturingSink;
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{scope_to_entity_class, CodeGraphRepository, CozoDbAdapter, Level1Exporter};
+use std::path::PathBuf;
+
+fn entity(name: &str, entity_type: EntityType, entity_class: EntityClass, line: u32) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(line, line + 2).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:item:{}:src_lib_rs:{}-{}", name, line, line + 2);
+    let mut entity = CodeEntity::new(key, signature, entity_class).unwrap();
+    entity.current_code = Some(format!("// {}", name));
+    entity.future_code = entity.current_code.clone();
+    entity
+}
+
+async fn seeded() -> Result<CozoDbAdapter> {
+    let db = CozoDbStorage::new("mem").await?;
+    db.ensure_schema().await?;
+    for entity in [
+        entity("run", EntityType::Function, EntityClass::CodeImplementation, 1),
+        entity("helper", EntityType::Function, EntityClass::CodeImplementation, 5),
+        entity("Config", EntityType::Struct, EntityClass::CodeImplementation, 9),
+        entity("test_run", EntityType::Function, EntityClass::TestImplementation, 13),
+    ] {
+        db.insert_entity(&entity).await?;
+    }
+    // Two edges from the same key: the count must not collapse them
+    for to in ["helper", "Config"] {
+        let edge = DependencyEdge::builder()
+            .from_key("rust:item:run:src_lib_rs:1-3")
+            .to_key(format!("rust:item:{}:src_lib_rs:0-0", to))
+            .edge_type(EdgeType::Calls)
+            .build()?;
+        db.insert_edge(&edge).await?;
+    }
+    Ok(CozoDbAdapter::new(db))
+}
+
+#[tokio::test]
+async fn test_entity_count_matches_full_export() -> Result<()> {
+    let adapter = seeded().await?;
+
+    for filter in ["ALL", "entity_type = 'function'"] {
+        let sink = CapturingSink::default();
+        Level1Exporter::new()
+            .export_dual_files_to(&adapter, &sink, "ctx", false, filter, false)
+            .await?;
+
+        let code = adapter.count_entities(&scope_to_entity_class("CODE", filter)).await?;
+        assert_eq!(code, sink.rows("ctx.json"), "CODE rows for {}", filter);
+
+        let test_filter = scope_to_entity_class("TEST", filter);
+        assert_eq!(
+            adapter.count_entities(&test_filter).await?,
+            adapter.query_entities(&test_filter).await?.len(),
+            "TEST rows for {}",
+            filter
+        );
+    }
+    assert_eq!(adapter.count_entities("entity_type = 'function'").await?, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_edge_count_matches_queried_edges() -> Result<()> {
+    let adapter = seeded().await?;
+
+    for filter in ["ALL", "edge_type = 'Calls'", "edge_type = 'Uses'"] {
+        assert_eq!(
+            adapter.count_edges(filter).await?,
+            adapter.query_edges(filter).await?.len(),
+            "edges for {}",
+            filter
+        );
+    }
+    assert_eq!(adapter.count_edges("ALL").await?, 2);
+    Ok(())
+}
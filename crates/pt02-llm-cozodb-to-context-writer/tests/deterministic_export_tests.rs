@@ -0,0 +1,95 @@
+//! Byte-identical exports (`--no-timestamp`)
+//!
+//! The same graph must export to the same bytes however the database
+//! happens to order its rows, once the timestamp is left out.
+
+mod common;
+
+use common::{calls, entity, CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{export_trait::Entity, Level0Exporter, Level1Exporter, Level2Exporter};
+use std::collections::BTreeMap;
+
+/// The same graph, its rows in either order
+fn sample(reversed: bool) -> MemoryRepository {
+    let mut entities: Vec<Entity> = [
+        ("parse", "src/parser.rs", 10, "CODE"),
+        ("tokenize", "src/parser.rs", 2, "CODE"),
+        ("run", "src/main.rs", 1, "CODE"),
+        ("test_parse", "tests/parser.rs", 1, "TEST"),
+        ("test_run", "tests/main.rs", 1, "TEST"),
+    ]
+    .into_iter()
+    .map(|(name, file, line, class)| Entity {
+        file_path: file.to_string(),
+        line_number: line,
+        entity_class: class.to_string(),
+        ..entity(name)
+    })
+    .collect();
+    let mut edges = vec![calls("run", "parse"), calls("parse", "tokenize"), calls("run", "tokenize")];
+    if reversed {
+        entities.reverse();
+        edges.reverse();
+    }
+    MemoryRepository { entities, edges }
+}
+
+/// Every file written by one export of each level
+async fn export_all_levels(db: &MemoryRepository, include_timestamp: bool) -> BTreeMap<String, Vec<u8>> {
+    let sink = CapturingSink::default();
+    Level0Exporter::new()
+        .with_timestamp(include_timestamp)
+        .export_dual_files_to(db, &sink, "edges", "ALL", false)
+        .await
+        .unwrap();
+    Level1Exporter::new()
+        .with_timestamp(include_timestamp)
+        .export_dual_files_to(db, &sink, "entities", true, "ALL", false)
+        .await
+        .unwrap();
+    Level2Exporter::new()
+        .with_timestamp(include_timestamp)
+        .export_dual_files_to(db, &sink, "typed", true, "ALL", false)
+        .await
+        .unwrap();
+    sink.into_writes()
+}
+
+#[tokio::test]
+async fn test_no_timestamp_exports_are_byte_identical() {
+    let first = export_all_levels(&sample(false), false).await;
+    let second = export_all_levels(&sample(true), false).await;
+
+    assert!(first.contains_key("entities.json") && first.contains_key("typed_test.json"));
+    for (name, bytes) in &first {
+        assert_eq!(
+            String::from_utf8_lossy(bytes),
+            String::from_utf8_lossy(&second[name]),
+            "{} differs between runs",
+            name
+        );
+        assert!(!String::from_utf8_lossy(bytes).contains("\"timestamp\""), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_entities_sorted_by_file_line_and_key() {
+    let writes = export_all_levels(&sample(true), false).await;
+
+    let json: serde_json::Value = serde_json::from_slice(&writes["entities.json"]).unwrap();
+    let names: Vec<_> = json["entities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["entity_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["run", "tokenize", "parse"]);
+}
+
+#[tokio::test]
+async fn test_timestamp_written_by_default() {
+    let writes = export_all_levels(&sample(false), true).await;
+
+    let json: serde_json::Value = serde_json::from_slice(&writes["entities.json"]).unwrap();
+    assert!(json["export_metadata"]["timestamp"].as_str().unwrap().contains('T'));
+}
@@ -0,0 +1,93 @@
+//! `--dedup-docs` export
+//!
+//! A doc comment shared by several entities is written once in
+//! `shared_docs`; the entities reference it by id.
+
+mod common;
+
+use common::{CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{export_trait::Entity, ExportConfig, Level1Exporter, LevelExporter};
+use serde_json::Value;
+
+const LICENSE_DOC: &str = "Copyright (c) Example Corp. Licensed under the Apache License 2.0.";
+
+fn entity(name: &str, line_number: u32, doc: &str) -> Entity {
+    Entity {
+        isgl1_key: format!("rust:fn:{}:src_lib_rs:{}-{}", name, line_number, line_number + 2),
+        line_number,
+        doc_comment: Some(doc.to_string()),
+        ..common::entity(name)
+    }
+}
+
+#[tokio::test]
+async fn test_shared_doc_comment_is_emitted_once() {
+    let db = MemoryRepository {
+        entities: vec![
+            entity("alpha", 1, LICENSE_DOC),
+            entity("beta", 10, LICENSE_DOC),
+            entity("gamma", 20, LICENSE_DOC),
+            entity("delta", 30, "Only delta has this doc"),
+        ],
+        ..Default::default()
+    };
+    let config = ExportConfig {
+        level: 1,
+        include_code: false,
+        where_filter: "ALL".to_string(),
+        output_path: "level1.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    };
+
+    let sink = CapturingSink::default();
+    let output = Level1Exporter::new()
+        .with_dedup_docs(true)
+        .export_to(&db, &config, &sink)
+        .await
+        .unwrap();
+
+    let shared_docs = output.shared_docs.expect("shared_docs present with --dedup-docs");
+    assert_eq!(shared_docs.len(), 1);
+    assert_eq!(shared_docs["doc1"], LICENSE_DOC);
+
+    let text = sink.text("level1.json");
+    assert_eq!(text.matches(LICENSE_DOC).count(), 1, "shared doc written once:\n{}", text);
+
+    let json: Value = serde_json::from_str(&text).unwrap();
+    let entities = json["entities"].as_array().unwrap();
+    assert_eq!(entities.len(), 4);
+    for entity in &entities[..3] {
+        assert_eq!(entity["doc_ref"], "doc1", "{}", entity);
+        assert!(entity.get("doc_comment").is_none(), "{}", entity);
+    }
+    assert_eq!(entities[3]["doc_comment"], "Only delta has this doc", "unique docs stay inline");
+    assert!(entities[3].get("doc_ref").is_none());
+}
+
+#[tokio::test]
+async fn test_export_without_dedup_has_no_shared_docs() {
+    let db = MemoryRepository {
+        entities: vec![entity("alpha", 1, LICENSE_DOC), entity("beta", 10, LICENSE_DOC)],
+        ..Default::default()
+    };
+    let config = ExportConfig {
+        level: 1,
+        include_code: false,
+        where_filter: "ALL".to_string(),
+        output_path: "level1.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    };
+
+    let sink = CapturingSink::default();
+    let output = Level1Exporter::new().export_to(&db, &config, &sink).await.unwrap();
+
+    assert!(output.shared_docs.is_none());
+    let text = sink.text("level1.json");
+    assert_eq!(text.matches(LICENSE_DOC).count(), 2);
+}
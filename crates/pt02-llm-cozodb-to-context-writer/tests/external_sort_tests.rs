@@ -0,0 +1,91 @@
+//! `--external-sort` Level 1 export
+//!
+//! Paging through CozoDB into small sorted runs and merging them must give
+//! the same entity list as sorting everything in memory, and leave no run
+//! files behind.
+
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{
+    sort_for_export, CozoDbAdapter, ExportConfig, ExternalSortExporter, Level1Exporter, LevelExporter,
+};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn entity(name: &str, file: &str, line: u32) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from(file),
+        line_range: LineRange::new(line, line + 1).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:fn:{}:{}:{}-{}", name, file.replace(['/', '.'], "_"), line, line + 1);
+    let mut entity = CodeEntity::new(key, signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(format!("fn {}() {{}}", name));
+    entity.future_code = entity.current_code.clone();
+    entity
+}
+
+/// 23 entities whose key order differs from file order
+async fn seeded() -> CozoDbAdapter {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    for i in 0..23u32 {
+        let file = format!("src/m{}.rs", (i * 7) % 5);
+        db.insert_entity(&entity(&format!("f{:02}", (i * 11) % 23), &file, i * 3 + 1))
+            .await
+            .unwrap();
+    }
+    CozoDbAdapter::new(db)
+}
+
+#[tokio::test]
+async fn test_external_sort_matches_in_memory_sort() {
+    let adapter = seeded().await;
+    let temp = TempDir::new().unwrap();
+
+    let mut output = Vec::new();
+    let written = ExternalSortExporter::new()
+        .with_run_size(4)
+        .with_temp_dir(temp.path())
+        .export_level1(&adapter, "entity_class = 'CODE'", true, &mut output)
+        .await
+        .unwrap();
+    let external: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    let config = ExportConfig {
+        level: 1,
+        include_code: true,
+        where_filter: "entity_class = 'CODE'".to_string(),
+        output_path: temp.path().join("in_memory.json"),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    };
+    let in_memory = Level1Exporter::new().export(&adapter, &config).await.unwrap();
+    let mut entities: Vec<pt02_llm_cozodb_to_context_writer::EntityExportLevel1> =
+        serde_json::from_value(in_memory.entities.unwrap()).unwrap();
+    sort_for_export(&mut entities);
+
+    assert_eq!(written, 23);
+    assert_eq!(external["export_metadata"]["total_entities"], 23);
+    assert_eq!(external["entities"], serde_json::to_value(&entities).unwrap());
+
+    let leftovers: Vec<_> = std::fs::read_dir(temp.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("pt02-sort-run-"))
+        .collect();
+    assert!(leftovers.is_empty(), "run files left behind: {:?}", leftovers);
+}
@@ -68,6 +68,9 @@ impl IntegrationMockDatabase {
                     is_public: Some(true),
                     is_async: Some(true),
                     is_unsafe: Some(false),
+                    lsp_metadata: None,
+                    is_generated: false,
+                    complexity_score: None,
                 },
 
                 // Private sync function without type info
@@ -96,6 +99,9 @@ impl IntegrationMockDatabase {
                     is_public: Some(false),
                     is_async: Some(false),
                     is_unsafe: Some(false),
+                    lsp_metadata: None,
+                    is_generated: false,
+                    complexity_score: None,
                 },
 
                 // Struct with trait implementations
@@ -124,6 +130,9 @@ impl IntegrationMockDatabase {
                     is_public: Some(true),
                     is_async: None,
                     is_unsafe: None,
+                    lsp_metadata: None,
+                    is_generated: false,
+                    complexity_score: None,
                 },
             ],
             edges: vec![
@@ -227,6 +236,7 @@ fn create_config(level: u8, include_code: bool, where_filter: &str, output_path:
         // v0.9.0: Dual output fields for code/test separation
         code_output_path: None,
         tests_output_path: None,
+        compact_json: false,
     }
 }
 
@@ -241,7 +251,7 @@ async fn test_integration_level0_export_all_edges() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level0.json");
     let config = create_config(0, false, "ALL", output_path.clone());
-    let exporter = Level0Exporter;
+    let exporter = Level0Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -271,7 +281,7 @@ async fn test_integration_level0_filter_depends_on() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level0_filtered.json");
     let config = create_config(0, false, "edge_type = 'depends_on'", output_path);
-    let exporter = Level0Exporter;
+    let exporter = Level0Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -292,7 +302,7 @@ async fn test_integration_level0_empty_database() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level0_empty.json");
     let config = create_config(0, false, "ALL", output_path);
-    let exporter = Level0Exporter;
+    let exporter = Level0Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -316,7 +326,7 @@ async fn test_integration_level1_export_all_entities_no_code() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level1_no_code.json");
     let config = create_config(1, false, "ALL", output_path);
-    let exporter = Level1Exporter;
+    let exporter = Level1Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -345,7 +355,7 @@ async fn test_integration_level1_export_with_code() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level1_with_code.json");
     let config = create_config(1, true, "ALL", output_path);
-    let exporter = Level1Exporter;
+    let exporter = Level1Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -366,7 +376,7 @@ async fn test_integration_level1_filter_public_functions() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level1_public.json");
     let config = create_config(1, false, "is_public = true", output_path);
-    let exporter = Level1Exporter;
+    let exporter = Level1Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -391,7 +401,7 @@ async fn test_integration_level1_filter_with_future_action() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level1_future.json");
     let config = create_config(1, false, "future_action != null", output_path);
-    let exporter = Level1Exporter;
+    let exporter = Level1Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -416,7 +426,7 @@ async fn test_integration_level1_dependency_arrays() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level1_deps.json");
     let config = create_config(1, false, "ALL", output_path);
-    let exporter = Level1Exporter;
+    let exporter = Level1Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -442,7 +452,7 @@ async fn test_integration_level2_type_system_fields() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level2.json");
     let config = create_config(2, false, "ALL", output_path);
-    let exporter = Level2Exporter;
+    let exporter = Level2Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -467,7 +477,7 @@ async fn test_integration_level2_includes_level1_fields() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level2_full.json");
     let config = create_config(2, false, "ALL", output_path);
-    let exporter = Level2Exporter;
+    let exporter = Level2Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -495,7 +505,7 @@ async fn test_integration_level2_filter_async_functions() {
     let temp_dir = TempDir::new().unwrap();
     let output_path = temp_dir.path().join("level2_async.json");
     let config = create_config(2, false, "is_async = true", output_path);
-    let exporter = Level2Exporter;
+    let exporter = Level2Exporter::new();
 
     // Act
     let result = exporter.export(&db, &config).await;
@@ -524,7 +534,7 @@ async fn test_integration_cross_level_field_progression() {
     let temp_dir = TempDir::new().unwrap();
 
     // Level 0: Only edges
-    let output0 = Level0Exporter.export(&db, &create_config(
+    let output0 = Level0Exporter::new().export(&db, &create_config(
         0, false, "ALL", temp_dir.path().join("l0.json")
     )).await.unwrap();
 
@@ -535,7 +545,7 @@ async fn test_integration_cross_level_field_progression() {
     assert!(!json0.contains("\"return_type\""));
 
     // Level 1: Entities + ISG (no type system)
-    let output1 = Level1Exporter.export(&db, &create_config(
+    let output1 = Level1Exporter::new().export(&db, &create_config(
         1, false, "ALL", temp_dir.path().join("l1.json")
     )).await.unwrap();
 
@@ -546,7 +556,7 @@ async fn test_integration_cross_level_field_progression() {
     assert!(!json1.contains("\"return_type\""), "Level 1 should NOT have type fields");
 
     // Level 2: All fields
-    let output2 = Level2Exporter.export(&db, &create_config(
+    let output2 = Level2Exporter::new().export(&db, &create_config(
         2, false, "ALL", temp_dir.path().join("l2.json")
     )).await.unwrap();
 
@@ -603,7 +613,7 @@ async fn test_integration_json_serialization_valid() {
     let temp_dir = TempDir::new().unwrap();
 
     // Level 0
-    let output0 = Level0Exporter.export(&db, &create_config(
+    let output0 = Level0Exporter::new().export(&db, &create_config(
         0, false, "ALL", temp_dir.path().join("json0.json")
     )).await.unwrap();
 
@@ -623,7 +633,7 @@ async fn test_integration_timestamp_format() {
     let db = IntegrationMockDatabase::create_realistic();
     let temp_dir = TempDir::new().unwrap();
 
-    let output = Level0Exporter.export(&db, &create_config(
+    let output = Level0Exporter::new().export(&db, &create_config(
         0, false, "ALL", temp_dir.path().join("timestamp.json")
     )).await.unwrap();
 
@@ -0,0 +1,51 @@
+//! `--keys` subset export
+//!
+//! Only the selected entities (plus, with `--with-deps`, their one-hop
+//! dependencies) are exported; keys matching nothing are reported.
+
+mod common;
+
+use common::{calls, entity, key, CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{export_trait::CodeGraphRepository, KeySelection, Level1Exporter};
+
+#[tokio::test]
+async fn test_two_key_selection_with_deps_pulls_in_dependency() {
+    let db = MemoryRepository {
+        entities: ["parse", "render", "tokenize", "unrelated"].into_iter().map(entity).collect(),
+        edges: vec![calls("parse", "tokenize"), calls("render", "println"), calls("unrelated", "parse")],
+    };
+
+    let selected = KeySelection::new([key("parse"), key("render"), key("vanished")])
+        .with_deps(true)
+        .resolve(&db)
+        .await
+        .unwrap();
+    assert_eq!(selected.missing(), [key("vanished")]);
+    assert_eq!(selected.len(), 3, "parse, render and the tokenize dependency");
+
+    let sink = CapturingSink::default();
+    Level1Exporter::new()
+        .export_dual_files_to(&selected, &sink, "ctx", false, "ALL", false)
+        .await
+        .unwrap();
+
+    let json = sink.text("ctx.json");
+    assert!(json.contains(&key("parse")));
+    assert!(json.contains(&key("render")));
+    assert!(json.contains(&key("tokenize")), "dependency pulled in: {}", json);
+    assert!(!json.contains(&key("unrelated")), "{}", json);
+}
+
+#[tokio::test]
+async fn test_selection_without_deps_exports_only_listed_keys() {
+    let db = MemoryRepository {
+        entities: ["parse", "tokenize"].into_iter().map(entity).collect(),
+        edges: vec![calls("parse", "tokenize")],
+    };
+
+    let selected = KeySelection::new([key("parse")]).resolve(&db).await.unwrap();
+
+    assert!(selected.missing().is_empty());
+    assert_eq!(selected.get_all_entities().await.unwrap().len(), 1);
+    assert_eq!(selected.get_all_edges().await.unwrap().len(), 1);
+}
@@ -18,7 +18,7 @@
 
 use anyhow::Result;
 use pt02_llm_cozodb_to_context_writer::{
-    models::{DependencyEdge, ExportConfig},
+    models::{DependencyEdge, ExportConfig, ExportOutput},
     export_trait::{CodeGraphRepository, Edge, Entity, LevelExporter},
     exporters::Level0Exporter,
 };
@@ -102,6 +102,7 @@ fn create_test_config(level: u8, where_filter: &str) -> ExportConfig {
         // v0.9.0: Dual output fields for code/test separation
         code_output_path: None,
         tests_output_path: None,
+        compact_json: false,
     }
 }
 
@@ -277,6 +278,46 @@ async fn test_level0_json_serialization() {
     assert!(json_str.contains("\"edge_type\""));
 }
 
+#[tokio::test]
+async fn test_level0_compact_json_round_trips() {
+    // Arrange
+    let edges = vec![
+        create_test_edge("rust:fn:foo:src_lib_rs:10", "rust:fn:bar:src_lib_rs:20", "depends_on"),
+        create_test_edge("rust:fn:bar:src_lib_rs:20", "rust:fn:baz:src_lib_rs:30", "Calls"),
+    ];
+    let db = MockDatabase::with_edges(edges);
+    let dir = tempfile::TempDir::new().unwrap();
+    let pretty_path = dir.path().join("pretty.json");
+    let compact_path = dir.path().join("compact.json");
+
+    let mut config = create_test_config(0, "ALL");
+    config.output_path = pretty_path.clone();
+    let pretty_output = Level0Exporter::new().export(&db, &config).await.unwrap();
+    config.output_path = compact_path.clone();
+    config.compact_json = true;
+    let compact_output = Level0Exporter::new().export(&db, &config).await.unwrap();
+
+    // Act
+    let pretty = std::fs::read_to_string(&pretty_path).unwrap();
+    let compact = std::fs::read_to_string(&compact_path).unwrap();
+
+    // Assert: single line, smaller, and the same edges after parsing
+    assert!(!compact.contains('\n'));
+    assert!(compact.len() < pretty.len());
+    let from_pretty: Vec<DependencyEdge> = serde_json::from_str(&pretty).unwrap();
+    let from_compact: Vec<DependencyEdge> = serde_json::from_str(&compact).unwrap();
+    assert_eq!(from_compact, from_pretty);
+
+    // Envelope written by export_dual_files round-trips as well
+    let envelope_path = dir.path().join("envelope.json");
+    compact_output.write_to_file_with(&envelope_path, true).unwrap();
+    let envelope = std::fs::read_to_string(&envelope_path).unwrap();
+    assert!(!envelope.contains('\n'));
+    let reparsed: ExportOutput = serde_json::from_str(&envelope).unwrap();
+    assert_eq!(reparsed.edges, pretty_output.edges);
+    assert_eq!(reparsed.export_metadata.total_edges, Some(2));
+}
+
 #[tokio::test]
 async fn test_level0_no_code_fields() {
     // Arrange: Verify Level 0 has NO code fields (current_code, future_code)
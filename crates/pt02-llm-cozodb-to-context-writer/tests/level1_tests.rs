@@ -113,6 +113,9 @@ fn create_test_entity(isgl1_key: &str, has_future_action: bool) -> Entity {
         is_public: Some(true),
         is_async: Some(false),
         is_unsafe: Some(false),
+        lsp_metadata: None,
+        is_generated: false,
+        complexity_score: None,
     }
 }
 
@@ -126,6 +129,7 @@ fn create_test_config(include_code: bool, where_filter: &str) -> ExportConfig {
         // v0.9.0: Dual output fields for code/test separation
         code_output_path: None,
         tests_output_path: None,
+        compact_json: false,
     }
 }
 
@@ -568,6 +572,10 @@ fn test_level1_entity_struct_clone() {
         interface_signature: "pub fn test()".to_string(),
         entity_class: "CODE".to_string(), // v0.9.0: EntityClass for code/test separation
         doc_comment: None,
+        provenance: None,
+        is_generated: false,
+        complexity_score: None,
+        tests: vec![],
     };
 
     let cloned = entity.clone();
@@ -95,6 +95,9 @@ fn create_typed_entity(isgl1_key: &str) -> Entity {
         is_public: Some(true),
         is_async: Some(false),
         is_unsafe: Some(false),
+        lsp_metadata: None,
+        is_generated: false,
+        complexity_score: None,
     }
 }
 
@@ -108,6 +111,7 @@ fn create_test_config(include_code: bool, where_filter: &str) -> ExportConfig {
         // v0.9.0: Dual output fields for code/test separation
         code_output_path: None,
         tests_output_path: None,
+        compact_json: false,
     }
 }
 
@@ -437,6 +441,13 @@ fn test_level2_entity_struct_clone() {
         is_public: true,
         is_async: false,
         is_unsafe: false,
+        hover: None,
+        definition_location: None,
+        references_count: None,
+        diagnostics: vec![],
+        provenance: None,
+        is_generated: false,
+        complexity_score: None,
     };
 
     let cloned = entity.clone();
@@ -0,0 +1,82 @@
+//! `compare_levels` reports what each export level costs
+//!
+//! Each level is a superset of the one below it, so for any seeded database
+//! the token estimates must not decrease from Level 0 to Level 2.
+
+mod common;
+
+use common::MemoryRepository;
+use pt02_llm_cozodb_to_context_writer::{
+    compare_levels, format_comparison,
+    export_trait::{Edge, Entity},
+};
+
+fn entity(name: &str, line: u32, deps: &[&str]) -> Entity {
+    Entity {
+        isgl1_key: format!("rust:fn:{}:src_lib_rs:{}", name, line),
+        forward_deps: deps.iter().map(|d| d.to_string()).collect(),
+        future_ind: 1,
+        current_code: Some(format!("pub fn {}() -> u32 {{ 0 }}", name)),
+        line_number: line,
+        interface_signature: format!("pub fn {}() -> u32", name),
+        return_type: Some("u32".to_string()),
+        param_types: Some(vec![]),
+        param_names: Some(vec![]),
+        generic_constraints: Some(vec![]),
+        trait_impls: Some(vec![]),
+        ..common::entity(name)
+    }
+}
+
+fn seeded_database() -> MemoryRepository {
+    let leaf = "rust:fn:leaf:src_lib_rs:20";
+    MemoryRepository {
+        entities: vec![
+            entity("root", 1, &[leaf]),
+            entity("middle", 10, &[leaf]),
+            entity("leaf", 20, &[]),
+        ],
+        edges: ["root", "middle"]
+            .iter()
+            .zip([1, 10])
+            .map(|(name, line)| Edge {
+                from_key: format!("rust:fn:{}:src_lib_rs:{}", name, line),
+                to_key: leaf.to_string(),
+                edge_type: "Calls".to_string(),
+            })
+            .collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_reported_tokens_are_monotonic_across_levels() {
+    let db = seeded_database();
+
+    for include_code in [false, true] {
+        let summaries = compare_levels(&db, "ALL", include_code).await.unwrap();
+
+        let levels: Vec<u8> = summaries.iter().map(|s| s.level).collect();
+        assert_eq!(levels, vec![0, 1, 2]);
+        assert_eq!(summaries[0].edge_count, Some(2));
+        assert_eq!(summaries[0].entity_count, None);
+        assert_eq!(summaries[1].entity_count, Some(3));
+        assert_eq!(summaries[2].entity_count, Some(3));
+
+        let tokens: Vec<usize> = summaries.iter().map(|s| s.estimated_tokens).collect();
+        assert!(tokens[0] > 0, "{:?}", tokens);
+        assert!(tokens[0] <= tokens[1] && tokens[1] <= tokens[2], "{:?}", tokens);
+    }
+}
+
+#[tokio::test]
+async fn test_comparison_table_lists_added_fields() {
+    let summaries = compare_levels(&seeded_database(), "ALL", false).await.unwrap();
+    let table = format_comparison(&summaries);
+
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4, "{}", table);
+    assert!(lines[0].starts_with("Level"));
+    assert!(lines[1].contains("from_key, to_key, edge_type"));
+    assert!(lines[2].contains("forward_deps"));
+    assert!(lines[3].contains("return_type"));
+}
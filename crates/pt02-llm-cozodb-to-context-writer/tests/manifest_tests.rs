@@ -0,0 +1,79 @@
+//! `{output}.manifest.json` describes the dual-file export it sits next to
+//!
+//! Every hash and count in the manifest must agree with the files actually
+//! written, so downstream tools can trust it for integrity checks.
+
+mod common;
+
+use common::MemoryRepository;
+use parseltongue_core::output_sink::FileSink;
+use pt02_llm_cozodb_to_context_writer::{
+    export_trait::Entity, manifest_name, ExportManifest, Level2Exporter, ManifestRecorder,
+};
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+fn entity(name: &str, entity_class: &str) -> Entity {
+    Entity {
+        isgl1_key: format!("rust:fn:{}:src_lib_rs:1", name),
+        entity_class: entity_class.to_string(),
+        is_public: Some(false),
+        ..common::entity(name)
+    }
+}
+
+#[tokio::test]
+async fn test_manifest_counts_and_hashes_match_output_files() {
+    let db = MemoryRepository {
+        entities: vec![
+            entity("alpha", "CODE"),
+            entity("beta", "CODE"),
+            entity("test_alpha", "TEST"),
+        ],
+        ..Default::default()
+    };
+    let dir = TempDir::new().unwrap();
+    let base = dir.path().join("context");
+    let base = base.to_str().unwrap();
+
+    let recorder = ManifestRecorder::new(&FileSink);
+    Level2Exporter::new()
+        .export_dual_files_to(&db, &recorder, base, false, "ALL", false)
+        .await
+        .unwrap();
+    let returned = recorder
+        .write_manifest(base, ExportManifest::new(2, "ALL", false, "mem"), false)
+        .await
+        .unwrap();
+
+    let manifest: ExportManifest =
+        serde_json::from_str(&std::fs::read_to_string(manifest_name(base)).unwrap()).unwrap();
+    assert_eq!(manifest, returned);
+    assert_eq!(
+        (manifest.level, manifest.where_filter.as_str(), manifest.include_code, manifest.db_path.as_str()),
+        (2, "ALL", false, "mem")
+    );
+    assert_eq!(manifest.tool_version, env!("CARGO_PKG_VERSION"));
+
+    let names: Vec<&str> = manifest.files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["context.json", "context.toon", "context_test.json", "context_test.toon"]);
+
+    let mut counted = 0;
+    for file in &manifest.files {
+        let bytes = std::fs::read(dir.path().join(&file.name)).unwrap();
+        assert_eq!(file.sha256, format!("{:x}", Sha256::digest(&bytes)), "{}", file.name);
+        assert_eq!(file.bytes, bytes.len(), "{}", file.name);
+
+        if file.name.ends_with(".json") {
+            let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            let entities = json["entities"].as_array().unwrap().len();
+            assert_eq!(file.entities, Some(entities), "{}", file.name);
+            counted += entities;
+        } else {
+            assert_eq!(file.entities, None, "{}", file.name);
+        }
+    }
+    assert_eq!(counted, 3);
+    assert_eq!(manifest.total_entities, Some(counted));
+    assert_eq!(manifest.total_edges, None);
+}
@@ -0,0 +1,46 @@
+//! `--format markdown` writes a readable `.md` twin of each JSON export
+//!
+//! A seeded entity must come out as a `### name` section under its file's
+//! `##` header, with the signature fenced and the doc comment as prose.
+
+mod common;
+
+use common::{entity, CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{export_trait::Entity, Level1Exporter, MarkdownSink};
+
+#[tokio::test]
+async fn test_markdown_twin_has_file_headers_and_fenced_signature() {
+    let db = MemoryRepository {
+        entities: vec![Entity {
+            isgl1_key: "rust:fn:calculate_total:src_billing_rs:42-50".to_string(),
+            forward_deps: vec!["rust:fn:apply_tax:src_tax_rs:3-9".to_string()],
+            current_code: None,
+            file_path: "src/billing.rs".to_string(),
+            line_number: 42,
+            interface_signature: "pub fn calculate_total(items: &[Item]) -> Money".to_string(),
+            doc_comment: Some("Sum of item prices after tax.".to_string()),
+            ..entity("calculate_total")
+        }],
+        ..Default::default()
+    };
+    let captured = CapturingSink::default();
+
+    Level1Exporter::new()
+        .export_dual_files_to(&db, &MarkdownSink::new(&captured), "context", false, "ALL", false)
+        .await
+        .unwrap();
+
+    let writes = captured.writes.lock().unwrap();
+    assert!(writes.contains_key("context.json"), "JSON is still written");
+    assert!(writes.contains_key("context_test.md"));
+
+    let markdown = String::from_utf8(writes["context.md"].clone()).unwrap();
+    assert!(markdown.starts_with("## src/billing.rs\n\n### calculate_total\n\n"), "{}", markdown);
+    assert!(
+        markdown.contains("```rust\npub fn calculate_total(items: &[Item]) -> Money\n```\n"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("Sum of item prices after tax.\n"), "{}", markdown);
+    assert!(markdown.contains("- `rust:fn:apply_tax:src_tax_rs:3-9`\n"), "{}", markdown);
+}
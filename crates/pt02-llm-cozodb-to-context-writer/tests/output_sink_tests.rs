@@ -0,0 +1,45 @@
+//! Exporters write through an `OutputSink`
+//!
+//! A capturing sink stands in for S3/HTTP: every artifact the exporter would
+//! have written to disk must arrive at the sink, and nothing touches the
+//! filesystem.
+
+mod common;
+
+use common::{CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{export_trait::Edge, exporters::Level0Exporter};
+
+#[tokio::test]
+async fn test_dual_export_writes_every_artifact_to_sink() {
+    let db = MemoryRepository {
+        edges: vec![Edge {
+            from_key: "rust:fn:main:src_main_rs:1-3".to_string(),
+            to_key: "rust:fn:helper:src_lib_rs:5-9".to_string(),
+            edge_type: "Calls".to_string(),
+        }],
+        ..Default::default()
+    };
+    let sink = CapturingSink::default();
+    // Relative name in a directory that does not exist: a stray fs write would fail
+    let base = "no_such_dir/context";
+
+    Level0Exporter::new()
+        .export_dual_files_to(&db, &sink, base, "ALL", false)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sink.names(),
+        vec![
+            "no_such_dir/context.json",
+            "no_such_dir/context.toon",
+            "no_such_dir/context_test.json",
+            "no_such_dir/context_test.toon",
+        ]
+    );
+    let json: serde_json::Value = serde_json::from_str(&sink.text("no_such_dir/context.json")).unwrap();
+    assert_eq!(json["export_metadata"]["level"], 0);
+    assert_eq!(json["edges"][0]["to_key"], "rust:fn:helper:src_lib_rs:5-9");
+    assert!(sink.text("no_such_dir/context.toon").contains("Calls"));
+    assert!(!std::path::Path::new("no_such_dir").exists());
+}
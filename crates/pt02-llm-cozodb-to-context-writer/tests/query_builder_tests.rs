@@ -0,0 +1,101 @@
+//! Typed `QueryBuilder` filters run against a real CozoDB
+//!
+//! The builder's output is plain Datalog, so it has to be accepted by
+//! `query_entities` exactly like a hand-written `--where-clause`.
+
+use chrono::{DateTime, TimeZone, Utc};
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{CodeGraphRepository, CozoDbAdapter, QueryBuilder};
+use std::path::PathBuf;
+
+fn entity(name: &str, entity_type: EntityType, visibility: Visibility, line: u32, modified_at: DateTime<Utc>) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type,
+        name: name.to_string(),
+        visibility,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(line, line + 2).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:item:{}:src_lib_rs:{}-{}", name, line, line + 2);
+    let mut entity = CodeEntity::new(key, signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(format!("// {}", name));
+    entity.future_code = entity.current_code.clone();
+    entity.metadata.modified_at = modified_at;
+    entity
+}
+
+fn day(month: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, month, 1, 0, 0, 0).unwrap()
+}
+
+async fn seeded() -> CozoDbAdapter {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    for entity in [
+        entity("api", EntityType::Function, Visibility::Public, 1, day(1)),
+        entity("helper", EntityType::Function, Visibility::Private, 5, day(3)),
+        entity("Config", EntityType::Struct, Visibility::Public, 9, day(1)),
+        entity("Mode", EntityType::Enum, Visibility::Private, 13, day(3)),
+    ] {
+        db.insert_entity(&entity).await.unwrap();
+    }
+    CozoDbAdapter::new(db)
+}
+
+async fn names(adapter: &CozoDbAdapter, builder: QueryBuilder) -> Vec<String> {
+    let mut names: Vec<_> = adapter
+        .query_entities(&builder.build().unwrap())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.isgl1_key.split(':').nth(2).unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_builder_and_chain_filters_entities() {
+    let adapter = seeded().await;
+
+    assert_eq!(
+        names(&adapter, QueryBuilder::new().entity_type(EntityType::Function)).await,
+        ["api", "helper"]
+    );
+    assert_eq!(
+        names(
+            &adapter,
+            QueryBuilder::new().entity_type(EntityType::Function).and().visibility(Visibility::Public)
+        )
+        .await,
+        ["api"]
+    );
+    assert_eq!(
+        names(&adapter, QueryBuilder::new().modified_after(day(2)).and().visibility(Visibility::Private)).await,
+        ["Mode", "helper"]
+    );
+}
+
+#[tokio::test]
+async fn test_builder_or_chain_filters_entities() {
+    let adapter = seeded().await;
+
+    let builder = QueryBuilder::new()
+        .entity_type(EntityType::Struct)
+        .or()
+        .entity_type(EntityType::Function)
+        .and()
+        .modified_after(day(2));
+
+    assert_eq!(names(&adapter, builder).await, ["Config", "helper"]);
+}
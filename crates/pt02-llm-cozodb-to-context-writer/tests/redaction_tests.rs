@@ -0,0 +1,86 @@
+//! `--redact` / `--redact-docs` export
+//!
+//! Code bodies (and with `--redact-docs`, doc comments) never reach any
+//! exported artifact; keys, signatures and edges still do.
+
+mod common;
+
+use common::{entity, CapturingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{
+    export_trait::{CodeGraphRepository, Edge, Entity},
+    redact, Level1Exporter, Level2Exporter, LevelExporter, RedactedRepository,
+};
+
+const SECRET_BODY: &str = "let secret_rate = 0.0425 * principal;";
+const SECRET_DOC: &str = "Uses `apply_secret_rate(principal)` internally";
+
+fn database() -> MemoryRepository {
+    let current = format!("fn interest(principal: f64) -> f64 {{ {} secret_rate }}", SECRET_BODY);
+    let interest = Entity {
+        isgl1_key: "rust:fn:interest:src_billing_rs:1-3".to_string(),
+        forward_deps: vec!["rust:fn:round:src_billing_rs:5-7".to_string()],
+        future_ind: 1,
+        future_action: Some("Edit".to_string()),
+        future_code: Some(current.replace("0.0425", "0.0450")),
+        current_code: Some(current),
+        file_path: "src/billing.rs".to_string(),
+        interface_signature: "fn interest(principal: f64) -> f64".to_string(),
+        doc_comment: Some(SECRET_DOC.to_string()),
+        return_type: Some("f64".to_string()),
+        param_types: Some(vec!["f64".to_string()]),
+        param_names: Some(vec!["principal".to_string()]),
+        ..entity("interest")
+    };
+    MemoryRepository {
+        entities: vec![interest],
+        edges: vec![Edge {
+            from_key: "rust:fn:interest:src_billing_rs:1-3".to_string(),
+            to_key: "rust:fn:round:src_billing_rs:5-7".to_string(),
+            edge_type: "Calls".to_string(),
+        }],
+    }
+}
+
+#[tokio::test]
+async fn test_redacted_export_contains_no_original_code() {
+    let db = database();
+    let original = db.entities[0].clone();
+    let redacted = RedactedRepository::new(&db).with_docs(true);
+
+    let sink = CapturingSink::default();
+    Level1Exporter::new()
+        .export_dual_files_to(&redacted, &sink, "ctx", true, "ALL", false)
+        .await
+        .unwrap();
+    let config = pt02_llm_cozodb_to_context_writer::ExportConfig {
+        level: 2,
+        include_code: true,
+        where_filter: "ALL".to_string(),
+        output_path: "level2.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    };
+    let level2 = Level2Exporter::new().export_to(&redacted, &config, &sink).await.unwrap();
+    level2.write_to_sink(&sink, "level2.json", false).await.unwrap();
+
+    let output = sink.everything();
+    for secret in ["secret_rate", "0.0425", "0.0450", "apply_secret_rate", SECRET_BODY] {
+        assert!(!output.contains(secret), "{:?} leaked into the export", secret);
+    }
+    assert!(output.contains("fn interest(principal: f64) -> f64"), "signature kept");
+    assert!(output.contains("rust:fn:round:src_billing_rs:5-7"), "dependencies kept");
+    assert!(output.contains(&redact(original.current_code.as_deref().unwrap())));
+    assert!(output.contains(&redact(original.future_code.as_deref().unwrap())));
+}
+
+#[tokio::test]
+async fn test_doc_comments_survive_without_redact_docs() {
+    let db = database();
+    let entities = RedactedRepository::new(&db).get_all_entities().await.unwrap();
+
+    assert_eq!(entities[0].doc_comment.as_deref(), Some(SECRET_DOC));
+    assert!(entities[0].current_code.as_deref().unwrap().starts_with("<redacted: "));
+    assert_eq!(RedactedRepository::new(&db).get_all_edges().await.unwrap().len(), 1);
+}
@@ -0,0 +1,128 @@
+//! `--since` / `--since-commit` incremental export
+//!
+//! A git revision resolves to its commit time, and only entities whose
+//! stored `last_modified` is later survive the filter, ANDed with the rest
+//! of the WHERE clause.
+
+use chrono::{DateTime, TimeZone, Utc};
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::{CozoDbStorage, DEFAULT_RELATION_NAME};
+use pt02_llm_cozodb_to_context_writer::{commit_time, with_since, CodeGraphRepository, CozoDbAdapter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str], date: &str) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .output()
+        .unwrap();
+    assert!(status.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&status.stderr));
+}
+
+/// Repo with `v1.0` committed 2023-06-01 and `v1.2` committed 2024-01-01
+fn tagged_repo() -> TempDir {
+    let repo = TempDir::new().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"], "2023-06-01T00:00:00Z");
+    git(dir, &["config", "user.email", "dev@example.com"], "2023-06-01T00:00:00Z");
+    git(dir, &["config", "user.name", "Dev"], "2023-06-01T00:00:00Z");
+    for (tag, date) in [("v1.0", "2023-06-01T00:00:00Z"), ("v1.2", "2024-01-01T00:00:00Z")] {
+        std::fs::write(dir.join("VERSION"), tag).unwrap();
+        git(dir, &["add", "VERSION"], date);
+        git(dir, &["commit", "-q", "-m", tag], date);
+        git(dir, &["tag", tag], date);
+    }
+    repo
+}
+
+fn entity(key: &str, name: &str, entity_type: EntityType, modified_at: DateTime<Utc>) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity = CodeEntity::new(key.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(format!("pub fn {}() {{}}", name));
+    entity.future_code = entity.current_code.clone();
+    entity.metadata.modified_at = modified_at;
+    entity
+}
+
+#[test]
+fn test_commit_time_resolves_tags() {
+    let repo = tagged_repo();
+
+    assert_eq!(
+        commit_time(repo.path(), "v1.2").unwrap(),
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+        commit_time(repo.path(), "v1.2~1").unwrap(),
+        Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap()
+    );
+
+    let err = commit_time(repo.path(), "v9.9").unwrap_err().to_string();
+    assert!(err.contains("Unknown revision 'v9.9'"), "{}", err);
+    assert!(!err.contains("shallow"), "{}", err);
+}
+
+#[test]
+fn test_unknown_revision_in_shallow_clone_explains_how_to_fetch() {
+    let repo = tagged_repo();
+    let clones = TempDir::new().unwrap();
+    let shallow = clones.path().join("shallow");
+    let url = format!("file://{}", repo.path().display());
+    git(clones.path(), &["clone", "-q", "--depth", "1", &url, "shallow"], "2024-01-01T00:00:00Z");
+
+    let err = commit_time(&shallow, "v1.0").unwrap_err().to_string();
+    assert!(err.contains("shallow clone"), "{}", err);
+    assert!(err.contains("--unshallow"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_since_commit_keeps_entities_modified_after_the_commit() {
+    let repo = tagged_repo();
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let day = |month, day| Utc.with_ymd_and_hms(2024, month, day, 0, 0, 0).unwrap();
+    let before = Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap();
+    for entity in [
+        entity("rust:fn:untouched:src_lib_rs:1-3", "untouched", EntityType::Function, before),
+        entity("rust:fn:edited:src_lib_rs:5-7", "edited", EntityType::Function, day(2, 1)),
+        entity("rust:struct:Added:src_lib_rs:9-11", "Added", EntityType::Struct, day(3, 1)),
+    ] {
+        db.insert_entity(&entity).await.unwrap();
+    }
+    let adapter = CozoDbAdapter::new(db);
+    let since = Some(commit_time(repo.path(), "v1.2").unwrap());
+
+    let keys = |entities: Vec<pt02_llm_cozodb_to_context_writer::Entity>| {
+        let mut keys: Vec<_> = entities.into_iter().map(|e| e.isgl1_key).collect();
+        keys.sort();
+        keys
+    };
+    assert_eq!(
+        keys(adapter.query_entities(&with_since("ALL", DEFAULT_RELATION_NAME, since)).await.unwrap()),
+        vec!["rust:fn:edited:src_lib_rs:5-7", "rust:struct:Added:src_lib_rs:9-11"]
+    );
+    assert_eq!(
+        keys(adapter.query_entities(&with_since("entity_type = 'function'", DEFAULT_RELATION_NAME, since)).await.unwrap()),
+        vec!["rust:fn:edited:src_lib_rs:5-7"]
+    );
+}
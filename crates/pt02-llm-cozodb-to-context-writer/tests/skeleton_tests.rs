@@ -0,0 +1,148 @@
+//! File skeleton export
+//!
+//! Entities stored for one file render back as declarations in line order,
+//! with doc comments kept and function bodies replaced by `{ ... }`.
+
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::render_file_skeleton;
+use std::path::PathBuf;
+
+fn entity(
+    key: &str,
+    name: &str,
+    entity_type: EntityType,
+    file: &str,
+    lines: (u32, u32),
+    doc: Option<&str>,
+    code: &str,
+) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from(file),
+        line_range: LineRange::new(lines.0, lines.1).unwrap(),
+        module_path: vec![],
+        documentation: doc.map(str::to_string),
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity = CodeEntity::new(key.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some(code.to_string());
+    entity.future_code = Some(code.to_string());
+    entity
+}
+
+#[tokio::test]
+async fn test_skeleton_replaces_bodies_with_placeholders() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+
+    let file = "src/shapes.rs";
+    // Inserted out of order; the skeleton follows line order
+    db.insert_entity(&entity(
+        "rust:fn:area:src_shapes_rs:8-10",
+        "area",
+        EntityType::Function,
+        file,
+        (8, 10),
+        Some("Area of a rectangle."),
+        "pub fn area(rect: &Rect) -> u32 {\n    rect.width * rect.height\n}",
+    ))
+    .await
+    .unwrap();
+    db.insert_entity(&entity(
+        "rust:struct:Rect:src_shapes_rs:2-5",
+        "Rect",
+        EntityType::Struct,
+        file,
+        (2, 5),
+        Some("An axis-aligned rectangle."),
+        "pub struct Rect {\n    pub width: u32,\n    pub height: u32,\n}",
+    ))
+    .await
+    .unwrap();
+    db.insert_entity(&entity(
+        "rust:fn:square:src_shapes_rs:12-17",
+        "square",
+        EntityType::Function,
+        file,
+        (12, 17),
+        None,
+        "pub fn square(side: u32)\n    -> Rect\n{\n    let rect = Rect { width: side, height: side };\n    rect\n}",
+    ))
+    .await
+    .unwrap();
+    db.insert_entity(&entity(
+        "rust:fn:unrelated:src_other_rs:1-3",
+        "unrelated",
+        EntityType::Function,
+        "src/other.rs",
+        (1, 3),
+        None,
+        "fn unrelated() {}",
+    ))
+    .await
+    .unwrap();
+
+    let entities = db.get_entities_by_file(file).await.unwrap();
+    assert_eq!(entities.len(), 3);
+
+    let skeleton = render_file_skeleton(&entities);
+    assert_eq!(
+        skeleton,
+        "\
+/// An axis-aligned rectangle.
+pub struct Rect {
+    pub width: u32,
+    pub height: u32,
+}
+/// Area of a rectangle.
+pub fn area(rect: &Rect) -> u32 { ... }
+pub fn square(side: u32)
+    -> Rect { ... }
+"
+    );
+    assert!(!skeleton.contains("rect.width * rect.height"), "bodies are elided");
+}
+
+#[test]
+fn test_skeleton_nests_methods_inside_impl() {
+    let file = "src/counter.rs";
+    let entities = vec![
+        entity(
+            "rust:method:increment:src_counter_rs:3-5",
+            "increment",
+            EntityType::Method,
+            file,
+            (3, 5),
+            Some("Add one."),
+            "    pub fn increment(&mut self) {\n        self.0 += 1;\n    }",
+        ),
+        entity(
+            "rust:impl:Counter:src_counter_rs:2-6",
+            "Counter",
+            EntityType::ImplBlock { trait_name: None, struct_name: "Counter".to_string() },
+            file,
+            (2, 6),
+            None,
+            "impl Counter {\n    pub fn increment(&mut self) {\n        self.0 += 1;\n    }\n}",
+        ),
+    ];
+
+    assert_eq!(
+        render_file_skeleton(&entities),
+        "\
+impl Counter {
+    /// Add one.
+    pub fn increment(&mut self) { ... }
+}
+"
+    );
+}
@@ -0,0 +1,59 @@
+//! Exporting a database ingested with `pt01 --store-spans-only`
+//!
+//! Entities stored as byte spans have no code column; every export path
+//! reads their code from the source file instead.
+
+use futures::TryStreamExt;
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::{CodeGraphRepository, CozoDbAdapter};
+use std::path::PathBuf;
+
+const SOURCE: &str = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+fn spans_only_entity(file_path: &str) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: "add".to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from(file_path),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    CodeEntity::new("rust:fn:add:src_lib_rs:1-3".to_string(), signature, EntityClass::CodeImplementation).unwrap()
+}
+
+#[tokio::test]
+async fn test_spans_only_entities_are_exported_with_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lib.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+    let path = path.to_string_lossy().to_string();
+
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    let entity = spans_only_entity(&path);
+    db.insert_entity(&entity).await.unwrap();
+    let span = CodeSpan { file_path: path, byte_start: 0, byte_end: SOURCE.len() };
+    db.insert_code_span(&entity.isgl1_key, &span).await.unwrap();
+    let adapter = CozoDbAdapter::new(db);
+
+    for entities in [
+        adapter.get_all_entities().await.unwrap(),
+        adapter.query_entities("entity_class = 'CODE'").await.unwrap(),
+        adapter.entities_after("ALL", None, 10).await.unwrap(),
+        adapter.entities_stream("ALL").try_collect().await.unwrap(),
+    ] {
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].current_code.as_deref(), Some(SOURCE));
+        assert_eq!(entities[0].future_code.as_deref(), Some(SOURCE), "unchanged: future equals current");
+    }
+}
@@ -0,0 +1,95 @@
+//! `--pair-tests` export
+//!
+//! Production entities list their tests, matched by `test_<name>` /
+//! `<name>_test`, in a `tests` field.
+
+mod common;
+
+use common::{DiscardingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{
+    export_trait::Entity, EntityExportLevel1, ExportConfig, Level1Exporter, LevelExporter, TestMatcher,
+};
+
+fn entity(name: &str, file_path: &str, line_number: u32, entity_class: &str) -> Entity {
+    Entity {
+        isgl1_key: format!("rust:fn:{}:{}:{}", name, file_path.replace(['/', '.'], "_"), line_number),
+        file_path: file_path.to_string(),
+        line_number,
+        entity_class: entity_class.to_string(),
+        ..common::entity(name)
+    }
+}
+
+fn database() -> MemoryRepository {
+    MemoryRepository {
+        entities: vec![
+            entity("add", "src/math.rs", 1, "CODE"),
+            entity("sub", "src/math.rs", 5, "CODE"),
+            entity("test_add", "tests/math_test.rs", 3, "TEST"),
+        ],
+        ..Default::default()
+    }
+}
+
+fn code_config() -> ExportConfig {
+    ExportConfig {
+        level: 1,
+        include_code: true,
+        where_filter: "entity_class = 'CODE'".to_string(),
+        output_path: "export.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    }
+}
+
+fn exported(output: &pt02_llm_cozodb_to_context_writer::ExportOutput) -> Vec<EntityExportLevel1> {
+    serde_json::from_value(output.entities.clone().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_add_is_paired_with_test_add() {
+    let output = Level1Exporter::new()
+        .with_pair_tests(true)
+        .export_to(&database(), &code_config(), &DiscardingSink)
+        .await
+        .unwrap();
+    let entities = exported(&output);
+
+    let add = entities.iter().find(|e| e.entity_name == "add").unwrap();
+    assert_eq!(add.tests.len(), 1);
+    assert_eq!(add.tests[0].entity_name, "test_add");
+    assert_eq!(add.tests[0].file_path, "tests/math_test.rs");
+    assert_eq!(add.tests[0].current_code.as_deref(), Some("fn test_add() {}"));
+
+    let sub = entities.iter().find(|e| e.entity_name == "sub").unwrap();
+    assert!(sub.tests.is_empty());
+    assert!(!serde_json::to_string(sub).unwrap().contains("\"tests\""), "no tests, no field");
+}
+
+#[tokio::test]
+async fn test_pairing_is_off_by_default() {
+    let output = Level1Exporter::new().export_to(&database(), &code_config(), &DiscardingSink).await.unwrap();
+    assert!(exported(&output).iter().all(|e| e.tests.is_empty()));
+}
+
+/// Pairs by file stem: `math.rs` with `math_test.rs`
+struct SameStemMatcher;
+
+impl TestMatcher for SameStemMatcher {
+    fn is_test_of(&self, test: &EntityExportLevel1, production: &EntityExportLevel1) -> bool {
+        let stem = |path: &str| path.rsplit('/').next().unwrap_or(path).trim_end_matches(".rs").trim_end_matches("_test").to_string();
+        stem(&test.file_path) == stem(&production.file_path)
+    }
+}
+
+#[tokio::test]
+async fn test_custom_matcher_replaces_naming_convention() {
+    let output = Level1Exporter::new()
+        .with_test_matcher(SameStemMatcher)
+        .export_to(&database(), &code_config(), &DiscardingSink)
+        .await
+        .unwrap();
+    assert!(exported(&output).iter().all(|e| e.tests.len() == 1), "both math.rs entities pair with math_test.rs");
+}
@@ -0,0 +1,105 @@
+//! `--order topological` export
+//!
+//! Dependencies come before their dependents; cycles keep key order and
+//! are listed in `cycles`.
+
+mod common;
+
+use common::{calls, key, DiscardingSink, MemoryRepository};
+use pt02_llm_cozodb_to_context_writer::{
+    export_trait::Entity, ExportConfig, ExportOrder, Level1Exporter, Level2Exporter, LevelExporter,
+};
+
+fn entity(name: &str, line_number: u32) -> Entity {
+    Entity { line_number, ..common::entity(name) }
+}
+
+fn config(level: u8) -> ExportConfig {
+    ExportConfig {
+        level,
+        include_code: false,
+        where_filter: "ALL".to_string(),
+        output_path: "export.json".into(),
+        db_path: String::new(),
+        code_output_path: None,
+        tests_output_path: None,
+        compact_json: false,
+    }
+}
+
+fn exported_names(entities: &serde_json::Value) -> Vec<String> {
+    entities
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["entity_name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_topological_order_puts_dependencies_first() {
+    // A -> B -> C, with A first in key order
+    let db = MemoryRepository {
+        entities: vec![entity("a", 1), entity("b", 10), entity("c", 20)],
+        edges: vec![calls("a", "b"), calls("b", "c")],
+    };
+
+    let output = Level1Exporter::new()
+        .with_order(ExportOrder::Topological)
+        .export_to(&db, &config(1), &DiscardingSink)
+        .await
+        .unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["c", "b", "a"]);
+    assert!(output.cycles.is_none());
+
+    let output = Level2Exporter::new()
+        .with_order(ExportOrder::Topological)
+        .export_to(&db, &config(2), &DiscardingSink)
+        .await
+        .unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["c", "b", "a"]);
+
+    let output = Level1Exporter::new().export_to(&db, &config(1), &DiscardingSink).await.unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["a", "b", "c"], "key order by default");
+}
+
+#[tokio::test]
+async fn test_cycle_keeps_key_order_and_is_flagged() {
+    // A -> B -> C -> B: B and C form a cycle that A depends on
+    let db = MemoryRepository {
+        entities: vec![entity("a", 1), entity("b", 10), entity("c", 20)],
+        edges: vec![calls("a", "b"), calls("b", "c"), calls("c", "b")],
+    };
+
+    let output = Level1Exporter::new()
+        .with_order(ExportOrder::Topological)
+        .export_to(&db, &config(1), &DiscardingSink)
+        .await
+        .unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["b", "c", "a"]);
+    assert_eq!(output.cycles, Some(vec![vec![key("b"), key("c")]]));
+}
+
+#[tokio::test]
+async fn test_unconnected_entities_keep_key_order() {
+    // Only A -> D; B and C are unrelated to everything
+    let db = MemoryRepository {
+        entities: vec![entity("a", 1), entity("b", 10), entity("c", 20), entity("d", 30)],
+        edges: vec![calls("a", "d")],
+    };
+
+    let output = Level1Exporter::new()
+        .with_order(ExportOrder::Topological)
+        .export_to(&db, &config(1), &DiscardingSink)
+        .await
+        .unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["b", "c", "d", "a"]);
+
+    let db = MemoryRepository { edges: vec![], ..db };
+    let output = Level1Exporter::new()
+        .with_order(ExportOrder::Topological)
+        .export_to(&db, &config(1), &DiscardingSink)
+        .await
+        .unwrap();
+    assert_eq!(exported_names(output.entities.as_ref().unwrap()), ["a", "b", "c", "d"], "no edges, key order");
+}
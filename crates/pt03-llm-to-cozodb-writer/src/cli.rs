@@ -5,7 +5,7 @@
 //! This crate has two CLI modes:
 //!
 //! 1. **Unified Binary** (production): Defined in `parseltongue/src/main.rs`
-//!    - Usage: `parseltongue llm-to-cozodb-writer --entity <key> --action <create|edit|delete> [--future-code <code>] [--db <path>]`
+//!    - Usage: `parseltongue llm-to-cozodb-writer --entity <key> --action <create|edit|delete> [--future-code <code>] [--expect-hash <version>] [--llm-backend <backend>] [--db <path>]`
 //!    - `--entity` and `--action` are required arguments
 //!
 //! 2. **Standalone Binary** (development): Defined in this file
@@ -45,7 +45,8 @@
 use clap::{Arg, ArgGroup, Command};
 
 use crate::{
-    AdvancedQueryConfig, EntityAction, InterfaceMode, LlmWriterConfig, SimpleUpdateConfig,
+    AdvancedQueryConfig, EntityAction, InterfaceMode, LlmBackend, LlmWriterConfig, LlmWriterError,
+    SimpleUpdateConfig,
 };
 
 /// CLI configuration builder
@@ -97,6 +98,13 @@ impl CliConfig {
                     .help("Future code content (required for create/edit)")
                     .conflicts_with("query"),
             )
+            .arg(
+                Arg::new("expect-hash")
+                    .long("expect-hash")
+                    .value_name("VERSION")
+                    .help("Entity version read by the caller; the write fails with a conflict if it changed")
+                    .conflicts_with("query"),
+            )
             // Advanced interface arguments
             .arg(
                 Arg::new("query")
@@ -113,6 +121,12 @@ impl CliConfig {
                     .help("Database file path")
                     .default_value("parseltongue.db"),
             )
+            .arg(
+                Arg::new("llm-backend")
+                    .long("llm-backend")
+                    .value_name("BACKEND")
+                    .help("LLM backend: openai, mock, local[:ENDPOINT] (env: PARSELTONGUE_LLM_BACKEND)"),
+            )
             // Mutual exclusion groups
             .group(
                 ArgGroup::new("interface")
@@ -166,6 +180,23 @@ impl CliConfig {
         }
     }
 
+    /// Expected entity version from `--expect-hash` (optimistic locking)
+    ///
+    /// Callers place it in `CodeEntity::metadata.content_hash` before calling
+    /// `CozoDbStorage::update_entity_internal`.
+    pub fn parse_expected_hash(matches: &clap::ArgMatches) -> Option<String> {
+        matches.get_one::<String>("expect-hash").cloned()
+    }
+
+    /// Resolve the LLM backend from `--llm-backend`, falling back to the environment
+    pub fn parse_llm_backend(matches: &clap::ArgMatches) -> crate::Result<LlmBackend> {
+        let cli_value = matches.get_one::<String>("llm-backend").map(String::as_str);
+        LlmBackend::resolve(cli_value).map_err(|e| LlmWriterError::ConfigurationError {
+            field: "llm-backend".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
     /// Print usage information
     pub fn print_usage() {
         let mut cli = Self::build_cli();
@@ -218,4 +249,62 @@ mod tests {
         assert_eq!(config.query, "?[b] := [[2]]");
         assert_eq!(config.db_path, "parseltongue.db"); // Default value
     }
+
+    #[test]
+    fn test_expect_hash_flag() {
+        let matches = CliConfig::build_cli()
+            .try_get_matches_from(&[
+                "parseltongue-02",
+                "--entity",
+                "rust:fn:hello:lib_rs:1-5",
+                "--action",
+                "edit",
+                "--future-code",
+                "fn hello() {}",
+                "--expect-hash",
+                "abc123",
+            ])
+            .unwrap();
+        assert_eq!(CliConfig::parse_expected_hash(&matches), Some("abc123".to_string()));
+
+        let matches = CliConfig::build_cli()
+            .try_get_matches_from(&["parseltongue-02", "--query", "?[e] := [[5]]"])
+            .unwrap();
+        assert_eq!(CliConfig::parse_expected_hash(&matches), None);
+    }
+
+    #[test]
+    fn test_llm_backend_flag() {
+        let cli = CliConfig::build_cli();
+        let matches = cli
+            .try_get_matches_from(&[
+                "parseltongue-02",
+                "--query",
+                "?[c] := [[3]]",
+                "--llm-backend",
+                "mock",
+            ])
+            .unwrap();
+
+        assert_eq!(CliConfig::parse_llm_backend(&matches).unwrap(), LlmBackend::Mock);
+    }
+
+    #[test]
+    fn test_invalid_llm_backend_is_configuration_error() {
+        let cli = CliConfig::build_cli();
+        let matches = cli
+            .try_get_matches_from(&[
+                "parseltongue-02",
+                "--query",
+                "?[d] := [[4]]",
+                "--llm-backend",
+                "carrier-pigeon",
+            ])
+            .unwrap();
+
+        assert!(matches!(
+            CliConfig::parse_llm_backend(&matches),
+            Err(LlmWriterError::ConfigurationError { .. })
+        ));
+    }
 }
\ No newline at end of file
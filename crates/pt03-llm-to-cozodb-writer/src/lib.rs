@@ -8,9 +8,30 @@
 //!
 //! The ultra-minimalist implementation (see main.rs):
 //! - Uses parseltongue-core::storage::CozoDbStorage directly
-//! - NO LLM client infrastructure (deleted in v0.7.1)
 //! - NO batch processing
 //! - Direct temporal state updates only
+//!
+//! ## LLM Backends
+//!
+//! Tools that do talk to an LLM obtain a client from
+//! [`ToolFactory::create_llm_client`], selected by `--llm-backend` or
+//! `PARSELTONGUE_LLM_BACKEND` (`openai`, `mock`, `local[:endpoint]`).
+//! The `mock` backend is offline and deterministic for CI.
+//! HTTP backends render their user prompt through a [`PromptTemplate`];
+//! set `PARSELTONGUE_PROMPT_TEMPLATE` to a file with `{entities}` and
+//! friends to replace the default wording.
+//!
+//! ## Retries
+//!
+//! A write made with `--idempotency-key` is recorded under that key (see
+//! [`write_once`]); retrying it returns the recorded result instead of
+//! applying the change again, so at-least-once delivery is safe.
+//!
+//! ## Filters
+//!
+//! `--query` takes raw Datalog. Callers composing one can build the entity
+//! filter with [`QueryBuilder`], the same validated builder pt02 uses, and
+//! splice its output into the script's body.
 
 #![warn(clippy::all)]
 #![warn(rust_2018_idioms)]
@@ -18,9 +39,23 @@
 
 pub mod cli;
 pub mod errors;
+pub mod llm_client;
+pub mod prompt_template;
 
 // Re-export commonly used types
 pub use errors::*;
+pub use llm_client::{HttpLlmClient, ToolFactory};
+pub use prompt_template::{DefaultPromptTemplate, FilePromptTemplate, PromptTemplate};
+pub use parseltongue_core::entity_filter::QueryBuilder;
+pub use parseltongue_core::llm_backend::{LlmBackend, MockLlmClient};
+
+use std::future::Future;
+
+use parseltongue_core::entities::{AppliedWrite, CodeEntity, TemporalAction};
+use parseltongue_core::storage::CozoDbStorage;
+
+/// Tool name recorded as the last modifier of entities this tool writes
+pub const PROVENANCE_TOOL: &str = "pt03";
 
 /// L1 Core Type: Entity modification actions
 ///
@@ -52,6 +87,111 @@ impl EntityAction {
             EntityAction::Delete => ("true", "false", "Delete"),
         }
     }
+
+    /// Core temporal action for this entity action
+    pub fn to_temporal_action(self) -> TemporalAction {
+        match self {
+            EntityAction::Create => TemporalAction::Create,
+            EntityAction::Edit => TemporalAction::Edit,
+            EntityAction::Delete => TemporalAction::Delete,
+        }
+    }
+}
+
+/// Persist an edit or delete and record pt03 as the entity's last modifier
+///
+/// The write is version-checked like `update_entity_internal`; provenance
+/// is only recorded once it succeeds.
+pub async fn write_entity_change(
+    storage: &CozoDbStorage,
+    entity: &CodeEntity,
+) -> parseltongue_core::Result<()> {
+    storage.update_entity_internal(entity).await?;
+    storage.record_provenance(&entity.isgl1_key, PROVENANCE_TOOL).await?;
+    Ok(())
+}
+
+/// Record an edit's future code without reading the entity first
+///
+/// For writers holding no expected version: skips the read that
+/// `write_entity_change` needs for its conflict check.
+pub async fn write_future_code(
+    storage: &CozoDbStorage,
+    isgl1_key: &str,
+    future_code: &str,
+) -> parseltongue_core::Result<()> {
+    storage.set_future_code(isgl1_key, future_code, TemporalAction::Edit).await?;
+    storage.record_provenance(isgl1_key, PROVENANCE_TOOL).await?;
+    Ok(())
+}
+
+/// Outcome of a write issued through [`write_once`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteOnce {
+    /// The write ran now
+    Applied(AppliedWrite),
+    /// The key was already used; this is the earlier write, not re-run
+    Replayed(AppliedWrite),
+}
+
+impl WriteOnce {
+    pub fn record(&self) -> &AppliedWrite {
+        match self {
+            WriteOnce::Applied(write) | WriteOnce::Replayed(write) => write,
+        }
+    }
+}
+
+/// Run `write` at most once per `idempotency_key`
+///
+/// The first call runs `write` and records its result under the key. A
+/// later call with the same key returns that record without running
+/// `write`, provided it targets the same entity and action; reusing a key
+/// for a different write is an error. A failed write records nothing, so
+/// it can be retried under the same key. `applied_at` comes from the
+/// storage's clock.
+pub async fn write_once<F, Fut>(
+    storage: &CozoDbStorage,
+    idempotency_key: &str,
+    isgl1_key: &str,
+    action: &str,
+    write: F,
+) -> anyhow::Result<WriteOnce>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<String>>,
+{
+    if let Some(prior) = storage.get_applied_write(idempotency_key).await? {
+        return replay(idempotency_key, isgl1_key, action, prior);
+    }
+
+    let result = write().await?;
+    let applied = AppliedWrite {
+        isgl1_key: isgl1_key.to_string(),
+        action: action.to_string(),
+        result,
+        applied_at: storage.clock().now().to_rfc3339(),
+    };
+    let recorded = storage.record_applied_write(idempotency_key, &applied).await?;
+    if recorded == applied {
+        Ok(WriteOnce::Applied(applied))
+    } else {
+        // A concurrent run recorded the key between our check and write
+        replay(idempotency_key, isgl1_key, action, recorded)
+    }
+}
+
+/// The earlier write under `idempotency_key`, if it was for the same change
+fn replay(idempotency_key: &str, isgl1_key: &str, action: &str, prior: AppliedWrite) -> anyhow::Result<WriteOnce> {
+    if prior.isgl1_key != isgl1_key || prior.action != action {
+        anyhow::bail!(
+            "Idempotency key {} was already used for {} {}; use a new key for a different write",
+            idempotency_key,
+            prior.action,
+            prior.isgl1_key
+        );
+    }
+    Ok(WriteOnce::Replayed(prior))
 }
 
 /// L1 Core Type: Simple interface configuration
@@ -78,9 +218,8 @@ impl SimpleUpdateConfig {
     /// # Returns
     /// Valid CozoDB Datalog query string
     ///
-    /// # Panics
-    /// Panics if Create/Edit action is called without future_code.
-    /// This is a precondition violation (contract-based programming).
+    /// # Errors
+    /// `ValidationError` if a Create/Edit action has no future_code.
     ///
     /// # Examples
     /// ```ignore
@@ -90,19 +229,17 @@ impl SimpleUpdateConfig {
     ///     future_code: Some("fn hello() {}".to_string()),
     ///     db_path: "test.db".to_string(),
     /// };
-    /// let datalog = config.to_datalog();
+    /// let datalog = config.to_datalog()?;
     /// ```
-    pub fn to_datalog(&self) -> String {
-        // Precondition validation (contract-based programming)
-        // Using panic! is intentional - this is a programming error, not a runtime error
-        self.validate_preconditions();
+    pub fn to_datalog(&self) -> Result<String> {
+        self.validate()?;
 
         let (current_ind, future_ind, action_str) = self.action.to_temporal_state();
         let future_code_value = self.escape_future_code();
 
         // Generate Datalog matching actual CodeGraph schema (14 fields - includes entity_class)
         // Note: ISGL1_key => indicates primary key in :put syntax
-        format!(
+        Ok(format!(
             r#"?[ISGL1_key, Current_Code, Future_Code, interface_signature, TDD_Classification,
               lsp_meta_data, current_ind, future_ind, Future_Action, file_path, language,
               last_modified, entity_type, entity_class] <-
@@ -117,17 +254,19 @@ impl SimpleUpdateConfig {
                 last_modified, entity_type, entity_class
             }}"#,
             self.entity_key, future_code_value, current_ind, future_ind, action_str
-        )
+        ))
     }
 
-    /// Validate preconditions (pure function - no side effects)
-    fn validate_preconditions(&self) {
-        match self.action {
-            EntityAction::Create | EntityAction::Edit if self.future_code.is_none() => {
-                panic!("{:?} action requires future_code", self.action);
-            }
-            _ => {}
-        }
+    /// Check the action/future_code invariant (same rule as
+    /// `CodeEntity::validate_temporal_consistency`)
+    pub fn validate(&self) -> Result<()> {
+        self.action
+            .to_temporal_action()
+            .check_future_code(self.future_code.as_deref())
+            .map_err(|reason| LlmWriterError::ValidationError {
+                field: "future_code".to_string(),
+                reason,
+            })
     }
 
     /// Escape future_code for Datalog (pure function)
@@ -0,0 +1,398 @@
+//! LLM clients selectable through [`LlmBackend`].
+//!
+//! `OpenAi` and `Local` share one OpenAI-compatible chat-completions client;
+//! `Mock` is served by [`MockLlmClient`] from parseltongue-core and never
+//! opens a socket.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use parseltongue_core::entities::TemporalAction;
+use parseltongue_core::error::{ParseltongError, Result as CoreResult};
+use parseltongue_core::interfaces::{
+    LlmClient, LlmRequest, LlmResponse, ProposedChange, RateLimitStatus, ValidationStatus,
+};
+use parseltongue_core::llm_backend::{LlmBackend, MockLlmClient};
+use serde::Deserialize;
+
+use crate::errors::{LlmWriterError, Result};
+use crate::prompt_template::{DefaultPromptTemplate, FilePromptTemplate, PromptTemplate};
+
+/// Hosted OpenAI chat-completions endpoint
+pub const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1/chat/completions";
+
+/// Environment variable holding the OpenAI API key
+pub const OPENAI_API_KEY_ENV_VAR: &str = "OPENAI_API_KEY";
+
+/// Environment variable overriding the model name
+pub const LLM_MODEL_ENV_VAR: &str = "PARSELTONGUE_LLM_MODEL";
+
+/// Model used when `PARSELTONGUE_LLM_MODEL` is unset
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// Environment variable naming a [`FilePromptTemplate`] to use instead of
+/// the default prompt
+pub const PROMPT_TEMPLATE_ENV_VAR: &str = "PARSELTONGUE_PROMPT_TEMPLATE";
+
+const SYSTEM_PROMPT: &str = "You propose code changes for a Parseltongue code graph. \
+Reply with JSON only: {\"reasoning\": string, \"confidence\": number, \
+\"changes\": [{\"target_entity\": string, \"action\": \"Create\"|\"Edit\"|\"Delete\", \
+\"new_content\": string, \"justification\": string, \"affected_dependencies\": [string]}]}";
+
+/// OpenAI-compatible chat-completions client
+pub struct HttpLlmClient {
+    http: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    model: String,
+    prompt: Box<dyn PromptTemplate>,
+    rate_limit: Mutex<Option<RateLimitStatus>>,
+}
+
+impl HttpLlmClient {
+    pub fn new(endpoint: impl Into<String>, api_key: Option<String>, model: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: endpoint.into(),
+            api_key,
+            model: model.into(),
+            prompt: Box::new(DefaultPromptTemplate),
+            rate_limit: Mutex::new(None),
+        }
+    }
+
+    /// Render user prompts with `prompt` instead of [`DefaultPromptTemplate`]
+    pub fn with_prompt_template(mut self, prompt: impl PromptTemplate + 'static) -> Self {
+        self.prompt = Box::new(prompt);
+        self
+    }
+
+    fn record_rate_limit(&self, headers: &reqwest::header::HeaderMap) {
+        let header_u32 = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u32>().ok())
+        };
+        if let (Some(remaining), Some(limit)) = (
+            header_u32("x-ratelimit-remaining-requests"),
+            header_u32("x-ratelimit-limit-requests"),
+        ) {
+            let status = RateLimitStatus {
+                requests_remaining: remaining,
+                reset_time: SystemTime::now(),
+                limit,
+            };
+            if let Ok(mut slot) = self.rate_limit.lock() {
+                *slot = Some(status);
+            }
+        }
+    }
+
+    async fn post(&self, request: &LlmRequest) -> Result<LlmResponse> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": request.constraints.max_tokens,
+            "temperature": request.constraints.temperature,
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": self.prompt.build_prompt(request) },
+            ],
+        });
+
+        let mut builder = self
+            .http
+            .post(&self.endpoint)
+            .timeout(request.task.success_criteria.max_duration)
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                LlmWriterError::TimeoutError {
+                    seconds: request.task.success_criteria.max_duration.as_secs(),
+                }
+            } else {
+                LlmWriterError::LlmApiError {
+                    status: e.status().map(|s| s.as_u16()).unwrap_or(0),
+                    message: e.to_string(),
+                }
+            }
+        })?;
+        self.record_rate_limit(response.headers());
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let seconds = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(60);
+            return Err(LlmWriterError::RateLimitError { seconds });
+        }
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(LlmWriterError::AuthenticationError {
+                reason: format!("endpoint {} rejected credentials", self.endpoint),
+            });
+        }
+        if !status.is_success() {
+            return Err(LlmWriterError::LlmApiError {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        let completion: ChatCompletion = response
+            .json()
+            .await
+            .map_err(|e| LlmWriterError::ResponseParseError { reason: e.to_string() })?;
+        let content = completion
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| LlmWriterError::ResponseParseError {
+                reason: "completion contained no choices".to_string(),
+            })?;
+
+        parse_completion_content(request, &content)
+    }
+}
+
+#[async_trait]
+impl LlmClient for HttpLlmClient {
+    async fn send_request(&self, request: LlmRequest) -> CoreResult<LlmResponse> {
+        self.post(&request).await.map_err(ParseltongError::from)
+    }
+
+    fn validate_response(&self, response: &LlmResponse, request: &LlmRequest) -> CoreResult<()> {
+        if response.request_id != request.request_id {
+            return Err(ParseltongError::LlmError {
+                reason: "response does not belong to this request".to_string(),
+            });
+        }
+        if response.confidence_score < request.constraints.min_confidence {
+            return Err(ParseltongError::LlmError {
+                reason: format!(
+                    "confidence {} below required {}",
+                    response.confidence_score, request.constraints.min_confidence
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    async fn get_rate_limit_status(&self) -> CoreResult<RateLimitStatus> {
+        let recorded = self.rate_limit.lock().ok().and_then(|slot| slot.clone());
+        Ok(recorded.unwrap_or(RateLimitStatus {
+            requests_remaining: u32::MAX,
+            reset_time: SystemTime::now() + Duration::from_secs(60),
+            limit: u32::MAX,
+        }))
+    }
+
+    fn estimate_tokens(&self, content: &str) -> usize {
+        content.len() / 4
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatCompletion {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ChangePayload {
+    #[serde(default)]
+    reasoning: String,
+    #[serde(default)]
+    confidence: f64,
+    #[serde(default)]
+    changes: Vec<ChangeEntry>,
+}
+
+#[derive(Deserialize)]
+struct ChangeEntry {
+    target_entity: String,
+    action: String,
+    #[serde(default)]
+    new_content: String,
+    #[serde(default)]
+    justification: String,
+    #[serde(default)]
+    affected_dependencies: Vec<String>,
+}
+
+/// Convert the model's JSON answer into an `LlmResponse` (pure function)
+fn parse_completion_content(request: &LlmRequest, content: &str) -> Result<LlmResponse> {
+    let payload: ChangePayload = serde_json::from_str(content.trim())
+        .map_err(|e| LlmWriterError::ResponseParseError { reason: e.to_string() })?;
+
+    let proposed_changes = payload
+        .changes
+        .into_iter()
+        .map(|change| {
+            let change_type = match change.action.as_str() {
+                "Create" => TemporalAction::Create,
+                "Edit" => TemporalAction::Edit,
+                "Delete" => TemporalAction::Delete,
+                other => {
+                    return Err(LlmWriterError::ValidationError {
+                        field: "action".to_string(),
+                        reason: format!("unknown action '{}' for {}", other, change.target_entity),
+                    })
+                }
+            };
+            Ok(ProposedChange {
+                target_entity: change.target_entity,
+                change_type,
+                new_content: change.new_content,
+                justification: change.justification,
+                affected_dependencies: change.affected_dependencies,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LlmResponse {
+        request_id: request.request_id,
+        reasoning: payload.reasoning,
+        proposed_changes,
+        confidence_score: payload.confidence,
+        validation_status: ValidationStatus::Unknown,
+    })
+}
+
+/// Tool factory for dependency injection
+pub struct ToolFactory;
+
+impl ToolFactory {
+    /// Create the LLM client for `backend`
+    ///
+    /// `OpenAi` reads its key from `OPENAI_API_KEY`; `Local` sends one only
+    /// if that variable is set. Both use the prompt template named by
+    /// `PARSELTONGUE_PROMPT_TEMPLATE` when set. `Mock` needs no configuration.
+    pub fn create_llm_client(backend: &LlmBackend) -> Result<Arc<dyn LlmClient>> {
+        let model = std::env::var(LLM_MODEL_ENV_VAR).unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        let api_key = std::env::var(OPENAI_API_KEY_ENV_VAR)
+            .ok()
+            .filter(|key| !key.is_empty());
+        let with_template = |client: HttpLlmClient| -> Result<Arc<dyn LlmClient>> {
+            match std::env::var_os(PROMPT_TEMPLATE_ENV_VAR).filter(|path| !path.is_empty()) {
+                Some(path) => Ok(Arc::new(
+                    client.with_prompt_template(FilePromptTemplate::from_file(path.as_ref())?),
+                )),
+                None => Ok(Arc::new(client)),
+            }
+        };
+
+        match backend {
+            LlmBackend::Mock => Ok(Arc::new(MockLlmClient::new())),
+            LlmBackend::OpenAi => {
+                let api_key = api_key.ok_or_else(|| LlmWriterError::AuthenticationError {
+                    reason: format!("{} is not set", OPENAI_API_KEY_ENV_VAR),
+                })?;
+                with_template(HttpLlmClient::new(OPENAI_ENDPOINT, Some(api_key), model))
+            }
+            LlmBackend::Local(endpoint) => {
+                with_template(HttpLlmClient::new(endpoint.clone(), api_key, model))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parseltongue_core::interfaces::*;
+    use uuid::Uuid;
+
+    fn empty_request() -> LlmRequest {
+        LlmRequest {
+            request_id: Uuid::new_v4(),
+            context: CodeGraphContext {
+                version: "test".to_string(),
+                generated_at: chrono::Utc::now(),
+                token_count: 0,
+                entities: vec![],
+                relationships: vec![],
+                optimization_info: OptimizationInfo {
+                    excluded_entities: vec![],
+                    truncation_applied: false,
+                    prioritization_strategy: "none".to_string(),
+                },
+            },
+            task: TaskSpecification {
+                task_type: TaskType::ChangeReasoning,
+                instruction: "rename".to_string(),
+                success_criteria: SuccessCriteria {
+                    min_confidence: 0.5,
+                    max_duration: Duration::from_secs(5),
+                    validation_rules: vec![],
+                },
+            },
+            constraints: RequestConstraints {
+                max_tokens: 256,
+                temperature: 0.0,
+                min_confidence: 0.5,
+            },
+        }
+    }
+
+    #[test]
+    fn parses_completion_json_into_proposed_changes() {
+        let request = empty_request();
+        let content = r#"{"reasoning":"ok","confidence":0.9,"changes":[
+            {"target_entity":"rust:fn:a:lib_rs:1-2","action":"Delete"}]}"#;
+
+        let response = parse_completion_content(&request, content).unwrap();
+        assert_eq!(response.request_id, request.request_id);
+        assert_eq!(response.proposed_changes.len(), 1);
+        assert_eq!(response.proposed_changes[0].change_type, TemporalAction::Delete);
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        let content = r#"{"changes":[{"target_entity":"k","action":"Rename"}]}"#;
+        assert!(matches!(
+            parse_completion_content(&empty_request(), content),
+            Err(LlmWriterError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn default_template_lists_task_and_entities() {
+        let prompt = DefaultPromptTemplate.build_prompt(&empty_request());
+        assert!(prompt.starts_with("Task (ChangeReasoning): rename\n\nEntities:"));
+    }
+
+    #[test]
+    fn file_template_renders_custom_instruction_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.txt");
+        std::fs::write(&path, "Answer tersely, fine-tuned style.\n{instruction}\n{entities}").unwrap();
+
+        let prompt = FilePromptTemplate::from_file(&path).unwrap().build_prompt(&empty_request());
+        assert_eq!(prompt, "Answer tersely, fine-tuned style.\nrename\n");
+        assert!(FilePromptTemplate::new("no placeholder").is_err());
+    }
+
+    #[test]
+    fn local_backend_needs_no_api_key() {
+        let backend = LlmBackend::Local("http://127.0.0.1:9/v1/chat/completions".to_string());
+        assert!(ToolFactory::create_llm_client(&backend).is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+//! User prompts sent by [`HttpLlmClient`](crate::HttpLlmClient).
+//!
+//! The system prompt fixes the JSON reply format the client parses, so only
+//! the user prompt is pluggable. [`DefaultPromptTemplate`] is the built-in
+//! wording; [`FilePromptTemplate`] reads one from disk so prompts can be
+//! tuned for a particular model without recompiling.
+
+use std::path::Path;
+
+use parseltongue_core::interfaces::LlmRequest;
+
+use crate::errors::{LlmWriterError, Result};
+
+/// Renders an [`LlmRequest`] into the user prompt
+pub trait PromptTemplate: Send + Sync {
+    fn build_prompt(&self, request: &LlmRequest) -> String;
+}
+
+/// Task, entity list and relationship list, one item per line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultPromptTemplate;
+
+impl PromptTemplate for DefaultPromptTemplate {
+    fn build_prompt(&self, request: &LlmRequest) -> String {
+        format!(
+            "Task ({}): {}\n\nEntities:\n{}\n\nRelationships:\n{}",
+            task_type(request),
+            request.task.instruction,
+            entity_lines(request),
+            relationship_lines(request)
+        )
+    }
+}
+
+/// Template text with placeholders, usually loaded from a file
+///
+/// Recognised placeholders: `{task}`, `{instruction}`, `{entities}` and
+/// `{relationships}`, rendered as in [`DefaultPromptTemplate`]. `{entities}`
+/// is required; a prompt without the entities is never what was meant.
+#[derive(Debug, Clone)]
+pub struct FilePromptTemplate {
+    text: String,
+}
+
+impl FilePromptTemplate {
+    pub fn new(text: impl Into<String>) -> Result<Self> {
+        let text = text.into();
+        if !text.contains("{entities}") {
+            return Err(LlmWriterError::ConfigurationError {
+                field: "prompt template".to_string(),
+                reason: "missing the {entities} placeholder".to_string(),
+            });
+        }
+        Ok(Self { text })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| LlmWriterError::ConfigurationError {
+            field: "prompt template".to_string(),
+            reason: format!("cannot read {}: {}", path.display(), e),
+        })?;
+        Self::new(text)
+    }
+}
+
+impl PromptTemplate for FilePromptTemplate {
+    fn build_prompt(&self, request: &LlmRequest) -> String {
+        self.text
+            .replace("{task}", &task_type(request))
+            .replace("{entities}", &entity_lines(request))
+            .replace("{relationships}", &relationship_lines(request))
+            // Last, so braces in free-form instruction text are left alone
+            .replace("{instruction}", &request.task.instruction)
+    }
+}
+
+fn task_type(request: &LlmRequest) -> String {
+    format!("{:?}", request.task.task_type)
+}
+
+fn entity_lines(request: &LlmRequest) -> String {
+    request
+        .context
+        .entities
+        .iter()
+        .map(|entity| format!("- {} ({})", entity.isgl1_key, entity.interface_signature.name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn relationship_lines(request: &LlmRequest) -> String {
+    request
+        .context
+        .relationships
+        .iter()
+        .map(|rel| format!("- {} -[{}]-> {}", rel.dependent, rel.relationship_type, rel.dependency))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
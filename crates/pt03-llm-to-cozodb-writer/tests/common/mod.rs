@@ -0,0 +1,48 @@
+//! Fixtures shared by the pt03 integration tests
+//!
+//! Every test binary compiles its own copy of this module and uses only
+//! part of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use parseltongue_core::entities::{
+    CodeEntity, EntityClass, EntityType, InterfaceSignature, LanguageSpecificSignature,
+    LineRange, RustSignature, Visibility,
+};
+use parseltongue_core::storage::CozoDbStorage;
+use std::path::PathBuf;
+
+/// Key of [`indexed_entity`]
+pub const KEY: &str = "rust:fn:hello:src_lib_rs:1-3";
+
+/// `pub fn hello() {}` in src/lib.rs, as ingestion stores it
+pub fn indexed_entity() -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: "hello".to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity =
+        CodeEntity::new(KEY.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = Some("pub fn hello() {}".to_string());
+    entity
+}
+
+/// In-memory storage holding just [`indexed_entity`]
+pub async fn storage_with_entity() -> CozoDbStorage {
+    let storage = CozoDbStorage::new("mem").await.unwrap();
+    storage.ensure_schema().await.unwrap();
+    storage.insert_entity(&indexed_entity()).await.unwrap();
+    storage
+}
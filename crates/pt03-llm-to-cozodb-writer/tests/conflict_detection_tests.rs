@@ -0,0 +1,86 @@
+//! Entity-level optimistic locking
+//!
+//! Two agents read the same entity; the first write wins and the second must
+//! fail with `ConflictError` instead of silently overwriting it.
+
+mod common;
+
+use common::{indexed_entity, storage_with_entity, KEY};
+use parseltongue_core::entities::{CodeEntity, TemporalAction};
+use parseltongue_core::error::ParseltongError;
+
+fn edited(mut entity: CodeEntity, code: &str) -> CodeEntity {
+    entity
+        .apply_temporal_change(TemporalAction::Edit, Some(code.to_string()))
+        .unwrap();
+    entity
+}
+
+#[tokio::test]
+async fn test_conflicting_write_is_rejected() {
+    let storage = storage_with_entity().await;
+
+    // Both agents read the same version
+    let agent_a = storage.get_entity(KEY).await.unwrap();
+    let agent_b = storage.get_entity(KEY).await.unwrap();
+    assert!(!agent_a.metadata.content_hash.is_empty());
+    assert_eq!(agent_a.metadata.content_hash, agent_b.metadata.content_hash);
+
+    storage
+        .update_entity_internal(&edited(agent_a, "pub fn hello() { /* a */ }"))
+        .await
+        .expect("first write succeeds");
+
+    let err = storage
+        .update_entity_internal(&edited(agent_b.clone(), "pub fn hello() { /* b */ }"))
+        .await
+        .expect_err("stale write must conflict");
+    match err {
+        ParseltongError::ConflictError { isgl1_key, expected, actual } => {
+            assert_eq!(isgl1_key, KEY);
+            assert_eq!(expected, agent_b.metadata.content_hash);
+            assert_ne!(actual, expected);
+        }
+        other => panic!("expected ConflictError, got {:?}", other),
+    }
+
+    // Agent A's change survived
+    let stored = storage.get_entity(KEY).await.unwrap();
+    assert_eq!(stored.future_code.as_deref(), Some("pub fn hello() { /* a */ }"));
+
+    // Re-read and retry succeeds
+    storage
+        .update_entity_internal(&edited(stored, "pub fn hello() { /* b */ }"))
+        .await
+        .expect("retry after re-read succeeds");
+    let stored = storage.get_entity(KEY).await.unwrap();
+    assert_eq!(stored.future_code.as_deref(), Some("pub fn hello() { /* b */ }"));
+}
+
+#[tokio::test]
+async fn test_explicit_expected_hash_and_unversioned_writes() {
+    let storage = storage_with_entity().await;
+    let read = storage.get_entity(KEY).await.unwrap();
+
+    // --expect-hash with a stale token conflicts
+    let mut stale = edited(read.clone(), "pub fn hello() { 1 }");
+    stale.metadata.content_hash = "not-the-current-version".to_string();
+    assert!(matches!(
+        storage.update_entity_internal(&stale).await,
+        Err(ParseltongError::ConflictError { .. })
+    ));
+
+    // Matching token writes, and the stored version is the written entity's
+    let write = edited(read, "pub fn hello() { 2 }");
+    storage
+        .update_entity_if_version(&write, &write.metadata.content_hash)
+        .await
+        .unwrap();
+    let stored = storage.get_entity(KEY).await.unwrap();
+    assert_eq!(stored.metadata.content_hash, write.version_hash());
+
+    // Entities without a version token are written unconditionally
+    let mut unversioned = edited(indexed_entity(), "pub fn hello() { 3 }");
+    unversioned.metadata.content_hash.clear();
+    storage.update_entity_internal(&unversioned).await.unwrap();
+}
@@ -0,0 +1,74 @@
+//! Idempotency keys: a retried write with the same key is applied once
+
+mod common;
+
+use common::{indexed_entity, storage_with_entity, KEY};
+use parseltongue_core::clock::FixedClock;
+use parseltongue_core::storage::CozoDbStorage;
+use pt03_llm_to_cozodb_writer::{write_future_code, write_once, WriteOnce};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_same_idempotency_key_applies_write_once() {
+    let instant = chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, 1, 12, 0, 0).unwrap();
+    let storage = CozoDbStorage::new("mem").await.unwrap().with_clock(Arc::new(FixedClock(instant)));
+    storage.ensure_schema().await.unwrap();
+    storage.insert_entity(&indexed_entity()).await.unwrap();
+
+    let applied = AtomicUsize::new(0);
+    let edit = |code: &'static str| {
+        let (storage, applied) = (&storage, &applied);
+        move || async move {
+            applied.fetch_add(1, Ordering::SeqCst);
+            write_future_code(storage, KEY, code).await?;
+            Ok::<_, anyhow::Error>(format!("Edit pending: {}", code))
+        }
+    };
+
+    let first = write_once(&storage, "retry-1", KEY, "edit", edit("pub fn hello() { 1 }")).await.unwrap();
+    assert!(matches!(first, WriteOnce::Applied(_)));
+    assert_eq!(first.record().applied_at, instant.to_rfc3339(), "stamped by the storage's clock");
+
+    // The agent timed out and retries; meanwhile the entity moved on
+    write_future_code(&storage, KEY, "pub fn hello() { 2 }").await.unwrap();
+    let retry = write_once(&storage, "retry-1", KEY, "edit", edit("pub fn hello() { 1 }")).await.unwrap();
+
+    assert_eq!(applied.load(Ordering::SeqCst), 1, "the retry must not re-run the write");
+    assert_eq!(retry, WriteOnce::Replayed(first.record().clone()));
+    assert_eq!(retry.record().result, "Edit pending: pub fn hello() { 1 }");
+    let entity = storage.get_entity(KEY).await.unwrap();
+    assert_eq!(entity.future_code.as_deref(), Some("pub fn hello() { 2 }"), "later write not clobbered");
+
+    // A new key is a new write
+    let fresh = write_once(&storage, "retry-2", KEY, "edit", edit("pub fn hello() { 3 }")).await.unwrap();
+    assert!(matches!(fresh, WriteOnce::Applied(_)));
+    assert_eq!(applied.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_idempotency_key_reused_for_other_write_is_rejected() {
+    let storage = storage_with_entity().await;
+
+    write_once(&storage, "k", KEY, "edit", || async {
+        write_future_code(&storage, KEY, "pub fn hello() { 1 }").await?;
+        Ok::<_, anyhow::Error>("Edit pending".to_string())
+    })
+    .await
+    .unwrap();
+
+    let err = write_once(&storage, "k", KEY, "delete", || async { Ok::<_, anyhow::Error>("Delete pending".to_string()) })
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("already used for edit"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_failed_write_records_no_idempotency_key() {
+    let storage = CozoDbStorage::new("mem").await.unwrap();
+    storage.ensure_schema().await.unwrap();
+
+    let failed = write_once(&storage, "k", KEY, "edit", || async { Err::<String, _>(anyhow::anyhow!("entity not found")) }).await;
+    assert!(failed.is_err());
+    assert_eq!(storage.get_applied_write("k").await.unwrap(), None);
+}
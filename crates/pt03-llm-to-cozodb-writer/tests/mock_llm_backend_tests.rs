@@ -0,0 +1,148 @@
+//! Hermetic LLM backend tests
+//!
+//! The mock backend must build a usable CodeGraphContext and answer requests
+//! without any network access, so CI can exercise the pt02 → pt03 pipeline.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use parseltongue_core::entities::{
+    CodeEntity, EntityClass, EntityType, InterfaceSignature, LanguageSpecificSignature,
+    LineRange, RustSignature, TemporalAction, Visibility,
+};
+use parseltongue_core::interfaces::*;
+use pt03_llm_to_cozodb_writer::{LlmBackend, MockLlmClient, ToolFactory};
+use uuid::Uuid;
+
+fn create_test_entity(name: &str, lines: (u32, u32)) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(lines.0, lines.1).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:fn:{}:src_lib_rs:{}-{}", name, lines.0, lines.1);
+    CodeEntity::new(key, signature, EntityClass::CodeImplementation).unwrap()
+}
+
+fn context_query(base: &str, size_limit: usize) -> ContextQuery {
+    ContextQuery {
+        base_entities: vec![base.to_string()],
+        hop_depth: 1,
+        change_type: ChangeType::Edit,
+        size_limit,
+        optimization_strategy: OptimizationStrategy::BlastRadius,
+    }
+}
+
+fn request_for(context: CodeGraphContext) -> LlmRequest {
+    LlmRequest {
+        request_id: Uuid::new_v4(),
+        context,
+        task: TaskSpecification {
+            task_type: TaskType::ChangeReasoning,
+            instruction: "add logging".to_string(),
+            success_criteria: SuccessCriteria {
+                min_confidence: 0.8,
+                max_duration: Duration::from_secs(1),
+                validation_rules: vec![],
+            },
+        },
+        constraints: RequestConstraints {
+            max_tokens: 1024,
+            temperature: 0.0,
+            min_confidence: 0.8,
+        },
+    }
+}
+
+#[tokio::test]
+async fn mock_backend_produces_valid_context_without_network() {
+    let backend = LlmBackend::resolve_from(Some("mock"), None).unwrap();
+    assert!(!backend.requires_network());
+
+    let entities = vec![
+        create_test_entity("beta", (10, 20)),
+        create_test_entity("alpha", (1, 5)),
+    ];
+    let base_key = entities[1].isgl1_key.clone();
+
+    let generator = MockLlmClient::new();
+    let context = generator
+        .generate_context(entities, &context_query(&base_key, 10_000))
+        .await
+        .unwrap();
+
+    assert!(!context.version.is_empty());
+    assert_eq!(context.entities.len(), 2);
+    assert_eq!(context.entities[0].isgl1_key, base_key, "base entity ranks first");
+    assert_eq!(context.token_count, ContextGenerator::estimate_tokens(&generator, &context));
+    assert!(!context.optimization_info.truncation_applied);
+
+    // Dispatch through the factory exactly as a tool would
+    let client = ToolFactory::create_llm_client(&backend).unwrap();
+    let request = request_for(context);
+    let response = client.send_request(request.clone()).await.unwrap();
+
+    client.validate_response(&response, &request).unwrap();
+    assert_eq!(response.proposed_changes.len(), 2);
+    assert!(response
+        .proposed_changes
+        .iter()
+        .all(|change| change.change_type == TemporalAction::Edit
+            && change.new_content.contains("add logging")));
+}
+
+#[tokio::test]
+async fn mock_backend_is_deterministic() {
+    let client = ToolFactory::create_llm_client(&LlmBackend::Mock).unwrap();
+    let context = MockLlmClient::new()
+        .generate_context(
+            vec![create_test_entity("gamma", (3, 4))],
+            &context_query("none", 10_000),
+        )
+        .await
+        .unwrap();
+    let request = request_for(context);
+
+    let first = client.send_request(request.clone()).await.unwrap();
+    let second = client.send_request(request).await.unwrap();
+
+    assert_eq!(first.reasoning, second.reasoning);
+    assert_eq!(
+        first.proposed_changes[0].new_content,
+        second.proposed_changes[0].new_content
+    );
+}
+
+#[tokio::test]
+async fn mock_context_respects_token_limit() {
+    let entities = (1..=5)
+        .map(|i| create_test_entity(&format!("function_{}", i), (i, i + 1)))
+        .collect::<Vec<_>>();
+    let base_key = entities[0].isgl1_key.clone();
+
+    let generator = MockLlmClient::new();
+    let context = generator
+        .generate_context(entities, &context_query(&base_key, 12))
+        .await
+        .unwrap();
+
+    assert!(context.token_count <= 12);
+    assert!(context.optimization_info.truncation_applied);
+    assert_eq!(context.entities[0].isgl1_key, base_key);
+    assert_eq!(
+        context.entities.len() + context.optimization_info.excluded_entities.len(),
+        5
+    );
+}
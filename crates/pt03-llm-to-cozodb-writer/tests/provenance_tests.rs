@@ -0,0 +1,52 @@
+//! Provenance: edits record which tool last modified an entity
+
+mod common;
+
+use common::{storage_with_entity, KEY};
+use parseltongue_core::entities::TemporalAction;
+use pt03_llm_to_cozodb_writer::{write_entity_change, PROVENANCE_TOOL};
+
+#[tokio::test]
+async fn test_edit_records_pt03_as_last_modifier() {
+    let storage = storage_with_entity().await;
+    assert_eq!(storage.get_provenance(KEY).await.unwrap(), None, "ingestion alone records nothing");
+
+    let mut entity = storage.get_entity(KEY).await.unwrap();
+    entity
+        .apply_temporal_change(TemporalAction::Edit, Some("pub fn hello() { /* edited */ }".to_string()))
+        .unwrap();
+    write_entity_change(&storage, &entity).await.unwrap();
+
+    let provenance = storage.get_provenance(KEY).await.unwrap().expect("edit records provenance");
+    assert_eq!(provenance.tool, PROVENANCE_TOOL);
+    assert_eq!(provenance.tool, "pt03");
+    assert!(chrono::DateTime::parse_from_rfc3339(&provenance.modified_at).is_ok(), "{}", provenance.modified_at);
+}
+
+#[tokio::test]
+async fn test_conflicting_edit_records_no_provenance() {
+    let storage = storage_with_entity().await;
+
+    let mut stale = storage.get_entity(KEY).await.unwrap();
+    stale.metadata.content_hash = "stale".to_string();
+    stale
+        .apply_temporal_change(TemporalAction::Edit, Some("pub fn hello() { /* b */ }".to_string()))
+        .unwrap();
+
+    assert!(write_entity_change(&storage, &stale).await.is_err());
+    assert_eq!(storage.get_provenance(KEY).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_unchecked_edit_records_pt03_without_reading() {
+    let storage = storage_with_entity().await;
+
+    pt03_llm_to_cozodb_writer::write_future_code(&storage, KEY, "pub fn hello() { /* fast */ }")
+        .await
+        .unwrap();
+
+    let entity = storage.get_entity(KEY).await.unwrap();
+    assert_eq!(entity.future_code.as_deref(), Some("pub fn hello() { /* fast */ }"));
+    assert_eq!(entity.current_code.as_deref(), Some("pub fn hello() {}"));
+    assert_eq!(storage.get_provenance(KEY).await.unwrap().unwrap().tool, "pt03");
+}
@@ -17,7 +17,7 @@ fn test_create_action_generates_datalog() {
         db_path: "test.db".to_string(),
     };
 
-    let datalog = config.to_datalog();
+    let datalog = config.to_datalog().unwrap();
     println!("Generated Datalog:\n{}", datalog);
 
     // Postcondition: current_ind=0, future_ind=1, Future_Action="Create"
@@ -39,7 +39,7 @@ fn test_edit_action_generates_datalog() {
         db_path: "test.db".to_string(),
     };
 
-    let datalog = config.to_datalog();
+    let datalog = config.to_datalog().unwrap();
 
     // Postcondition: current_ind=1, future_ind=1, Future_Action="Edit"
     assert!(datalog.contains(":put CodeGraph"));
@@ -57,7 +57,7 @@ fn test_delete_action_generates_datalog() {
         db_path: "test.db".to_string(),
     };
 
-    let datalog = config.to_datalog();
+    let datalog = config.to_datalog().unwrap();
 
     // Postcondition: current_ind=1, future_ind=0, Future_Action="Delete"
     assert!(datalog.contains(":put CodeGraph"));
@@ -68,7 +68,6 @@ fn test_delete_action_generates_datalog() {
 
 /// RED Test 4: Error condition - Create without future_code
 #[test]
-#[should_panic(expected = "Create action requires future_code")]
 fn test_create_requires_future_code() {
     let config = SimpleUpdateConfig {
         entity_key: "rust:fn:new:src_lib_rs:10-15".to_string(),
@@ -77,12 +76,13 @@ fn test_create_requires_future_code() {
         db_path: "test.db".to_string(),
     };
 
-    config.to_datalog(); // Should panic
+    // Recoverable validation error, not a panic
+    let err = config.to_datalog().unwrap_err();
+    assert!(err.to_string().contains("Create action requires future_code"), "{}", err);
 }
 
 /// RED Test 5: Error condition - Edit without future_code
 #[test]
-#[should_panic(expected = "Edit action requires future_code")]
 fn test_edit_requires_future_code() {
     let config = SimpleUpdateConfig {
         entity_key: "rust:fn:existing:src_lib_rs:5-10".to_string(),
@@ -91,7 +91,9 @@ fn test_edit_requires_future_code() {
         db_path: "test.db".to_string(),
     };
 
-    config.to_datalog(); // Should panic
+    // Recoverable validation error, not a panic
+    let err = config.to_datalog().unwrap_err();
+    assert!(err.to_string().contains("Edit action requires future_code"), "{}", err);
 }
 
 /// RED Test 6: CLI mutual exclusion - cannot use --query with --entity
@@ -168,7 +170,7 @@ async fn test_e2e_simple_create_workflow() {
     };
 
     // Generate and execute Datalog
-    let datalog = config.to_datalog();
+    let datalog = config.to_datalog().unwrap();
     println!("Generated Datalog for E2E test:\n{}", datalog);
 
     let result = storage.execute_query(&datalog).await;
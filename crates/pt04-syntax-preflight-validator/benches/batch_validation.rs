@@ -0,0 +1,45 @@
+//! Sequential vs batch validation of a large change set
+//!
+//! Run with `cargo bench -p pt04-syntax-preflight-validator`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use parseltongue_core::entities::Language;
+use pt04_syntax_preflight_validator::{validate_batch, SimpleSyntaxValidator};
+
+/// `count` Rust and Python snippets, every tenth one broken
+fn snippets(count: usize) -> Vec<(String, String, Language)> {
+    (0..count)
+        .map(|i| {
+            let (code, language) = match (i % 2, i % 10) {
+                (_, 9) => (format!("fn broken_{}( {{ let x = ; }}", i), Language::Rust),
+                (0, _) => (
+                    format!("pub fn handler_{i}(input: &[u8]) -> usize {{\n    input.iter().filter(|b| **b > {i} as u8).count()\n}}\n"),
+                    Language::Rust,
+                ),
+                _ => (format!("def handler_{i}(items):\n    return [x for x in items if x > {i}]\n"), Language::Python),
+            };
+            (format!("entity_{}", i), code, language)
+        })
+        .collect()
+}
+
+fn bench_validation(c: &mut Criterion) {
+    let snippets = snippets(2_000);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    c.bench_function("validate_sequential_2000", |b| {
+        b.iter(|| {
+            let mut validator = SimpleSyntaxValidator::new().unwrap();
+            for (_, code, language) in &snippets {
+                black_box(validator.validate_syntax(code, *language).unwrap());
+            }
+        })
+    });
+
+    c.bench_function("validate_batch_2000", |b| {
+        b.iter(|| black_box(runtime.block_on(validate_batch(&snippets))))
+    });
+}
+
+criterion_group!(benches, bench_validation);
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! # Batch Syntax Validation
+//!
+//! Validates many snippets in parallel. Tree-sitter parsers can't be shared
+//! across threads, so each worker runs on `spawn_blocking` with its own
+//! `SimpleSyntaxValidator` and takes a contiguous slice of the input.
+//! Results come back in input order, each paired with its snippet's key.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! use pt04_syntax_preflight_validator::validate_batch;
+//!
+//! let results = validate_batch(&snippets).await;
+//! for (key, result) in &results {
+//!     if !result.is_valid {
+//!         eprintln!("❌ {}: {}", key, result.errors.join("; "));
+//!     }
+//! }
+//! ```
+
+use parseltongue_core::entities::Language;
+
+use crate::simple_validator::{SimpleSyntaxValidator, ValidationResult};
+
+/// Validate `(key, code, language)` snippets across one worker per CPU
+///
+/// Same results as calling `validate_syntax` on each snippet in turn; a
+/// snippet that can't be validated at all (no parser for its language) gets
+/// an invalid result carrying the reason.
+pub async fn validate_batch(snippets: &[(String, String, Language)]) -> Vec<(String, ValidationResult)> {
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    validate_batch_with_workers(snippets, workers).await
+}
+
+/// Same as `validate_batch` with at most `workers` parsers in parallel
+pub async fn validate_batch_with_workers(
+    snippets: &[(String, String, Language)],
+    workers: usize,
+) -> Vec<(String, ValidationResult)> {
+    if snippets.is_empty() {
+        return vec![];
+    }
+    let chunk_size = snippets.len().div_ceil(workers.max(1));
+
+    let tasks: Vec<_> = snippets
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let chunk = chunk.to_vec();
+            tokio::task::spawn_blocking(move || validate_chunk(chunk))
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(snippets.len());
+    for task in tasks {
+        match task.await {
+            Ok(chunk_results) => results.extend(chunk_results),
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+    results
+}
+
+/// One worker: validate `chunk` in order with a validator of its own
+fn validate_chunk(chunk: Vec<(String, String, Language)>) -> Vec<(String, ValidationResult)> {
+    let mut validator = match SimpleSyntaxValidator::new() {
+        Ok(validator) => validator,
+        Err(e) => {
+            let error = format!("Failed to create validator: {}", e);
+            return chunk
+                .into_iter()
+                .map(|(key, _, _)| (key, ValidationResult::invalid(vec![error.clone()])))
+                .collect();
+        }
+    };
+
+    chunk
+        .into_iter()
+        .map(|(key, code, language)| {
+            let result = validator
+                .validate_syntax(&code, language)
+                .unwrap_or_else(|e| ValidationResult::invalid(vec![e.to_string()]));
+            (key, result)
+        })
+        .collect()
+}
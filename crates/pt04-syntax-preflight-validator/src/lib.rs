@@ -18,6 +18,8 @@
 //!
 //! ## Performance
 //! - <20ms for typical change set (50 entities)
+//! - Large change sets: `validate_batch` spreads snippets across one parser per CPU
+//! - Repeat runs: `--only-new` skips code that passed before (`ValidationCache`)
 //! - No cargo compilation overhead
 //! - No temporary file I/O
 //!
@@ -47,6 +49,9 @@
 
 // Simplified validator module (tree-sitter only)
 pub mod simple_validator;
+pub mod batch;
+pub mod report;
+pub mod validation_cache;
 
 // Legacy modules (kept for backward compatibility, will be removed)
 pub mod errors;
@@ -54,7 +59,10 @@ pub mod types;
 pub mod validator;
 
 // Re-export simplified API
-pub use simple_validator::{SimpleSyntaxValidator, ValidationResult};
+pub use batch::{validate_batch, validate_batch_with_workers};
+pub use report::{EntityValidation, SyntaxValidationReport};
+pub use simple_validator::{SimpleSyntaxValidator, SyntaxError, ValidationResult};
+pub use validation_cache::ValidationCache;
 
 // Legacy re-exports (deprecated)
 pub use errors::{Severity, ValidationError};
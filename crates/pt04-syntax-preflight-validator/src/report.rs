@@ -0,0 +1,71 @@
+//! # Syntax Validation Report
+//!
+//! Machine-readable outcome of a pt04 run, written by `--json` for CI
+//! gating and PR annotation.
+//!
+//! The legacy [`crate::ValidationReport`] describes one code snippet with
+//! unpositioned error strings, so it cannot carry per-entity results; this
+//! report keeps one [`EntityValidation`] per entity instead, with the
+//! parser positions from [`SyntaxError`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::simple_validator::{SyntaxError, ValidationResult};
+
+/// Validation outcome for one entity's future_code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityValidation {
+    pub isgl1_key: String,
+    /// Whether the entity passed (warnings count with `--fail-on-warnings`)
+    pub is_valid: bool,
+    /// Syntax errors with positions relative to the future_code
+    pub errors: Vec<SyntaxError>,
+    pub warnings: Vec<String>,
+}
+
+/// Per-entity results of one pt04 run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyntaxValidationReport {
+    /// Whether every entity passed; pt04's exit code follows this
+    pub overall_valid: bool,
+    pub entities_validated: usize,
+    pub entities_failed: usize,
+    pub entities: Vec<EntityValidation>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl SyntaxValidationReport {
+    /// Create an empty (valid) report
+    pub fn new() -> Self {
+        Self {
+            overall_valid: true,
+            entities_validated: 0,
+            entities_failed: 0,
+            entities: Vec::new(),
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Record the result for `isgl1_key`
+    pub fn add_result(&mut self, isgl1_key: &str, result: &ValidationResult, fail_on_warnings: bool) {
+        let is_valid = result.passes(fail_on_warnings);
+        self.entities_validated += 1;
+        if !is_valid {
+            self.entities_failed += 1;
+            self.overall_valid = false;
+        }
+        self.entities.push(EntityValidation {
+            isgl1_key: isgl1_key.to_string(),
+            is_valid,
+            errors: result.syntax_errors.clone(),
+            warnings: result.warnings.clone(),
+        });
+    }
+}
+
+impl Default for SyntaxValidationReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -34,9 +34,13 @@
 //! ```
 
 use anyhow::{Result, Context};
-use tree_sitter::{Parser, Node};
+use tree_sitter::{Parser, Node, Tree};
 use parseltongue_core::entities::Language;
+use parseltongue_core::text::{LineCol, LineIndex};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
 
 /// Simple syntax validator using tree-sitter
 pub struct SimpleSyntaxValidator {
@@ -81,68 +85,255 @@ impl SimpleSyntaxValidator {
     ///
     /// Returns ValidationResult with is_valid and error details
     pub fn validate_syntax(&mut self, code: &str, language: Language) -> Result<ValidationResult> {
+        let tree = self.parse(code, language)?;
+        Ok(self.check_tree(&tree, code))
+    }
+
+    /// Validate `code`, and when it is valid also return it with whitespace normalized
+    ///
+    /// See [`normalize_whitespace`]. Invalid code is returned unchanged so the
+    /// reported error positions still match it.
+    pub fn validate_and_normalize(&mut self, code: &str, language: Language) -> Result<(ValidationResult, String)> {
+        let tree = self.parse(code, language)?;
+        let result = self.check_tree(&tree, code);
+        let code = if result.is_valid {
+            normalize_whitespace(code, &tree)
+        } else {
+            code.to_string()
+        };
+        Ok((result, code))
+    }
+
+    /// Check that `code` defines the entity its key names
+    ///
+    /// Catches future_code written for the wrong entity: code proposed for
+    /// `foo` that actually defines `bar` is syntactically fine but would
+    /// replace `foo` with a different item. `expected_name` is the key's
+    /// name segment; qualified names (`Foo::bar`, `Foo.bar`) match on their
+    /// last segment, and case is ignored since canonical keys fold it for
+    /// case-insensitive languages. Code whose leading item has no name of
+    /// its own (impl blocks, bare statements) passes.
+    ///
+    /// A mismatch is a warning: the result stays valid unless checked with
+    /// `passes(true)` (`--fail-on-warnings`).
+    pub fn check_name_match(&mut self, code: &str, language: Language, expected_name: &str) -> Result<ValidationResult> {
+        match self.defined_name(code, language)? {
+            Some(defined) if !names_match(expected_name, &defined) => Ok(ValidationResult::valid().with_warning(format!(
+                "Name mismatch: future_code defines `{}` but the key names `{}`",
+                defined, expected_name
+            ))),
+            _ => Ok(ValidationResult::valid()),
+        }
+    }
+
+    /// Name of the first top-level item `code` defines
+    pub fn defined_name(&mut self, code: &str, language: Language) -> Result<Option<String>> {
+        let tree = self.parse(code, language)?;
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let first_item = root
+            .named_children(&mut cursor)
+            .find(|node| !is_preamble(node.kind()));
+        Ok(first_item.and_then(|item| item_name(&item, code, 0)))
+    }
+
+    /// Parse `code` with the parser for `language`
+    fn parse(&mut self, code: &str, language: Language) -> Result<Tree> {
         // Get parser for the specified language
         let parser = self.parsers.get_mut(&language)
             .ok_or_else(|| anyhow::anyhow!("No parser available for language: {:?}", language))?;
 
         // Parse code with tree-sitter
-        let tree = parser
+        parser
             .parse(code, None)
-            .context("Failed to parse code with tree-sitter")?;
+            .context("Failed to parse code with tree-sitter")
+    }
 
+    /// Validation result for a parsed `code`
+    fn check_tree(&self, tree: &Tree, code: &str) -> ValidationResult {
         let root = tree.root_node();
 
         // Check for syntax errors in parse tree
         if root.has_error() {
-            let errors = self.collect_syntax_errors(&root, code);
-            return Ok(ValidationResult {
-                is_valid: false,
-                errors,
-            });
+            let errors = self.collect_syntax_errors(&root, &LineIndex::new(code));
+            return ValidationResult::invalid_syntax(errors);
         }
 
-        Ok(ValidationResult {
-            is_valid: true,
-            errors: vec![],
-        })
+        ValidationResult::valid()
     }
 
     /// Recursively collect syntax errors from parse tree
-    fn collect_syntax_errors(&self, node: &Node, source: &str) -> Vec<String> {
+    fn collect_syntax_errors(&self, node: &Node<'_>, index: &LineIndex<'_>) -> Vec<SyntaxError> {
         let mut errors = Vec::new();
 
         // Check if this node is an error node
         if node.is_error() || node.is_missing() {
-            let line = node.start_position().row + 1;
-            let col = node.start_position().column + 1;
-            let end_line = node.end_position().row + 1;
-            let end_col = node.end_position().column + 1;
-
-            let error_msg = if node.is_missing() {
-                format!(
-                    "Missing syntax element at line {}, column {}",
-                    line, col
-                )
-            } else {
-                format!(
-                    "Syntax error at line {}, column {} (ends at line {}, column {})",
-                    line, col, end_line, end_col
-                )
-            };
+            // Tree-sitter columns are bytes; report characters
+            let position = |byte| index.offset_to_line_col(byte).unwrap_or(LineCol { line: 0, col: 0 });
+            let LineCol { line, col } = position(node.start_byte());
+            let LineCol { line: end_line, col: end_col } = position(node.end_byte());
 
-            errors.push(error_msg);
+            errors.push(SyntaxError {
+                missing: node.is_missing(),
+                line,
+                column: col,
+                end_line,
+                end_column: end_col,
+            });
         }
 
         // Recursively check children
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            errors.extend(self.collect_syntax_errors(&child, source));
+            errors.extend(self.collect_syntax_errors(&child, index));
         }
 
         errors
     }
 }
 
+/// Top-level nodes that precede the item itself
+fn is_preamble(kind: &str) -> bool {
+    kind.contains("comment")
+        || matches!(
+            kind,
+            "attribute_item"
+                | "inner_attribute_item"
+                | "decorator"
+                | "use_declaration"
+                | "import_statement"
+                | "import_from_statement"
+                | "import_declaration"
+                | "package_clause"
+                | "package_declaration"
+        )
+}
+
+/// Declared name of `node`, looking through wrappers such as decorated
+/// definitions, exports and `const f = ...` declarations
+fn item_name(node: &Node<'_>, code: &str, depth: usize) -> Option<String> {
+    if node.kind() == "impl_item" {
+        return None;
+    }
+    if let Some(name) = node.child_by_field_name("name") {
+        return name.utf8_text(code.as_bytes()).ok().map(str::to_string);
+    }
+    if depth >= 2 {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let name = node
+        .named_children(&mut cursor)
+        .filter(|child| !is_preamble(child.kind()))
+        .find_map(|child| item_name(&child, code, depth + 1));
+    name
+}
+
+/// Whether a key's name segment refers to the item named `defined`, ignoring case
+fn names_match(expected: &str, defined: &str) -> bool {
+    let (expected, defined) = (expected.to_lowercase(), defined.to_lowercase());
+    expected == defined
+        || ["::", ".", "#"]
+            .iter()
+            .any(|separator| expected.ends_with(&format!("{}{}", separator, defined)))
+}
+
+/// Normalize the whitespace between the tokens of `tree`
+///
+/// - Tabs in leading indentation expand to 4-column tab stops
+/// - Trailing whitespace is stripped from every line
+/// - The code ends with exactly one line break
+///
+/// Text inside a token is never edited: a multi-line string literal,
+/// comment or heredoc keeps its tabs and trailing spaces. Line endings are
+/// kept as they are (`\r\n` stays `\r\n`), and line breaks, token order and
+/// the indentation depth of space-indented lines are left alone, so the
+/// result parses to the same tree.
+pub fn normalize_whitespace(code: &str, tree: &Tree) -> String {
+    let tokens = token_ranges(tree.root_node());
+    let inside_token = |offset: usize| {
+        let next = tokens.partition_point(|token| token.end <= offset);
+        tokens.get(next).is_some_and(|token| token.start <= offset)
+    };
+    let line_break = if code.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut normalized = String::with_capacity(code.len() + line_break.len());
+    let mut offset = 0;
+    for line in code.trim_end().split_inclusive('\n') {
+        let text = line.trim_end_matches(['\r', '\n']);
+        let body = text.trim_start_matches([' ', '\t']);
+        let indent = &text[..text.len() - body.len()];
+        if inside_token(offset) {
+            normalized.push_str(indent);
+        } else {
+            let mut column = 0;
+            for c in indent.chars() {
+                column = if c == '\t' { column + 4 - column % 4 } else { column + 1 };
+            }
+            normalized.push_str(&" ".repeat(column));
+        }
+
+        let content = body.trim_end_matches([' ', '\t']);
+        normalized.push_str(content);
+        let trailing = offset + indent.len() + content.len();
+        if trailing < offset + text.len() && inside_token(trailing) {
+            normalized.push_str(&body[content.len()..]);
+        }
+        normalized.push_str(&line[text.len()..]);
+        offset += line.len();
+    }
+    normalized.push_str(line_break);
+    normalized
+}
+
+/// Sorted byte ranges whose text belongs to a token
+///
+/// Leaves, plus whole string, comment and heredoc nodes: grammars leave
+/// gaps between those nodes' children that are still literal text.
+fn token_ranges(root: Node<'_>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut pending = vec![root];
+    while let Some(node) = pending.pop() {
+        let kind = node.kind();
+        if node.child_count() == 0 || ["string", "comment", "heredoc", "nowdoc"].iter().any(|k| kind.contains(k)) {
+            ranges.push(node.byte_range());
+        } else {
+            let mut cursor = node.walk();
+            pending.extend(node.children(&mut cursor));
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+/// Position of one tree-sitter error or missing node
+///
+/// Lines and columns are as in `LineIndex` (characters, not bytes).
+/// `Display` gives the message pt04 prints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxError {
+    /// A node the grammar expected but the code lacks, rather than an unparseable one
+    pub missing: bool,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.missing {
+            write!(f, "Missing syntax element at line {}, column {}", self.line, self.column)
+        } else {
+            write!(
+                f,
+                "Syntax error at line {}, column {} (ends at line {}, column {})",
+                self.line, self.column, self.end_line, self.end_column
+            )
+        }
+    }
+}
+
 /// Validation result from syntax check
 #[derive(Debug, Clone)]
 pub struct ValidationResult {
@@ -150,6 +341,10 @@ pub struct ValidationResult {
     pub is_valid: bool,
     /// List of error messages (empty if valid)
     pub errors: Vec<String>,
+    /// Positions behind `errors` when they came from the parser
+    pub syntax_errors: Vec<SyntaxError>,
+    /// Non-blocking findings, such as a name mismatch
+    pub warnings: Vec<String>,
 }
 
 impl ValidationResult {
@@ -158,6 +353,8 @@ impl ValidationResult {
         Self {
             is_valid: true,
             errors: vec![],
+            syntax_errors: vec![],
+            warnings: vec![],
         }
     }
 
@@ -166,8 +363,30 @@ impl ValidationResult {
         Self {
             is_valid: false,
             errors,
+            syntax_errors: vec![],
+            warnings: vec![],
+        }
+    }
+
+    /// Create an invalid result from parser errors, keeping their positions
+    pub fn invalid_syntax(syntax_errors: Vec<SyntaxError>) -> Self {
+        Self {
+            errors: syntax_errors.iter().map(ToString::to_string).collect(),
+            syntax_errors,
+            ..Self::invalid(vec![])
         }
     }
+
+    /// Add a warning to this result
+    pub fn with_warning(mut self, warning: String) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// Whether this result passes; with `fail_on_warnings` warnings fail it too
+    pub fn passes(&self, fail_on_warnings: bool) -> bool {
+        self.is_valid && (!fail_on_warnings || self.warnings.is_empty())
+    }
 }
 
 #[cfg(test)]
@@ -195,6 +414,45 @@ mod tests {
         let result = validator.validate_syntax(code, Language::Rust).unwrap();
         assert!(!result.is_valid);
         assert!(!result.errors.is_empty());
+        assert_eq!(result.errors[0], result.syntax_errors[0].to_string());
+    }
+
+    #[test]
+    fn test_normalize_expands_tabs_and_adds_trailing_newline() {
+        let mut validator = SimpleSyntaxValidator::new().unwrap();
+        let code = "fn main() {\n\tlet x = 1;  \n  \tif x > 0 {\n\t\tprintln!(\"{}\", x);\n\t}\n}";
+        let (result, normalized) = validator.validate_and_normalize(code, Language::Rust).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(
+            normalized,
+            "fn main() {\n    let x = 1;\n    if x > 0 {\n        println!(\"{}\", x);\n    }\n}\n"
+        );
+
+        let (result, unchanged) = validator.validate_and_normalize("fn main( {\t", Language::Rust).unwrap();
+        assert!(!result.is_valid);
+        assert_eq!(unchanged, "fn main( {\t");
+    }
+
+    #[test]
+    fn test_normalize_leaves_string_literals_and_crlf_alone() {
+        let mut validator = SimpleSyntaxValidator::new().unwrap();
+        let code = "fn main() {\r\n\tlet s = \"a\t  \r\n\tb\";  \r\n}\r\n\r\n";
+        let (result, normalized) = validator.validate_and_normalize(code, Language::Rust).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(normalized, "fn main() {\r\n    let s = \"a\t  \r\n\tb\";\r\n}\r\n");
+    }
+
+    #[test]
+    fn test_defined_name_looks_past_attributes_and_wrappers() {
+        let mut validator = SimpleSyntaxValidator::new().unwrap();
+        let name = |validator: &mut SimpleSyntaxValidator, code, language| validator.defined_name(code, language).unwrap();
+        assert_eq!(name(&mut validator, "/// Docs\n#[inline]\npub fn add() {}", Language::Rust).as_deref(), Some("add"));
+        assert_eq!(name(&mut validator, "@cache\ndef load():\n    pass", Language::Python).as_deref(), Some("load"));
+        assert_eq!(name(&mut validator, "export const render = () => 1;", Language::JavaScript).as_deref(), Some("render"));
+        assert_eq!(name(&mut validator, "impl Foo {}", Language::Rust), None);
+        assert!(names_match("Calculator::add", "add"));
+        assert!(names_match("getuser", "GetUser"), "canonical keys fold case");
+        assert!(!names_match("add", "subtract"));
     }
 
     #[test]
@@ -0,0 +1,111 @@
+//! # Incremental Validation
+//!
+//! Every pt04 run records the entities whose future_code passed cleanly
+//! (no errors, no warnings) in a JSON sidecar next to the database, as
+//! `ISGL1 key => SHA-256 of the code`. With `--only-new` an entity whose
+//! code still hashes to its recorded pass is skipped; any edit changes the
+//! hash, so the entity is validated again. Failures are never recorded.
+//!
+//! A pass only stands for the checks that ran, so the sidecar also notes
+//! whether `--check-name-match` was on; loading it for a run with the
+//! other setting yields an empty cache and everything is validated.
+//!
+//! ## Usage
+//! ```rust,ignore
+//! let mut cache = ValidationCache::load(&sidecar, check_name_match);
+//! for (key, code) in &snippets {
+//!     if only_new && cache.is_validated(key, code) {
+//!         continue;
+//!     }
+//!     let result = validator.validate_syntax(code, language)?;
+//!     cache.record(key, code, &result);
+//! }
+//! cache.save(&sidecar)?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::simple_validator::ValidationResult;
+
+/// Suffix appended to the database path to form the sidecar path
+pub const VALIDATION_CACHE_SUFFIX: &str = ".pt04-validated.json";
+
+/// Entities whose current future_code passed validation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationCache {
+    /// Whether the passes include the `--check-name-match` check
+    pub check_name_match: bool,
+    /// Hex SHA-256 of the code that passed, keyed by ISGL1 key
+    pub passed: BTreeMap<String, String>,
+}
+
+impl ValidationCache {
+    pub fn new(check_name_match: bool) -> Self {
+        Self {
+            check_name_match,
+            passed: BTreeMap::new(),
+        }
+    }
+
+    /// Sidecar location for a database connection string
+    ///
+    /// Returns `None` for in-memory databases, whose entities do not
+    /// outlive the run.
+    pub fn sidecar_path_for_db(db_path: &str) -> Option<PathBuf> {
+        let location = db_path.split_once(':').map_or(db_path, |(_, rest)| rest);
+        if db_path == "mem" || location.is_empty() {
+            return None;
+        }
+        Some(PathBuf::from(format!("{}{}", location, VALIDATION_CACHE_SUFFIX)))
+    }
+
+    /// Load the passes recorded with the same `check_name_match` setting
+    ///
+    /// A missing, unreadable or differently configured sidecar yields an
+    /// empty cache, so everything is validated.
+    pub fn load(path: &Path, check_name_match: bool) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(|cache| cache.check_name_match == check_name_match)
+            .unwrap_or_else(|| Self::new(check_name_match))
+    }
+
+    /// Persist atomically (write to a temp file, then rename)
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, path)
+    }
+
+    /// Whether `code` is exactly what passed last time for `isgl1_key`
+    pub fn is_validated(&self, isgl1_key: &str, code: &str) -> bool {
+        self.passed.get(isgl1_key).is_some_and(|hash| *hash == code_hash(code))
+    }
+
+    /// Remember a clean pass of `code`, or forget the entity otherwise
+    pub fn record(&mut self, isgl1_key: &str, code: &str, result: &ValidationResult) {
+        if result.is_valid && result.warnings.is_empty() {
+            self.passed.insert(isgl1_key.to_string(), code_hash(code));
+        } else {
+            self.passed.remove(isgl1_key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.passed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passed.is_empty()
+    }
+}
+
+/// Hex-encoded SHA-256 of `code` (pure function)
+fn code_hash(code: &str) -> String {
+    format!("{:x}", Sha256::digest(code.as_bytes()))
+}
@@ -0,0 +1,66 @@
+//! # Batch Validation Tests
+//!
+//! `validate_batch` must give exactly the sequential results, in input order.
+
+use parseltongue_core::entities::Language;
+use pt04_syntax_preflight_validator::{validate_batch, validate_batch_with_workers, SimpleSyntaxValidator};
+
+fn snippets() -> Vec<(String, String, Language)> {
+    let mut snippets = Vec::new();
+    for i in 0..40 {
+        let (code, language) = match i % 4 {
+            0 => (format!("fn valid_{}() -> u32 {{ {} }}", i, i), Language::Rust),
+            1 => (format!("fn broken_{}( {{", i), Language::Rust),
+            2 => (format!("def valid_{}():\n    return {}\n", i, i), Language::Python),
+            _ => (format!("function broken_{}() {{ return ; ]", i), Language::JavaScript),
+        };
+        snippets.push((format!("entity_{}", i), code, language));
+    }
+    // No parser for Kotlin: reported per snippet, not as a batch failure
+    snippets.push(("kotlin_entity".to_string(), "fun main() {}".to_string(), Language::Kotlin));
+    snippets
+}
+
+/// Batch results match sequential results for the same inputs
+#[tokio::test]
+async fn test_batch_matches_sequential() {
+    let snippets = snippets();
+
+    let mut validator = SimpleSyntaxValidator::new().unwrap();
+    let sequential: Vec<(String, bool, Vec<String>)> = snippets
+        .iter()
+        .map(|(key, code, language)| match validator.validate_syntax(code, *language) {
+            Ok(result) => (key.clone(), result.is_valid, result.errors),
+            Err(e) => (key.clone(), false, vec![e.to_string()]),
+        })
+        .collect();
+
+    for batch in [validate_batch(&snippets).await, validate_batch_with_workers(&snippets, 3).await] {
+        let batch: Vec<(String, bool, Vec<String>)> = batch
+            .into_iter()
+            .map(|(key, result)| (key, result.is_valid, result.errors))
+            .collect();
+        assert_eq!(batch, sequential);
+    }
+
+    // Sanity: the inputs exercise both outcomes
+    assert!(sequential.iter().any(|(_, valid, _)| *valid));
+    assert!(sequential.iter().any(|(_, valid, _)| !*valid));
+}
+
+/// Each result stays with its own key
+#[tokio::test]
+async fn test_batch_preserves_key_association() {
+    let snippets = snippets();
+    let results = validate_batch_with_workers(&snippets, 8).await;
+
+    assert_eq!(results.len(), snippets.len());
+    for ((key, _, _), (result_key, result)) in snippets.iter().zip(&results) {
+        assert_eq!(key, result_key);
+        if key.starts_with("entity_") {
+            let index: usize = key["entity_".len()..].parse().unwrap();
+            assert_eq!(result.is_valid, index % 2 == 0, "{}", key);
+        }
+    }
+    assert!(validate_batch(&[]).await.is_empty());
+}
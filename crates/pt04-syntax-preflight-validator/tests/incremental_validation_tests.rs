@@ -0,0 +1,65 @@
+//! Incremental validation (`--only-new`)
+//!
+//! Clean passes are recorded in a sidecar keyed by (ISGL1 key, code hash);
+//! a later run skips them until the code changes.
+
+use parseltongue_core::entities::Language;
+use pt04_syntax_preflight_validator::{SimpleSyntaxValidator, ValidationCache};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// One `--only-new` run over `snippets`: returns the keys it validated
+fn run(sidecar: &Path, snippets: &[(&str, &str)]) -> Vec<String> {
+    let mut cache = ValidationCache::load(sidecar, false);
+    let mut validator = SimpleSyntaxValidator::new().unwrap();
+    let mut validated = Vec::new();
+    for (key, code) in snippets {
+        if cache.is_validated(key, code) {
+            continue;
+        }
+        let result = validator.validate_syntax(code, Language::Rust).unwrap();
+        cache.record(key, code, &result);
+        validated.push(key.to_string());
+    }
+    cache.save(sidecar).unwrap();
+    validated
+}
+
+#[test]
+fn test_second_run_skips_unchanged_and_revalidates_edits() {
+    let dir = TempDir::new().unwrap();
+    let sidecar = ValidationCache::sidecar_path_for_db(&format!("rocksdb:{}", dir.path().join("db").display())).unwrap();
+
+    let mut snippets = vec![
+        ("rust:fn:alpha:src_lib_rs:1-1", "fn alpha() -> u8 { 1 }"),
+        ("rust:fn:beta:src_lib_rs:3-3", "fn beta() -> u8 { 2 }"),
+        ("rust:fn:gamma:src_lib_rs:5-5", "fn gamma() -> u8 { 3 }"),
+    ];
+    assert_eq!(run(&sidecar, &snippets).len(), 3);
+    assert!(run(&sidecar, &snippets).is_empty(), "nothing changed, nothing validated");
+
+    snippets[1].1 = "fn beta() -> u8 { 20 }";
+    assert_eq!(run(&sidecar, &snippets), ["rust:fn:beta:src_lib_rs:3-3"]);
+    assert!(run(&sidecar, &snippets).is_empty());
+}
+
+#[test]
+fn test_failures_are_validated_every_run() {
+    let dir = TempDir::new().unwrap();
+    let sidecar = dir.path().join("db.pt04-validated.json");
+    let snippets = [("rust:fn:broken:src_lib_rs:1-1", "fn broken( {")];
+
+    assert_eq!(run(&sidecar, &snippets).len(), 1);
+    assert_eq!(run(&sidecar, &snippets).len(), 1, "a failure is never recorded as passed");
+}
+
+#[test]
+fn test_cache_from_other_checks_is_ignored() {
+    let dir = TempDir::new().unwrap();
+    let sidecar = dir.path().join("db.pt04-validated.json");
+    run(&sidecar, &[("rust:fn:alpha:src_lib_rs:1-1", "fn alpha() {}")]);
+
+    assert_eq!(ValidationCache::load(&sidecar, false).len(), 1);
+    assert!(ValidationCache::load(&sidecar, true).is_empty(), "passes without --check-name-match don't count with it");
+    assert_eq!(ValidationCache::sidecar_path_for_db("mem"), None);
+}
@@ -152,3 +152,78 @@ fn test_import_error_passes_syntax_check() {
         "Import errors should pass syntax validation (cargo catches these)"
     );
 }
+
+/// Test 9: Error columns count characters, not bytes
+#[test]
+fn test_error_column_counts_multibyte_characters() {
+    let mut validator = SimpleSyntaxValidator::new().expect("Failed to create validator");
+
+    // Each 'é' is two bytes; the stray `$` is character 16 but byte 19
+    let code = "fn f() { \"ééé\" $ }";
+
+    let result = validator.validate_syntax(code, Language::Rust).expect("Validation failed");
+    assert!(!result.is_valid);
+    assert!(
+        result.errors.iter().any(|e| e.contains("line 1, column 16")),
+        "{:?}",
+        result.errors
+    );
+}
+
+/// Test 10: future_code defining a differently-named function is flagged
+#[test]
+fn test_name_mismatch_is_flagged() {
+    let mut validator = SimpleSyntaxValidator::new().expect("Failed to create validator");
+
+    let code = "fn subtract(a: i32, b: i32) -> i32 {\n    a - b\n}";
+
+    let result = validator.validate_syntax(code, Language::Rust).expect("Validation failed");
+    assert!(result.is_valid, "The code itself parses");
+
+    let result = validator.check_name_match(code, Language::Rust, "add").expect("Check failed");
+    assert_eq!(result.warnings.len(), 1, "Code for `add` that defines `subtract` must be flagged");
+    assert!(result.warnings[0].contains("`subtract`") && result.warnings[0].contains("`add`"), "{:?}", result.warnings);
+
+    let result = validator.check_name_match(code, Language::Rust, "Calculator::subtract").expect("Check failed");
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+}
+
+/// Test 11: A name mismatch warning fails only under --fail-on-warnings
+#[test]
+fn test_name_mismatch_fails_on_warnings() {
+    let mut validator = SimpleSyntaxValidator::new().expect("Failed to create validator");
+
+    let result = validator
+        .check_name_match("fn subtract() {}", Language::Rust, "add")
+        .expect("Check failed");
+    assert!(result.passes(false), "A warning alone does not fail validation");
+    assert!(!result.passes(true), "Under --fail-on-warnings the mismatch fails");
+
+    let matching = validator.check_name_match("fn add() {}", Language::Rust, "add").expect("Check failed");
+    assert!(matching.passes(true));
+}
+
+/// Test 12: The JSON report round-trips and records each entity's outcome
+#[test]
+fn test_json_report_reflects_mixed_results() {
+    use pt04_syntax_preflight_validator::SyntaxValidationReport;
+
+    let mut validator = SimpleSyntaxValidator::new().expect("Failed to create validator");
+    let mut report = SyntaxValidationReport::new();
+    let valid = validator.validate_syntax("fn add() {}", Language::Rust).expect("Validation failed");
+    report.add_result("rust:fn:add:src_lib_rs:1-1", &valid, false);
+    let invalid = validator.validate_syntax("fn broken( {\n}", Language::Rust).expect("Validation failed");
+    report.add_result("rust:fn:broken:src_lib_rs:3-4", &invalid, false);
+
+    let json = serde_json::to_string(&report).expect("Report serializes");
+    let parsed: SyntaxValidationReport = serde_json::from_str(&json).expect("Report deserializes");
+
+    assert!(!parsed.overall_valid);
+    assert_eq!((parsed.entities_validated, parsed.entities_failed), (2, 1));
+    assert!(parsed.entities[0].is_valid && parsed.entities[0].errors.is_empty());
+    let broken = &parsed.entities[1];
+    assert_eq!(broken.isgl1_key, "rust:fn:broken:src_lib_rs:3-4");
+    assert!(!broken.is_valid);
+    assert!(!broken.errors.is_empty());
+    assert!(broken.errors[0].line >= 1, "{:?}", broken.errors);
+}
@@ -17,6 +17,19 @@ pub struct Cli {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Finish the writes recorded by an interrupted run's journal
+    #[arg(long)]
+    pub recover: bool,
+
+    /// Before writing, verify each edited or deleted file still holds the
+    /// graph's current_code; abort on drift
+    #[arg(long)]
+    pub check: bool,
+
+    /// With --check: write even when files drifted
+    #[arg(long, requires = "check")]
+    pub force: bool,
+
     /// Enable verbose output
     #[arg(short, long)]
     pub verbose: bool,
@@ -39,10 +52,35 @@ mod tests {
             database: PathBuf::from("./parseltongue.db"),
             root: PathBuf::from("./project"),
             dry_run: false,
+            recover: false,
+            check: false,
+            force: false,
             verbose: false,
         };
 
         assert_eq!(cli.database, PathBuf::from("./parseltongue.db"));
         assert_eq!(cli.root, PathBuf::from("./project"));
     }
+
+    #[test]
+    fn test_recover_flag() {
+        let cli = Cli::try_parse_from([
+            "pt05",
+            "--database",
+            "parseltongue.db",
+            "--root",
+            ".",
+            "--recover",
+        ])
+        .unwrap();
+        assert!(cli.recover);
+    }
+
+    #[test]
+    fn test_force_requires_check() {
+        let base = ["pt05", "--database", "parseltongue.db", "--root", "."];
+        assert!(Cli::try_parse_from(base.iter().chain(&["--force"])).is_err());
+        let cli = Cli::try_parse_from(base.iter().chain(&["--check", "--force"])).unwrap();
+        assert!(cli.check && cli.force);
+    }
 }
@@ -19,6 +19,7 @@
 //! | CREATE    | None         | Some        | None       | Entity doesn't exist yet, use hash-based key |
 //! | EDIT      | Some         | Some        | Some       | Need both before/after, precise line location |
 //! | DELETE    | Some         | None        | Some       | Show what's being removed, location to delete |
+//! | MOVE      | Some         | Some        | Some       | A DELETE + CREATE pair with the same body (`--detect-moves`); `moved_to` names the new file |
 //!
 //! This table drives the pattern matching in `entity_to_change()`.
 //!
@@ -38,44 +39,182 @@
 //! Example: `src_lib_rs` → "src/lib" + ".rs" → "src/lib.rs"
 
 use anyhow::{Context, Result};
+use futures::stream::{self, Stream, TryStreamExt};
 use parseltongue_core::entities::{CodeEntity, TemporalAction};
+use parseltongue_core::isgl1_key::{parse_key, KeyFormat};
 use parseltongue_core::storage::CozoDbStorage;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::diff_types::{Change, CodeDiff, LineRange, Operation};
+use crate::git_patch::render_git_patch;
 
 /// Diff generator that reads from CozoDB (with dependency injection)
 pub struct DiffGenerator {
     storage: Arc<CozoDbStorage>,
+    source_root: PathBuf,
+    poll_interval: Duration,
+    detect_moves: bool,
+}
+
+/// Progress of a `stream_new_changes` follower
+struct FollowState {
+    /// Changes pending at the last poll, by ISGL1 key
+    seen: HashMap<String, Change>,
+    /// Storage revision of the last poll; `None` before the baseline
+    revision: Option<u64>,
+    /// New changes found but not yet yielded
+    queued: VecDeque<Change>,
 }
 
 impl DiffGenerator {
     /// Create a new diff generator (dependency injection pattern)
+    ///
+    /// File paths in ISGL1 keys are resolved against the current directory;
+    /// use `with_source_root` when the indexed project lives elsewhere.
     pub fn new(storage: Arc<CozoDbStorage>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            source_root: PathBuf::from("."),
+            poll_interval: Duration::from_millis(500),
+            detect_moves: false,
+        }
+    }
+
+    /// Resolve entity file paths against `root` when reading original sources
+    pub fn with_source_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.source_root = root.into();
+        self
+    }
+
+    /// How often `stream_new_changes` checks for new writes (default 500ms)
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Collapse Delete + Create pairs with the same body into Moves (`--detect-moves`)
+    pub fn with_move_detection(mut self, detect_moves: bool) -> Self {
+        self.detect_moves = detect_moves;
+        self
     }
 
     /// Generate CodeDiff from all entities with future_action
+    ///
+    /// `generated_at` comes from the storage's clock.
     pub async fn generate_diff(&self) -> Result<CodeDiff> {
-        // Get all changed entities from CozoDB
-        let changed_entities = self
-            .storage
-            .get_changed_entities()
-            .await
-            .context("Failed to get changed entities from CozoDB")?;
+        // Stream changed entities from CozoDB rather than loading them at once
+        let mut changed_entities = std::pin::pin!(self.storage.changed_entities_stream());
 
-        let mut diff = CodeDiff::new();
+        let generated_at = self.storage.clock().now();
+        let mut diff = CodeDiff::at(generated_at);
 
-        for entity in changed_entities {
+        while let Some(entity) = changed_entities
+            .try_next()
+            .await
+            .context("Failed to get changed entities from CozoDB")?
+        {
             if let Some(change) = self.entity_to_change(&entity)? {
                 diff.add_change(change);
             }
         }
 
+        if self.detect_moves {
+            let changes = collapse_moves(std::mem::take(&mut diff.changes));
+            diff = CodeDiff::at(generated_at);
+            for change in changes {
+                diff.add_change(change);
+            }
+        }
+
         Ok(diff)
     }
 
+    /// Generate a `git apply`-compatible patch for all changed entities
+    ///
+    /// Edits and Deletes become hunks against the original files under the
+    /// source root (context lines are read from disk); Creates become
+    /// new-file hunks, or are appended when the target file already exists.
+    /// Entities lacking the code or line range their operation needs are
+    /// skipped. All files are concatenated into one patch.
+    pub async fn generate_git_patch(&self) -> Result<String> {
+        let diff = self.generate_diff().await?;
+
+        let mut originals = BTreeMap::new();
+        for change in &diff.changes {
+            if originals.contains_key(&change.file_path) {
+                continue;
+            }
+            let full_path = self.source_root.join(&change.file_path);
+            let content = match std::fs::read_to_string(&full_path) {
+                Ok(content) => Some(content),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("Failed to read original source {}", full_path.display())
+                    })
+                }
+            };
+            originals.insert(change.file_path.clone(), content);
+        }
+
+        render_git_patch(&diff, &originals)
+    }
+
+    /// Follow the database and yield each change as it is written
+    ///
+    /// The changes already pending when the stream is first polled are the
+    /// baseline and are not yielded. After that, every poll interval the
+    /// storage revision is checked; when it has moved, the pending changes
+    /// are re-read and those that are new, or differ from what was last
+    /// seen for their key, are yielded.
+    ///
+    /// Works in-process only: the revision counts writes made through this
+    /// `CozoDbStorage` handle, so writes from another process (or another
+    /// handle on the same database) are never seen. Share the storage `Arc`
+    /// with the writer to follow it.
+    ///
+    /// The stream never ends on its own. If the database can no longer be
+    /// read it yields that error and ends.
+    pub fn stream_new_changes(&self) -> impl Stream<Item = Result<Change>> + '_ {
+        let state = FollowState {
+            seen: HashMap::new(),
+            revision: None,
+            queued: VecDeque::new(),
+        };
+        stream::unfold(Some(state), move |state| async move {
+            let mut state = state?;
+            loop {
+                if let Some(change) = state.queued.pop_front() {
+                    return Some((Ok(change), Some(state)));
+                }
+                if state.revision.is_some() {
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+
+                let revision = self.storage.revision();
+                if state.revision == Some(revision) {
+                    continue;
+                }
+                let diff = match self.generate_diff().await {
+                    Ok(diff) => diff,
+                    Err(e) => return Some((Err(e), None)),
+                };
+                let mut seen = HashMap::with_capacity(diff.changes.len());
+                for change in diff.changes {
+                    if state.revision.is_some() && state.seen.get(&change.isgl1_key) != Some(&change) {
+                        state.queued.push_back(change.clone());
+                    }
+                    seen.insert(change.isgl1_key.clone(), change);
+                }
+                state.seen = seen;
+                state.revision = Some(revision);
+            }
+        })
+    }
+
     /// Convert CodeEntity to Change (with enhanced fields)
     fn entity_to_change(&self, entity: &CodeEntity) -> Result<Option<Change>> {
         // Determine operation from temporal state's future_action
@@ -97,14 +236,14 @@ impl DiffGenerator {
         // - EDIT/DELETE: Some (need to know what to replace/remove)
         let current_code = match operation {
             Operation::Create => None,
-            Operation::Edit | Operation::Delete => entity.current_code.clone(),
+            Operation::Edit | Operation::Delete | Operation::Move { .. } => entity.current_code.clone(),
         };
 
         // Extract future_code based on operation:
         // - CREATE/EDIT: Some (what to write)
         // - DELETE: None (removing code)
         let future_code = match operation {
-            Operation::Create | Operation::Edit => entity.future_code.clone(),
+            Operation::Create | Operation::Edit | Operation::Move { .. } => entity.future_code.clone(),
             Operation::Delete => None,
         };
 
@@ -124,6 +263,7 @@ impl DiffGenerator {
             future_code,
             line_range,
             interface_signature,
+            moved_to: None,
         };
 
         Ok(Some(change))
@@ -132,31 +272,24 @@ impl DiffGenerator {
     /// Extract file path from ISGL1 key
     fn extract_file_path(&self, isgl1_key: &str) -> Result<PathBuf> {
         // ISGL1 key formats:
-        // - Line-based: "rust:fn:name:src_lib_rs:42-56"
+        // - Line-based, either `KeyFormat`: "rust:fn:name:src_lib_rs:42-56"
+        //   or "src_lib_rs-name-fn-rust-42-56"
         // - Hash-based: "src_lib_rs-new_feature-fn-abc12345"
         //
         // Sanitized paths encode "src/lib.rs" as "src_lib_rs"
         // The "_rs" suffix represents the ".rs" extension
         // Other underscores represent directory separators
 
-        if isgl1_key.contains(':') {
-            // Line-based format
-            let parts: Vec<&str> = isgl1_key.split(':').collect();
-            if parts.len() >= 4 {
-                let sanitized_path = parts[3]; // "src_lib_rs"
-                return Ok(self.desanitize_path(sanitized_path));
-            }
-        } else {
-            // Hash-based format
-            let parts: Vec<&str> = isgl1_key.split('-').collect();
-            if !parts.is_empty() {
-                // First part is sanitized file path
-                let sanitized_path = parts[0];
-                return Ok(self.desanitize_path(sanitized_path));
+        if let Ok(components) = parse_key(isgl1_key) {
+            return Ok(self.desanitize_path(&components.file_path));
+        }
+        // Hash-based format: the sanitized file path comes first
+        match isgl1_key.split_once('-') {
+            Some((sanitized_path, _)) if KeyFormat::of(isgl1_key) == KeyFormat::DashSimple => {
+                Ok(self.desanitize_path(sanitized_path))
             }
+            _ => anyhow::bail!("Invalid ISGL1 key format: {}", isgl1_key),
         }
-
-        anyhow::bail!("Invalid ISGL1 key format: {}", isgl1_key)
     }
 
     /// Desanitize file path from ISGL1 key format
@@ -184,30 +317,77 @@ impl DiffGenerator {
 
     /// Extract line range from ISGL1 key (returns None for hash-based keys)
     fn extract_line_range(&self, isgl1_key: &str) -> Option<LineRange> {
-        // Only line-based keys have line ranges: "rust:fn:name:src_lib_rs:42-56"
-        // Hash-based keys do not: "src_lib_rs-new_feature-fn-abc12345"
+        let (start, end) = parse_key(isgl1_key).ok()?.line_range;
+        Some(LineRange { start: start as u32, end: end as u32 })
+    }
+}
 
-        if !isgl1_key.contains(':') {
-            return None; // Hash-based key
+/// Pair each Delete with a Create of the same body and merge them into a Move
+///
+/// Bodies match when they are equal after collapsing whitespace, so a move
+/// that also re-indents is still found. Each Create absorbs at most one
+/// Delete; the Move takes the Delete's place in the change order.
+fn collapse_moves(changes: Vec<Change>) -> Vec<Change> {
+    let mut creates: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (idx, change) in changes.iter().enumerate() {
+        if let (Operation::Create, Some(code)) = (&change.operation, &change.future_code) {
+            creates.entry(normalized_body(code)).or_default().push_back(idx);
         }
+    }
 
-        // Line-based format: last part is "start-end"
-        let parts: Vec<&str> = isgl1_key.split(':').collect();
-        if parts.len() < 5 {
-            return None;
+    let mut paired: HashMap<usize, usize> = HashMap::new();
+    for (idx, change) in changes.iter().enumerate() {
+        if let (Operation::Delete, Some(code)) = (&change.operation, &change.current_code) {
+            if let Some(create) = creates.get_mut(&normalized_body(code)).and_then(VecDeque::pop_front) {
+                paired.insert(idx, create);
+            }
         }
+    }
+    if paired.is_empty() {
+        return changes;
+    }
 
-        let line_part = parts[4]; // "42-56"
-        let line_nums: Vec<&str> = line_part.split('-').collect();
-        if line_nums.len() != 2 {
-            return None;
+    let absorbed: std::collections::HashSet<usize> = paired.values().copied().collect();
+    let mut collapsed = Vec::with_capacity(changes.len() - paired.len());
+    for (idx, change) in changes.iter().enumerate() {
+        if absorbed.contains(&idx) {
+            continue;
         }
+        let Some(&create_idx) = paired.get(&idx) else {
+            collapsed.push(change.clone());
+            continue;
+        };
+        let create = &changes[create_idx];
+        let old = change.current_code.as_deref().unwrap_or_default();
+        let new = create.future_code.as_deref().unwrap_or_default();
+        collapsed.push(Change {
+            operation: Operation::Move {
+                similarity: line_similarity(old, new),
+            },
+            future_code: create.future_code.clone(),
+            moved_to: Some(create.file_path.clone()),
+            ..change.clone()
+        });
+    }
+    collapsed
+}
 
-        let start = line_nums[0].parse::<u32>().ok()?;
-        let end = line_nums[1].parse::<u32>().ok()?;
+/// Code with every whitespace run collapsed to one space
+fn normalized_body(code: &str) -> String {
+    code.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-        Some(LineRange { start, end })
+/// Percentage of line positions whose text is identical in `old` and `new`,
+/// rounded down so only identical bodies score 100
+fn line_similarity(old: &str, new: &str) -> u8 {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let total = old_lines.len().max(new_lines.len());
+    if total == 0 {
+        return 100;
     }
+    let same = old_lines.iter().zip(&new_lines).filter(|(a, b)| a == b).count();
+    (same * 100 / total) as u8
 }
 
 // Unit tests for extract_file_path and extract_line_range are covered by integration tests
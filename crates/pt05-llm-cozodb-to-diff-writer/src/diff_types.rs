@@ -48,9 +48,10 @@
 //! The `DiffMetadata` struct provides summary statistics that enable:
 //! - Pre-flight validation (e.g., "Does this diff have more than 50 changes?")
 //! - Audit trails (generated_at timestamp)
-//! - Operation breakdowns (create_count, edit_count, delete_count)
+//! - Operation breakdowns (create_count, edit_count, delete_count, move_count)
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// CodeDiff.json root structure
@@ -63,6 +64,20 @@ pub struct CodeDiff {
     pub metadata: DiffMetadata,
 }
 
+/// CodeDiff.json with changes grouped per file (`--group-by-file`)
+///
+/// Files are keyed by path in sorted order. Within a file, changes are
+/// ordered by start line; changes without a line range (appended creates)
+/// come last, in their original order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroupedCodeDiff {
+    /// Changes to apply, per file path
+    pub files: BTreeMap<PathBuf, Vec<Change>>,
+
+    /// Metadata about the diff generation
+    pub metadata: DiffMetadata,
+}
+
 /// A single change to apply
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Change {
@@ -88,6 +103,11 @@ pub struct Change {
 
     /// Interface signature for reference
     pub interface_signature: String,
+
+    /// File a Move puts the entity in (`file_path` is the one it leaves);
+    /// None for every other operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<PathBuf>,
 }
 
 /// Line range in source file
@@ -109,6 +129,15 @@ pub enum Operation {
     Edit,
     /// Delete a file/entity
     Delete,
+    /// Delete + Create of the same body, collapsed by `--detect-moves`
+    ///
+    /// The change keeps the deleted entity's key, `file_path`, `line_range`
+    /// and `current_code` (what to remove) and takes the created entity's
+    /// `future_code` and file as `moved_to` (what to add where).
+    Move {
+        /// Percentage of lines carried over verbatim (100 = byte-identical body)
+        similarity: u8,
+    },
 }
 
 /// Metadata about diff generation
@@ -121,6 +150,8 @@ pub struct DiffMetadata {
     pub create_count: usize,
     pub edit_count: usize,
     pub delete_count: usize,
+    #[serde(default)]
+    pub move_count: usize,
 
     /// Generation timestamp (ISO 8601)
     pub generated_at: String,
@@ -129,6 +160,11 @@ pub struct DiffMetadata {
 impl CodeDiff {
     /// Create a new empty CodeDiff
     pub fn new() -> Self {
+        Self::at(chrono::Utc::now())
+    }
+
+    /// Create a new empty CodeDiff generated at `generated_at`
+    pub fn at(generated_at: chrono::DateTime<chrono::Utc>) -> Self {
         Self {
             changes: Vec::new(),
             metadata: DiffMetadata {
@@ -136,7 +172,8 @@ impl CodeDiff {
                 create_count: 0,
                 edit_count: 0,
                 delete_count: 0,
-                generated_at: chrono::Utc::now().to_rfc3339(),
+                move_count: 0,
+                generated_at: generated_at.to_rfc3339(),
             },
         }
     }
@@ -147,6 +184,7 @@ impl CodeDiff {
             Operation::Create => self.metadata.create_count += 1,
             Operation::Edit => self.metadata.edit_count += 1,
             Operation::Delete => self.metadata.delete_count += 1,
+            Operation::Move { .. } => self.metadata.move_count += 1,
         }
         self.metadata.total_changes += 1;
         self.changes.push(change);
@@ -156,6 +194,38 @@ impl CodeDiff {
     pub fn to_json_pretty(&self) -> serde_json::Result<String> {
         serde_json::to_string_pretty(self)
     }
+
+    /// Convert to single-line JSON (for machine consumers, `--compact`)
+    pub fn to_json_compact(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Same changes and metadata, grouped per file and ordered by line
+    pub fn group_by_file(&self) -> GroupedCodeDiff {
+        let mut files: BTreeMap<PathBuf, Vec<Change>> = BTreeMap::new();
+        for change in &self.changes {
+            files.entry(change.file_path.clone()).or_default().push(change.clone());
+        }
+        for changes in files.values_mut() {
+            changes.sort_by_key(|c| (c.line_range.is_none(), c.line_range.map(|r| r.start)));
+        }
+        GroupedCodeDiff {
+            files,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl GroupedCodeDiff {
+    /// Convert to pretty-printed JSON
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Convert to single-line JSON (for machine consumers, `--compact`)
+    pub fn to_json_compact(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
 }
 
 impl Default for CodeDiff {
@@ -187,6 +257,7 @@ mod tests {
             future_code: Some("fn test() {}".to_string()),
             line_range: None, // Hash-based keys have no line range
             interface_signature: "fn test()".to_string(),
+            moved_to: None,
         };
 
         diff.add_change(change);
@@ -211,6 +282,7 @@ mod tests {
             future_code: Some("fn new() {}".to_string()),
             line_range: None,
             interface_signature: "fn new()".to_string(),
+            moved_to: None,
         });
 
         // Add edit
@@ -222,6 +294,7 @@ mod tests {
             future_code: Some("fn updated() {}".to_string()),
             line_range: Some(LineRange { start: 10, end: 20 }),
             interface_signature: "fn updated()".to_string(),
+            moved_to: None,
         });
 
         // Add delete
@@ -233,6 +306,7 @@ mod tests {
             future_code: None,
             line_range: Some(LineRange { start: 30, end: 40 }),
             interface_signature: "fn gone()".to_string(),
+            moved_to: None,
         });
 
         assert_eq!(diff.metadata.total_changes, 3);
@@ -253,6 +327,7 @@ mod tests {
             future_code: Some("fn test() {}".to_string()),
             line_range: None,
             interface_signature: "fn test()".to_string(),
+            moved_to: None,
         });
 
         let json = diff.to_json_pretty().expect("JSON serialization failed");
@@ -264,4 +339,60 @@ mod tests {
         assert!(json.contains("\"future_code\""));
         assert!(json.contains("\"line_range\""));
     }
+
+    #[test]
+    fn test_json_compact_round_trips() {
+        let mut diff = CodeDiff::new();
+        diff.add_change(Change {
+            isgl1_key: "test-key".to_string(),
+            file_path: PathBuf::from("src/test.rs"),
+            operation: Operation::Edit,
+            current_code: Some("fn test() {}".to_string()),
+            future_code: Some("fn test() {\n    todo!()\n}".to_string()),
+            line_range: Some(LineRange { start: 1, end: 1 }),
+            interface_signature: "fn test()".to_string(),
+            moved_to: None,
+        });
+
+        let compact = diff.to_json_compact().expect("JSON serialization failed");
+        let pretty = diff.to_json_pretty().expect("JSON serialization failed");
+        assert!(!compact.contains('\n'));
+        assert!(compact.len() < pretty.len());
+
+        let reparsed: CodeDiff = serde_json::from_str(&compact).unwrap();
+        assert_eq!(reparsed, diff);
+    }
+
+    #[test]
+    fn test_group_by_file_orders_changes_by_line() {
+        let change = |file: &str, key: &str, range: Option<(u32, u32)>| Change {
+            isgl1_key: key.to_string(),
+            file_path: PathBuf::from(file),
+            operation: if range.is_some() { Operation::Edit } else { Operation::Create },
+            current_code: range.map(|_| "fn old() {}".to_string()),
+            future_code: Some("fn new() {}".to_string()),
+            line_range: range.map(|(start, end)| LineRange { start, end }),
+            interface_signature: format!("fn {}()", key),
+            moved_to: None,
+        };
+        let mut diff = CodeDiff::new();
+        diff.add_change(change("src/b.rs", "b-late", Some((40, 45))));
+        diff.add_change(change("src/a.rs", "a-new", None));
+        diff.add_change(change("src/b.rs", "b-early", Some((3, 9))));
+        diff.add_change(change("src/a.rs", "a-mid", Some((12, 14))));
+
+        let grouped = diff.group_by_file();
+
+        let keys = |file: &str| -> Vec<&str> {
+            grouped.files[&PathBuf::from(file)].iter().map(|c| c.isgl1_key.as_str()).collect()
+        };
+        assert_eq!(grouped.files.len(), 2);
+        assert_eq!(keys("src/a.rs"), ["a-mid", "a-new"]);
+        assert_eq!(keys("src/b.rs"), ["b-early", "b-late"]);
+        assert_eq!(grouped.metadata, diff.metadata);
+
+        let json: serde_json::Value = serde_json::from_str(&grouped.to_json_pretty().unwrap()).unwrap();
+        assert_eq!(json["files"]["src/b.rs"][0]["isgl1_key"], "b-early");
+        assert!(json.get("changes").is_none());
+    }
 }
@@ -24,6 +24,18 @@ pub enum FileWriterError {
 
     #[error("Database error: {0}")]
     Database(String),
+
+    #[error("Incomplete write journal at {path}: a previous run was interrupted, rerun with --recover")]
+    IncompleteJournal { path: PathBuf },
+
+    #[error("Write journal error: {details}")]
+    Journal { details: String },
+
+    #[error("Line range {start}-{end} of {key} is stale in {path}; re-ingest before writing")]
+    StaleLineRange { key: String, path: PathBuf, start: usize, end: usize },
+
+    #[error("Files changed outside the graph since ingestion: {}; re-ingest or rerun with --force", paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
+    Drift { paths: Vec<PathBuf> },
 }
 
 impl FileWriterError {
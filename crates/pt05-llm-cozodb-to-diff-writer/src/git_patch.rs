@@ -0,0 +1,450 @@
+//! # Git Patch Rendering
+//!
+//! Turns a [`CodeDiff`] into a unified diff that `git apply` accepts.
+//!
+//! ## Hunk Construction
+//!
+//! Each patchable change maps to a line region of the original file:
+//!
+//! | Operation | Region replaced                  | Requires                                  |
+//! |-----------|----------------------------------|-------------------------------------------|
+//! | EDIT      | `line_range` → `future_code`     | current_code, future_code, line_range     |
+//! | DELETE    | `line_range` → nothing           | current_code, line_range                  |
+//! | CREATE    | new file (or appended at EOF)    | future_code                               |
+//!
+//! A MOVE is rendered as the DELETE from its source file plus the CREATE in
+//! its destination file.
+//!
+//! Changes missing any required field are skipped. Regions within one file are
+//! merged into hunks with [`CONTEXT_LINES`] lines of context, exactly like
+//! `git diff`, because `git apply` refuses context-free hunks mid-file.
+//!
+//! The original file text is supplied by the caller, keeping this module pure.
+
+use anyhow::{bail, Result};
+use parseltongue_core::text::LineIndex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::diff_types::{Change, CodeDiff, Operation};
+
+/// Context lines around each hunk (git's default)
+pub const CONTEXT_LINES: usize = 3;
+
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file\n";
+
+/// A replacement of `old_len` original lines starting at 0-based `old_start`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Region {
+    old_start: usize,
+    old_len: usize,
+    new_lines: Vec<String>,
+}
+
+/// Original file split into lines plus its trailing-newline flag
+struct SourceFile {
+    lines: Vec<String>,
+    trailing_newline: bool,
+}
+
+impl SourceFile {
+    fn parse(content: &str) -> Self {
+        if content.is_empty() {
+            return Self { lines: vec![], trailing_newline: true };
+        }
+        let trailing_newline = content.ends_with('\n');
+        // A final `\n` terminates the last line rather than starting another
+        let index = LineIndex::new(content);
+        let line_count = index.line_count() - usize::from(trailing_newline);
+        Self {
+            lines: (1..=line_count)
+                .filter_map(|line| index.line_text(line))
+                .map(str::to_string)
+                .collect(),
+            trailing_newline,
+        }
+    }
+
+    /// Whether `idx` is the final line and lacks a newline terminator
+    fn is_unterminated_last(&self, idx: usize) -> bool {
+        !self.trailing_newline && idx + 1 == self.lines.len()
+    }
+}
+
+/// Render every patchable change in `diff` into one patch
+///
+/// `originals` maps each file path to its current content (`None` when the
+/// file does not exist). Files are emitted in path order for stable output.
+pub fn render_git_patch(diff: &CodeDiff, originals: &BTreeMap<PathBuf, Option<String>>) -> Result<String> {
+    let changes: Vec<Change> = diff.changes.iter().flat_map(split_move).collect();
+    let mut by_file: BTreeMap<&Path, Vec<&Change>> = BTreeMap::new();
+    for change in changes.iter().filter(|c| is_patchable(c)) {
+        by_file.entry(change.file_path.as_path()).or_default().push(change);
+    }
+
+    let mut patch = String::new();
+    for (path, changes) in by_file {
+        let original = originals.get(path).and_then(|content| content.as_deref());
+        patch.push_str(&render_file(path, original, &changes)?);
+    }
+    Ok(patch)
+}
+
+/// A Move as the Delete and Create it replaced; other changes as-is
+fn split_move(change: &Change) -> Vec<Change> {
+    let (Operation::Move { .. }, Some(to_path)) = (change.operation, &change.moved_to) else {
+        return vec![change.clone()];
+    };
+    let delete = Change {
+        operation: Operation::Delete,
+        future_code: None,
+        moved_to: None,
+        ..change.clone()
+    };
+    let create = Change {
+        file_path: to_path.clone(),
+        operation: Operation::Create,
+        current_code: None,
+        line_range: None,
+        moved_to: None,
+        ..change.clone()
+    };
+    vec![delete, create]
+}
+
+/// Whether a change carries everything needed to become a hunk
+fn is_patchable(change: &Change) -> bool {
+    match change.operation {
+        Operation::Create => change.future_code.is_some(),
+        Operation::Edit => {
+            change.current_code.is_some() && change.future_code.is_some() && change.line_range.is_some()
+        }
+        Operation::Delete => change.current_code.is_some() && change.line_range.is_some(),
+        // Split into Delete + Create before rendering
+        Operation::Move { .. } => false,
+    }
+}
+
+fn render_file(path: &Path, original: Option<&str>, changes: &[&Change]) -> Result<String> {
+    let display = path.to_string_lossy().replace('\\', "/");
+
+    let Some(original) = original else {
+        if let Some(change) = changes.iter().find(|c| c.operation != Operation::Create) {
+            bail!(
+                "Cannot patch {}: {} targets a file that does not exist",
+                display,
+                change.isgl1_key
+            );
+        }
+        return Ok(render_new_file(&display, changes));
+    };
+
+    let source = SourceFile::parse(original);
+    let regions = collect_regions(&display, &source, changes)?;
+    if regions.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut out = format!("diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n", display);
+    for hunk in group_regions(&regions) {
+        out.push_str(&render_hunk(&source, hunk, &regions));
+    }
+    Ok(out)
+}
+
+fn render_new_file(display: &str, changes: &[&Change]) -> String {
+    let content = changes
+        .iter()
+        .filter_map(|c| c.future_code.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut out = format!(
+        "diff --git a/{0} b/{0}\nnew file mode 100644\n--- /dev/null\n+++ b/{0}\n@@ -0,0 +{1} @@\n",
+        display,
+        hunk_range(1, lines.len())
+    );
+    for line in lines {
+        out.push('+');
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Map changes onto line regions, verifying them against the original file
+fn collect_regions(display: &str, source: &SourceFile, changes: &[&Change]) -> Result<Vec<Region>> {
+    let mut regions = Vec::new();
+    let mut appended: Vec<String> = Vec::new();
+
+    for change in changes {
+        let Some(range) = change.line_range else {
+            // Creates without a location are appended to the end of the file
+            if let Some(code) = &change.future_code {
+                if !appended.is_empty() || !source.lines.is_empty() {
+                    appended.push(String::new());
+                }
+                appended.extend(code.lines().map(str::to_string));
+            }
+            continue;
+        };
+
+        let (start, end) = (range.start as usize, range.end as usize);
+        if start == 0 || start > end || end > source.lines.len() {
+            bail!(
+                "Cannot patch {}: line range {}-{} of {} is outside the file ({} lines)",
+                display,
+                start,
+                end,
+                change.isgl1_key,
+                source.lines.len()
+            );
+        }
+
+        let old = &source.lines[start - 1..end];
+        if let Some(current) = &change.current_code {
+            if !same_code(old, current) {
+                bail!(
+                    "Cannot patch {}: lines {}-{} no longer match current code of {} (re-run ingestion)",
+                    display,
+                    start,
+                    end,
+                    change.isgl1_key
+                );
+            }
+        }
+
+        let new_lines = match (&change.operation, &change.future_code) {
+            (Operation::Delete, _) | (_, None) => vec![],
+            (_, Some(future)) => reindent_first_line(&old[0], future),
+        };
+        regions.push(Region { old_start: start - 1, old_len: end - start + 1, new_lines });
+    }
+
+    if !appended.is_empty() {
+        regions.push(append_region(source, appended));
+    }
+
+    regions.sort_by_key(|r| r.old_start);
+    for pair in regions.windows(2) {
+        if pair[0].old_start + pair[0].old_len > pair[1].old_start {
+            bail!(
+                "Cannot patch {}: overlapping changes at lines {} and {}",
+                display,
+                pair[0].old_start + 1,
+                pair[1].old_start + 1
+            );
+        }
+    }
+    Ok(regions)
+}
+
+/// Region inserting `lines` at EOF; rewrites an unterminated last line so the
+/// appended code starts on a fresh line
+fn append_region(source: &SourceFile, lines: Vec<String>) -> Region {
+    match source.lines.last() {
+        Some(last) if !source.trailing_newline => {
+            let mut new_lines = vec![last.clone()];
+            new_lines.extend(lines);
+            Region { old_start: source.lines.len() - 1, old_len: 1, new_lines }
+        }
+        _ => Region { old_start: source.lines.len(), old_len: 0, new_lines: lines },
+    }
+}
+
+/// Compare file lines with stored code, ignoring indentation differences
+fn same_code(file_lines: &[String], code: &str) -> bool {
+    let stored: Vec<&str> = code.lines().map(str::trim).collect();
+    let actual: Vec<&str> = file_lines.iter().map(|l| l.trim()).collect();
+    stored == actual
+}
+
+/// Entity text from tree-sitter starts at the node, not the line; restore the
+/// original indentation of the first line when the new code lacks it
+fn reindent_first_line(original_first: &str, future: &str) -> Vec<String> {
+    let indent: String = original_first.chars().take_while(|c| c.is_whitespace()).collect();
+    future
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 && !line.starts_with(char::is_whitespace) {
+                format!("{}{}", indent, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Group sorted regions whose context windows touch into single hunks
+fn group_regions(regions: &[Region]) -> Vec<std::ops::Range<usize>> {
+    let mut groups = Vec::new();
+    let mut begin = 0;
+    for i in 1..=regions.len() {
+        let split = i == regions.len() || {
+            let prev = &regions[i - 1];
+            regions[i].old_start - (prev.old_start + prev.old_len) > 2 * CONTEXT_LINES
+        };
+        if split {
+            groups.push(begin..i);
+            begin = i;
+        }
+    }
+    groups
+}
+
+fn render_hunk(source: &SourceFile, group: std::ops::Range<usize>, all: &[Region]) -> String {
+    let regions = &all[group.clone()];
+    let first = &regions[0];
+    let last = &regions[regions.len() - 1];
+    let total = source.lines.len();
+
+    let old_first = first.old_start.saturating_sub(CONTEXT_LINES);
+    let old_end = (last.old_start + last.old_len + CONTEXT_LINES).min(total);
+
+    // Net line shift introduced by earlier hunks
+    let shift: isize = all[..group.start]
+        .iter()
+        .map(|r| r.new_lines.len() as isize - r.old_len as isize)
+        .sum();
+
+    let mut body = String::new();
+    let mut old_count = 0;
+    let mut new_count = 0;
+    let mut cursor = old_first;
+
+    for region in regions {
+        let context = push_context(&mut body, source, cursor..region.old_start);
+        old_count += context;
+        new_count += context;
+        for idx in region.old_start..region.old_start + region.old_len {
+            body.push('-');
+            body.push_str(&source.lines[idx]);
+            body.push('\n');
+            if source.is_unterminated_last(idx) {
+                body.push_str(NO_NEWLINE_MARKER);
+            }
+            old_count += 1;
+        }
+        for line in &region.new_lines {
+            body.push('+');
+            body.push_str(line);
+            body.push('\n');
+            new_count += 1;
+        }
+        cursor = region.old_start + region.old_len;
+    }
+    let context = push_context(&mut body, source, cursor..old_end);
+    old_count += context;
+    new_count += context;
+
+    let new_first = (old_first as isize + shift) as usize;
+    format!(
+        "@@ -{} +{} @@\n{}",
+        hunk_range(old_first + 1, old_count),
+        hunk_range(new_first + 1, new_count),
+        body
+    )
+}
+
+/// Append unchanged lines as context, returning how many were written
+fn push_context(body: &mut String, source: &SourceFile, lines: std::ops::Range<usize>) -> usize {
+    let count = lines.len();
+    for idx in lines {
+        body.push(' ');
+        body.push_str(&source.lines[idx]);
+        body.push('\n');
+        if source.is_unterminated_last(idx) {
+            body.push_str(NO_NEWLINE_MARKER);
+        }
+    }
+    count
+}
+
+/// Format a hunk range; empty ranges point at the line before, per diff(1)
+fn hunk_range(start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", start - 1)
+    } else {
+        format!("{},{}", start, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_types::LineRange;
+
+    fn change(operation: Operation, current: Option<&str>, future: Option<&str>, range: Option<(u32, u32)>) -> Change {
+        Change {
+            isgl1_key: "rust:fn:f:src_lib_rs:1-1".to_string(),
+            file_path: PathBuf::from("src/lib.rs"),
+            operation,
+            current_code: current.map(str::to_string),
+            future_code: future.map(str::to_string),
+            line_range: range.map(|(start, end)| LineRange { start, end }),
+            interface_signature: "Function f".to_string(),
+            moved_to: None,
+        }
+    }
+
+    fn originals(content: Option<&str>) -> BTreeMap<PathBuf, Option<String>> {
+        BTreeMap::from([(PathBuf::from("src/lib.rs"), content.map(str::to_string))])
+    }
+
+    fn diff_of(changes: Vec<Change>) -> CodeDiff {
+        let mut diff = CodeDiff::new();
+        changes.into_iter().for_each(|c| diff.add_change(c));
+        diff
+    }
+
+    #[test]
+    fn edit_produces_hunk_with_context() {
+        let source = "a\nb\nc\nfn f() {}\nd\ne\nf\ng\n";
+        let diff = diff_of(vec![change(Operation::Edit, Some("fn f() {}"), Some("fn f() { 1 }"), Some((4, 4)))]);
+
+        let patch = render_git_patch(&diff, &originals(Some(source))).unwrap();
+        assert_eq!(
+            patch,
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,7 +1,7 @@\n a\n b\n c\n-fn f() {}\n+fn f() { 1 }\n d\n e\n f\n"
+        );
+    }
+
+    #[test]
+    fn create_without_file_is_new_file_hunk() {
+        let diff = diff_of(vec![change(Operation::Create, None, Some("fn g() {}\n"), None)]);
+
+        let patch = render_git_patch(&diff, &originals(None)).unwrap();
+        assert!(patch.contains("new file mode 100644\n--- /dev/null\n+++ b/src/lib.rs\n@@ -0,0 +1,1 @@\n+fn g() {}\n"));
+    }
+
+    #[test]
+    fn delete_removes_all_entity_lines() {
+        let source = "fn f() {\n    1\n}\n";
+        let diff = diff_of(vec![change(Operation::Delete, Some("fn f() {\n    1\n}"), None, Some((1, 3)))]);
+
+        let patch = render_git_patch(&diff, &originals(Some(source))).unwrap();
+        assert!(patch.ends_with("@@ -1,3 +0,0 @@\n-fn f() {\n-    1\n-}\n"));
+    }
+
+    #[test]
+    fn stale_current_code_is_rejected() {
+        let diff = diff_of(vec![change(Operation::Edit, Some("fn other() {}"), Some("x"), Some((1, 1)))]);
+        assert!(render_git_patch(&diff, &originals(Some("fn f() {}\n"))).is_err());
+    }
+
+    #[test]
+    fn missing_fields_are_skipped() {
+        let diff = diff_of(vec![change(Operation::Edit, None, Some("x"), Some((1, 1)))]);
+        assert_eq!(render_git_patch(&diff, &originals(Some("fn f() {}\n"))).unwrap(), "");
+    }
+
+    #[test]
+    fn unterminated_last_line_gets_marker() {
+        let diff = diff_of(vec![change(Operation::Edit, Some("b"), Some("B"), Some((2, 2)))]);
+        let patch = render_git_patch(&diff, &originals(Some("a\nb"))).unwrap();
+        assert!(patch.ends_with("@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+B\n"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Write-ahead journal for crash-safe file writes.
+//!
+//! Before touching the working tree, [`FileWriter::write_entities`] records
+//! every intended write (with its full content) in a journal next to the
+//! project root. Each file is then written to a temp file and renamed into
+//! place, and its journal entry is marked complete. A crash leaves the
+//! journal behind; `--recover` replays the entries that never completed.
+//!
+//! This is about atomicity, not history: the journal holds future content
+//! only, never the previous file contents, and is deleted on success.
+//!
+//! [`FileWriter::write_entities`]: crate::writer::FileWriter::write_entities
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::errors::FileWriterError;
+use crate::types::WriteOperation;
+
+/// Journal file name, placed in the writer's root directory
+pub const JOURNAL_FILE_NAME: &str = ".pt05-write-journal.json";
+
+/// One intended file operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Target file
+    pub path: PathBuf,
+    /// Create, Edit or Delete
+    pub operation: WriteOperation,
+    /// Full file content for Create/Edit
+    pub content: Option<String>,
+    /// Set once the operation reached disk
+    pub completed: bool,
+}
+
+/// Ordered list of intended writes for one run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteJournal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl WriteJournal {
+    /// Journal location for a writer rooted at `root`
+    pub fn path_for_root(root: &Path) -> PathBuf {
+        root.join(JOURNAL_FILE_NAME)
+    }
+
+    /// Load a journal; `Ok(None)` when no journal exists
+    pub fn load(path: &Path) -> Result<Option<Self>, FileWriterError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| FileWriterError::Journal {
+                details: format!("{}: {}", path.display(), e),
+            })
+    }
+
+    /// Persist the journal atomically
+    pub fn save(&self, path: &Path) -> Result<(), FileWriterError> {
+        let json = serde_json::to_string(self).map_err(|e| FileWriterError::Journal {
+            details: e.to_string(),
+        })?;
+        write_atomic(path, &json)
+    }
+
+    /// Delete the journal (missing file is not an error)
+    pub fn remove(path: &Path) -> Result<(), FileWriterError> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Indices of entries that have not reached disk yet
+    pub fn pending(&self) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.completed)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.entries.iter().all(|entry| entry.completed)
+    }
+}
+
+/// Write `content` to a sibling temp file, then rename it over `path`
+///
+/// The rename is atomic on the same filesystem, so readers see either the
+/// old file or the new one, never a partial write.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), FileWriterError> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.pt05-tmp", file_name));
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(path: &str, completed: bool) -> JournalEntry {
+        JournalEntry {
+            path: PathBuf::from(path),
+            operation: WriteOperation::Edit,
+            content: Some("fn main() {}".to_string()),
+            completed,
+        }
+    }
+
+    #[test]
+    fn test_journal_round_trip_and_pending() {
+        let dir = TempDir::new().unwrap();
+        let path = WriteJournal::path_for_root(dir.path());
+        assert_eq!(WriteJournal::load(&path).unwrap(), None);
+
+        let journal = WriteJournal {
+            entries: vec![entry("a.rs", true), entry("b.rs", false)],
+        };
+        journal.save(&path).unwrap();
+
+        let loaded = WriteJournal::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, journal);
+        assert_eq!(loaded.pending(), vec![1]);
+        assert!(!loaded.is_complete());
+
+        WriteJournal::remove(&path).unwrap();
+        WriteJournal::remove(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
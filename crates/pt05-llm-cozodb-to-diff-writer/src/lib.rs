@@ -16,9 +16,13 @@
 //!    - Future code content
 //!    - Interface signature
 //! 3. Outputs single JSON file
+//! 4. Optionally renders the same changes as a `git apply`-compatible patch
+//! 5. With `--apply`, splices the changes into the source files through
+//!    the write-ahead journal of [`FileWriter`] (`--recover` finishes an
+//!    interrupted run)
 //!
 //! ## What It Does NOT Do
-//! - ❌ Does NOT write files directly (LLM does that)
+//! - ❌ Does NOT write files unless asked to (`--apply`; otherwise the LLM does that)
 //! - ❌ Does NOT create backups
 //! - ❌ Does NOT validate code (Tool 4 handles syntax, cargo handles types)
 //!
@@ -51,17 +55,20 @@
 
 pub mod diff_generator;
 pub mod diff_types;
+pub mod git_patch;
 
 // Legacy modules (will be removed after refactoring)
 pub mod errors;
+pub mod journal;
 pub mod types;
 pub mod writer;
 
 // Re-export new API
 pub use diff_generator::DiffGenerator;
-pub use diff_types::{Change, CodeDiff, DiffMetadata, LineRange, Operation};
+pub use diff_types::{Change, CodeDiff, DiffMetadata, GroupedCodeDiff, LineRange, Operation};
 
 // Legacy re-exports (deprecated)
 pub use errors::FileWriterError;
-pub use types::{WriteOperation, WriteResult, WriteSummary};
+pub use journal::WriteJournal;
+pub use types::{CheckResult, FileWriterConfig, LineEnding, WriteOperation, WriteResult, WriteSummary};
 pub use writer::FileWriter;
@@ -55,6 +55,85 @@ pub enum WriteOperation {
     NoOp,
 }
 
+/// Line ending written to source files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// Match the dominant line ending of the file being replaced; new
+    /// files get `Lf`
+    #[default]
+    Preserve,
+}
+
+impl LineEnding {
+    /// Dominant line ending of `existing` (`Lf` on a tie or no line breaks)
+    pub fn detect(existing: &str) -> Self {
+        let crlf = existing.matches("\r\n").count();
+        let lf = existing.matches('\n').count() - crlf;
+        if crlf > lf {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// `content` with every line break rewritten to this ending
+    ///
+    /// `Preserve` must be resolved against the existing file first; on its
+    /// own it leaves `content` as given.
+    pub fn apply(self, content: &str) -> String {
+        match self {
+            LineEnding::Lf => content.replace("\r\n", "\n"),
+            LineEnding::Crlf => content.replace("\r\n", "\n").replace('\n', "\r\n"),
+            LineEnding::Preserve => content.to_string(),
+        }
+    }
+}
+
+/// Output settings for `FileWriter`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileWriterConfig {
+    /// Line ending for created and edited files
+    pub line_ending: LineEnding,
+    /// Splice edits into the entity's `line_range` of the existing file,
+    /// re-indented to that line's leading whitespace, instead of replacing
+    /// the whole file with future_code; deletes remove their lines and
+    /// creates are appended. A range that no longer fits the file fails
+    /// the write
+    pub indent_context: bool,
+    /// Before a batch, confirm every Edit/Delete target still holds the
+    /// entity's current_code (see `FileWriter::check_entity`) and refuse
+    /// the batch on drift
+    pub check: bool,
+    /// With `check`: write despite drift
+    pub force: bool,
+}
+
+/// Whether the file on disk still holds what the graph believes it does
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckResult {
+    /// The entity's span hashes the same as its current_code (or the entity
+    /// has no current_code to compare)
+    Match,
+    /// The span was changed outside the graph
+    Drift {
+        path: PathBuf,
+        expected_hash: String,
+        actual_hash: String,
+    },
+    /// The target file, or the entity's line range in it, no longer exists
+    Missing { path: PathBuf },
+}
+
+impl CheckResult {
+    pub fn is_match(&self) -> bool {
+        matches!(self, CheckResult::Match)
+    }
+}
+
 /// Summary of all write operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WriteSummary {
@@ -119,6 +198,15 @@ mod tests {
         assert_eq!(result.operation, WriteOperation::Create);
     }
 
+    #[test]
+    fn test_line_ending_detection_and_conversion() {
+        assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::Crlf);
+        assert_eq!(LineEnding::detect("a\nb\r\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("no breaks"), LineEnding::Lf);
+        assert_eq!(LineEnding::Crlf.apply("a\nb\r\n"), "a\r\nb\r\n");
+        assert_eq!(LineEnding::Lf.apply("a\r\nb\n"), "a\nb\n");
+    }
+
     #[test]
     fn test_write_summary() {
         let mut summary = WriteSummary::new();
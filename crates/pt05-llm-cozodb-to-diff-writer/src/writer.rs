@@ -1,25 +1,46 @@
 use anyhow::Result;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use parseltongue_core::entities::{CodeEntity, FutureAction};
 
 use crate::errors::FileWriterError;
-use crate::types::{WriteOperation, WriteResult};
+use crate::journal::{write_atomic, JournalEntry, WriteJournal};
+use crate::types::{CheckResult, FileWriterConfig, LineEnding, WriteOperation, WriteResult, WriteSummary};
 
 /// Ultra-minimalist file writer
 ///
 /// NO BACKUPS - Direct file operations only
-/// NO CONFIGURATION - Single reliable operation
 /// NO ROLLBACK - Permanent changes
+///
+/// Settings are the line ending written and whether edits are spliced in
+/// with their surrounding indentation (see [`FileWriterConfig`]); by
+/// default edits keep the existing file's line ending and replace it whole.
+///
+/// Files are replaced via temp-file-and-rename, and batches go through a
+/// write-ahead journal (see [`crate::journal`]) so a crash never leaves a
+/// half-written file or an unrecoverable half-applied batch.
 pub struct FileWriter {
     /// Root directory for file operations
     root_path: PathBuf,
+    /// Output settings
+    config: FileWriterConfig,
 }
 
 impl FileWriter {
     /// Create a new file writer with the given root path
     pub fn new(root_path: PathBuf) -> Self {
-        Self { root_path }
+        Self {
+            root_path,
+            config: FileWriterConfig::default(),
+        }
+    }
+
+    /// Use `config` instead of the defaults
+    pub fn with_config(mut self, config: FileWriterConfig) -> Self {
+        self.config = config;
+        self
     }
 
     /// Write a single entity to disk
@@ -29,59 +50,246 @@ impl FileWriter {
     /// - Direct write operations
     /// - Fail-fast error handling
     pub async fn write_entity(&self, entity: &CodeEntity) -> Result<WriteResult> {
-        match &entity.temporal_state.future_action {
-            Some(FutureAction::Create) => self.create_file(entity).await,
-            Some(FutureAction::Edit) => self.modify_file(entity).await,
-            Some(FutureAction::Delete) => self.delete_file(entity).await,
+        let entities = std::slice::from_ref(entity);
+        self.preflight(entities)?;
+        match self.journal_entries(entities)?.first() {
+            Some(entry) => self.apply_entry(entry, false).await,
             None => Ok(WriteResult::no_op()),
         }
     }
 
-    /// Create a new file (fails if file already exists)
-    async fn create_file(&self, entity: &CodeEntity) -> Result<WriteResult> {
-        // GREEN phase: Minimal implementation
-        let file_path = self.resolve_file_path(&entity.isgl1_key)?;
+    /// Write a batch of entities through the write-ahead journal
+    ///
+    /// Refuses to start while an earlier run's journal is still on disk;
+    /// call [`FileWriter::recover`] first.
+    pub async fn write_entities(&self, entities: &[CodeEntity]) -> Result<WriteSummary> {
+        let journal_path = self.journal_path();
+        if journal_path.exists() {
+            return Err(FileWriterError::IncompleteJournal { path: journal_path }.into());
+        }
+        self.preflight(entities)?;
+
+        let mut journal = WriteJournal {
+            entries: self.journal_entries(entities)?,
+        };
+        journal.save(&journal_path)?;
+
+        self.run_journal(&mut journal, false).await
+    }
+
+    /// Compare the entity's span on disk with its current_code
+    ///
+    /// Reads the entity's `line_range` from its target file and compares
+    /// SHA-256 hashes with `current_code`, ignoring line-ending style and
+    /// trailing newlines. An entity without current_code (a Create) has
+    /// nothing on disk to drift from and matches.
+    pub fn check_entity(&self, entity: &CodeEntity) -> CheckResult {
+        let Some(expected) = &entity.current_code else {
+            return CheckResult::Match;
+        };
+        let path = self.target_path(entity);
+        let Ok(existing) = std::fs::read_to_string(&path) else {
+            return CheckResult::Missing { path };
+        };
+
+        let range = &entity.interface_signature.line_range;
+        let (start, end) = (range.start as usize, range.end as usize);
+        let lines: Vec<&str> = existing.lines().collect();
+        if start == 0 || start > end || end > lines.len() {
+            return CheckResult::Missing { path };
+        }
+        let expected_hash = span_hash(expected);
+        let actual_hash = span_hash(&lines[start - 1..end].join("\n"));
+        if expected_hash == actual_hash {
+            CheckResult::Match
+        } else {
+            CheckResult::Drift { path, expected_hash, actual_hash }
+        }
+    }
+
+    /// [`FileWriter::check_entity`] over every Edit/Delete, keeping the
+    /// entities that did not match
+    pub fn check_entities(&self, entities: &[CodeEntity]) -> Vec<(String, CheckResult)> {
+        entities
+            .iter()
+            .filter(|entity| {
+                matches!(
+                    entity.temporal_state.future_action,
+                    Some(FutureAction::Edit) | Some(FutureAction::Delete)
+                )
+            })
+            .map(|entity| (entity.isgl1_key.clone(), self.check_entity(entity)))
+            .filter(|(_, result)| !result.is_match())
+            .collect()
+    }
 
-        // Ensure parent directory exists
-        if let Some(parent) = file_path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
+    /// With `config.check`, refuse to write when an Edit/Delete target no
+    /// longer matches its current_code (unless `config.force`)
+    ///
+    /// Returns the entities that did not match.
+    fn preflight(&self, entities: &[CodeEntity]) -> Result<Vec<(String, CheckResult)>> {
+        if !self.config.check {
+            return Ok(vec![]);
         }
+        let mismatched = self.check_entities(entities);
+
+        if !mismatched.is_empty() && !self.config.force {
+            let paths = mismatched
+                .iter()
+                .map(|(_, result)| match result {
+                    CheckResult::Drift { path, .. } | CheckResult::Missing { path } => path.clone(),
+                    CheckResult::Match => unreachable!("matches were filtered out"),
+                })
+                .collect();
+            return Err(FileWriterError::Drift { paths }.into());
+        }
+        Ok(mismatched)
+    }
 
-        // Get content to write
-        let content = entity.future_code
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Future code missing for Create operation"))?;
+    /// Location of this writer's journal
+    pub fn journal_path(&self) -> PathBuf {
+        WriteJournal::path_for_root(&self.root_path)
+    }
 
-        // Write file directly (ultra-minimalist: no backups)
-        tokio::fs::write(&file_path, content).await?;
+    /// Whether a previous batch was interrupted and needs `--recover`
+    pub fn has_incomplete_journal(&self) -> bool {
+        self.journal_path().exists()
+    }
 
-        Ok(WriteResult::success(file_path, WriteOperation::Create))
+    /// Replay the unfinished entries of an interrupted batch
+    ///
+    /// Re-applying is idempotent: writes carry the full file content, and a
+    /// delete whose file is already gone counts as done.
+    pub async fn recover(&self) -> Result<WriteSummary> {
+        match WriteJournal::load(&self.journal_path())? {
+            Some(mut journal) => self.run_journal(&mut journal, true).await,
+            None => Ok(WriteSummary::new()),
+        }
     }
 
-    /// Modify an existing file (direct overwrite, NO backup)
-    async fn modify_file(&self, entity: &CodeEntity) -> Result<WriteResult> {
-        // GREEN phase: Minimal implementation - direct overwrite
-        let file_path = self.resolve_file_path(&entity.isgl1_key)?;
+    /// Apply pending entries in order, checkpointing each, then drop the journal
+    async fn run_journal(&self, journal: &mut WriteJournal, recovering: bool) -> Result<WriteSummary> {
+        let journal_path = self.journal_path();
+        let mut summary = WriteSummary::new();
 
-        let content = entity.future_code
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Future code missing for Edit operation"))?;
+        for index in journal.pending() {
+            let result = self.apply_entry(&journal.entries[index], recovering).await?;
+            journal.entries[index].completed = true;
+            journal.save(&journal_path)?;
+            summary.add_result(&result);
+        }
+
+        WriteJournal::remove(&journal_path)?;
+        Ok(summary)
+    }
 
-        // Ultra-minimalist: Direct overwrite, NO backup
-        tokio::fs::write(&file_path, content).await?;
+    /// Translate the entities' temporal actions into file operations
+    ///
+    /// With `indent_context`, all changes to one file become a single entry
+    /// built by [`splice_changes`], so entities sharing a file are written
+    /// together instead of each replacing the whole file.
+    fn journal_entries(&self, entities: &[CodeEntity]) -> Result<Vec<JournalEntry>> {
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        let mut spliced: HashMap<PathBuf, (usize, Vec<&CodeEntity>)> = HashMap::new();
+
+        for entity in entities {
+            let operation = match &entity.temporal_state.future_action {
+                Some(FutureAction::Create) => WriteOperation::Create,
+                Some(FutureAction::Edit) => WriteOperation::Edit,
+                Some(FutureAction::Delete) => WriteOperation::Delete,
+                None => continue,
+            };
+            let path = self.target_path(entity);
+
+            let content = match operation {
+                WriteOperation::Delete => None,
+                _ => Some(entity.future_code.clone().ok_or_else(|| {
+                    FileWriterError::MissingFutureCode {
+                        action: format!("{:?}", operation),
+                    }
+                })?),
+            };
+            if self.config.indent_context {
+                // Content is filled in once every change to the file is known
+                let index = entries.len();
+                let (_, changes) = spliced.entry(path.clone()).or_insert_with(|| {
+                    entries.push(JournalEntry { path, operation, content: None, completed: false });
+                    (index, Vec::new())
+                });
+                changes.push(entity);
+                continue;
+            }
+
+            entries.push(JournalEntry {
+                path,
+                operation,
+                content,
+                completed: false,
+            });
+        }
 
-        Ok(WriteResult::success(file_path, WriteOperation::Edit))
+        for (path, (index, changes)) in spliced {
+            let existing = match std::fs::read_to_string(&path) {
+                Ok(existing) => Some(existing),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            let (operation, content) = splice_changes(&path, existing, changes)?;
+            entries[index].operation = operation;
+            entries[index].content = Some(content);
+        }
+        Ok(entries)
     }
 
-    /// Delete a file permanently (NO trash/recycle)
-    async fn delete_file(&self, entity: &CodeEntity) -> Result<WriteResult> {
-        // GREEN phase: Minimal implementation - permanent deletion
-        let file_path = self.resolve_file_path(&entity.isgl1_key)?;
+    /// Perform one file operation (create/overwrite atomically, or delete)
+    async fn apply_entry(&self, entry: &JournalEntry, recovering: bool) -> Result<WriteResult> {
+        match (&entry.operation, &entry.content) {
+            (WriteOperation::Create | WriteOperation::Edit, Some(content)) => {
+                // Ensure parent directory exists
+                if let Some(parent) = entry.path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                // Ultra-minimalist: Direct overwrite, NO backup
+                let content = self.line_ending_for(&entry.path).apply(content);
+                write_atomic(&entry.path, &content)?;
+            }
+            (WriteOperation::Delete, _) => {
+                // Ultra-minimalist: Permanent deletion, NO trash
+                match tokio::fs::remove_file(&entry.path).await {
+                    Err(e) if recovering && e.kind() == std::io::ErrorKind::NotFound => {}
+                    other => other?,
+                }
+            }
+            (operation, _) => {
+                return Err(FileWriterError::MissingFutureCode {
+                    action: format!("{:?}", operation),
+                }
+                .into())
+            }
+        }
+
+        Ok(WriteResult::success(entry.path.clone(), entry.operation))
+    }
 
-        // Ultra-minimalist: Permanent deletion, NO trash
-        tokio::fs::remove_file(&file_path).await?;
+    /// Line ending to write at `path`, resolving `Preserve` against the
+    /// file currently there
+    fn line_ending_for(&self, path: &std::path::Path) -> LineEnding {
+        match self.config.line_ending {
+            LineEnding::Preserve => match std::fs::read(path) {
+                Ok(existing) => LineEnding::detect(&String::from_utf8_lossy(&existing)),
+                Err(_) => LineEnding::Lf,
+            },
+            line_ending => line_ending,
+        }
+    }
 
-        Ok(WriteResult::success(file_path, WriteOperation::Delete))
+    /// File an entity is written to
+    ///
+    /// Legacy `path-file-rs-Name` keys encode it; otherwise it is the
+    /// entity's `file_path` under the root.
+    fn target_path(&self, entity: &CodeEntity) -> PathBuf {
+        self.resolve_file_path(&entity.isgl1_key)
+            .unwrap_or_else(|_| self.root_path.join(&entity.interface_signature.file_path))
     }
 
     /// Parse ISGL1 key to extract file path
@@ -107,6 +315,123 @@ impl FileWriter {
     }
 }
 
+/// SHA-256 of `code` with CRLF read as LF and trailing newlines dropped
+fn span_hash(code: &str) -> String {
+    let normalized = code.replace("\r\n", "\n");
+    format!("{:x}", Sha256::digest(normalized.trim_end_matches('\n').as_bytes()))
+}
+
+/// New content of one file with every change to it spliced in
+///
+/// Edits replace their line range with future_code and Deletes remove it,
+/// from the bottom of the file up so one splice never shifts the lines of
+/// another; Creates are appended. A range outside the file, overlapping
+/// another, or no longer holding the entity's current_code is stale and
+/// fails the whole file rather than corrupting it. A missing file can only
+/// be created.
+fn splice_changes(
+    path: &Path,
+    existing: Option<String>,
+    changes: Vec<&CodeEntity>,
+) -> Result<(WriteOperation, String), FileWriterError> {
+    let (mut ranged, creates): (Vec<&CodeEntity>, Vec<&CodeEntity>) = changes
+        .into_iter()
+        .partition(|entity| !matches!(entity.temporal_state.future_action, Some(FutureAction::Create)));
+    let append_creates = |mut content: String| {
+        for entity in &creates {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(entity.future_code.as_deref().unwrap_or_default().trim_end_matches(['\r', '\n']));
+            content.push('\n');
+        }
+        content
+    };
+    let Some(mut content) = existing else {
+        if !ranged.is_empty() {
+            return Err(FileWriterError::file_not_found(path.to_path_buf()));
+        }
+        return Ok((WriteOperation::Create, append_creates(String::new())));
+    };
+
+    ranged.sort_by_key(|entity| std::cmp::Reverse(entity.interface_signature.line_range.start));
+    let mut below = usize::MAX;
+    for entity in ranged {
+        let range = &entity.interface_signature.line_range;
+        let (start, end) = (range.start as usize, range.end as usize);
+        let stale = || FileWriterError::StaleLineRange {
+            key: entity.isgl1_key.clone(),
+            path: path.to_path_buf(),
+            start,
+            end,
+        };
+        if end >= below {
+            return Err(stale());
+        }
+        if let Some(current) = &entity.current_code {
+            let lines: Vec<&str> = content.lines().collect();
+            let span = lines.get(start.wrapping_sub(1)..end).ok_or_else(stale)?;
+            if span_hash(&span.join("\n")) != span_hash(current) {
+                return Err(stale());
+            }
+        }
+        let code = match entity.temporal_state.future_action {
+            Some(FutureAction::Delete) => None,
+            _ => Some(entity.future_code.as_deref().unwrap_or_default()),
+        };
+        content = splice_indented(&content, start, end, code).ok_or_else(stale)?;
+        below = start;
+    }
+    Ok((WriteOperation::Edit, append_creates(content)))
+}
+
+/// `existing` with 1-based lines `start..=end` replaced by `code`,
+/// re-indented to the leading whitespace of line `start`, or removed when
+/// `code` is `None`
+///
+/// `None` when the range is not inside `existing`.
+fn splice_indented(existing: &str, start: usize, end: usize, code: Option<&str>) -> Option<String> {
+    let lines: Vec<&str> = existing.split_inclusive('\n').collect();
+    if start == 0 || start > end || end > lines.len() {
+        return None;
+    }
+    let first = lines[start - 1];
+    let indent = &first[..first.len() - first.trim_start_matches([' ', '\t']).len()];
+
+    let mut spliced: String = lines[..start - 1].concat();
+    if let Some(code) = code {
+        spliced.push_str(&reindent(code, indent));
+        if lines[end - 1].ends_with('\n') {
+            spliced.push('\n');
+        }
+    }
+    spliced.push_str(&lines[end..].concat());
+    Some(spliced)
+}
+
+/// `code` with its common leading whitespace replaced by `indent`
+///
+/// Blank lines stay empty; a trailing newline is dropped.
+fn reindent(code: &str, indent: &str) -> String {
+    let code = code.trim_end_matches(['\r', '\n']);
+    let common = code
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    code.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, &line[common..])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +477,8 @@ mod tests {
                 created_at: Utc::now(),
                 modified_at: Utc::now(),
                 content_hash: "test_hash".to_string(),
+                semantic_hash: String::new(),
+                complexity_score: None,
                 additional: HashMap::new(),
             },
             // v0.9.0: Add mandatory entity_class field
@@ -211,6 +538,144 @@ mod tests {
         assert_eq!(content, "fn new() {}");
     }
 
+    #[tokio::test]
+    async fn test_edit_keeps_crlf_line_endings_under_preserve() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/windows.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "fn old() {\r\n    1\r\n}\r\n").unwrap();
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf());
+        let entity = create_test_entity(
+            "src-windows-rs-New",
+            Some("fn new() {\n    2\n}\n".to_string()),
+            TemporalState::edit(),
+        );
+        writer.write_entity(&entity).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "fn new() {\r\n    2\r\n}\r\n");
+
+        // Creates have nothing to preserve and get LF
+        let created = create_test_entity(
+            "src-fresh-rs-Fresh",
+            Some("fn fresh() {\r\n}\r\n".to_string()),
+            TemporalState::create(),
+        );
+        writer.write_entity(&created).await.unwrap();
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join("src/fresh.rs")).unwrap(), "fn fresh() {\n}\n");
+
+        // An explicit ending overrides the file's
+        let writer = writer.with_config(FileWriterConfig { line_ending: LineEnding::Lf, ..Default::default() });
+        writer.write_entity(&entity).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "fn new() {\n    2\n}\n");
+    }
+
+    #[tokio::test]
+    async fn test_spliced_edit_keeps_crlf_line_endings_under_preserve() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/windows.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "fn keep() {}\r\nfn old() {\r\n    1\r\n}\r\n").unwrap();
+
+        let mut entity = create_test_entity("src-windows-rs-old", Some("fn old() {\n    2\n}\n".to_string()), TemporalState::edit());
+        entity.interface_signature.line_range = LineRange { start: 2, end: 4 };
+        let writer = FileWriter::new(temp_dir.path().to_path_buf())
+            .with_config(FileWriterConfig { indent_context: true, ..Default::default() });
+        writer.write_entities(&[entity]).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "fn keep() {}\r\nfn old() {\r\n    2\r\n}\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_indent_context_splices_edit_at_module_indentation() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/nested.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &file_path,
+            "mod inner {\n    fn old() {\n        1\n    }\n\n    fn keep() {}\n}\n",
+        )
+        .unwrap();
+
+        let mut entity = create_test_entity(
+            "src-nested-rs-old",
+            Some("fn old() {\n    let x = 2;\n\n    x\n}\n".to_string()),
+            TemporalState::edit(),
+        );
+        entity.interface_signature.line_range = LineRange { start: 2, end: 4 };
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf())
+            .with_config(FileWriterConfig { indent_context: true, ..Default::default() });
+        writer.write_entity(&entity).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "mod inner {\n    fn old() {\n        let x = 2;\n\n        x\n    }\n\n    fn keep() {}\n}\n"
+        );
+
+        // A range outside the file is stale and leaves the file alone
+        let before = std::fs::read_to_string(&file_path).unwrap();
+        entity.interface_signature.line_range = LineRange { start: 40, end: 50 };
+        let err = writer.write_entity(&entity).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FileWriterError>(),
+            Some(FileWriterError::StaleLineRange { start: 40, end: 50, .. })
+        ));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_indent_context_splices_every_edit_to_a_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/pair.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "mod m {\n    fn a() {\n        1\n    }\n\n    fn b() {\n        2\n    }\n}\n").unwrap();
+
+        let edit = |range: (u32, u32), code: &str, current: &str| {
+            let mut entity = create_test_entity("src-pair-rs-f", Some(code.to_string()), TemporalState::edit());
+            entity.interface_signature.line_range = LineRange { start: range.0, end: range.1 };
+            entity.current_code = Some(current.to_string());
+            entity
+        };
+        let a = edit((2, 4), "fn a() {\n    10\n}", "    fn a() {\n        1\n    }");
+        let b = edit((6, 8), "fn b() {\n    20\n}", "    fn b() {\n        2\n    }");
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf())
+            .with_config(FileWriterConfig { indent_context: true, ..Default::default() });
+        let summary = writer.write_entities(&[a.clone(), b]).await.unwrap();
+        assert_eq!(summary.edited, 1, "one journal entry per file");
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "mod m {\n    fn a() {\n        10\n    }\n\n    fn b() {\n        20\n    }\n}\n"
+        );
+
+        // The file no longer holds `a`'s current_code at its range
+        let err = writer.write_entity(&a).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FileWriterError>(),
+            Some(FileWriterError::StaleLineRange { start: 2, end: 4, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_indent_context_deletes_lines_and_appends_creates() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/ops.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "fn keep() {}\n\nfn drop_me() {\n}\n").unwrap();
+
+        let mut deleted = create_test_entity("src-ops-rs-drop_me", None, TemporalState::delete());
+        deleted.interface_signature.line_range = LineRange { start: 3, end: 4 };
+        let created = create_test_entity("src-ops-rs-added", Some("fn added() {}\n".to_string()), TemporalState::create());
+        let fresh = create_test_entity("src-fresh-rs-fresh", Some("fn fresh() {}".to_string()), TemporalState::create());
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf())
+            .with_config(FileWriterConfig { indent_context: true, ..Default::default() });
+        let summary = writer.write_entities(&[deleted, created, fresh]).await.unwrap();
+
+        assert_eq!((summary.edited, summary.created, summary.deleted), (1, 1, 0));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "fn keep() {}\n\nfn added() {}\n");
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join("src/fresh.rs")).unwrap(), "fn fresh() {}\n");
+    }
+
     #[tokio::test]
     async fn test_delete_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -274,6 +739,142 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_write_entities_is_journaled_and_cleans_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let writer = FileWriter::new(temp_dir.path().to_path_buf());
+
+        let summary = writer
+            .write_entities(&[
+                create_test_entity("src-a-rs-A", Some("fn a() {}".to_string()), TemporalState::create()),
+                create_test_entity("src-b-rs-B", Some("fn b() {}".to_string()), TemporalState::create()),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(summary.created, 2);
+        assert!(!writer.has_incomplete_journal());
+        let names: Vec<_> = std::fs::read_dir(temp_dir.path().join("src"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(names.iter().all(|name| !name.contains("pt05-tmp")), "{:?}", names);
+    }
+
+    #[tokio::test]
+    async fn test_recover_completes_writes_after_crash() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("first.rs"), "fn first_old() {}").unwrap();
+        std::fs::write(src.join("second.rs"), "fn second_old() {}").unwrap();
+        std::fs::write(src.join("gone.rs"), "fn gone() {}").unwrap();
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf());
+        let entities = [
+            create_test_entity("src-first-rs-F", Some("fn first_new() {}".to_string()), TemporalState::edit()),
+            create_test_entity("src-second-rs-S", Some("fn second_new() {}".to_string()), TemporalState::edit()),
+            create_test_entity("src-gone-rs-G", None, TemporalState::delete()),
+        ];
+
+        // Simulate a run that crashes right after the first write
+        let mut journal = WriteJournal {
+            entries: writer.journal_entries(&entities).unwrap(),
+        };
+        journal.save(&writer.journal_path()).unwrap();
+        writer.apply_entry(&journal.entries[0], false).await.unwrap();
+        journal.entries[0].completed = true;
+        journal.save(&writer.journal_path()).unwrap();
+        drop(writer);
+
+        // Restart: the interrupted journal blocks new batches until recovered
+        let writer = FileWriter::new(temp_dir.path().to_path_buf());
+        assert!(writer.has_incomplete_journal());
+        let blocked = writer.write_entities(&entities).await.unwrap_err();
+        assert!(matches!(
+            blocked.downcast_ref::<FileWriterError>(),
+            Some(FileWriterError::IncompleteJournal { .. })
+        ));
+
+        let summary = writer.recover().await.unwrap();
+        assert_eq!((summary.edited, summary.deleted), (1, 1));
+        assert!(!writer.has_incomplete_journal());
+        assert_eq!(std::fs::read_to_string(src.join("first.rs")).unwrap(), "fn first_new() {}");
+        assert_eq!(std::fs::read_to_string(src.join("second.rs")).unwrap(), "fn second_new() {}");
+        assert!(!src.join("gone.rs").exists());
+
+        // Nothing left to recover
+        assert_eq!(writer.recover().await.unwrap().total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_drift_after_external_edit() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("src/calc.rs");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "use std::fmt;\n\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+
+        let mut entity = create_test_entity(
+            "src-calc-rs-add",
+            Some("fn add(a: i32, b: i32) -> i32 {\n    b + a\n}".to_string()),
+            TemporalState::edit(),
+        );
+        entity.current_code = Some("fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string());
+        entity.interface_signature.line_range = LineRange { start: 3, end: 5 };
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf())
+            .with_config(FileWriterConfig { check: true, ..Default::default() });
+        assert_eq!(writer.check_entity(&entity), CheckResult::Match);
+
+        // Someone edits the function outside the graph
+        let edited = "use std::fmt;\n\nfn add(a: i32, b: i32) -> i32 {\n    a.wrapping_add(b)\n}\n";
+        std::fs::write(&file_path, edited).unwrap();
+        assert!(matches!(
+            writer.check_entity(&entity),
+            CheckResult::Drift { path, .. } if path == file_path
+        ));
+
+        // The preflight refuses to clobber the change
+        let err = writer.write_entities(&[entity.clone()]).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FileWriterError>(),
+            Some(FileWriterError::Drift { paths }) if paths == &vec![file_path.clone()]
+        ));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), edited);
+        assert!(!writer.has_incomplete_journal());
+
+        // --force writes anyway
+        let writer = writer.with_config(FileWriterConfig { check: true, force: true, ..Default::default() });
+        writer.write_entities(&[entity.clone()]).await.unwrap();
+        assert!(std::fs::read_to_string(&file_path).unwrap().contains("b + a"));
+
+        // A deleted file is Missing
+        std::fs::remove_file(&file_path).unwrap();
+        assert_eq!(writer.check_entity(&entity), CheckResult::Missing { path: file_path });
+    }
+
+    #[test]
+    fn test_check_entities_finds_isgl1_keyed_files_by_file_path() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/lib.rs"), "fn add() {\n    1\n}\n").unwrap();
+
+        let mut entity = create_test_entity("rust:fn:add:src_lib_rs:1-3", Some("fn add() {}".to_string()), TemporalState::edit());
+        entity.interface_signature.file_path = PathBuf::from("src/lib.rs");
+        entity.interface_signature.line_range = LineRange { start: 1, end: 3 };
+        entity.current_code = Some("fn add() {\n    1\n}".to_string());
+
+        let writer = FileWriter::new(temp_dir.path().to_path_buf());
+        assert!(writer.check_entities(std::slice::from_ref(&entity)).is_empty());
+
+        entity.current_code = Some("fn add() {\n    2\n}".to_string());
+        let mismatched = writer.check_entities(&[entity]);
+        assert!(matches!(
+            &mismatched[..],
+            [(key, CheckResult::Drift { path, .. })] if key == "rust:fn:add:src_lib_rs:1-3" && path == &temp_dir.path().join("src/lib.rs")
+        ));
+    }
+
     #[tokio::test]
     async fn test_resolve_file_path() {
         let writer = FileWriter::new(PathBuf::from("/tmp"));
@@ -5,6 +5,7 @@
 use pt05_llm_cozodb_to_diff_writer::{DiffGenerator, Operation};
 use parseltongue_core::entities::{CodeEntity, TemporalAction, TemporalState};
 use parseltongue_core::storage::CozoDbStorage;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Test: Generate CodeDiff for entities with Create action
@@ -213,6 +214,113 @@ async fn test_code_diff_json_output() {
     assert!(json.contains("\"future_code\""));
 }
 
+/// Test: generated_at comes from the storage's clock
+#[tokio::test]
+async fn test_generated_at_uses_storage_clock() {
+    let instant = chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, 1, 12, 0, 0).unwrap();
+    let storage = CozoDbStorage::new("mem")
+        .await
+        .expect("Failed to create storage")
+        .with_clock(Arc::new(parseltongue_core::clock::FixedClock(instant)));
+    storage.create_schema().await.expect("Failed to create schema");
+    let entity = create_test_entity("src_lib_rs-test-fn-abc", Some("fn test() {}"), TemporalAction::Create);
+    storage.insert_entity(&entity).await.unwrap();
+
+    let diff = DiffGenerator::new(Arc::new(storage))
+        .with_move_detection(true)
+        .generate_diff()
+        .await
+        .unwrap();
+
+    assert_eq!(diff.metadata.generated_at, instant.to_rfc3339());
+}
+
+/// Test: A function deleted in one file and re-created in another is one Move
+#[tokio::test]
+async fn test_detect_moves_collapses_delete_and_create() {
+    let storage = CozoDbStorage::new("mem").await.expect("Failed to create storage");
+    storage.create_schema().await.expect("Failed to create schema");
+
+    let body = "fn helper() -> u32 {\n    42\n}";
+    let mut deleted = create_test_entity("rust:fn:helper:src_util_rs:5-7", None, TemporalAction::Delete);
+    deleted.current_code = Some(body.to_string());
+    let created = create_test_entity("src_lib_rs-helper-fn-abc123", Some(body), TemporalAction::Create);
+    let unrelated = create_test_entity("src_lib_rs-other-fn-def456", Some("fn other() {}"), TemporalAction::Create);
+    for entity in [&deleted, &created, &unrelated] {
+        storage.insert_entity(entity).await.expect("Failed to insert entity");
+    }
+    let storage = Arc::new(storage);
+
+    // Without the flag the move stays a Delete + Create
+    let plain = DiffGenerator::new(storage.clone()).generate_diff().await.unwrap();
+    assert_eq!(plain.changes.len(), 3);
+    assert_eq!(plain.metadata.move_count, 0);
+
+    let diff = DiffGenerator::new(storage)
+        .with_move_detection(true)
+        .generate_diff()
+        .await
+        .expect("Failed to generate diff");
+
+    assert_eq!(diff.changes.len(), 2);
+    assert_eq!(diff.metadata.move_count, 1);
+    assert_eq!(diff.metadata.delete_count, 0);
+    assert_eq!(diff.metadata.create_count, 1);
+
+    let moved = diff
+        .changes
+        .iter()
+        .find(|c| matches!(c.operation, Operation::Move { .. }))
+        .expect("Move change");
+    assert_eq!(moved.operation, Operation::Move { similarity: 100 });
+    assert_eq!(moved.file_path, PathBuf::from("src/util.rs"));
+    assert_eq!(moved.moved_to, Some(PathBuf::from("src/lib.rs")));
+    assert_eq!(moved.isgl1_key, "rust:fn:helper:src_util_rs:5-7");
+    assert_eq!(moved.current_code.as_deref(), Some(body));
+    assert_eq!(moved.future_code.as_deref(), Some(body));
+    assert!(moved.line_range.is_some());
+}
+
+/// Test: Following the database yields only changes written after it starts
+#[tokio::test]
+async fn test_stream_new_changes_yields_only_new_writes() {
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    let storage = Arc::new(CozoDbStorage::new("mem").await.expect("Failed to create storage"));
+    storage.create_schema().await.expect("Failed to create schema");
+    let existing = create_test_entity(
+        "rust:fn:existing:src_lib_rs:1-5",
+        Some("fn existing() { 1 }"),
+        TemporalAction::Edit,
+    );
+    storage.insert_entity(&existing).await.expect("Failed to insert entity");
+
+    let generator = DiffGenerator::new(Arc::clone(&storage)).with_poll_interval(Duration::from_millis(20));
+    let changes = generator.stream_new_changes();
+    futures::pin_mut!(changes);
+
+    // The pending Edit is the baseline, so nothing is yielded yet
+    let quiet = Duration::from_millis(150);
+    assert!(tokio::time::timeout(quiet, changes.next()).await.is_err());
+
+    let added = create_test_entity(
+        "src_lib_rs-added-fn-abc123",
+        Some("fn added() {}"),
+        TemporalAction::Create,
+    );
+    storage.insert_entity(&added).await.expect("Failed to insert entity");
+
+    let change = tokio::time::timeout(Duration::from_secs(5), changes.next())
+        .await
+        .expect("new change was not streamed")
+        .expect("stream ended")
+        .expect("database read failed");
+    assert_eq!(change.isgl1_key, "src_lib_rs-added-fn-abc123");
+    assert_eq!(change.operation, Operation::Create);
+    assert!(tokio::time::timeout(quiet, changes.next()).await.is_err());
+}
+
 // Helper function to create test entities
 fn create_test_entity(isgl1_key: &str, future_code: Option<&str>, action: TemporalAction) -> CodeEntity {
     use parseltongue_core::entities::{
@@ -220,7 +328,6 @@ fn create_test_entity(isgl1_key: &str, future_code: Option<&str>, action: Tempor
         LanguageSpecificSignature, LineRange, RiskLevel, RustSignature, TddClassification, TestabilityLevel,
         Visibility,
     };
-    use std::path::PathBuf;
 
     CodeEntity {
         isgl1_key: isgl1_key.to_string(),
@@ -0,0 +1,193 @@
+//! # Git Patch Integration Tests
+//!
+//! Generated patches must apply cleanly with `git apply` to a checkout of
+//! the original code and produce exactly the future state.
+
+use parseltongue_core::entities::{
+    CodeEntity, EntityClass, EntityType, InterfaceSignature, LanguageSpecificSignature,
+    LineRange, RustSignature, TemporalAction, Visibility,
+};
+use parseltongue_core::storage::CozoDbStorage;
+use pt05_llm_cozodb_to_diff_writer::DiffGenerator;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+const ORIGINAL_LIB: &str = "\
+use std::fmt;
+
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+pub fn obsolete() {
+    println!(\"remove me\");
+}
+
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+}
+";
+
+const EXPECTED_LIB: &str = "\
+use std::fmt;
+
+pub fn add(a: i32, b: i32) -> i32 {
+    a.checked_add(b).expect(\"overflow\")
+}
+
+
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    pub fn x(&self) -> i32 {
+        self.x.abs()
+    }
+}
+";
+
+fn git(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new("git")
+        .args(["-c", "user.name=pt05", "-c", "user.email=pt05@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git must be installed to run patch tests")
+}
+
+fn create_entity(
+    key: &str,
+    current: Option<&str>,
+    future: Option<&str>,
+    action: TemporalAction,
+) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: key.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 1).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let mut entity =
+        CodeEntity::new(key.to_string(), signature, EntityClass::CodeImplementation).unwrap();
+    entity.current_code = current.map(str::to_string);
+    entity.future_code = future.map(str::to_string);
+    entity.apply_temporal_change(action, future.map(str::to_string)).unwrap();
+    entity
+}
+
+async fn storage_with(entities: Vec<CodeEntity>) -> Arc<CozoDbStorage> {
+    let storage = CozoDbStorage::new("mem").await.expect("Failed to create storage");
+    storage.create_schema().await.expect("Failed to create schema");
+    for entity in &entities {
+        storage.insert_entity(entity).await.expect("Failed to insert entity");
+    }
+    Arc::new(storage)
+}
+
+/// Test: Edit, Delete and Create changes apply cleanly with `git apply`
+#[tokio::test]
+async fn test_git_patch_applies_cleanly_to_original_checkout() {
+    let checkout = TempDir::new().unwrap();
+    std::fs::create_dir_all(checkout.path().join("src")).unwrap();
+    std::fs::write(checkout.path().join("src/lib.rs"), ORIGINAL_LIB).unwrap();
+    git(checkout.path(), &["init", "-q"]);
+    git(checkout.path(), &["add", "."]);
+    git(checkout.path(), &["commit", "-q", "-m", "original"]);
+
+    let storage = storage_with(vec![
+        create_entity(
+            "rust:fn:add:src_lib_rs:3-5",
+            Some("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"),
+            Some("pub fn add(a: i32, b: i32) -> i32 {\n    a.checked_add(b).expect(\"overflow\")\n}"),
+            TemporalAction::Edit,
+        ),
+        create_entity(
+            "rust:fn:obsolete:src_lib_rs:7-9",
+            Some("pub fn obsolete() {\n    println!(\"remove me\");\n}"),
+            None,
+            TemporalAction::Delete,
+        ),
+        // Method text starts at `pub`, without its indentation
+        create_entity(
+            "rust:method:x:src_lib_rs:16-18",
+            Some("pub fn x(&self) -> i32 {\n        self.x\n    }"),
+            Some("pub fn x(&self) -> i32 {\n        self.x.abs()\n    }"),
+            TemporalAction::Edit,
+        ),
+        create_entity(
+            "src_util_rs-helper-fn-abc12345",
+            None,
+            Some("pub fn helper() -> u8 {\n    42\n}"),
+            TemporalAction::Create,
+        ),
+    ])
+    .await;
+
+    let patch = DiffGenerator::new(storage)
+        .with_source_root(checkout.path())
+        .generate_git_patch()
+        .await
+        .expect("Failed to generate patch");
+
+    assert!(patch.contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+    assert!(patch.contains("--- /dev/null\n+++ b/src/util.rs\n"));
+
+    let patch_path = checkout.path().join("changes.patch");
+    std::fs::write(&patch_path, &patch).unwrap();
+
+    let check = git(checkout.path(), &["apply", "--check", "changes.patch"]);
+    assert!(
+        check.status.success(),
+        "git apply --check failed: {}\n{}",
+        String::from_utf8_lossy(&check.stderr),
+        patch
+    );
+    assert!(git(checkout.path(), &["apply", "changes.patch"]).status.success());
+
+    let patched = std::fs::read_to_string(checkout.path().join("src/lib.rs")).unwrap();
+    assert_eq!(patched, EXPECTED_LIB);
+    let created = std::fs::read_to_string(checkout.path().join("src/util.rs")).unwrap();
+    assert_eq!(created, "pub fn helper() -> u8 {\n    42\n}\n");
+}
+
+/// Test: Stale line ranges are reported instead of producing a bad patch
+#[tokio::test]
+async fn test_git_patch_rejects_stale_entities() {
+    let checkout = TempDir::new().unwrap();
+    std::fs::create_dir_all(checkout.path().join("src")).unwrap();
+    std::fs::write(checkout.path().join("src/lib.rs"), ORIGINAL_LIB).unwrap();
+
+    let storage = storage_with(vec![create_entity(
+        "rust:fn:add:src_lib_rs:7-9",
+        Some("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"),
+        Some("pub fn add() {}"),
+        TemporalAction::Edit,
+    )])
+    .await;
+
+    let result = DiffGenerator::new(storage)
+        .with_source_root(checkout.path())
+        .generate_git_patch()
+        .await;
+
+    assert!(result.is_err());
+}
@@ -4,9 +4,10 @@ use parseltongue_core::storage::CozoDbStorage;
 
 /// Ultra-minimalist state reset manager
 ///
-/// NO BACKUPS - Delete and recreate only
+/// NO BACKUPS - Delete and recreate only (deletes are soft: rows stay
+///   recoverable until `CozoDbStorage::purge_expired` reclaims them)
 /// NO CONFIGURATION - Single deterministic operation
-/// NO ROLLBACK - Permanent state reset
+/// NO ROLLBACK - Reset state is not restored automatically
 pub struct StateResetManager {
     pub(crate) storage: CozoDbStorage,
 }
@@ -42,7 +43,8 @@ impl StateResetManager {
 
     /// Delete CodeGraph table (ultra-minimalist: NO backups)
     ///
-    /// GREEN Phase: Minimal implementation using brute-force deletion
+    /// GREEN Phase: Minimal implementation using brute-force deletion;
+    /// each entity is soft-deleted, hidden from reads until purged
     /// Ultra-minimalist approach: iterate and delete, no fancy table operations
     async fn delete_table(&self) -> Result<()> {
         // Get all entities (simple, direct)
@@ -0,0 +1,71 @@
+//! Count incoming dependency edges (in-degree) per entity
+//!
+//! The most-depended-upon entities are the riskiest to change; ranking them
+//! by in-degree feeds the dependency heatmap.
+//!
+//! ## TDD Contract
+//! - **Precondition**: Entity keys and `Vec<DependencyEdge>` from CozoDB
+//! - **Postcondition**: Entities with in-degree > 0 ranked descending (ties by key),
+//!   plus the number of entities nobody depends on
+//! - **Error Conditions**: None (counting is infallible, empty input gives empty ranking)
+
+use pt02_llm_cozodb_to_context_writer::DependencyEdge;
+use std::collections::HashMap;
+
+/// In-degree ranking for a set of entities
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IncomingDependencyCounts {
+    /// (ISGL1 key, in-degree), highest first; only entities with in-degree > 0
+    pub ranked: Vec<(String, usize)>,
+    /// Entities with no incoming edges
+    pub zero_in_degree_count: usize,
+}
+
+/// Compute in-degree for every entity in `entity_keys`
+///
+/// Edges pointing at keys outside `entity_keys` (unresolved external calls,
+/// filtered-out tests) are ignored. Duplicate keys are counted once.
+///
+/// # Example
+/// ```
+/// use pt02_llm_cozodb_to_context_writer::DependencyEdge;
+/// use pt07_visual_analytics_terminal::core::compute_incoming_dependency_counts;
+///
+/// let keys = vec!["a".to_string(), "b".to_string()];
+/// let edges = vec![DependencyEdge {
+///     from_key: "a".to_string(),
+///     to_key: "b".to_string(),
+///     edge_type: "Calls".to_string(),
+/// }];
+/// let counts = compute_incoming_dependency_counts(&keys, &edges);
+/// assert_eq!(counts.ranked, vec![("b".to_string(), 1)]);
+/// assert_eq!(counts.zero_in_degree_count, 1);
+/// ```
+pub fn compute_incoming_dependency_counts(
+    entity_keys: &[String],
+    edges: &[DependencyEdge],
+) -> IncomingDependencyCounts {
+    let mut in_degree: HashMap<&str, usize> = entity_keys
+        .iter()
+        .map(|key| (key.as_str(), 0))
+        .collect();
+
+    for edge in edges {
+        if let Some(count) = in_degree.get_mut(edge.to_key.as_str()) {
+            *count += 1;
+        }
+    }
+
+    let zero_in_degree_count = in_degree.values().filter(|&&count| count == 0).count();
+    let mut ranked: Vec<(String, usize)> = in_degree
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(key, count)| (key.to_string(), count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    IncomingDependencyCounts {
+        ranked,
+        zero_in_degree_count,
+    }
+}
@@ -101,6 +101,8 @@ mod tests {
                 created_at: chrono::Utc::now(),
                 modified_at: chrono::Utc::now(),
                 content_hash: String::new(),
+                semantic_hash: String::new(),
+                complexity_score: None,
                 additional: HashMap::new(),
             },
             entity_class,  // v0.9.0: mandatory field
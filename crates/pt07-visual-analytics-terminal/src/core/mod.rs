@@ -3,7 +3,11 @@
 pub mod filter_implementation_entities_only;
 pub mod filter_implementation_edges_only;
 pub mod cycle_detection;
+pub mod compute_incoming_dependency_counts;
+pub mod summarize_pending_temporal_changes;
 
 pub use filter_implementation_entities_only::*;
 pub use filter_implementation_edges_only::*;
 pub use cycle_detection::*;
+pub use compute_incoming_dependency_counts::*;
+pub use summarize_pending_temporal_changes::*;
@@ -0,0 +1,97 @@
+//! Summarize pending temporal changes (entities with a `future_action`)
+//!
+//! Before pt05/pt06 apply changes, the pending set answers "what is about
+//! to happen": how many creates/edits/deletes, which files take the most
+//! churn, and a few of the affected entities.
+//!
+//! ## TDD Contract
+//! - **Precondition**: Entities from CozoDB (any mix of pending and unchanged)
+//! - **Postcondition**: Counts per action, files ranked by change volume
+//!   (descending, ties by path), and a sample in key order
+//! - **Error Conditions**: None (empty input gives an empty summary)
+
+use parseltongue_core::entities::{CodeEntity, TemporalAction};
+use std::collections::BTreeMap;
+
+/// One entity with a planned change
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingChange {
+    pub isgl1_key: String,
+    pub file_path: String,
+    pub action: TemporalAction,
+}
+
+/// Pending changes of the entities that have a `future_action`
+pub fn collect_pending_changes_from_entities(entities: &[CodeEntity]) -> Vec<PendingChange> {
+    entities
+        .iter()
+        .filter_map(|entity| {
+            Some(PendingChange {
+                isgl1_key: entity.isgl1_key.clone(),
+                file_path: entity.interface_signature.file_path.to_string_lossy().to_string(),
+                action: entity.temporal_state.future_action.clone()?,
+            })
+        })
+        .collect()
+}
+
+/// Aggregated view of the pending changes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PendingChangeSummary {
+    pub create_count: usize,
+    pub edit_count: usize,
+    pub delete_count: usize,
+    /// (file path, pending changes in it), highest first
+    pub files_ranked: Vec<(String, usize)>,
+    /// First `sample_size` changes by ISGL1 key
+    pub sample: Vec<PendingChange>,
+}
+
+impl PendingChangeSummary {
+    pub fn total(&self) -> usize {
+        self.create_count + self.edit_count + self.delete_count
+    }
+}
+
+/// Count pending changes by action and by file, keeping `sample_size` examples
+///
+/// # Example
+/// ```
+/// use parseltongue_core::entities::TemporalAction;
+/// use pt07_visual_analytics_terminal::core::{summarize_pending_temporal_changes, PendingChange};
+///
+/// let changes = vec![PendingChange {
+///     isgl1_key: "rust:fn:run:src_lib_rs:1-3".to_string(),
+///     file_path: "src/lib.rs".to_string(),
+///     action: TemporalAction::Edit,
+/// }];
+/// let summary = summarize_pending_temporal_changes(&changes, 5);
+/// assert_eq!(summary.edit_count, 1);
+/// assert_eq!(summary.files_ranked, vec![("src/lib.rs".to_string(), 1)]);
+/// ```
+pub fn summarize_pending_temporal_changes(changes: &[PendingChange], sample_size: usize) -> PendingChangeSummary {
+    let mut summary = PendingChangeSummary::default();
+    let mut per_file: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for change in changes {
+        match change.action {
+            TemporalAction::Create => summary.create_count += 1,
+            TemporalAction::Edit => summary.edit_count += 1,
+            TemporalAction::Delete => summary.delete_count += 1,
+        }
+        *per_file.entry(change.file_path.as_str()).or_insert(0) += 1;
+    }
+
+    summary.files_ranked = per_file
+        .into_iter()
+        .map(|(path, count)| (path.to_string(), count))
+        .collect();
+    // Stable sort keeps the BTreeMap's path order among equal counts
+    summary.files_ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut sorted: Vec<&PendingChange> = changes.iter().collect();
+    sorted.sort_by(|a, b| a.isgl1_key.cmp(&b.isgl1_key));
+    summary.sample = sorted.into_iter().take(sample_size).cloned().collect();
+
+    summary
+}
@@ -51,6 +51,11 @@ impl Pt07DbAdapter {
         Ok(Self { inner })
     }
 
+    /// Wrap an already-open pt02 adapter (e.g. over an in-memory database)
+    pub fn from_pt02_adapter(inner: CozoDbAdapter) -> Self {
+        Self { inner }
+    }
+
     /// Check if adapter is connected
     ///
     /// For testing purposes - always returns true if constructor succeeded.
@@ -75,24 +80,20 @@ impl Pt07DbAdapter {
     pub async fn query_all_entities_from_database(&self) -> Result<Vec<CodeEntity>> {
         // Query entities using CodeGraphRepository trait
         let entities = self.inner.get_all_entities().await?;
+        Ok(convert_entities_skipping_invalid(entities))
+    }
 
-        // Convert each entity from pt02::Entity → parseltongue_core::CodeEntity
-        let mut code_entities = Vec::with_capacity(entities.len());
-
-        for entity in entities {
-            // Convert Entity to EntityExportLevel1 format for conversion function
-            let level1_entity = entity_trait_to_export_level1(entity);
-
-            match convert_pt02_entity_to_code_entity(level1_entity) {
-                Ok(code_entity) => code_entities.push(code_entity),
-                Err(e) => {
-                    // Log conversion error but continue processing other entities
-                    eprintln!("⚠️ Warning: Failed to convert entity: {}", e);
-                }
-            }
-        }
-
-        Ok(code_entities)
+    /// Query entities with a planned change (`future_action` set)
+    ///
+    /// ## Postcondition
+    /// - Returns only entities pending Create/Edit/Delete
+    /// - Empty vec when nothing is pending (not an error)
+    ///
+    /// ## Error Conditions
+    /// - Database query fails
+    pub async fn query_pending_change_entities_from_database(&self) -> Result<Vec<CodeEntity>> {
+        let entities = self.inner.query_entities("Future_Action != null").await?;
+        Ok(convert_entities_skipping_invalid(entities))
     }
 
     /// Query all edges from database
@@ -126,6 +127,28 @@ impl Pt07DbAdapter {
 // Helper Functions
 // ============================================================================
 
+/// Convert each entity from pt02::Entity → parseltongue_core::CodeEntity
+fn convert_entities_skipping_invalid(
+    entities: Vec<pt02_llm_cozodb_to_context_writer::export_trait::Entity>,
+) -> Vec<CodeEntity> {
+    let mut code_entities = Vec::with_capacity(entities.len());
+
+    for entity in entities {
+        // Convert Entity to EntityExportLevel1 format for conversion function
+        let level1_entity = entity_trait_to_export_level1(entity);
+
+        match convert_pt02_entity_to_code_entity(level1_entity) {
+            Ok(code_entity) => code_entities.push(code_entity),
+            Err(e) => {
+                // Log conversion error but continue processing other entities
+                eprintln!("⚠️ Warning: Failed to convert entity: {}", e);
+            }
+        }
+    }
+
+    code_entities
+}
+
 /// Convert Entity (from CodeGraphRepository trait) to EntityExportLevel1
 ///
 /// The Entity struct from the trait is essentially the same as EntityExportLevel1,
@@ -147,6 +170,10 @@ fn entity_trait_to_export_level1(entity: pt02_llm_cozodb_to_context_writer::expo
         interface_signature: entity.interface_signature,
         entity_class: entity.entity_class,
         doc_comment: entity.doc_comment,
+        provenance: None,
+        is_generated: entity.is_generated,
+        complexity_score: entity.complexity_score,
+        tests: vec![],
     }
 }
 
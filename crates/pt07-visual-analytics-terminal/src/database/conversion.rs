@@ -7,6 +7,7 @@
 
 use anyhow::Result;
 use parseltongue_core::entities::*;
+use parseltongue_core::isgl1_key::parse_key;
 use pt02_llm_cozodb_to_context_writer::EntityExportLevel1;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -26,7 +27,7 @@ pub fn convert_pt02_entity_to_code_entity(
     pt02_entity: EntityExportLevel1,
 ) -> Result<CodeEntity> {
     // Parse ISGL1 key: rust:fn:calculate_total:src_billing_rs:42
-    let key_parts = parse_isgl1_key(&pt02_entity.isgl1_key)?;
+    let key_parts = parse_key(&pt02_entity.isgl1_key)?;
 
     // Convert temporal indicators to TemporalState
     let temporal_state = convert_temporal_indicators_to_state(
@@ -82,6 +83,8 @@ pub fn convert_pt02_entity_to_code_entity(
         created_at: chrono::Utc::now(),
         modified_at: chrono::Utc::now(),
         content_hash: String::new(),
+        semantic_hash: String::new(),
+        complexity_score: None,
         additional: HashMap::new(),
     };
 
@@ -98,45 +101,6 @@ pub fn convert_pt02_entity_to_code_entity(
     })
 }
 
-/// Parse ISGL1 key into components
-///
-/// Format: rust:fn:calculate_total:src_billing_rs:42
-struct IsglKeyComponents {
-    language: String,
-    entity_type: String,
-    entity_name: String,
-    file_path: String,
-    line_number: u32,
-}
-
-fn parse_isgl1_key(key: &str) -> Result<IsglKeyComponents> {
-    let parts: Vec<&str> = key.split(':').collect();
-
-    if parts.len() < 5 {
-        anyhow::bail!("Invalid ISGL1 key format: {}", key);
-    }
-
-    // Parse line number from last part (handle ranges like "445-450")
-    let line_str = parts[4];
-    let line_number = if line_str.contains('-') {
-        // It's a range, take the first number
-        line_str.split('-').next().unwrap().parse::<u32>()
-            .map_err(|_| anyhow::anyhow!("Invalid line number in ISGL1 key: {}", parts[4]))?
-    } else {
-        // Single line number
-        line_str.parse::<u32>()
-            .map_err(|_| anyhow::anyhow!("Invalid line number in ISGL1 key: {}", parts[4]))?
-    };
-
-    Ok(IsglKeyComponents {
-        language: parts[0].to_string(),
-        entity_type: parts[1].to_string(),
-        entity_name: parts[2].to_string(),
-        file_path: parts[3].to_string(),
-        line_number,
-    })
-}
-
 /// Convert temporal indicators (current_ind, future_ind) to TemporalState
 fn convert_temporal_indicators_to_state(
     current_ind: u8,
@@ -207,19 +171,19 @@ mod tests {
     #[test]
     fn test_parse_isgl1_key_valid() {
         let key = "rust:fn:calculate_total:src_billing_rs:42";
-        let result = parse_isgl1_key(key).unwrap();
+        let result = parse_key(key).unwrap();
 
         assert_eq!(result.language, "rust");
         assert_eq!(result.entity_type, "fn");
-        assert_eq!(result.entity_name, "calculate_total");
+        assert_eq!(result.name, "calculate_total");
         assert_eq!(result.file_path, "src_billing_rs");
-        assert_eq!(result.line_number, 42);
+        assert_eq!(result.line_range.0, 42);
     }
 
     #[test]
     fn test_parse_isgl1_key_invalid() {
         let key = "rust:fn:foo";  // Too few parts
-        let result = parse_isgl1_key(key);
+        let result = parse_key(key);
         assert!(result.is_err());
     }
 
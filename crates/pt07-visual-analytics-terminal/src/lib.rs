@@ -11,12 +11,67 @@
 use anyhow::Result;
 use chrono::Local;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub mod core;
 pub mod primitives;
 pub mod database;
 pub mod visualizations;
 
+/// Default saved-file name: `<command>-YYYYMMDDHHMMSS.txt`
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{command}-{timestamp}.txt";
+
+/// Where saved visualizations go
+///
+/// `filename_template` supports `{command}`, `{timestamp}` (local time,
+/// `YYYYMMDDHHMMSS`) and `{db}` (database file stem, e.g. `code` for
+/// `rocksdb:out/code.db`). The default writes to the current directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLocation {
+    pub output_dir: PathBuf,
+    pub filename_template: String,
+}
+
+impl Default for OutputLocation {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::new(),
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl OutputLocation {
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = output_dir.into();
+        self
+    }
+
+    pub fn with_filename_template(mut self, template: impl Into<String>) -> Self {
+        self.filename_template = template.into();
+        self
+    }
+
+    /// Path for one saved visualization (pure function)
+    pub fn resolve(&self, command_name: &str, db: &str, timestamp: &str) -> PathBuf {
+        let filename = self
+            .filename_template
+            .replace("{command}", command_name)
+            .replace("{timestamp}", timestamp)
+            .replace("{db}", &db_stem(db));
+        self.output_dir.join(filename)
+    }
+}
+
+/// File stem of a database connection string (`rocksdb:out/code.db` → `code`)
+fn db_stem(db: &str) -> String {
+    let location = db.split_once(':').map_or(db, |(_, rest)| rest);
+    Path::new(location)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "db".to_string())
+}
+
 /// Save visualization output to both stdout and timestamped txt file
 ///
 /// This is called by every visualization binary to:
@@ -52,6 +107,21 @@ pub fn save_visualization_output_to_file(
     command_args: &str,
     visualization_output: &str,
 ) -> Result<()> {
+    save_visualization_output_to(&OutputLocation::default(), command_name, command_args, "", visualization_output)
+        .map(|_| ())
+}
+
+/// `save_visualization_output_to_file` with a custom location
+///
+/// `db` fills the `{db}` placeholder. Creates `output_dir` if needed and
+/// returns the path written.
+pub fn save_visualization_output_to(
+    location: &OutputLocation,
+    command_name: &str,
+    command_args: &str,
+    db: &str,
+    visualization_output: &str,
+) -> Result<PathBuf> {
     // Build full command for logging
     let full_command = format!("{} {}", command_name, command_args);
 
@@ -62,16 +132,19 @@ pub fn save_visualization_output_to_file(
     print!("{}", full_output);
 
     // Generate timestamp filename
-    let timestamp = Local::now().format("%Y%m%d%H%M%S");
-    let filename = format!("{}-{}.txt", command_name, timestamp);
+    let timestamp = Local::now().format("%Y%m%d%H%M%S").to_string();
+    let path = location.resolve(command_name, db, &timestamp);
 
     // Write to file (no permission needed, just do it)
-    fs::write(&filename, &full_output)?;
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, &full_output)?;
 
     // Print save confirmation to stderr (doesn't interfere with piped output)
-    eprintln!("📄 Saved to: {}", filename);
+    eprintln!("📄 Saved to: {}", path.display());
 
-    Ok(())
+    Ok(path)
 }
 
 #[cfg(test)]
@@ -120,4 +193,30 @@ mod tests {
         // Cleanup
         std::env::set_current_dir(original_dir).unwrap();
     }
+
+    #[test]
+    fn test_custom_template_routes_into_output_dir() {
+        let location = OutputLocation::default()
+            .with_output_dir("analytics")
+            .with_filename_template("{db}-{command}-{timestamp}.txt");
+        assert_eq!(
+            location.resolve("heatmap", "rocksdb:out/billing.db", "20251105223045"),
+            PathBuf::from("analytics/billing-heatmap-20251105223045.txt")
+        );
+        assert_eq!(
+            OutputLocation::default().resolve("heatmap", "mem", "20251105223045"),
+            PathBuf::from("heatmap-20251105223045.txt")
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let location = OutputLocation::default()
+            .with_output_dir(temp_dir.path().join("analytics"))
+            .with_filename_template("{db}-{command}.txt");
+        let path = save_visualization_output_to(&location, "heatmap", "--top 5", "code.db", "Test Output").unwrap();
+
+        assert_eq!(path, temp_dir.path().join("analytics").join("code-heatmap.txt"));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Command: heatmap --top 5"));
+        assert!(content.contains("Test Output"));
+    }
 }
@@ -24,10 +24,36 @@ pub fn render_progress_bar_with_percentage_horizontal(
     format!("[████░░] {}%", percentage)
 }
 
+/// Render bar scaled to width horizontal
+///
+/// Length is proportional to `value / max`; any non-zero value gets at
+/// least one filled cell so small counts stay visible.
+///
+/// # Example
+/// ```text
+/// ██████░░░░  (value 6 of max 10, width 10)
+/// ```
+pub fn render_bar_scaled_to_width(value: usize, max: usize, width: usize) -> String {
+    let filled = if max == 0 || value == 0 {
+        0
+    } else {
+        ((value.min(max) * width) / max).max(1)
+    };
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bar_scales_to_width() {
+        assert_eq!(render_bar_scaled_to_width(10, 10, 4), "████");
+        assert_eq!(render_bar_scaled_to_width(5, 10, 4), "██░░");
+        assert_eq!(render_bar_scaled_to_width(1, 100, 4), "█░░░");
+        assert_eq!(render_bar_scaled_to_width(0, 0, 4), "░░░░");
+    }
+
     #[test]
     fn test_stub_renders_progress_bar() {
         let result = render_progress_bar_with_percentage_horizontal(8, 10, 10);
@@ -5,15 +5,29 @@
 
 use anyhow::Result;
 use crate::core::{
+    collect_pending_changes_from_entities,
+    compute_incoming_dependency_counts,
     detect_cycles_in_dependency_graph,
     filter_implementation_edges_only,
     filter_implementation_entities_only,
     filter_include_all_edge_types,
     filter_include_all_entity_types,
+    summarize_pending_temporal_changes,
+    PendingChangeSummary,
 };
 use crate::database::Pt07DbAdapter;
+use crate::primitives::{render_bar_scaled_to_width, render_text_with_color_and_emoji_terminal};
+use parseltongue_core::entities::TemporalAction;
+use parseltongue_core::isgl1_key::parse_key;
+use pt02_llm_cozodb_to_context_writer::DependencyEdge;
 use std::collections::{HashMap, HashSet};
 
+/// Default number of entities shown by the dependency heatmap
+pub const DEFAULT_HEATMAP_TOP_N: usize = 10;
+
+/// Default number of files and sample entities shown for pending changes
+pub const DEFAULT_PENDING_TOP_N: usize = 5;
+
 /// Render entity count bar chart visualization
 ///
 /// Returns the visualization as a string for display/saving.
@@ -181,3 +195,216 @@ pub async fn render_dependency_cycle_warning_list_visualization(
 
     Ok(output)
 }
+
+/// Render dependency heatmap visualization (most-depended-upon entities)
+///
+/// Returns the visualization as a string for display/saving.
+pub async fn render_dependency_heatmap_visualization(
+    db_path: &str,
+    include_tests: bool,
+    top_n: usize,
+) -> Result<String> {
+    // Query entities and edges from CozoDB
+    let adapter = Pt07DbAdapter::connect_to_database_from_path(db_path).await?;
+    let all_entities = adapter.query_all_entities_from_database().await?;
+    let all_edges = adapter.query_all_edges_from_database().await?;
+
+    // Apply filter based on include_tests flag
+    let filtered_entities = if include_tests {
+        filter_include_all_entity_types(all_entities)
+    } else {
+        filter_implementation_entities_only(all_entities)
+    };
+
+    let entity_keys: Vec<String> = filtered_entities
+        .iter()
+        .map(|e| e.isgl1_key.clone())
+        .collect();
+    let impl_keys: HashSet<String> = entity_keys.iter().cloned().collect();
+
+    let filtered_edges = if include_tests {
+        filter_include_all_edge_types(all_edges, &impl_keys)
+    } else {
+        filter_implementation_edges_only(all_edges, &impl_keys)
+    };
+
+    Ok(render_dependency_heatmap_from_graph(
+        &entity_keys,
+        &filtered_edges,
+        top_n,
+        include_tests,
+    ))
+}
+
+/// Render the dependency heatmap from already-loaded graph data (pure function)
+///
+/// Shows the `top_n` entities by in-degree as a sorted bar chart; entities
+/// nobody depends on are summarized in the footer.
+pub fn render_dependency_heatmap_from_graph(
+    entity_keys: &[String],
+    edges: &[DependencyEdge],
+    top_n: usize,
+    include_tests: bool,
+) -> String {
+    const NAME_WIDTH: usize = 20;
+    const BAR_WIDTH: usize = 16;
+
+    let counts = compute_incoming_dependency_counts(entity_keys, edges);
+
+    let title = if include_tests {
+        "Dependency Heatmap (All)"
+    } else {
+        "Dependency Heatmap (Impl Only)"
+    };
+
+    let mut output = String::new();
+    output.push_str("╔═══════════════════════════════════════════════╗\n");
+    output.push_str(&format!("║ {:^45} ║\n", title));
+    output.push_str("╠═══════════════════════════════════════════════╣\n");
+
+    if counts.ranked.is_empty() {
+        output.push_str(&format!("║ {:45} ║\n", "No dependency edges found"));
+    } else {
+        let max_count = counts.ranked[0].1;
+        for (key, count) in counts.ranked.iter().take(top_n) {
+            output.push_str(&format!(
+                "║ {:name_width$} [{}] {:>5} ║\n",
+                truncate_to_width(&display_name_from_isgl1_key(key), NAME_WIDTH),
+                render_bar_scaled_to_width(*count, max_count, BAR_WIDTH),
+                count,
+                name_width = NAME_WIDTH,
+            ));
+        }
+    }
+
+    output.push_str("╚═══════════════════════════════════════════════╝\n");
+    output.push_str(&format!(
+        "\nShowing {} of {} depended-upon entities\n",
+        counts.ranked.len().min(top_n),
+        counts.ranked.len()
+    ));
+    output.push_str(&render_text_with_color_and_emoji_terminal(
+        &format!("{} entities with zero in-degree", counts.zero_in_degree_count),
+        "💤",
+        "yellow",
+    ));
+    output.push('\n');
+
+    output
+}
+
+/// Render pending temporal changes (entities with a `future_action`)
+///
+/// Returns the visualization as a string for display/saving.
+pub async fn render_pending_changes_visualization(
+    db_path: &str,
+    top_files: usize,
+    sample_size: usize,
+) -> Result<String> {
+    let adapter = Pt07DbAdapter::connect_to_database_from_path(db_path).await?;
+    let pending = adapter.query_pending_change_entities_from_database().await?;
+
+    let changes = collect_pending_changes_from_entities(&pending);
+    let summary = summarize_pending_temporal_changes(&changes, sample_size);
+    Ok(render_pending_changes_from_summary(&summary, top_files))
+}
+
+/// Render the pending-changes dashboard from a summary (pure function)
+///
+/// Three panels: counts per action, the `top_files` files with the most
+/// pending changes, and the sampled entities. Create/Edit/Delete are green,
+/// yellow and red throughout. With nothing pending a single all-clear panel
+/// is rendered instead.
+pub fn render_pending_changes_from_summary(summary: &PendingChangeSummary, top_files: usize) -> String {
+    const NAME_WIDTH: usize = 30;
+    const BAR_WIDTH: usize = 16;
+
+    let mut output = String::new();
+    output.push_str("╔═══════════════════════════════════════════════╗\n");
+    output.push_str(&format!("║ {:^45} ║\n", "Pending Changes"));
+    output.push_str("╠═══════════════════════════════════════════════╣\n");
+
+    if summary.total() == 0 {
+        output.push_str(&format!("║ {:45} ║\n", "✅ Nothing pending"));
+        output.push_str(&format!("║ {:45} ║\n", "Database matches the indexed code"));
+        output.push_str("╚═══════════════════════════════════════════════╝\n");
+        output.push_str("\nNo Create/Edit/Delete actions to apply\n");
+        return output;
+    }
+
+    // Panel 1: counts by action
+    let max_count = summary.create_count.max(summary.edit_count).max(summary.delete_count);
+    for (action, count) in [
+        (TemporalAction::Create, summary.create_count),
+        (TemporalAction::Edit, summary.edit_count),
+        (TemporalAction::Delete, summary.delete_count),
+    ] {
+        output.push_str(&format!(
+            "║ {} [{}] {:>5}             ║\n",
+            render_action_label(&action),
+            render_bar_scaled_to_width(count, max_count, BAR_WIDTH),
+            count,
+        ));
+    }
+
+    // Panel 2: files by change volume
+    output.push_str("╠═══════════════════════════════════════════════╣\n");
+    output.push_str(&format!("║ {:45} ║\n", "Top files by change volume"));
+    for (path, count) in summary.files_ranked.iter().take(top_files) {
+        output.push_str(&format!(
+            "║   {:name_width$} {:>12} ║\n",
+            truncate_to_width(path, NAME_WIDTH),
+            count,
+            name_width = NAME_WIDTH,
+        ));
+    }
+
+    // Panel 3: sample of affected entities
+    output.push_str("╠═══════════════════════════════════════════════╣\n");
+    output.push_str(&format!("║ {:45} ║\n", "Sample of affected entities"));
+    for change in &summary.sample {
+        output.push_str(&format!(
+            "║ {} {:name_width$} ║\n",
+            render_action_label(&change.action),
+            truncate_to_width(&display_name_from_isgl1_key(&change.isgl1_key), NAME_WIDTH + 6),
+            name_width = NAME_WIDTH + 6,
+        ));
+    }
+
+    output.push_str("╚═══════════════════════════════════════════════╝\n");
+    output.push_str(&format!(
+        "\nTotal Pending: {} ({} create, {} edit, {} delete) across {} files\n",
+        summary.total(),
+        summary.create_count,
+        summary.edit_count,
+        summary.delete_count,
+        summary.files_ranked.len()
+    ));
+
+    output
+}
+
+/// Colored, fixed-width (8 column) label for a temporal action
+fn render_action_label(action: &TemporalAction) -> String {
+    let (marker, name, color) = match action {
+        TemporalAction::Create => ("+", "Create", "green"),
+        TemporalAction::Edit => ("~", "Edit", "yellow"),
+        TemporalAction::Delete => ("-", "Delete", "red"),
+    };
+    render_text_with_color_and_emoji_terminal(&format!("{:6}", name), marker, color)
+}
+
+/// Entity name segment of an ISGL1 key, or the whole key if it has none
+fn display_name_from_isgl1_key(key: &str) -> String {
+    parse_key(key).map_or_else(|_| key.to_string(), |components| components.name)
+}
+
+/// Cut `text` to at most `width` characters, marking the cut with `…`
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", kept)
+    }
+}
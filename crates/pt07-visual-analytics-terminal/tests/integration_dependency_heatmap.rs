@@ -0,0 +1,86 @@
+//! Integration tests for the dependency heatmap
+//!
+//! ## Test Coverage
+//! 1. Ranks entities by in-degree, highest first, limited to top N
+//! 2. Summarizes zero in-degree entities in the footer
+//! 3. Ignores edges to entities outside the entity set
+//! 4. Renders gracefully when there are no edges
+
+use pt02_llm_cozodb_to_context_writer::DependencyEdge;
+use pt07_visual_analytics_terminal::core::compute_incoming_dependency_counts;
+use pt07_visual_analytics_terminal::visualizations::render_dependency_heatmap_from_graph;
+
+fn key(name: &str) -> String {
+    format!("rust:fn:{}:src_lib_rs:1-5", name)
+}
+
+fn edge(from: &str, to: &str) -> DependencyEdge {
+    DependencyEdge {
+        from_key: key(from),
+        to_key: key(to),
+        edge_type: "Calls".to_string(),
+    }
+}
+
+fn sample_graph() -> (Vec<String>, Vec<DependencyEdge>) {
+    let keys = ["config", "logger", "parse", "main", "helper"]
+        .iter()
+        .map(|name| key(name))
+        .collect();
+    let edges = vec![
+        edge("main", "config"),
+        edge("parse", "config"),
+        edge("helper", "config"),
+        edge("main", "logger"),
+        edge("parse", "logger"),
+        edge("main", "parse"),
+        // Unresolved external call: not an entity, not counted
+        DependencyEdge {
+            from_key: key("main"),
+            to_key: "rust:fn:println:unknown:0-0".to_string(),
+            edge_type: "Calls".to_string(),
+        },
+    ];
+    (keys, edges)
+}
+
+#[test]
+fn test_in_degree_ranking_and_zero_count() {
+    let (keys, edges) = sample_graph();
+
+    let counts = compute_incoming_dependency_counts(&keys, &edges);
+
+    assert_eq!(
+        counts.ranked,
+        vec![(key("config"), 3), (key("logger"), 2), (key("parse"), 1)]
+    );
+    assert_eq!(counts.zero_in_degree_count, 2); // main, helper
+}
+
+#[test]
+fn test_heatmap_shows_top_n_sorted_with_footer() {
+    let (keys, edges) = sample_graph();
+
+    let output = render_dependency_heatmap_from_graph(&keys, &edges, 2, false);
+
+    let config_row = output.find("config").expect("config shown");
+    let logger_row = output.find("logger").expect("logger shown");
+    assert!(config_row < logger_row, "sorted by in-degree:\n{}", output);
+    assert!(!output.contains("parse "), "only top 2 shown:\n{}", output);
+    assert!(output.contains("Showing 2 of 3 depended-upon entities"));
+    assert!(output.contains("2 entities with zero in-degree"));
+}
+
+#[test]
+fn test_heatmap_handles_database_without_edges() {
+    let keys = vec![key("lonely")];
+
+    let output = render_dependency_heatmap_from_graph(&keys, &[], 10, false);
+
+    assert!(output.contains("No dependency edges found"));
+    assert!(output.contains("1 entities with zero in-degree"));
+
+    let empty = render_dependency_heatmap_from_graph(&[], &[], 10, true);
+    assert!(empty.contains("Dependency Heatmap (All)"));
+    assert!(empty.contains("0 entities with zero in-degree"));
+}
@@ -0,0 +1,150 @@
+//! Integration tests for the pending-changes dashboard
+//!
+//! ## Test Coverage
+//! 1. Counts pending changes by action and ranks files by change volume
+//! 2. Only entities with a `future_action` are collected
+//! 3. Renders Create/Edit/Delete panels with counts, files and a sample
+//! 4. Renders a reassuring empty state when nothing is pending
+//! 5. The adapter fetches exactly the entities with a `future_action`
+
+use parseltongue_core::entities::*;
+use parseltongue_core::storage::CozoDbStorage;
+use pt02_llm_cozodb_to_context_writer::CozoDbAdapter;
+use pt07_visual_analytics_terminal::core::{
+    collect_pending_changes_from_entities, summarize_pending_temporal_changes, PendingChange,
+};
+use pt07_visual_analytics_terminal::database::Pt07DbAdapter;
+use pt07_visual_analytics_terminal::visualizations::render_pending_changes_from_summary;
+use std::path::PathBuf;
+
+fn change(name: &str, file: &str, action: TemporalAction) -> PendingChange {
+    PendingChange {
+        isgl1_key: format!("rust:fn:{}:{}:1-5", name, file.replace(['/', '.'], "_")),
+        file_path: file.to_string(),
+        action,
+    }
+}
+
+fn sample_changes() -> Vec<PendingChange> {
+    vec![
+        change("parse", "src/parser.rs", TemporalAction::Edit),
+        change("tokenize", "src/parser.rs", TemporalAction::Edit),
+        change("parse_v2", "src/parser.rs", TemporalAction::Create),
+        change("legacy", "src/compat.rs", TemporalAction::Delete),
+        change("run", "src/main.rs", TemporalAction::Edit),
+    ]
+}
+
+fn entity(name: &str, temporal_state: TemporalState) -> CodeEntity {
+    let signature = InterfaceSignature {
+        entity_type: EntityType::Function,
+        name: name.to_string(),
+        visibility: Visibility::Public,
+        file_path: PathBuf::from("src/lib.rs"),
+        line_range: LineRange::new(1, 3).unwrap(),
+        module_path: vec![],
+        documentation: None,
+        language_specific: LanguageSpecificSignature::Rust(RustSignature {
+            generics: vec![],
+            lifetimes: vec![],
+            where_clauses: vec![],
+            attributes: vec![],
+            trait_impl: None,
+        }),
+    };
+    let key = format!("rust:fn:{}:src_lib_rs:1-3", name);
+    let mut entity = CodeEntity::new(key, signature, EntityClass::CodeImplementation).unwrap();
+    entity.temporal_state = temporal_state;
+    entity
+}
+
+#[test]
+fn test_summary_counts_actions_and_ranks_files() {
+    let summary = summarize_pending_temporal_changes(&sample_changes(), 2);
+
+    assert_eq!((summary.create_count, summary.edit_count, summary.delete_count), (1, 3, 1));
+    assert_eq!(summary.total(), 5);
+    assert_eq!(
+        summary.files_ranked,
+        vec![
+            ("src/parser.rs".to_string(), 3),
+            ("src/compat.rs".to_string(), 1),
+            ("src/main.rs".to_string(), 1),
+        ]
+    );
+    // Sample is the first N by key
+    let sampled: Vec<_> = summary.sample.iter().map(|c| c.isgl1_key.as_str()).collect();
+    assert_eq!(sampled, vec!["rust:fn:legacy:src_compat_rs:1-5", "rust:fn:parse:src_parser_rs:1-5"]);
+}
+
+#[test]
+fn test_only_entities_with_future_action_are_collected() {
+    let entities = vec![
+        entity("unchanged", TemporalState::unchanged()),
+        entity("edited", TemporalState::edit()),
+        entity("deleted", TemporalState::delete()),
+    ];
+
+    let changes = collect_pending_changes_from_entities(&entities);
+
+    let actions: Vec<_> = changes.iter().map(|c| (c.isgl1_key.as_str(), c.action.clone())).collect();
+    assert_eq!(
+        actions,
+        vec![
+            ("rust:fn:edited:src_lib_rs:1-3", TemporalAction::Edit),
+            ("rust:fn:deleted:src_lib_rs:1-3", TemporalAction::Delete),
+        ]
+    );
+    assert_eq!(changes[0].file_path, "src/lib.rs");
+}
+
+#[test]
+fn test_dashboard_shows_counts_files_and_sample() {
+    let summary = summarize_pending_temporal_changes(&sample_changes(), 3);
+
+    let output = render_pending_changes_from_summary(&summary, 2);
+
+    for label in ["Create", "Edit", "Delete"] {
+        assert!(output.contains(label), "missing {} row:\n{}", label, output);
+    }
+    assert!(output.contains("src/parser.rs"));
+    assert!(output.contains("src/compat.rs"));
+    assert!(!output.contains("src/main.rs"), "only the top 2 files are listed");
+    assert!(output.contains("legacy"));
+    assert!(output.contains("Total Pending: 5 (1 create, 3 edit, 1 delete) across 3 files"));
+}
+
+#[test]
+fn test_empty_state_when_nothing_is_pending() {
+    let summary = summarize_pending_temporal_changes(&[], 5);
+
+    let output = render_pending_changes_from_summary(&summary, 5);
+
+    assert!(output.contains("Nothing pending"));
+    assert!(!output.contains("Top files"));
+}
+
+#[tokio::test]
+async fn test_adapter_queries_only_pending_entities() {
+    let db = CozoDbStorage::new("mem").await.unwrap();
+    db.ensure_schema().await.unwrap();
+    for (name, state) in [
+        ("unchanged", TemporalState::unchanged()),
+        ("edited", TemporalState::edit()),
+        ("deleted", TemporalState::delete()),
+    ] {
+        let mut entity = entity(name, state);
+        entity.current_code = Some(format!("fn {}() {{}}", name));
+        if entity.temporal_state.future_action == Some(TemporalAction::Edit) {
+            entity.future_code = Some(format!("fn {}() {{ todo!() }}", name));
+        }
+        db.insert_entity(&entity).await.unwrap();
+    }
+    let adapter = Pt07DbAdapter::from_pt02_adapter(CozoDbAdapter::new(db));
+
+    let pending = adapter.query_pending_change_entities_from_database().await.unwrap();
+
+    let mut names: Vec<_> = pending.iter().map(|e| e.interface_signature.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["deleted", "edited"]);
+}
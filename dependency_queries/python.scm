@@ -1,7 +1,7 @@
 ; Python Dependency Queries (v0.9.0)
 ;
 ; Captures two main types of dependencies:
-; 1. Import statements (Uses edge)
+; 1. Import statements (Imports edge)
 ; 2. Function/method calls (Calls edge)
 ;
 ; Python's dynamic nature makes complete static analysis challenging,
@@ -88,7 +88,7 @@
 ; - Type hints could provide additional dependency information
 ;
 ; Parseltongue EdgeType mapping:
-; - @dependency.import* → EdgeType::Uses
+; - @dependency.import* → EdgeType::Imports
 ; - @dependency.call → EdgeType::Calls
 ; - @dependency.method_call → EdgeType::Calls
 ; - @dependency.inherits → EdgeType::Implements (semantic equivalence)
@@ -2,7 +2,7 @@
 ;
 ; Captures three types of dependencies:
 ; 1. Function calls (Calls edge)
-; 2. Use declarations (Uses edge)
+; 2. Use declarations (Imports edge)
 ; 3. Trait implementations (Implements edge)
 ;
 ; Query structure follows tree-sitter conventions:
@@ -126,6 +126,7 @@
 ;
 ; Parseltongue EdgeType mapping:
 ; - @dependency.call → EdgeType::Calls
-; - @dependency.use* → EdgeType::Uses
+; - @dependency.use* → EdgeType::Imports
 ; - @dependency.implements → EdgeType::Implements
-; - @dependency.*_type_ref → EdgeType::Uses
+; - @dependency.*_type_ref → EdgeType::References
+; - nested entities (impl → method) → EdgeType::Contains (from line ranges)